@@ -1,18 +1,87 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
-use shared::CharacterSummary;
-use ui_widgets::colors::{MAIN_COLOR, SECONDARY_COLOR, STROKE_COLOR, TEXT_COLOR};
+use shared::{CharacterSummary, ReferenceKind};
+use strum::IntoEnumIterator;
+use ui_widgets::colors::palette;
 
 use crate::create_character::CreateCharacterOpen;
-use crate::network::{ClientSkillRegistry, ClientTraitRegistry, PendingClientMessages};
+use crate::files::{pick_file, PickedFile};
+use crate::network::{
+    AdminSession, ClientRuleset, ClientSkillRegistry, ClientTraitRegistry, PendingClientMessages,
+};
 use crate::portrait::{CropEditorSlot, PendingCreationPortrait, PortraitPickerResult};
 
 use crate::state::AppScreen;
+use crate::toasts::{ToastLevel, Toasts};
+use uuid::Uuid;
 
 /// Holds the list of character summaries received from the server.
 #[derive(Debug, Clone, Resource, Default)]
 pub struct CharacterList {
     pub characters: Vec<CharacterSummary>,
+    /// Result of the last admin "find by reference" query; filters the list while set.
+    pub reference_matches: Option<ReferenceMatches>,
+}
+
+/// Characters referencing a registry entry, as reported by the server.
+#[derive(Debug, Clone)]
+pub struct ReferenceMatches {
+    /// What was searched for, e.g. `Trait "Cursed"`.
+    pub label: String,
+    pub ids: Vec<Uuid>,
+}
+
+/// Characters shown per page of the list.
+const PAGE_SIZE: u32 = 20;
+
+/// The page of the character list on screen, as served by the server.
+#[derive(Resource, Default)]
+pub struct CharacterPage {
+    pub characters: Vec<CharacterSummary>,
+    /// Characters matching the search across all pages.
+    pub total: u32,
+    offset: u32,
+    /// Name search typed above the list.
+    query: String,
+    /// Set when the page has to be requested again.
+    stale: bool,
+}
+
+impl CharacterPage {
+    fn request(&self) -> shared::ClientMessage {
+        let query = self.query.trim();
+        shared::ClientMessage::RequestCharacterPage {
+            offset: self.offset,
+            limit: PAGE_SIZE,
+            query: (!query.is_empty()).then(|| query.to_string()),
+        }
+    }
+}
+
+/// Characters ticked for bulk deletion, plus the confirm-dialog flag.
+#[derive(Resource, Default)]
+struct BulkSelection {
+    ids: BTreeSet<Uuid>,
+    confirm_open: bool,
+}
+
+/// Tag the character list is filtered by; `None` shows everyone.
+#[derive(Resource, Default)]
+struct TagFilter(Option<String>);
+
+/// JSON file picked for import, sent to the server once read.
+#[derive(Resource, Default)]
+struct ImportPicker(PickedFile);
+
+/// Keyboard focus in the visible character list.
+#[derive(Resource, Default)]
+struct CharacterFocus {
+    /// Position of the focused card among the visible characters.
+    index: Option<usize>,
+    /// Character the Delete key asked to remove, awaiting confirmation.
+    confirm_delete: Option<Uuid>,
 }
 
 pub struct CharacterSelectPlugin;
@@ -21,16 +90,22 @@ impl Plugin for CharacterSelectPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<CharacterList>()
             .init_resource::<CreateCharacterOpen>()
+            .init_resource::<BulkSelection>()
+            .init_resource::<TagFilter>()
+            .init_resource::<ImportPicker>()
+            .init_resource::<CharacterFocus>()
+            .init_resource::<CharacterPage>()
             .add_systems(
                 EguiPrimaryContextPass,
                 render_character_select.run_if(in_state(AppScreen::CharacterSelect)),
-            );
+            )
+            .add_systems(Update, send_picked_import);
     }
 }
 
 fn render_character_select(
     mut contexts: EguiContexts,
-    character_list: Res<CharacterList>,
+    mut character_list: ResMut<CharacterList>,
     mut pending_messages: ResMut<PendingClientMessages>,
     mut next_state: ResMut<NextState<AppScreen>>,
     mut create_open: ResMut<CreateCharacterOpen>,
@@ -39,19 +114,91 @@ fn render_character_select(
     portrait_picker: Res<PortraitPickerResult>,
     mut pending_creation_portrait: ResMut<PendingCreationPortrait>,
     mut crop_editor: ResMut<CropEditorSlot>,
+    mut bulk: ResMut<BulkSelection>,
+    mut tag_filter: ResMut<TagFilter>,
+    admin: Res<AdminSession>,
+    ruleset: Res<ClientRuleset>,
+    import_picker: Res<ImportPicker>,
+    (mut page, mut focus): (ResMut<CharacterPage>, ResMut<CharacterFocus>),
 ) -> Result {
     let ctx = contexts.ctx_mut()?;
 
+    // Drop selections for characters that no longer exist (e.g. after a delete)
+    bulk.ids
+        .retain(|id| character_list.characters.iter().any(|c| c.id == *id));
+    if bulk.ids.is_empty() {
+        bulk.confirm_open = false;
+    }
+
     egui::CentralPanel::default()
-        .frame(egui::Frame::NONE.fill(MAIN_COLOR))
+        .frame(egui::Frame::NONE.fill(palette().main))
         .show(ctx, |_ui| {});
 
+    let tag_counts = count_tags(&character_list.characters);
+    if tag_filter
+        .0
+        .as_ref()
+        .is_some_and(|tag| !tag_counts.contains_key(tag))
+    {
+        tag_filter.0 = None;
+    }
+    render_tag_sidebar(
+        ctx,
+        &tag_counts,
+        character_list.characters.len(),
+        &mut tag_filter,
+    );
+
     let screen_rect = ctx.viewport_rect();
-    let panel_width = (screen_rect.width() * 0.4).max(340.0).min(500.0);
+    let panel_width = (screen_rect.width() * 0.4).clamp(340.0, 500.0);
     let scroll_height = (screen_rect.height() * 0.6).max(350.0);
 
     let mut selected: Option<CharacterSummary> = None;
 
+    // The list shrinks after a delete or a filter change, so keep the focus on it
+    // Creates, deletes and tag edits all land in the full list
+    if character_list.is_changed() {
+        page.stale = true;
+    }
+    // The last page emptied, e.g. after deleting its only character
+    if page.characters.is_empty() && page.offset > 0 && page.offset >= page.total {
+        page.offset = page.total.saturating_sub(1) / PAGE_SIZE * PAGE_SIZE;
+        page.stale = true;
+    }
+
+    let visible_ids: Vec<Uuid> = visible_characters(&page, &character_list, &tag_filter)
+        .map(|c| c.id)
+        .collect();
+    focus.index = clamp_focus(focus.index, visible_ids.len());
+    let mut focus_moved = false;
+    let overlay_open = create_open.0 || bulk.confirm_open || focus.confirm_delete.is_some();
+    if !overlay_open && !ctx.wants_keyboard_input() {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::N)) {
+            create_open.0 = true;
+        }
+        let (down, up, enter, delete) = ctx.input(|i| {
+            (
+                i.key_pressed(egui::Key::ArrowDown),
+                i.key_pressed(egui::Key::ArrowUp),
+                i.key_pressed(egui::Key::Enter),
+                i.key_pressed(egui::Key::Delete),
+            )
+        });
+        if down || up {
+            focus.index = step_focus(focus.index, visible_ids.len(), down);
+            focus_moved = true;
+        }
+        let focused_id = focus.index.and_then(|i| visible_ids.get(i));
+        if enter {
+            selected = focused_id
+                .and_then(|id| page.characters.iter().find(|c| c.id == *id))
+                .cloned();
+        } else if delete && admin.0 {
+            focus.confirm_delete = focused_id.copied();
+        }
+    }
+    let focused_id = focus.index.and_then(|i| visible_ids.get(i)).copied();
+
     egui::Window::new("Select Character")
         .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
         .title_bar(false)
@@ -59,9 +206,9 @@ fn render_character_select(
         .resizable(false)
         .frame(
             egui::Frame::new()
-                .fill(SECONDARY_COLOR)
+                .fill(palette().secondary)
                 .corner_radius(8.0)
-                .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
                 .inner_margin(egui::Margin::same(20)),
         )
         .min_width(panel_width)
@@ -72,7 +219,7 @@ fn render_character_select(
                 ui.label(
                     egui::RichText::new("Select Character")
                         .size(24.0)
-                        .color(TEXT_COLOR)
+                        .color(palette().text)
                         .strong(),
                 );
                 ui.add_space(12.0);
@@ -81,36 +228,188 @@ fn render_character_select(
             ui.separator();
             ui.add_space(8.0);
 
+            if let Some(matches) = &character_list.reference_matches {
+                let mut clear = false;
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Referencing {}: {} match(es)",
+                            matches.label,
+                            matches.ids.len()
+                        ))
+                        .color(palette().text),
+                    );
+                    clear = ui.small_button("Clear").clicked();
+                });
+                if clear {
+                    character_list.reference_matches = None;
+                }
+                ui.add_space(6.0);
+            }
+
+            let search = ui.add(
+                egui::TextEdit::singleline(&mut page.query)
+                    .hint_text("Search by name")
+                    .desired_width(f32::INFINITY),
+            );
+            if search.changed() {
+                page.offset = 0;
+                page.stale = true;
+            }
+            ui.add_space(6.0);
+
             egui::ScrollArea::vertical()
                 .max_height(scroll_height)
                 .show(ui, |ui| {
-                    for summary in &character_list.characters {
-                        if render_character_entry(ui, summary) {
+                    for summary in visible_characters(&page, &character_list, &tag_filter) {
+                        let checked = bulk.ids.contains(&summary.id);
+                        let focused = focused_id == Some(summary.id);
+                        let action = render_character_entry(ui, summary, checked, focused);
+                        if focused && focus_moved {
+                            action.response.scroll_to_me(None);
+                        }
+                        if let Some(tags) = action.set_tags {
+                            pending_messages.0.push(shared::ClientMessage::SetTags {
+                                id: summary.id,
+                                tags,
+                            });
+                        }
+                        if let Some(new_name) = action.duplicate {
+                            pending_messages
+                                .0
+                                .push(shared::ClientMessage::CloneCharacter {
+                                    id: summary.id,
+                                    new_name,
+                                });
+                        }
+                        if let Some(password) = action.unlock {
+                            pending_messages
+                                .0
+                                .push(shared::ClientMessage::AuthenticateCharacter {
+                                    id: summary.id,
+                                    password,
+                                });
+                        }
+                        if action.toggle_selected {
+                            if checked {
+                                bulk.ids.remove(&summary.id);
+                            } else {
+                                bulk.ids.insert(summary.id);
+                            }
+                        } else if action.open {
                             selected = Some(summary.clone());
                         }
                         ui.add_space(6.0);
                     }
                 });
 
+            if page.total > PAGE_SIZE {
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    let has_prev = page.offset > 0;
+                    if ui
+                        .add_enabled(has_prev, egui::Button::new("< Prev"))
+                        .clicked()
+                    {
+                        page.offset = page.offset.saturating_sub(PAGE_SIZE);
+                        page.stale = true;
+                    }
+                    let last = (page.offset + PAGE_SIZE).min(page.total);
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{}-{} of {}",
+                            page.offset + 1,
+                            last,
+                            page.total
+                        ))
+                        .color(palette().text),
+                    );
+                    if ui
+                        .add_enabled(last < page.total, egui::Button::new("Next >"))
+                        .clicked()
+                    {
+                        page.offset += PAGE_SIZE;
+                        page.stale = true;
+                    }
+                });
+            }
+
             ui.add_space(8.0);
             ui.separator();
             ui.add_space(8.0);
 
             ui.vertical_centered(|ui| {
-                let button =
-                    egui::Button::new(egui::RichText::new("Create").size(16.0).color(TEXT_COLOR))
-                        .corner_radius(6.0)
-                        .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
-                        .fill(MAIN_COLOR)
-                        .min_size(egui::vec2(panel_width * 0.5, 36.0));
+                let button = egui::Button::new(
+                    egui::RichText::new("Create")
+                        .size(16.0)
+                        .color(palette().text),
+                )
+                .corner_radius(6.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .fill(palette().main)
+                .min_size(egui::vec2(panel_width * 0.5, 36.0));
 
                 if ui.add(button).clicked() {
                     create_open.0 = true;
                 }
+                ui.add_space(4.0);
+                if ui
+                    .button("Import JSON")
+                    .on_hover_text("Create a character from an exported file")
+                    .clicked()
+                {
+                    pick_file("JSON", &["json"], &import_picker.0);
+                }
+
+                if !bulk.ids.is_empty() {
+                    ui.add_space(6.0);
+                    let delete_button = egui::Button::new(
+                        egui::RichText::new(format!("Delete selected ({})", bulk.ids.len()))
+                            .size(14.0)
+                            .color(egui::Color32::WHITE),
+                    )
+                    .corner_radius(6.0)
+                    .fill(egui::Color32::from_rgb(0xCC, 0x33, 0x33))
+                    .min_size(egui::vec2(panel_width * 0.5, 30.0));
+                    if ui.add(delete_button).clicked() {
+                        bulk.confirm_open = true;
+                    }
+                    if admin.0 {
+                        ui.add_space(6.0);
+                        let ids: Vec<_> = bulk.ids.iter().copied().collect();
+                        render_grant_xp(ui, ids, &mut pending_messages);
+                    }
+                }
             });
+            ui.add_space(8.0);
+            if admin.0 {
+                render_reference_search(ui, &mut pending_messages);
+                ui.add_space(4.0);
+                let new_day = ui
+                    .button("New day")
+                    .on_hover_text("Make long rests available again for every character");
+                if new_day.clicked() {
+                    pending_messages.0.push(shared::ClientMessage::NewDay);
+                }
+                let reload = ui
+                    .button("Reload data files")
+                    .on_hover_text("Re-read traits, weapons, equipment and items on the server");
+                if reload.clicked() {
+                    pending_messages
+                        .0
+                        .push(shared::ClientMessage::ReloadRegistries);
+                }
+            } else {
+                render_admin_login(ui, &mut pending_messages);
+            }
             ui.add_space(4.0);
         });
 
+    if page.stale {
+        pending_messages.0.push(page.request());
+        page.stale = false;
+    }
+
     if let Some(summary) = selected {
         pending_messages
             .0
@@ -118,6 +417,49 @@ fn render_character_select(
         next_state.set(AppScreen::VersionSelect);
     }
 
+    if bulk.confirm_open {
+        let names: Vec<&str> = character_list
+            .characters
+            .iter()
+            .filter(|c| bulk.ids.contains(&c.id))
+            .map(|c| c.name.as_str())
+            .collect();
+        match render_bulk_delete_confirm(ctx, &names) {
+            Some(true) => {
+                pending_messages
+                    .0
+                    .push(shared::ClientMessage::DeleteCharacters {
+                        ids: bulk.ids.iter().copied().collect(),
+                    });
+                bulk.ids.clear();
+                bulk.confirm_open = false;
+            }
+            Some(false) => bulk.confirm_open = false,
+            None => {}
+        }
+    }
+
+    if let Some(id) = focus.confirm_delete {
+        let name = character_list
+            .characters
+            .iter()
+            .find(|c| c.id == id)
+            .map(|c| c.name.as_str());
+        let cancelled = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+        match name.and_then(|name| render_bulk_delete_confirm(ctx, &[name])) {
+            Some(true) => {
+                pending_messages
+                    .0
+                    .push(shared::ClientMessage::DeleteCharacter { id });
+                focus.confirm_delete = None;
+            }
+            Some(false) => focus.confirm_delete = None,
+            // Already gone, e.g. deleted from another client
+            None if name.is_none() || cancelled => focus.confirm_delete = None,
+            None => {}
+        }
+    }
+
     if create_open.0 {
         let existing_names: Vec<String> = character_list
             .characters
@@ -129,6 +471,7 @@ fn render_character_select(
             &mut create_open,
             &skill_registry,
             &trait_registry,
+            &ruleset,
             &mut pending_messages,
             &portrait_picker,
             &mut pending_creation_portrait,
@@ -139,47 +482,441 @@ fn render_character_select(
 
     // Poll picker & render crop popup when image is ready.
     crate::portrait::poll_and_render_crop_popup(ctx, &mut crop_editor, &portrait_picker);
+    crate::portrait::render_portrait_gallery(ctx, &mut crop_editor, &mut pending_messages);
 
     Ok(())
 }
 
-/// Renders a single character summary entry as a clickable card.
-/// Returns `true` if the card was clicked.
-fn render_character_entry(ui: &mut egui::Ui, summary: &CharacterSummary) -> bool {
+/// Sends a picked import file to the server.
+fn send_picked_import(
+    picker: Res<ImportPicker>,
+    mut pending_messages: ResMut<PendingClientMessages>,
+    mut toasts: ResMut<Toasts>,
+) {
+    let Some(bytes) = picker.0.take() else {
+        return;
+    };
+    match String::from_utf8(bytes) {
+        Ok(json) => pending_messages
+            .0
+            .push(shared::ClientMessage::ImportCharacter { json }),
+        Err(_) => toasts.push(ToastLevel::Error, "The picked file is not text JSON"),
+    }
+}
+
+/// XP amount field and button awarding it to the selected characters.
+fn render_grant_xp(
+    ui: &mut egui::Ui,
+    ids: Vec<Uuid>,
+    pending_messages: &mut PendingClientMessages,
+) {
+    let amount_id = egui::Id::new("grant_xp_amount");
+    let mut amount: u32 = ui.data(|d| d.get_temp(amount_id)).unwrap_or(10);
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::DragValue::new(&mut amount)
+                .range(1..=100_000)
+                .suffix(" XP"),
+        );
+        if ui
+            .button(format!("Grant to selected ({})", ids.len()))
+            .clicked()
+        {
+            pending_messages
+                .0
+                .push(shared::ClientMessage::GrantXp { ids, amount });
+        }
+    });
+    ui.data_mut(|d| d.insert_temp(amount_id, amount));
+}
+
+/// Collapsible password field that requests admin rights from the server.
+fn render_admin_login(ui: &mut egui::Ui, pending_messages: &mut PendingClientMessages) {
+    let password_id = egui::Id::new("admin_login_password");
+    let mut password: String = ui.data(|d| d.get_temp(password_id)).unwrap_or_default();
+
+    egui::CollapsingHeader::new(egui::RichText::new("Admin login").color(palette().text))
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let field = ui.add(
+                    egui::TextEdit::singleline(&mut password)
+                        .password(true)
+                        .hint_text("Password")
+                        .desired_width(160.0),
+                );
+                let submitted = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if (ui.button("Log in").clicked() || submitted) && !password.is_empty() {
+                    pending_messages
+                        .0
+                        .push(shared::ClientMessage::Authenticate {
+                            password: std::mem::take(&mut password),
+                        });
+                }
+            });
+        });
+
+    ui.data_mut(|d| d.insert_temp(password_id, password));
+}
+
+/// Admin query for characters that reference a trait, weapon, item, etc.
+fn render_reference_search(ui: &mut egui::Ui, pending_messages: &mut PendingClientMessages) {
+    let state_id = egui::Id::new("reference_search");
+    let (mut kind, mut name): (ReferenceKind, String) = ui
+        .data(|d| d.get_temp(state_id))
+        .unwrap_or((ReferenceKind::Trait, String::new()));
+
+    egui::CollapsingHeader::new(egui::RichText::new("Find by reference").color(palette().text))
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt("reference_kind")
+                    .selected_text(kind.to_string())
+                    .width(100.0)
+                    .show_ui(ui, |ui| {
+                        for option in ReferenceKind::iter() {
+                            ui.selectable_value(&mut kind, option, option.to_string());
+                        }
+                    });
+                let field = ui.add(
+                    egui::TextEdit::singleline(&mut name)
+                        .hint_text("Name")
+                        .desired_width(160.0),
+                );
+                let submitted = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let name = name.trim();
+                if (ui.button("Find").clicked() || submitted) && !name.is_empty() {
+                    pending_messages
+                        .0
+                        .push(shared::ClientMessage::FindByReference {
+                            kind,
+                            name: name.to_string(),
+                        });
+                }
+            });
+        });
+
+    ui.data_mut(|d| d.insert_temp(state_id, (kind, name)));
+}
+
+/// Characters of the current page left after the tag and reference filters.
+fn visible_characters<'a>(
+    page: &'a CharacterPage,
+    character_list: &'a CharacterList,
+    tag_filter: &'a TagFilter,
+) -> impl Iterator<Item = &'a CharacterSummary> {
+    page.characters
+        .iter()
+        .filter(|c| tag_filter.0.as_ref().is_none_or(|tag| c.has_tag(tag)))
+        .filter(|c| {
+            character_list
+                .reference_matches
+                .as_ref()
+                .is_none_or(|m| m.ids.contains(&c.id))
+        })
+}
+
+/// Keeps a focus index inside a list of `len` cards.
+fn clamp_focus(index: Option<usize>, len: usize) -> Option<usize> {
+    index.filter(|_| len > 0).map(|i| i.min(len - 1))
+}
+
+/// Moves the focus one card down (or up), starting at the first card.
+fn step_focus(index: Option<usize>, len: usize, down: bool) -> Option<usize> {
+    let next = match index {
+        None => 0,
+        Some(i) if down => i + 1,
+        Some(i) => i.saturating_sub(1),
+    };
+    clamp_focus(Some(next), len)
+}
+
+struct CharacterEntryAction {
+    response: egui::Response,
+    open: bool,
+    toggle_selected: bool,
+    /// New tags entered in the card's context menu.
+    set_tags: Option<Vec<String>>,
+    /// Name for a copy of this character, from the card's context menu.
+    duplicate: Option<String>,
+    /// Password entered to unlock a protected character.
+    unlock: Option<String>,
+}
+
+/// Number of characters carrying each tag.
+fn count_tags(characters: &[CharacterSummary]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for tag in characters.iter().flat_map(|c| &c.tags) {
+        *counts.entry(tag.clone()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Lists every tag with its character count; clicking one filters the list.
+fn render_tag_sidebar(
+    ctx: &egui::Context,
+    tag_counts: &BTreeMap<String, usize>,
+    total: usize,
+    tag_filter: &mut TagFilter,
+) {
+    if tag_counts.is_empty() {
+        return;
+    }
+    egui::Window::new("Tags")
+        .anchor(egui::Align2::LEFT_CENTER, [16.0, 0.0])
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(12)),
+        )
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new("Tags")
+                    .size(16.0)
+                    .color(palette().text)
+                    .strong(),
+            );
+            ui.add_space(6.0);
+            if ui
+                .selectable_label(tag_filter.0.is_none(), format!("All ({total})"))
+                .clicked()
+            {
+                tag_filter.0 = None;
+            }
+            for (tag, count) in tag_counts {
+                let selected = tag_filter.0.as_ref() == Some(tag);
+                if ui
+                    .selectable_label(selected, format!("{tag} ({count})"))
+                    .clicked()
+                {
+                    tag_filter.0 = if selected { None } else { Some(tag.clone()) };
+                }
+            }
+        });
+}
+
+/// Renders a single character summary entry as a clickable card with a
+/// bulk-selection checkbox. Ctrl-clicking the card also toggles selection.
+/// The keyboard-focused card gets a heavier outline.
+fn render_character_entry(
+    ui: &mut egui::Ui,
+    summary: &CharacterSummary,
+    checked: bool,
+    focused: bool,
+) -> CharacterEntryAction {
     let id = ui.id().with(summary.id);
     let was_hovered = ui.data(|d| d.get_temp::<bool>(id).unwrap_or(false));
 
-    let fill = if was_hovered {
-        MAIN_COLOR
+    let fill = if was_hovered || checked || focused {
+        palette().main
     } else {
-        SECONDARY_COLOR
+        palette().secondary
     };
 
+    let stroke = match crate::ui::accent_color(summary.accent_color) {
+        Some(accent) => egui::Stroke::new(2.0, accent),
+        None => egui::Stroke::new(1.0, palette().stroke),
+    };
+    let stroke = if focused {
+        egui::Stroke::new(3.0, palette().text)
+    } else {
+        stroke
+    };
+
+    let mut checkbox_clicked = false;
+
     let frame_response = egui::Frame::new()
         .corner_radius(6.0)
-        .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
+        .stroke(stroke)
         .inner_margin(egui::Margin::symmetric(14, 10))
         .fill(fill)
         .show(ui, |ui| {
             ui.set_width(ui.available_width());
-            ui.label(
-                egui::RichText::new(&summary.name)
-                    .size(16.0)
-                    .color(TEXT_COLOR),
-            );
-            ui.add_space(2.0);
-            ui.label(
-                egui::RichText::new(format!(
-                    "{}  |  {}  |  Level {}",
-                    summary.race, summary.class, summary.level
-                ))
-                .size(13.0)
-                .color(egui::Color32::from_rgb(0x88, 0x88, 0x99)),
-            );
+            ui.horizontal(|ui| {
+                let mut is_checked = checked;
+                if ui.checkbox(&mut is_checked, "").clicked() {
+                    checkbox_clicked = true;
+                }
+                ui.vertical(|ui| {
+                    let name = if summary.protected {
+                        format!("🔒 {}", summary.name)
+                    } else {
+                        summary.name.clone()
+                    };
+                    ui.label(egui::RichText::new(name).size(16.0).color(palette().text));
+                    ui.add_space(2.0);
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{}  |  {}  |  Level {}  |  Power {}",
+                            summary.race, summary.class, summary.level, summary.power_score
+                        ))
+                        .size(13.0)
+                        .color(egui::Color32::from_rgb(0x88, 0x88, 0x99)),
+                    );
+                    if !summary.tags.is_empty() {
+                        ui.label(
+                            egui::RichText::new(summary.tags.join(", "))
+                                .size(12.0)
+                                .italics()
+                                .color(egui::Color32::from_rgb(0x88, 0x88, 0x99)),
+                        );
+                    }
+                });
+            });
         });
 
-    let response = frame_response.response.interact(egui::Sense::click());
-    ui.data_mut(|d| d.insert_temp(id, response.hovered()));
+    let tags_id = id.with("tags_input");
+    let copy_name_id = id.with("copy_name");
+    let password_id = id.with("unlock_password");
+    let mut set_tags = None;
+    let mut duplicate = None;
+    let mut unlock = None;
+    frame_response
+        .response
+        .interact(egui::Sense::click())
+        .context_menu(|ui| {
+            let mut input: String = ui
+                .data(|d| d.get_temp(tags_id))
+                .unwrap_or_else(|| summary.tags.join(", "));
+            ui.label("Tags (comma-separated)");
+            ui.text_edit_singleline(&mut input);
+            if ui.button("Apply").clicked() {
+                set_tags = Some(input.split(',').map(|t| t.trim().to_string()).collect());
+                ui.data_mut(|d| d.remove::<String>(tags_id));
+                ui.close();
+            } else {
+                ui.data_mut(|d| d.insert_temp(tags_id, input));
+            }
+            ui.separator();
+            let mut copy_name: String = ui
+                .data(|d| d.get_temp(copy_name_id))
+                .unwrap_or_else(|| format!("{} (copy)", summary.name));
+            ui.label("Copy name");
+            ui.text_edit_singleline(&mut copy_name);
+            let trimmed = copy_name.trim().to_string();
+            if ui
+                .add_enabled(!trimmed.is_empty(), egui::Button::new("Duplicate"))
+                .clicked()
+            {
+                duplicate = Some(trimmed);
+                ui.data_mut(|d| d.remove::<String>(copy_name_id));
+                ui.close();
+            } else {
+                ui.data_mut(|d| d.insert_temp(copy_name_id, copy_name));
+            }
+            if summary.protected {
+                ui.separator();
+                let mut password: String = ui.data(|d| d.get_temp(password_id)).unwrap_or_default();
+                ui.label("Password to edit");
+                ui.add(egui::TextEdit::singleline(&mut password).password(true));
+                if ui
+                    .add_enabled(!password.is_empty(), egui::Button::new("Unlock"))
+                    .clicked()
+                {
+                    unlock = Some(password);
+                    ui.data_mut(|d| d.remove::<String>(password_id));
+                    ui.close();
+                } else {
+                    ui.data_mut(|d| d.insert_temp(password_id, password));
+                }
+            }
+        });
+
+    let response = &frame_response.response;
+    let hovered = response.contains_pointer();
+    let card_clicked = hovered && !checkbox_clicked && ui.input(|i| i.pointer.primary_clicked());
+    let ctrl_held = ui.input(|i| i.modifiers.command);
+    ui.data_mut(|d| d.insert_temp(id, hovered));
+
+    CharacterEntryAction {
+        response: frame_response.response,
+        open: card_clicked && !ctrl_held,
+        toggle_selected: checkbox_clicked || (card_clicked && ctrl_held),
+        set_tags,
+        duplicate,
+        unlock,
+    }
+}
 
-    response.clicked()
+/// Renders the bulk-delete confirmation listing the selected character names.
+/// Returns `Some(true)` on confirm, `Some(false)` on cancel, `None` while open.
+fn render_bulk_delete_confirm(ctx: &egui::Context, names: &[&str]) -> Option<bool> {
+    let mut result = None;
+    egui::Window::new("Confirm Bulk Delete")
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(20)),
+        )
+        .show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(format!("Delete {} character(s)?", names.len()))
+                        .size(18.0)
+                        .color(palette().text),
+                );
+                ui.add_space(8.0);
+                for name in names {
+                    ui.label(egui::RichText::new(*name).size(14.0).color(palette().text));
+                }
+                ui.add_space(16.0);
+                ui.horizontal(|ui| {
+                    let delete_btn = ui.add(
+                        egui::Button::new(
+                            egui::RichText::new("Delete")
+                                .size(14.0)
+                                .color(egui::Color32::WHITE),
+                        )
+                        .fill(egui::Color32::from_rgb(0xCC, 0x33, 0x33))
+                        .corner_radius(4.0),
+                    );
+                    if delete_btn.clicked() {
+                        result = Some(true);
+                    }
+                    ui.add_space(8.0);
+                    let cancel_btn = ui.add(
+                        egui::Button::new(
+                            egui::RichText::new("Cancel")
+                                .size(14.0)
+                                .color(palette().text),
+                        )
+                        .fill(palette().main)
+                        .stroke(egui::Stroke::new(1.0, palette().stroke))
+                        .corner_radius(4.0),
+                    );
+                    if cancel_btn.clicked() {
+                        result = Some(false);
+                    }
+                });
+                ui.add_space(4.0);
+            });
+        });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_stays_inside_the_list() {
+        assert_eq!(step_focus(None, 3, true), Some(0));
+        assert_eq!(step_focus(Some(2), 3, true), Some(2));
+        assert_eq!(step_focus(Some(0), 3, false), Some(0));
+        // The focused character was deleted from the end of the list
+        assert_eq!(clamp_focus(Some(2), 2), Some(1));
+        assert_eq!(clamp_focus(Some(0), 0), None);
+    }
 }
@@ -4,8 +4,9 @@ use bevy::prelude::*;
 use bevy_egui::egui;
 use shared::character::OnLvlUp;
 use shared::{
-    Character, CharacterSkill, CharacteristicKind, Characteristics, Class, Effect, EquipmentSlot,
-    InventoryItem, Protection, Race, Resist, Wallet as SharedWallet,
+    AbilityRuntime, Character, CharacterSkill, CharacteristicKind, Characteristics, Class,
+    Condition, Effect, EquipmentSlot, InventoryItem, Loadout, Protection, Race, Resist, SeededRng,
+    Stacked, StackingRules, TempEffect, Wallet as SharedWallet,
 };
 use strum::IntoEnumIterator;
 use uuid::Uuid;
@@ -21,6 +22,10 @@ pub struct CharacterId(pub Uuid);
 #[derive(Component)]
 pub struct CharacterName(pub String);
 
+/// Party accent color (RGB); `None` keeps the neutral styling.
+#[derive(Component)]
+pub struct AccentColor(pub Option<[u8; 3]>);
+
 #[derive(Component, Deref)]
 pub struct CharacterRace(pub Race);
 
@@ -37,6 +42,8 @@ pub struct Experience(pub u32);
 pub struct Hp {
     pub current: u32,
     pub max: u32,
+    /// Temporary HP on top of `max`; absorbs damage first.
+    pub temp: u32,
 }
 
 #[derive(Component)]
@@ -86,20 +93,64 @@ pub struct CharacterWeaponNames(pub Vec<String>);
 #[derive(Component, Deref, DerefMut)]
 pub struct CharacterEquipment(pub BTreeMap<EquipmentSlot, Vec<String>>);
 
+#[derive(Component, Deref, DerefMut)]
+pub struct CharacterLoadouts(pub BTreeMap<String, Loadout>);
+
+#[derive(Component, Deref, DerefMut)]
+pub struct CharacterHotbar(pub Vec<String>);
+
+/// Free-form player notes.
+#[derive(Component)]
+pub struct CharacterNotes(pub String);
+
+/// Spent per-rest uses and running cooldowns, by ability name.
+#[derive(Component, Deref, DerefMut)]
+pub struct AbilityRuntimes(pub BTreeMap<String, AbilityRuntime>);
+
+/// Long rests taken since the GM last started a new day.
+#[derive(Component)]
+pub struct RestsUsedToday(pub u32);
+
+/// Dice source of the character: its seeded sequence when it has an
+/// `rng_seed`, system randomness otherwise.
+#[derive(Component)]
+pub struct DiceRng(pub Option<SeededRng>);
+
+impl DiceRng {
+    pub fn next_u32(&mut self) -> u32 {
+        match &mut self.0 {
+            Some(rng) => rng.next_u32(),
+            None => getrandom::u32().unwrap_or_default(),
+        }
+    }
+}
+
 #[derive(Component, Deref, DerefMut)]
 #[allow(dead_code)]
 pub struct Inventory(pub Vec<InventoryItem>);
 
+/// Units in each stack of plain inventory items, by item name.
+#[derive(Component, Deref, DerefMut)]
+pub struct ItemCounts(pub BTreeMap<String, u32>);
+
 #[derive(Component, Deref, DerefMut)]
 pub struct ActiveEffects(pub Vec<Effect>);
 
+/// Effects added by hand for a number of turns.
+#[derive(Component, Deref, DerefMut)]
+pub struct TemporaryEffects(pub Vec<TempEffect>);
+
+/// Status conditions in effect until removed by hand.
+#[derive(Component, Deref, DerefMut)]
+pub struct CharacterConditions(pub Vec<Condition>);
+
 /// Holds the portrait texture for the active character.
 /// When absent, the placeholder avatar is used.
 #[derive(Component)]
 pub struct PortraitTexture(pub egui::TextureHandle);
 
 impl ActiveEffects {
-    pub fn get_resists(&self) -> BTreeMap<Resist, i32> {
+    pub fn get_resists(&self, stacking: &StackingRules) -> BTreeMap<Resist, Stacked> {
         let mut result: BTreeMap<Resist, i32> = Resist::iter().map(|k| (k, 0)).collect();
         for effect in self.iter() {
             if let Effect::Resist(r, m) = effect {
@@ -107,9 +158,12 @@ impl ActiveEffects {
             }
         }
         result
+            .into_iter()
+            .map(|(r, total)| (r, Stacked::new(total, stacking.resist)))
+            .collect()
     }
 
-    pub fn get_protections(&self) -> BTreeMap<Protection, i32> {
+    pub fn get_protections(&self, stacking: &StackingRules) -> BTreeMap<Protection, Stacked> {
         let mut result: BTreeMap<Protection, i32> = Protection::iter().map(|k| (k, 0)).collect();
         for effect in self.iter() {
             if let Effect::Protection(p, m) = effect {
@@ -117,6 +171,9 @@ impl ActiveEffects {
             }
         }
         result
+            .into_iter()
+            .map(|(p, total)| (p, Stacked::new(total, stacking.protection)))
+            .collect()
     }
 
     pub fn armor(&self) -> i32 {
@@ -137,18 +194,30 @@ impl ActiveEffects {
             .sum()
     }
 
-    pub fn characteristic_bonuses(&self) -> BTreeMap<CharacteristicKind, i32> {
+    /// Summed `Effect::Characteristic` bonuses, each up to the stacking cap.
+    pub fn characteristic_bonuses(
+        &self,
+        stacking: &StackingRules,
+    ) -> BTreeMap<CharacteristicKind, i32> {
         let mut result = BTreeMap::new();
         for effect in self.iter() {
             if let Effect::Characteristic(kind, v) = effect {
                 *result.entry(*kind).or_insert(0) += v;
             }
         }
+        for bonus in result.values_mut() {
+            *bonus = Stacked::new(*bonus, stacking.characteristic).value;
+        }
         result
     }
 
-    /// Returns the effective characteristic level (base + bonus from effects), minimum 0.
-    pub fn effective_level(&self, stats: &Characteristics, kind: CharacteristicKind) -> u32 {
+    /// Returns the effective characteristic level (base + capped bonus from effects), minimum 0.
+    pub fn effective_level(
+        &self,
+        stats: &Characteristics,
+        kind: CharacteristicKind,
+        stacking: &StackingRules,
+    ) -> u32 {
         let base = stats.get_level(kind);
         let bonus: i32 = self
             .0
@@ -158,16 +227,19 @@ impl ActiveEffects {
                 _ => None,
             })
             .sum();
-        (base as i32 + bonus).max(0) as u32
+        (base as i32 + Stacked::new(bonus, stacking.characteristic).value).max(0) as u32
     }
 
-    pub fn skill_bonus(&self, skill_name: &str) -> i32 {
-        self.iter()
+    /// Summed `Effect::Skill` bonuses to `skill_name`, up to the stacking cap.
+    pub fn skill_bonus(&self, skill_name: &str, stacking: &StackingRules) -> i32 {
+        let bonus = self
+            .iter()
             .filter_map(|e| match e {
                 Effect::Skill(name, v) if name == skill_name => Some(*v),
                 _ => None,
             })
-            .sum()
+            .sum();
+        Stacked::new(bonus, stacking.skill).value
     }
 
     pub fn action_points_bonus(&self) -> i32 {
@@ -181,7 +253,13 @@ impl ActiveEffects {
 }
 
 /// Spawns a new entity representing the active character with all ECS components.
-pub fn spawn_character(commands: &mut Commands, character: &Character) -> Entity {
+/// HP and mana maxima come from `character.active_effects`, so damage and spent
+/// mana beyond the bare formula survive the load.
+pub fn spawn_character(
+    commands: &mut Commands,
+    character: &Character,
+    campaign: &shared::CampaignRules,
+) -> Entity {
     commands
         .spawn((
             ActiveCharacter,
@@ -192,14 +270,15 @@ pub fn spawn_character(commands: &mut Commands, character: &Character) -> Entity
             Level(character.level),
             Experience(character.experience),
             {
-                let max = character.stats.endurance.level * 3 + 3;
+                let max = character.max_hp();
                 Hp {
                     current: max.saturating_sub(character.hp_spent),
                     max,
+                    temp: character.temp_hp,
                 }
             },
             {
-                let max = character.stats.willpower.level * 3 + 3;
+                let max = character.max_mana(campaign);
                 Mana {
                     current: max.saturating_sub(character.mana_spent),
                     max,
@@ -209,8 +288,18 @@ pub fn spawn_character(commands: &mut Commands, character: &Character) -> Entity
                 current: character.action_points.current,
                 max: character.action_points.max,
             },
+            (
+                CharacterHotbar(character.hotbar.clone()),
+                CharacterNotes(character.notes.clone()),
+                AbilityRuntimes(character.ability_runtime.clone()),
+            ),
+            RestsUsedToday(character.rests_used_today),
+            DiceRng(character.rng_seed.map(SeededRng::new)),
+            TemporaryEffects(character.temporary_effects.clone()),
+            CharacterConditions(character.conditions.clone()),
         ))
         .insert((
+            AccentColor(character.accent_color),
             CharacterStats(character.stats),
             CharacteristicPoints(character.characteristic_points),
             SkillPoints(character.skill_points),
@@ -221,14 +310,22 @@ pub fn spawn_character(commands: &mut Commands, character: &Character) -> Entity
             CharacterAbilityNames(character.abilities.clone()),
             CharacterWeaponNames(character.equipped_weapons.clone()),
             CharacterEquipment(character.equipped_equipment.clone()),
-            Inventory(character.inventory.clone()),
+            CharacterLoadouts(character.loadouts.clone()),
+            {
+                // Inventories saved before items stacked hold repeated cells
+                let mut inventory = character.inventory.clone();
+                let mut counts = character.item_counts.clone();
+                shared::stack_items(&mut inventory, &mut counts);
+                (Inventory(inventory), ItemCounts(counts))
+            },
             Wallet(character.wallet),
             ActiveEffects(character.active_effects.clone()),
         ))
         .id()
 }
 
-/// Recalculates active effects from race, traits, equipment, and base level-up bonuses.
+/// Recalculates active effects from race, traits, equipment, conditions,
+/// temporary effects, carried load and base level-up bonuses.
 /// Applies all effect types: Characteristic bonuses flow into derived stats (protections, HP, mana),
 /// ActionPoints bonus adjusts AP max, and Mana effects add to max mana.
 pub fn recalculate_effects(
@@ -238,28 +335,54 @@ pub fn recalculate_effects(
             &CharacterTraitNames,
             &CharacterWeaponNames,
             &CharacterEquipment,
+            &Inventory,
+            &ItemCounts,
+            &TemporaryEffects,
+            &CharacterConditions,
             &CharacterStats,
             &mut ActiveEffects,
             &mut Hp,
             &mut Mana,
             &mut ActionPoints,
+            &mut CharacterSkillList,
         ),
         Or<(
             Changed<CharacterRace>,
             Changed<CharacterTraitNames>,
             Changed<CharacterWeaponNames>,
             Changed<CharacterEquipment>,
+            Changed<Inventory>,
+            Changed<ItemCounts>,
+            Changed<TemporaryEffects>,
+            Changed<CharacterConditions>,
             Changed<CharacterStats>,
         )>,
     >,
     trait_registry: Res<crate::network::ClientTraitRegistry>,
     weapon_registry: Res<crate::network::ClientWeaponRegistry>,
     equipment_registry: Res<crate::network::ClientEquipmentRegistry>,
+    item_registry: Res<crate::network::ClientItemRegistry>,
+    ruleset: Res<crate::network::ClientRuleset>,
 ) {
-    for (race, traits, weapons, equipment, stats, mut effects, mut hp, mut mana, mut ap) in
-        &mut query
+    for (
+        race,
+        traits,
+        weapons,
+        equipment,
+        inventory,
+        counts,
+        temporary,
+        conditions,
+        stats,
+        mut effects,
+        mut hp,
+        mut mana,
+        mut ap,
+        mut skills,
+    ) in &mut query
     {
         let s = &**stats;
+        let stacking = &ruleset.stacking;
 
         // Step 1: Collect source effects and store them so effective_level() works
         **effects = shared::collect_source_effects(
@@ -267,52 +390,57 @@ pub fn recalculate_effects(
             traits,
             weapons,
             equipment,
+            conditions,
             &trait_registry,
             &weapon_registry,
             &equipment_registry,
+            &ruleset.campaign.effect_modifiers,
+        );
+        effects.extend(shared::active_temp_effects(temporary));
+        let load = shared::carried_load(
+            inventory,
+            counts,
+            weapons,
+            equipment,
+            &item_registry,
+            &equipment_registry,
+            &weapon_registry,
         );
+        let capacity = shared::carry_capacity(effects.effective_level(
+            s,
+            CharacteristicKind::Strength,
+            stacking,
+        ));
+        effects.extend(shared::overload_penalty(load, capacity));
 
         // Step 2: Compute effective characteristic levels (base + bonuses from source effects)
-        let eff_dexterity = effects.effective_level(s, CharacteristicKind::Dexterity);
-        let eff_endurance = effects.effective_level(s, CharacteristicKind::Endurance);
-        let eff_magic = effects.effective_level(s, CharacteristicKind::Magic);
-        let eff_willpower = effects.effective_level(s, CharacteristicKind::Willpower);
-        let eff_intellect = effects.effective_level(s, CharacteristicKind::Intellect);
+        let eff_endurance = effects.effective_level(s, CharacteristicKind::Endurance, stacking);
+        let eff_intellect = effects.effective_level(s, CharacteristicKind::Intellect, stacking);
 
         // Step 3: Prepend default effects (computed from effective levels)
         let mut combined = vec![
             Effect::OnLvlUp(OnLvlUp::AddAbilityPoints(1)),
             Effect::OnLvlUp(OnLvlUp::AddSkillPoints(3 + eff_intellect as i32)),
             Effect::OnLvlUp(OnLvlUp::AddCharacteristicPoints(2)),
-            Effect::Protection(Protection::Melee, 10 + eff_dexterity as i32),
-            Effect::Protection(Protection::Magic, 10 + eff_magic as i32),
-            Effect::Protection(Protection::Body, 10 + eff_endurance as i32),
-            Effect::Protection(Protection::Mind, 10 + eff_willpower as i32),
-            // ProtectionRange comes from race size
         ];
+        combined.extend(shared::base_protections(|kind| {
+            effects.effective_level(s, kind, stacking)
+        }));
         combined.append(&mut effects);
         **effects = combined;
 
-        // Step 4: Recompute HP max using effective endurance
-        let new_max_hp = eff_endurance * 3 + 3;
+        // Step 4: Recompute HP max using effective endurance + MaxHp effect bonuses
+        let new_max_hp = shared::max_hp(eff_endurance, &effects);
         if hp.max != new_max_hp {
             let spent = hp.max.saturating_sub(hp.current);
             hp.max = new_max_hp;
             hp.current = new_max_hp.saturating_sub(spent);
         }
 
-        // Step 5: Recompute Mana max using effective willpower + Mana effect bonuses
-        let mut new_max_mana = eff_willpower * 3 + 3;
-        for effect in effects.iter() {
-            if let Effect::Mana {
-                dependent,
-                increase_per_point,
-            } = effect
-            {
-                let dep_level = effects.effective_level(s, *dependent) as i32;
-                new_max_mana = (new_max_mana as i32 + dep_level * increase_per_point).max(0) as u32;
-            }
-        }
+        // Step 5: Recompute Mana max from the campaign's base characteristic + Mana/MaxMana bonuses
+        let new_max_mana = ruleset
+            .campaign
+            .max_mana(&effects, |k| effects.effective_level(s, k, stacking));
         if mana.max != new_max_mana {
             let spent = mana.max.saturating_sub(mana.current);
             mana.max = new_max_mana;
@@ -327,15 +455,50 @@ pub fn recalculate_effects(
             ap.max = new_max_ap;
             ap.current = new_max_ap.saturating_sub(spent);
         }
+
+        // Step 9: Add or remove free skill ranks granted by FreeSkillRank effects
+        if shared::sync_free_ranks(&mut skills.bypass_change_detection().0, &effects) {
+            skills.set_changed();
+        }
     }
 }
 
-/// Despawns the active character entity when leaving the character sheet screen.
+/// Despawns the active character entity when leaving the character sheet screen,
+/// together with the real character behind a running simulation.
 pub fn despawn_active_character(
     mut commands: Commands,
-    query: Query<Entity, With<ActiveCharacter>>,
+    query: Query<(Entity, Option<&crate::simulation::Simulating>), With<ActiveCharacter>>,
 ) {
-    for entity in &query {
+    for (entity, simulating) in &query {
         commands.entity(entity).despawn();
+        if let Some(simulating) = simulating {
+            commands.entity(simulating.original).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawn_keeps_damage_beyond_base_max_hp() {
+        let mut world = World::new();
+        let mut character = Character::new("Tank".to_string());
+        character.stats.endurance.level = 2;
+        character.active_effects = vec![Effect::MaxHp(10)];
+        // 19 max HP with the bonus, only 9 without it
+        character.hp_spent = 15;
+
+        let entity = spawn_character(
+            &mut world.commands(),
+            &character,
+            &shared::CampaignRules::default(),
+        );
+        world.flush();
+
+        let hp = world.get::<Hp>(entity).unwrap();
+        assert_eq!(hp.max, 19);
+        assert_eq!(hp.current, 4);
     }
 }
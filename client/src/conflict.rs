@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+use shared::{CharacterSummary, CharacterVersion, ClientMessage};
+use ui_widgets::colors::palette;
+use uuid::Uuid;
+
+use crate::network::PendingClientMessages;
+use crate::offline::OfflineStore;
+use crate::state::AppScreen;
+
+/// Newest server version of the open character when it was loaded or last
+/// saved, sent with saves so edits made elsewhere are not overwritten.
+#[derive(Resource, Default)]
+pub struct BaseVersion(pub Option<(Uuid, u32)>);
+
+impl BaseVersion {
+    /// The base version to save `id` from, if it is the character tracked.
+    pub fn of(&self, id: Uuid) -> Option<u32> {
+        self.0
+            .filter(|(base_id, _)| *base_id == id)
+            .map(|(_, version)| version)
+    }
+}
+
+/// The stored version a save was refused over, waiting for the user to
+/// reload it or keep editing.
+#[derive(Resource, Default)]
+pub struct PendingConflict(pub Option<CharacterVersion>);
+
+/// The open character as it was last loaded or saved, to tell whether the
+/// sheet has edits that are not stored yet.
+#[derive(Resource, Default)]
+pub struct SavedCharacter(pub Option<shared::Character>);
+
+/// A save of the open character made by another connection, waiting to be
+/// applied to the sheet.
+#[derive(Resource, Default)]
+pub struct RemoteSave(pub Option<CharacterSummary>);
+
+pub struct ConflictPlugin;
+
+impl Plugin for ConflictPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BaseVersion>()
+            .init_resource::<PendingConflict>()
+            .init_resource::<SavedCharacter>()
+            .init_resource::<RemoteSave>()
+            .add_systems(EguiPrimaryContextPass, render_conflict);
+    }
+}
+
+/// "Reload" fetches the stored latest version, dropping the local edits;
+/// "Keep editing" takes it as the new base, so the next save overwrites it.
+/// A save made offline is refused the same way once it is replayed; its
+/// prompt shows on any screen, and "Keep local" saves the offline copy over
+/// the server's version right away, while "Keep server" opens the latter.
+fn render_conflict(
+    mut contexts: EguiContexts,
+    mut pending: ResMut<PendingConflict>,
+    mut base: ResMut<BaseVersion>,
+    mut pending_messages: ResMut<PendingClientMessages>,
+    mut offline: ResMut<OfflineStore>,
+    screen: Res<State<AppScreen>>,
+) -> Result {
+    let Some(latest) = &pending.0 else {
+        return Ok(());
+    };
+    let id = latest.character.id;
+    let offline_edit = offline.saves.contains_key(&id);
+    if !offline_edit && *screen.get() != AppScreen::CharacterSheet {
+        return Ok(());
+    }
+    let ctx = contexts.ctx_mut()?;
+
+    let (title, since, question, reload_label, keep_label) = if offline_edit {
+        (
+            "Your offline changes conflict with the server",
+            "while you were offline",
+            "Keep your local version and save it over theirs, or keep the server's?",
+            "Keep server",
+            "Keep local",
+        )
+    } else {
+        (
+            "Someone else edited this character",
+            "since you opened it",
+            "Reload their version, or keep editing and save over it?",
+            "Reload",
+            "Keep editing",
+        )
+    };
+    let mut reload = false;
+    let mut keep = false;
+    egui::Window::new(title)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(12)),
+        )
+        .show(ctx, |ui| {
+            let by = latest
+                .saved_by
+                .as_deref()
+                .map(|by| format!(" by {by}"))
+                .unwrap_or_default();
+            ui.label(
+                egui::RichText::new(format!(
+                    "{} was saved as version {}{by} {since}. \
+                     Your changes were not saved.",
+                    latest.character.name, latest.version
+                ))
+                .color(palette().text),
+            );
+            ui.add_space(4.0);
+            ui.label(egui::RichText::new(question).color(palette().text));
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                reload = ui.button(reload_label).clicked();
+                keep = ui.button(keep_label).clicked();
+            });
+        });
+
+    if reload {
+        pending_messages
+            .0
+            .push(ClientMessage::RequestCharacterVersion { id, version: None });
+        offline.saves.remove(&id);
+    }
+    if keep {
+        if base.of(id).is_some() || !offline_edit {
+            base.0 = Some((id, latest.version));
+        }
+        // The offline copy stays until the server takes this save
+        if let Some(local) = offline.saves.get(&id) {
+            pending_messages.0.push(ClientMessage::UpdateCharacter {
+                character: Box::new(local.clone()),
+                base_version: Some(latest.version),
+            });
+        }
+    }
+    if reload || keep {
+        pending.0 = None;
+    }
+
+    Ok(())
+}
@@ -1,12 +1,15 @@
 use bevy::prelude::*;
 use bevy_egui::egui;
 use strum::IntoEnumIterator;
-use ui_widgets::colors::{MAIN_COLOR, STROKE_COLOR, TEXT_COLOR};
+use ui_widgets::colors::palette;
 use ui_widgets::composites::{
     Characteristics, GridAction, Points, SkillEntry, Skills, TraitEntry, Traits,
 };
 
-use shared::{CharacterSkill, Characteristics as Stats, Class, Race};
+use shared::{
+    CharacterSkill, CharacteristicKind, Characteristics as Stats, Class, CreationRules, Dice, Race,
+    Ruleset,
+};
 
 use crate::ui::{format_effect, render_trait_select_overlay, TraitSelectMode};
 
@@ -16,14 +19,24 @@ use crate::portrait::{CropEditorSlot, PendingCreationPortrait, PortraitPickerRes
 #[derive(Resource, Default)]
 pub struct CreateCharacterOpen(pub bool);
 
+/// Levels a rolled characteristic can land on, before race floors.
+const ROLLED_LEVELS: std::ops::RangeInclusive<u32> = 1..=5;
+
 #[derive(Clone)]
 struct CreateCharacterState {
     name: String,
+    /// Optional password protecting the character from edits by others.
+    password: String,
+    accent_color: Option<[u8; 3]>,
     race_idx: usize,
     class_idx: usize,
     characteristic_points: u32,
     skill_points: i32,
     stats: Stats,
+    /// Race whose stat floors `stats` currently start from (`None`: default floors).
+    floors_race: Option<Race>,
+    /// Characteristics were rolled instead of bought with points.
+    rolled: bool,
     skills: Vec<CharacterSkill>,
     selected_traits: Vec<String>,
     traits_open: bool,
@@ -31,15 +44,89 @@ struct CreateCharacterState {
     portrait_texture: Option<egui::TextureHandle>,
 }
 
-impl Default for CreateCharacterState {
-    fn default() -> Self {
+impl CreateCharacterState {
+    /// Floor of `kind` for the race the stats currently start from.
+    fn stat_floor(&self, ruleset: &Ruleset, kind: CharacteristicKind) -> u32 {
+        self.floors_race.map_or(shared::DEFAULT_STAT_FLOOR, |race| {
+            ruleset.races.stat_floor(&race, kind)
+        })
+    }
+
+    /// Restarts every characteristic at `race`'s floor, refunding the points
+    /// spent above the previous floors. Rolled characteristics are only raised
+    /// to the new floors.
+    fn apply_race_floors(&mut self, ruleset: &Ruleset, race: Race) {
+        let prev_intellect = self.stats.intellect.level;
+        for kind in CharacteristicKind::iter() {
+            let old_floor = self.stat_floor(ruleset, kind);
+            let floor = ruleset.races.stat_floor(&race, kind);
+            let stat = self.stats.get_mut(kind);
+            if self.rolled {
+                stat.level = stat.level.max(floor);
+                continue;
+            }
+            // Raising a characteristic from N costs N + 1
+            self.characteristic_points += (old_floor + 1..=stat.level).sum::<u32>();
+            stat.level = floor;
+        }
+        let per_intellect = ruleset.creation.skill_points_per_intellect as i32;
+        self.skill_points +=
+            per_intellect * (self.stats.intellect.level as i32 - prev_intellect as i32);
+        self.floors_race = Some(race);
+    }
+
+    /// Lowers a characteristic by one level, refunding its cost, unless it is
+    /// already at the race floor.
+    fn downgrade_stat(&mut self, ruleset: &Ruleset, kind: CharacteristicKind) {
+        let floor = self.stat_floor(ruleset, kind);
+        let stat = self.stats.get_mut(kind);
+        if stat.level > floor {
+            let refund = stat.level;
+            stat.level -= 1;
+            self.characteristic_points += refund;
+        }
+    }
+
+    /// Rolls every characteristic (never below its race floor). No
+    /// characteristic points are left to spend and skills start over.
+    fn roll_stats(&mut self, ruleset: &Ruleset, rng: &mut impl FnMut() -> u32) {
+        for kind in CharacteristicKind::iter() {
+            let floor = self.stat_floor(ruleset, kind);
+            let level = Dice::STAT.roll_level(rng, ROLLED_LEVELS).max(floor);
+            self.stats.get_mut(kind).level = level;
+        }
+        self.rolled = true;
+        self.characteristic_points = 0;
+        self.skills.clear();
+        self.skill_points = ruleset
+            .creation
+            .skill_points_for(self.stats.intellect.level) as i32;
+    }
+
+    /// Goes back to point-buy from the race floors, with the starting points.
+    fn reset_point_buy(&mut self, ruleset: &Ruleset) {
+        let fresh = Self::new(&ruleset.creation);
+        self.rolled = false;
+        self.characteristic_points = fresh.characteristic_points;
+        self.skill_points = fresh.skill_points;
+        self.stats = fresh.stats;
+        self.skills.clear();
+        if let Some(race) = self.floors_race.take() {
+            self.apply_race_floors(ruleset, race);
+        }
+    }
+
+    /// Fresh point-buy state with the campaign's starting points.
+    fn new(creation: &CreationRules) -> Self {
         let intellect = 1;
         Self {
             name: String::new(),
+            password: String::new(),
+            accent_color: None,
             race_idx: 0,
             class_idx: 0,
-            characteristic_points: 18,
-            skill_points: 10 + 3 * intellect as i32,
+            characteristic_points: creation.characteristic_points,
+            skill_points: creation.skill_points_for(intellect) as i32,
             stats: Stats {
                 strength: shared::Characteristic::new(1),
                 dexterity: shared::Characteristic::new(1),
@@ -50,6 +137,8 @@ impl Default for CreateCharacterState {
                 intellect: shared::Characteristic::new(intellect),
                 charisma: shared::Characteristic::new(1),
             },
+            floors_race: None,
+            rolled: false,
             skills: Vec::new(),
             selected_traits: Vec::new(),
             traits_open: false,
@@ -64,6 +153,7 @@ pub fn render_create_character_overlay(
     create_open: &mut CreateCharacterOpen,
     skill_registry: &ClientSkillRegistry,
     trait_registry: &ClientTraitRegistry,
+    ruleset: &Ruleset,
     pending_messages: &mut crate::network::PendingClientMessages,
     portrait_picker: &PortraitPickerResult,
     pending_creation_portrait: &mut PendingCreationPortrait,
@@ -91,9 +181,11 @@ pub fn render_create_character_overlay(
         });
 
     // Centered white dialog
-    let dialog_w = (screen.width() * 0.5).max(440.0).min(700.0);
+    let dialog_w = (screen.width() * 0.5).clamp(440.0, 700.0);
 
-    let mut state: CreateCharacterState = ctx.data(|d| d.get_temp(state_id)).unwrap_or_default();
+    let mut state: CreateCharacterState = ctx
+        .data(|d| d.get_temp(state_id))
+        .unwrap_or_else(|| CreateCharacterState::new(&ruleset.creation));
 
     let selected_class = Class::iter().nth(state.class_idx).unwrap_or_default();
 
@@ -105,9 +197,9 @@ pub fn render_create_character_overlay(
         .resizable(false)
         .frame(
             egui::Frame::new()
-                .fill(MAIN_COLOR)
+                .fill(palette().main)
                 .corner_radius(12.0)
-                .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
                 .inner_margin(egui::Margin::same(20)),
         )
         .min_width(dialog_w)
@@ -118,7 +210,7 @@ pub fn render_create_character_overlay(
                 ui.label(
                     egui::RichText::new("Create Character")
                         .size(24.0)
-                        .color(TEXT_COLOR)
+                        .color(palette().text)
                         .strong(),
                 );
                 ui.add_space(12.0);
@@ -148,7 +240,7 @@ pub fn render_create_character_overlay(
                         egui::Align2::CENTER_CENTER,
                         "?",
                         egui::FontId::proportional(24.0),
-                        TEXT_COLOR,
+                        palette().text,
                     );
                 }
                 ui.add_space(8.0);
@@ -158,14 +250,39 @@ pub fn render_create_character_overlay(
                     } else {
                         "Upload Portrait"
                     };
-                    let button =
-                        egui::Button::new(egui::RichText::new(label).size(14.0).color(TEXT_COLOR))
-                            .corner_radius(6.0)
-                            .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
-                            .fill(MAIN_COLOR);
+                    let button = egui::Button::new(
+                        egui::RichText::new(label).size(14.0).color(palette().text),
+                    )
+                    .corner_radius(6.0)
+                    .stroke(egui::Stroke::new(1.0, palette().stroke))
+                    .fill(palette().main);
                     if ui.add(button).clicked() {
                         crate::portrait::spawn_portrait_picker(portrait_picker);
                     }
+                    ui.add_space(4.0);
+                    let gallery = egui::Button::new(
+                        egui::RichText::new("Choose from Gallery")
+                            .size(14.0)
+                            .color(palette().text),
+                    )
+                    .corner_radius(6.0)
+                    .stroke(egui::Stroke::new(1.0, palette().stroke))
+                    .fill(palette().main);
+                    if ui.add(gallery).clicked() {
+                        crop_editor.gallery.show();
+                    }
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        let mut use_accent = state.accent_color.is_some();
+                        ui.checkbox(&mut use_accent, "Accent color");
+                        match (use_accent, &mut state.accent_color) {
+                            (true, Some(rgb)) => {
+                                ui.color_edit_button_srgb(rgb);
+                            }
+                            (true, None) => state.accent_color = Some([0x80, 0x80, 0x80]),
+                            (false, _) => state.accent_color = None,
+                        }
+                    });
                 });
             });
 
@@ -182,12 +299,30 @@ pub fn render_create_character_overlay(
 
             // Name
             ui.horizontal(|ui| {
-                ui.label(egui::RichText::new("Name:").size(15.0).color(TEXT_COLOR));
+                ui.label(
+                    egui::RichText::new("Name:")
+                        .size(15.0)
+                        .color(palette().text),
+                );
                 ui.add_sized(
                     [ui.available_width(), 24.0],
                     egui::TextEdit::singleline(&mut state.name),
                 );
             });
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new("Password:")
+                        .size(15.0)
+                        .color(palette().text),
+                );
+                ui.add_sized(
+                    [ui.available_width(), 24.0],
+                    egui::TextEdit::singleline(&mut state.password)
+                        .password(true)
+                        .hint_text("Optional, needed by others to edit"),
+                );
+            });
             ui.add_space(10.0);
 
             // Race & Class
@@ -196,7 +331,11 @@ pub fn render_create_character_overlay(
             let classes: Vec<String> = Class::iter().map(|c| c.to_string()).collect();
             state.class_idx = state.class_idx.min(classes.len().saturating_sub(1));
             ui.horizontal(|ui| {
-                ui.label(egui::RichText::new("Race:").size(15.0).color(TEXT_COLOR));
+                ui.label(
+                    egui::RichText::new("Race:")
+                        .size(15.0)
+                        .color(palette().text),
+                );
                 egui::ComboBox::from_id_salt("create_char_race")
                     .selected_text(&races[state.race_idx])
                     .show_ui(ui, |ui| {
@@ -204,8 +343,16 @@ pub fn render_create_character_overlay(
                             ui.selectable_value(&mut state.race_idx, i, label.as_str());
                         }
                     });
+                let race = Race::iter().nth(state.race_idx).unwrap_or_default();
+                if state.floors_race != Some(race) {
+                    state.apply_race_floors(ruleset, race);
+                }
                 ui.add_space(12.0);
-                ui.label(egui::RichText::new("Class:").size(15.0).color(TEXT_COLOR));
+                ui.label(
+                    egui::RichText::new("Class:")
+                        .size(15.0)
+                        .color(palette().text),
+                );
                 egui::ComboBox::from_id_salt("create_char_class")
                     .selected_text(&classes[state.class_idx])
                     .show_ui(ui, |ui| {
@@ -218,11 +365,11 @@ pub fn render_create_character_overlay(
                 let trait_button = egui::Button::new(
                     egui::RichText::new(trait_label)
                         .size(14.0)
-                        .color(TEXT_COLOR),
+                        .color(palette().text),
                 )
                 .corner_radius(6.0)
-                .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
-                .fill(MAIN_COLOR);
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .fill(palette().main);
                 if ui.add(trait_button).clicked() {
                     state.traits_open = !state.traits_open;
                 }
@@ -230,6 +377,24 @@ pub fn render_create_character_overlay(
 
             ui.add_space(12.0);
 
+            // Creation mode: point-buy (default) or rolled characteristics
+            ui.horizontal(|ui| {
+                let mut rolled = state.rolled;
+                ui.checkbox(&mut rolled, "Roll stats");
+                let mut rng = || getrandom::u32().unwrap_or_default();
+                if rolled != state.rolled {
+                    if rolled {
+                        state.roll_stats(ruleset, &mut rng);
+                    } else {
+                        state.reset_point_buy(ruleset);
+                    }
+                }
+                if state.rolled && ui.button("Reroll").clicked() {
+                    state.roll_stats(ruleset, &mut rng);
+                }
+            });
+            ui.add_space(8.0);
+
             // Points (editable)
             let width = ui.available_width();
             let points_size = egui::vec2(width, 36.0);
@@ -240,7 +405,7 @@ pub fn render_create_character_overlay(
                     .layout(egui::Layout::top_down(egui::Align::Min)),
             );
             let pts = Points::new(state.characteristic_points, state.skill_points)
-                .editable(true)
+                .editable(!state.rolled)
                 .show(&mut points_ui);
             state.characteristic_points = pts.characteristic_points;
             state.skill_points = pts.skill_points;
@@ -270,7 +435,7 @@ pub fn render_create_character_overlay(
             );
             let prev_intellect = state.stats.intellect.level;
             match Characteristics::new(char_values)
-                .edit_mode(true, state.characteristic_points)
+                .edit_mode(!state.rolled, state.characteristic_points)
                 .show(&mut char_ui)
             {
                 Some(GridAction::Upgrade(idx)) => {
@@ -289,31 +454,18 @@ pub fn render_create_character_overlay(
                     state.characteristic_points -= spent;
                 }
                 Some(GridAction::Downgrade(idx)) => {
-                    let stat = match idx {
-                        0 => &mut state.stats.strength,
-                        1 => &mut state.stats.dexterity,
-                        2 => &mut state.stats.endurance,
-                        3 => &mut state.stats.perception,
-                        4 => &mut state.stats.magic,
-                        5 => &mut state.stats.willpower,
-                        6 => &mut state.stats.intellect,
-                        7 => &mut state.stats.charisma,
-                        _ => unreachable!(),
-                    };
-                    if stat.level > 1 {
-                        let refund = stat.level;
-                        stat.level -= 1;
-                        state.characteristic_points += refund;
-                    }
+                    let kind = CharacteristicKind::iter().nth(idx).expect("grid index");
+                    state.downgrade_stat(ruleset, kind);
                 }
-                None => {}
+                Some(GridAction::Roll(_)) | None => {}
             }
             // Intellect change adjusts skill points (±1 per level change)
             let new_intellect = state.stats.intellect.level;
+            let per_intellect = ruleset.creation.skill_points_per_intellect as i32;
             if new_intellect > prev_intellect {
-                state.skill_points += 3;
+                state.skill_points += per_intellect;
             } else if new_intellect < prev_intellect {
-                state.skill_points -= 3;
+                state.skill_points -= per_intellect;
             }
 
             ui.add_space(8.0);
@@ -389,7 +541,7 @@ pub fn render_create_character_overlay(
                     // Remove skills with level 0
                     state.skills.retain(|s| s.level > 0);
                 }
-                None => {}
+                Some(GridAction::Roll(_)) | None => {}
             }
 
             ui.add_space(8.0);
@@ -443,12 +595,15 @@ pub fn render_create_character_overlay(
                     && !has_over_limit_skills
                     && all_points_spent
                     && state.selected_traits.len() == 3;
-                let button =
-                    egui::Button::new(egui::RichText::new("Create").size(16.0).color(TEXT_COLOR))
-                        .corner_radius(6.0)
-                        .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
-                        .fill(MAIN_COLOR)
-                        .min_size(egui::vec2(dialog_w * 0.5, 36.0));
+                let button = egui::Button::new(
+                    egui::RichText::new("Create")
+                        .size(16.0)
+                        .color(palette().text),
+                )
+                .corner_radius(6.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .fill(palette().main)
+                .min_size(egui::vec2(dialog_w * 0.5, 36.0));
 
                 if ui.add_enabled(can_create, button).clicked() {
                     let selected_race = Race::iter().nth(state.race_idx).unwrap_or_default();
@@ -459,13 +614,15 @@ pub fn render_create_character_overlay(
                         .0
                         .push(shared::ClientMessage::CreateCharacter {
                             name: state.name.clone(),
+                            accent_color: state.accent_color,
                             race: selected_race,
                             class: selected_class,
-                            stats: state.stats.clone(),
+                            stats: state.stats,
                             skills: state.skills.clone(),
                             traits: state.selected_traits.clone(),
+                            password: Some(state.password.clone()).filter(|p| !p.is_empty()),
                         });
-                    state = CreateCharacterState::default();
+                    state = CreateCharacterState::new(&ruleset.creation);
                     create_open.0 = false;
                 }
             });
@@ -487,3 +644,43 @@ pub fn render_create_character_overlay(
 
     ctx.data_mut(|d| d.insert_temp(state_id, state));
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use shared::{RaceRules, RaceRulesRegistry};
+
+    use super::*;
+
+    #[test]
+    fn test_race_floor_blocks_downgrade() {
+        let ruleset = Ruleset {
+            races: RaceRulesRegistry {
+                races: BTreeMap::from([(
+                    Race::HalfElf,
+                    RaceRules {
+                        stat_floors: BTreeMap::from([(CharacteristicKind::Strength, 3)]),
+                    },
+                )]),
+            },
+            ..Default::default()
+        };
+        let mut state = CreateCharacterState::new(&ruleset.creation);
+        let points = state.characteristic_points;
+
+        state.apply_race_floors(&ruleset, Race::HalfElf);
+        assert_eq!(state.stats.strength.level, 3);
+        assert_eq!(state.characteristic_points, points);
+
+        state.downgrade_stat(&ruleset, CharacteristicKind::Strength);
+        assert_eq!(state.stats.strength.level, 3);
+        assert_eq!(state.characteristic_points, points);
+
+        // Other characteristics keep the default floor of 1
+        state.stats.dexterity.level = 2;
+        state.downgrade_stat(&ruleset, CharacteristicKind::Dexterity);
+        state.downgrade_stat(&ruleset, CharacteristicKind::Dexterity);
+        assert_eq!(state.stats.dexterity.level, 1);
+    }
+}
@@ -1,22 +1,22 @@
 use bevy::prelude::*;
 use bevy_egui::egui;
 use strum::IntoEnumIterator;
-use ui_widgets::colors::MAIN_COLOR;
+use ui_widgets::colors::palette;
 
 use shared::character::OnLvlUp;
 use shared::{
-    CharacteristicKind, Effect, EquipmentSlot, MeleeKind, Protection, RangeKind, Resist,
-    WeaponGrip, WeaponKind,
+    CharacteristicKind, Effect, EquipmentRegistry, EquipmentSlot, InventoryItem, ItemRegistry,
+    MeleeKind, Protection, RangeKind, Resist, WeaponGrip, WeaponKind, WeaponRegistry,
 };
 
-use crate::events::CreateItem;
+use crate::events::{CreateItem, ItemEntry};
 
 #[derive(Resource, Default)]
 pub struct CreateItemOpen(pub bool);
 
 const ITEM_TYPE_LABELS: [&str; 3] = ["Item", "Equipment", "Weapon"];
 
-const EFFECT_TYPE_LABELS: [&str; 9] = [
+const EFFECT_TYPE_LABELS: [&str; 12] = [
     "Resist",
     "Skill",
     "Protection",
@@ -26,6 +26,9 @@ const EFFECT_TYPE_LABELS: [&str; 9] = [
     "Armor",
     "Mana",
     "OnLvlUp",
+    "Free Skill Rank",
+    "Max HP",
+    "Max Mana",
 ];
 
 /// Renders an egui ComboBox populated from an `EnumIter + Display` enum.
@@ -58,6 +61,10 @@ struct CreateItemState {
     item_type: usize,
     name: String,
     description: String,
+    /// Optional value in base currency units.
+    value: String,
+    /// Optional carry weight.
+    weight: String,
     slot_idx: usize,
     damage: String,
     attack: String,
@@ -66,14 +73,222 @@ struct CreateItemState {
     melee_subtype_idx: usize,
     grip_idx: usize,
     range: String,
+    /// 0 for untyped damage, otherwise 1 + the `Resist` variant index.
+    damage_type_idx: usize,
     condition: String,
     // Effects
     effects: Vec<Effect>,
-    effect_type_idx: usize,
-    effect_sub_idx: usize,
-    effect_value: String,
-    effect_skill_idx: usize,
-    effect_mana_value: String,
+    effect: EffectDraft,
+    /// A newly built effect that collides with `effects[index]`, awaiting the user's choice.
+    pending_conflict: Option<(usize, Effect)>,
+    /// Item type and name of the registry entry being edited instead of created.
+    editing: Option<(usize, String)>,
+}
+
+/// Position of `value` in an `EnumIter` enum's iteration order.
+fn variant_index<E: IntoEnumIterator + PartialEq>(value: E) -> usize {
+    E::iter().position(|v| v == value).unwrap_or(0)
+}
+
+/// Looks up the registry entry named `name` for the given item type index.
+fn existing_entry(
+    item_type: usize,
+    name: &str,
+    items: &ItemRegistry,
+    equipment: &EquipmentRegistry,
+    weapons: &WeaponRegistry,
+) -> Option<ItemEntry> {
+    match item_type {
+        0 => items.items.get(name).cloned().map(ItemEntry::Item),
+        1 => equipment
+            .equipment
+            .get(name)
+            .cloned()
+            .map(ItemEntry::Equipment),
+        2 => weapons.weapons.get(name).cloned().map(ItemEntry::Weapon),
+        _ => None,
+    }
+}
+
+/// Egui temp-data key of the dialog's form state.
+fn state_id() -> egui::Id {
+    egui::Id::new("create_item_state")
+}
+
+/// Prefills the item dialog from the registry definition of an inventory
+/// item. Returns false if the item has no definition to edit.
+pub(crate) fn open_item_editor(
+    ctx: &egui::Context,
+    item: &InventoryItem,
+    items: &ItemRegistry,
+    equipment: &EquipmentRegistry,
+    weapons: &WeaponRegistry,
+) -> bool {
+    let (item_type, name) = match item {
+        InventoryItem::Item(name) => (0, name),
+        InventoryItem::Equipment(name) => (1, name),
+        InventoryItem::Weapon(name) => (2, name),
+    };
+    let Some(entry) = existing_entry(item_type, name, items, equipment, weapons) else {
+        return false;
+    };
+    let state = CreateItemState::from_entry(&entry);
+    ctx.data_mut(|d| d.insert_temp(state_id(), state));
+    true
+}
+
+impl CreateItemState {
+    /// Form state prefilled from an existing registry entry, marked as editing it.
+    fn from_entry(entry: &ItemEntry) -> Self {
+        let value =
+            |v: &Option<shared::Wallet>| v.map(|w| w.total().to_string()).unwrap_or_default();
+        let weight = |w: &Option<u32>| w.map(|w| w.to_string()).unwrap_or_default();
+        let mut state = match entry {
+            ItemEntry::Item(item) => Self {
+                item_type: 0,
+                name: item.name.clone(),
+                description: item.description.clone(),
+                value: value(&item.value),
+                weight: weight(&item.weight),
+                ..Default::default()
+            },
+            ItemEntry::Equipment(eq) => Self {
+                item_type: 1,
+                name: eq.name.clone(),
+                description: eq.description.clone(),
+                value: value(&eq.value),
+                weight: weight(&eq.weight),
+                slot_idx: variant_index(eq.slot),
+                effects: eq.effects.clone(),
+                ..Default::default()
+            },
+            ItemEntry::Weapon(weapon) => {
+                let mut state = Self {
+                    item_type: 2,
+                    name: weapon.name.clone(),
+                    description: weapon.description.clone(),
+                    value: value(&weapon.value),
+                    weight: weight(&weapon.weight),
+                    damage: weapon.damage.clone(),
+                    attack: weapon.attack.to_string(),
+                    grip_idx: variant_index(weapon.grip),
+                    range: weapon.range.to_string(),
+                    damage_type_idx: weapon.damage_type.map_or(0, |t| variant_index(t) + 1),
+                    condition: weapon.condition.clone().unwrap_or_default(),
+                    effects: weapon.effects.clone(),
+                    ..Default::default()
+                };
+                match weapon.kind {
+                    WeaponKind::Range(sub) => state.range_subtype_idx = variant_index(sub),
+                    WeaponKind::Melee(sub) => {
+                        state.weapon_kind_idx = 1;
+                        state.melee_subtype_idx = variant_index(sub);
+                    }
+                    WeaponKind::Shield => state.weapon_kind_idx = 2,
+                    WeaponKind::BardInstrument => state.weapon_kind_idx = 3,
+                }
+                state
+            }
+        };
+        state.editing = Some((state.item_type, state.name.clone()));
+        state
+    }
+
+    /// Builds the registry entry described by the form, or `None` if the name is empty.
+    fn build(&self) -> Option<ItemEntry> {
+        let name = self.name.trim().to_string();
+        if name.is_empty() {
+            return None;
+        }
+        let value = self.value.parse().ok().map(shared::Wallet::from_total);
+        let weight = self.weight.parse().ok();
+        match self.item_type {
+            0 => Some(ItemEntry::Item(shared::Item {
+                name,
+                description: self.description.clone(),
+                value,
+                weight,
+            })),
+            1 => {
+                let slot =
+                    nth_variant::<EquipmentSlot>(self.slot_idx).unwrap_or(EquipmentSlot::Any);
+                Some(ItemEntry::Equipment(shared::Equipment {
+                    name,
+                    description: self.description.clone(),
+                    slot,
+                    effects: self.effects.clone(),
+                    value,
+                    weight,
+                }))
+            }
+            2 => {
+                let kind = match self.weapon_kind_idx {
+                    1 => {
+                        let sub = nth_variant::<MeleeKind>(self.melee_subtype_idx)
+                            .unwrap_or(MeleeKind::Slashing);
+                        WeaponKind::Melee(sub)
+                    }
+                    2 => WeaponKind::Shield,
+                    3 => WeaponKind::BardInstrument,
+                    _ => {
+                        let sub = nth_variant::<RangeKind>(self.range_subtype_idx)
+                            .unwrap_or(RangeKind::Bow);
+                        WeaponKind::Range(sub)
+                    }
+                };
+                let is_combat = self.weapon_kind_idx <= 1;
+                let grip = if is_combat {
+                    nth_variant::<WeaponGrip>(self.grip_idx).unwrap_or(WeaponGrip::OneHanded)
+                } else {
+                    WeaponGrip::OneHanded
+                };
+                Some(ItemEntry::Weapon(shared::Weapon {
+                    name,
+                    description: self.description.clone(),
+                    damage: if is_combat {
+                        self.damage.clone()
+                    } else {
+                        String::new()
+                    },
+                    attack: if is_combat {
+                        self.attack.parse().unwrap_or(0)
+                    } else {
+                        0
+                    },
+                    kind,
+                    grip,
+                    range: if is_combat {
+                        self.range.parse().unwrap_or(1)
+                    } else {
+                        0
+                    },
+                    effects: self.effects.clone(),
+                    condition: if self.condition.trim().is_empty() {
+                        None
+                    } else {
+                        Some(self.condition.trim().to_string())
+                    },
+                    value,
+                    weight,
+                    damage_type: if is_combat {
+                        self.damage_type_idx
+                            .checked_sub(1)
+                            .and_then(nth_variant::<Resist>)
+                    } else {
+                        None
+                    },
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    /// True when the form edits the existing entry it was prefilled from.
+    fn is_editing(&self) -> bool {
+        self.editing
+            .as_ref()
+            .is_some_and(|(t, n)| *t == self.item_type && n == self.name.trim())
+    }
 }
 
 pub fn render_create_item_popup(
@@ -82,9 +297,10 @@ pub fn render_create_item_popup(
     create_item_events: &mut MessageWriter<CreateItem>,
     format_effect: &dyn Fn(&Effect) -> String,
     skill_names: &[String],
-    existing_item_names: &std::collections::BTreeSet<String>,
-    existing_equipment_names: &std::collections::BTreeSet<String>,
-    existing_weapon_names: &std::collections::BTreeSet<String>,
+    items: &ItemRegistry,
+    equipment: &EquipmentRegistry,
+    weapons: &WeaponRegistry,
+    admin: bool,
 ) {
     let screen = ctx.content_rect();
 
@@ -106,24 +322,27 @@ pub fn render_create_item_popup(
         screen.center().y - screen.height() * 0.65 / 2.0,
     );
 
-    let state_id = egui::Id::new("create_item_state");
+    let state_id = state_id();
     let mut state: CreateItemState = ctx.data(|d| d.get_temp(state_id)).unwrap_or_default();
     state.item_type = state.item_type.min(2);
-    state.effect_type_idx = state.effect_type_idx.min(EFFECT_TYPE_LABELS.len() - 1);
 
     egui::Area::new(egui::Id::new("create_item_dialog"))
         .order(egui::Order::Foreground)
         .fixed_pos(dialog_pos)
         .show(ctx, |ui| {
             egui::Frame::NONE
-                .fill(MAIN_COLOR)
+                .fill(palette().main)
                 .stroke(egui::Stroke::new(1.0, egui::Color32::from_gray(200)))
                 .corner_radius(egui::CornerRadius::same(12))
                 .inner_margin(egui::Margin::same(16))
                 .show(ui, |ui| {
                     ui.set_width(dialog_w - 32.0);
 
-                    ui.heading("Create Item");
+                    ui.heading(if state.is_editing() {
+                        "Edit Item"
+                    } else {
+                        "Create Item"
+                    });
                     ui.add_space(8.0);
 
                     // Type selector
@@ -143,11 +362,56 @@ pub fn render_create_item_popup(
                         ui.label("Name:");
                         ui.text_edit_singleline(&mut state.name);
                     });
+                    let existing = existing_entry(
+                        state.item_type,
+                        state.name.trim(),
+                        items,
+                        equipment,
+                        weapons,
+                    );
+                    if let Some(entry) = &existing {
+                        let mut edit_existing = state.is_editing();
+                        ui.horizontal(|ui| {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(0xAA, 0x44, 0x44),
+                                "Name already exists",
+                            );
+                            // Only admins may overwrite a definition
+                            if admin {
+                                ui.checkbox(&mut edit_existing, "Edit existing");
+                            }
+                        });
+                        if edit_existing && !state.is_editing() {
+                            state = CreateItemState::from_entry(entry);
+                        } else if !edit_existing {
+                            state.editing = None;
+                        }
+                    }
                     ui.add_space(4.0);
                     ui.horizontal(|ui| {
                         ui.label("Description:");
                         ui.text_edit_singleline(&mut state.description);
                     });
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        ui.label("Value:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut state.value)
+                                .desired_width(80.0)
+                                .hint_text("optional"),
+                        );
+                    });
+                    state.value.retain(|c| c.is_ascii_digit());
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        ui.label("Weight:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut state.weight)
+                                .desired_width(80.0)
+                                .hint_text("optional"),
+                        );
+                    });
+                    state.weight.retain(|c| c.is_ascii_digit());
 
                     match state.item_type {
                         0 => {}
@@ -245,6 +509,27 @@ pub fn render_create_item_popup(
                                     );
                                 });
                                 state.range.retain(|c| c.is_ascii_digit());
+                                ui.add_space(4.0);
+                                ui.horizontal(|ui| {
+                                    ui.label("Damage type:");
+                                    let types: Vec<String> = std::iter::once("None".to_string())
+                                        .chain(Resist::iter().map(|r| r.to_string()))
+                                        .collect();
+                                    state.damage_type_idx =
+                                        state.damage_type_idx.min(types.len() - 1);
+                                    egui::ComboBox::from_id_salt("wpn_damage_type")
+                                        .selected_text(&types[state.damage_type_idx])
+                                        .width(100.0)
+                                        .show_ui(ui, |ui| {
+                                            for (i, label) in types.iter().enumerate() {
+                                                ui.selectable_value(
+                                                    &mut state.damage_type_idx,
+                                                    i,
+                                                    label.as_str(),
+                                                );
+                                            }
+                                        });
+                                });
                             }
 
                             ui.add_space(4.0);
@@ -264,9 +549,13 @@ pub fn render_create_item_popup(
                         ui.label("Effects:");
 
                         let mut remove_idx = None;
+                        let mut edit_idx = None;
                         for (i, effect) in state.effects.iter().enumerate() {
                             ui.horizontal(|ui| {
                                 ui.label(format_effect(effect));
+                                if ui.small_button("edit").clicked() {
+                                    edit_idx = Some(i);
+                                }
                                 if ui.small_button("x").clicked() {
                                     remove_idx = Some(i);
                                 }
@@ -275,121 +564,47 @@ pub fn render_create_item_popup(
                         if let Some(i) = remove_idx {
                             state.effects.remove(i);
                         }
+                        // Editing moves the effect back into the fields below
+                        if let Some(i) = edit_idx {
+                            let effect = state.effects.remove(i);
+                            state.effect = EffectDraft::from_effect(&effect, skill_names);
+                        }
 
                         ui.add_space(4.0);
 
                         ui.horizontal(|ui| {
-                            egui::ComboBox::from_id_salt("eff_type")
-                                .selected_text(EFFECT_TYPE_LABELS[state.effect_type_idx])
-                                .width(110.0)
-                                .show_ui(ui, |ui| {
-                                    for (i, label) in EFFECT_TYPE_LABELS.iter().enumerate() {
-                                        ui.selectable_value(&mut state.effect_type_idx, i, *label);
-                                    }
-                                });
-
-                            render_effect_fields(ui, &mut state, skill_names);
+                            state.effect.show(ui, "eff", skill_names);
 
                             if ui.small_button("+").clicked() {
-                                if let Some(effect) = build_effect_from_state(&state, skill_names) {
-                                    state.effects.push(effect);
-                                    state.effect_value.clear();
-                                    state.effect_mana_value.clear();
+                                if let Some(effect) = state.effect.build(skill_names) {
+                                    match state
+                                        .effects
+                                        .iter()
+                                        .position(|e| e.conflicts_with(&effect))
+                                    {
+                                        Some(i) => state.pending_conflict = Some((i, effect)),
+                                        None => state.effects.push(effect),
+                                    }
+                                    state.effect.clear_values();
                                 }
                             }
                         });
-                        state
-                            .effect_value
-                            .retain(|c| c.is_ascii_digit() || c == '-');
-                        state
-                            .effect_mana_value
-                            .retain(|c| c.is_ascii_digit() || c == '-');
+                        render_effect_conflict(ui, &mut state, format_effect);
+                        state.effect.sanitize();
                     }
 
                     ui.add_space(12.0);
 
-                    let trimmed_name = state.name.trim().to_string();
-                    let name_taken = match state.item_type {
-                        0 => existing_item_names.contains(&trimmed_name),
-                        1 => existing_equipment_names.contains(&trimmed_name),
-                        2 => existing_weapon_names.contains(&trimmed_name),
-                        _ => false,
-                    };
-                    let can_create = !trimmed_name.is_empty() && !name_taken;
+                    let replace = existing.is_some() && state.is_editing();
+                    let can_save = existing.is_none() || replace;
+                    let built = state.build().filter(|_| can_save);
+                    let label = if replace { "Update" } else { "Create" };
                     if ui
-                        .add_enabled(can_create, egui::Button::new("Create"))
+                        .add_enabled(built.is_some(), egui::Button::new(label))
                         .clicked()
                     {
-                        match state.item_type {
-                            0 => {
-                                create_item_events.write(CreateItem::Item(shared::Item {
-                                    name: state.name.trim().to_string(),
-                                    description: state.description.clone(),
-                                }));
-                            }
-                            1 => {
-                                let slot = nth_variant::<EquipmentSlot>(state.slot_idx)
-                                    .unwrap_or(EquipmentSlot::Any);
-                                create_item_events.write(CreateItem::Equipment(
-                                    shared::Equipment {
-                                        name: state.name.trim().to_string(),
-                                        description: state.description.clone(),
-                                        slot,
-                                        effects: state.effects.clone(),
-                                    },
-                                ));
-                            }
-                            2 => {
-                                let kind = match state.weapon_kind_idx {
-                                    1 => {
-                                        let sub = nth_variant::<MeleeKind>(state.melee_subtype_idx)
-                                            .unwrap_or(MeleeKind::Slashing);
-                                        WeaponKind::Melee(sub)
-                                    }
-                                    2 => WeaponKind::Shield,
-                                    3 => WeaponKind::BardInstrument,
-                                    _ => {
-                                        let sub = nth_variant::<RangeKind>(state.range_subtype_idx)
-                                            .unwrap_or(RangeKind::Bow);
-                                        WeaponKind::Range(sub)
-                                    }
-                                };
-                                let is_combat = state.weapon_kind_idx <= 1;
-                                let grip = if is_combat {
-                                    nth_variant::<WeaponGrip>(state.grip_idx)
-                                        .unwrap_or(WeaponGrip::OneHanded)
-                                } else {
-                                    WeaponGrip::OneHanded
-                                };
-                                create_item_events.write(CreateItem::Weapon(shared::Weapon {
-                                    name: state.name.trim().to_string(),
-                                    description: state.description.clone(),
-                                    damage: if is_combat {
-                                        state.damage.clone()
-                                    } else {
-                                        String::new()
-                                    },
-                                    attack: if is_combat {
-                                        state.attack.parse().unwrap_or(0)
-                                    } else {
-                                        0
-                                    },
-                                    kind,
-                                    grip,
-                                    range: if is_combat {
-                                        state.range.parse().unwrap_or(1)
-                                    } else {
-                                        0
-                                    },
-                                    effects: state.effects.clone(),
-                                    condition: if state.condition.trim().is_empty() {
-                                        None
-                                    } else {
-                                        Some(state.condition.trim().to_string())
-                                    },
-                                }));
-                            }
-                            _ => {}
+                        if let Some(entry) = built {
+                            create_item_events.write(CreateItem { entry, replace });
                         }
                         state = CreateItemState::default();
                         create_item_open.0 = false;
@@ -400,105 +615,331 @@ pub fn render_create_item_popup(
     ctx.data_mut(|d| d.insert_temp(state_id, state));
 }
 
-fn render_effect_fields(ui: &mut egui::Ui, state: &mut CreateItemState, skill_names: &[String]) {
-    match state.effect_type_idx {
-        0 => {
-            enum_combo::<Resist>(ui, "eff_sub", &mut state.effect_sub_idx, 80.0);
-            ui.add(egui::TextEdit::singleline(&mut state.effect_value).desired_width(40.0));
-        }
-        1 => {
-            if !skill_names.is_empty() {
-                state.effect_skill_idx = state.effect_skill_idx.min(skill_names.len() - 1);
-                egui::ComboBox::from_id_salt("eff_skill")
-                    .selected_text(&skill_names[state.effect_skill_idx])
-                    .width(100.0)
-                    .show_ui(ui, |ui| {
-                        for (i, name) in skill_names.iter().enumerate() {
-                            ui.selectable_value(&mut state.effect_skill_idx, i, name.as_str());
-                        }
-                    });
+/// Advisory prompt shown when a newly added effect collides with an existing one.
+/// Offers merging (summed magnitude) or keeping both entries.
+fn render_effect_conflict(
+    ui: &mut egui::Ui,
+    state: &mut CreateItemState,
+    format_effect: &dyn Fn(&Effect) -> String,
+) {
+    let Some((idx, effect)) = state.pending_conflict.clone() else {
+        return;
+    };
+    let Some(existing) = state.effects.get(idx).cloned() else {
+        state.pending_conflict = None;
+        return;
+    };
+    ui.add_space(4.0);
+    ui.colored_label(
+        egui::Color32::from_rgb(0xAA, 0x44, 0x44),
+        format!(
+            "{} conflicts with {}",
+            format_effect(&effect),
+            format_effect(&existing)
+        ),
+    );
+    ui.horizontal(|ui| {
+        if ui.small_button("Merge").clicked() {
+            if let Some(merged) = existing.merge(&effect) {
+                state.effects[idx] = merged;
             }
-            ui.add(egui::TextEdit::singleline(&mut state.effect_value).desired_width(40.0));
-        }
-        2 => {
-            enum_combo::<Protection>(ui, "eff_sub", &mut state.effect_sub_idx, 80.0);
-            ui.add(egui::TextEdit::singleline(&mut state.effect_value).desired_width(40.0));
-        }
-        3 | 5 | 6 => {
-            ui.add(egui::TextEdit::singleline(&mut state.effect_value).desired_width(40.0));
+            state.pending_conflict = None;
         }
-        4 => {
-            enum_combo::<CharacteristicKind>(ui, "eff_sub", &mut state.effect_sub_idx, 60.0);
-            ui.add(egui::TextEdit::singleline(&mut state.effect_value).desired_width(40.0));
+        if ui.small_button("Keep both").clicked() {
+            state.effects.push(effect);
+            state.pending_conflict = None;
         }
-        7 => {
-            enum_combo::<CharacteristicKind>(ui, "eff_sub", &mut state.effect_sub_idx, 60.0);
-            ui.add(
-                egui::TextEdit::singleline(&mut state.effect_mana_value)
-                    .desired_width(40.0)
-                    .hint_text("+/pt"),
-            );
+        if ui.small_button("Cancel").clicked() {
+            state.pending_conflict = None;
         }
-        8 => {
-            enum_combo::<OnLvlUp>(ui, "eff_sub", &mut state.effect_sub_idx, 130.0);
-            ui.add(egui::TextEdit::singleline(&mut state.effect_value).desired_width(40.0));
+    });
+}
+
+/// Effect type index, sub-type index and typed value the effect fields show
+/// for `effect`; the inverse of [`EffectDraft::build`]. Skill effects have
+/// no sub-type, their skill is looked up by name instead.
+fn effect_to_editor_state(effect: &Effect) -> (usize, usize, String) {
+    match effect {
+        Effect::Resist(resist, v) => (0, variant_index(*resist), v.to_string()),
+        Effect::Skill(_, v) => (1, 0, v.to_string()),
+        Effect::Protection(protection, v) => (2, variant_index(*protection), v.to_string()),
+        Effect::Initiative(v) => (3, 0, v.to_string()),
+        Effect::Characteristic(kind, v) => (4, variant_index(*kind), v.to_string()),
+        Effect::ActionPoints(v) => (5, 0, v.to_string()),
+        Effect::Armor(v) => (6, 0, v.to_string()),
+        Effect::Mana {
+            dependent,
+            increase_per_point,
+        } => (7, variant_index(*dependent), increase_per_point.to_string()),
+        Effect::OnLvlUp(on_lvl_up) => {
+            let sub = OnLvlUp::iter()
+                .position(|v| std::mem::discriminant(&v) == std::mem::discriminant(on_lvl_up))
+                .unwrap_or(0);
+            let (OnLvlUp::AddSkillPoints(v)
+            | OnLvlUp::AddAbilityPoints(v)
+            | OnLvlUp::AddCharacteristicPoints(v)) = on_lvl_up;
+            (8, sub, v.to_string())
         }
-        _ => {}
+        Effect::FreeSkillRank { ranks, .. } => (9, 0, ranks.to_string()),
+        Effect::MaxHp(v) => (10, 0, v.to_string()),
+        Effect::MaxMana(v) => (11, 0, v.to_string()),
     }
 }
 
-fn build_effect_from_state(state: &CreateItemState, skill_names: &[String]) -> Option<Effect> {
-    match state.effect_type_idx {
-        0 => {
-            let val: i32 = state.effect_value.parse().ok()?;
-            let resist = nth_variant::<Resist>(state.effect_sub_idx)?;
-            Some(Effect::Resist(resist, val))
-        }
-        1 => {
-            let val: i32 = state.effect_value.parse().ok()?;
-            let name = skill_names.get(state.effect_skill_idx)?;
-            Some(Effect::Skill(name.clone(), val))
-        }
-        2 => {
-            let val: i32 = state.effect_value.parse().ok()?;
-            let prot = nth_variant::<Protection>(state.effect_sub_idx)?;
-            Some(Effect::Protection(prot, val))
-        }
-        3 => {
-            let val: i32 = state.effect_value.parse().ok()?;
-            Some(Effect::Initiative(val))
-        }
-        4 => {
-            let val: i32 = state.effect_value.parse().ok()?;
-            let kind = nth_variant::<CharacteristicKind>(state.effect_sub_idx)?;
-            Some(Effect::Characteristic(kind, val))
-        }
-        5 => {
-            let val: i32 = state.effect_value.parse().ok()?;
-            Some(Effect::ActionPoints(val))
+/// Form fields for building a single [`Effect`], shared by the item
+/// dialog and the buffs window.
+#[derive(Clone, Default)]
+pub(crate) struct EffectDraft {
+    type_idx: usize,
+    sub_idx: usize,
+    value: String,
+    skill_idx: usize,
+    mana_value: String,
+}
+
+impl EffectDraft {
+    /// Fields describing `effect`, so it can be changed and built again.
+    pub(crate) fn from_effect(effect: &Effect, skill_names: &[String]) -> Self {
+        let (type_idx, sub_idx, value) = effect_to_editor_state(effect);
+        let skill_idx = match effect {
+            Effect::Skill(skill, _) | Effect::FreeSkillRank { skill, .. } => {
+                skill_names.iter().position(|s| s == skill).unwrap_or(0)
+            }
+            _ => 0,
+        };
+        let mut draft = Self {
+            type_idx,
+            sub_idx,
+            skill_idx,
+            ..Default::default()
+        };
+        if matches!(effect, Effect::Mana { .. }) {
+            draft.mana_value = value;
+        } else {
+            draft.value = value;
         }
-        6 => {
-            let val: i32 = state.effect_value.parse().ok()?;
-            Some(Effect::Armor(val))
+        draft
+    }
+
+    /// Renders the effect type combo and the fields for that type. Widget ids
+    /// are derived from `id_prefix`, so several drafts can share a screen.
+    pub(crate) fn show(&mut self, ui: &mut egui::Ui, id_prefix: &str, skill_names: &[String]) {
+        self.type_idx = self.type_idx.min(EFFECT_TYPE_LABELS.len() - 1);
+        egui::ComboBox::from_id_salt(format!("{id_prefix}_type"))
+            .selected_text(EFFECT_TYPE_LABELS[self.type_idx])
+            .width(110.0)
+            .show_ui(ui, |ui| {
+                for (i, label) in EFFECT_TYPE_LABELS.iter().enumerate() {
+                    ui.selectable_value(&mut self.type_idx, i, *label);
+                }
+            });
+        let sub_id = format!("{id_prefix}_sub");
+        match self.type_idx {
+            0 => {
+                enum_combo::<Resist>(ui, &sub_id, &mut self.sub_idx, 80.0);
+                ui.add(egui::TextEdit::singleline(&mut self.value).desired_width(40.0));
+            }
+            1 | 9 => {
+                if !skill_names.is_empty() {
+                    self.skill_idx = self.skill_idx.min(skill_names.len() - 1);
+                    egui::ComboBox::from_id_salt(format!("{id_prefix}_skill"))
+                        .selected_text(&skill_names[self.skill_idx])
+                        .width(100.0)
+                        .show_ui(ui, |ui| {
+                            for (i, name) in skill_names.iter().enumerate() {
+                                ui.selectable_value(&mut self.skill_idx, i, name.as_str());
+                            }
+                        });
+                }
+                ui.add(egui::TextEdit::singleline(&mut self.value).desired_width(40.0));
+            }
+            2 => {
+                enum_combo::<Protection>(ui, &sub_id, &mut self.sub_idx, 80.0);
+                ui.add(egui::TextEdit::singleline(&mut self.value).desired_width(40.0));
+            }
+            3 | 5 | 6 | 10 | 11 => {
+                ui.add(egui::TextEdit::singleline(&mut self.value).desired_width(40.0));
+            }
+            4 => {
+                enum_combo::<CharacteristicKind>(ui, &sub_id, &mut self.sub_idx, 60.0);
+                ui.add(egui::TextEdit::singleline(&mut self.value).desired_width(40.0));
+            }
+            7 => {
+                enum_combo::<CharacteristicKind>(ui, &sub_id, &mut self.sub_idx, 60.0);
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.mana_value)
+                        .desired_width(40.0)
+                        .hint_text("+/pt"),
+                );
+            }
+            8 => {
+                enum_combo::<OnLvlUp>(ui, &sub_id, &mut self.sub_idx, 130.0);
+                ui.add(egui::TextEdit::singleline(&mut self.value).desired_width(40.0));
+            }
+            _ => {}
         }
-        7 => {
-            let val: i32 = state.effect_mana_value.parse().ok()?;
-            let dependent = nth_variant::<CharacteristicKind>(state.effect_sub_idx)?;
-            Some(Effect::Mana {
-                dependent,
-                increase_per_point: val,
-            })
+    }
+
+    /// The effect the fields describe, or `None` while a value is missing.
+    pub(crate) fn build(&self, skill_names: &[String]) -> Option<Effect> {
+        match self.type_idx {
+            0 => {
+                let val: i32 = self.value.parse().ok()?;
+                let resist = nth_variant::<Resist>(self.sub_idx)?;
+                Some(Effect::Resist(resist, val))
+            }
+            1 => {
+                let val: i32 = self.value.parse().ok()?;
+                let name = skill_names.get(self.skill_idx)?;
+                Some(Effect::Skill(name.clone(), val))
+            }
+            2 => {
+                let val: i32 = self.value.parse().ok()?;
+                let prot = nth_variant::<Protection>(self.sub_idx)?;
+                Some(Effect::Protection(prot, val))
+            }
+            3 => {
+                let val: i32 = self.value.parse().ok()?;
+                Some(Effect::Initiative(val))
+            }
+            4 => {
+                let val: i32 = self.value.parse().ok()?;
+                let kind = nth_variant::<CharacteristicKind>(self.sub_idx)?;
+                Some(Effect::Characteristic(kind, val))
+            }
+            5 => {
+                let val: i32 = self.value.parse().ok()?;
+                Some(Effect::ActionPoints(val))
+            }
+            6 => {
+                let val: i32 = self.value.parse().ok()?;
+                Some(Effect::Armor(val))
+            }
+            7 => {
+                let val: i32 = self.mana_value.parse().ok()?;
+                let dependent = nth_variant::<CharacteristicKind>(self.sub_idx)?;
+                Some(Effect::Mana {
+                    dependent,
+                    increase_per_point: val,
+                })
+            }
+            8 => {
+                let val: i32 = self.value.parse().ok()?;
+                let variant = nth_variant::<OnLvlUp>(self.sub_idx)?;
+                let on_lvl_up = match variant {
+                    OnLvlUp::AddSkillPoints(_) => OnLvlUp::AddSkillPoints(val),
+                    OnLvlUp::AddAbilityPoints(_) => OnLvlUp::AddAbilityPoints(val),
+                    OnLvlUp::AddCharacteristicPoints(_) => OnLvlUp::AddCharacteristicPoints(val),
+                };
+                Some(Effect::OnLvlUp(on_lvl_up))
+            }
+            9 => {
+                let ranks: u32 = self.value.parse().ok()?;
+                let skill = skill_names.get(self.skill_idx)?.clone();
+                Some(Effect::FreeSkillRank { skill, ranks })
+            }
+            10 => {
+                let val: i32 = self.value.parse().ok()?;
+                Some(Effect::MaxHp(val))
+            }
+            11 => {
+                let val: i32 = self.value.parse().ok()?;
+                Some(Effect::MaxMana(val))
+            }
+            _ => None,
         }
-        8 => {
-            let val: i32 = state.effect_value.parse().ok()?;
-            let variant = nth_variant::<OnLvlUp>(state.effect_sub_idx)?;
-            let on_lvl_up = match variant {
-                OnLvlUp::AddSkillPoints(_) => OnLvlUp::AddSkillPoints(val),
-                OnLvlUp::AddAbilityPoints(_) => OnLvlUp::AddAbilityPoints(val),
-                OnLvlUp::AddCharacteristicPoints(_) => OnLvlUp::AddCharacteristicPoints(val),
-            };
-            Some(Effect::OnLvlUp(on_lvl_up))
+    }
+
+    /// Clears the typed values, keeping the chosen effect type.
+    pub(crate) fn clear_values(&mut self) {
+        self.value.clear();
+        self.mana_value.clear();
+    }
+
+    /// Drops anything but digits and minus signs from the value fields.
+    pub(crate) fn sanitize(&mut self) {
+        self.value.retain(|c| c.is_ascii_digit() || c == '-');
+        self.mana_value.retain(|c| c.is_ascii_digit() || c == '-');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editing_existing_weapon_prefills_form() {
+        let weapons = WeaponRegistry::load_from_str(
+            r#"[{
+                "name": "Sword",
+                "description": "Plain steel",
+                "damage": "1d8",
+                "attack": 1,
+                "kind": { "Melee": "Slashing" },
+                "grip": "OneHanded",
+                "range": 1,
+                "effects": [{ "Armor": 1 }],
+                "condition": null
+            }]"#,
+        )
+        .unwrap();
+        let existing = existing_entry(
+            2,
+            "Sword",
+            &ItemRegistry::default(),
+            &EquipmentRegistry::default(),
+            &weapons,
+        )
+        .expect("sword is in the registry");
+
+        let mut state = CreateItemState::from_entry(&existing);
+        assert!(state.is_editing());
+        assert_eq!(state.build().as_ref(), Some(&existing));
+
+        state.damage = "2d6".to_string();
+        let Some(ItemEntry::Weapon(edited)) = state.build() else {
+            panic!("expected a weapon");
+        };
+        assert_eq!(edited.name, "Sword");
+        assert_eq!(edited.damage, "2d6");
+        assert_eq!(edited.effects, vec![Effect::Armor(1)]);
+    }
+
+    #[test]
+    fn test_effect_editor_state_round_trips() {
+        let skill_names = vec!["Athletics".to_string(), "Stealth".to_string()];
+        let effects = [
+            Effect::Resist(Resist::iter().next_back().unwrap(), -2),
+            Effect::Skill("Stealth".to_string(), 3),
+            Effect::Protection(Protection::iter().next_back().unwrap(), 1),
+            Effect::Initiative(2),
+            Effect::Characteristic(CharacteristicKind::iter().next_back().unwrap(), 1),
+            Effect::ActionPoints(1),
+            Effect::Armor(4),
+            Effect::Mana {
+                dependent: CharacteristicKind::iter().next_back().unwrap(),
+                increase_per_point: 2,
+            },
+            Effect::OnLvlUp(OnLvlUp::AddCharacteristicPoints(1)),
+            Effect::FreeSkillRank {
+                skill: "Stealth".to_string(),
+                ranks: 2,
+            },
+            Effect::MaxHp(-5),
+            Effect::MaxMana(3),
+        ];
+        for effect in effects {
+            let draft = EffectDraft::from_effect(&effect, &skill_names);
+            assert_eq!(draft.build(&skill_names), Some(effect));
         }
-        _ => None,
+
+        assert_eq!(
+            effect_to_editor_state(&Effect::OnLvlUp(OnLvlUp::AddAbilityPoints(2))),
+            (8, 1, "2".to_string())
+        );
+        assert_eq!(
+            effect_to_editor_state(&Effect::Armor(-1)),
+            (6, 0, "-1".to_string())
+        );
     }
 }
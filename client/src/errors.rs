@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+use shared::ErrorCode;
+use ui_widgets::colors::palette;
+
+/// Number of server errors kept in the history.
+const HISTORY_LEN: usize = 20;
+
+pub struct ErrorEntry {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+/// Recent server errors, kept in a small history. The toast each one raises
+/// goes through [`crate::toasts::Toasts`].
+#[derive(Resource, Default)]
+pub struct ErrorLog {
+    entries: VecDeque<ErrorEntry>,
+    history_open: bool,
+    /// Reason the server is read-only, if it started in safe mode.
+    safe_mode: Option<String>,
+}
+
+impl ErrorLog {
+    pub fn push(&mut self, code: ErrorCode, message: String) {
+        self.entries.push_back(ErrorEntry { code, message });
+        while self.entries.len() > HISTORY_LEN {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn set_safe_mode(&mut self, reason: String) {
+        self.safe_mode = Some(reason);
+    }
+}
+
+pub struct ErrorLogPlugin;
+
+impl Plugin for ErrorLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ErrorLog>().add_systems(
+            EguiPrimaryContextPass,
+            (render_error_history, render_safe_mode_banner),
+        );
+    }
+}
+
+fn code_color(code: ErrorCode) -> egui::Color32 {
+    match code {
+        ErrorCode::Unauthorized => egui::Color32::from_rgb(0xE0, 0xA0, 0x40),
+        ErrorCode::NotFound => egui::Color32::from_rgb(0x90, 0xA0, 0xC0),
+        ErrorCode::Invalid => egui::Color32::from_rgb(0xE0, 0x60, 0x60),
+        ErrorCode::ReadOnly => egui::Color32::from_rgb(0xE0, 0x80, 0x30),
+        ErrorCode::NameTaken => egui::Color32::from_rgb(0xC0, 0x70, 0xC0),
+        ErrorCode::RateLimited => egui::Color32::from_rgb(0xD0, 0xC0, 0x50),
+    }
+}
+
+fn error_row(ui: &mut egui::Ui, entry: &ErrorEntry) {
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(entry.code.to_string())
+                .size(12.0)
+                .color(code_color(entry.code))
+                .strong(),
+        );
+        ui.label(
+            egui::RichText::new(&entry.message)
+                .size(12.0)
+                .color(palette().text),
+        );
+    });
+}
+
+/// Warns on every screen that the server is read-only.
+fn render_safe_mode_banner(mut contexts: EguiContexts, log: Res<ErrorLog>) -> Result {
+    let Some(reason) = &log.safe_mode else {
+        return Ok(());
+    };
+    let ctx = contexts.ctx_mut()?;
+    egui::TopBottomPanel::top("safe_mode_banner")
+        .frame(
+            egui::Frame::new()
+                .fill(code_color(ErrorCode::ReadOnly))
+                .inner_margin(egui::Margin::symmetric(12, 6)),
+        )
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(format!("Server is in safe mode (read-only): {reason}"))
+                    .color(egui::Color32::BLACK)
+                    .strong(),
+            );
+        });
+    Ok(())
+}
+
+/// Offers the error history behind a small button in the bottom-right corner.
+fn render_error_history(mut contexts: EguiContexts, mut log: ResMut<ErrorLog>) -> Result {
+    if log.entries.is_empty() {
+        return Ok(());
+    }
+    let ctx = contexts.ctx_mut()?;
+    let log = &mut *log;
+
+    let mut toggle_history = false;
+    egui::Area::new(egui::Id::new("error_history_button"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -12.0])
+        .show(ctx, |ui| {
+            let history_label = format!("Errors ({})", log.entries.len());
+            if ui.small_button(history_label).clicked() {
+                toggle_history = true;
+            }
+        });
+    if toggle_history {
+        log.history_open = !log.history_open;
+    }
+
+    if log.history_open {
+        let mut open = true;
+        let mut clear = false;
+        egui::Window::new("Error history")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .frame(
+                egui::Frame::new()
+                    .fill(palette().secondary)
+                    .corner_radius(8.0)
+                    .stroke(egui::Stroke::new(1.0, palette().stroke))
+                    .inner_margin(egui::Margin::same(12)),
+            )
+            .show(ctx, |ui| {
+                for entry in log.entries.iter().rev() {
+                    error_row(ui, entry);
+                }
+                ui.add_space(6.0);
+                if ui.button("Clear").clicked() {
+                    clear = true;
+                }
+            });
+        log.history_open = open && !clear;
+        if clear {
+            log.entries.clear();
+        }
+    }
+
+    Ok(())
+}
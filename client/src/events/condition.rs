@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+use shared::Condition;
+
+/// Fired from the conditions row on the character sheet.
+#[derive(Message)]
+pub enum ConditionsChanged {
+    Add(Condition),
+    Remove(Condition),
+}
@@ -1,9 +1,21 @@
 use bevy::prelude::*;
 
-/// Fired when the player creates a new item from the constructor popup.
-#[derive(Message)]
-pub enum CreateItem {
+/// A registry definition built in the item constructor popup.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemEntry {
     Weapon(shared::Weapon),
     Equipment(shared::Equipment),
     Item(shared::Item),
 }
+
+/// Fired when the player saves the item constructor popup. With `replace` set the
+/// registry entry of the same name is overwritten instead of a new item being added.
+#[derive(Message)]
+pub struct CreateItem {
+    pub entry: ItemEntry,
+    pub replace: bool,
+}
+
+/// Fired when the player deletes an item's definition from the shared registry.
+#[derive(Message)]
+pub struct DeleteRegistryEntry(pub shared::InventoryItem);
@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+/// Fired when an ability is dragged onto the hotbar or unpinned from it.
+#[derive(Message)]
+pub enum HotbarChanged {
+    Pin(String),
+    Unpin(String),
+}
+
+/// Fired when an ability is used from the abilities panel or the hotbar.
+#[derive(Message)]
+pub struct ActivateAbility(pub String);
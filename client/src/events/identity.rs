@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+use shared::{Class, Race};
+
+/// Fired when the name, race or class is edited on the identity bar.
+#[derive(Message)]
+pub enum IdentityChanged {
+    Name(String),
+    Race(Race),
+    /// Skills the new class doesn't have are dropped and refunded.
+    Class(Class),
+}
@@ -1,17 +1,21 @@
 use bevy::prelude::*;
-use shared::InventoryItem;
+use shared::{EquipmentSlot, InventoryItem};
 
 /// Fired when the player triggers an equip/unequip action from a context menu.
 #[derive(Message)]
 pub enum InventoryChanged {
     /// Equip the inventory item at the given index.
     Equip(usize),
-    /// Remove the inventory item at the given index.
+    /// Remove the inventory item at the given index, keeping it in the trash.
     Remove(usize),
-    /// Unequip the equipped equipment at the given index (in the flattened equipped list).
-    UnequipGear(usize),
+    /// Remove the inventory item at the given index for good (e.g. it was sold).
+    Sold(usize),
+    /// Unequip the `usize`-th item worn in the given equipment slot.
+    UnequipGear(EquipmentSlot, usize),
     /// Unequip the weapon at the given slot index.
     UnequipWeapon(usize),
     /// Add an existing item from the registry to inventory.
     AddExisting(InventoryItem),
+    /// Sort the inventory so identical items sit together in one stack.
+    MergeDuplicates,
 }
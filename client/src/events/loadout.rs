@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+/// Fired from the portrait's "Loadouts" menu.
+#[derive(Message)]
+pub enum LoadoutChanged {
+    /// Save the current weapons and equipment under the given name.
+    Save(String),
+    /// Equip the loadout with the given name.
+    Apply(String),
+}
@@ -1,19 +1,31 @@
+mod condition;
 mod create_item;
 mod experience;
+mod hotbar;
+mod identity;
 mod inventory;
 mod learn_ability;
 mod learn_trait;
 mod level_up;
+mod loadout;
 mod resource;
+mod roll;
+mod temp_effect;
 mod upgrade;
 mod wallet;
 
-pub use create_item::CreateItem;
+pub use condition::ConditionsChanged;
+pub use create_item::{CreateItem, DeleteRegistryEntry, ItemEntry};
 pub use experience::ExperienceChanged;
+pub use hotbar::{ActivateAbility, HotbarChanged};
+pub use identity::IdentityChanged;
 pub use inventory::InventoryChanged;
 pub use learn_ability::LearnAbility;
 pub use learn_trait::LearnTrait;
 pub use level_up::LevelUp;
+pub use loadout::LoadoutChanged;
 pub use resource::ResourceChanged;
+pub use roll::{RollCheck, RollDamage};
+pub use temp_effect::TempEffectsChanged;
 pub use upgrade::UpgradeEvent;
 pub use wallet::WalletChanged;
@@ -1,9 +1,18 @@
 use bevy::prelude::*;
+use shared::RestKind;
 
-/// Fired when the player clicks a progress-bar cell to change a resource.
-#[derive(Message)]
+/// Fired when the player clicks a progress-bar cell to change a resource,
+/// picks damage, healing, temporary HP or a rest from the HP bar's menu,
+/// or types a value into a bar's numeric editor.
+#[derive(Message, Debug, PartialEq)]
 pub enum ResourceChanged {
     Hp(u32),
+    HpDelta(i32),
+    TempHp(u32),
     Mp(u32),
     Ap(u32),
+    /// A short rest restores AP and half of max HP. A long rest restores HP,
+    /// MP, AP and per-rest ability uses, if the campaign leaves one for today.
+    /// Both end ability cooldowns.
+    Rest(RestKind),
 }
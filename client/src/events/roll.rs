@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+
+/// Fired when the player rolls a d20 check (e.g. a skill), to be shared
+/// with every connection.
+#[derive(Message)]
+pub struct RollCheck {
+    pub label: String,
+    pub modifier: i32,
+}
+
+/// Fired when the player rolls the damage of an equipped weapon.
+#[derive(Message)]
+pub struct RollDamage {
+    pub weapon: String,
+}
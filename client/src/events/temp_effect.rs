@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+use shared::TempEffect;
+
+/// Fired from the buffs window.
+#[derive(Message)]
+pub enum TempEffectsChanged {
+    Add(TempEffect),
+    /// Remove the temporary effect at this index.
+    Remove(usize),
+    /// Count one turn off every temporary effect.
+    EndTurn,
+}
@@ -7,4 +7,6 @@ pub enum UpgradeEvent {
     Characteristic(usize),
     /// Skill name to upgrade.
     Skill(String),
+    /// Refund all spent characteristic and skill points.
+    Respec,
 }
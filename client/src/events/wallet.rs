@@ -1,6 +1,11 @@
 use bevy::prelude::*;
 
-/// Fired when the player clicks a wallet currency cell.
-/// The value is a signed delta in base currency units.
+/// Fired when money enters or leaves the wallet.
 #[derive(Message)]
-pub struct WalletChanged(pub i64);
+pub enum WalletChanged {
+    /// The player clicked a currency cell: a signed number of coins of the
+    /// denomination at `denomination`, highest first.
+    Coins { denomination: usize, count: i64 },
+    /// A signed delta in base currency units, e.g. a sale price.
+    Base(i64),
+}
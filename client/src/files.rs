@@ -0,0 +1,111 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::prelude::*;
+
+/// Contents of a file the user picked, filled in by [`pick_file`] once the
+/// dialog closes. Poll with [`PickedFile::take`].
+#[derive(Clone, Default)]
+pub struct PickedFile(Arc<Mutex<Option<Vec<u8>>>>);
+
+impl PickedFile {
+    pub fn take(&self) -> Option<Vec<u8>> {
+        self.0.lock().ok().and_then(|mut guard| guard.take())
+    }
+}
+
+/// Filesystem-safe stem for a file named after a character: "Mira Ashvale"
+/// becomes "Mira_Ashvale"; `None` when nothing usable is left.
+pub fn file_stem_for(character_name: &str) -> Option<String> {
+    let stem: String = character_name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    (!stem.is_empty()).then_some(stem)
+}
+
+/// Asks where to save `data` (native) or hands it to the browser as a
+/// download (wasm). `filter` names the file type offered in the dialog.
+pub fn save_file(data: Vec<u8>, file_name: String, filter: &'static str, extension: &'static str) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (filter, extension);
+        if let Err(e) = download(&data, &file_name) {
+            warn!("Failed to download {file_name}: {e:?}");
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::thread::spawn(move || {
+            let path = rfd::FileDialog::new()
+                .add_filter(filter, &[extension])
+                .set_file_name(&file_name)
+                .save_file();
+            if let Some(path) = path {
+                if let Err(e) = std::fs::write(&path, data) {
+                    warn!("Failed to save {}: {e}", path.display());
+                }
+            }
+        });
+    }
+}
+
+/// Opens a file dialog without blocking; the chosen file's bytes land in `into`.
+pub fn pick_file(filter: &'static str, extensions: &'static [&'static str], into: &PickedFile) {
+    let shared = into.0.clone();
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let task = async move {
+            let file = rfd::AsyncFileDialog::new()
+                .add_filter(filter, extensions)
+                .pick_file()
+                .await;
+            if let Some(file) = file {
+                let bytes = file.read().await;
+                if let Ok(mut guard) = shared.lock() {
+                    *guard = Some(bytes);
+                }
+            }
+        };
+        wasm_bindgen_futures::spawn_local(task);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::thread::spawn(move || {
+            let file = rfd::FileDialog::new()
+                .add_filter(filter, extensions)
+                .pick_file();
+            if let Some(path) = file {
+                match std::fs::read(&path) {
+                    Ok(bytes) => {
+                        if let Ok(mut guard) = shared.lock() {
+                            *guard = Some(bytes);
+                        }
+                    }
+                    Err(e) => warn!("Failed to read {}: {e}", path.display()),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn download(data: &[u8], file_name: &str) -> Result<(), web_sys::wasm_bindgen::JsValue> {
+    use web_sys::js_sys::{Array, Uint8Array};
+    use web_sys::wasm_bindgen::JsCast;
+
+    let parts = Array::of1(&Uint8Array::from(data).into());
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("no document")?;
+    let link = document.create_element("a")?;
+    link.set_attribute("href", &url)?;
+    link.set_attribute("download", file_name)?;
+    link.dyn_into::<web_sys::HtmlElement>()?.click();
+    web_sys::Url::revoke_object_url(&url)
+}
@@ -1,11 +1,24 @@
+// Bevy systems routinely take many parameters and nested query tuples.
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
 mod character_select;
 mod components;
+mod conflict;
 mod create_character;
 mod create_item;
+mod errors;
 mod events;
+mod files;
 mod network;
+mod notes;
+mod offline;
 mod portrait;
+mod presence;
+mod reconcile;
+mod screenshot;
+mod simulation;
 mod state;
+mod toasts;
 mod ui;
 mod version_select;
 
@@ -14,9 +27,17 @@ use bevy_egui::EguiPlugin;
 
 use character_select::CharacterSelectPlugin;
 use components::{despawn_active_character, recalculate_effects};
+use conflict::ConflictPlugin;
+use errors::ErrorLogPlugin;
 use network::NetworkPlugin;
+use notes::NotesPlugin;
+use offline::OfflinePlugin;
 use portrait::PortraitPlugin;
+use presence::PresencePlugin;
+use reconcile::ReconcilePlugin;
+use screenshot::ScreenshotPlugin;
 use state::AppScreen;
+use toasts::ToastsPlugin;
 use ui::UiPlugin;
 use version_select::VersionSelectPlugin;
 
@@ -45,7 +66,15 @@ fn main() {
     .add_plugins(CharacterSelectPlugin)
     .add_plugins(VersionSelectPlugin)
     .add_plugins(NetworkPlugin)
+    .add_plugins(OfflinePlugin)
     .add_plugins(PortraitPlugin)
+    .add_plugins(PresencePlugin)
+    .add_plugins(ScreenshotPlugin)
+    .add_plugins(NotesPlugin)
+    .add_plugins(ReconcilePlugin)
+    .add_plugins(ConflictPlugin)
+    .add_plugins(ErrorLogPlugin)
+    .add_plugins(ToastsPlugin)
     .add_plugins(UiPlugin)
     .add_systems(PreStartup, setup)
     .add_systems(
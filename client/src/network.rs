@@ -1,16 +1,39 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
 use ewebsock::{WsEvent, WsMessage, WsReceiver, WsSender};
 use shared::character::SkillRegistry;
 use shared::{
-    deserialize, AbilityRegistry, ClientMessage, EquipmentRegistry, ItemRegistry, ServerMessage,
-    TraitRegistry, WeaponRegistry,
+    deserialize, AbilityRegistry, ClientMessage, EquipmentRegistry, InventoryItem, ItemRegistry,
+    Ruleset, ServerMessage, TraitRegistry, WeaponRegistry,
 };
 
-use crate::character_select::CharacterList;
-use crate::components::spawn_character;
-use crate::portrait::{PendingCreationPortrait, PendingPortraitData};
+use crate::character_select::{CharacterList, CharacterPage, ReferenceMatches};
+use crate::components::{spawn_character, ActiveCharacter, CharacterTraitNames, RestsUsedToday};
+use crate::conflict::{BaseVersion, PendingConflict, RemoteSave, SavedCharacter};
+use crate::errors::ErrorLog;
+use crate::files::{file_stem_for, save_file};
+use crate::notes::GmNotes;
+use crate::offline::{sync_offline_store, OfflineStore};
+use crate::portrait::{CropEditorSlot, PendingCreationPortrait, PendingPortraitData};
+use crate::presence::{Following, Presence, RollEntry, RollFeed};
+use crate::reconcile::PendingReconciliation;
+use crate::simulation::Simulating;
 use crate::state::AppScreen;
-use crate::version_select::VersionList;
+use crate::toasts::{ToastLevel, Toasts};
+use crate::version_select::{VersionDiff, VersionList};
+use ui_widgets::colors::palette;
+use uuid::Uuid;
+
+/// Wait before the first reconnect attempt; doubles with every failed one.
+const RECONNECT_BASE: Duration = Duration::from_secs(1);
+/// Longest wait between reconnect attempts.
+const RECONNECT_MAX: Duration = Duration::from_secs(30);
+/// Failed reconnect attempts after which the client counts as offline.
+const OFFLINE_AFTER: u32 = 5;
 
 /// Holds the WebSocket sender/receiver pair as a non-send Bevy resource.
 ///
@@ -40,6 +63,33 @@ pub struct ClientEquipmentRegistry(pub EquipmentRegistry);
 #[allow(dead_code)]
 pub struct ClientItemRegistry(pub ItemRegistry);
 
+/// Weapons, equipment and items shipped with the client. Anything else in the
+/// registries was created by users and may be deleted from them.
+#[derive(Resource, Default)]
+pub struct BuiltinEntries(pub BTreeSet<InventoryItem>);
+
+impl BuiltinEntries {
+    fn of(weapons: &WeaponRegistry, equipment: &EquipmentRegistry, items: &ItemRegistry) -> Self {
+        let weapons = weapons.weapons.keys().cloned().map(InventoryItem::Weapon);
+        let equipment = equipment
+            .equipment
+            .keys()
+            .cloned()
+            .map(InventoryItem::Equipment);
+        let items = items.items.keys().cloned().map(InventoryItem::Item);
+        Self(weapons.chain(equipment).chain(items).collect())
+    }
+}
+
+/// Campaign ruleset (XP curve, creation, class, race and campaign rules),
+/// received from the server on connect.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ClientRuleset(pub Ruleset);
+
+/// Whether this connection has admin rights (debug tools, maintenance commands).
+#[derive(Resource, Default)]
+pub struct AdminSession(pub bool);
+
 /// Buffer for server messages drained from the WebSocket.
 /// Filled by `drain_ws`, consumed by `process_server_messages`.
 #[derive(Resource, Default, Deref, DerefMut)]
@@ -55,7 +105,64 @@ struct ReconnectTimer(Timer);
 
 impl Default for ReconnectTimer {
     fn default() -> Self {
-        Self(Timer::from_seconds(3.0, TimerMode::Repeating))
+        Self(Timer::new(RECONNECT_BASE, TimerMode::Once))
+    }
+}
+
+/// Passwords replayed after a reconnect so an admin session and unlocked
+/// characters survive a dropped connection.
+#[derive(Resource, Default)]
+struct Credentials {
+    /// Password of the last `Authenticate` sent.
+    admin: Option<String>,
+    /// Passwords of `AuthenticateCharacter` requests awaiting an answer.
+    tried: BTreeMap<Uuid, String>,
+    /// Passwords the server accepted, by character.
+    characters: BTreeMap<Uuid, String>,
+}
+
+/// State of the link to the server. Client messages wait in
+/// [`PendingClientMessages`] until it is `Connected`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConnectionStatus {
+    /// The first connection is being opened.
+    #[default]
+    Connecting,
+    Connected,
+    /// The connection was lost; `failed` reconnect attempts failed since.
+    Reconnecting {
+        failed: u32,
+    },
+    /// Reconnecting keeps failing; attempts go on at the longest backoff.
+    Offline,
+}
+
+impl ConnectionStatus {
+    /// Status once the connection, or an attempt to open one, closes.
+    fn after_close(self) -> Self {
+        let failed = match self {
+            Self::Connected => return Self::Reconnecting { failed: 0 },
+            Self::Connecting => 1,
+            Self::Reconnecting { failed } => failed + 1,
+            Self::Offline => return Self::Offline,
+        };
+        if failed >= OFFLINE_AFTER {
+            Self::Offline
+        } else {
+            Self::Reconnecting { failed }
+        }
+    }
+
+    /// Wait before the next reconnect attempt.
+    fn backoff(self) -> Duration {
+        let failed = match self {
+            Self::Connecting | Self::Connected => 0,
+            Self::Reconnecting { failed } => failed,
+            Self::Offline => return RECONNECT_MAX,
+        };
+        RECONNECT_BASE
+            .saturating_mul(2u32.saturating_pow(failed))
+            .min(RECONNECT_MAX)
     }
 }
 
@@ -76,21 +183,34 @@ impl Plugin for NetworkPlugin {
                 .expect("failed to parse embedded equipment.json");
         let item_reg = ItemRegistry::load_from_str(include_str!("../../data/items.json"))
             .expect("failed to parse embedded items.json");
+        let builtin = BuiltinEntries::of(&weapon_reg, &equipment_reg, &item_reg);
         app.insert_resource(ClientTraitRegistry(trait_reg))
             .insert_resource(ClientSkillRegistry(skill_reg))
             .insert_resource(ClientAbilityRegistry(ability_reg))
             .insert_resource(ClientWeaponRegistry(weapon_reg))
             .insert_resource(ClientEquipmentRegistry(equipment_reg))
             .insert_resource(ClientItemRegistry(item_reg))
+            .insert_resource(builtin)
+            .init_resource::<ClientRuleset>()
+            .init_resource::<AdminSession>()
             .init_resource::<PendingServerMessages>()
             .init_resource::<PendingClientMessages>()
             .init_resource::<ReconnectTimer>()
+            .init_resource::<Credentials>()
+            .init_resource::<ConnectionStatus>()
             .add_systems(Startup, connect_to_server)
             .add_systems(
                 Update,
-                (drain_ws, process_server_messages, send_client_messages).chain(),
+                (
+                    drain_ws,
+                    process_server_messages,
+                    send_client_messages,
+                    sync_offline_store,
+                )
+                    .chain(),
             )
-            .add_systems(Update, attempt_reconnect);
+            .add_systems(Update, attempt_reconnect)
+            .add_systems(EguiPrimaryContextPass, render_connection_status);
     }
 }
 
@@ -135,10 +255,18 @@ fn connect_to_server(world: &mut World) {
     }
 }
 
-/// Drains the WebSocket and buffers decoded messages.
+/// Drains the WebSocket and buffers decoded messages. Tracks the connection
+/// status; after a reconnect, the admin login is sent again ahead of the
+/// messages queued while the connection was down.
 fn drain_ws(
     conn: Option<NonSend<WsConnection>>,
     mut pending: ResMut<PendingServerMessages>,
+    mut pending_client: ResMut<PendingClientMessages>,
+    mut status: ResMut<ConnectionStatus>,
+    mut timer: ResMut<ReconnectTimer>,
+    credentials: Res<Credentials>,
+    admin: Res<AdminSession>,
+    mut toasts: ResMut<Toasts>,
     mut commands: Commands,
 ) {
     let Some(conn) = conn else { return };
@@ -148,6 +276,26 @@ fn drain_ws(
         match event {
             WsEvent::Opened => {
                 info!("WebSocket connection opened");
+                if *status != ConnectionStatus::Connecting {
+                    let unlocks = credentials.characters.iter().map(|(&id, password)| {
+                        ClientMessage::AuthenticateCharacter {
+                            id,
+                            password: password.clone(),
+                        }
+                    });
+                    pending_client.splice(0..0, unlocks);
+                    if let Some(password) = credentials.admin.as_ref().filter(|_| admin.0) {
+                        pending_client.insert(
+                            0,
+                            ClientMessage::Authenticate {
+                                password: password.clone(),
+                            },
+                        );
+                    }
+                    info!("Replaying {} queued message(s)", pending_client.len());
+                    toasts.push(ToastLevel::Info, "Reconnected to the server");
+                }
+                *status = ConnectionStatus::Connected;
             }
             WsEvent::Message(WsMessage::Binary(bytes)) => {
                 match deserialize::<ServerMessage>(&bytes) {
@@ -172,21 +320,49 @@ fn drain_ws(
             world.remove_non_send_resource::<WsConnection>();
         });
         info!("Cleaned up WebSocket connection resource");
+        let previous = *status;
+        *status = previous.after_close();
+        timer.reset();
+        if previous == ConnectionStatus::Connected {
+            toasts.push(
+                ToastLevel::Warning,
+                "Lost connection to the server, reconnecting...",
+            );
+        } else if *status == ConnectionStatus::Offline && previous != ConnectionStatus::Offline {
+            toasts.push(
+                ToastLevel::Error,
+                "Can't reach the server. Changes are kept and sent once it is back.",
+            );
+        }
     }
 }
 
+/// Registries needed to recalculate the effects of a received character.
+#[derive(SystemParam)]
+struct EffectSourceRegistries<'w> {
+    traits: Res<'w, ClientTraitRegistry>,
+    weapons: Res<'w, ClientWeaponRegistry>,
+    equipment: Res<'w, ClientEquipmentRegistry>,
+}
+
 /// Normal system: processes buffered server messages and updates game state.
 fn process_server_messages(
     mut commands: Commands,
     mut pending: ResMut<PendingServerMessages>,
     mut pending_client: ResMut<PendingClientMessages>,
-    mut character_list: ResMut<CharacterList>,
+    (mut character_list, mut character_page): (ResMut<CharacterList>, ResMut<CharacterPage>),
     mut version_list: ResMut<VersionList>,
-    trait_registry: Res<ClientTraitRegistry>,
-    weapon_registry: Res<ClientWeaponRegistry>,
-    equipment_registry: Res<ClientEquipmentRegistry>,
+    registries: EffectSourceRegistries,
     mut next_state: ResMut<NextState<AppScreen>>,
     mut pending_creation_portrait: ResMut<PendingCreationPortrait>,
+    mut ruleset: ResMut<ClientRuleset>,
+    (mut admin, mut credentials): (ResMut<AdminSession>, ResMut<Credentials>),
+    mut presence: ResMut<Presence>,
+    mut following: ResMut<Following>,
+    mut errors: ResMut<ErrorLog>,
+    mut roll_feed: ResMut<RollFeed>,
+    mut toasts: ResMut<Toasts>,
+    active_characters: Query<(Entity, Option<&Simulating>), With<ActiveCharacter>>,
 ) {
     for msg in pending.drain(..) {
         match msg {
@@ -197,6 +373,10 @@ fn process_server_messages(
                 );
                 character_list.characters = characters;
             }
+            ServerMessage::CharacterPage { characters, total } => {
+                character_page.characters = characters;
+                character_page.total = total;
+            }
             ServerMessage::VersionList { id, versions } => {
                 info!(
                     "Received {} version(s) for character {}",
@@ -209,26 +389,97 @@ fn process_server_messages(
                     .find(|c| c.id == id)
                     .map(|c| c.name.clone())
                     .unwrap_or_default();
+                if version_list.character_id != id {
+                    version_list.diff = None;
+                    version_list.audit = None;
+                }
                 version_list.character_id = id;
                 version_list.character_name = name;
                 version_list.versions = versions;
             }
+            ServerMessage::VersionDiff {
+                id,
+                from,
+                to,
+                changes,
+            } => {
+                if id == version_list.character_id {
+                    version_list.diff = Some(VersionDiff { from, to, changes });
+                }
+            }
+            ServerMessage::AuditLog { id, entries } => {
+                if id == version_list.character_id {
+                    version_list.audit = Some(entries);
+                }
+            }
             ServerMessage::CharacterVersion {
                 id,
                 version,
+                latest_version,
                 character,
                 ..
             } => {
                 info!("Received character {} version {}", id, version);
                 let mut character = *character;
                 character.recalculate_effects(
-                    &trait_registry,
-                    &weapon_registry,
-                    &equipment_registry,
+                    &registries.traits,
+                    &registries.weapons,
+                    &registries.equipment,
+                    &ruleset.campaign.effect_modifiers,
                 );
-                spawn_character(&mut commands, &character);
+                // Every load replaces the prompt, so a stale one never outlives its sheet
+                let reconciliation = character.reconcile(&ruleset);
+                commands.insert_resource(PendingReconciliation(
+                    (!reconciliation.is_clean()).then_some(reconciliation),
+                ));
+                commands.insert_resource(BaseVersion(Some((id, latest_version))));
+                commands.insert_resource(PendingConflict(None));
+                commands.insert_resource(SavedCharacter(Some(character.clone())));
+                // Replace the open sheet when a followed editor switches or saves
+                for (entity, simulating) in &active_characters {
+                    commands.entity(entity).despawn();
+                    if let Some(simulating) = simulating {
+                        commands.entity(simulating.original).despawn();
+                    }
+                }
+                spawn_character(&mut commands, &character, &ruleset.campaign);
                 next_state.set(AppScreen::CharacterSheet);
             }
+            ServerMessage::CharacterExport { id, json } => {
+                info!("Received export of character {}", id);
+                let stem = character_list
+                    .characters
+                    .iter()
+                    .find(|c| c.id == id)
+                    .and_then(|c| file_stem_for(&c.name))
+                    .unwrap_or_else(|| "character".to_string());
+                save_file(json.into_bytes(), format!("{stem}.json"), "JSON", "json");
+            }
+            ServerMessage::ImportResult {
+                id,
+                missing_traits,
+                missing_weapons,
+                missing_equipment,
+            } => {
+                info!("Character imported as {}", id);
+                let missing: Vec<String> = [
+                    ("traits", missing_traits),
+                    ("weapons", missing_weapons),
+                    ("equipment", missing_equipment),
+                ]
+                .into_iter()
+                .filter(|(_, names)| !names.is_empty())
+                .map(|(kind, names)| format!("{kind}: {}", names.join(", ")))
+                .collect();
+                if missing.is_empty() {
+                    toasts.push(ToastLevel::Info, "Character imported");
+                } else {
+                    toasts.push(
+                        ToastLevel::Warning,
+                        format!("Imported with unknown {}", missing.join("; ")),
+                    );
+                }
+            }
             ServerMessage::CharacterCreated { summary } => {
                 info!("Character created: {}", summary.name);
                 let id = summary.id;
@@ -245,6 +496,18 @@ fn process_server_messages(
             }
             ServerMessage::CharacterUpdated { summary } => {
                 info!("Character updated: {}", summary.name);
+                // Our own save went through; the next one builds on it
+                let (id, latest) = (summary.id, summary.latest_version);
+                commands.queue(move |world: &mut World| {
+                    let mut base = world.resource_mut::<BaseVersion>();
+                    if base.of(id).is_some() {
+                        base.0 = Some((id, latest));
+                    }
+                    let mut offline = world.resource_mut::<OfflineStore>();
+                    if offline.saves.contains_key(&id) {
+                        offline.saves.remove(&id);
+                    }
+                });
                 if let Some(existing) = character_list
                     .characters
                     .iter_mut()
@@ -253,6 +516,24 @@ fn process_server_messages(
                     *existing = summary;
                 }
             }
+            ServerMessage::CharacterChanged { summary } => {
+                info!("Character {} saved elsewhere", summary.name);
+                if let Some(existing) = character_list
+                    .characters
+                    .iter_mut()
+                    .find(|c| c.id == summary.id)
+                {
+                    *existing = summary.clone();
+                }
+                commands.insert_resource(RemoteSave(Some(summary)));
+            }
+            ServerMessage::UpdateConflict { id, latest } => {
+                warn!(
+                    "Save of character {} refused, version {} was saved meanwhile",
+                    id, latest.version
+                );
+                commands.insert_resource(PendingConflict(Some(*latest)));
+            }
             ServerMessage::CharacterDeleted { id } => {
                 info!("Character deleted: {}", id);
                 character_list.characters.retain(|c| c.id != id);
@@ -267,40 +548,239 @@ fn process_server_messages(
                 info!("Received portrait for character {}", id);
                 commands.insert_resource(PendingPortraitData { id, png_data });
             }
-            ServerMessage::Error { message } => {
-                error!("Server error: {message}");
+            ServerMessage::PortraitList { hashes, thumbnails } => {
+                info!("Received {} gallery portrait(s)", hashes.len());
+                commands.queue(move |world: &mut World| {
+                    world
+                        .resource_mut::<CropEditorSlot>()
+                        .gallery
+                        .set_thumbnails(hashes, thumbnails);
+                });
+            }
+            ServerMessage::GalleryPortrait { hash, png_data } => {
+                info!("Received gallery portrait {}", hash);
+                commands.queue(move |world: &mut World| {
+                    world.resource_mut::<CropEditorSlot>().result = Some(png_data);
+                });
+            }
+            ServerMessage::Ruleset { rules } => {
+                info!(
+                    "Received ruleset for {} class(es) and {} race(s)",
+                    rules.classes.classes.len(),
+                    rules.races.races.len()
+                );
+                **ruleset = rules;
+            }
+            ServerMessage::Registries {
+                traits,
+                weapons,
+                equipment,
+                items,
+            } => {
+                info!(
+                    "Received {} trait(s), {} weapon(s), {} equipment and {} item(s)",
+                    traits.len(),
+                    weapons.len(),
+                    equipment.len(),
+                    items.len()
+                );
+                commands.insert_resource(ClientTraitRegistry(TraitRegistry { traits }));
+                commands.insert_resource(ClientWeaponRegistry(WeaponRegistry { weapons }));
+                commands.insert_resource(ClientEquipmentRegistry(EquipmentRegistry { equipment }));
+                commands.insert_resource(ClientItemRegistry(ItemRegistry { items }));
+                // Effects of the open character may come from changed definitions
+                for (entity, _) in &active_characters {
+                    commands.queue(move |world: &mut World| {
+                        if let Some(mut traits) = world.get_mut::<CharacterTraitNames>(entity) {
+                            traits.set_changed();
+                        }
+                    });
+                }
+                toasts.push(ToastLevel::Info, "Data files reloaded");
+            }
+            ServerMessage::WeaponDeleted { name } => {
+                info!("Weapon {name} deleted from the registry");
+                commands.queue(move |world: &mut World| {
+                    world
+                        .resource_mut::<ClientWeaponRegistry>()
+                        .weapons
+                        .remove(&name);
+                });
+            }
+            ServerMessage::EquipmentDeleted { name } => {
+                info!("Equipment {name} deleted from the registry");
+                commands.queue(move |world: &mut World| {
+                    world
+                        .resource_mut::<ClientEquipmentRegistry>()
+                        .equipment
+                        .remove(&name);
+                });
+            }
+            ServerMessage::ItemDeleted { name } => {
+                info!("Item {name} deleted from the registry");
+                commands.queue(move |world: &mut World| {
+                    world
+                        .resource_mut::<ClientItemRegistry>()
+                        .items
+                        .remove(&name);
+                });
+            }
+            ServerMessage::RecomputeReport { checked, repaired } => {
+                let report = format!(
+                    "Recomputed {} character(s), repaired {}",
+                    checked,
+                    repaired.len()
+                );
+                info!("{report}");
+                toasts.push(ToastLevel::Info, report);
+            }
+            ServerMessage::Welcome { connection_id } => {
+                info!("Connected as #{connection_id}");
+                presence.connection_id = Some(connection_id);
+                *following = Following::default();
+            }
+            ServerMessage::Session { admin: is_admin } => {
+                info!("Session admin rights: {is_admin}");
+                admin.0 = is_admin;
+            }
+            ServerMessage::CharacterUnlocked { id } => {
+                if let Some(password) = credentials.tried.remove(&id) {
+                    credentials.characters.insert(id, password);
+                }
+                let name = character_list
+                    .characters
+                    .iter()
+                    .find(|c| c.id == id)
+                    .map_or("the character", |c| c.name.as_str());
+                toasts.push(ToastLevel::Info, format!("You can now edit {name}"));
+            }
+            ServerMessage::Presence { connections } => {
+                presence.connections = connections;
+            }
+            ServerMessage::FollowView { connection_id, id } => {
+                if following.requested == Some(connection_id) {
+                    following.requested = None;
+                    following.current = Some(connection_id);
+                } else if following.current != Some(connection_id) {
+                    continue;
+                }
+                match id {
+                    Some(id) => pending_client
+                        .0
+                        .push(shared::ClientMessage::RequestCharacterVersion { id, version: None }),
+                    None => next_state.set(AppScreen::CharacterSelect),
+                }
+            }
+            ServerMessage::FollowEnded { connection_id } => {
+                info!("Connection #{connection_id} went away, follow mode ended");
+                if following.current == Some(connection_id) {
+                    following.current = None;
+                }
+            }
+            ServerMessage::ReferenceMatches {
+                kind,
+                name,
+                characters,
+            } => {
+                character_list.reference_matches = Some(ReferenceMatches {
+                    label: format!("{kind} \"{name}\""),
+                    ids: characters.iter().map(|c| c.id).collect(),
+                });
+            }
+            ServerMessage::Notes { id, notes } => {
+                commands.queue(move |world: &mut World| {
+                    world.resource_mut::<GmNotes>().set(id, notes);
+                });
+            }
+            ServerMessage::NoteAdded { id, note } => {
+                info!("GM note added to character {}", id);
+                commands.queue(move |world: &mut World| {
+                    world.resource_mut::<GmNotes>().add(id, note);
+                });
+            }
+            ServerMessage::RollBroadcast {
+                from,
+                label,
+                total,
+                detail,
+            } => {
+                roll_feed.push(RollEntry {
+                    from,
+                    label,
+                    total,
+                    detail,
+                });
+            }
+            ServerMessage::DayStarted => {
+                info!("A new day started");
+                toasts.push(ToastLevel::Info, "A new day has started");
+                commands.queue(|world: &mut World| {
+                    let mut query =
+                        world.query_filtered::<&mut RestsUsedToday, With<ActiveCharacter>>();
+                    for mut rests in query.iter_mut(world) {
+                        rests.0 = 0;
+                    }
+                });
+            }
+            ServerMessage::SafeMode { reason } => {
+                warn!("Server is in safe mode: {reason}");
+                errors.set_safe_mode(reason);
+            }
+            ServerMessage::Error { code, message } => {
+                error!("Server error ({code}): {message}");
+                toasts.push(ToastLevel::Error, format!("{code}: {message}"));
+                errors.push(code, message);
             }
         }
     }
 }
 
-/// Sends buffered client messages over the WebSocket.
+/// Sends buffered client messages over the WebSocket once it is open;
+/// until then they stay queued.
 fn send_client_messages(
     mut conn: Option<NonSendMut<WsConnection>>,
     mut pending: ResMut<PendingClientMessages>,
+    mut credentials: ResMut<Credentials>,
+    status: Res<ConnectionStatus>,
 ) {
     let Some(conn) = conn.as_mut() else { return };
+    if *status != ConnectionStatus::Connected {
+        return;
+    }
     for msg in pending.drain(..) {
+        match &msg {
+            ClientMessage::Authenticate { password } => {
+                credentials.admin = Some(password.clone());
+            }
+            ClientMessage::AuthenticateCharacter { id, password } => {
+                credentials.tried.insert(*id, password.clone());
+            }
+            _ => {}
+        }
         if let Ok(bytes) = shared::serialize(&msg) {
             conn.sender.send(WsMessage::Binary(bytes));
         }
     }
 }
 
-/// Exclusive system: reconnects if the WebSocket is gone.
+/// Exclusive system: reconnects if the WebSocket is gone, waiting longer
+/// after each failed attempt (see [`ConnectionStatus::backoff`]).
 /// Must be exclusive because `WsSender`/`WsReceiver` are not `Send` on WASM
 /// (`Rc<WebSocket>`), so `commands.queue()` cannot move them across threads.
 fn attempt_reconnect(world: &mut World) {
     if world.get_non_send_resource::<WsConnection>().is_some() {
-        world.resource_mut::<ReconnectTimer>().reset();
         return;
     }
 
     let delta = world.resource::<Time>().delta();
-    world.resource_mut::<ReconnectTimer>().tick(delta);
-    if !world.resource::<ReconnectTimer>().just_finished() {
+    let backoff = world.resource::<ConnectionStatus>().backoff();
+    let mut timer = world.resource_mut::<ReconnectTimer>();
+    timer.set_duration(backoff);
+    timer.tick(delta);
+    if !timer.just_finished() {
         return;
     }
+    timer.reset();
 
     let url = match server_url() {
         Ok(url) => url,
@@ -313,11 +793,77 @@ fn attempt_reconnect(world: &mut World) {
 
     match ewebsock::connect(&url, ewebsock::Options::default()) {
         Ok((sender, receiver)) => {
-            info!("Reconnected to server");
+            info!("Reconnection initiated");
             world.insert_non_send_resource(WsConnection { sender, receiver });
         }
         Err(err) => {
             warn!("Reconnection failed: {err}");
+            let mut status = world.resource_mut::<ConnectionStatus>();
+            *status = status.after_close();
+        }
+    }
+}
+
+/// Small badge in the bottom-left corner while the server is unreachable.
+fn render_connection_status(
+    mut contexts: EguiContexts,
+    status: Res<ConnectionStatus>,
+    pending: Res<PendingClientMessages>,
+) -> Result {
+    let (color, text) = match *status {
+        ConnectionStatus::Connected => return Ok(()),
+        ConnectionStatus::Connecting => {
+            (egui::Color32::from_rgb(0x70, 0xA8, 0xE0), "Connecting...")
+        }
+        ConnectionStatus::Reconnecting { .. } => {
+            (egui::Color32::from_rgb(0xE0, 0xA0, 0x40), "Reconnecting...")
+        }
+        ConnectionStatus::Offline => (egui::Color32::from_rgb(0xE0, 0x60, 0x60), "Offline"),
+    };
+    let text = match pending.len() {
+        0 => text.to_string(),
+        queued => format!("{text} {queued} change(s) waiting"),
+    };
+    egui::Area::new(egui::Id::new("connection_status"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::LEFT_BOTTOM, [12.0, -12.0])
+        .interactable(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(6.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::symmetric(8, 4))
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        let (rect, _) =
+                            ui.allocate_exact_size(egui::vec2(8.0, 8.0), egui::Sense::hover());
+                        ui.painter().circle_filled(rect.center(), 4.0, color);
+                        ui.label(egui::RichText::new(text).size(11.0).color(palette().text));
+                    });
+                });
+        });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_backs_off_until_offline() {
+        let mut status = ConnectionStatus::Connected.after_close();
+        assert_eq!(status, ConnectionStatus::Reconnecting { failed: 0 });
+        assert_eq!(status.backoff(), RECONNECT_BASE);
+
+        let mut waits = Vec::new();
+        while status != ConnectionStatus::Offline {
+            status = status.after_close();
+            waits.push(status.backoff());
         }
+        assert!(waits.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(waits.len() as u32, OFFLINE_AFTER);
+        assert_eq!(status.backoff(), RECONNECT_MAX);
+        assert_eq!(status.after_close(), ConnectionStatus::Offline);
     }
 }
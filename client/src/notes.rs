@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+use shared::GmNote;
+use ui_widgets::colors::{palette, TEMP_HP_COLOR};
+use uuid::Uuid;
+
+use crate::components::{ActiveCharacter, CharacterId};
+use crate::network::{AdminSession, PendingClientMessages};
+use crate::state::AppScreen;
+
+/// GM notes of the character open on the sheet.
+#[derive(Resource, Default)]
+pub struct GmNotes {
+    character: Option<Uuid>,
+    notes: Vec<GmNote>,
+    /// Notes received live since the list was last opened.
+    unread: usize,
+    open: bool,
+    /// Text of the note an admin is writing.
+    draft: String,
+}
+
+impl GmNotes {
+    /// Replaces the notes with the full list sent when a character is opened.
+    pub fn set(&mut self, character: Uuid, notes: Vec<GmNote>) {
+        self.character = Some(character);
+        self.notes = notes;
+        self.unread = 0;
+    }
+
+    /// Adds a note pushed live; ignored unless it is for the open character.
+    pub fn add(&mut self, character: Uuid, note: GmNote) {
+        if self.character != Some(character) {
+            return;
+        }
+        self.notes.push(note);
+        if !self.open {
+            self.unread += 1;
+        }
+    }
+}
+
+pub struct NotesPlugin;
+
+impl Plugin for NotesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GmNotes>()
+            .add_systems(OnExit(AppScreen::CharacterSheet), clear_notes)
+            .add_systems(
+                EguiPrimaryContextPass,
+                render_gm_notes.run_if(in_state(AppScreen::CharacterSheet)),
+            );
+    }
+}
+
+fn clear_notes(mut notes: ResMut<GmNotes>) {
+    *notes = GmNotes::default();
+}
+
+/// Notes button (highlighted while there are unread notes) and the notes window.
+/// Admins can push new notes from the window.
+fn render_gm_notes(
+    mut contexts: EguiContexts,
+    mut notes: ResMut<GmNotes>,
+    admin: Res<AdminSession>,
+    character: Query<&CharacterId, With<ActiveCharacter>>,
+    mut pending_messages: ResMut<PendingClientMessages>,
+) -> Result {
+    let Ok(&CharacterId(id)) = character.single() else {
+        return Ok(());
+    };
+    if notes.notes.is_empty() && !admin.0 {
+        return Ok(());
+    }
+    let ctx = contexts.ctx_mut()?;
+    let notes = &mut *notes;
+
+    egui::Area::new(egui::Id::new("gm_notes_button"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::RIGHT_TOP, [-12.0, 8.0])
+        .show(ctx, |ui| {
+            let label = if notes.unread > 0 {
+                format!("New GM note ({})", notes.unread)
+            } else {
+                format!("GM notes ({})", notes.notes.len())
+            };
+            let fill = if notes.unread > 0 {
+                TEMP_HP_COLOR
+            } else {
+                palette().secondary
+            };
+            let button = egui::Button::new(egui::RichText::new(label).color(palette().text))
+                .fill(fill)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .corner_radius(6.0);
+            if ui.add(button).clicked() {
+                notes.open = !notes.open;
+            }
+        });
+
+    if !notes.open {
+        return Ok(());
+    }
+    notes.unread = 0;
+    let mut open = true;
+    egui::Window::new("GM notes")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_width(320.0)
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(12)),
+        )
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    if notes.notes.is_empty() {
+                        ui.label(egui::RichText::new("No notes yet").color(palette().text));
+                    }
+                    for note in &notes.notes {
+                        ui.label(
+                            egui::RichText::new(format!("{}: {}", note.author, note.text))
+                                .color(palette().text),
+                        );
+                        ui.add_space(4.0);
+                    }
+                });
+            if admin.0 {
+                ui.separator();
+                ui.add(
+                    egui::TextEdit::multiline(&mut notes.draft)
+                        .desired_rows(2)
+                        .hint_text("Note for the player"),
+                );
+                if ui
+                    .add_enabled(
+                        !notes.draft.trim().is_empty(),
+                        egui::Button::new("Push note"),
+                    )
+                    .clicked()
+                {
+                    pending_messages.0.push(shared::ClientMessage::PushNote {
+                        target_id: id,
+                        text: std::mem::take(&mut notes.draft),
+                    });
+                }
+            }
+        });
+    notes.open = open;
+
+    Ok(())
+}
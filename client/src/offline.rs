@@ -0,0 +1,212 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use shared::{Character, ClientMessage};
+use uuid::Uuid;
+
+use crate::network::{ConnectionStatus, PendingClientMessages};
+use crate::toasts::{ToastLevel, Toasts};
+
+/// File the offline edits are kept in between sessions on native.
+#[cfg(not(target_arch = "wasm32"))]
+const OFFLINE_FILE: &str = "offline.json";
+
+/// localStorage key the offline edits are kept under in the browser.
+#[cfg(target_arch = "wasm32")]
+const OFFLINE_KEY: &str = "character-sheet-offline";
+
+/// Edits made while the server was unreachable, kept across restarts until
+/// they are sent.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OfflineStore {
+    /// Unsent requests that change stored data, in the order they were made.
+    pub queue: Vec<ClientMessage>,
+    /// Characters as last saved offline, kept until the server accepts the
+    /// save so a conflicting one can still be kept over the server's version.
+    pub saves: BTreeMap<Uuid, Character>,
+}
+
+impl OfflineStore {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load() -> Self {
+        std::fs::read_to_string(OFFLINE_FILE)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn load() -> Self {
+        local_storage()
+            .and_then(|storage| storage.get_item(OFFLINE_KEY).ok().flatten())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_offline_file(store: &OfflineStore) {
+    let result = serde_json::to_string(store)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(OFFLINE_FILE, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("Failed to save {OFFLINE_FILE}: {e}");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_offline_file(store: &OfflineStore) {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    let result = serde_json::to_string(store)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            storage
+                .set_item(OFFLINE_KEY, &json)
+                .map_err(|e| format!("{e:?}"))
+        });
+    if let Err(e) = result {
+        warn!("Failed to save offline edits: {e}");
+    }
+}
+
+pub struct OfflinePlugin;
+
+impl Plugin for OfflinePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(OfflineStore::load())
+            .add_systems(Startup, restore_offline_queue)
+            .add_systems(Update, save_offline_store);
+    }
+}
+
+/// Queues the edits left unsent by the last session ahead of this one's.
+fn restore_offline_queue(
+    store: Res<OfflineStore>,
+    mut pending: ResMut<PendingClientMessages>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if store.queue.is_empty() {
+        return;
+    }
+    info!("Restoring {} offline edit(s)", store.queue.len());
+    pending.splice(0..0, store.queue.iter().cloned());
+    toasts.push(
+        ToastLevel::Info,
+        format!(
+            "{} change(s) made offline will be sent once connected",
+            store.queue.len()
+        ),
+    );
+}
+
+/// Mirrors the unsent edits into the offline store while the server is
+/// unreachable, and drops them from it once they went out.
+pub(crate) fn sync_offline_store(
+    status: Res<ConnectionStatus>,
+    mut pending: ResMut<PendingClientMessages>,
+    mut store: ResMut<OfflineStore>,
+) {
+    if *status == ConnectionStatus::Connected {
+        if !store.queue.is_empty() {
+            store.queue.clear();
+        }
+        return;
+    }
+    if !pending.is_changed() {
+        return;
+    }
+    drop_superseded_saves(&mut pending);
+    let queue: Vec<ClientMessage> = pending
+        .iter()
+        .filter(|msg| msg.is_mutation())
+        .cloned()
+        .collect();
+    for msg in &queue {
+        if let ClientMessage::UpdateCharacter { character, .. } = msg {
+            store.saves.insert(character.id, (**character).clone());
+        }
+    }
+    store.queue = queue;
+}
+
+/// Keeps only the last queued save of each character. Saves made offline all
+/// start from the same base version, so replaying an earlier one would make
+/// every later one conflict with it.
+fn drop_superseded_saves(queue: &mut Vec<ClientMessage>) {
+    let mut later = BTreeSet::new();
+    let keep: Vec<bool> = queue
+        .iter()
+        .rev()
+        .map(|msg| match msg {
+            ClientMessage::UpdateCharacter { character, .. } => later.insert(character.id),
+            _ => true,
+        })
+        .collect();
+    let mut keep = keep.into_iter().rev();
+    queue.retain(|_| keep.next().unwrap_or(true));
+}
+
+/// Saves the offline store whenever it changes after startup.
+fn save_offline_store(store: Res<OfflineStore>) {
+    if store.is_changed() && !store.is_added() {
+        save_offline_file(&store);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save(character: &Character, experience: u32) -> ClientMessage {
+        ClientMessage::UpdateCharacter {
+            character: Box::new(Character {
+                experience,
+                ..character.clone()
+            }),
+            base_version: Some(1),
+        }
+    }
+
+    #[test]
+    fn test_offline_saves_collapse_and_survive_json() {
+        let first = Character::new("First".to_string());
+        let second = Character::new("Second".to_string());
+        let mut queue = vec![
+            save(&first, 1),
+            save(&second, 1),
+            ClientMessage::SetTags {
+                id: first.id,
+                tags: vec!["party".to_string()],
+            },
+            save(&first, 2),
+        ];
+        drop_superseded_saves(&mut queue);
+        let experience: Vec<u32> = queue
+            .iter()
+            .filter_map(|msg| match msg {
+                ClientMessage::UpdateCharacter { character, .. } => Some(character.experience),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(queue.len(), 3);
+        assert_eq!(experience, vec![1, 2]);
+
+        let store = OfflineStore {
+            queue,
+            saves: BTreeMap::from([(first.id, first.clone())]),
+        };
+        let json = serde_json::to_string(&store).unwrap();
+        let restored: OfflineStore = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.queue.len(), 3);
+        assert_eq!(restored.saves[&first.id], first);
+    }
+}
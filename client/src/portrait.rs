@@ -1,12 +1,12 @@
 use std::io::Cursor;
-use std::sync::{Arc, Mutex};
 
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
-use ui_widgets::colors::{MAIN_COLOR, STROKE_COLOR, TEXT_COLOR};
+use ui_widgets::colors::palette;
 use uuid::Uuid;
 
 use crate::components::{ActiveCharacter, CharacterId, PortraitTexture};
+use crate::files::{pick_file, PickedFile};
 
 /// Maximum portrait size in bytes (512KB).
 const MAX_PORTRAIT_SIZE: usize = 512 * 1024;
@@ -16,14 +16,8 @@ const MAX_PORTRAIT_SIZE: usize = 512 * 1024;
 // ---------------------------------------------------------------------------
 
 /// Shared buffer for receiving file bytes from the async file dialog.
-#[derive(Resource, Clone)]
-pub struct PortraitPickerResult(pub Arc<Mutex<Option<Vec<u8>>>>);
-
-impl Default for PortraitPickerResult {
-    fn default() -> Self {
-        Self(Arc::new(Mutex::new(None)))
-    }
-}
+#[derive(Resource, Clone, Default)]
+pub struct PortraitPickerResult(pub PickedFile);
 
 /// Buffered portrait data received from the server, waiting for texture creation.
 #[derive(Resource)]
@@ -45,6 +39,30 @@ pub struct CropEditorSlot {
     pub open: bool,
     pub editor: Option<PortraitCropEditor>,
     pub result: Option<Vec<u8>>,
+    pub gallery: PortraitGallery,
+}
+
+/// Previously uploaded portraits offered instead of the file picker.
+/// Picking one requests the full image, which arrives in `CropEditorSlot::result`.
+#[derive(Default)]
+pub struct PortraitGallery {
+    open: bool,
+    requested: bool,
+    /// Thumbnails received from the server, decoded on first render.
+    pending: Option<(Vec<String>, Vec<Vec<u8>>)>,
+    thumbnails: Vec<(String, egui::TextureHandle)>,
+}
+
+impl PortraitGallery {
+    /// Opens the gallery and refreshes its contents from the server.
+    pub fn show(&mut self) {
+        self.open = true;
+        self.requested = false;
+    }
+
+    pub fn set_thumbnails(&mut self, hashes: Vec<String>, thumbnails: Vec<Vec<u8>>) {
+        self.pending = Some((hashes, thumbnails));
+    }
 }
 
 /// State for the interactive crop/adjust editor.
@@ -92,40 +110,7 @@ impl PortraitCropEditor {
 
 /// Opens the file dialog and stores the result in [`PortraitPickerResult`].
 pub fn spawn_portrait_picker(result: &PortraitPickerResult) {
-    let shared = result.0.clone();
-
-    #[cfg(target_arch = "wasm32")]
-    {
-        let task = async move {
-            let file = rfd::AsyncFileDialog::new()
-                .add_filter("Images", &["png", "jpg", "jpeg", "webp", "bmp"])
-                .pick_file()
-                .await;
-            if let Some(file) = file {
-                let bytes = file.read().await;
-                if let Ok(mut guard) = shared.lock() {
-                    *guard = Some(bytes);
-                }
-            }
-        };
-        wasm_bindgen_futures::spawn_local(task);
-    }
-
-    #[cfg(not(target_arch = "wasm32"))]
-    {
-        std::thread::spawn(move || {
-            let file = rfd::FileDialog::new()
-                .add_filter("Images", &["png", "jpg", "jpeg", "webp", "bmp"])
-                .pick_file();
-            if let Some(path) = file {
-                if let Ok(bytes) = std::fs::read(&path) {
-                    if let Ok(mut guard) = shared.lock() {
-                        *guard = Some(bytes);
-                    }
-                }
-            }
-        });
-    }
+    pick_file("Images", &["png", "jpg", "jpeg", "webp", "bmp"], &result.0);
 }
 
 // ---------------------------------------------------------------------------
@@ -208,12 +193,10 @@ pub fn poll_and_render_crop_popup(
     picker: &PortraitPickerResult,
 ) {
     // Poll picker — when raw bytes arrive, open the crop popup automatically.
-    if let Ok(mut guard) = picker.0.lock() {
-        if let Some(raw_bytes) = guard.take() {
-            slot.editor = PortraitCropEditor::from_raw_bytes(ctx, &raw_bytes);
-            if slot.editor.is_some() {
-                slot.open = true;
-            }
+    if let Some(raw_bytes) = picker.0.take() {
+        slot.editor = PortraitCropEditor::from_raw_bytes(ctx, &raw_bytes);
+        if slot.editor.is_some() {
+            slot.open = true;
         }
     }
 
@@ -236,9 +219,9 @@ pub fn poll_and_render_crop_popup(
         .resizable(false)
         .frame(
             egui::Frame::new()
-                .fill(MAIN_COLOR)
+                .fill(palette().main)
                 .corner_radius(12.0)
-                .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
                 .inner_margin(egui::Margin::same(16)),
         )
         .min_width(popup_w)
@@ -248,7 +231,7 @@ pub fn poll_and_render_crop_popup(
                 ui.label(
                     egui::RichText::new("Portrait")
                         .size(18.0)
-                        .color(TEXT_COLOR)
+                        .color(palette().text)
                         .strong(),
                 );
                 ui.add_space(10.0);
@@ -295,7 +278,11 @@ pub fn poll_and_render_crop_popup(
 
                 // Zoom slider.
                 ui.horizontal(|ui| {
-                    ui.label(egui::RichText::new("Zoom:").size(13.0).color(TEXT_COLOR));
+                    ui.label(
+                        egui::RichText::new("Zoom:")
+                            .size(13.0)
+                            .color(palette().text),
+                    );
                     ui.add(
                         egui::Slider::new(&mut editor.zoom, 0.2..=5.0)
                             .show_value(false)
@@ -308,11 +295,13 @@ pub fn poll_and_render_crop_popup(
                 // Ok / Cancel buttons.
                 ui.horizontal(|ui| {
                     let cancel = egui::Button::new(
-                        egui::RichText::new("Cancel").size(14.0).color(TEXT_COLOR),
+                        egui::RichText::new("Cancel")
+                            .size(14.0)
+                            .color(palette().text),
                     )
                     .corner_radius(6.0)
-                    .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
-                    .fill(MAIN_COLOR)
+                    .stroke(egui::Stroke::new(1.0, palette().stroke))
+                    .fill(palette().main)
                     .min_size(egui::vec2(80.0, 28.0));
                     if ui.add(cancel).clicked() {
                         still_open = false;
@@ -320,12 +309,13 @@ pub fn poll_and_render_crop_popup(
 
                     ui.add_space(8.0);
 
-                    let ok_btn =
-                        egui::Button::new(egui::RichText::new("Ok").size(14.0).color(TEXT_COLOR))
-                            .corner_radius(6.0)
-                            .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
-                            .fill(MAIN_COLOR)
-                            .min_size(egui::vec2(80.0, 28.0));
+                    let ok_btn = egui::Button::new(
+                        egui::RichText::new("Ok").size(14.0).color(palette().text),
+                    )
+                    .corner_radius(6.0)
+                    .stroke(egui::Stroke::new(1.0, palette().stroke))
+                    .fill(palette().main)
+                    .min_size(egui::vec2(80.0, 28.0));
                     if ui.add(ok_btn).clicked() {
                         let enc_rect = egui::Rect::from_min_size(
                             egui::Pos2::ZERO,
@@ -355,6 +345,75 @@ pub fn poll_and_render_crop_popup(
     }
 }
 
+/// Renders the portrait gallery window while it is open. Requests the
+/// thumbnail list when opened; clicking a thumbnail requests the full image.
+pub fn render_portrait_gallery(
+    ctx: &egui::Context,
+    slot: &mut CropEditorSlot,
+    pending_messages: &mut crate::network::PendingClientMessages,
+) {
+    let gallery = &mut slot.gallery;
+    if !gallery.open {
+        return;
+    }
+    if !gallery.requested {
+        gallery.requested = true;
+        pending_messages
+            .0
+            .push(shared::ClientMessage::ListPortraits);
+    }
+    if let Some((hashes, thumbnails)) = gallery.pending.take() {
+        gallery.thumbnails = hashes
+            .into_iter()
+            .zip(thumbnails)
+            .filter_map(|(hash, png)| {
+                let texture = png_to_texture(ctx, &format!("gallery_{hash}"), &png)?;
+                Some((hash, texture))
+            })
+            .collect();
+    }
+
+    let mut open = true;
+    let mut picked = None;
+    egui::Window::new("Portrait Gallery")
+        .open(&mut open)
+        .order(egui::Order::Foreground)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([360.0, 300.0])
+        .frame(
+            egui::Frame::new()
+                .fill(palette().main)
+                .corner_radius(12.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(12)),
+        )
+        .show(ctx, |ui| {
+            if gallery.thumbnails.is_empty() {
+                ui.label(egui::RichText::new("No stored portraits").color(palette().text));
+                return;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for (hash, texture) in &gallery.thumbnails {
+                        let image = egui::Image::new(texture).max_size(egui::vec2(64.0, 64.0));
+                        if ui.add(egui::Button::image(image)).clicked() {
+                            picked = Some(hash.clone());
+                        }
+                    }
+                });
+            });
+        });
+
+    if let Some(hash) = picked {
+        pending_messages
+            .0
+            .push(shared::ClientMessage::RequestGalleryPortrait { hash });
+        open = false;
+    }
+    gallery.open = open;
+}
+
 /// Paints the zoomed/panned image with a bright portrait-shaped ellipse
 /// and dimmed surroundings.
 fn paint_crop_preview(
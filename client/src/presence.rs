@@ -0,0 +1,281 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+use shared::{ConnectionId, PresenceEntry};
+use ui_widgets::colors::palette;
+
+use crate::character_select::CharacterList;
+use crate::components::CharacterId;
+use crate::network::PendingClientMessages;
+use crate::state::AppScreen;
+
+/// Live connections known to the server, received on connect and on every change.
+#[derive(Resource, Default)]
+pub struct Presence {
+    /// Id the server assigned to this client.
+    pub connection_id: Option<ConnectionId>,
+    pub connections: Vec<PresenceEntry>,
+}
+
+/// Number of rolls kept in the session's roll feed.
+const ROLL_FEED_LEN: usize = 30;
+
+/// A dice roll shared by a live connection.
+pub struct RollEntry {
+    pub from: ConnectionId,
+    pub label: String,
+    pub total: i32,
+    pub detail: String,
+}
+
+/// Rolls broadcast during this session, oldest first. Not persisted.
+#[derive(Resource, Default)]
+pub struct RollFeed {
+    entries: VecDeque<RollEntry>,
+}
+
+impl RollFeed {
+    pub fn push(&mut self, entry: RollEntry) {
+        self.entries.push_back(entry);
+        while self.entries.len() > ROLL_FEED_LEN {
+            self.entries.pop_front();
+        }
+    }
+}
+
+/// Connection this client is mirroring. While set, the sheet is read-only.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Following {
+    #[deref]
+    pub current: Option<ConnectionId>,
+    /// Connection asked to follow; becomes `current` once the server answers
+    /// with its view, and stays unconfirmed if the request is refused.
+    pub requested: Option<ConnectionId>,
+}
+
+pub struct PresencePlugin;
+
+impl Plugin for PresencePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Presence>()
+            .init_resource::<Following>()
+            .init_resource::<RollFeed>()
+            .add_systems(
+                Update,
+                announce_viewing.run_if(in_state(AppScreen::CharacterSheet)),
+            )
+            .add_systems(OnExit(AppScreen::CharacterSheet), announce_left_sheet)
+            .add_systems(
+                EguiPrimaryContextPass,
+                (
+                    render_live_sessions.run_if(in_state(AppScreen::CharacterSelect)),
+                    render_follow_banner.run_if(in_state(AppScreen::CharacterSheet)),
+                    render_roll_feed,
+                ),
+            );
+    }
+}
+
+/// Tells the server which character was just opened on the sheet.
+fn announce_viewing(
+    opened: Query<&CharacterId, Added<CharacterId>>,
+    mut pending_messages: ResMut<PendingClientMessages>,
+) {
+    for id in &opened {
+        pending_messages
+            .0
+            .push(shared::ClientMessage::Viewing { id: Some(id.0) });
+    }
+}
+
+fn announce_left_sheet(mut pending_messages: ResMut<PendingClientMessages>) {
+    pending_messages
+        .0
+        .push(shared::ClientMessage::Viewing { id: None });
+}
+
+/// Name shown for a connection: its id plus the character it has open.
+fn display_name(
+    presence: &Presence,
+    character_list: &CharacterList,
+    connection_id: ConnectionId,
+) -> String {
+    if presence.connection_id == Some(connection_id) {
+        return "You".to_string();
+    }
+    let name = presence
+        .connections
+        .iter()
+        .find(|entry| entry.connection_id == connection_id)
+        .and_then(|entry| entry.viewing)
+        .and_then(|id| character_list.characters.iter().find(|c| c.id == id));
+    match name {
+        Some(summary) => format!("#{connection_id} {}", summary.name),
+        None => format!("#{connection_id}"),
+    }
+}
+
+/// Shows the session's shared rolls: who rolled, the total and the breakdown.
+fn render_roll_feed(
+    mut contexts: EguiContexts,
+    feed: Res<RollFeed>,
+    presence: Res<Presence>,
+    character_list: Res<CharacterList>,
+) -> Result {
+    if feed.entries.is_empty() {
+        return Ok(());
+    }
+    let ctx = contexts.ctx_mut()?;
+    egui::Window::new("Rolls")
+        .anchor(egui::Align2::LEFT_BOTTOM, [16.0, -16.0])
+        .collapsible(true)
+        .resizable(false)
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(12)),
+        )
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for entry in &feed.entries {
+                        let who = display_name(&presence, &character_list, entry.from);
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{who}: {} = {}",
+                                entry.label, entry.total
+                            ))
+                            .size(13.0)
+                            .color(palette().text)
+                            .strong(),
+                        );
+                        if !entry.detail.is_empty() {
+                            ui.label(
+                                egui::RichText::new(&entry.detail)
+                                    .size(12.0)
+                                    .color(egui::Color32::from_rgb(0x88, 0x88, 0x99)),
+                            );
+                        }
+                    }
+                });
+        });
+
+    Ok(())
+}
+
+/// Lists other connections that have a sheet open, each with a "Follow" button.
+fn render_live_sessions(
+    mut contexts: EguiContexts,
+    presence: Res<Presence>,
+    character_list: Res<CharacterList>,
+    mut following: ResMut<Following>,
+    mut pending_messages: ResMut<PendingClientMessages>,
+) -> Result {
+    let sessions: Vec<(ConnectionId, String)> = presence
+        .connections
+        .iter()
+        .filter(|entry| Some(entry.connection_id) != presence.connection_id)
+        .filter_map(|entry| {
+            let id = entry.viewing?;
+            let name = character_list
+                .characters
+                .iter()
+                .find(|c| c.id == id)
+                .map(|c| c.name.clone())
+                .unwrap_or_else(|| "Unknown character".to_string());
+            Some((entry.connection_id, name))
+        })
+        .collect();
+    if sessions.is_empty() {
+        return Ok(());
+    }
+
+    let ctx = contexts.ctx_mut()?;
+    egui::Window::new("Live sessions")
+        .anchor(egui::Align2::RIGHT_TOP, [-16.0, 16.0])
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(12)),
+        )
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new("Live sessions")
+                    .size(16.0)
+                    .color(palette().text)
+                    .strong(),
+            );
+            ui.add_space(6.0);
+            for (connection_id, name) in sessions {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!("#{connection_id}  {name}"))
+                            .size(13.0)
+                            .color(palette().text),
+                    );
+                    let button = egui::Button::new(
+                        egui::RichText::new("Follow")
+                            .size(13.0)
+                            .color(palette().text),
+                    )
+                    .corner_radius(4.0)
+                    .fill(palette().main);
+                    if ui.add(button).clicked() {
+                        following.requested = Some(connection_id);
+                        pending_messages
+                            .0
+                            .push(shared::ClientMessage::Follow { connection_id });
+                    }
+                });
+            }
+        });
+
+    Ok(())
+}
+
+/// Shows who is being followed, with an "Unfollow" button that returns control.
+fn render_follow_banner(
+    mut contexts: EguiContexts,
+    mut following: ResMut<Following>,
+    mut pending_messages: ResMut<PendingClientMessages>,
+) -> Result {
+    let Some(connection_id) = following.current else {
+        return Ok(());
+    };
+    let ctx = contexts.ctx_mut()?;
+
+    egui::Area::new(egui::Id::new("follow_banner"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 8.0])
+        .show(ctx, |ui| {
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(6.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::symmetric(12, 6))
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!("Following #{connection_id} (read-only)"))
+                                .color(palette().text),
+                        );
+                        if ui.button("Unfollow").clicked() {
+                            following.current = None;
+                            pending_messages.0.push(shared::ClientMessage::Unfollow);
+                        }
+                    });
+                });
+        });
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+use shared::{PointPool, Reconciliation};
+use ui_widgets::colors::palette;
+
+use crate::components::{
+    AbilityPoints, ActiveCharacter, CharacteristicPoints, SkillPoints, TraitPoints,
+};
+use crate::state::AppScreen;
+
+/// Impossible point totals found when the open character was loaded, waiting
+/// for the user to accept or dismiss the proposed corrections. Replaced on
+/// every character load.
+#[derive(Resource, Default)]
+pub struct PendingReconciliation(pub Option<Reconciliation>);
+
+pub struct ReconcilePlugin;
+
+impl Plugin for ReconcilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingReconciliation>().add_systems(
+            EguiPrimaryContextPass,
+            render_reconciliation.run_if(in_state(AppScreen::CharacterSheet)),
+        );
+    }
+}
+
+/// Lists the issues with the proposed values. "Accept" writes them to the
+/// sheet, to be kept with the next save; "Ignore" keeps the stored values.
+fn render_reconciliation(
+    mut contexts: EguiContexts,
+    mut pending: ResMut<PendingReconciliation>,
+    mut query: Query<
+        (
+            &mut CharacteristicPoints,
+            &mut SkillPoints,
+            &mut AbilityPoints,
+            &mut TraitPoints,
+        ),
+        With<ActiveCharacter>,
+    >,
+) -> Result {
+    let Some(reconciliation) = &pending.0 else {
+        return Ok(());
+    };
+    let ctx = contexts.ctx_mut()?;
+
+    let mut accept = false;
+    let mut ignore = false;
+    egui::Window::new("Point totals look wrong")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(12)),
+        )
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new("This character has more points than the rules allow:")
+                    .color(palette().text),
+            );
+            ui.add_space(4.0);
+            for issue in &reconciliation.issues {
+                ui.label(egui::RichText::new(format!("• {}", issue.message)).color(palette().text));
+                ui.label(
+                    egui::RichText::new(format!(
+                        "   {} unspent {} → {}",
+                        issue.stored, issue.pool, issue.proposed
+                    ))
+                    .color(palette().text),
+                );
+            }
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                accept = ui.button("Accept").clicked();
+                ignore = ui.button("Ignore").clicked();
+            });
+        });
+
+    if accept {
+        if let Ok((mut characteristic, mut skill, mut ability, mut traits)) = query.single_mut() {
+            for issue in &reconciliation.issues {
+                let points = match issue.pool {
+                    PointPool::Characteristic => &mut characteristic.0,
+                    PointPool::Skill => &mut skill.0,
+                    PointPool::Ability => &mut ability.0,
+                    PointPool::Trait => &mut traits.0,
+                };
+                *points = issue.proposed;
+            }
+        }
+    }
+    if accept || ignore {
+        pending.0 = None;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use std::io::Cursor;
+
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{Screenshot, ScreenshotCaptured};
+
+use crate::components::{ActiveCharacter, CharacterName};
+use crate::files::{file_stem_for, save_file};
+use crate::state::AppScreen;
+use crate::toasts::{ToastLevel, Toasts};
+
+/// Sheet screenshot state. The portrait menu sets `requested`; the capture
+/// is taken on the next frame and saved as a PNG.
+#[derive(Resource, Default)]
+pub struct SheetScreenshot {
+    pub requested: bool,
+    /// A capture is in flight; further requests wait until it is saved.
+    pending: bool,
+}
+
+impl SheetScreenshot {
+    /// Whether the "Save screenshot" action can be used right now.
+    pub fn available(&self) -> bool {
+        !self.requested && !self.pending
+    }
+}
+
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SheetScreenshot>().add_systems(
+            Update,
+            capture_sheet
+                .run_if(in_state(AppScreen::CharacterSheet))
+                .run_if(any_with_component::<ActiveCharacter>),
+        );
+    }
+}
+
+/// Captures the whole window, so all three sheet columns end up in the image
+/// at the window's physical resolution.
+fn capture_sheet(
+    mut commands: Commands,
+    mut screenshot: ResMut<SheetScreenshot>,
+    query: Query<&CharacterName, With<ActiveCharacter>>,
+) {
+    if !screenshot.requested || screenshot.pending {
+        return;
+    }
+    screenshot.requested = false;
+    screenshot.pending = true;
+    let file_name = query
+        .single()
+        .map(|name| file_name_for(&name.0))
+        .unwrap_or_else(|_| file_name_for(""));
+    commands.spawn(Screenshot::primary_window()).observe(
+        move |captured: On<ScreenshotCaptured>,
+              mut screenshot: ResMut<SheetScreenshot>,
+              mut toasts: ResMut<Toasts>| {
+            screenshot.pending = false;
+            match encode_png(captured.image.clone()) {
+                Some(png) => save_file(png, file_name.clone(), "PNG image", "png"),
+                None => {
+                    warn!("Failed to encode the sheet screenshot");
+                    toasts.push(ToastLevel::Error, "Failed to capture the sheet");
+                }
+            }
+        },
+    );
+}
+
+/// "Mira Ashvale" becomes "Mira_Ashvale.png"; anything unusable falls back to "sheet.png".
+fn file_name_for(character_name: &str) -> String {
+    let stem = file_stem_for(character_name).unwrap_or_else(|| "sheet".to_string());
+    format!("{stem}.png")
+}
+
+/// Encodes a captured frame as PNG. The alpha channel is dropped, since the
+/// window surface does not carry meaningful transparency.
+fn encode_png(image: Image) -> Option<Vec<u8>> {
+    let rgb = image
+        .try_into_dynamic()
+        .inspect_err(|e| warn!("Unsupported screenshot format: {e}"))
+        .ok()?
+        .to_rgb8();
+    let mut png = Vec::new();
+    rgb.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .inspect_err(|e| warn!("Failed to encode PNG: {e}"))
+        .ok()?;
+    Some(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::RenderAssetUsages;
+    use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+    use super::*;
+
+    #[test]
+    fn test_capture_encodes_to_png() {
+        let size = Extent3d {
+            width: 4,
+            height: 3,
+            depth_or_array_layers: 1,
+        };
+        let frame = Image::new_fill(
+            size,
+            TextureDimension::D2,
+            &[40, 40, 60, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+
+        let png = encode_png(frame).expect("frame should encode");
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        let decoded = image::load_from_memory(&png).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (4, 3));
+
+        assert_eq!(file_name_for("Mira Ashvale"), "Mira_Ashvale.png");
+        assert_eq!(file_name_for("  "), "sheet.png");
+    }
+}
@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::components::{spawn_character, ActiveCharacter, PortraitTexture};
+
+/// Marks a scratch copy of a character used to try out a respec.
+/// While it exists the scratch copy is the `ActiveCharacter`; the real
+/// character stays untouched on `original` until the simulation is committed.
+#[derive(Component)]
+pub struct Simulating {
+    pub original: Entity,
+}
+
+/// What the player chose to do with a simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationAction {
+    Start,
+    Commit,
+    Discard,
+}
+
+/// Spawns a scratch copy of `character` and makes it the active character.
+/// Returns the scratch entity.
+pub fn start(
+    commands: &mut Commands,
+    original: Entity,
+    character: &shared::Character,
+    campaign: &shared::CampaignRules,
+    portrait: Option<&PortraitTexture>,
+) -> Entity {
+    let scratch = spawn_character(commands, character, campaign);
+    commands.entity(scratch).insert(Simulating { original });
+    if let Some(portrait) = portrait {
+        commands
+            .entity(scratch)
+            .insert(PortraitTexture(portrait.0.clone()));
+    }
+    commands.entity(original).remove::<ActiveCharacter>();
+    scratch
+}
+
+/// Throws the scratch copy away and reactivates the original character.
+pub fn discard(commands: &mut Commands, scratch: Entity, simulating: &Simulating) {
+    commands.entity(scratch).despawn();
+    commands.entity(simulating.original).insert(ActiveCharacter);
+}
+
+/// Keeps the scratch copy as the real character and drops the original.
+/// The caller is responsible for sending the committed character to the server.
+pub fn commit(commands: &mut Commands, scratch: Entity, simulating: &Simulating) {
+    commands.entity(simulating.original).despawn();
+    commands.entity(scratch).remove::<Simulating>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{CharacterStats, SkillPoints};
+
+    #[test]
+    fn test_discard_leaves_original_unchanged() {
+        let mut world = World::new();
+        let mut character = shared::Character::new("Respec".to_string());
+        character.skill_points = 5;
+        character.stats.strength.level = 3;
+
+        let campaign = shared::CampaignRules::default();
+        let original = spawn_character(&mut world.commands(), &character, &campaign);
+        world.flush();
+        let scratch = start(&mut world.commands(), original, &character, &campaign, None);
+        world.flush();
+        assert!(!world.entity(original).contains::<ActiveCharacter>());
+        assert!(world.entity(scratch).contains::<ActiveCharacter>());
+
+        world.get_mut::<SkillPoints>(scratch).unwrap().0 = 0;
+        world
+            .get_mut::<CharacterStats>(scratch)
+            .unwrap()
+            .strength
+            .level = 7;
+
+        let simulating = world.entity_mut(scratch).take::<Simulating>().unwrap();
+        discard(&mut world.commands(), scratch, &simulating);
+        world.flush();
+
+        assert!(world.get_entity(scratch).is_err());
+        assert!(world.entity(original).contains::<ActiveCharacter>());
+        assert_eq!(world.get::<SkillPoints>(original).unwrap().0, 5);
+        assert_eq!(
+            world
+                .get::<CharacterStats>(original)
+                .unwrap()
+                .strength
+                .level,
+            3
+        );
+    }
+}
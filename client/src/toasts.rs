@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+use ui_widgets::colors::palette;
+
+/// How long a toast stays on screen, in seconds.
+const TOAST_SECS: f64 = 4.0;
+/// Toasts beyond this many drop the oldest one.
+const MAX_TOASTS: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl ToastLevel {
+    fn color(self) -> egui::Color32 {
+        match self {
+            Self::Info => egui::Color32::from_rgb(0x70, 0xA8, 0xE0),
+            Self::Warning => egui::Color32::from_rgb(0xE0, 0xA0, 0x40),
+            Self::Error => egui::Color32::from_rgb(0xE0, 0x60, 0x60),
+        }
+    }
+}
+
+struct Toast {
+    level: ToastLevel,
+    message: String,
+    /// egui time at which the toast was first shown.
+    shown_at: Option<f64>,
+}
+
+/// Short-lived notifications stacked in the top-right corner. Any system can
+/// push one; they disappear on their own or when clicked.
+#[derive(Resource, Default)]
+pub struct Toasts {
+    toasts: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, level: ToastLevel, message: impl Into<String>) {
+        self.toasts.push_back(Toast {
+            level,
+            message: message.into(),
+            shown_at: None,
+        });
+        while self.toasts.len() > MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+    }
+
+    /// Starts the clock on toasts not shown yet and drops expired ones.
+    fn expire(&mut self, now: f64) {
+        for toast in self.toasts.iter_mut() {
+            toast.shown_at.get_or_insert(now);
+        }
+        self.toasts
+            .retain(|t| t.shown_at.is_some_and(|shown| now - shown < TOAST_SECS));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+}
+
+pub struct ToastsPlugin;
+
+impl Plugin for ToastsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Toasts>()
+            .add_systems(EguiPrimaryContextPass, render_toasts_system);
+    }
+}
+
+fn render_toasts_system(mut contexts: EguiContexts, mut toasts: ResMut<Toasts>) -> Result {
+    if toasts.is_empty() {
+        return Ok(());
+    }
+    render_toasts(contexts.ctx_mut()?, &mut toasts);
+    Ok(())
+}
+
+/// Draws the live toasts, newest on top; a click dismisses one.
+fn render_toasts(ctx: &egui::Context, toasts: &mut Toasts) {
+    let now = ctx.input(|i| i.time);
+    toasts.expire(now);
+    if toasts.is_empty() {
+        return;
+    }
+    ctx.request_repaint_after(std::time::Duration::from_millis(250));
+
+    let mut dismissed = None;
+    egui::Area::new(egui::Id::new("toasts"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::RIGHT_TOP, [-12.0, 12.0])
+        .show(ctx, |ui| {
+            ui.with_layout(egui::Layout::top_down(egui::Align::Max), |ui| {
+                for (i, toast) in toasts.toasts.iter().enumerate().rev() {
+                    let response = egui::Frame::new()
+                        .fill(palette().secondary)
+                        .corner_radius(6.0)
+                        .stroke(egui::Stroke::new(1.0, palette().stroke))
+                        .inner_margin(egui::Margin::symmetric(10, 6))
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                let (rect, _) = ui.allocate_exact_size(
+                                    egui::vec2(4.0, 14.0),
+                                    egui::Sense::hover(),
+                                );
+                                ui.painter().rect_filled(rect, 2.0, toast.level.color());
+                                ui.label(
+                                    egui::RichText::new(&toast.message)
+                                        .size(12.0)
+                                        .color(palette().text),
+                                );
+                            });
+                        })
+                        .response
+                        .interact(egui::Sense::click())
+                        .on_hover_cursor(egui::CursorIcon::PointingHand);
+                    if response.clicked() {
+                        dismissed = Some(i);
+                    }
+                    ui.add_space(4.0);
+                }
+            });
+        });
+    if let Some(i) = dismissed {
+        toasts.toasts.remove(i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_frame(ctx: &egui::Context, toasts: &mut Toasts, time: f64) {
+        let input = egui::RawInput {
+            time: Some(time),
+            ..Default::default()
+        };
+        let _ = ctx.run(input, |ctx| render_toasts(ctx, toasts));
+    }
+
+    #[test]
+    fn test_toast_is_shown_then_expires() {
+        let ctx = egui::Context::default();
+        let mut toasts = Toasts::default();
+        toasts.push(ToastLevel::Warning, "Lost connection to the server");
+
+        run_frame(&ctx, &mut toasts, 1.0);
+        assert!(!toasts.is_empty());
+        assert!(ctx.memory(|m| m.area_rect(egui::Id::new("toasts")).is_some()));
+
+        // Still up just before its time runs out, gone after
+        run_frame(&ctx, &mut toasts, 1.0 + TOAST_SECS - 0.1);
+        assert!(!toasts.is_empty());
+        run_frame(&ctx, &mut toasts, 1.0 + TOAST_SECS);
+        assert!(toasts.is_empty());
+    }
+}
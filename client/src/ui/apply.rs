@@ -1,34 +1,68 @@
 use bevy::prelude::*;
 
 use shared::character::OnLvlUp;
-use shared::{CharacteristicKind, Effect, EquipmentSlot, InventoryItem};
+use shared::{CharacteristicKind, Effect, EquipmentSlot, InventoryItem, LevelUpPools, Loadout};
 
 use crate::components::{
-    AbilityPoints, ActionPoints, ActiveCharacter, ActiveEffects, CharacterAbilityNames,
-    CharacterClass, CharacterEquipment, CharacterSkillList, CharacterStats, CharacterTraitNames,
-    CharacterWeaponNames, CharacteristicPoints, Experience, Hp, Inventory as InventoryComponent,
-    Level, Mana, SkillPoints, TraitPoints, Wallet,
+    AbilityPoints, AbilityRuntimes, ActionPoints, ActiveCharacter, ActiveEffects,
+    CharacterAbilityNames, CharacterClass, CharacterConditions, CharacterEquipment,
+    CharacterHotbar, CharacterId, CharacterLoadouts, CharacterName, CharacterRace,
+    CharacterSkillList, CharacterStats, CharacterTraitNames, CharacterWeaponNames,
+    CharacteristicPoints, DiceRng, Experience, Hp, Inventory as InventoryComponent, ItemCounts,
+    Level, Mana, RestsUsedToday, SkillPoints, TemporaryEffects, TraitPoints, Wallet,
 };
 use crate::events::{
-    CreateItem, ExperienceChanged, InventoryChanged, LearnAbility, LearnTrait, LevelUp,
-    ResourceChanged, UpgradeEvent, WalletChanged,
+    ActivateAbility, ConditionsChanged, CreateItem, DeleteRegistryEntry, ExperienceChanged,
+    HotbarChanged, IdentityChanged, InventoryChanged, ItemEntry, LearnAbility, LearnTrait, LevelUp,
+    LoadoutChanged, ResourceChanged, RollCheck, RollDamage, TempEffectsChanged, UpgradeEvent,
+    WalletChanged,
 };
+use crate::toasts::{ToastLevel, Toasts};
 
 use super::helpers::{check_trait_requirement, save_to_json_file};
 
 /// Applies resource change messages to the active character's ECS components.
 pub(super) fn apply_resource_changes(
-    mut query: Query<(&mut Hp, &mut Mana, &mut ActionPoints), With<ActiveCharacter>>,
+    mut query: Query<
+        (
+            &mut Hp,
+            &mut Mana,
+            &mut ActionPoints,
+            &mut RestsUsedToday,
+            &mut AbilityRuntimes,
+        ),
+        With<ActiveCharacter>,
+    >,
     mut reader: MessageReader<ResourceChanged>,
+    ruleset: Res<crate::network::ClientRuleset>,
 ) {
-    let Ok((mut hp, mut mana, mut ap)) = query.single_mut() else {
+    let Ok((mut hp, mut mana, mut ap, mut rests, mut abilities)) = query.single_mut() else {
         return;
     };
     for event in reader.read() {
         match event {
             ResourceChanged::Hp(v) => hp.current = (*v).min(hp.max),
+            ResourceChanged::HpDelta(delta) => {
+                (hp.current, hp.temp) = shared::HpDelta(*delta).apply(hp.current, hp.temp, hp.max);
+            }
+            ResourceChanged::TempHp(v) => hp.temp = hp.temp.max(*v),
             ResourceChanged::Mp(v) => mana.current = (*v).min(mana.max),
             ResourceChanged::Ap(v) => ap.current = (*v).min(ap.max),
+            ResourceChanged::Rest(shared::RestKind::Short) => {
+                hp.current = (hp.current + shared::short_rest_heal(hp.max)).min(hp.max);
+                ap.current = ap.max;
+                shared::clear_ability_cooldowns(&mut abilities);
+            }
+            ResourceChanged::Rest(shared::RestKind::Long) => {
+                if ruleset.campaign.long_rest_available(rests.0) {
+                    hp.current = hp.max;
+                    mana.current = mana.max;
+                    ap.current = ap.max;
+                    shared::reset_ability_uses(&mut abilities);
+                    shared::clear_ability_cooldowns(&mut abilities);
+                    rests.0 += 1;
+                }
+            }
         }
     }
 }
@@ -38,30 +72,28 @@ pub(super) fn apply_experience_changes(
     mut query: Query<(&mut Experience, &mut Level), With<ActiveCharacter>>,
     mut reader: MessageReader<ExperienceChanged>,
     mut level_up: MessageWriter<LevelUp>,
+    ruleset: Res<crate::network::ClientRuleset>,
 ) {
     let Ok((mut exp, mut level)) = query.single_mut() else {
         return;
     };
     for event in reader.read() {
-        exp.0 += event.0;
-        loop {
-            let needed = shared::xp_to_next_level(level.0);
-            if exp.0 < needed {
-                break;
-            }
-            exp.0 -= needed;
-            level.0 += 1;
+        let gained = ruleset.xp.gain(&mut level.0, &mut exp.0, event.0);
+        for _ in 0..gained {
             level_up.write(LevelUp);
         }
     }
 }
 
-/// Applies all OnLvlUp effects from active effects on level up.
+/// Applies all OnLvlUp effects from active effects on level up, plus the class
+/// skill-point formula and ability-point cadence from `ClassRules`.
 /// Also grants 1 trait point every 3 levels and 2 characteristic points every 5 levels.
 pub(super) fn apply_level_up(
     mut query: Query<
         (
             &Level,
+            &CharacterClass,
+            &CharacterStats,
             &ActiveEffects,
             &mut AbilityPoints,
             &mut SkillPoints,
@@ -71,9 +103,18 @@ pub(super) fn apply_level_up(
         With<ActiveCharacter>,
     >,
     mut reader: MessageReader<LevelUp>,
+    ruleset: Res<crate::network::ClientRuleset>,
 ) {
-    let Ok((level, effects, mut ability_pts, mut skill_pts, mut char_pts, mut trait_pts)) =
-        query.single_mut()
+    let Ok((
+        level,
+        class,
+        stats,
+        effects,
+        mut ability_pts,
+        mut skill_pts,
+        mut char_pts,
+        mut trait_pts,
+    )) = query.single_mut()
     else {
         return;
     };
@@ -81,35 +122,32 @@ pub(super) fn apply_level_up(
     if level_ups == 0 {
         return;
     }
-    for _ in 0..level_ups {
-        for effect in effects.iter() {
-            if let Effect::OnLvlUp(on_lvl_up) = effect {
-                match on_lvl_up {
-                    OnLvlUp::AddAbilityPoints(v) => {
-                        ability_pts.0 = (ability_pts.0 as i32 + v).max(0) as u32;
-                    }
-                    OnLvlUp::AddSkillPoints(v) => {
-                        skill_pts.0 = (skill_pts.0 as i32 + v).max(0) as u32;
-                    }
-                    OnLvlUp::AddCharacteristicPoints(v) => {
-                        char_pts.0 = (char_pts.0 as i32 + v).max(0) as u32;
-                    }
-                }
-            }
-        }
-    }
-    // Grant 1 trait point for each level divisible by 3 that was crossed.
-    let prev_level = level.0 - level_ups;
-    trait_pts.0 += level.0 / 3 - prev_level / 3;
-    // Grant 2 characteristic points for each level divisible by 5 that was crossed.
-    char_pts.0 += (level.0 / 5 - prev_level / 5) * 2;
+    let mut pools = LevelUpPools {
+        ability: ability_pts.0,
+        skill: skill_pts.0,
+        characteristic: char_pts.0,
+        traits: trait_pts.0,
+    };
+    ruleset.apply_level_ups(
+        &mut pools,
+        level.0 - level_ups,
+        level.0,
+        class.0,
+        effects,
+        |kind| effects.effective_level(stats, kind, &ruleset.stacking),
+    );
+    ability_pts.0 = pools.ability;
+    skill_pts.0 = pools.skill;
+    char_pts.0 = pools.characteristic;
+    trait_pts.0 = pools.traits;
 }
 
-/// Applies characteristic and skill upgrades from edit mode.
+/// Applies characteristic and skill upgrades and respecs from edit mode.
 pub(super) fn apply_upgrades(
     mut query: Query<
         (
             &CharacterClass,
+            &CharacterRace,
             &mut CharacterStats,
             &mut CharacteristicPoints,
             &mut SkillPoints,
@@ -120,8 +158,9 @@ pub(super) fn apply_upgrades(
     >,
     mut reader: MessageReader<UpgradeEvent>,
     skill_registry: Res<crate::network::ClientSkillRegistry>,
+    ruleset: Res<crate::network::ClientRuleset>,
 ) {
-    let Ok((class, mut stats, mut char_pts, mut skill_pts, mut skills, effects)) =
+    let Ok((class, race, mut stats, mut char_pts, mut skill_pts, mut skills, effects)) =
         query.single_mut()
     else {
         return;
@@ -138,7 +177,7 @@ pub(super) fn apply_upgrades(
         CharacteristicKind::Charisma,
     ];
 
-    let char_bonuses = effects.characteristic_bonuses();
+    let char_bonuses = effects.characteristic_bonuses(&ruleset.stacking);
 
     for event in reader.read() {
         match event {
@@ -179,30 +218,231 @@ pub(super) fn apply_upgrades(
                     skills.push(shared::CharacterSkill {
                         name: name.clone(),
                         level: 1,
+                        free_ranks: 0,
                     });
                     skill_pts.0 -= 1;
                 }
             }
+            UpgradeEvent::Respec => shared::respec_points(
+                &mut stats,
+                &mut char_pts.0,
+                &mut skills,
+                &mut skill_pts.0,
+                |kind| ruleset.races.stat_floor(race, kind),
+            ),
         }
     }
 }
 
 /// Learns an ability: adds it to the character's ability list and deducts one ability point.
+/// Abilities unlocked by the character's traits bypass the class and prerequisite checks.
 pub(super) fn apply_learn_ability(
-    mut query: Query<(&mut CharacterAbilityNames, &mut AbilityPoints), With<ActiveCharacter>>,
+    mut query: Query<
+        (
+            &CharacterClass,
+            &Level,
+            &CharacterTraitNames,
+            &mut CharacterAbilityNames,
+            &mut AbilityPoints,
+        ),
+        With<ActiveCharacter>,
+    >,
     mut reader: MessageReader<LearnAbility>,
+    ability_registry: Res<crate::network::ClientAbilityRegistry>,
+    trait_registry: Res<crate::network::ClientTraitRegistry>,
 ) {
-    let Ok((mut abilities, mut pts)) = query.single_mut() else {
+    let Ok((class, level, traits, mut abilities, mut pts)) = query.single_mut() else {
         return;
     };
+    let unlocked = trait_registry.unlocked_abilities(traits);
     for event in reader.read() {
-        if pts.0 > 0 && !abilities.contains(&event.0) {
+        if pts.0 > 0 && ability_registry.can_learn(class, &event.0, &abilities, level.0, &unlocked)
+        {
             abilities.push(event.0.clone());
             pts.0 -= 1;
         }
     }
 }
 
+/// Abilities the hotbar can hold.
+pub(super) const HOTBAR_SLOTS: usize = 8;
+
+/// Pins abilities of the character's class to the hotbar (no duplicates, at
+/// most `HOTBAR_SLOTS`) and unpins them.
+pub(super) fn apply_hotbar_changes(
+    mut query: Query<(&CharacterClass, &mut CharacterHotbar), With<ActiveCharacter>>,
+    mut reader: MessageReader<HotbarChanged>,
+    ability_registry: Res<crate::network::ClientAbilityRegistry>,
+) {
+    let Ok((class, mut hotbar)) = query.single_mut() else {
+        return;
+    };
+    for event in reader.read() {
+        match event {
+            HotbarChanged::Pin(name) => {
+                if hotbar.len() < HOTBAR_SLOTS
+                    && !hotbar.contains(name)
+                    && ability_registry.get(class, name).is_some()
+                {
+                    hotbar.push(name.clone());
+                }
+            }
+            HotbarChanged::Unpin(name) => hotbar.retain(|n| n != name),
+        }
+    }
+}
+
+/// Uses an ability: spends its MP and AP cost and one of its per-rest uses and
+/// starts its cooldown when the character has enough of both resources,
+/// otherwise does nothing. Abilities on cooldown or out of uses are refused
+/// with a toast.
+pub(super) fn apply_ability_activation(
+    mut query: Query<
+        (
+            &CharacterClass,
+            &mut Mana,
+            &mut ActionPoints,
+            &mut AbilityRuntimes,
+        ),
+        With<ActiveCharacter>,
+    >,
+    mut reader: MessageReader<ActivateAbility>,
+    ability_registry: Res<crate::network::ClientAbilityRegistry>,
+    mut toasts: ResMut<Toasts>,
+) {
+    let Ok((class, mut mana, mut ap, mut runtime)) = query.single_mut() else {
+        return;
+    };
+    for ActivateAbility(name) in reader.read() {
+        let Some(ability) = ability_registry.get(class, name) else {
+            continue;
+        };
+        let requirements = ability.requirements.as_ref();
+        let spent = runtime.get(name).copied().unwrap_or_default();
+        if let Some(reason) = spent.unavailable(requirements) {
+            toasts.push(ToastLevel::Warning, format!("{name}: {reason}"));
+            continue;
+        }
+        let mp_cost = requirements.and_then(|r| r.mp).unwrap_or(0);
+        let ap_cost = requirements.and_then(|r| r.action_points).unwrap_or(0);
+        if mana.current >= mp_cost && ap.current >= ap_cost {
+            shared::use_ability(&mut runtime, name, requirements);
+            mana.current -= mp_cost;
+            ap.current -= ap_cost;
+        }
+    }
+}
+
+/// Rolls d20 checks with the character's dice and shares the results.
+pub(super) fn apply_roll_checks(
+    mut query: Query<&mut DiceRng, With<ActiveCharacter>>,
+    mut reader: MessageReader<RollCheck>,
+    mut pending_messages: ResMut<crate::network::PendingClientMessages>,
+) {
+    let Ok(mut dice) = query.single_mut() else {
+        return;
+    };
+    for RollCheck { label, modifier } in reader.read() {
+        let check = shared::Check::roll(*modifier, &mut || dice.next_u32());
+        pending_messages
+            .0
+            .push(shared::ClientMessage::BroadcastRoll {
+                label: label.clone(),
+                total: check.total(),
+                detail: check.detail(),
+            });
+    }
+}
+
+/// Rolls the damage of equipped weapons with the character's dice, keeps the
+/// breakdown for the weapon tooltip and shares the result.
+pub(super) fn apply_damage_rolls(
+    mut query: Query<&mut DiceRng, With<ActiveCharacter>>,
+    mut reader: MessageReader<RollDamage>,
+    weapons: Res<crate::network::ClientWeaponRegistry>,
+    mut rolls: ResMut<super::params::DamageRolls>,
+    mut pending_messages: ResMut<crate::network::PendingClientMessages>,
+) {
+    let Ok(mut dice) = query.single_mut() else {
+        return;
+    };
+    for RollDamage { weapon } in reader.read() {
+        let Some(damage) = weapons.get(weapon).map(|w| &w.damage) else {
+            continue;
+        };
+        let Ok(expr) = shared::parse_dice(damage) else {
+            continue;
+        };
+        let roll = expr.roll(&mut || dice.next_u32());
+        let detail = format!("{damage}: {}", roll.detail());
+        rolls.0.insert(weapon.clone(), detail.clone());
+        pending_messages
+            .0
+            .push(shared::ClientMessage::BroadcastRoll {
+                label: format!("{weapon} damage"),
+                total: roll.total,
+                detail,
+            });
+    }
+}
+
+/// Adds and removes temporary effects and ends turns for them and for ability
+/// cooldowns. Effects that wear off are reported in a toast.
+pub(super) fn apply_temp_effects(
+    mut query: Query<(&mut TemporaryEffects, &mut AbilityRuntimes), With<ActiveCharacter>>,
+    mut reader: MessageReader<TempEffectsChanged>,
+    mut toasts: ResMut<Toasts>,
+) {
+    let Ok((mut temporary, mut abilities)) = query.single_mut() else {
+        return;
+    };
+    for event in reader.read() {
+        match event {
+            TempEffectsChanged::Add(effect) => temporary.push(effect.clone()),
+            TempEffectsChanged::Remove(index) => {
+                if *index < temporary.len() {
+                    temporary.remove(*index);
+                }
+            }
+            TempEffectsChanged::EndTurn => {
+                let expired: Vec<String> = temporary
+                    .iter()
+                    .filter(|t| t.remaining_turns <= 1)
+                    .map(|t| super::format_effect(&t.effect))
+                    .collect();
+                shared::tick_temp_effects(&mut temporary);
+                shared::tick_ability_cooldowns(&mut abilities);
+                if !expired.is_empty() {
+                    toasts.push(
+                        ToastLevel::Info,
+                        format!("Wore off: {}", expired.join(", ")),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Adds and removes status conditions. A condition is listed at most once.
+pub(super) fn apply_condition_changes(
+    mut query: Query<&mut CharacterConditions, With<ActiveCharacter>>,
+    mut reader: MessageReader<ConditionsChanged>,
+) {
+    let Ok(mut conditions) = query.single_mut() else {
+        return;
+    };
+    for event in reader.read() {
+        match event {
+            ConditionsChanged::Add(condition) => {
+                if !conditions.contains(condition) {
+                    conditions.push(*condition);
+                }
+            }
+            ConditionsChanged::Remove(condition) => conditions.retain(|c| c != condition),
+        }
+    }
+}
+
 /// Learns a trait: validates conditions, adds it to the character's trait list and deducts one trait point.
 /// OnLvlUp effects from the trait are applied immediately.
 pub(super) fn apply_learn_trait(
@@ -256,33 +496,103 @@ pub(super) fn apply_learn_trait(
     }
 }
 
+/// Renames the active character or changes its race or class. A class change
+/// drops the skills the new class doesn't have and refunds their points.
+pub(super) fn apply_identity_changes(
+    mut query: Query<
+        (
+            &mut CharacterName,
+            &mut CharacterRace,
+            &mut CharacterClass,
+            &mut CharacterSkillList,
+            &mut SkillPoints,
+        ),
+        With<ActiveCharacter>,
+    >,
+    mut reader: MessageReader<IdentityChanged>,
+    skill_registry: Res<crate::network::ClientSkillRegistry>,
+    mut toasts: ResMut<Toasts>,
+) {
+    let Ok((mut name, mut race, mut class, mut skills, mut points)) = query.single_mut() else {
+        return;
+    };
+    for event in reader.read() {
+        match event {
+            IdentityChanged::Name(new_name) => name.0 = new_name.clone(),
+            IdentityChanged::Race(new_race) => race.0 = *new_race,
+            IdentityChanged::Class(new_class) => {
+                if class.0 == *new_class {
+                    continue;
+                }
+                class.0 = *new_class;
+                let class_skills = skill_registry.get_class_skills(new_class);
+                let dropped = shared::drop_foreign_skills(&mut skills.0, &mut points.0, |skill| {
+                    class_skills.is_some_and(|s| s.contains_key(skill))
+                });
+                if !dropped.is_empty() {
+                    toasts.push(
+                        ToastLevel::Info,
+                        format!(
+                            "Refunded skills not available to {}: {}",
+                            new_class,
+                            dropped.join(", ")
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
 pub(super) fn apply_wallet_changes(
     mut query: Query<&mut Wallet, With<ActiveCharacter>>,
     mut reader: MessageReader<WalletChanged>,
+    ruleset: Res<crate::network::ClientRuleset>,
 ) {
     let Ok(mut wallet) = query.single_mut() else {
         return;
     };
     for event in reader.read() {
-        wallet.add(event.0);
+        match *event {
+            WalletChanged::Coins {
+                denomination,
+                count,
+            } => wallet.add_coins(&ruleset.campaign.currency, denomination, count),
+            WalletChanged::Base(delta) => wallet.add(delta),
+        }
     }
 }
 
 const MAX_EQUIPPED_WEAPONS: usize = 3;
 
+/// `UnequipGear` events removing several worn items at once. Each removal
+/// shifts the items after it in its slot, so indices go high-to-low within a
+/// slot to keep the remaining ones pointing at the same items.
+pub(super) fn bulk_unequip_events(mut picks: Vec<(EquipmentSlot, usize)>) -> Vec<InventoryChanged> {
+    picks.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+    picks.dedup();
+    picks
+        .into_iter()
+        .map(|(slot, index)| InventoryChanged::UnequipGear(slot, index))
+        .collect()
+}
+
 pub(super) fn apply_inventory_changes(
     mut query: Query<
         (
             &mut InventoryComponent,
+            &mut ItemCounts,
             &mut CharacterEquipment,
             &mut CharacterWeaponNames,
+            &CharacterId,
         ),
         With<ActiveCharacter>,
     >,
     mut reader: MessageReader<InventoryChanged>,
     equipment_registry: Res<crate::network::ClientEquipmentRegistry>,
+    mut trash: ResMut<super::trash::InventoryTrash>,
 ) {
-    let Ok((mut inventory, mut equipment, mut weapons)) = query.single_mut() else {
+    let Ok((mut inventory, mut counts, mut equipment, mut weapons, id)) = query.single_mut() else {
         return;
     };
     for event in reader.read() {
@@ -322,26 +632,21 @@ pub(super) fn apply_inventory_changes(
                 }
             }
             InventoryChanged::Remove(idx) => {
-                let idx = *idx;
-                if idx < inventory.len() {
-                    inventory.remove(idx);
+                if let Some((item, _)) = shared::take_items(&mut inventory, &mut counts, *idx, 1) {
+                    trash.push(id.0, item);
                 }
             }
-            InventoryChanged::UnequipGear(idx) => {
-                let idx = *idx;
-                let mut current = 0;
-                let mut target: Option<(EquipmentSlot, usize)> = None;
-                for (slot, names) in equipment.iter() {
-                    if idx < current + names.len() {
-                        target = Some((*slot, idx - current));
-                        break;
-                    }
-                    current += names.len();
-                }
-                if let Some((slot, inner_idx)) = target {
-                    let name = equipment.get_mut(&slot).unwrap().remove(inner_idx);
-                    if equipment.get(&slot).is_some_and(|v| v.is_empty()) {
-                        equipment.remove(&slot);
+            InventoryChanged::Sold(idx) => {
+                shared::take_items(&mut inventory, &mut counts, *idx, 1);
+            }
+            InventoryChanged::UnequipGear(slot, idx) => {
+                let Some(names) = equipment.get_mut(slot) else {
+                    continue;
+                };
+                if *idx < names.len() {
+                    let name = names.remove(*idx);
+                    if names.is_empty() {
+                        equipment.remove(slot);
                     }
                     inventory.push(InventoryItem::Equipment(name));
                 }
@@ -354,7 +659,58 @@ pub(super) fn apply_inventory_changes(
                 }
             }
             InventoryChanged::AddExisting(item) => {
-                inventory.push(item.clone());
+                shared::add_item(&mut inventory, &mut counts, item.clone(), 1);
+            }
+            InventoryChanged::MergeDuplicates => {
+                shared::merge_duplicates(&mut inventory);
+                shared::stack_items(&mut inventory, &mut counts);
+            }
+        }
+    }
+}
+
+/// Saves and equips named loadouts on the active character.
+pub(super) fn apply_loadout_changes(
+    mut query: Query<
+        (
+            &mut InventoryComponent,
+            &mut CharacterEquipment,
+            &mut CharacterWeaponNames,
+            &mut CharacterLoadouts,
+        ),
+        With<ActiveCharacter>,
+    >,
+    mut reader: MessageReader<LoadoutChanged>,
+    weapon_registry: Res<crate::network::ClientWeaponRegistry>,
+    equipment_registry: Res<crate::network::ClientEquipmentRegistry>,
+    mut toasts: ResMut<Toasts>,
+) {
+    let Ok((mut inventory, mut equipment, mut weapons, mut loadouts)) = query.single_mut() else {
+        return;
+    };
+    for event in reader.read() {
+        match event {
+            LoadoutChanged::Save(name) => {
+                loadouts.insert(name.clone(), Loadout::capture(&weapons, &equipment));
+            }
+            LoadoutChanged::Apply(name) => {
+                let Some(loadout) = loadouts.get(name) else {
+                    continue;
+                };
+                let warnings = loadout.apply(
+                    &mut weapons,
+                    &mut equipment,
+                    &mut inventory,
+                    &weapon_registry,
+                    &equipment_registry,
+                );
+                for warning in warnings {
+                    warn!("Loadout \"{}\": {}", name, warning);
+                    toasts.push(
+                        ToastLevel::Warning,
+                        format!("Loadout \"{name}\": {warning}"),
+                    );
+                }
             }
         }
     }
@@ -372,42 +728,323 @@ pub(super) fn apply_create_item(
         return;
     };
     for event in reader.read() {
-        match event {
-            CreateItem::Weapon(weapon) => {
+        let replace = event.replace;
+        match &event.entry {
+            ItemEntry::Weapon(weapon) => {
                 let item_name = weapon.name.clone();
                 weapon_registry
                     .weapons
                     .insert(item_name.clone(), weapon.clone());
-                inventory.push(InventoryItem::Weapon(item_name));
+                if !replace {
+                    inventory.push(InventoryItem::Weapon(item_name));
+                }
                 save_to_json_file(
                     "data/weapons.json",
                     weapon_registry.weapons.values().collect(),
                 );
                 pending_messages.push(shared::ClientMessage::CreateWeapon {
                     weapon: weapon.clone(),
+                    replace,
                 });
             }
-            CreateItem::Equipment(eq) => {
+            ItemEntry::Equipment(eq) => {
                 let item_name = eq.name.clone();
                 equipment_registry
                     .equipment
                     .insert(item_name.clone(), eq.clone());
-                inventory.push(InventoryItem::Equipment(item_name));
+                if !replace {
+                    inventory.push(InventoryItem::Equipment(item_name));
+                }
                 save_to_json_file(
                     "data/equipment.json",
                     equipment_registry.equipment.values().collect(),
                 );
                 pending_messages.push(shared::ClientMessage::CreateEquipment {
                     equipment: eq.clone(),
+                    replace,
                 });
             }
-            CreateItem::Item(item) => {
+            ItemEntry::Item(item) => {
                 let item_name = item.name.clone();
                 item_registry.items.insert(item_name.clone(), item.clone());
-                inventory.push(InventoryItem::Item(item_name));
+                if !replace {
+                    inventory.push(InventoryItem::Item(item_name));
+                }
                 save_to_json_file("data/items.json", item_registry.items.values().collect());
-                pending_messages.push(shared::ClientMessage::CreateItem { item: item.clone() });
+                pending_messages.push(shared::ClientMessage::CreateItem {
+                    item: item.clone(),
+                    replace,
+                });
             }
         }
     }
 }
+
+/// Asks the server to delete registry definitions. The local registries drop
+/// them once the server confirms.
+pub(super) fn apply_registry_deletions(
+    mut reader: MessageReader<DeleteRegistryEntry>,
+    mut pending_messages: ResMut<crate::network::PendingClientMessages>,
+) {
+    for DeleteRegistryEntry(entry) in reader.read() {
+        pending_messages.push(match entry.clone() {
+            InventoryItem::Weapon(name) => shared::ClientMessage::DeleteWeapon { name },
+            InventoryItem::Equipment(name) => shared::ClientMessage::DeleteEquipment { name },
+            InventoryItem::Item(name) => shared::ClientMessage::DeleteItem { name },
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::network::ClientEquipmentRegistry;
+    use crate::ui::helpers::sell_item;
+    use crate::ui::trash::InventoryTrash;
+
+    #[test]
+    fn test_selling_credits_resale_fraction() {
+        let items = shared::ItemRegistry::load_from_str(
+            r#"[{ "name": "Silver Goblet", "description": "", "value": 50 }]"#,
+        )
+        .unwrap();
+        let campaign = shared::CampaignRules::default();
+        let goblet = InventoryItem::Item("Silver Goblet".to_string());
+        let value = goblet
+            .value(&items, &Default::default(), &Default::default())
+            .expect("goblet has a value");
+        let price = campaign.resale_price(value);
+
+        let mut app = App::new();
+        app.add_message::<InventoryChanged>()
+            .add_message::<WalletChanged>()
+            .insert_resource(ClientEquipmentRegistry(Default::default()))
+            .init_resource::<crate::network::ClientRuleset>()
+            .init_resource::<InventoryTrash>()
+            .add_systems(
+                Update,
+                (
+                    move |mut inventory: MessageWriter<InventoryChanged>,
+                          mut wallet: MessageWriter<WalletChanged>| {
+                        sell_item(&mut inventory, &mut wallet, 0, price);
+                    },
+                    apply_inventory_changes,
+                    apply_wallet_changes,
+                )
+                    .chain(),
+            );
+        let entity = app
+            .world_mut()
+            .spawn((
+                ActiveCharacter,
+                CharacterId(uuid::Uuid::new_v4()),
+                InventoryComponent(vec![goblet]),
+                ItemCounts(BTreeMap::new()),
+                CharacterEquipment(BTreeMap::new()),
+                CharacterWeaponNames(Vec::new()),
+                Wallet(shared::Wallet::from_total(10)),
+            ))
+            .id();
+        app.update();
+
+        let world = app.world();
+        assert!(world.get::<InventoryComponent>(entity).unwrap().is_empty());
+        // Default resale is half of the 50 copper value
+        assert_eq!(world.get::<Wallet>(entity).unwrap().total(), 35);
+    }
+
+    #[test]
+    fn test_level_up_grants_class_skill_points() {
+        let classes = shared::ClassRulesRegistry::load_from_str(
+            r#"{ "Bard": { "skill_points": { "base": 2, "characteristic": "Intellect", "divisor": 4 } } }"#,
+        )
+        .unwrap();
+        let ruleset = shared::Ruleset {
+            classes,
+            ..Default::default()
+        };
+        let mut app = App::new();
+        app.add_message::<LevelUp>()
+            .insert_resource(crate::network::ClientRuleset(ruleset))
+            .add_systems(Update, apply_level_up);
+        let mut stats = shared::Characteristics::default();
+        stats.intellect.level = 9;
+        let entity = app
+            .world_mut()
+            .spawn((
+                ActiveCharacter,
+                Level(4),
+                CharacterClass(shared::Class::Bard),
+                CharacterStats(stats),
+                ActiveEffects(vec![Effect::OnLvlUp(OnLvlUp::AddSkillPoints(1))]),
+                AbilityPoints(0),
+                SkillPoints(1),
+                CharacteristicPoints(0),
+                TraitPoints(0),
+            ))
+            .id();
+
+        app.world_mut()
+            .write_message_batch([LevelUp, LevelUp, LevelUp]);
+        app.update();
+
+        // Three levels of 2 + 9 / 4 class points plus the effect's 1
+        let world = app.world();
+        assert_eq!(world.get::<SkillPoints>(entity).unwrap().0, 1 + 3 * (4 + 1));
+        assert_eq!(world.get::<TraitPoints>(entity).unwrap().0, 1);
+    }
+
+    #[test]
+    fn test_bulk_unequip_removes_picked_rings() {
+        let mut app = App::new();
+        app.add_message::<InventoryChanged>()
+            .insert_resource(ClientEquipmentRegistry(Default::default()))
+            .init_resource::<InventoryTrash>()
+            .add_systems(Update, apply_inventory_changes);
+        let rings = ["Gold Ring", "Silver Ring", "Iron Ring"].map(String::from);
+        let entity = app
+            .world_mut()
+            .spawn((
+                ActiveCharacter,
+                CharacterId(uuid::Uuid::new_v4()),
+                InventoryComponent(Vec::new()),
+                ItemCounts(BTreeMap::new()),
+                CharacterEquipment(BTreeMap::from([
+                    (EquipmentSlot::Head, vec!["Iron Helm".to_string()]),
+                    (EquipmentSlot::Ring, rings.to_vec()),
+                ])),
+                CharacterWeaponNames(Vec::new()),
+            ))
+            .id();
+
+        // Picked low-to-high, as a selection would list them
+        let picks = vec![
+            (EquipmentSlot::Ring, 0),
+            (EquipmentSlot::Ring, 2),
+            (EquipmentSlot::Head, 0),
+        ];
+        app.world_mut()
+            .write_message_batch(bulk_unequip_events(picks));
+        app.update();
+
+        let world = app.world();
+        let equipment = world.get::<CharacterEquipment>(entity).unwrap();
+        assert_eq!(
+            equipment.0,
+            BTreeMap::from([(EquipmentSlot::Ring, vec!["Silver Ring".to_string()])])
+        );
+        let inventory = world.get::<InventoryComponent>(entity).unwrap();
+        for name in ["Gold Ring", "Iron Ring", "Iron Helm"] {
+            assert!(inventory.contains(&InventoryItem::Equipment(name.to_string())));
+        }
+        assert_eq!(inventory.len(), 3);
+    }
+
+    #[test]
+    fn test_restoring_removed_item_returns_it() {
+        let mut app = App::new();
+        app.add_message::<InventoryChanged>()
+            .insert_resource(ClientEquipmentRegistry(Default::default()))
+            .init_resource::<InventoryTrash>()
+            .add_systems(Update, apply_inventory_changes);
+        let id = uuid::Uuid::new_v4();
+        let items = vec![
+            InventoryItem::Item("Rope".to_string()),
+            InventoryItem::Weapon("Dagger".to_string()),
+        ];
+        let entity = app
+            .world_mut()
+            .spawn((
+                ActiveCharacter,
+                CharacterId(id),
+                InventoryComponent(items.clone()),
+                ItemCounts(BTreeMap::new()),
+                CharacterEquipment(BTreeMap::new()),
+                CharacterWeaponNames(Vec::new()),
+            ))
+            .id();
+
+        app.world_mut().write_message(InventoryChanged::Remove(0));
+        app.update();
+        assert_eq!(
+            app.world().get::<InventoryComponent>(entity).unwrap().len(),
+            1
+        );
+
+        let restored = app
+            .world_mut()
+            .resource_mut::<InventoryTrash>()
+            .take_last(id)
+            .expect("removed item is in the trash");
+        app.world_mut()
+            .write_message(InventoryChanged::AddExisting(restored));
+        app.update();
+
+        let inventory = &app.world().get::<InventoryComponent>(entity).unwrap().0;
+        assert_eq!(inventory.len(), items.len());
+        assert!(items.iter().all(|item| inventory.contains(item)));
+        assert!(app
+            .world_mut()
+            .resource_mut::<InventoryTrash>()
+            .take_last(id)
+            .is_none());
+    }
+
+    #[test]
+    fn test_hotbar_activation_spends_ability_cost() {
+        let abilities = shared::AbilityRegistry::load_from_str(
+            r#"{ "Bard": { "Acquire": { "Ballad": {
+                "description": "", "type": "Peaceful",
+                "requirements": { "mp": 3, "action_points": 1 } } } } }"#,
+        )
+        .unwrap();
+        let mut app = App::new();
+        app.add_message::<HotbarChanged>()
+            .add_message::<ActivateAbility>()
+            .insert_resource(crate::network::ClientAbilityRegistry(abilities))
+            .init_resource::<Toasts>()
+            .add_systems(
+                Update,
+                (apply_hotbar_changes, apply_ability_activation).chain(),
+            );
+        let entity = app
+            .world_mut()
+            .spawn((
+                ActiveCharacter,
+                CharacterClass(shared::Class::Bard),
+                CharacterHotbar(Vec::new()),
+                AbilityRuntimes(BTreeMap::new()),
+                Mana { current: 5, max: 5 },
+                ActionPoints { current: 2, max: 2 },
+            ))
+            .id();
+
+        app.world_mut()
+            .write_message(HotbarChanged::Pin("Ballad".to_string()));
+        app.world_mut()
+            .write_message(HotbarChanged::Pin("Unknown".to_string()));
+        app.update();
+        let pinned = app
+            .world()
+            .get::<CharacterHotbar>(entity)
+            .unwrap()
+            .0
+            .clone();
+        assert_eq!(pinned, vec!["Ballad".to_string()]);
+
+        app.world_mut()
+            .write_message(ActivateAbility(pinned[0].clone()));
+        app.update();
+        let world = app.world();
+        assert_eq!(world.get::<Mana>(entity).unwrap().current, 2);
+        assert_eq!(world.get::<ActionPoints>(entity).unwrap().current, 1);
+
+        // Not enough mana for a second use: nothing is spent
+        app.world_mut()
+            .write_message(ActivateAbility(pinned[0].clone()));
+        app.update();
+        assert_eq!(app.world().get::<Mana>(entity).unwrap().current, 2);
+    }
+}
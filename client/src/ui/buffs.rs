@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use shared::TempEffect;
+use ui_widgets::colors::palette;
+
+use super::format_effect;
+use super::params::BuffsOpen;
+use crate::components::{ActiveCharacter, CharacterName, TemporaryEffects};
+use crate::create_item::EffectDraft;
+use crate::events::TempEffectsChanged;
+
+/// Form for a new temporary effect, kept in egui temp data between frames.
+#[derive(Clone)]
+struct BuffDraft {
+    effect: EffectDraft,
+    turns: String,
+}
+
+impl Default for BuffDraft {
+    fn default() -> Self {
+        Self {
+            effect: EffectDraft::default(),
+            turns: "1".to_string(),
+        }
+    }
+}
+
+/// Lists the active character's temporary effects with their remaining
+/// turns, adds new ones and ends the turn.
+pub(super) fn render_buffs(
+    mut contexts: EguiContexts,
+    mut open: ResMut<BuffsOpen>,
+    character: Query<(&CharacterName, &TemporaryEffects), With<ActiveCharacter>>,
+    skills: Res<crate::network::ClientSkillRegistry>,
+    mut changes: MessageWriter<TempEffectsChanged>,
+) -> Result {
+    if !open.0 {
+        return Ok(());
+    }
+    let Ok((name, temporary)) = character.single() else {
+        return Ok(());
+    };
+    let ctx = contexts.ctx_mut()?;
+    let skill_names: Vec<String> = skills
+        .classes
+        .values()
+        .flat_map(|skills| skills.keys().cloned())
+        .collect();
+
+    let draft_id = egui::Id::new("buff_draft");
+    let mut draft: BuffDraft = ctx.data(|d| d.get_temp(draft_id)).unwrap_or_default();
+    egui::Window::new(format!("{} — Buffs", name.0))
+        .id(egui::Id::new("buffs_window"))
+        .open(&mut open.0)
+        .collapsible(false)
+        .resizable(false)
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(12)),
+        )
+        .show(ctx, |ui| {
+            if temporary.is_empty() {
+                ui.label(egui::RichText::new("No temporary effects").color(palette().text));
+            }
+            for (index, temp) in temporary.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} ({} turn{})",
+                            format_effect(&temp.effect),
+                            temp.remaining_turns,
+                            if temp.remaining_turns == 1 { "" } else { "s" }
+                        ))
+                        .color(palette().text),
+                    );
+                    if ui.small_button("x").clicked() {
+                        changes.write(TempEffectsChanged::Remove(index));
+                    }
+                });
+            }
+
+            ui.add_space(4.0);
+            ui.separator();
+            ui.horizontal(|ui| {
+                draft.effect.show(ui, "buff", &skill_names);
+                ui.add(
+                    egui::TextEdit::singleline(&mut draft.turns)
+                        .desired_width(30.0)
+                        .hint_text("turns"),
+                );
+                let turns = draft.turns.parse::<u32>().ok().filter(|&t| t > 0);
+                let effect = draft.effect.build(&skill_names);
+                let built = effect.zip(turns);
+                if ui
+                    .add_enabled(built.is_some(), egui::Button::new("Add"))
+                    .clicked()
+                {
+                    if let Some((effect, remaining_turns)) = built {
+                        changes.write(TempEffectsChanged::Add(TempEffect {
+                            effect,
+                            remaining_turns,
+                        }));
+                        draft.effect.clear_values();
+                    }
+                }
+            });
+            draft.effect.sanitize();
+            draft.turns.retain(|c| c.is_ascii_digit());
+
+            ui.add_space(8.0);
+            if ui
+                .add_enabled(!temporary.is_empty(), egui::Button::new("End turn"))
+                .clicked()
+            {
+                changes.write(TempEffectsChanged::EndTurn);
+            }
+        });
+    ctx.data_mut(|d| d.insert_temp(draft_id, draft));
+
+    Ok(())
+}
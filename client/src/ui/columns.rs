@@ -0,0 +1,163 @@
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+use strum::{Display, EnumIter};
+
+/// Gap between neighbouring columns, as a fraction of the sheet width.
+pub(super) const COL_GAP: f32 = 0.01;
+
+/// The three columns of the character sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, EnumIter)]
+pub(super) enum SheetColumn {
+    /// Portrait, resources, hotbar, stats, weapons and the save/back buttons.
+    Character,
+    /// Characteristics, skills, abilities and traits.
+    Skills,
+    /// Equipment, wallet and inventory.
+    Inventory,
+}
+
+impl SheetColumn {
+    /// Width in the default arrangement, as a fraction of the sheet width.
+    fn base_width(self) -> f32 {
+        match self {
+            Self::Character => 0.24,
+            Self::Skills => 0.46,
+            Self::Inventory => 0.24,
+        }
+    }
+
+    /// The character column holds the save and back buttons, so it stays visible.
+    fn collapsible(self) -> bool {
+        self != Self::Character
+    }
+}
+
+/// Order and visibility of the sheet columns. Kept for the whole session,
+/// so the arrangement survives switching characters.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub(super) struct ColumnLayout {
+    pub order: [SheetColumn; 3],
+    pub collapsed: BTreeSet<SheetColumn>,
+}
+
+impl Default for ColumnLayout {
+    fn default() -> Self {
+        Self {
+            order: [
+                SheetColumn::Character,
+                SheetColumn::Skills,
+                SheetColumn::Inventory,
+            ],
+            collapsed: BTreeSet::new(),
+        }
+    }
+}
+
+impl ColumnLayout {
+    /// Visible columns left to right with their widths (fractions of the
+    /// sheet width). Collapsed columns give their width and gap to the rest,
+    /// shared in proportion to their default widths.
+    pub fn columns(&self) -> Vec<(SheetColumn, f32)> {
+        let visible: Vec<SheetColumn> = self
+            .order
+            .iter()
+            .copied()
+            .filter(|c| !self.collapsed.contains(c))
+            .collect();
+        let available: f32 = self.order.iter().map(|c| c.base_width()).sum::<f32>()
+            + COL_GAP * (self.order.len() - visible.len()) as f32;
+        let visible_width: f32 = visible.iter().map(|c| c.base_width()).sum();
+        visible
+            .into_iter()
+            .map(|c| (c, c.base_width() / visible_width * available))
+            .collect()
+    }
+
+    /// Swaps `column` with its neighbour; `offset` is -1 for left, 1 for right.
+    fn shift(&mut self, column: SheetColumn, offset: isize) {
+        let Some(i) = self.order.iter().position(|&c| c == column) else {
+            return;
+        };
+        let j = i as isize + offset;
+        if (0..self.order.len() as isize).contains(&j) {
+            self.order.swap(i, j as usize);
+        }
+    }
+}
+
+/// Menu to reorder the columns and collapse the collapsible ones.
+pub(super) fn render_layout_menu(ui: &mut egui::Ui, layout: &mut ColumnLayout) {
+    ui.menu_button(" ⇆ ", |ui| {
+        for (i, column) in layout.order.into_iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(i > 0, egui::Button::new("◀").small())
+                    .clicked()
+                {
+                    layout.shift(column, -1);
+                }
+                if ui
+                    .add_enabled(i + 1 < layout.order.len(), egui::Button::new("▶").small())
+                    .clicked()
+                {
+                    layout.shift(column, 1);
+                }
+                let mut shown = !layout.collapsed.contains(&column);
+                let toggle = ui.add_enabled(
+                    column.collapsible(),
+                    egui::Checkbox::new(&mut shown, column.to_string()),
+                );
+                if toggle.changed() {
+                    if shown {
+                        layout.collapsed.remove(&column);
+                    } else {
+                        layout.collapsed.insert(column);
+                    }
+                }
+            });
+        }
+        ui.separator();
+        if ui.button("Reset").clicked() {
+            *layout = ColumnLayout::default();
+        }
+    })
+    .response
+    .on_hover_text("Arrange columns");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_layout_reorders_and_widens_columns() {
+        let default_columns: Vec<SheetColumn> = ColumnLayout::default()
+            .columns()
+            .into_iter()
+            .map(|(c, _)| c)
+            .collect();
+        assert_eq!(
+            default_columns,
+            vec![
+                SheetColumn::Character,
+                SheetColumn::Skills,
+                SheetColumn::Inventory
+            ]
+        );
+
+        let mut layout = ColumnLayout::default();
+        layout.shift(SheetColumn::Inventory, -1);
+        layout.shift(SheetColumn::Inventory, -1);
+        layout.collapsed.insert(SheetColumn::Skills);
+        let columns = layout.columns();
+        let order: Vec<SheetColumn> = columns.iter().map(|(c, _)| *c).collect();
+        assert_eq!(order, vec![SheetColumn::Inventory, SheetColumn::Character]);
+
+        // The collapsed column's width and gap go to the remaining two
+        let total: f32 = columns.iter().map(|(_, w)| w).sum();
+        assert!((total - (0.94 + COL_GAP)).abs() < 1e-5);
+        assert!((columns[0].1 - columns[1].1).abs() < 1e-5);
+    }
+}
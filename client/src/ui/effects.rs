@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use bevy_egui::egui;
+use shared::{Effect, EffectSource, Protection, Resist, Stacked, StackingRules};
+use ui_widgets::colors::palette;
+
+use super::helpers::{effect_color, format_effect};
+use crate::components::ActiveEffects;
+
+/// Contents of the "Effects" window: active effects grouped by source, plus totals.
+pub(super) struct EffectsBreakdown {
+    groups: Vec<(EffectSource, Vec<Effect>)>,
+    armor: i32,
+    initiative: i32,
+    resists: BTreeMap<Resist, Stacked>,
+    protections: BTreeMap<Protection, Stacked>,
+}
+
+impl EffectsBreakdown {
+    /// `initiative` is the full initiative (campaign formula plus effect bonuses).
+    pub fn new(
+        groups: Vec<(EffectSource, Vec<Effect>)>,
+        initiative: i32,
+        stacking: &StackingRules,
+    ) -> Self {
+        let effects = ActiveEffects(groups.iter().flat_map(|(_, e)| e.clone()).collect());
+        Self {
+            armor: effects.armor(),
+            initiative,
+            resists: effects.get_resists(stacking),
+            protections: effects.get_protections(stacking),
+            groups,
+        }
+    }
+
+    /// Number of effect rows listed in the window.
+    pub fn effect_count(&self) -> usize {
+        self.groups.iter().map(|(_, effects)| effects.len()).sum()
+    }
+}
+
+fn total_row(ui: &mut egui::Ui, label: &str, total: Stacked) {
+    ui.label(egui::RichText::new(label).color(palette().text));
+    let value = total.value;
+    match total.uncapped {
+        Some(uncapped) => {
+            ui.label(egui::RichText::new(format!("{value:+} (capped)")).color(palette().text))
+                .on_hover_text(format!("{uncapped:+} before the stacking cap"));
+        }
+        None => {
+            ui.label(egui::RichText::new(format!("{value:+}")).color(palette().text));
+        }
+    }
+    ui.end_row();
+}
+
+/// Window listing every active effect by source, recomputed each frame.
+pub(super) fn render_effects_window(
+    ctx: &egui::Context,
+    open: &mut bool,
+    name: &str,
+    breakdown: &EffectsBreakdown,
+) {
+    egui::Window::new(format!("{name} — Effects"))
+        .id(egui::Id::new("effects_window"))
+        .open(open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([360.0, 480.0])
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(12)),
+        )
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    if breakdown.effect_count() == 0 {
+                        ui.label(egui::RichText::new("No active effects").color(palette().text));
+                    }
+                    for (source, effects) in &breakdown.groups {
+                        ui.label(
+                            egui::RichText::new(source.to_string())
+                                .color(palette().text)
+                                .strong(),
+                        );
+                        for effect in effects {
+                            ui.label(
+                                egui::RichText::new(format!("  {}", format_effect(effect)))
+                                    .color(effect_color(effect)),
+                            );
+                        }
+                        ui.add_space(4.0);
+                    }
+
+                    ui.separator();
+                    ui.label(egui::RichText::new("Totals").color(palette().text).strong());
+                    egui::Grid::new("effects_totals")
+                        .num_columns(2)
+                        .spacing([24.0, 2.0])
+                        .show(ui, |ui| {
+                            total_row(ui, "Armor", Stacked::new(breakdown.armor, None));
+                            total_row(ui, "Initiative", Stacked::new(breakdown.initiative, None));
+                            for (resist, value) in &breakdown.resists {
+                                total_row(ui, &format!("{resist} Resist"), *value);
+                            }
+                            for (protection, value) in &breakdown.protections {
+                                total_row(ui, &format!("{protection} Protection"), *value);
+                            }
+                        });
+                });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use shared::GetEffects;
+
+    use super::*;
+
+    #[test]
+    fn test_breakdown_lists_gear_effects() {
+        let equipment = shared::EquipmentRegistry::load_from_str(
+            r#"[
+                { "name": "Iron Helm", "description": "", "slot": "Head",
+                  "effects": [{ "Armor": 2 }, { "Protection": ["Melee", 1] }] },
+                { "name": "Lucky Ring", "description": "", "slot": "Ring",
+                  "effects": [{ "Initiative": 1 }] }
+            ]"#,
+        )
+        .unwrap();
+        let worn = BTreeMap::from([
+            (shared::EquipmentSlot::Head, vec!["Iron Helm".to_string()]),
+            (shared::EquipmentSlot::Ring, vec!["Lucky Ring".to_string()]),
+        ]);
+        let race = shared::Race::default();
+        let groups = shared::collect_attributed_effects(
+            race,
+            &[],
+            &[],
+            &worn,
+            &[],
+            &Default::default(),
+            &Default::default(),
+            &equipment,
+            &[],
+        );
+        let innate = race.get_effects().len() + race.size().get_effects().len();
+
+        let breakdown = EffectsBreakdown::new(groups, 0, &StackingRules::default());
+        assert_eq!(breakdown.effect_count(), innate + 3);
+        assert!(breakdown.groups.iter().any(|(source, effects)| *source
+            == EffectSource::Equipment("Iron Helm".to_string())
+            && effects.len() == 2));
+    }
+}
@@ -0,0 +1,266 @@
+use bevy_egui::egui;
+use shared::character::OnLvlUp;
+use shared::{AbilityType, CharacteristicKind, Effect, Protection, Resist};
+use strum::IntoEnumIterator;
+use ui_widgets::colors::palette;
+
+/// A titled group of glossary terms.
+pub(super) struct GlossarySection {
+    pub title: &'static str,
+    pub entries: Vec<(String, &'static str)>,
+}
+
+/// Every term the help overlay explains. Descriptions come from exhaustive
+/// matches, so a new variant does not compile until it is described.
+pub(super) fn glossary() -> Vec<GlossarySection> {
+    vec![
+        GlossarySection {
+            title: "Effects",
+            entries: effect_samples()
+                .iter()
+                .map(|effect| {
+                    let (term, description) = effect_entry(effect);
+                    (term.to_string(), description)
+                })
+                .collect(),
+        },
+        GlossarySection {
+            title: "Resists",
+            entries: Resist::iter()
+                .map(|r| (r.to_string(), resist_description(r)))
+                .collect(),
+        },
+        GlossarySection {
+            title: "Protections",
+            entries: Protection::iter()
+                .map(|p| (p.to_string(), protection_description(p)))
+                .collect(),
+        },
+        GlossarySection {
+            title: "Characteristics",
+            entries: CharacteristicKind::iter()
+                .map(|k| (format!("{k:?} ({k})"), characteristic_description(k)))
+                .collect(),
+        },
+        GlossarySection {
+            title: "Ability types",
+            entries: AbilityType::iter()
+                .map(|t| (t.to_string(), ability_type_description(t)))
+                .collect(),
+        },
+    ]
+}
+
+/// One effect of each kind, in the order the glossary lists them.
+fn effect_samples() -> Vec<Effect> {
+    vec![
+        Effect::Armor(0),
+        Effect::Resist(Resist::Fire, 0),
+        Effect::Protection(Protection::Melee, 0),
+        Effect::Initiative(0),
+        Effect::Characteristic(CharacteristicKind::Strength, 0),
+        Effect::Skill(String::new(), 0),
+        Effect::FreeSkillRank {
+            skill: String::new(),
+            ranks: 0,
+        },
+        Effect::ActionPoints(0),
+        Effect::MaxHp(0),
+        Effect::MaxMana(0),
+        Effect::Mana {
+            dependent: CharacteristicKind::Magic,
+            increase_per_point: 0,
+        },
+        Effect::OnLvlUp(OnLvlUp::AddSkillPoints(0)),
+    ]
+}
+
+fn effect_entry(effect: &Effect) -> (&'static str, &'static str) {
+    match effect {
+        Effect::Armor(_) => (
+            "Armor",
+            "Reduces the damage of every hit, whatever its source.",
+        ),
+        Effect::Resist(..) => (
+            "Resist",
+            "Reduces damage of one element, such as fire or poison.",
+        ),
+        Effect::Protection(..) => (
+            "Protection",
+            "Makes it harder to be hit by one kind of attack at all.",
+        ),
+        Effect::Initiative(_) => ("Initiative", "Acts earlier in the turn order."),
+        Effect::Characteristic(..) => (
+            "Characteristic",
+            "Raises or lowers a characteristic while the source is held.",
+        ),
+        Effect::Skill(..) => (
+            "Skill bonus",
+            "Adds to checks with a skill without changing its ranks.",
+        ),
+        Effect::FreeSkillRank { .. } => (
+            "Free skill rank",
+            "Real skill ranks that cost no skill points while the source is held.",
+        ),
+        Effect::ActionPoints(_) => ("Action points", "Changes the action points per turn."),
+        Effect::MaxHp(_) => ("Max HP", "Changes maximum HP; it never drops below 1."),
+        Effect::MaxMana(_) => ("Max mana", "Changes maximum mana; it never drops below 1."),
+        Effect::Mana { .. } => (
+            "Mana per point",
+            "Grants extra mana for every level of a characteristic.",
+        ),
+        Effect::OnLvlUp(_) => (
+            "On level up",
+            "Grants extra skill, ability or characteristic points each level.",
+        ),
+    }
+}
+
+fn resist_description(resist: Resist) -> &'static str {
+    match resist {
+        Resist::Fire => "Flames, burns and heat.",
+        Resist::Ice => "Frost and cold.",
+        Resist::Lightning => "Shocks and storms.",
+        Resist::Poison => "Venoms, toxins and disease.",
+        Resist::Spirit => "Holy power and harm to the soul.",
+        Resist::Dark => "Shadow and necrotic magic.",
+    }
+}
+
+fn protection_description(protection: Protection) -> &'static str {
+    match protection {
+        Protection::Melee => "Against weapon attacks at arm's reach.",
+        Protection::Range => "Against arrows, bolts and thrown weapons.",
+        Protection::Magic => "Against hostile spells.",
+        Protection::Body => "Against effects on the body: stuns, knockdowns, sickness.",
+        Protection::Mind => "Against fear, charms and illusions.",
+    }
+}
+
+fn characteristic_description(kind: CharacteristicKind) -> &'static str {
+    match kind {
+        CharacteristicKind::Strength => "Physical power and might.",
+        CharacteristicKind::Dexterity => "Agility, balance and precision.",
+        CharacteristicKind::Endurance => "Toughness: sets maximum HP.",
+        CharacteristicKind::Perception => "Awareness: noticing details and danger.",
+        CharacteristicKind::Magic => "Talent for spellcasting.",
+        CharacteristicKind::Willpower => "Inner strength: sets maximum mana.",
+        CharacteristicKind::Intellect => "Learning: more skill points.",
+        CharacteristicKind::Charisma => "Presence and persuasion.",
+    }
+}
+
+fn ability_type_description(ability_type: AbilityType) -> &'static str {
+    match ability_type {
+        AbilityType::Stance => "Stays active until dropped, changing how you fight.",
+        AbilityType::Attack => "Strikes a target and deals damage.",
+        AbilityType::Debuff => "Weakens a target for a while.",
+        AbilityType::Peaceful => "Used outside combat or without hostile intent.",
+        AbilityType::Passive => "Always on; never needs to be activated.",
+        AbilityType::Touch => "Needs contact with the target.",
+    }
+}
+
+/// Help overlay listing the glossary over a dimmed backdrop. Clicking the
+/// backdrop or "Close" hides it.
+pub(super) fn render_glossary_overlay(ctx: &egui::Context, open: &mut bool) {
+    let screen = ctx.content_rect();
+
+    egui::Area::new(egui::Id::new("glossary_backdrop"))
+        .order(egui::Order::Middle)
+        .fixed_pos(screen.min)
+        .show(ctx, |ui| {
+            let (rect, resp) = ui.allocate_exact_size(screen.size(), egui::Sense::click());
+            ui.painter()
+                .rect_filled(rect, 0.0, egui::Color32::from_black_alpha(120));
+            if resp.clicked() {
+                *open = false;
+            }
+        });
+
+    let dialog_size = egui::vec2(screen.width() * 0.4, screen.height() * 0.7);
+    egui::Area::new(egui::Id::new("glossary_dialog"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(screen.center() - dialog_size / 2.0)
+        .show(ctx, |ui| {
+            egui::Frame::new()
+                .fill(palette().main)
+                .corner_radius(16.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(16))
+                .show(ui, |ui| {
+                    ui.set_width(dialog_size.x - 32.0);
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new("Glossary")
+                                .size(18.0)
+                                .strong()
+                                .color(palette().text),
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button("Close").clicked() {
+                                *open = false;
+                            }
+                        });
+                    });
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .max_height(dialog_size.y - 80.0)
+                        .show(ui, |ui| {
+                            for section in glossary() {
+                                egui::Frame::new()
+                                    .fill(palette().secondary)
+                                    .corner_radius(8.0)
+                                    .inner_margin(egui::Margin::same(8))
+                                    .show(ui, |ui| {
+                                        ui.set_width(ui.available_width());
+                                        ui.label(
+                                            egui::RichText::new(section.title)
+                                                .strong()
+                                                .color(palette().text),
+                                        );
+                                        for (term, description) in &section.entries {
+                                            ui.label(
+                                                egui::RichText::new(format!(
+                                                    "{term}: {description}"
+                                                ))
+                                                .color(palette().text),
+                                            );
+                                        }
+                                    });
+                                ui.add_space(6.0);
+                            }
+                        });
+                });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glossary_covers_every_ability_type() {
+        let sections = glossary();
+        let ability_types = sections
+            .iter()
+            .find(|s| s.title == "Ability types")
+            .expect("glossary has an ability type section");
+        for ability_type in AbilityType::iter() {
+            let term = ability_type.to_string();
+            assert!(
+                ability_types
+                    .entries
+                    .iter()
+                    .any(|(t, d)| *t == term && !d.is_empty()),
+                "no glossary entry for {term}"
+            );
+        }
+
+        // One entry per effect kind, none repeated
+        let effects = &sections[0].entries;
+        let mut terms: Vec<&String> = effects.iter().map(|(t, _)| t).collect();
+        terms.dedup();
+        assert_eq!(terms.len(), effect_samples().len());
+    }
+}
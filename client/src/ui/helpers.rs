@@ -1,16 +1,35 @@
 use bevy::prelude::*;
+use bevy_egui::egui;
 use shared::character::OnLvlUp;
-use shared::Effect;
+use shared::{Effect, Weapon};
+
+use crate::events::{InventoryChanged, WalletChanged};
+
+/// Converts a stored RGB accent color into an egui color.
+pub fn accent_color(rgb: Option<[u8; 3]>) -> Option<egui::Color32> {
+    rgb.map(|[r, g, b]| egui::Color32::from_rgb(r, g, b))
+}
+
+/// Weapon damage with its element, e.g. "1d8 Fire".
+pub(super) fn weapon_damage(weapon: &Weapon) -> String {
+    match weapon.damage_type {
+        Some(damage_type) => format!("{} {damage_type}", weapon.damage),
+        None => weapon.damage.clone(),
+    }
+}
 
 pub fn format_effect(effect: &Effect) -> String {
     match effect {
         Effect::Resist(r, v) => format!("{r} Resist {v:+}"),
-        Effect::Skill(name, v) => format!("{name} {v:+}"),
+        Effect::Skill(name, v) => format!("{name} {v:+} (check bonus)"),
+        Effect::FreeSkillRank { skill, ranks } => format!("{skill} +{ranks} free rank(s)"),
         Effect::Protection(p, v) => format!("{p} Protection {v:+}"),
         Effect::Initiative(v) => format!("Initiative {v:+}"),
         Effect::Characteristic(c, v) => format!("{c} {v:+}"),
         Effect::ActionPoints(v) => format!("Action Points {v:+}"),
         Effect::Armor(v) => format!("Armor {v:+}"),
+        Effect::MaxHp(v) => format!("Max HP {v:+}"),
+        Effect::MaxMana(v) => format!("Max Mana {v:+}"),
         Effect::Mana {
             dependent,
             increase_per_point,
@@ -27,6 +46,36 @@ pub fn format_effect(effect: &Effect) -> String {
     }
 }
 
+/// Green for bonuses, red for penalties.
+pub(super) fn effect_color(effect: &Effect) -> egui::Color32 {
+    let magnitude = match effect {
+        Effect::Resist(_, v)
+        | Effect::Skill(_, v)
+        | Effect::Protection(_, v)
+        | Effect::Initiative(v)
+        | Effect::Characteristic(_, v)
+        | Effect::ActionPoints(v)
+        | Effect::Armor(v)
+        | Effect::MaxHp(v)
+        | Effect::MaxMana(v)
+        | Effect::Mana {
+            increase_per_point: v,
+            ..
+        } => *v,
+        Effect::OnLvlUp(
+            OnLvlUp::AddSkillPoints(v)
+            | OnLvlUp::AddAbilityPoints(v)
+            | OnLvlUp::AddCharacteristicPoints(v),
+        ) => *v,
+        Effect::FreeSkillRank { .. } => 1,
+    };
+    if magnitude < 0 {
+        egui::Color32::from_rgb(0xE0, 0x60, 0x60)
+    } else {
+        egui::Color32::from_rgb(0x70, 0xC0, 0x70)
+    }
+}
+
 pub(super) fn check_trait_requirement(
     stats: &shared::Characteristics,
     condition: Option<&shared::TraitCondition>,
@@ -40,6 +89,17 @@ pub(super) fn check_trait_requirement(
     }
 }
 
+/// Sells the inventory item at `index`: removes it and credits `price` base currency units.
+pub(super) fn sell_item(
+    inventory: &mut MessageWriter<InventoryChanged>,
+    wallet: &mut MessageWriter<WalletChanged>,
+    index: usize,
+    price: u64,
+) {
+    inventory.write(InventoryChanged::Sold(index));
+    wallet.write(WalletChanged::Base(price as i64));
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub(super) fn save_to_json_file<T: serde::Serialize>(path: &str, items: Vec<&T>) {
     match serde_json::to_string_pretty(&items) {
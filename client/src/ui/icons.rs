@@ -1,6 +1,5 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
-use ui_widgets::styles::UiStyle;
 
 #[derive(Resource)]
 pub(super) struct UiIcons {
@@ -37,7 +36,6 @@ fn load_png_texture(ctx: &egui::Context, name: &str, png_bytes: &[u8]) -> egui::
 
 pub(super) fn init_icons(mut contexts: EguiContexts, mut commands: Commands) -> Result {
     let ctx = contexts.ctx_mut()?;
-    UiStyle::apply_global_style(ctx);
     commands.insert_resource(UiIcons {
         avatar_border_1: load_png_texture(
             ctx,
@@ -0,0 +1,100 @@
+use bevy_egui::egui;
+use shared::InventoryItem;
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+/// Order of the inventory grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display, EnumIter)]
+pub(super) enum InventorySort {
+    /// The order items were added in.
+    #[default]
+    Added,
+    Name,
+    /// Weapons, then equipment, then items, each by name.
+    Type,
+}
+
+/// Filter text and sort order of the inventory grid, kept in egui temp data.
+#[derive(Debug, Clone, Default)]
+pub(super) struct InventoryView {
+    filter: String,
+    sort: InventorySort,
+}
+
+impl InventoryView {
+    fn id() -> egui::Id {
+        egui::Id::new("inventory_view")
+    }
+
+    pub(super) fn load(ctx: &egui::Context) -> Self {
+        ctx.data(|d| d.get_temp(Self::id())).unwrap_or_default()
+    }
+
+    pub(super) fn store(self, ctx: &egui::Context) {
+        ctx.data_mut(|d| d.insert_temp(Self::id(), self));
+    }
+
+    /// Indices into `inventory` of the items to show, in display order.
+    /// Cell actions index this list and must be mapped back through it.
+    pub(super) fn order(&self, inventory: &[InventoryItem]) -> Vec<usize> {
+        let filter = self.filter.trim().to_lowercase();
+        let mut order: Vec<usize> = (0..inventory.len())
+            .filter(|&i| inventory[i].name().to_lowercase().contains(&filter))
+            .collect();
+        match self.sort {
+            InventorySort::Added => {}
+            InventorySort::Name => {
+                order.sort_by_cached_key(|&i| inventory[i].name().to_lowercase());
+            }
+            InventorySort::Type => order.sort_by(|&a, &b| inventory[a].cmp(&inventory[b])),
+        }
+        order
+    }
+
+    /// Filter field and sort dropdown shown above the grid.
+    pub(super) fn show_toolbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter)
+                    .hint_text("Filter")
+                    .desired_width(ui.available_width() * 0.6),
+            );
+            egui::ComboBox::from_id_salt("inventory_sort")
+                .selected_text(format!("Sort: {}", self.sort))
+                .show_ui(ui, |ui| {
+                    for sort in InventorySort::iter() {
+                        ui.selectable_value(&mut self.sort, sort, sort.to_string());
+                    }
+                });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_keeps_original_indices() {
+        let inventory = vec![
+            InventoryItem::Item("rope".to_string()),
+            InventoryItem::Weapon("Sword".to_string()),
+            InventoryItem::Equipment("Helm".to_string()),
+            InventoryItem::Item("Arrow".to_string()),
+            InventoryItem::Weapon("Shortbow".to_string()),
+        ];
+        let mut view = InventoryView::default();
+        assert_eq!(view.order(&inventory), vec![0, 1, 2, 3, 4]);
+
+        view.sort = InventorySort::Name;
+        assert_eq!(view.order(&inventory), vec![3, 2, 0, 4, 1]);
+
+        view.sort = InventorySort::Type;
+        assert_eq!(view.order(&inventory), vec![4, 1, 2, 3, 0]);
+
+        view.filter = " S ".to_string();
+        assert_eq!(view.order(&inventory), vec![4, 1]);
+        view.filter = "o".to_string();
+        view.sort = InventorySort::Added;
+        assert_eq!(view.order(&inventory), vec![0, 1, 3, 4]);
+    }
+}
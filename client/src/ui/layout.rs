@@ -1,39 +1,46 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
-use ui_widgets::colors::MAIN_COLOR;
+use shared::{CharacteristicKind, GetEffects};
+use strum::IntoEnumIterator;
+use ui_widgets::colors::{palette, HP_COLOR};
 use ui_widgets::composites::{
-    Abilities, AbilityEntry, AddItemMenu, AddItemSelection, Characteristics, EquippedGear,
-    GridAction, IdentityBar, Inventory, Points, Portrait, SkillEntry, Skills, Stats, StatusBar,
-    StatusBarResponse, TraitEntry, Traits, Wallet as WalletWidget, WalletResponse, Weapon,
-    WeaponSlot,
+    Abilities, AbilityEntry, AddItemMenu, AddItemSelection, Characteristics, ConditionChip,
+    Conditions, ConditionsAction, EquippedGear, GearAction, GridAction, Hotbar, HotbarAction,
+    HotbarEntry, IdentityBar, Inventory, Points, Portrait, SkillEntry, Skills, Stats, StatusBar,
+    StatusBarResponse, TraitEntry, Traits, ValueEdit, Wallet as WalletWidget, Weapon, WeaponSlot,
 };
-use ui_widgets::molecules::{CellAction, InventoryTooltip};
+use ui_widgets::molecules::{CellAction, InventoryTooltip, PointsBadge};
 
 use crate::components::{
-    AbilityPoints, ActionPoints, ActiveCharacter, ActiveEffects, CharacterAbilityNames,
-    CharacterClass, CharacterEquipment, CharacterId, CharacterName, CharacterRace,
+    AbilityPoints, AbilityRuntimes, AccentColor, ActionPoints, ActiveCharacter, ActiveEffects,
+    CharacterAbilityNames, CharacterClass, CharacterConditions, CharacterEquipment,
+    CharacterHotbar, CharacterId, CharacterLoadouts, CharacterName, CharacterNotes, CharacterRace,
     CharacterSkillList, CharacterStats, CharacterTraitNames, CharacterWeaponNames,
-    CharacteristicPoints, Experience, Hp, Inventory as InventoryComponent, Level, Mana,
-    PortraitTexture, SkillPoints, TraitPoints, Wallet,
+    CharacteristicPoints, DiceRng, Experience, Hp, Inventory as InventoryComponent, ItemCounts,
+    Level, Mana, PortraitTexture, RestsUsedToday, SkillPoints, TemporaryEffects, TraitPoints,
+    Wallet,
 };
 use crate::events::{
-    ExperienceChanged, InventoryChanged, ResourceChanged, UpgradeEvent, WalletChanged,
+    ActivateAbility, ConditionsChanged, DeleteRegistryEntry, ExperienceChanged, HotbarChanged,
+    IdentityChanged, InventoryChanged, LoadoutChanged, ResourceChanged, RollCheck, RollDamage,
+    UpgradeEvent, WalletChanged,
 };
+use crate::simulation::{Simulating, SimulationAction};
 
-use super::helpers::format_effect;
+use super::apply::{bulk_unequip_events, HOTBAR_SLOTS};
+use super::columns::{render_layout_menu, ColumnLayout, SheetColumn, COL_GAP};
+use super::helpers::{accent_color, format_effect, sell_item, weapon_damage};
 use super::icons::UiIcons;
-use super::params::{Registries, UiEvents, UiModals};
+use super::inventory_view::InventoryView;
+use super::params::{Registries, Session, UiEvents, UiModals};
 
 const MARGIN: f32 = 0.02;
-const COL_GAP: f32 = 0.01;
-const COL1_WIDTH: f32 = 0.24;
-const COL2_WIDTH: f32 = 0.46;
-const COL3_WIDTH: f32 = 0.24;
 
 #[derive(bevy::ecs::query::QueryData)]
 pub(super) struct CharacterQueryData {
     pub id: &'static CharacterId,
     pub name: &'static CharacterName,
+    pub accent: &'static AccentColor,
     pub race: &'static CharacterRace,
     pub class: &'static CharacterClass,
     pub level: &'static Level,
@@ -52,9 +59,19 @@ pub(super) struct CharacterQueryData {
     pub wallet: &'static Wallet,
     pub weapon_names: &'static CharacterWeaponNames,
     pub equipment: &'static CharacterEquipment,
+    pub loadouts: &'static CharacterLoadouts,
+    pub hotbar: &'static CharacterHotbar,
+    pub notes: &'static CharacterNotes,
+    pub ability_runtime: &'static AbilityRuntimes,
+    pub rests: &'static RestsUsedToday,
+    pub dice: &'static DiceRng,
     pub inventory: &'static InventoryComponent,
+    pub item_counts: &'static ItemCounts,
     pub effects: &'static ActiveEffects,
+    pub temp_effects: &'static TemporaryEffects,
+    pub conditions: &'static CharacterConditions,
     pub portrait: Option<&'static PortraitTexture>,
+    pub simulating: Option<&'static Simulating>,
 }
 
 pub(super) fn render_ui(
@@ -69,6 +86,8 @@ pub(super) fn render_ui(
     mut next_state: ResMut<NextState<crate::state::AppScreen>>,
     portrait_picker: Res<crate::portrait::PortraitPickerResult>,
     mut crop_editor: ResMut<crate::portrait::CropEditorSlot>,
+    session: Session,
+    mut column_layout: ResMut<ColumnLayout>,
 ) -> Result {
     let Some(icons) = icons else {
         return Ok(());
@@ -82,9 +101,11 @@ pub(super) fn render_ui(
 
     let mut save_clicked = false;
     let mut back_clicked = false;
+    let mut simulation_action = None;
+    let mut skill_roll = None;
 
     egui::CentralPanel::default()
-        .frame(egui::Frame::NONE.fill(MAIN_COLOR))
+        .frame(egui::Frame::NONE.fill(palette().main))
         .show(ctx, |ui| {
             let total_w = ui.available_width();
             let total_h = ui.available_height();
@@ -98,66 +119,139 @@ pub(super) fn render_ui(
             ui.spacing_mut().item_spacing = egui::vec2(0.0, 0.0);
             ui.add_space(top_margin);
 
+            // Mirroring another connection: the sheet is view-only.
+            if session.following.is_some() {
+                ui.disable();
+            }
+
             ui.horizontal(|ui| {
                 ui.add_space(margin);
-                let left_resp = render_left_column(
-                    ui,
-                    total_w * COL1_WIDTH,
-                    col_h,
-                    &icons,
-                    &character,
-                    &registries,
-                    &mut ui_events,
-                    &mut modals,
-                    &portrait_picker,
-                    &mut crop_editor,
-                );
-                save_clicked = left_resp.save;
-                back_clicked = left_resp.back;
-                if let Some(png_bytes) = left_resp.upload_portrait {
-                    if let Some(texture) =
-                        crate::portrait::png_to_texture(ctx, "character_portrait", &png_bytes)
-                    {
-                        commands
-                            .entity(active_entity)
-                            .insert(PortraitTexture(texture));
+                for (i, (column, fraction)) in column_layout.columns().into_iter().enumerate() {
+                    if i > 0 {
+                        ui.add_space(gap);
+                    }
+                    let width = total_w * fraction;
+                    match column {
+                        SheetColumn::Character => {
+                            let left_resp = render_left_column(
+                                ui,
+                                width,
+                                col_h,
+                                &icons,
+                                &character,
+                                &registries,
+                                &mut ui_events,
+                                &mut modals,
+                                &portrait_picker,
+                                &mut crop_editor,
+                                session.admin.0,
+                            );
+                            save_clicked = left_resp.save;
+                            back_clicked = left_resp.back;
+                            if left_resp.simulate {
+                                simulation_action = Some(SimulationAction::Start);
+                            }
+                            if left_resp.export {
+                                pending_messages
+                                    .0
+                                    .push(shared::ClientMessage::ExportCharacter {
+                                        id: character.id.0,
+                                        version: None,
+                                    });
+                            }
+                            if let Some(accent) = left_resp.accent {
+                                commands.entity(active_entity).insert(AccentColor(accent));
+                            }
+                            if let Some(png_bytes) = left_resp.upload_portrait {
+                                if let Some(texture) = crate::portrait::png_to_texture(
+                                    ctx,
+                                    "character_portrait",
+                                    &png_bytes,
+                                ) {
+                                    commands
+                                        .entity(active_entity)
+                                        .insert(PortraitTexture(texture));
+                                }
+                                pending_messages
+                                    .0
+                                    .push(shared::ClientMessage::UploadPortrait {
+                                        id: character.id.0,
+                                        png_data: png_bytes,
+                                    });
+                            }
+                        }
+                        SheetColumn::Skills => {
+                            skill_roll = render_center_column(
+                                ui,
+                                width,
+                                col_h,
+                                &icons,
+                                &character,
+                                &registries,
+                                &mut ui_events,
+                                modals.edit_mode.0,
+                            );
+                        }
+                        SheetColumn::Inventory => {
+                            let edit_item = render_right_column(
+                                ui,
+                                width,
+                                col_h,
+                                &icons,
+                                &character,
+                                &registries,
+                                &mut ui_events,
+                                &mut modals,
+                                session.admin.0,
+                            );
+                            if edit_item {
+                                modals.create_item.0 = true;
+                            }
+                        }
                     }
-                    pending_messages
-                        .0
-                        .push(shared::ClientMessage::UploadPortrait {
-                            id: character.id.0,
-                            png_data: png_bytes,
-                        });
                 }
-                ui.add_space(gap);
-                render_center_column(
-                    ui,
-                    total_w * COL2_WIDTH,
-                    col_h,
-                    &icons,
-                    &character,
-                    &registries,
-                    &mut ui_events,
-                    modals.edit_mode.0,
-                );
-                ui.add_space(gap);
-                render_right_column(
-                    ui,
-                    total_w * COL3_WIDTH,
-                    col_h,
-                    &icons,
-                    &character,
-                    &registries,
-                    &mut ui_events,
-                );
             });
         });
 
+    if character.simulating.is_some() {
+        simulation_action = super::overlays::render_simulation_banner(ctx).or(simulation_action);
+        save_clicked = false;
+    }
+
+    match (simulation_action, character.simulating) {
+        (Some(SimulationAction::Start), None) => {
+            let ch = build_character_from_components(&character);
+            crate::simulation::start(
+                &mut commands,
+                active_entity,
+                &ch,
+                &registries.ruleset.campaign,
+                character.portrait,
+            );
+        }
+        (Some(SimulationAction::Commit), Some(simulating)) => {
+            crate::simulation::commit(&mut commands, active_entity, simulating);
+            save_clicked = true;
+        }
+        (Some(SimulationAction::Discard), Some(simulating)) => {
+            crate::simulation::discard(&mut commands, active_entity, simulating);
+        }
+        _ => {}
+    }
+
     if save_clicked {
         let ch = build_character_from_components(&character);
+        commands.insert_resource(crate::conflict::SavedCharacter(Some(ch.clone())));
         pending_messages
             .0
-            .push(shared::ClientMessage::UpdateCharacter { character: ch });
+            .push(shared::ClientMessage::UpdateCharacter {
+                base_version: session.base_version.of(ch.id),
+                character: Box::new(ch),
+            });
+    }
+
+    if let Some((label, modifier)) = skill_roll {
+        ui_events.roll.write(RollCheck { label, modifier });
     }
 
     if back_clicked {
@@ -166,6 +260,7 @@ pub(super) fn render_ui(
 
     // Poll picker & render crop popup when image is ready.
     crate::portrait::poll_and_render_crop_popup(ctx, &mut crop_editor, &portrait_picker);
+    crate::portrait::render_portrait_gallery(ctx, &mut crop_editor, &mut pending_messages);
 
     // "Learn Ability" overlay
     if modals.learn_ability.0 {
@@ -213,28 +308,110 @@ pub(super) fn render_ui(
             .values()
             .flat_map(|skills| skills.keys().cloned())
             .collect();
-        let existing_item_names = registries.items.0.items.keys().cloned().collect();
-        let existing_equipment_names = registries.equipment.0.equipment.keys().cloned().collect();
-        let existing_weapon_names = registries.weapons.0.weapons.keys().cloned().collect();
         crate::create_item::render_create_item_popup(
             ctx,
             &mut modals.create_item,
             &mut ui_events.create_item,
             &format_effect,
             &skill_names,
-            &existing_item_names,
-            &existing_equipment_names,
-            &existing_weapon_names,
+            &registries.items.0,
+            &registries.equipment.0,
+            &registries.weapons.0,
+            session.admin.0,
+        );
+    }
+
+    // "Effects" breakdown window
+    if modals.effects.0 {
+        let mut groups = shared::collect_attributed_effects(
+            **character.race,
+            character.trait_names,
+            character.weapon_names,
+            character.equipment,
+            character.conditions,
+            &registries.traits,
+            &registries.weapons,
+            &registries.equipment,
+            &registries.ruleset.campaign.effect_modifiers,
+        );
+        let temporary: Vec<_> = shared::active_temp_effects(character.temp_effects).collect();
+        if !temporary.is_empty() {
+            groups.push((shared::EffectSource::Temporary, temporary));
+        }
+        let initiative = registries.ruleset.campaign.initiative.initiative(
+            |kind| character.stats.get_level(kind),
+            character.effects.initiative_bonus(),
+        );
+        let breakdown =
+            super::effects::EffectsBreakdown::new(groups, initiative, &registries.ruleset.stacking);
+        super::effects::render_effects_window(
+            ctx,
+            &mut modals.effects.0,
+            &character.name.0,
+            &breakdown,
         );
     }
 
+    // Column arrangement menu, "?" help button and the glossary it opens
+    egui::Area::new(egui::Id::new("glossary_button"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -8.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                render_layout_menu(ui, &mut column_layout);
+                if ui
+                    .button(egui::RichText::new(" ? ").strong())
+                    .on_hover_text("Glossary")
+                    .clicked()
+                {
+                    modals.glossary.0 = !modals.glossary.0;
+                }
+            });
+        });
+    if modals.glossary.0 {
+        super::glossary::render_glossary_overlay(ctx, &mut modals.glossary.0);
+    }
+
+    // "Inspect JSON" debugging window
+    if modals.inspect_json.0 && session.admin.0 {
+        let working = build_character_from_components(&character);
+        super::overlays::render_inspect_json_window(ctx, &mut modals.inspect_json.0, &working);
+    }
+
+    // "Respec" confirmation
+    if modals.respec.0 && super::overlays::render_respec_confirm(ctx, &mut modals.respec.0) {
+        ui_events.upgrade.write(UpgradeEvent::Respec);
+    }
+
+    // Inventory item removal confirmation
+    if let Some((index, name)) = modals.remove_item.0.clone() {
+        let mut open = true;
+        if super::overlays::render_remove_item_confirm(ctx, &name, &mut open) {
+            // The inventory may have changed while the dialog was open
+            let same_item = character
+                .inventory
+                .get(index)
+                .is_some_and(|item| item.name() == name);
+            if same_item {
+                ui_events.inventory.write(InventoryChanged::Remove(index));
+            }
+        }
+        if !open {
+            modals.remove_item.0 = None;
+        }
+    }
+
     Ok(())
 }
 
 struct LeftColumnResponse {
     save: bool,
     back: bool,
+    simulate: bool,
+    export: bool,
     upload_portrait: Option<Vec<u8>>,
+    /// Accent color picked on the portrait; `Some(None)` resets it.
+    accent: Option<Option<[u8; 3]>>,
 }
 
 fn render_left_column(
@@ -248,9 +425,13 @@ fn render_left_column(
     modals: &mut UiModals,
     portrait_picker: &crate::portrait::PortraitPickerResult,
     crop_editor: &mut crate::portrait::CropEditorSlot,
+    admin: bool,
 ) -> LeftColumnResponse {
     let gap = height * 0.03 / 4.0;
-    let initiative = character.stats.perception.level as i32 + character.effects.initiative_bonus();
+    let initiative = registries.ruleset.campaign.initiative.initiative(
+        |kind| character.stats.get_level(kind),
+        character.effects.initiative_bonus(),
+    );
 
     let mut upload_portrait: Option<Vec<u8>> = None;
 
@@ -270,14 +451,14 @@ fn render_left_column(
             upload_portrait = Some(bytes);
         }
 
-        let (save_clicked, back_clicked);
+        let (save_clicked, back_clicked, simulate_clicked, export_clicked, accent_picked);
         {
             let add_item_menu = build_add_item_menu(
                 &registries.weapons,
                 &registries.equipment,
                 &registries.items,
             );
-            let xp_next = shared::xp_to_next_level(character.level.0);
+            let xp_next = registries.ruleset.xp.to_next_level(character.level.0);
             let xp_fraction = character.exp.0 as f32 / xp_next as f32;
             let avatar_texture = character
                 .portrait
@@ -302,15 +483,47 @@ fn render_left_column(
             .trait_points(character.trait_pts.0)
             .add_item_menu(add_item_menu)
             .avatar_size(avatar_size)
+            .inspect_json(admin)
+            .simulating(character.simulating.is_some())
+            .accent(accent_color(character.accent.0))
+            .loadouts(character.loadouts.keys().cloned().collect())
+            .screenshot(modals.screenshot.available())
+            .respec(admin)
+            .confirm_destructive(modals.settings.confirm_destructive)
             .show(&mut portrait_ui);
+
+            // Unspent points badge in the free top-right corner of the portrait area
+            let badge_radius = (portrait_rect.width() * 0.06).clamp(9.0, 16.0);
+            PointsBadge::new(
+                character.char_pts.0,
+                character.skill_pts.0,
+                character.ability_pts.0,
+                character.trait_pts.0,
+            )
+            .show(
+                &mut portrait_ui,
+                portrait_rect.right_top() + egui::vec2(-badge_radius - 4.0, badge_radius + 4.0),
+                badge_radius,
+            );
             save_clicked = portrait_resp.save;
             back_clicked = portrait_resp.back;
+            simulate_clicked = portrait_resp.simulate;
+            export_clicked = portrait_resp.export;
+            accent_picked = portrait_resp
+                .accent
+                .map(|color| color.map(|c| [c.r(), c.g(), c.b()]));
             if let Some(exp) = portrait_resp.add_exp {
                 ui_events.experience.write(ExperienceChanged(exp));
             }
             if portrait_resp.toggle_edit {
                 modals.edit_mode.0 = !modals.edit_mode.0;
             }
+            if portrait_resp.toggle_theme {
+                modals.settings.theme = modals.settings.theme.toggled();
+            }
+            if portrait_resp.toggle_confirm_destructive {
+                modals.settings.confirm_destructive = !modals.settings.confirm_destructive;
+            }
             if portrait_resp.open_learn_ability {
                 modals.learn_ability.0 = true;
             }
@@ -323,6 +536,36 @@ fn render_left_column(
             if portrait_resp.upload_portrait {
                 crate::portrait::spawn_portrait_picker(portrait_picker);
             }
+            if let Some(name) = portrait_resp.save_loadout {
+                ui_events.loadout.write(LoadoutChanged::Save(name));
+            }
+            if let Some(name) = portrait_resp.apply_loadout {
+                ui_events.loadout.write(LoadoutChanged::Apply(name));
+            }
+            if portrait_resp.open_gallery {
+                crop_editor.gallery.show();
+            }
+            if portrait_resp.inspect_json {
+                modals.inspect_json.0 = true;
+            }
+            if portrait_resp.open_effects {
+                modals.effects.0 = true;
+            }
+            if portrait_resp.open_buffs {
+                modals.buffs.0 = true;
+            }
+            if portrait_resp.open_notes {
+                modals.notes.0 = true;
+            }
+            if portrait_resp.respec && admin {
+                modals.respec.0 = true;
+            }
+            if portrait_resp.screenshot {
+                modals.screenshot.requested = true;
+            }
+            if portrait_resp.auto_allocate {
+                auto_allocate_points(character, registries, &mut ui_events.upgrade);
+            }
             if let Some(selection) = portrait_resp.add_item {
                 let inv_item = match selection {
                     AddItemSelection::Item(name) => shared::InventoryItem::Item(name),
@@ -335,14 +578,46 @@ fn render_left_column(
             }
         }
         ui.add_space(gap);
-        ui.add_sized(
-            [width, height * 0.11],
-            IdentityBar::new(
-                &character.name.0,
-                character.race.to_string(),
-                character.class.to_string(),
-            ),
-        );
+        let mut identity_bar = IdentityBar::new(
+            &character.name.0,
+            character.race.to_string(),
+            character.class.to_string(),
+        )
+        .accent(accent_color(character.accent.0))
+        .power_score(registries.ruleset.power.score(
+            character.level.0,
+            character.stats,
+            character.skills,
+            &character.effects.0,
+        ));
+        let races: Vec<shared::Race> = shared::Race::iter().collect();
+        let classes: Vec<shared::Class> = shared::Class::iter().collect();
+        if modals.edit_mode.0 {
+            identity_bar = identity_bar.editable(
+                races.iter().map(ToString::to_string).collect(),
+                races
+                    .iter()
+                    .position(|r| *r == character.race.0)
+                    .unwrap_or_default(),
+                classes.iter().map(ToString::to_string).collect(),
+                classes
+                    .iter()
+                    .position(|c| *c == character.class.0)
+                    .unwrap_or_default(),
+            );
+        }
+        let identity = ui
+            .allocate_ui(egui::vec2(width, height * 0.11), |ui| identity_bar.show(ui))
+            .inner;
+        if let Some(name) = identity.name {
+            ui_events.identity.write(IdentityChanged::Name(name));
+        }
+        if let Some(race) = identity.race.and_then(|i| races.get(i)) {
+            ui_events.identity.write(IdentityChanged::Race(*race));
+        }
+        if let Some(class) = identity.class.and_then(|i| classes.get(i)) {
+            ui_events.identity.write(IdentityChanged::Class(*class));
+        }
         ui.add_space(gap);
 
         send_status_bar_events(
@@ -351,15 +626,60 @@ fn render_left_column(
             height * 0.16,
             character,
             initiative,
+            &registries.ruleset.campaign,
             &mut ui_events.resource,
         );
         ui.add_space(gap);
 
+        render_conditions(
+            ui,
+            width,
+            height * 0.035,
+            character,
+            &mut ui_events.conditions,
+        );
+        ui.add_space(gap);
+
+        let hotbar_entries: Vec<HotbarEntry> = character
+            .hotbar
+            .iter()
+            .filter_map(|name| {
+                let ability = registries.abilities.get(character.class, name)?;
+                let requirements = ability.requirements.as_ref();
+                Some(HotbarEntry {
+                    name: name.clone(),
+                    mp_cost: requirements.and_then(|r| r.mp),
+                    ap_cost: requirements.and_then(|r| r.action_points),
+                })
+            })
+            .collect();
+        let (hotbar_rect, _) =
+            ui.allocate_exact_size(egui::vec2(width, height * 0.05), egui::Sense::hover());
+        let mut hotbar_ui = ui.new_child(egui::UiBuilder::new().max_rect(hotbar_rect));
+        match Hotbar::new(hotbar_entries, HOTBAR_SLOTS).show(&mut hotbar_ui) {
+            Some(HotbarAction::Pin(name)) => {
+                ui_events.hotbar.write(HotbarChanged::Pin(name));
+            }
+            Some(HotbarAction::Unpin(name)) => {
+                ui_events.hotbar.write(HotbarChanged::Unpin(name));
+            }
+            Some(HotbarAction::Activate(name)) => {
+                ui_events.activate_ability.write(ActivateAbility(name));
+            }
+            None => {}
+        }
+        ui.add_space(gap);
+
+        let stacking = &registries.ruleset.stacking;
+        let mut capped = std::collections::BTreeSet::new();
         let resists = character
             .effects
-            .get_resists()
+            .get_resists(stacking)
             .into_iter()
-            .map(|(r, v)| {
+            .map(|(r, total)| {
+                if total.is_capped() {
+                    capped.insert(r.to_string());
+                }
                 let icon = match r {
                     shared::Resist::Fire => icons.resist_fire.id(),
                     shared::Resist::Ice => icons.resist_ice.id(),
@@ -368,14 +688,17 @@ fn render_left_column(
                     shared::Resist::Spirit => icons.resist_spirit.id(),
                     shared::Resist::Dark => icons.resist_dark.id(),
                 };
-                (r.to_string(), (icon, v))
+                (r.to_string(), (icon, total.value))
             })
             .collect();
         let protections = character
             .effects
-            .get_protections()
+            .get_protections(stacking)
             .into_iter()
-            .map(|(p, v)| {
+            .map(|(p, total)| {
+                if total.is_capped() {
+                    capped.insert(p.to_string());
+                }
                 let icon = match p {
                     shared::Protection::Melee => icons.protection_melee.id(),
                     shared::Protection::Range => icons.protection_range.id(),
@@ -383,52 +706,124 @@ fn render_left_column(
                     shared::Protection::Body => icons.protection_body.id(),
                     shared::Protection::Mind => icons.protection_mind.id(),
                 };
-                (p.to_string(), (icon, v))
+                (p.to_string(), (icon, total.value))
             })
             .collect();
-        ui.add_sized([width, height * 0.20], Stats::new(resists, protections));
+        ui.add_sized(
+            [width, height * 0.16],
+            Stats::new(resists, protections).capped(capped),
+        );
         ui.add_space(gap);
 
         let weapon_slots: Vec<WeaponSlot> = character
             .weapon_names
             .iter()
             .filter_map(|name| {
-                registries.weapons.get(name).map(|w| WeaponSlot {
-                    name: w.name.clone(),
-                    description: w.description.clone(),
-                    kind: w.kind.to_string(),
-                    attack: format!("{:+}", w.attack),
-                    damage: w.damage.clone(),
-                    range: w.range.to_string(),
-                    condition: w.condition.clone().unwrap_or_default(),
+                registries.weapons.get(name).map(|w| {
+                    let parsed = shared::parse_dice(&w.damage);
+                    let roll_note = match &parsed {
+                        Ok(_) => modals.damage_rolls.0.get(name).cloned().unwrap_or_default(),
+                        Err(e) => format!("Damage can't be rolled: {e}"),
+                    };
+                    WeaponSlot {
+                        name: w.name.clone(),
+                        description: w.description.clone(),
+                        kind: w.kind.to_string(),
+                        attack: format!(
+                            "{:+}",
+                            shared::attack_bonus(w, |kind| {
+                                character.effects.effective_level(
+                                    character.stats,
+                                    kind,
+                                    &registries.ruleset.stacking,
+                                )
+                            })
+                        ),
+                        damage: weapon_damage(w),
+                        range: w.range.to_string(),
+                        condition: w.condition.clone().unwrap_or_default(),
+                        can_roll: parsed.is_ok(),
+                        roll_note,
+                    }
                 })
             })
             .collect();
 
-        let weapon_size = egui::vec2(width, height * 0.20);
+        let weapon_size = egui::vec2(width, height * 0.15);
         let (weapon_rect, _) = ui.allocate_exact_size(weapon_size, egui::Sense::hover());
         let mut weapon_ui = ui.new_child(
             egui::UiBuilder::new()
                 .max_rect(weapon_rect)
                 .layout(egui::Layout::top_down(egui::Align::Min)),
         );
-        if let Some(i) =
-            Weapon::new(icons.weapon_placeholder.id(), weapon_slots).show(&mut weapon_ui)
-        {
+        let weapon_names: Vec<String> = weapon_slots.iter().map(|s| s.name.clone()).collect();
+        let weapon_resp = Weapon::new(icons.weapon_placeholder.id(), weapon_slots)
+            .defense(shared::defense(character.effects))
+            .show(&mut weapon_ui);
+        if let Some(i) = weapon_resp.unequip {
             ui_events
                 .inventory
                 .write(InventoryChanged::UnequipWeapon(i));
         }
+        if let Some(weapon) = weapon_resp.roll.and_then(|i| weapon_names.get(i)) {
+            ui_events.roll_damage.write(RollDamage {
+                weapon: weapon.clone(),
+            });
+        }
 
         LeftColumnResponse {
             save: save_clicked,
             back: back_clicked,
+            simulate: simulate_clicked,
+            export: export_clicked,
             upload_portrait,
+            accent: accent_picked,
         }
     })
     .inner
 }
 
+/// Queues upgrades that spend all unspent characteristic and skill points
+/// following the class's allocation weights.
+fn auto_allocate_points(
+    character: &CharacterQueryDataItem,
+    registries: &Registries,
+    upgrade: &mut MessageWriter<UpgradeEvent>,
+) {
+    let class = &**character.class;
+    let no_skills = std::collections::BTreeMap::new();
+    let weights = registries
+        .ruleset
+        .classes
+        .get(class)
+        .map(|rules| rules.allocation.clone())
+        .unwrap_or_default();
+    let plan = shared::plan_allocation(
+        character.stats,
+        character.char_pts.0,
+        character.skills,
+        character.skill_pts.0,
+        registries
+            .skills
+            .get_class_skills(class)
+            .unwrap_or(&no_skills),
+        &character
+            .effects
+            .characteristic_bonuses(&registries.ruleset.stacking),
+        &weights,
+    );
+    for step in plan {
+        upgrade.write(match step {
+            shared::Allocation::Characteristic(kind) => UpgradeEvent::Characteristic(
+                CharacteristicKind::iter()
+                    .position(|k| k == kind)
+                    .unwrap_or_default(),
+            ),
+            shared::Allocation::Skill(name) => UpgradeEvent::Skill(name),
+        });
+    }
+}
+
 fn build_add_item_menu(
     weapon_registry: &shared::WeaponRegistry,
     equipment_registry: &shared::EquipmentRegistry,
@@ -468,7 +863,7 @@ fn build_add_item_menu(
                 description: w.description.clone(),
                 kind: w.kind.to_string(),
                 attack: format!("{:+}", w.attack),
-                damage: w.damage.clone(),
+                damage: weapon_damage(w),
                 range: w.range.to_string(),
                 condition: w.condition.clone().unwrap_or_default(),
                 effects: w.effects.iter().map(format_effect).collect(),
@@ -482,12 +877,51 @@ fn build_add_item_menu(
     }
 }
 
+/// Active status conditions as chips, plus a menu adding the others.
+fn render_conditions(
+    ui: &mut egui::Ui,
+    width: f32,
+    height: f32,
+    character: &CharacterQueryDataItem,
+    events: &mut MessageWriter<ConditionsChanged>,
+) {
+    let active: Vec<ConditionChip> = character
+        .conditions
+        .iter()
+        .map(|condition| ConditionChip {
+            name: condition.to_string(),
+            effects: condition.get_effects().iter().map(format_effect).collect(),
+        })
+        .collect();
+    let available: Vec<shared::Condition> = shared::Condition::iter()
+        .filter(|condition| !character.conditions.contains(condition))
+        .collect();
+
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, height), egui::Sense::hover());
+    let mut conditions_ui = ui.new_child(egui::UiBuilder::new().max_rect(rect));
+    let names = available.iter().map(ToString::to_string).collect();
+    match Conditions::new(active, names).show(&mut conditions_ui) {
+        Some(ConditionsAction::Add(i)) => {
+            if let Some(condition) = available.get(i) {
+                events.write(ConditionsChanged::Add(*condition));
+            }
+        }
+        Some(ConditionsAction::Remove(i)) => {
+            if let Some(condition) = character.conditions.get(i) {
+                events.write(ConditionsChanged::Remove(*condition));
+            }
+        }
+        None => {}
+    }
+}
+
 fn send_status_bar_events(
     ui: &mut egui::Ui,
     width: f32,
     height: f32,
     character: &CharacterQueryDataItem,
     initiative: i32,
+    campaign: &shared::CampaignRules,
     events: &mut MessageWriter<ResourceChanged>,
 ) {
     let status_size = egui::vec2(width, height);
@@ -506,32 +940,90 @@ fn send_status_bar_events(
         character.ap.max,
         initiative,
     )
+    .temp_hp(character.hp.temp)
+    .rests(
+        campaign.long_rest_available(character.rests.0),
+        rest_status(campaign.long_rests_per_day, character.rests.0),
+    )
     .show(&mut status_ui);
 
-    send_resource_events(events, result);
+    events.write_batch(resource_events(
+        result,
+        character.mana.current,
+        character.ap.current,
+    ));
+}
+
+/// Long rests left today, e.g. "1 of 2 long rests left today".
+fn rest_status(per_day: Option<u32>, used: u32) -> String {
+    match per_day {
+        None => "Long rests are unlimited".to_string(),
+        Some(limit) => format!(
+            "{} of {limit} long rests left today",
+            limit.saturating_sub(used)
+        ),
+    }
 }
 
-fn send_resource_events(events: &mut MessageWriter<ResourceChanged>, result: StatusBarResponse) {
+/// Resource events for a status bar interaction. Typed MP and AP changes are
+/// resolved against `mp_current`/`ap_current`; HP changes go through
+/// `HpDelta` so temporary HP absorbs damage first.
+fn resource_events(
+    result: StatusBarResponse,
+    mp_current: u32,
+    ap_current: u32,
+) -> Vec<ResourceChanged> {
+    let resolve = |edit: ValueEdit, current: u32| match edit {
+        ValueEdit::Set(v) => v,
+        ValueEdit::Delta(delta) => current.saturating_add_signed(delta),
+    };
+    let mut events = Vec::new();
     if let Some(v) = result.hp {
-        events.write(ResourceChanged::Hp(v));
+        events.push(ResourceChanged::Hp(v));
+    }
+    if let Some(delta) = result.hp_delta {
+        events.push(ResourceChanged::HpDelta(delta));
+    }
+    match result.hp_edit {
+        Some(ValueEdit::Set(v)) => events.push(ResourceChanged::Hp(v)),
+        Some(ValueEdit::Delta(delta)) => events.push(ResourceChanged::HpDelta(delta)),
+        None => {}
+    }
+    if let Some(v) = result.temp_hp {
+        events.push(ResourceChanged::TempHp(v));
     }
     if let Some(v) = result.mp {
-        events.write(ResourceChanged::Mp(v));
+        events.push(ResourceChanged::Mp(v));
+    }
+    if let Some(edit) = result.mp_edit {
+        events.push(ResourceChanged::Mp(resolve(edit, mp_current)));
     }
     if let Some(v) = result.ap {
-        events.write(ResourceChanged::Ap(v));
+        events.push(ResourceChanged::Ap(v));
+    }
+    if let Some(edit) = result.ap_edit {
+        events.push(ResourceChanged::Ap(resolve(edit, ap_current)));
     }
+    if result.short_rest {
+        events.push(ResourceChanged::Rest(shared::RestKind::Short));
+    }
+    if result.long_rest {
+        events.push(ResourceChanged::Rest(shared::RestKind::Long));
+    }
+    events
 }
 
-fn build_character_from_components(c: &CharacterQueryDataItem) -> shared::Character {
+pub(super) fn build_character_from_components(c: &CharacterQueryDataItem) -> shared::Character {
     shared::Character {
         id: c.id.0,
         name: c.name.0.clone(),
+        accent_color: c.accent.0,
         race: c.race.0,
         class: c.class.0,
         level: c.level.0,
         experience: c.exp.0,
         hp_spent: c.hp.max.saturating_sub(c.hp.current),
+        temp_hp: c.hp.temp,
         mana_spent: c.mana.max.saturating_sub(c.mana.current),
         action_points: shared::Resource {
             current: c.ap.current,
@@ -548,11 +1040,21 @@ fn build_character_from_components(c: &CharacterQueryDataItem) -> shared::Charac
         equipped_weapons: c.weapon_names.to_vec(),
         equipped_equipment: c.equipment.0.clone(),
         inventory: c.inventory.to_vec(),
+        item_counts: c.item_counts.0.clone(),
         wallet: **c.wallet,
-        active_effects: Vec::new(),
+        loadouts: c.loadouts.0.clone(),
+        hotbar: c.hotbar.0.clone(),
+        notes: c.notes.0.clone(),
+        ability_runtime: c.ability_runtime.0.clone(),
+        rests_used_today: c.rests.0,
+        rng_seed: c.dice.0.map(|rng| rng.seed()),
+        temporary_effects: c.temp_effects.0.clone(),
+        conditions: c.conditions.0.clone(),
+        active_effects: c.effects.0.clone(),
     }
 }
 
+/// Returns the skill clicked for a check and its effective level, if any.
 fn render_center_column(
     ui: &mut egui::Ui,
     width: f32,
@@ -562,9 +1064,10 @@ fn render_center_column(
     registries: &Registries,
     ui_events: &mut UiEvents,
     edit_mode: bool,
-) {
+) -> Option<(String, i32)> {
     let gap = height * 0.03 / 4.0;
     let stats = character.stats;
+    let mut skill_roll = None;
 
     ui.vertical(|ui| {
         ui.spacing_mut().item_spacing = egui::vec2(0.0, 0.0);
@@ -591,8 +1094,14 @@ fn render_center_column(
                 .max_rect(char_rect)
                 .layout(egui::Layout::top_down(egui::Align::Min)),
         );
+        let softcap = registries
+            .ruleset
+            .classes
+            .get(character.class)
+            .and_then(|rules| rules.allocation.characteristic_cap);
         if let Some(GridAction::Upgrade(idx)) = Characteristics::new(char_values)
             .edit_mode(edit_mode, character.char_pts.0)
+            .softcap(softcap)
             .show(&mut char_ui)
         {
             ui_events.upgrade.write(UpgradeEvent::Characteristic(idx));
@@ -614,8 +1123,14 @@ fn render_center_column(
                     .iter()
                     .find(|s| s.name == *name)
                     .map_or(0, |s| s.level);
-                let skill_bonus = character.effects.skill_bonus(name);
-                let max_level = character.effects.effective_level(stats, skill.dependency);
+                let skill_bonus = character
+                    .effects
+                    .skill_bonus(name, &registries.ruleset.stacking);
+                let max_level = character.effects.effective_level(
+                    stats,
+                    skill.dependency,
+                    &registries.ruleset.stacking,
+                );
                 SkillEntry {
                     name: name.clone(),
                     dependency: skill.dependency.to_string(),
@@ -632,19 +1147,21 @@ fn render_center_column(
                 .max_rect(skill_rect)
                 .layout(egui::Layout::top_down(egui::Align::Min)),
         );
-        if let Some(GridAction::Upgrade(idx)) = Skills::new(skill_entries)
+        let check_levels: Vec<(String, i32)> = skill_entries
+            .iter()
+            .map(|e| (e.name.clone(), e.level))
+            .collect();
+        match Skills::new(skill_entries)
             .edit_mode(edit_mode, character.skill_pts.0)
             .show(&mut skill_ui)
         {
-            if let Some(name) = registries
-                .skills
-                .get_class_skills(character.class)
-                .into_iter()
-                .flat_map(|skills| skills.keys())
-                .nth(idx)
-            {
-                ui_events.upgrade.write(UpgradeEvent::Skill(name.clone()));
+            Some(GridAction::Upgrade(idx)) => {
+                if let Some((name, _)) = check_levels.get(idx) {
+                    ui_events.upgrade.write(UpgradeEvent::Skill(name.clone()));
+                }
             }
+            Some(GridAction::Roll(idx)) => skill_roll = check_levels.get(idx).cloned(),
+            Some(GridAction::Downgrade(_)) | None => {}
         }
         ui.add_space(gap);
         let trait_entries: Vec<TraitEntry> = character
@@ -692,20 +1209,47 @@ fn render_center_column(
                         .as_ref()
                         .map(|e| e.to_string())
                         .unwrap_or_default(),
+                    unavailable: character
+                        .ability_runtime
+                        .get(name)
+                        .copied()
+                        .unwrap_or_default()
+                        .unavailable(a.requirements.as_ref()),
                 })
             })
             .collect();
         let abilities_size = egui::vec2(width, height * 0.40);
         let (abilities_rect, _) = ui.allocate_exact_size(abilities_size, egui::Sense::hover());
         let mut abilities_ui = ui.new_child(egui::UiBuilder::new().max_rect(abilities_rect));
-        if let Some(new_mp) =
-            Abilities::new(ability_entries, character.mana.current).show(&mut abilities_ui)
-        {
-            ui_events.resource.write(ResourceChanged::Mp(new_mp));
+        let abilities_resp = Abilities::new(ability_entries).show(&mut abilities_ui);
+        if let Some(name) = abilities_resp.activated {
+            ui_events.activate_ability.write(ActivateAbility(name));
+        }
+        let rolled_check = abilities_resp.roll.and_then(|name| {
+            let ability = class_abilities
+                .and_then(|ca| ca.innate.get(&name).or_else(|| ca.acquire.get(&name)))?;
+            let check = ability.check.as_ref()?;
+            let modifier = shared::ability_check_modifier(
+                check,
+                &character.skills.0,
+                &character.effects.0,
+                &registries.ruleset.stacking,
+                |kind| {
+                    character
+                        .effects
+                        .effective_level(stats, kind, &registries.ruleset.stacking)
+                },
+            );
+            Some((format!("{name} ({check})"), modifier))
+        });
+        if rolled_check.is_some() {
+            skill_roll = rolled_check;
         }
     });
+    skill_roll
 }
 
+/// Returns true when an inventory item was loaded into the item dialog.
 fn render_right_column(
     ui: &mut egui::Ui,
     width: f32,
@@ -714,11 +1258,16 @@ fn render_right_column(
     character: &CharacterQueryDataItem,
     registries: &Registries,
     ui_events: &mut UiEvents,
-) {
+    modals: &mut UiModals,
+    admin: bool,
+) -> bool {
+    use std::collections::BTreeMap;
+
     let gap = height * 0.03 / 2.0;
     let wallet = character.wallet;
+    let mut edit_item = false;
 
-    let inventory_items: Vec<Option<InventoryTooltip>> = character
+    let mut inventory_items: Vec<Option<InventoryTooltip>> = character
         .inventory
         .iter()
         .map(|inv_item| match inv_item {
@@ -731,7 +1280,7 @@ fn render_right_column(
                         description: w.description.clone(),
                         kind: w.kind.to_string(),
                         attack: format!("{:+}", w.attack),
-                        damage: w.damage.clone(),
+                        damage: weapon_damage(w),
                         range: w.range.to_string(),
                         condition: w.condition.clone().unwrap_or_default(),
                         effects: w.effects.iter().map(format_effect).collect(),
@@ -756,23 +1305,54 @@ fn render_right_column(
             }
         })
         .collect();
+    let quantities: Vec<u32> = character
+        .inventory
+        .iter()
+        .map(|inv_item| inv_item.quantity(character.item_counts))
+        .collect();
+    // Only admins may delete, and only definitions users created
+    let deletable: Vec<bool> = character
+        .inventory
+        .iter()
+        .map(|inv_item| admin && !registries.builtin.0.contains(inv_item))
+        .collect();
 
-    let equipped_items: Vec<Option<InventoryTooltip>> = character
-        .equipment
-        .values()
-        .flat_map(|names| names.iter())
-        .map(|name| {
-            registries
-                .equipment
-                .get(name)
-                .map(|e| InventoryTooltip::Equipment {
+    let mut gear = EquippedGear::new(icons.inventory_placeholder.id());
+    for (slot, names) in character.equipment.iter() {
+        for name in names {
+            let tooltip = match registries.equipment.get(name) {
+                Some(e) => InventoryTooltip::Equipment {
                     name: e.name.clone(),
                     slot: e.slot.to_string(),
                     description: e.description.clone(),
                     effects: e.effects.iter().map(format_effect).collect(),
-                })
-        })
-        .collect();
+                },
+                None => InventoryTooltip::Item {
+                    name: name.clone(),
+                    description: String::new(),
+                },
+            };
+            gear = gear.item(&slot.to_string(), tooltip);
+        }
+    }
+
+    // Inventory equipment that fits each slot, offered when an empty slot is clicked.
+    let mut slot_options: BTreeMap<String, Vec<(usize, String)>> = BTreeMap::new();
+    for (idx, inv_item) in character.inventory.iter().enumerate() {
+        if let shared::InventoryItem::Equipment(name) = inv_item {
+            if let Some(e) = registries.equipment.get(name) {
+                slot_options
+                    .entry(e.slot.to_string())
+                    .or_default()
+                    .push((idx, name.clone()));
+            }
+        }
+    }
+    for (slot, options) in &slot_options {
+        gear = gear.options(slot, options.iter().map(|(_, name)| name.clone()).collect());
+    }
+    let metrics = auto_equip_metrics();
+    gear = gear.auto_metrics(metrics.iter().map(|(label, _)| label.clone()).collect());
 
     ui.vertical(|ui| {
         ui.spacing_mut().item_spacing = egui::vec2(0.0, 0.0);
@@ -784,11 +1364,41 @@ fn render_right_column(
                 .max_rect(equipped_rect)
                 .layout(egui::Layout::top_down(egui::Align::Min)),
         );
-        if let Some(i) = EquippedGear::new(icons.inventory_placeholder.id())
-            .items(equipped_items)
-            .show(&mut equipped_ui)
-        {
-            ui_events.inventory.write(InventoryChanged::UnequipGear(i));
+        match gear.show(&mut equipped_ui) {
+            Some(GearAction::Unequip { slot, index }) => {
+                let slot = character.equipment.keys().find(|s| s.to_string() == slot);
+                if let Some(slot) = slot {
+                    ui_events
+                        .inventory
+                        .write(InventoryChanged::UnequipGear(*slot, index));
+                }
+            }
+            Some(GearAction::UnequipMany(picks)) => {
+                let picks = picks
+                    .into_iter()
+                    .filter_map(|(slot, index)| {
+                        let slot = character.equipment.keys().find(|s| s.to_string() == slot);
+                        slot.map(|slot| (*slot, index))
+                    })
+                    .collect();
+                ui_events.inventory.write_batch(bulk_unequip_events(picks));
+            }
+            Some(GearAction::Equip { slot, option }) => {
+                if let Some((idx, _)) = slot_options.get(&slot).and_then(|o| o.get(option)) {
+                    ui_events.inventory.write(InventoryChanged::Equip(*idx));
+                }
+            }
+            Some(GearAction::AutoEquip { slot, metric }) => {
+                let slot = shared::EquipmentSlot::iter().find(|s| s.to_string() == slot);
+                if let (Some(slot), Some((_, metric))) = (slot, metrics.get(metric)) {
+                    let inventory = character.inventory;
+                    let best = registries.equipment.best_for_slot(inventory, slot, *metric);
+                    if let Some(idx) = best {
+                        ui_events.inventory.write(InventoryChanged::Equip(idx));
+                    }
+                }
+            }
+            None => {}
         }
 
         ui.add_space(gap);
@@ -800,20 +1410,40 @@ fn render_right_column(
                 .max_rect(wallet_rect)
                 .layout(egui::Layout::top_down(egui::Align::Min)),
         );
-        let result = WalletWidget::new(
-            wallet.gold(),
-            wallet.silver(),
-            wallet.copper(),
-            icons.wallet_gold.id(),
-            icons.wallet_silver.id(),
-            icons.wallet_copper.id(),
-        )
-        .show(&mut wallet_ui);
-        send_wallet_events(&mut ui_events.wallet, result);
+        let scheme = &registries.ruleset.campaign.currency;
+        let parts = wallet.parts(scheme);
+        let tiers = scheme.names.len();
+        let mut wallet_widget = WalletWidget::new();
+        for (i, name) in scheme.names.iter().enumerate() {
+            wallet_widget = wallet_widget.denomination(
+                name.as_str(),
+                parts[i],
+                currency_icon(icons, name, tiers - 1 - i),
+            );
+        }
+        if let Some((denomination, count)) = wallet_widget.show(&mut wallet_ui).coins {
+            ui_events.wallet.write(WalletChanged::Coins {
+                denomination,
+                count,
+            });
+        }
 
         ui.add_space(gap);
 
-        let inventory_size = egui::vec2(width, height * 0.48);
+        let footer_height = 18.0;
+        let toolbar_height = 22.0;
+        let mut view = InventoryView::load(ui.ctx());
+        ui.allocate_ui(egui::vec2(width, toolbar_height), |ui| {
+            view.show_toolbar(ui);
+        });
+        // Cells are shown in view order; actions map back to inventory indices
+        let order = view.order(character.inventory);
+        view.store(ui.ctx());
+        let shown_items = order.iter().map(|&i| inventory_items[i].take()).collect();
+        let shown_quantities = order.iter().map(|&i| quantities[i]).collect();
+        let shown_deletable = order.iter().map(|&i| deletable[i]).collect();
+
+        let inventory_size = egui::vec2(width, height * 0.48 - footer_height - toolbar_height);
         let (inventory_rect, _) = ui.allocate_exact_size(inventory_size, egui::Sense::hover());
         let mut inventory_ui = ui.new_child(
             egui::UiBuilder::new()
@@ -821,25 +1451,171 @@ fn render_right_column(
                 .layout(egui::Layout::top_down(egui::Align::Min)),
         );
         match Inventory::new(icons.inventory_placeholder.id())
-            .items(inventory_items)
+            .items(shown_items)
+            .quantities(shown_quantities)
+            .deletable(shown_deletable)
             .show(&mut inventory_ui)
+            .and_then(|action| action.map_index(|i| order.get(i).copied()))
         {
             Some(CellAction::Primary(i)) => {
                 ui_events.inventory.write(InventoryChanged::Equip(i));
             }
             Some(CellAction::Remove(i)) => {
-                ui_events.inventory.write(InventoryChanged::Remove(i));
+                let name = character
+                    .inventory
+                    .get(i)
+                    .map(|item| item.name().to_string());
+                match name {
+                    Some(name) if modals.settings.confirm_destructive => {
+                        modals.remove_item.0 = Some((i, name));
+                    }
+                    _ => {
+                        ui_events.inventory.write(InventoryChanged::Remove(i));
+                    }
+                }
+            }
+            Some(CellAction::Edit(i)) => {
+                if let Some(item) = character.inventory.get(i) {
+                    edit_item = crate::create_item::open_item_editor(
+                        ui.ctx(),
+                        item,
+                        &registries.items,
+                        &registries.equipment,
+                        &registries.weapons,
+                    );
+                }
+            }
+            Some(CellAction::DeleteFromRegistry(i)) => {
+                if let Some(item) = character.inventory.get(i) {
+                    ui_events
+                        .delete_entry
+                        .write(DeleteRegistryEntry(item.clone()));
+                }
+            }
+            Some(CellAction::Sell(i)) => {
+                let value = character.inventory.get(i).and_then(|item| {
+                    item.value(
+                        &registries.items,
+                        &registries.equipment,
+                        &registries.weapons,
+                    )
+                });
+                match value {
+                    Some(value) => sell_item(
+                        &mut ui_events.inventory,
+                        &mut ui_events.wallet,
+                        i,
+                        registries.ruleset.campaign.resale_price(value),
+                    ),
+                    None => super::overlays::open_sell_prompt(ui.ctx(), i),
+                }
             }
             None => {}
         }
+        let totals = shared::InventoryTotals::of(
+            character.inventory,
+            character.item_counts,
+            &registries.items,
+            &registries.equipment,
+            &registries.weapons,
+        );
+        let resale = scheme.format(registries.ruleset.campaign.resale_price(totals.value));
+        let stacks = shared::merge_duplicates(&mut character.inventory.to_vec()).len();
+        let load = shared::carried_load(
+            character.inventory,
+            character.item_counts,
+            character.weapon_names,
+            character.equipment,
+            &registries.items,
+            &registries.equipment,
+            &registries.weapons,
+        );
+        let strength = character.effects.effective_level(
+            character.stats,
+            shared::CharacteristicKind::Strength,
+            &registries.ruleset.stacking,
+        );
+        let capacity = shared::carry_capacity(strength);
+        ui.allocate_ui(egui::vec2(width, footer_height), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(format!("Items: {} · ", totals.count)).small());
+                let load_text = egui::RichText::new(format!("Load: {load}/{capacity}")).small();
+                match shared::overload_penalty(load, capacity) {
+                    Some(shared::Effect::ActionPoints(penalty)) => {
+                        ui.label(load_text.color(HP_COLOR))
+                            .on_hover_text(format!("Overloaded: {penalty} AP"));
+                    }
+                    _ => {
+                        ui.label(load_text)
+                            .on_hover_text("Weight carried, equipped gear included");
+                    }
+                }
+                ui.label(egui::RichText::new(format!(" · Resale: {resale}")).small());
+                let merge = egui::Button::new(egui::RichText::new("Merge duplicates").small());
+                if ui
+                    .add_enabled(stacks < character.inventory.len(), merge)
+                    .on_hover_text("Group identical items and sort the inventory")
+                    .clicked()
+                {
+                    ui_events.inventory.write(InventoryChanged::MergeDuplicates);
+                }
+            });
+        });
+        let base_unit = scheme.names.last().map(String::as_str).unwrap_or_default();
+        if let Some((i, price)) = super::overlays::render_sell_prompt(ui.ctx(), base_unit) {
+            sell_item(&mut ui_events.inventory, &mut ui_events.wallet, i, price);
+        }
     });
+    edit_item
+}
+
+/// Metrics the gear "auto" button can maximize: armor, then each resist.
+fn auto_equip_metrics() -> Vec<(String, shared::EffectTotal)> {
+    std::iter::once(("Armor".to_string(), shared::EffectTotal::Armor))
+        .chain(
+            shared::Resist::iter().map(|r| (format!("{r} resist"), shared::EffectTotal::Resist(r))),
+        )
+        .collect()
 }
 
-fn send_wallet_events(events: &mut MessageWriter<WalletChanged>, result: WalletResponse) {
-    for delta in [result.gold, result.silver, result.copper]
-        .into_iter()
-        .flatten()
-    {
-        events.write(WalletChanged(delta));
+/// Icon for a currency denomination: matched by name, otherwise by tier counted
+/// from the base unit (copper, then silver, anything higher gold).
+fn currency_icon(icons: &UiIcons, name: &str, tier_from_base: usize) -> egui::TextureId {
+    match (name.to_lowercase().as_str(), tier_from_base) {
+        ("gold", _) => icons.wallet_gold.id(),
+        ("silver", _) => icons.wallet_silver.id(),
+        ("copper", _) => icons.wallet_copper.id(),
+        (_, 0) => icons.wallet_copper.id(),
+        (_, 1) => icons.wallet_silver.id(),
+        _ => icons.wallet_gold.id(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hp_editor_delta_emits_hp_delta() {
+        let typed = |text: &str| StatusBarResponse {
+            hp_edit: ValueEdit::parse(text),
+            ..Default::default()
+        };
+        assert_eq!(
+            resource_events(typed("+5"), 3, 2),
+            vec![ResourceChanged::HpDelta(5)]
+        );
+        assert_eq!(
+            resource_events(typed("12"), 3, 2),
+            vec![ResourceChanged::Hp(12)]
+        );
+        // Invalid input reverts: nothing is sent
+        assert!(resource_events(typed("lots"), 3, 2).is_empty());
+
+        let mana = StatusBarResponse {
+            mp_edit: ValueEdit::parse("-5"),
+            ..Default::default()
+        };
+        assert_eq!(resource_events(mana, 3, 2), vec![ResourceChanged::Mp(0)]);
     }
 }
@@ -1,20 +1,34 @@
 mod apply;
+mod buffs;
+mod columns;
+mod effects;
+mod glossary;
 mod helpers;
 mod icons;
+mod inventory_view;
 mod layout;
+mod notes;
 mod overlays;
 mod params;
+mod remote;
+mod settings;
+mod theme;
+mod trash;
+mod undo;
 
-pub use helpers::format_effect;
+pub use helpers::{accent_color, format_effect};
 pub use overlays::{render_trait_select_overlay, TraitSelectMode};
 pub use params::EditMode;
 
 use bevy::prelude::*;
+use bevy_egui::input::egui_wants_any_keyboard_input;
 use bevy_egui::EguiPrimaryContextPass;
 
 use crate::events::{
-    CreateItem, ExperienceChanged, InventoryChanged, LearnAbility, LearnTrait, LevelUp,
-    ResourceChanged, UpgradeEvent, WalletChanged,
+    ActivateAbility, ConditionsChanged, CreateItem, DeleteRegistryEntry, ExperienceChanged,
+    HotbarChanged, IdentityChanged, InventoryChanged, LearnAbility, LearnTrait, LevelUp,
+    LoadoutChanged, ResourceChanged, RollCheck, RollDamage, TempEffectsChanged, UpgradeEvent,
+    WalletChanged,
 };
 use crate::state::AppScreen;
 
@@ -25,21 +39,46 @@ impl Plugin for UiPlugin {
         app.init_resource::<EditMode>()
             .init_resource::<params::LearnAbilityOpen>()
             .init_resource::<params::LearnTraitOpen>()
+            .init_resource::<params::InspectJsonOpen>()
+            .init_resource::<params::EffectsOpen>()
+            .init_resource::<params::GlossaryOpen>()
+            .init_resource::<params::BuffsOpen>()
+            .init_resource::<params::NotesOpen>()
+            .init_resource::<params::RespecConfirm>()
+            .init_resource::<params::RemoveItemConfirm>()
+            .init_resource::<params::DamageRolls>()
+            .init_resource::<columns::ColumnLayout>()
             .init_resource::<crate::create_item::CreateItemOpen>()
+            .init_resource::<trash::InventoryTrash>()
+            .init_resource::<undo::UndoStack>()
+            .insert_resource(settings::Settings::load())
+            .add_message::<IdentityChanged>()
             .add_message::<ResourceChanged>()
             .add_message::<WalletChanged>()
             .add_message::<InventoryChanged>()
+            .add_message::<LoadoutChanged>()
             .add_message::<ExperienceChanged>()
             .add_message::<LevelUp>()
             .add_message::<UpgradeEvent>()
             .add_message::<LearnAbility>()
             .add_message::<LearnTrait>()
             .add_message::<CreateItem>()
+            .add_message::<DeleteRegistryEntry>()
+            .add_message::<HotbarChanged>()
+            .add_message::<ActivateAbility>()
+            .add_message::<RollCheck>()
+            .add_message::<RollDamage>()
+            .add_message::<TempEffectsChanged>()
+            .add_message::<ConditionsChanged>()
             .add_systems(
                 EguiPrimaryContextPass,
                 (
+                    theme::apply_theme,
                     icons::init_icons.run_if(not(resource_exists::<icons::UiIcons>)),
                     layout::render_ui.run_if(in_state(AppScreen::CharacterSheet)),
+                    trash::render_inventory_trash.run_if(in_state(AppScreen::CharacterSheet)),
+                    buffs::render_buffs.run_if(in_state(AppScreen::CharacterSheet)),
+                    notes::render_notes.run_if(in_state(AppScreen::CharacterSheet)),
                 ),
             )
             .add_systems(
@@ -48,13 +87,39 @@ impl Plugin for UiPlugin {
                     apply::apply_resource_changes,
                     apply::apply_wallet_changes,
                     apply::apply_inventory_changes,
+                    apply::apply_loadout_changes,
                     apply::apply_experience_changes,
                     apply::apply_level_up,
                     apply::apply_upgrades,
                     apply::apply_learn_ability,
                     apply::apply_learn_trait,
-                ),
+                    apply::apply_hotbar_changes,
+                    apply::apply_ability_activation,
+                    apply::apply_roll_checks,
+                    apply::apply_damage_rolls,
+                    apply::apply_temp_effects,
+                )
+                    .after(undo::record_undo_snapshot),
+            )
+            .add_systems(
+                Update,
+                (
+                    apply::apply_create_item,
+                    apply::apply_registry_deletions,
+                    apply::apply_identity_changes,
+                    apply::apply_condition_changes,
+                )
+                    .after(undo::record_undo_snapshot),
+            )
+            .add_systems(
+                Update,
+                (
+                    undo::record_undo_snapshot,
+                    undo::undo_shortcuts.run_if(not(egui_wants_any_keyboard_input)),
+                )
+                    .run_if(in_state(AppScreen::CharacterSheet)),
             )
-            .add_systems(Update, apply::apply_create_item);
+            .add_systems(Update, (settings::save_settings, remote::apply_remote_save))
+            .add_systems(OnExit(AppScreen::CharacterSheet), undo::clear_undo_stack);
     }
 }
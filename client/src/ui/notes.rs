@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use ui_widgets::colors::palette;
+
+use super::params::NotesOpen;
+use crate::components::{ActiveCharacter, CharacterName, CharacterNotes};
+
+/// Free-form notes for the active character, saved with the sheet.
+pub(super) fn render_notes(
+    mut contexts: EguiContexts,
+    mut open: ResMut<NotesOpen>,
+    mut character: Query<(&CharacterName, &mut CharacterNotes), With<ActiveCharacter>>,
+) -> Result {
+    if !open.0 {
+        return Ok(());
+    }
+    let Ok((name, mut notes)) = character.single_mut() else {
+        return Ok(());
+    };
+    let ctx = contexts.ctx_mut()?;
+
+    let mut text = notes.0.clone();
+    egui::Window::new(format!("{} — Notes", name.0))
+        .id(egui::Id::new("notes_window"))
+        .open(&mut open.0)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([360.0, 280.0])
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(12)),
+        )
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut text)
+                            .hint_text("Quests, debts, names to remember…")
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+        });
+    if text != notes.0 {
+        notes.0 = text;
+    }
+
+    Ok(())
+}
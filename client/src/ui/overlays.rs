@@ -1,9 +1,11 @@
 use bevy::prelude::*;
 use bevy_egui::egui;
-use ui_widgets::colors::{MAIN_COLOR, SECONDARY_COLOR};
+use shared::AbilityLock;
+use ui_widgets::colors::palette;
 use ui_widgets::molecules::{AbilityCard, SmallAbility};
 
 use crate::events::LearnAbility;
+use crate::simulation::SimulationAction;
 
 use super::helpers::{check_trait_requirement, format_effect};
 use super::icons::UiIcons;
@@ -45,7 +47,7 @@ pub(super) fn render_learn_ability_overlay(
         .show(ctx, |ui| {
             let (rect, _) = ui.allocate_exact_size(dialog_size, egui::Sense::hover());
             ui.painter()
-                .rect_filled(rect, egui::CornerRadius::same(16), MAIN_COLOR);
+                .rect_filled(rect, egui::CornerRadius::same(16), palette().main);
             ui.painter().rect_stroke(
                 rect,
                 egui::CornerRadius::same(16),
@@ -54,7 +56,50 @@ pub(super) fn render_learn_ability_overlay(
             );
 
             let pad = rect.width() * 0.04;
-            let content = rect.shrink(pad);
+            let mut content = rect.shrink(pad);
+
+            // Trait-unlocked abilities that have no cell in the class grid
+            let unlocked = registries.traits.unlocked_abilities(character.trait_names);
+            let off_grid: Vec<&str> = unlocked
+                .iter()
+                .filter(|name| !character.ability_names.contains(name))
+                .filter(|name| {
+                    registries
+                        .abilities
+                        .get_acquire(character.class, name)
+                        .is_none_or(|a| a.learn_screen_position.is_none())
+                })
+                .filter(|name| registries.abilities.find_acquire(name).is_some())
+                .map(String::as_str)
+                .collect();
+            if !off_grid.is_empty() {
+                let strip_h = content.height() * 0.12;
+                let strip = egui::Rect::from_min_max(
+                    egui::pos2(content.min.x, content.max.y - strip_h),
+                    content.max,
+                );
+                content.max.y -= strip_h + pad / 2.0;
+                let mut strip_ui = ui.new_child(
+                    egui::UiBuilder::new()
+                        .max_rect(strip)
+                        .layout(egui::Layout::left_to_right(egui::Align::Center)),
+                );
+                strip_ui.label("Unlocked by traits:");
+                for name in off_grid {
+                    let button = egui::Button::new(name);
+                    if strip_ui
+                        .add_enabled(character.ability_pts.0 > 0, button)
+                        .clicked()
+                    {
+                        ui_events
+                            .learn_ability
+                            .write(LearnAbility(name.to_string()));
+                        if character.ability_pts.0 == 1 {
+                            learn_ability_open.0 = false;
+                        }
+                    }
+                }
+            }
 
             // 3 rows in staggered pattern: 3, 2, 3
             let rows: [usize; 3] = [3, 2, 3];
@@ -66,8 +111,9 @@ pub(super) fn render_learn_ability_overlay(
             // Build a grid of ability data from the registry.
             // grid[row][col] -- row maps directly to LearnScreenPosition.row,
             // col maps to LearnScreenPosition.column.
-            // Tuple: (name, mp_cost, can_learn, already_learned)
-            let mut grid: [[Option<(&str, Option<u32>, bool, bool)>; 3]; 3] = Default::default();
+            // Tuple: (name, mp_cost, lock, already_learned)
+            let mut grid: [[Option<(&str, Option<u32>, Option<AbilityLock>, bool)>; 3]; 3] =
+                Default::default();
             if let Some(class_abilities) = registries.abilities.get_class_abilities(character.class)
             {
                 let known: &[String] = character.ability_names;
@@ -78,12 +124,12 @@ pub(super) fn render_learn_ability_overlay(
                         if r < 3 && c < 3 {
                             let mp = ability.requirements.as_ref().and_then(|r| r.mp);
                             let already_learned = known.contains(name);
-                            let can_learn = ability.can_learn_after.is_empty()
-                                || ability
-                                    .can_learn_after
-                                    .iter()
-                                    .any(|prereq| known.contains(prereq));
-                            grid[r][c] = Some((name.as_str(), mp, can_learn, already_learned));
+                            let lock = if unlocked.contains(name) {
+                                None
+                            } else {
+                                ability.lock(known, character.level.0)
+                            };
+                            grid[r][c] = Some((name.as_str(), mp, lock, already_learned));
                         }
                     }
                 }
@@ -94,20 +140,25 @@ pub(super) fn render_learn_ability_overlay(
             for (row_idx, &col_count) in rows.iter().enumerate() {
                 let y = content.min.y + (cell_h + gap) * row_idx as f32;
                 let x_offset = if col_count == 2 { half_offset } else { 0.0 };
-                for col in 0..col_count {
+                for (col, cell) in grid[row_idx].iter().enumerate().take(col_count) {
                     let x = content.min.x + x_offset + (cell_w + gap) * col as f32;
                     let cell_rect =
                         egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(cell_w, cell_h));
-                    if let Some((name, mp, can_learn, learned)) = grid[row_idx][col] {
+                    if let Some((name, mp, lock, learned)) = cell {
+                        let (name, mp, learned) = (*name, *mp, *learned);
+                        // Learned abilities are never shown as locked
+                        let lock = lock.as_ref().filter(|_| !learned);
+                        let can_learn = lock.is_none();
                         let fill = if can_learn {
-                            MAIN_COLOR
+                            palette().main
                         } else {
-                            SECONDARY_COLOR
+                            palette().secondary
                         };
                         SmallAbility::new(name, ability_icon)
                             .mp_cost(mp)
                             .fill(fill)
                             .learned(learned)
+                            .locked(!can_learn)
                             .paint(ui.painter(), cell_rect);
 
                         let cell_id = egui::Id::new("learn_cell").with(row_idx).with(col);
@@ -126,6 +177,16 @@ pub(super) fn render_learn_ability_overlay(
                             }
                         }
                         if response.hovered() {
+                            if let Some(lock) = lock {
+                                egui::Area::new(cell_id.with("lock_tooltip"))
+                                    .order(egui::Order::Tooltip)
+                                    .fixed_pos(egui::pos2(cell_rect.min.x, cell_rect.max.y + 4.0))
+                                    .show(ui.ctx(), |ui| {
+                                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                                            ui.label(format!("🔒 {lock}"));
+                                        });
+                                    });
+                            }
                             if let Some(ability) =
                                 class_abilities.and_then(|ca| ca.acquire.get(name))
                             {
@@ -248,7 +309,7 @@ pub fn render_trait_select_overlay(
         .show(ctx, |ui| {
             let (rect, _) = ui.allocate_exact_size(dialog_size, egui::Sense::hover());
             ui.painter()
-                .rect_filled(rect, egui::CornerRadius::same(16), MAIN_COLOR);
+                .rect_filled(rect, egui::CornerRadius::same(16), palette().main);
             ui.painter().rect_stroke(
                 rect,
                 egui::CornerRadius::same(16),
@@ -274,7 +335,7 @@ pub fn render_trait_select_overlay(
                 egui::Align2::CENTER_CENTER,
                 &title_text,
                 egui::FontId::proportional(20.0),
-                ui_widgets::colors::TEXT_COLOR,
+                ui_widgets::colors::palette().text,
             );
 
             // Buttons area at the bottom
@@ -427,7 +488,7 @@ fn render_trait_info(
             egui::RichText::new(label)
                 .strong()
                 .size(14.0)
-                .color(ui_widgets::colors::TEXT_COLOR),
+                .color(ui_widgets::colors::palette().text),
         );
         ui.label(
             egui::RichText::new(&ct.description)
@@ -461,3 +522,225 @@ fn render_trait_info(
         }
     });
 }
+
+/// Read-only debugging window with the working character's serialized JSON.
+pub(super) fn render_inspect_json_window(
+    ctx: &egui::Context,
+    open: &mut bool,
+    character: &shared::Character,
+) {
+    let json = character
+        .to_pretty_json()
+        .unwrap_or_else(|e| format!("Failed to serialize character: {e}"));
+
+    egui::Window::new(format!("{} — JSON", character.name))
+        .id(egui::Id::new("inspect_json_window"))
+        .open(open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([480.0, 560.0])
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .inner_margin(egui::Margin::same(12)),
+        )
+        .show(ctx, |ui| {
+            if ui.button("Copy").clicked() {
+                ui.ctx().copy_text(json.clone());
+            }
+            ui.add_space(6.0);
+            egui::ScrollArea::both()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut json.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+        });
+}
+
+/// Asks before refunding all spent characteristic and skill points.
+/// Returns true when confirmed; either button closes the dialog.
+pub(super) fn render_respec_confirm(ctx: &egui::Context, open: &mut bool) -> bool {
+    render_confirm_dialog(
+        ctx,
+        "Confirm Respec",
+        "Refund all characteristic and skill points?",
+        "Respec",
+        open,
+    )
+}
+
+/// Asks before deleting the inventory item `name` for good.
+/// Returns true when confirmed; either button closes the dialog.
+pub(super) fn render_remove_item_confirm(ctx: &egui::Context, name: &str, open: &mut bool) -> bool {
+    render_confirm_dialog(
+        ctx,
+        "Confirm Remove",
+        &format!("Remove {name} from the inventory?"),
+        "Remove",
+        open,
+    )
+}
+
+/// Centered dialog asking `question`, with a red `action` button and Cancel.
+/// Returns true when confirmed; either button sets `open` to false.
+fn render_confirm_dialog(
+    ctx: &egui::Context,
+    title: &str,
+    question: &str,
+    action: &str,
+    open: &mut bool,
+) -> bool {
+    let mut confirmed = false;
+    egui::Window::new(title)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .frame(
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(8.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::same(20)),
+        )
+        .show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(question)
+                        .size(18.0)
+                        .color(palette().text),
+                );
+                ui.add_space(16.0);
+                ui.horizontal(|ui| {
+                    let action_btn = ui.add(
+                        egui::Button::new(
+                            egui::RichText::new(action)
+                                .size(14.0)
+                                .color(egui::Color32::WHITE),
+                        )
+                        .fill(egui::Color32::from_rgb(0xCC, 0x33, 0x33))
+                        .corner_radius(4.0),
+                    );
+                    if action_btn.clicked() {
+                        confirmed = true;
+                        *open = false;
+                    }
+                    ui.add_space(8.0);
+                    let cancel_btn = ui.add(
+                        egui::Button::new(
+                            egui::RichText::new("Cancel")
+                                .size(14.0)
+                                .color(palette().text),
+                        )
+                        .fill(palette().main)
+                        .stroke(egui::Stroke::new(1.0, palette().stroke))
+                        .corner_radius(4.0),
+                    );
+                    if cancel_btn.clicked() {
+                        *open = false;
+                    }
+                });
+                ui.add_space(4.0);
+            });
+        });
+    confirmed
+}
+
+/// Banner shown while a respec simulation runs. Returns the chosen action.
+pub(super) fn render_simulation_banner(ctx: &egui::Context) -> Option<SimulationAction> {
+    let mut action = None;
+    egui::Area::new(egui::Id::new("simulation_banner"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 8.0])
+        .show(ctx, |ui| {
+            egui::Frame::new()
+                .fill(palette().secondary)
+                .corner_radius(6.0)
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
+                .inner_margin(egui::Margin::symmetric(12, 6))
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new("Simulating respec (not saved)")
+                                .color(palette().text),
+                        );
+                        if ui.button("Commit").clicked() {
+                            action = Some(SimulationAction::Commit);
+                        }
+                        if ui.button("Discard").clicked() {
+                            action = Some(SimulationAction::Discard);
+                        }
+                    });
+                });
+        });
+    action
+}
+
+/// State of the manual price prompt for items without a registry value.
+#[derive(Clone)]
+struct SellPromptState {
+    index: usize,
+    input: String,
+}
+
+fn sell_prompt_id() -> egui::Id {
+    egui::Id::new("sell_prompt")
+}
+
+/// Asks for a price before selling the inventory item at `index`.
+pub(super) fn open_sell_prompt(ctx: &egui::Context, index: usize) {
+    ctx.data_mut(|d| {
+        d.insert_temp(
+            sell_prompt_id(),
+            SellPromptState {
+                index,
+                input: String::new(),
+            },
+        )
+    });
+}
+
+/// Renders the manual price prompt, if open. Returns the item index and the
+/// entered price in base currency units once confirmed.
+pub(super) fn render_sell_prompt(ctx: &egui::Context, base_unit: &str) -> Option<(usize, u64)> {
+    let mut state: SellPromptState = ctx.data(|d| d.get_temp(sell_prompt_id()))?;
+    let mut open = true;
+    let mut done = false;
+    let mut sold = None;
+    egui::Window::new("Sell item")
+        .collapsible(false)
+        .resizable(false)
+        .open(&mut open)
+        .show(ctx, |ui| {
+            ui.label("This item has no listed value.");
+            ui.horizontal(|ui| {
+                ui.label(format!("Price ({base_unit}):"));
+                ui.add(egui::TextEdit::singleline(&mut state.input).desired_width(80.0));
+            });
+            state.input.retain(|c| c.is_ascii_digit());
+            let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+            ui.horizontal(|ui| {
+                if ui.button("Sell").clicked() || enter_pressed {
+                    if let Ok(price) = state.input.parse::<u64>() {
+                        sold = Some((state.index, price));
+                    }
+                    done = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    done = true;
+                }
+            });
+        });
+    if done || !open {
+        ctx.data_mut(|d| d.remove::<SellPromptState>(sell_prompt_id()));
+    } else {
+        ctx.data_mut(|d| d.insert_temp(sell_prompt_id(), state));
+    }
+    sold
+}
@@ -1,9 +1,12 @@
+use std::collections::BTreeMap;
+
 use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
 use crate::events::{
-    CreateItem, ExperienceChanged, InventoryChanged, LearnAbility, LearnTrait, ResourceChanged,
-    UpgradeEvent, WalletChanged,
+    ActivateAbility, ConditionsChanged, CreateItem, DeleteRegistryEntry, ExperienceChanged,
+    HotbarChanged, IdentityChanged, InventoryChanged, LearnAbility, LearnTrait, LoadoutChanged,
+    ResourceChanged, RollCheck, RollDamage, UpgradeEvent, WalletChanged,
 };
 
 #[derive(Resource, Default)]
@@ -15,16 +18,51 @@ pub(super) struct LearnAbilityOpen(pub bool);
 #[derive(Resource, Default)]
 pub(super) struct LearnTraitOpen(pub bool);
 
+#[derive(Resource, Default)]
+pub(super) struct InspectJsonOpen(pub bool);
+
+#[derive(Resource, Default)]
+pub(super) struct EffectsOpen(pub bool);
+
+#[derive(Resource, Default)]
+pub(super) struct GlossaryOpen(pub bool);
+
+#[derive(Resource, Default)]
+pub(super) struct BuffsOpen(pub bool);
+
+#[derive(Resource, Default)]
+pub(super) struct NotesOpen(pub bool);
+
+/// Whether the "Respec" confirmation dialog is shown.
+#[derive(Resource, Default)]
+pub(super) struct RespecConfirm(pub bool);
+
+/// Inventory index and name of the item waiting for a removal confirmation.
+#[derive(Resource, Default)]
+pub(super) struct RemoveItemConfirm(pub Option<(usize, String)>);
+
+/// Breakdown of the last damage roll of each weapon, by weapon name.
+#[derive(Resource, Default)]
+pub(super) struct DamageRolls(pub BTreeMap<String, String>);
+
 #[derive(SystemParam)]
 pub(super) struct UiEvents<'w> {
+    pub identity: MessageWriter<'w, IdentityChanged>,
     pub resource: MessageWriter<'w, ResourceChanged>,
+    pub conditions: MessageWriter<'w, ConditionsChanged>,
     pub wallet: MessageWriter<'w, WalletChanged>,
     pub inventory: MessageWriter<'w, InventoryChanged>,
+    pub loadout: MessageWriter<'w, LoadoutChanged>,
     pub experience: MessageWriter<'w, ExperienceChanged>,
     pub upgrade: MessageWriter<'w, UpgradeEvent>,
     pub learn_ability: MessageWriter<'w, LearnAbility>,
     pub learn_trait: MessageWriter<'w, LearnTrait>,
     pub create_item: MessageWriter<'w, CreateItem>,
+    pub delete_entry: MessageWriter<'w, DeleteRegistryEntry>,
+    pub hotbar: MessageWriter<'w, HotbarChanged>,
+    pub activate_ability: MessageWriter<'w, ActivateAbility>,
+    pub roll: MessageWriter<'w, RollCheck>,
+    pub roll_damage: MessageWriter<'w, RollDamage>,
 }
 
 #[derive(SystemParam)]
@@ -35,6 +73,8 @@ pub(super) struct Registries<'w> {
     pub weapons: Res<'w, crate::network::ClientWeaponRegistry>,
     pub equipment: Res<'w, crate::network::ClientEquipmentRegistry>,
     pub items: Res<'w, crate::network::ClientItemRegistry>,
+    pub builtin: Res<'w, crate::network::BuiltinEntries>,
+    pub ruleset: Res<'w, crate::network::ClientRuleset>,
 }
 
 #[derive(SystemParam)]
@@ -43,4 +83,22 @@ pub(super) struct UiModals<'w> {
     pub learn_ability: ResMut<'w, LearnAbilityOpen>,
     pub learn_trait: ResMut<'w, LearnTraitOpen>,
     pub create_item: ResMut<'w, crate::create_item::CreateItemOpen>,
+    pub inspect_json: ResMut<'w, InspectJsonOpen>,
+    pub effects: ResMut<'w, EffectsOpen>,
+    pub glossary: ResMut<'w, GlossaryOpen>,
+    pub buffs: ResMut<'w, BuffsOpen>,
+    pub notes: ResMut<'w, NotesOpen>,
+    pub respec: ResMut<'w, RespecConfirm>,
+    pub remove_item: ResMut<'w, RemoveItemConfirm>,
+    pub screenshot: ResMut<'w, crate::screenshot::SheetScreenshot>,
+    pub damage_rolls: Res<'w, DamageRolls>,
+    pub settings: ResMut<'w, super::settings::Settings>,
+}
+
+/// What the connection knows about the open sheet's server side.
+#[derive(SystemParam)]
+pub(super) struct Session<'w> {
+    pub following: Res<'w, crate::presence::Following>,
+    pub admin: Res<'w, crate::network::AdminSession>,
+    pub base_version: Res<'w, crate::conflict::BaseVersion>,
 }
@@ -0,0 +1,61 @@
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use shared::ClientMessage;
+
+use crate::components::ActiveCharacter;
+use crate::conflict::{RemoteSave, SavedCharacter};
+use crate::network::PendingClientMessages;
+use crate::state::AppScreen;
+use crate::toasts::{ToastLevel, Toasts};
+
+use super::layout::{build_character_from_components, CharacterQueryData};
+
+/// Reloads the open sheet when another connection saves it. A sheet with
+/// edits of its own is left alone, so the next save runs into the usual
+/// conflict prompt instead of silently dropping them.
+pub(super) fn apply_remote_save(
+    mut remote: ResMut<RemoteSave>,
+    saved: Res<SavedCharacter>,
+    character: Query<CharacterQueryData, With<ActiveCharacter>>,
+    mut pending: ResMut<PendingClientMessages>,
+    mut toasts: ResMut<Toasts>,
+    screen: Res<State<AppScreen>>,
+) {
+    let Some(summary) = remote.0.take() else {
+        return;
+    };
+    if *screen.get() != AppScreen::CharacterSheet {
+        return;
+    }
+    let Ok(character) = character.single() else {
+        return;
+    };
+    let current = build_character_from_components(&character);
+    if current.id != summary.id {
+        return;
+    }
+    let edited = character.simulating.is_some()
+        || saved
+            .0
+            .as_ref()
+            .is_none_or(|saved| shared::significant_change(saved, &current, &BTreeSet::new()));
+    if edited {
+        toasts.push(
+            ToastLevel::Warning,
+            format!(
+                "{} was saved elsewhere; saving will ask which version to keep",
+                summary.name
+            ),
+        );
+        return;
+    }
+    pending.push(ClientMessage::RequestCharacterVersion {
+        id: summary.id,
+        version: None,
+    });
+    toasts.push(
+        ToastLevel::Info,
+        format!("{} was saved elsewhere and reloaded", summary.name),
+    );
+}
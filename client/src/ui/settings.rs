@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use ui_widgets::colors::Theme;
+
+/// File the settings are kept in between sessions on native.
+#[cfg(not(target_arch = "wasm32"))]
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Client preferences, toggled from the portrait menu.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(super) struct Settings {
+    /// Ask before deleting an inventory item for good.
+    pub confirm_destructive: bool,
+    /// Light or dark UI.
+    #[serde(with = "theme_name")]
+    pub theme: Theme,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            confirm_destructive: true,
+            theme: Theme::default(),
+        }
+    }
+}
+
+/// Stores a [`Theme`] as `"light"` or `"dark"`; anything else reads as light.
+mod theme_name {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use ui_widgets::colors::Theme;
+
+    pub fn serialize<S: Serializer>(theme: &Theme, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match theme {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        })
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Theme, D::Error> {
+        Ok(match String::deserialize(deserializer)?.as_str() {
+            "dark" => Theme::Dark,
+            _ => Theme::Light,
+        })
+    }
+}
+
+impl Settings {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(super) fn load() -> Self {
+        std::fs::read_to_string(SETTINGS_FILE)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub(super) fn load() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_settings_file(settings: &Settings) {
+    let result = serde_json::to_string_pretty(settings)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(SETTINGS_FILE, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("Failed to save {SETTINGS_FILE}: {e}");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_settings_file(_settings: &Settings) {}
+
+/// Saves the settings whenever they change after startup.
+pub(super) fn save_settings(settings: Res<Settings>) {
+    if settings.is_changed() && !settings.is_added() {
+        save_settings_file(&settings);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_is_kept_in_settings_file() {
+        let settings = Settings {
+            theme: Theme::Dark,
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(json.contains(r#""theme":"dark""#));
+        assert_eq!(serde_json::from_str::<Settings>(&json).unwrap(), settings);
+
+        // Files written before the theme moved here keep the light default
+        let older: Settings = serde_json::from_str(r#"{ "confirm_destructive": false }"#).unwrap();
+        assert_eq!(older.theme, Theme::Light);
+        assert!(!older.confirm_destructive);
+    }
+}
@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use ui_widgets::colors::Theme;
+use ui_widgets::styles::UiStyle;
+
+use super::settings::Settings;
+
+/// Restyles egui and switches the widget palette whenever the theme in the
+/// settings changes.
+pub(super) fn apply_theme(
+    mut contexts: EguiContexts,
+    settings: Res<Settings>,
+    mut applied: Local<Option<Theme>>,
+) -> Result {
+    if *applied == Some(settings.theme) {
+        return Ok(());
+    }
+    let ctx = contexts.ctx_mut()?;
+    UiStyle::apply_global_style(ctx, settings.theme.palette());
+    *applied = Some(settings.theme);
+    Ok(())
+}
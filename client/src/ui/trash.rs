@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use shared::InventoryItem;
+use ui_widgets::colors::palette;
+use uuid::Uuid;
+
+use crate::components::{ActiveCharacter, CharacterId};
+use crate::events::InventoryChanged;
+
+/// Number of removed items kept for restoring.
+const TRASH_LEN: usize = 10;
+/// How long the "undo remove" snackbar stays on screen, in seconds.
+const UNDO_SECS: f64 = 5.0;
+
+struct TrashedItem {
+    character: Uuid,
+    item: InventoryItem,
+    /// egui time at which the snackbar was first shown.
+    shown_at: Option<f64>,
+}
+
+/// Items recently removed from inventories during this session, newest last.
+#[derive(Resource, Default)]
+pub struct InventoryTrash {
+    entries: VecDeque<TrashedItem>,
+    open: bool,
+}
+
+impl InventoryTrash {
+    pub fn push(&mut self, character: Uuid, item: InventoryItem) {
+        self.entries.push_back(TrashedItem {
+            character,
+            item,
+            shown_at: None,
+        });
+        while self.entries.len() > TRASH_LEN {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Takes the most recently removed item of `character` out of the trash.
+    pub fn take_last(&mut self, character: Uuid) -> Option<InventoryItem> {
+        let index = self
+            .entries
+            .iter()
+            .rposition(|entry| entry.character == character)?;
+        self.entries.remove(index).map(|entry| entry.item)
+    }
+}
+
+fn item_label(item: &InventoryItem) -> String {
+    match item {
+        InventoryItem::Weapon(name) => format!("{name} (weapon)"),
+        InventoryItem::Equipment(name) => format!("{name} (equipment)"),
+        InventoryItem::Item(name) => name.clone(),
+    }
+}
+
+/// Offers undo for a few seconds after each removal, plus a list of the
+/// active character's trashed items with restore buttons.
+pub(super) fn render_inventory_trash(
+    mut contexts: EguiContexts,
+    mut trash: ResMut<InventoryTrash>,
+    character: Query<&CharacterId, With<ActiveCharacter>>,
+    mut inventory: MessageWriter<InventoryChanged>,
+) -> Result {
+    let Ok(&CharacterId(id)) = character.single() else {
+        return Ok(());
+    };
+    if !trash.entries.iter().any(|entry| entry.character == id) {
+        return Ok(());
+    }
+    let ctx = contexts.ctx_mut()?;
+    let now = ctx.input(|i| i.time);
+
+    let trash = &mut *trash;
+    for entry in trash.entries.iter_mut() {
+        entry.shown_at.get_or_insert(now);
+    }
+    let latest = trash
+        .entries
+        .iter()
+        .rev()
+        .find(|entry| entry.character == id)
+        .filter(|entry| entry.shown_at.is_some_and(|t| now - t < UNDO_SECS));
+    if latest.is_some() {
+        ctx.request_repaint_after(std::time::Duration::from_millis(250));
+    }
+
+    let mut undo = false;
+    let mut toggle_list = false;
+    egui::Area::new(egui::Id::new("inventory_trash"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -12.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if let Some(entry) = latest {
+                    egui::Frame::new()
+                        .fill(palette().secondary)
+                        .corner_radius(6.0)
+                        .stroke(egui::Stroke::new(1.0, palette().stroke))
+                        .inner_margin(egui::Margin::symmetric(10, 6))
+                        .show(ui, |ui| {
+                            ui.label(
+                                egui::RichText::new(format!("Removed {}", item_label(&entry.item)))
+                                    .size(12.0)
+                                    .color(palette().text),
+                            );
+                            if ui.small_button("Undo").clicked() {
+                                undo = true;
+                            }
+                        });
+                }
+                let count = trash.entries.iter().filter(|e| e.character == id).count();
+                if ui.small_button(format!("Trash ({count})")).clicked() {
+                    toggle_list = true;
+                }
+            });
+        });
+    if toggle_list {
+        trash.open = !trash.open;
+    }
+    if undo {
+        if let Some(item) = trash.take_last(id) {
+            inventory.write(InventoryChanged::AddExisting(item));
+        }
+    }
+
+    if trash.open {
+        let mut open = true;
+        let mut restore = None;
+        egui::Window::new("Removed items")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .frame(
+                egui::Frame::new()
+                    .fill(palette().secondary)
+                    .corner_radius(8.0)
+                    .stroke(egui::Stroke::new(1.0, palette().stroke))
+                    .inner_margin(egui::Margin::same(12)),
+            )
+            .show(ctx, |ui| {
+                for (index, entry) in trash.entries.iter().enumerate().rev() {
+                    if entry.character != id {
+                        continue;
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(item_label(&entry.item)).color(palette().text),
+                        );
+                        if ui.small_button("Restore").clicked() {
+                            restore = Some(index);
+                        }
+                    });
+                }
+            });
+        trash.open = open;
+        if let Some(entry) = restore.and_then(|index| trash.entries.remove(index)) {
+            inventory.write(InventoryChanged::AddExisting(entry.item));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use std::collections::VecDeque;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::components::{spawn_character, ActiveCharacter, PortraitTexture};
+use crate::events::{
+    ActivateAbility, ConditionsChanged, CreateItem, ExperienceChanged, HotbarChanged,
+    IdentityChanged, InventoryChanged, LearnAbility, LearnTrait, LevelUp, LoadoutChanged,
+    ResourceChanged, TempEffectsChanged, UpgradeEvent, WalletChanged,
+};
+use crate::simulation::Simulating;
+
+use super::layout::{build_character_from_components, CharacterQueryData};
+
+/// How many edits can be undone.
+const UNDO_LIMIT: usize = 50;
+
+/// Snapshots of the active character taken before each sheet edit.
+#[derive(Resource, Default)]
+pub(super) struct UndoStack {
+    undo: VecDeque<shared::Character>,
+    redo: Vec<shared::Character>,
+}
+
+impl UndoStack {
+    /// Remembers the state before an edit. A new edit drops the redo history,
+    /// and switching characters drops everything recorded for the previous one.
+    fn record(&mut self, before: shared::Character) {
+        if self.undo.back().is_some_and(|last| last.id != before.id) {
+            self.clear();
+        }
+        self.undo.push_back(before);
+        if self.undo.len() > UNDO_LIMIT {
+            self.undo.pop_front();
+        }
+        self.redo.clear();
+    }
+
+    /// Trades `current` for the state before the last edit.
+    fn undo(&mut self, current: shared::Character) -> Option<shared::Character> {
+        let previous = self.undo.pop_back()?;
+        if previous.id != current.id {
+            self.clear();
+            return None;
+        }
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// Trades `current` for the state the last undo left.
+    fn redo(&mut self, current: shared::Character) -> Option<shared::Character> {
+        let next = self.redo.pop()?;
+        if next.id != current.id {
+            self.clear();
+            return None;
+        }
+        self.undo.push_back(current);
+        Some(next)
+    }
+
+    fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+/// Every message whose `apply_*` system changes the character.
+#[derive(SystemParam)]
+pub(super) struct SheetEdits<'w, 's> {
+    resource: MessageReader<'w, 's, ResourceChanged>,
+    wallet: MessageReader<'w, 's, WalletChanged>,
+    inventory: MessageReader<'w, 's, InventoryChanged>,
+    loadout: MessageReader<'w, 's, LoadoutChanged>,
+    experience: MessageReader<'w, 's, ExperienceChanged>,
+    level_up: MessageReader<'w, 's, LevelUp>,
+    upgrade: MessageReader<'w, 's, UpgradeEvent>,
+    learn_ability: MessageReader<'w, 's, LearnAbility>,
+    learn_trait: MessageReader<'w, 's, LearnTrait>,
+    hotbar: MessageReader<'w, 's, HotbarChanged>,
+    activate_ability: MessageReader<'w, 's, ActivateAbility>,
+    temp_effects: MessageReader<'w, 's, TempEffectsChanged>,
+    create_item: MessageReader<'w, 's, CreateItem>,
+    identity: MessageReader<'w, 's, IdentityChanged>,
+    conditions: MessageReader<'w, 's, ConditionsChanged>,
+}
+
+impl SheetEdits<'_, '_> {
+    /// Whether any edit arrived since the last call.
+    fn any(&mut self) -> bool {
+        let counts = [
+            self.resource.read().count(),
+            self.wallet.read().count(),
+            self.inventory.read().count(),
+            self.loadout.read().count(),
+            self.experience.read().count(),
+            self.level_up.read().count(),
+            self.upgrade.read().count(),
+            self.learn_ability.read().count(),
+            self.learn_trait.read().count(),
+            self.hotbar.read().count(),
+            self.activate_ability.read().count(),
+            self.temp_effects.read().count(),
+            self.create_item.read().count(),
+            self.identity.read().count(),
+            self.conditions.read().count(),
+        ];
+        counts.iter().any(|&count| count > 0)
+    }
+}
+
+/// Snapshots the active character before this frame's edits are applied.
+pub(super) fn record_undo_snapshot(
+    mut edits: SheetEdits,
+    character: Query<CharacterQueryData, With<ActiveCharacter>>,
+    mut stack: ResMut<UndoStack>,
+) {
+    if !edits.any() {
+        return;
+    }
+    let Ok(character) = character.single() else {
+        return;
+    };
+    stack.record(build_character_from_components(&character));
+}
+
+/// Ctrl+Z undoes the last edit, Ctrl+Y or Ctrl+Shift+Z redoes it.
+/// The snapshot is respawned like a character loaded from the server, so
+/// effects, maximum HP and mana are recalculated the same way.
+pub(super) fn undo_shortcuts(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    character: Query<(Entity, CharacterQueryData), With<ActiveCharacter>>,
+    mut stack: ResMut<UndoStack>,
+    ruleset: Res<crate::network::ClientRuleset>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let undo = !shift && keys.just_pressed(KeyCode::KeyZ);
+    let redo = keys.just_pressed(KeyCode::KeyY) || (shift && keys.just_pressed(KeyCode::KeyZ));
+    if !undo && !redo {
+        return;
+    }
+    let Ok((entity, character)) = character.single() else {
+        return;
+    };
+    let current = build_character_from_components(&character);
+    let snapshot = if undo {
+        stack.undo(current)
+    } else {
+        stack.redo(current)
+    };
+    let Some(snapshot) = snapshot else {
+        return;
+    };
+
+    commands.entity(entity).despawn();
+    let restored = spawn_character(&mut commands, &snapshot, &ruleset.campaign);
+    if let Some(portrait) = character.portrait {
+        commands
+            .entity(restored)
+            .insert(PortraitTexture(portrait.0.clone()));
+    }
+    if let Some(simulating) = character.simulating {
+        commands.entity(restored).insert(Simulating {
+            original: simulating.original,
+        });
+    }
+}
+
+pub(super) fn clear_undo_stack(mut stack: ResMut<UndoStack>) {
+    stack.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_skill_points(character: &shared::Character, skill_points: u32) -> shared::Character {
+        shared::Character {
+            skill_points,
+            ..character.clone()
+        }
+    }
+
+    #[test]
+    fn test_undo_redo_round_trip() {
+        let base = shared::Character::new("Undo".to_string());
+        let mut stack = UndoStack::default();
+
+        stack.record(with_skill_points(&base, 1));
+        stack.record(with_skill_points(&base, 2));
+
+        let undone = stack.undo(with_skill_points(&base, 3)).unwrap();
+        assert_eq!(undone.skill_points, 2);
+        let redone = stack.redo(undone).unwrap();
+        assert_eq!(redone.skill_points, 3);
+
+        let undone = stack.undo(redone).unwrap();
+        stack.record(undone);
+        assert!(stack.redo(with_skill_points(&base, 4)).is_none());
+    }
+
+    #[test]
+    fn test_record_caps_history_and_resets_on_character_switch() {
+        let first = shared::Character::new("First".to_string());
+        let second = shared::Character::new("Second".to_string());
+        let mut stack = UndoStack::default();
+
+        for points in 0..UNDO_LIMIT as u32 + 5 {
+            stack.record(with_skill_points(&first, points));
+        }
+        assert_eq!(stack.undo.len(), UNDO_LIMIT);
+        assert_eq!(stack.undo.front().unwrap().skill_points, 5);
+
+        stack.record(second);
+        assert_eq!(stack.undo.len(), 1);
+        assert!(stack.undo(first).is_none());
+    }
+}
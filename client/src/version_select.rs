@@ -1,10 +1,10 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
-use shared::VersionSummary;
-use ui_widgets::colors::{MAIN_COLOR, SECONDARY_COLOR, STROKE_COLOR, TEXT_COLOR};
+use shared::{AuditEntry, DiffEntry, VersionSummary};
+use ui_widgets::colors::palette;
 use uuid::Uuid;
 
-use crate::network::PendingClientMessages;
+use crate::network::{AdminSession, PendingClientMessages};
 use crate::state::AppScreen;
 
 /// Holds the version list for the currently selected character.
@@ -13,12 +13,77 @@ pub struct VersionList {
     pub character_id: Uuid,
     pub character_name: String,
     pub versions: Vec<VersionSummary>,
+    /// Last diff the server sent for this character.
+    pub diff: Option<VersionDiff>,
+    /// Last audit log the server sent for this character.
+    pub audit: Option<Vec<AuditEntry>>,
 }
 
-/// Pending delete confirmation state.
+/// Changes from version `from` to version `to`.
+#[derive(Debug, Clone)]
+pub struct VersionDiff {
+    pub from: u32,
+    pub to: u32,
+    pub changes: Vec<DiffEntry>,
+}
+
+/// Versions ticked for comparison, at most two, and the pair whose diff
+/// was last requested.
+#[derive(Resource, Default)]
+struct CompareSelection {
+    versions: Vec<u32>,
+    requested: Option<(u32, u32)>,
+}
+
+impl CompareSelection {
+    /// Ticks or unticks `version`. Ticking a third drops the oldest tick.
+    fn toggle(&mut self, version: u32) {
+        if let Some(index) = self.versions.iter().position(|&v| v == version) {
+            self.versions.remove(index);
+        } else {
+            self.versions.push(version);
+            if self.versions.len() > 2 {
+                self.versions.remove(0);
+            }
+        }
+    }
+
+    /// The ticked pair, older version first.
+    fn pair(&self) -> Option<(u32, u32)> {
+        match self.versions[..] {
+            [a, b] => Some((a.min(b), a.max(b))),
+            _ => None,
+        }
+    }
+}
+
+/// Pending delete confirmation state: a single version, or a prune down
+/// to the newest `prune` versions.
 #[derive(Resource, Default)]
 struct DeleteConfirm {
     version: Option<u32>,
+    prune: Option<u32>,
+}
+
+/// How many of the newest versions a prune keeps.
+#[derive(Resource)]
+struct PruneKeep(u32);
+
+impl Default for PruneKeep {
+    fn default() -> Self {
+        Self(10)
+    }
+}
+
+/// Whether the audit log is shown under the version list (admin only).
+#[derive(Resource, Default)]
+struct AuditOpen(bool);
+
+/// Version whose label is being edited inline, with the text typed so far.
+#[derive(Resource, Default)]
+struct LabelEdit {
+    version: Option<u32>,
+    text: String,
 }
 
 pub struct VersionSelectPlugin;
@@ -27,6 +92,10 @@ impl Plugin for VersionSelectPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<VersionList>()
             .init_resource::<DeleteConfirm>()
+            .init_resource::<PruneKeep>()
+            .init_resource::<LabelEdit>()
+            .init_resource::<CompareSelection>()
+            .init_resource::<AuditOpen>()
             .add_systems(
                 EguiPrimaryContextPass,
                 render_version_select.run_if(in_state(AppScreen::VersionSelect)),
@@ -40,20 +109,42 @@ fn render_version_select(
     mut pending_messages: ResMut<PendingClientMessages>,
     mut next_state: ResMut<NextState<AppScreen>>,
     mut delete_confirm: ResMut<DeleteConfirm>,
+    mut label_edit: ResMut<LabelEdit>,
+    mut compare: ResMut<CompareSelection>,
+    mut prune_keep: ResMut<PruneKeep>,
+    mut audit_open: ResMut<AuditOpen>,
+    admin: Res<AdminSession>,
 ) -> Result {
     let ctx = contexts.ctx_mut()?;
 
     egui::CentralPanel::default()
-        .frame(egui::Frame::NONE.fill(MAIN_COLOR))
+        .frame(egui::Frame::NONE.fill(palette().main))
         .show(ctx, |_ui| {});
 
     let screen_rect = ctx.viewport_rect();
-    let panel_width = (screen_rect.width() * 0.4).max(340.0).min(500.0);
+    let panel_width = (screen_rect.width() * 0.4).clamp(340.0, 500.0);
     let scroll_height = (screen_rect.height() * 0.6).max(350.0);
 
     let mut selected_version: Option<u32> = None;
     let mut go_back = false;
     let mut request_delete: Option<u32> = None;
+    let mut restore: Option<u32> = None;
+    let mut new_label: Option<(u32, String)> = None;
+    let mut toggle_compare: Option<u32> = None;
+    let mut request_prune: Option<u32> = None;
+    let mut toggle_audit = false;
+
+    // Deleted versions can't stay ticked
+    compare
+        .versions
+        .retain(|v| version_list.versions.iter().any(|s| s.version == *v));
+    let pair = compare.pair();
+    let show_audit = admin.0 && audit_open.0;
+    let list_height = if pair.is_some() || show_audit {
+        scroll_height * 0.6
+    } else {
+        scroll_height
+    };
 
     egui::Window::new("Select Version")
         .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
@@ -62,9 +153,9 @@ fn render_version_select(
         .resizable(false)
         .frame(
             egui::Frame::new()
-                .fill(SECONDARY_COLOR)
+                .fill(palette().secondary)
                 .corner_radius(8.0)
-                .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
+                .stroke(egui::Stroke::new(1.0, palette().stroke))
                 .inner_margin(egui::Margin::same(20)),
         )
         .min_width(panel_width)
@@ -75,7 +166,7 @@ fn render_version_select(
                 ui.label(
                     egui::RichText::new(&version_list.character_name)
                         .size(24.0)
-                        .color(TEXT_COLOR)
+                        .color(palette().text)
                         .strong(),
                 );
                 ui.add_space(4.0);
@@ -95,9 +186,9 @@ fn render_version_select(
                     egui::Button::new(
                         egui::RichText::new("< Back to Characters")
                             .size(14.0)
-                            .color(TEXT_COLOR),
+                            .color(palette().text),
                     )
-                    .fill(SECONDARY_COLOR)
+                    .fill(palette().secondary)
                     .stroke(egui::Stroke::NONE),
                 )
                 .clicked()
@@ -107,26 +198,123 @@ fn render_version_select(
 
             ui.add_space(8.0);
 
+            let count = version_list.versions.len() as u32;
+            if admin.0 {
+                ui.horizontal(|ui| {
+                    if count > 1 {
+                        ui.label(
+                            egui::RichText::new("Keep newest")
+                                .size(13.0)
+                                .color(palette().text),
+                        );
+                        ui.add(egui::DragValue::new(&mut prune_keep.0).range(1..=count - 1));
+                        let prune_btn = ui
+                            .small_button("Prune older versions")
+                            .on_hover_text("Delete every version but the newest ones");
+                        if prune_btn.clicked() {
+                            request_prune = Some(prune_keep.0.clamp(1, count - 1));
+                        }
+                    }
+                    let audit_label = if audit_open.0 {
+                        "Hide audit log"
+                    } else {
+                        "Audit log"
+                    };
+                    let audit_btn = ui
+                        .small_button(audit_label)
+                        .on_hover_text("Every change saved to this character");
+                    if audit_btn.clicked() {
+                        toggle_audit = true;
+                    }
+                });
+                ui.add_space(8.0);
+            }
+
             let can_delete = version_list.versions.len() > 1;
+            let latest = version_list.versions.last().map(|v| v.version);
             egui::ScrollArea::vertical()
-                .max_height(scroll_height)
+                .max_height(list_height)
                 .show(ui, |ui| {
                     // Iterate in reverse to show newest first
                     for version in version_list.versions.iter().rev() {
-                        let action = render_version_entry(ui, version, can_delete);
+                        let can_restore = Some(version.version) != latest;
+                        let action = render_version_entry(
+                            ui,
+                            version,
+                            can_delete,
+                            can_restore,
+                            compare.versions.contains(&version.version),
+                            admin.0.then_some(&mut *label_edit),
+                        );
                         if action.selected {
                             selected_version = Some(version.version);
                         }
                         if action.delete {
                             request_delete = Some(version.version);
                         }
+                        if action.restore {
+                            restore = Some(version.version);
+                        }
+                        if let Some(label) = action.label {
+                            new_label = Some((version.version, label));
+                        }
+                        if action.compare {
+                            toggle_compare = Some(version.version);
+                        }
                         ui.add_space(6.0);
                     }
                 });
+
+            if let Some((from, to)) = pair {
+                ui.add_space(8.0);
+                ui.separator();
+                let diff = version_list
+                    .diff
+                    .as_ref()
+                    .filter(|d| (d.from, d.to) == (from, to));
+                render_diff(ui, from, to, diff, scroll_height * 0.4);
+            }
+
+            if show_audit {
+                ui.add_space(8.0);
+                ui.separator();
+                render_audit_log(ui, version_list.audit.as_deref(), scroll_height * 0.4);
+            }
         });
 
+    if toggle_audit {
+        audit_open.0 = !audit_open.0;
+        if audit_open.0 {
+            pending_messages
+                .0
+                .push(shared::ClientMessage::RequestAuditLog {
+                    id: version_list.character_id,
+                });
+        }
+    }
+
+    if let Some(version) = toggle_compare {
+        compare.toggle(version);
+    }
+
+    if let Some((from, to)) = compare.pair() {
+        if compare.requested != Some((from, to)) {
+            compare.requested = Some((from, to));
+            pending_messages
+                .0
+                .push(shared::ClientMessage::RequestVersionDiff {
+                    id: version_list.character_id,
+                    from,
+                    to,
+                });
+        }
+    }
+
     if go_back {
-        delete_confirm.version = None;
+        *delete_confirm = DeleteConfirm::default();
+        label_edit.version = None;
+        *compare = CompareSelection::default();
+        audit_open.0 = false;
         next_state.set(AppScreen::CharacterSelect);
     }
 
@@ -139,12 +327,51 @@ fn render_version_select(
             });
     }
 
+    if let Some((version, label)) = new_label {
+        pending_messages
+            .0
+            .push(shared::ClientMessage::SetVersionLabel {
+                id: version_list.character_id,
+                version,
+                label,
+            });
+    }
+
+    if let Some(version) = restore {
+        pending_messages
+            .0
+            .push(shared::ClientMessage::RollbackVersion {
+                id: version_list.character_id,
+                version,
+            });
+    }
+
     if let Some(version) = request_delete {
         delete_confirm.version = Some(version);
     }
+    if let Some(keep) = request_prune {
+        delete_confirm.prune = Some(keep);
+    }
 
     // Confirmation dialog
-    if let Some(version) = delete_confirm.version {
+    let id = version_list.character_id;
+    let confirm = match *delete_confirm {
+        DeleteConfirm {
+            version: Some(version),
+            ..
+        } => Some((
+            format!("Delete version {}?", version),
+            shared::ClientMessage::DeleteVersion { id, version },
+        )),
+        DeleteConfirm {
+            prune: Some(keep), ..
+        } => Some((
+            format!("Delete all but the newest {} version(s)?", keep),
+            shared::ClientMessage::PruneVersions { id, keep },
+        )),
+        _ => None,
+    };
+    if let Some((prompt, message)) = confirm {
         let mut close = false;
         egui::Window::new("Confirm Delete")
             .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
@@ -153,19 +380,15 @@ fn render_version_select(
             .resizable(false)
             .frame(
                 egui::Frame::new()
-                    .fill(SECONDARY_COLOR)
+                    .fill(palette().secondary)
                     .corner_radius(8.0)
-                    .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
+                    .stroke(egui::Stroke::new(1.0, palette().stroke))
                     .inner_margin(egui::Margin::same(20)),
             )
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
                     ui.add_space(4.0);
-                    ui.label(
-                        egui::RichText::new(format!("Delete version {}?", version))
-                            .size(18.0)
-                            .color(TEXT_COLOR),
-                    );
+                    ui.label(egui::RichText::new(prompt).size(18.0).color(palette().text));
                     ui.add_space(16.0);
                     ui.horizontal(|ui| {
                         let delete_btn = ui.add(
@@ -178,21 +401,18 @@ fn render_version_select(
                             .corner_radius(4.0),
                         );
                         if delete_btn.clicked() {
-                            pending_messages
-                                .0
-                                .push(shared::ClientMessage::DeleteVersion {
-                                    id: version_list.character_id,
-                                    version,
-                                });
+                            pending_messages.0.push(message);
                             close = true;
                         }
                         ui.add_space(8.0);
                         let cancel_btn = ui.add(
                             egui::Button::new(
-                                egui::RichText::new("Cancel").size(14.0).color(TEXT_COLOR),
+                                egui::RichText::new("Cancel")
+                                    .size(14.0)
+                                    .color(palette().text),
                             )
-                            .fill(MAIN_COLOR)
-                            .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
+                            .fill(palette().main)
+                            .stroke(egui::Stroke::new(1.0, palette().stroke))
                             .corner_radius(4.0),
                         );
                         if cancel_btn.clicked() {
@@ -203,7 +423,7 @@ fn render_version_select(
                 });
             });
         if close {
-            delete_confirm.version = None;
+            *delete_confirm = DeleteConfirm::default();
         }
     }
 
@@ -213,28 +433,123 @@ fn render_version_select(
 struct VersionEntryAction {
     selected: bool,
     delete: bool,
+    restore: bool,
+    /// Label confirmed in the inline editor.
+    label: Option<String>,
+    /// The compare tick was toggled.
+    compare: bool,
 }
 
-/// Renders a single version entry as a clickable card with a delete button.
+/// Lists the changes from version `from` to `to` in a scroll area, or a
+/// placeholder while the server hasn't answered yet.
+fn render_diff(ui: &mut egui::Ui, from: u32, to: u32, diff: Option<&VersionDiff>, height: f32) {
+    let muted = egui::Color32::from_rgb(0x88, 0x88, 0x99);
+    ui.label(
+        egui::RichText::new(format!("Changes from version {from} to {to}"))
+            .size(15.0)
+            .color(palette().text)
+            .strong(),
+    );
+    ui.add_space(4.0);
+    let Some(diff) = diff else {
+        ui.label(egui::RichText::new("Loading…").size(13.0).color(muted));
+        return;
+    };
+    if diff.changes.is_empty() {
+        ui.label(egui::RichText::new("No changes").size(13.0).color(muted));
+        return;
+    }
+    egui::ScrollArea::vertical()
+        .id_salt("version_diff")
+        .max_height(height)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            for change in &diff.changes {
+                ui.label(
+                    egui::RichText::new(change.to_string())
+                        .size(13.0)
+                        .color(palette().text),
+                );
+            }
+        });
+}
+
+/// Lists the recorded mutations of the character, newest first, or a
+/// placeholder while the server hasn't answered yet.
+fn render_audit_log(ui: &mut egui::Ui, entries: Option<&[AuditEntry]>, height: f32) {
+    let muted = egui::Color32::from_rgb(0x88, 0x88, 0x99);
+    ui.label(
+        egui::RichText::new("Audit log")
+            .size(15.0)
+            .color(palette().text)
+            .strong(),
+    );
+    ui.add_space(4.0);
+    let Some(entries) = entries else {
+        ui.label(egui::RichText::new("Loading…").size(13.0).color(muted));
+        return;
+    };
+    if entries.is_empty() {
+        ui.label(
+            egui::RichText::new("Nothing recorded")
+                .size(13.0)
+                .color(muted),
+        );
+        return;
+    }
+    egui::ScrollArea::vertical()
+        .id_salt("audit_log")
+        .max_height(height)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            for entry in entries.iter().rev() {
+                let mut text = entry.action.to_string();
+                if let Some(version) = entry.version {
+                    text.push_str(&format!(" (v{version})"));
+                }
+                if let Some(by) = &entry.by {
+                    text.push_str(&format!(" by {by}"));
+                }
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format_timestamp(entry.at))
+                            .size(12.0)
+                            .color(muted),
+                    );
+                    ui.label(egui::RichText::new(text).size(13.0).color(palette().text));
+                });
+            }
+        });
+}
+
+/// Renders a single version entry as a clickable card with delete and
+/// restore buttons. The label can be edited when `label_edit` is given.
 fn render_version_entry(
     ui: &mut egui::Ui,
     version: &VersionSummary,
     can_delete: bool,
+    can_restore: bool,
+    compared: bool,
+    mut label_edit: Option<&mut LabelEdit>,
 ) -> VersionEntryAction {
     let id = ui.id().with(("version", version.version));
     let was_hovered = ui.data(|d| d.get_temp::<bool>(id).unwrap_or(false));
 
     let fill = if was_hovered {
-        MAIN_COLOR
+        palette().main
     } else {
-        SECONDARY_COLOR
+        palette().secondary
     };
 
     let mut delete_clicked = false;
+    let mut restore_clicked = false;
+    let mut compare_clicked = false;
+    let mut editing = false;
+    let mut label = None;
 
     let frame_response = egui::Frame::new()
         .corner_radius(6.0)
-        .stroke(egui::Stroke::new(1.0, STROKE_COLOR))
+        .stroke(egui::Stroke::new(1.0, palette().stroke))
         .inner_margin(egui::Margin::symmetric(14, 10))
         .fill(fill)
         .show(ui, |ui| {
@@ -243,8 +558,44 @@ fn render_version_entry(
                 ui.label(
                     egui::RichText::new(format!("Version {}", version.version))
                         .size(16.0)
-                        .color(TEXT_COLOR),
+                        .color(palette().text),
                 );
+                match label_edit.as_mut() {
+                    Some(edit) if edit.version == Some(version.version) => {
+                        editing = true;
+                        let field = ui.add(
+                            egui::TextEdit::singleline(&mut edit.text)
+                                .hint_text("Label")
+                                .desired_width(160.0),
+                        );
+                        if field.lost_focus() {
+                            if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                label = Some(std::mem::take(&mut edit.text));
+                            }
+                            edit.version = None;
+                        } else {
+                            field.request_focus();
+                        }
+                    }
+                    edit => {
+                        if let Some(text) = &version.label {
+                            ui.label(
+                                egui::RichText::new(text)
+                                    .size(14.0)
+                                    .italics()
+                                    .color(egui::Color32::from_rgb(0x88, 0x88, 0x99)),
+                            );
+                        }
+                        if let Some(edit) = edit {
+                            let pencil = ui.small_button("✏").on_hover_text("Edit label");
+                            if pencil.clicked() {
+                                edit.version = Some(version.version);
+                                edit.text = version.label.clone().unwrap_or_default();
+                                editing = true;
+                            }
+                        }
+                    }
+                }
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if can_delete {
                         let x_btn = ui.add(
@@ -261,6 +612,20 @@ fn render_version_entry(
                             delete_clicked = true;
                         }
                     }
+                    let compare_btn = ui
+                        .selectable_label(compared, "Compare")
+                        .on_hover_text("Tick two versions to see what changed between them");
+                    if compare_btn.clicked() {
+                        compare_clicked = true;
+                    }
+                    if can_restore {
+                        let restore_btn = ui
+                            .small_button("Restore this version")
+                            .on_hover_text("Save a copy of this version as the newest one");
+                        if restore_btn.clicked() {
+                            restore_clicked = true;
+                        }
+                    }
                     ui.label(
                         egui::RichText::new(format!("Level {}", version.level))
                             .size(13.0)
@@ -270,20 +635,32 @@ fn render_version_entry(
             });
             ui.add_space(2.0);
             ui.label(
-                egui::RichText::new(format_timestamp(version.saved_at))
-                    .size(13.0)
-                    .color(egui::Color32::from_rgb(0x88, 0x88, 0x99)),
+                egui::RichText::new(format!(
+                    "{} — saved by {}",
+                    format_timestamp(version.saved_at),
+                    version.saved_by.as_deref().unwrap_or("unknown")
+                ))
+                .size(13.0)
+                .color(egui::Color32::from_rgb(0x88, 0x88, 0x99)),
             );
         });
 
     let response = &frame_response.response;
     let hovered = response.contains_pointer();
-    let card_clicked = hovered && !delete_clicked && ui.input(|i| i.pointer.primary_clicked());
+    let card_clicked = hovered
+        && !delete_clicked
+        && !restore_clicked
+        && !compare_clicked
+        && !editing
+        && ui.input(|i| i.pointer.primary_clicked());
     ui.data_mut(|d| d.insert_temp(id, hovered));
 
     VersionEntryAction {
         selected: card_clicked,
         delete: delete_clicked,
+        restore: restore_clicked,
+        label,
+        compare: compare_clicked,
     }
 }
 
@@ -0,0 +1,248 @@
+use shared::{ConnectionId, ErrorCode, PresenceEntry, ServerMessage};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use uuid::Uuid;
+
+/// Server-side state of a single websocket connection.
+struct Connection {
+    outbox: mpsc::UnboundedSender<ServerMessage>,
+    viewing: Option<Uuid>,
+    following: Option<ConnectionId>,
+    admin: bool,
+    /// Password-protected characters this connection authenticated for.
+    unlocked: BTreeSet<Uuid>,
+}
+
+/// Registry of live connections: tracks what each client is viewing and
+/// routes messages between them (presence and follow mode).
+#[derive(Clone, Default)]
+pub struct Hub {
+    connections: Arc<RwLock<BTreeMap<ConnectionId, Connection>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Hub {
+    /// Registers a new connection. Messages for it arrive on the returned receiver.
+    pub async fn register(&self) -> (ConnectionId, mpsc::UnboundedReceiver<ServerMessage>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (outbox, inbox) = mpsc::unbounded_channel();
+        self.connections.write().await.insert(
+            id,
+            Connection {
+                outbox,
+                viewing: None,
+                following: None,
+                admin: false,
+                unlocked: BTreeSet::new(),
+            },
+        );
+        (id, inbox)
+    }
+
+    /// Removes a connection and ends follow mode for everyone following it.
+    pub async fn unregister(&self, id: ConnectionId) {
+        {
+            let mut connections = self.connections.write().await;
+            connections.remove(&id);
+            for conn in connections.values_mut() {
+                if conn.following == Some(id) {
+                    conn.following = None;
+                    let _ = conn
+                        .outbox
+                        .send(ServerMessage::FollowEnded { connection_id: id });
+                }
+            }
+        }
+        self.broadcast_presence().await;
+    }
+
+    /// Queues a message for a single connection.
+    pub async fn send(&self, id: ConnectionId, msg: ServerMessage) {
+        if let Some(conn) = self.connections.read().await.get(&id) {
+            let _ = conn.outbox.send(msg);
+        }
+    }
+
+    pub async fn set_admin(&self, id: ConnectionId, admin: bool) {
+        if let Some(conn) = self.connections.write().await.get_mut(&id) {
+            conn.admin = admin;
+        }
+    }
+
+    pub async fn is_admin(&self, id: ConnectionId) -> bool {
+        self.connections
+            .read()
+            .await
+            .get(&id)
+            .is_some_and(|conn| conn.admin)
+    }
+
+    /// Lets a connection edit the password-protected character `character`.
+    pub async fn unlock(&self, id: ConnectionId, character: Uuid) {
+        if let Some(conn) = self.connections.write().await.get_mut(&id) {
+            conn.unlocked.insert(character);
+        }
+    }
+
+    pub async fn has_unlocked(&self, id: ConnectionId, character: Uuid) -> bool {
+        self.connections
+            .read()
+            .await
+            .get(&id)
+            .is_some_and(|conn| conn.unlocked.contains(&character))
+    }
+
+    /// Character a connection currently has open, if any.
+    pub async fn viewing(&self, id: ConnectionId) -> Option<Uuid> {
+        self.connections.read().await.get(&id)?.viewing
+    }
+
+    /// Records what a connection is viewing and forwards the change to its followers.
+    pub async fn set_viewing(&self, id: ConnectionId, viewing: Option<Uuid>) {
+        {
+            let mut connections = self.connections.write().await;
+            let Some(conn) = connections.get_mut(&id) else {
+                return;
+            };
+            if conn.viewing == viewing {
+                return;
+            }
+            conn.viewing = viewing;
+        }
+        self.notify_followers(
+            id,
+            ServerMessage::FollowView {
+                connection_id: id,
+                id: viewing,
+            },
+        )
+        .await;
+        self.broadcast_presence().await;
+    }
+
+    /// Starts mirroring `target` on `follower`. Returns the target's current view.
+    pub async fn follow(
+        &self,
+        follower: ConnectionId,
+        target: ConnectionId,
+    ) -> Result<Option<Uuid>, (ErrorCode, String)> {
+        if follower == target {
+            return Err((ErrorCode::Invalid, "Cannot follow yourself".to_string()));
+        }
+        let mut connections = self.connections.write().await;
+        let viewing = connections
+            .get(&target)
+            .map(|conn| conn.viewing)
+            .ok_or_else(|| {
+                (
+                    ErrorCode::NotFound,
+                    format!("Connection {} not found", target),
+                )
+            })?;
+        if let Some(conn) = connections.get_mut(&follower) {
+            conn.following = Some(target);
+        }
+        Ok(viewing)
+    }
+
+    pub async fn unfollow(&self, follower: ConnectionId) {
+        if let Some(conn) = self.connections.write().await.get_mut(&follower) {
+            conn.following = None;
+        }
+    }
+
+    /// Sends a message to every connection currently following `id`.
+    pub async fn notify_followers(&self, id: ConnectionId, msg: ServerMessage) {
+        for conn in self.connections.read().await.values() {
+            if conn.following == Some(id) {
+                let _ = conn.outbox.send(msg.clone());
+            }
+        }
+    }
+
+    /// Sends a message to every connection currently viewing `character`.
+    pub async fn notify_viewers(&self, character: Uuid, msg: ServerMessage) {
+        for conn in self.connections.read().await.values() {
+            if conn.viewing == Some(character) {
+                let _ = conn.outbox.send(msg.clone());
+            }
+        }
+    }
+
+    /// Sends a message to every connection viewing `character` except `source`
+    /// and the connections following it, which are sent the saved version.
+    pub async fn notify_other_viewers(
+        &self,
+        character: Uuid,
+        source: ConnectionId,
+        msg: ServerMessage,
+    ) {
+        for (&id, conn) in self.connections.read().await.iter() {
+            if id != source && conn.following != Some(source) && conn.viewing == Some(character) {
+                let _ = conn.outbox.send(msg.clone());
+            }
+        }
+    }
+
+    /// Queues a message for every connection, including the sender.
+    pub async fn broadcast(&self, msg: ServerMessage) {
+        for conn in self.connections.read().await.values() {
+            let _ = conn.outbox.send(msg.clone());
+        }
+    }
+
+    /// Sends the current presence list to every connection.
+    pub async fn broadcast_presence(&self) {
+        let connections = self.connections.read().await;
+        let entries: Vec<PresenceEntry> = connections
+            .iter()
+            .map(|(&connection_id, conn)| PresenceEntry {
+                connection_id,
+                viewing: conn.viewing,
+            })
+            .collect();
+        for conn in connections.values() {
+            let _ = conn.outbox.send(ServerMessage::Presence {
+                connections: entries.clone(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Drains queued messages, keeping only follow-view targets.
+    fn follow_views(inbox: &mut mpsc::UnboundedReceiver<ServerMessage>) -> Vec<Option<Uuid>> {
+        let mut views = Vec::new();
+        while let Ok(msg) = inbox.try_recv() {
+            if let ServerMessage::FollowView { id, .. } = msg {
+                views.push(id);
+            }
+        }
+        views
+    }
+
+    #[tokio::test]
+    async fn test_follower_receives_new_view_target() {
+        let hub = Hub::default();
+        let (editor, _editor_inbox) = hub.register().await;
+        let (follower, mut follower_inbox) = hub.register().await;
+
+        let first = Uuid::new_v4();
+        hub.set_viewing(editor, Some(first)).await;
+        assert_eq!(hub.follow(follower, editor).await, Ok(Some(first)));
+        assert!(follow_views(&mut follower_inbox).is_empty());
+
+        let second = Uuid::new_v4();
+        hub.set_viewing(editor, Some(second)).await;
+        assert_eq!(follow_views(&mut follower_inbox), vec![Some(second)]);
+
+        hub.unfollow(follower).await;
+        hub.set_viewing(editor, None).await;
+        assert!(follow_views(&mut follower_inbox).is_empty());
+    }
+}
@@ -1,17 +1,36 @@
+mod hub;
+mod password;
+mod registries;
 mod storage;
 mod websocket;
 
 use axum::{routing::get, Router};
+use shared::CosmeticField;
+use std::collections::BTreeSet;
+use std::future::Future;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tower_http::{
     cors::{Any, CorsLayer},
     services::ServeDir,
 };
-use tracing::info;
+use tracing::{info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use hub::Hub;
 use storage::CharacterStore;
-use websocket::ws_handler;
+use websocket::{ws_handler, Limits};
+
+/// Shared state handed to every websocket connection.
+#[derive(Clone)]
+pub struct AppState {
+    pub store: CharacterStore,
+    pub hub: Hub,
+    /// Password that grants admin rights; when unset every connection is admin.
+    pub admin_password: Option<Arc<str>>,
+    /// Caps on the size and rate of client messages.
+    pub limits: Limits,
+}
 
 #[tokio::main]
 async fn main() {
@@ -24,7 +43,44 @@ async fn main() {
         .init();
 
     // Initialize storage
-    let store = CharacterStore::new("data").await;
+    // Optional cap on versions kept per character (unlimited when unset)
+    let max_versions = std::env::var("MAX_VERSIONS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok());
+    // Keep labeled versions when pruning old ones
+    let keep_labeled_versions =
+        std::env::var("KEEP_LABELED_VERSIONS").is_ok_and(|v| v == "1" || v == "true");
+    // Reject names already used by another character, ignoring case
+    let unique_names = std::env::var("UNIQUE_NAMES").is_ok_and(|v| v == "1" || v == "true");
+    // Comma-separated fields (e.g. "accent_color,hotbar,notes") that never
+    // add a version on their own; every change is versioned when unset
+    let cosmetic_fields = match std::env::var("COSMETIC_FIELDS") {
+        Ok(list) => list
+            .split(',')
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .filter_map(|f| {
+                f.parse::<CosmeticField>()
+                    .inspect_err(|_| warn!("Unknown cosmetic field {f:?}"))
+                    .ok()
+            })
+            .collect(),
+        Err(_) => BTreeSet::new(),
+    };
+    let store = CharacterStore::new("data")
+        .await
+        .with_max_versions(max_versions)
+        .with_keep_labeled_versions(keep_labeled_versions)
+        .with_unique_names(unique_names)
+        .with_cosmetic_fields(cosmetic_fields);
+
+    let admin_password = std::env::var("ADMIN_PASSWORD")
+        .ok()
+        .filter(|p| !p.is_empty())
+        .map(Arc::from);
+    if admin_password.is_none() {
+        warn!("ADMIN_PASSWORD is not set, every connection gets admin rights");
+    }
 
     // CORS layer for development
     // TODO: Restrict CORS origins in production to specific allowed domains
@@ -38,7 +94,12 @@ async fn main() {
         .route("/ws", get(ws_handler))
         .nest_service("/", ServeDir::new("static"))
         .layer(cors)
-        .with_state(store);
+        .with_state(AppState {
+            store: store.clone(),
+            hub: Hub::default(),
+            admin_password,
+            limits: Limits::from_env(),
+        });
 
     // Start server
     let addr = SocketAddr::from(([0, 0, 0, 0], 8080));
@@ -47,7 +108,100 @@ async fn main() {
     let listener = tokio::net::TcpListener::bind(addr)
         .await
         .expect("Failed to bind TCP listener - port 8080 may be in use");
+    serve(listener, app, store, shutdown_signal()).await;
+    info!("Server shut down cleanly");
+}
+
+/// Serves `app` until `shutdown` resolves, then refuses new mutations and
+/// waits for the in-flight ones to finish writing before returning.
+async fn serve(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    store: CharacterStore,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) {
     axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            shutdown.await;
+            info!("Shutdown requested, finishing pending writes");
+            store.close().await;
+        })
         .await
         .expect("Server terminated unexpectedly");
 }
+
+/// Resolves on Ctrl+C or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to listen for Ctrl+C");
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pending_writes_finish_before_shutdown() {
+        let dir = std::env::temp_dir().join(format!("shutdown-{}", uuid::Uuid::new_v4()));
+        let store = CharacterStore::new(dir.to_str().unwrap()).await;
+        let id = store
+            .create(
+                "Courier".to_string(),
+                None,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Vec::new(),
+                Vec::new(),
+            )
+            .await
+            .unwrap()
+            .id;
+
+        // A mutation already in flight when shutdown starts
+        let permit = store.begin_write().await.unwrap();
+        let writer = {
+            let store = store.clone();
+            tokio::spawn(async move {
+                let _permit = permit;
+                let mut character = store.get_character_version(id, None).await?.character;
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                character.level += 1;
+                store.update(character, None).await.ok().flatten()
+            })
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, Router::new(), store.clone(), async {
+            let _ = rx.await;
+        }));
+        tx.send(()).unwrap();
+        server.await.unwrap();
+
+        // The write landed before the server task returned
+        assert_eq!(store.get_version_list(id).await.unwrap().len(), 2);
+        assert!(store.begin_write().await.is_none());
+        assert!(writer.await.unwrap().is_some());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
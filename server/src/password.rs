@@ -0,0 +1,150 @@
+//! Hashing of character owner passwords, stored in each character file as
+//! `pbkdf2-sha256$<iterations>$<salt>$<digest>` with salt and digest in hex.
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+const SCHEME: &str = "pbkdf2-sha256";
+
+/// PBKDF2 rounds for new hashes. Stored with each hash, so raising it leaves
+/// existing hashes valid. Tests need the format, not the cost.
+const ITERATIONS: u32 = if cfg!(test) { 1_000 } else { 600_000 };
+
+const BLOCK_SIZE: usize = 64;
+
+/// Salted PBKDF2-HMAC-SHA256 of `password`. Slow on purpose; run it off the
+/// async runtime.
+pub fn hash_password(password: &str) -> String {
+    let salt = Uuid::new_v4().simple().to_string();
+    let digest = pbkdf2(password.as_bytes(), salt.as_bytes(), ITERATIONS);
+    format!("{SCHEME}${ITERATIONS}${salt}${}", hex(&digest))
+}
+
+/// Whether `password` produces the `stored` hash. Also accepts the single
+/// salted SHA-256 (`salt$digest`) that older files hold.
+pub fn password_matches(stored: &str, password: &str) -> bool {
+    let parts: Vec<&str> = stored.split('$').collect();
+    match parts.as_slice() {
+        [SCHEME, iterations, salt, digest] => iterations.parse().is_ok_and(|iterations| {
+            let derived = pbkdf2(password.as_bytes(), salt.as_bytes(), iterations);
+            constant_time_eq(hex(&derived).as_bytes(), digest.as_bytes())
+        }),
+        [salt, digest] => {
+            let derived = Sha256::new()
+                .chain_update(salt)
+                .chain_update(password)
+                .finalize();
+            constant_time_eq(hex(&derived).as_bytes(), digest.as_bytes())
+        }
+        _ => false,
+    }
+}
+
+/// Whether `stored` predates the current scheme or iteration count and
+/// should be replaced once the password is known.
+pub fn needs_rehash(stored: &str) -> bool {
+    stored
+        .strip_prefix(SCHEME)
+        .and_then(|rest| {
+            rest.strip_prefix('$')?
+                .split('$')
+                .next()?
+                .parse::<u32>()
+                .ok()
+        })
+        .is_none_or(|iterations| iterations < ITERATIONS)
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// HMAC-SHA256 keyed with `key`, as its inner and outer hash states so each
+/// round only hashes the message.
+struct Hmac {
+    inner: Sha256,
+    outer: Sha256,
+}
+
+impl Hmac {
+    fn new(key: &[u8]) -> Self {
+        let mut block = [0u8; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            block[..32].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let pad = |byte: u8| block.map(|b| b ^ byte);
+        Self {
+            inner: Sha256::new().chain_update(pad(0x36)),
+            outer: Sha256::new().chain_update(pad(0x5c)),
+        }
+    }
+
+    fn mac(&self, parts: &[&[u8]]) -> [u8; 32] {
+        let mut inner = self.inner.clone();
+        for part in parts {
+            inner.update(part);
+        }
+        self.outer
+            .clone()
+            .chain_update(inner.finalize())
+            .finalize()
+            .into()
+    }
+}
+
+/// PBKDF2-HMAC-SHA256 with a single 32-byte output block.
+fn pbkdf2(password: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+    let hmac = Hmac::new(password);
+    let mut u = hmac.mac(&[salt, &1u32.to_be_bytes()]);
+    let mut derived = u;
+    for _ in 1..iterations {
+        u = hmac.mac(&[&u]);
+        derived.iter_mut().zip(&u).for_each(|(d, x)| *d ^= x);
+    }
+    derived
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pbkdf2_matches_reference_vectors() {
+        // RFC 7914, section 11, truncated to the first block
+        assert_eq!(
+            hex(&pbkdf2(b"passwd", b"salt", 1)),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+        assert_eq!(
+            hex(&pbkdf2(b"password", b"salt", 4096)),
+            "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"
+        );
+    }
+
+    #[test]
+    fn test_stored_hash_checks_password() {
+        let stored = hash_password("moss");
+        assert!(stored.starts_with(&format!("pbkdf2-sha256${ITERATIONS}$")));
+        assert!(password_matches(&stored, "moss"));
+        assert!(!password_matches(&stored, "stone"));
+        assert!(!password_matches(&stored, ""));
+        assert!(!needs_rehash(&stored));
+    }
+
+    #[test]
+    fn test_legacy_sha256_hash_still_verifies() {
+        // `salt$sha256(salt + password)`, as written before PBKDF2
+        let salt = "0123456789abcdef";
+        let digest = hex(&Sha256::digest(format!("{salt}moss")));
+        let legacy = format!("{salt}${digest}");
+        assert!(password_matches(&legacy, "moss"));
+        assert!(!password_matches(&legacy, "stone"));
+        assert!(needs_rehash(&legacy));
+    }
+}
@@ -0,0 +1,105 @@
+use shared::{EquipmentRegistry, ItemRegistry, ServerMessage, TraitRegistry, WeaponRegistry};
+use std::path::Path;
+use tracing::warn;
+
+/// Trait, weapon, equipment and item definitions loaded from the data
+/// directory. Replaced as a whole when the files are reloaded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Registries {
+    pub traits: TraitRegistry,
+    pub weapons: WeaponRegistry,
+    pub equipment: EquipmentRegistry,
+    pub items: ItemRegistry,
+}
+
+/// Outcome of reading one registry file on reload.
+pub struct RegistryReload {
+    pub file: &'static str,
+    /// Entries after the reload.
+    pub entries: usize,
+    pub changed: bool,
+    /// Why the file could not be read; the previous definitions are kept.
+    pub error: Option<String>,
+}
+
+/// Reads `file` from `data_dir`, keeping `fallback` if it cannot be read.
+fn load_or<T>(
+    data_dir: &Path,
+    file: &'static str,
+    load: impl Fn(&Path) -> Result<T, Box<dyn std::error::Error>>,
+    fallback: &T,
+    len: impl Fn(&T) -> usize,
+    reports: &mut Vec<RegistryReload>,
+) -> T
+where
+    T: Clone + PartialEq,
+{
+    let path = data_dir.join(file);
+    let (registry, error) = match load(&path) {
+        Ok(registry) => (registry, None),
+        Err(e) => {
+            warn!("Failed to load {} from {:?}: {}", file, path, e);
+            (fallback.clone(), Some(e.to_string()))
+        }
+    };
+    reports.push(RegistryReload {
+        file,
+        entries: len(&registry),
+        changed: registry != *fallback,
+        error,
+    });
+    registry
+}
+
+impl Registries {
+    /// Loads every registry file in `data_dir`. Files that cannot be read
+    /// keep the definitions from `previous`.
+    pub fn load(data_dir: &Path, previous: &Registries) -> (Self, Vec<RegistryReload>) {
+        let mut reports = Vec::new();
+        let registries = Self {
+            traits: load_or(
+                data_dir,
+                "traits.json",
+                TraitRegistry::load_from_file,
+                &previous.traits,
+                |r| r.traits.len(),
+                &mut reports,
+            ),
+            weapons: load_or(
+                data_dir,
+                "weapons.json",
+                WeaponRegistry::load_from_file,
+                &previous.weapons,
+                |r| r.weapons.len(),
+                &mut reports,
+            ),
+            equipment: load_or(
+                data_dir,
+                "equipment.json",
+                EquipmentRegistry::load_from_file,
+                &previous.equipment,
+                |r| r.equipment.len(),
+                &mut reports,
+            ),
+            items: load_or(
+                data_dir,
+                "items.json",
+                ItemRegistry::load_from_file,
+                &previous.items,
+                |r| r.items.len(),
+                &mut reports,
+            ),
+        };
+        (registries, reports)
+    }
+
+    /// Message carrying every definition, for clients to replace theirs.
+    pub fn to_message(&self) -> ServerMessage {
+        ServerMessage::Registries {
+            traits: self.traits.traits.clone(),
+            weapons: self.weapons.weapons.clone(),
+            equipment: self.equipment.equipment.clone(),
+            items: self.items.items.clone(),
+        }
+    }
+}
@@ -1,17 +1,46 @@
 use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
 use shared::character::CharacterSkill;
 use shared::{
-    Character, CharacterFile, CharacterSummary, CharacterVersion, Characteristics, Class,
-    Equipment, EquipmentRegistry, Item, ItemRegistry, Named, Race, Resource, TraitRegistry,
-    VersionSummary, Weapon, WeaponRegistry,
+    significant_change, AuditAction, AuditEntry, Character, CharacterExport, CharacterFile,
+    CharacterSummary, CharacterVersion, Characteristics, Class, CosmeticField, Equipment, GmNote,
+    Item, Named, Race, ReferenceKind, Resource, Ruleset, VersionSummary, Weapon, GM_AUTHOR,
 };
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{
+    mpsc, oneshot, Mutex, OwnedMutexGuard, OwnedRwLockReadGuard, RwLock, RwLockWriteGuard,
+};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::password::{self, hex};
+use crate::registries::{Registries, RegistryReload};
+
+/// Side file holding per-character tags, relative to the data directory.
+const TAGS_FILE: &str = "tags.json";
+
+/// Side file holding GM notes per character, outside the versioned files.
+const NOTES_FILE: &str = "gm_notes.json";
+
+/// Subdirectory of the data directory holding one JSONL audit log per character.
+const AUDIT_DIR: &str = "audit";
+
+/// Subdirectory of the portraits directory holding images keyed by content hash.
+const GALLERY_DIR: &str = "gallery";
+
+/// Edge length, in pixels, of the gallery thumbnails.
+const THUMBNAIL_SIZE: u32 = 64;
+
+/// Share of unreadable character files above which the store starts read-only.
+const SAFE_MODE_FAILURE_RATIO: f64 = 0.5;
+
+/// Lower bound for `max_versions`, so there is always a version to roll back to.
+const MIN_RETAINED_VERSIONS: usize = 2;
+
 /// Legacy format for migration from single-file storage.
 #[derive(Debug, serde::Serialize, serde::Deserialize, Default)]
 struct LegacyStorageData {
@@ -22,19 +51,109 @@ struct LegacyStorageData {
 struct CharacterIndex {
     file_path: PathBuf,
     summary: CharacterSummary,
+    /// Registry entries the latest version references, for `find_by_reference`.
+    references: BTreeSet<(ReferenceKind, String)>,
+}
+
+fn latest_references(file: &CharacterFile) -> BTreeSet<(ReferenceKind, String)> {
+    file.versions
+        .last()
+        .map(|v| v.character.references())
+        .unwrap_or_default()
+}
+
+/// Work for the audit writer task.
+enum AuditJob {
+    Append(Uuid, AuditEntry),
+    /// Answered once every entry queued before it is written.
+    Flush(oneshot::Sender<()>),
+}
+
+/// Appends audit entries in the order they were queued, off the save path.
+async fn run_audit_writer(dir: PathBuf, mut jobs: mpsc::UnboundedReceiver<AuditJob>) {
+    while let Some(job) = jobs.recv().await {
+        match job {
+            AuditJob::Append(id, entry) => append_audit_entry(&dir, id, &entry).await,
+            AuditJob::Flush(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+async fn append_audit_entry(dir: &Path, id: Uuid, entry: &AuditEntry) {
+    let mut line = match serde_json::to_string(entry) {
+        Ok(line) => line,
+        Err(e) => {
+            error!("Failed to serialize audit entry: {}", e);
+            return;
+        }
+    };
+    line.push('\n');
+    let path = dir.join(format!("{}.log", id));
+    let result = async {
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        file.write_all(line.as_bytes()).await
+    }
+    .await;
+    if let Err(e) = result {
+        warn!("Failed to append to audit log {:?}: {}", path, e);
+    }
+}
+
+/// Returned by `create` and `update` when unique names are enforced and the
+/// name collides with another character's.
+#[derive(Debug, PartialEq, Eq)]
+pub struct NameTaken;
+
+/// Why `update_from` refused to save.
+#[derive(Debug, PartialEq, Eq)]
+pub enum UpdateError {
+    NameTaken,
+    /// Someone saved past the client's base version; holds the stored latest.
+    Conflict(Box<CharacterVersion>),
 }
 
 #[derive(Clone)]
 pub struct CharacterStore {
     characters: Arc<RwLock<BTreeMap<Uuid, CharacterIndex>>>,
-    trait_registry: Arc<TraitRegistry>,
-    weapon_registry: Arc<WeaponRegistry>,
-    equipment_registry: Arc<EquipmentRegistry>,
-    #[allow(dead_code)]
-    item_registry: Arc<ItemRegistry>,
+    /// Summaries of every indexed character, built on first read and dropped
+    /// whenever the index is written through `index_mut`.
+    summaries: Arc<Mutex<Option<Arc<Vec<CharacterSummary>>>>>,
+    /// Swapped for a fresh snapshot by `reload_registries`.
+    registries: Arc<std::sync::RwLock<Arc<Registries>>>,
+    ruleset: Arc<Ruleset>,
+    /// Versions kept per character; older ones are dropped on save. `None` keeps all.
+    max_versions: Option<usize>,
+    /// Labeled versions survive pruning, beyond the retention limit.
+    keep_labeled_versions: bool,
+    /// Rejects names already used by another character, ignoring case.
+    unique_names: bool,
+    /// Fields whose changes alone update the latest version in place. Empty
+    /// by default, so every change adds a version.
+    cosmetic_fields: BTreeSet<CosmeticField>,
     characters_dir: PathBuf,
     portraits_dir: PathBuf,
     data_dir: PathBuf,
+    /// Serializes writes of `tags.json`.
+    tags_lock: Arc<Mutex<()>>,
+    /// One lock per character, held across each read-modify-write of its
+    /// file so concurrent edits cannot interleave.
+    file_locks: Arc<Mutex<BTreeMap<Uuid, Arc<Mutex<()>>>>>,
+    /// GM notes per character, mirrored to `gm_notes.json`.
+    notes: Arc<RwLock<BTreeMap<Uuid, Vec<GmNote>>>>,
+    /// Why the index is considered partially loaded; mutations are refused while set.
+    safe_mode: Option<String>,
+    /// Held shared by every in-flight mutation and taken exclusively on shutdown.
+    write_gate: Arc<RwLock<()>>,
+    /// Set once shutdown has begun; new mutations are refused.
+    closing: Arc<AtomicBool>,
+    /// Queue of the task appending to `audit/<id>.log`.
+    audit: mpsc::UnboundedSender<AuditJob>,
 }
 
 fn current_timestamp() -> i64 {
@@ -63,19 +182,74 @@ fn character_filename(name: &str, id: Uuid) -> String {
     format!("{sanitized}_{short_id}.json")
 }
 
-fn summary_from_file(file: &CharacterFile) -> Option<CharacterSummary> {
+fn summary_from_file(
+    file: &CharacterFile,
+    power_score: impl Fn(&Character) -> u32,
+) -> Option<CharacterSummary> {
     let latest = file.versions.last()?;
     Some(CharacterSummary {
         id: file.id,
         name: latest.character.name.clone(),
+        accent_color: latest.character.accent_color,
         race: latest.character.race,
         class: latest.character.class,
         level: latest.character.level,
         version_count: file.versions.len() as u32,
+        latest_version: latest.version,
+        power_score: power_score(&latest.character),
         last_updated: latest.saved_at,
+        tags: Vec::new(),
+        protected: file.owner_hash.is_some(),
     })
 }
 
+/// Hashes `password` on the blocking pool, as the key derivation is slow.
+async fn hash_password(password: &str) -> String {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || password::hash_password(&password))
+        .await
+        .expect("password hashing panicked")
+}
+
+/// Checks `password` against `stored` on the blocking pool.
+async fn password_matches(stored: &str, password: &str) -> bool {
+    let (stored, password) = (stored.to_string(), password.to_string());
+    tokio::task::spawn_blocking(move || password::password_matches(&stored, &password))
+        .await
+        .expect("password check panicked")
+}
+
+/// Content hash of a portrait (SHA-256, hex), naming its files on disk.
+/// Collision resistant, so one upload can never stand in for another.
+fn content_hash(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+/// Downscaled PNG copy of a portrait, or `None` if the image cannot be decoded.
+fn make_thumbnail(png_data: &[u8]) -> Option<Vec<u8>> {
+    let image = image::load_from_memory(png_data).ok()?;
+    let mut out = std::io::Cursor::new(Vec::new());
+    image
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .write_to(&mut out, image::ImageFormat::Png)
+        .ok()?;
+    Some(out.into_inner())
+}
+
+/// Drops versions older than the newest `keep` (never fewer than one), except
+/// labeled ones when `keep_labeled` is set. The versions that remain keep
+/// their numbers. Returns how many were dropped.
+fn prune_oldest(versions: &mut Vec<CharacterVersion>, keep: usize, keep_labeled: bool) -> usize {
+    let cutoff = versions.len().saturating_sub(keep.max(1));
+    let before = versions.len();
+    let mut index = 0;
+    versions.retain(|v| {
+        index += 1;
+        index > cutoff || (keep_labeled && v.label.is_some())
+    });
+    before - versions.len()
+}
+
 async fn write_character_file(path: &Path, file: &CharacterFile) {
     let json = match serde_json::to_string_pretty(file) {
         Ok(json) => json,
@@ -99,45 +273,27 @@ impl CharacterStore {
         let data_dir_path = PathBuf::from(data_dir);
         let characters_dir = data_dir_path.join("characters");
         let portraits_dir = data_dir_path.join("portraits");
+        let audit_dir = data_dir_path.join(AUDIT_DIR);
 
         // Ensure directories exist
-        for dir in [&characters_dir, &portraits_dir] {
+        for dir in [
+            &characters_dir,
+            &portraits_dir,
+            &portraits_dir.join(GALLERY_DIR),
+            &audit_dir,
+        ] {
             if let Err(e) = tokio::fs::create_dir_all(dir).await {
                 error!("Failed to create directory {:?}: {}", dir, e);
             }
         }
 
-        let traits_path = data_dir_path.join("traits.json");
-        let trait_registry = Arc::new(TraitRegistry::load_from_file(&traits_path).unwrap_or_else(
-            |e| {
-                warn!("Failed to load traits from {:?}: {}", traits_path, e);
-                TraitRegistry::default()
-            },
-        ));
-
-        let weapons_path = data_dir_path.join("weapons.json");
-        let weapon_registry = Arc::new(
-            WeaponRegistry::load_from_file(&weapons_path).unwrap_or_else(|e| {
-                warn!("Failed to load weapons from {:?}: {}", weapons_path, e);
-                WeaponRegistry::default()
-            }),
-        );
-
-        let equipment_path = data_dir_path.join("equipment.json");
-        let equipment_registry = Arc::new(
-            EquipmentRegistry::load_from_file(&equipment_path).unwrap_or_else(|e| {
-                warn!("Failed to load equipment from {:?}: {}", equipment_path, e);
-                EquipmentRegistry::default()
-            }),
-        );
+        let (registries, _) = Registries::load(&data_dir_path, &Registries::default());
 
-        let items_path = data_dir_path.join("items.json");
-        let item_registry = Arc::new(ItemRegistry::load_from_file(&items_path).unwrap_or_else(
-            |e| {
-                warn!("Failed to load items from {:?}: {}", items_path, e);
-                ItemRegistry::default()
-            },
-        ));
+        let ruleset_path = data_dir_path.join("ruleset.json");
+        let ruleset = Arc::new(Ruleset::load_from_file(&ruleset_path).unwrap_or_else(|e| {
+            warn!("Failed to load ruleset from {:?}: {}", ruleset_path, e);
+            Ruleset::default()
+        }));
 
         // Migrate legacy characters.json if it exists
         let legacy_path = data_dir_path.join("characters.json");
@@ -146,20 +302,246 @@ impl CharacterStore {
         }
 
         // Scan characters directory and build index
-        let index = Self::build_index(&characters_dir).await;
+        let (mut index, failures) = Self::build_index(&characters_dir, |c| {
+            c.power_score(
+                &registries.traits,
+                &registries.weapons,
+                &registries.equipment,
+                &ruleset,
+            )
+        })
+        .await;
+        let total = index.len() + failures;
+        let safe_mode = (failures > 0 && failures as f64 / total as f64 > SAFE_MODE_FAILURE_RATIO)
+            .then(|| format!("{} of {} character files failed to load", failures, total));
+        if let Some(reason) = &safe_mode {
+            error!("Entering safe mode, refusing changes: {}", reason);
+        }
+
+        // Tags live in a side file so changing them does not create versions
+        let mut tags = Self::load_tags(&data_dir_path.join(TAGS_FILE)).await;
+        for (id, ci) in index.iter_mut() {
+            ci.summary.tags = tags.remove(id).unwrap_or_default();
+        }
+
+        let notes = match tokio::fs::read_to_string(data_dir_path.join(NOTES_FILE)).await {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("Failed to parse GM notes file: {}", e);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+
+        let (audit, audit_jobs) = mpsc::unbounded_channel();
+        tokio::spawn(run_audit_writer(audit_dir, audit_jobs));
 
         Self {
             characters: Arc::new(RwLock::new(index)),
-            trait_registry,
-            weapon_registry,
-            equipment_registry,
-            item_registry,
+            summaries: Arc::new(Mutex::new(None)),
+            registries: Arc::new(std::sync::RwLock::new(Arc::new(registries))),
+            ruleset,
+            max_versions: None,
+            keep_labeled_versions: false,
+            unique_names: false,
+            cosmetic_fields: BTreeSet::new(),
             characters_dir,
             portraits_dir,
             data_dir: data_dir_path,
+            tags_lock: Arc::new(Mutex::new(())),
+            file_locks: Arc::new(Mutex::new(BTreeMap::new())),
+            notes: Arc::new(RwLock::new(notes)),
+            safe_mode,
+            write_gate: Arc::new(RwLock::new(())),
+            closing: Arc::new(AtomicBool::new(false)),
+            audit,
+        }
+    }
+
+    /// Reason the store is read-only, if too many character files failed to load.
+    pub fn safe_mode(&self) -> Option<&str> {
+        self.safe_mode.as_deref()
+    }
+
+    /// Permit for one mutation, held until its writes are done. `None` once
+    /// shutdown has begun.
+    pub async fn begin_write(&self) -> Option<OwnedRwLockReadGuard<()>> {
+        let permit = self.write_gate.clone().read_owned().await;
+        // Checked after taking the permit, so `close` either waits for this
+        // mutation or it is refused
+        (!self.closing.load(Ordering::Acquire)).then_some(permit)
+    }
+
+    /// Refuses new mutations and waits for those in flight to finish writing.
+    pub async fn close(&self) {
+        self.closing.store(true, Ordering::Release);
+        let _ = self.write_gate.write().await;
+        self.flush_audit().await;
+        info!(
+            "Store closed with {} character(s) indexed",
+            self.characters.read().await.len()
+        );
+    }
+
+    /// Queues an entry for the audit log of `id`. Never waits for the write,
+    /// so a failing audit log cannot hold up the mutation it records.
+    fn audit(&self, id: Uuid, action: AuditAction, version: Option<u32>, by: Option<String>) {
+        let entry = AuditEntry {
+            at: current_timestamp(),
+            action,
+            version,
+            by,
+        };
+        if self.audit.send(AuditJob::Append(id, entry)).is_err() {
+            warn!("Audit writer is gone, dropping entry for character {}", id);
+        }
+    }
+
+    /// Waits until every audit entry queued so far is written.
+    async fn flush_audit(&self) {
+        let (done, written) = oneshot::channel();
+        if self.audit.send(AuditJob::Flush(done)).is_ok() {
+            let _ = written.await;
+        }
+    }
+
+    /// Recorded mutations of `id`, oldest first. Kept after the character is
+    /// deleted; empty when nothing was recorded.
+    pub async fn audit_log(&self, id: Uuid) -> Vec<AuditEntry> {
+        self.flush_audit().await;
+        let path = self.data_dir.join(AUDIT_DIR).join(format!("{}.log", id));
+        let Ok(content) = tokio::fs::read_to_string(&path).await else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .filter_map(|line| {
+                serde_json::from_str(line)
+                    .map_err(|e| warn!("Skipping bad line in audit log {:?}: {}", path, e))
+                    .ok()
+            })
+            .collect()
+    }
+
+    async fn load_tags(path: &Path) -> BTreeMap<Uuid, Vec<String>> {
+        match tokio::fs::read_to_string(path).await {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("Failed to parse tags file {:?}: {}", path, e);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        }
+    }
+
+    /// GM notes of a character, oldest first.
+    pub async fn notes(&self, id: Uuid) -> Vec<GmNote> {
+        self.notes
+            .read()
+            .await
+            .get(&id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Appends a GM note to an existing character. Returns the stored note.
+    pub async fn push_note(&self, id: Uuid, text: String) -> Option<GmNote> {
+        if !self.characters.read().await.contains_key(&id) {
+            return None;
+        }
+        let note = GmNote {
+            author: GM_AUTHOR.to_string(),
+            text,
+            created_at: current_timestamp(),
+        };
+        let mut notes = self.notes.write().await;
+        notes.entry(id).or_default().push(note.clone());
+        Self::write_notes(&self.data_dir, &notes).await;
+        Some(note)
+    }
+
+    async fn remove_notes(&self, ids: &[Uuid]) {
+        let mut notes = self.notes.write().await;
+        let before = notes.len();
+        for id in ids {
+            notes.remove(id);
+        }
+        if notes.len() != before {
+            Self::write_notes(&self.data_dir, &notes).await;
+        }
+    }
+
+    async fn write_notes(data_dir: &Path, notes: &BTreeMap<Uuid, Vec<GmNote>>) {
+        let path = data_dir.join(NOTES_FILE);
+        match serde_json::to_string_pretty(notes) {
+            Ok(json) => {
+                if let Err(e) = tokio::fs::write(&path, json).await {
+                    error!("Failed to write GM notes file {:?}: {}", path, e);
+                }
+            }
+            Err(e) => error!("Failed to serialize GM notes: {}", e),
+        }
+    }
+
+    /// Writes the tags of all indexed characters to the side file.
+    async fn save_tags(&self) {
+        let _guard = self.tags_lock.lock().await;
+        let tags: BTreeMap<Uuid, Vec<String>> = self
+            .characters
+            .read()
+            .await
+            .iter()
+            .filter(|(_, ci)| !ci.summary.tags.is_empty())
+            .map(|(id, ci)| (*id, ci.summary.tags.clone()))
+            .collect();
+        let path = self.data_dir.join(TAGS_FILE);
+        match serde_json::to_string_pretty(&tags) {
+            Ok(json) => {
+                if let Err(e) = tokio::fs::write(&path, json).await {
+                    error!("Failed to write tags file {:?}: {}", path, e);
+                }
+            }
+            Err(e) => error!("Failed to serialize tags: {}", e),
         }
     }
 
+    /// Replaces a character's tags (trimmed, deduplicated and sorted) without
+    /// creating a version. Returns the updated summary, or `None` if not found.
+    pub async fn set_tags(&self, id: Uuid, tags: Vec<String>) -> Option<CharacterSummary> {
+        let tags: BTreeSet<String> = tags
+            .iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        let summary = {
+            let mut index = self.index_mut().await;
+            let ci = index.get_mut(&id)?;
+            ci.summary.tags = tags.into_iter().collect();
+            ci.summary.clone()
+        };
+        self.save_tags().await;
+        Some(summary)
+    }
+
+    /// Bounds per-character history to `max` versions (never fewer than 2).
+    pub fn with_max_versions(mut self, max: Option<usize>) -> Self {
+        self.max_versions = max.map(|m| m.max(MIN_RETAINED_VERSIONS));
+        self
+    }
+
+    pub fn with_keep_labeled_versions(mut self, keep: bool) -> Self {
+        self.keep_labeled_versions = keep;
+        self
+    }
+
+    pub fn with_unique_names(mut self, unique_names: bool) -> Self {
+        self.unique_names = unique_names;
+        self
+    }
+
+    pub fn with_cosmetic_fields(mut self, cosmetic_fields: BTreeSet<CosmeticField>) -> Self {
+        self.cosmetic_fields = cosmetic_fields;
+        self
+    }
+
     async fn migrate_legacy(legacy_path: &Path, characters_dir: &Path) {
         let content = match tokio::fs::read_to_string(legacy_path).await {
             Ok(c) => c,
@@ -186,7 +568,10 @@ impl CharacterStore {
                     version: 1,
                     saved_at: now,
                     character,
+                    saved_by: None,
+                    label: None,
                 }],
+                owner_hash: None,
             };
             let name = file
                 .versions
@@ -210,13 +595,18 @@ impl CharacterStore {
         }
     }
 
-    async fn build_index(characters_dir: &Path) -> BTreeMap<Uuid, CharacterIndex> {
+    /// Scans the characters directory. Also returns how many files could not be loaded.
+    async fn build_index(
+        characters_dir: &Path,
+        power_score: impl Fn(&Character) -> u32,
+    ) -> (BTreeMap<Uuid, CharacterIndex>, usize) {
         let mut index = BTreeMap::new();
+        let mut failures = 0;
         let mut entries = match tokio::fs::read_dir(characters_dir).await {
             Ok(entries) => entries,
             Err(e) => {
                 warn!("Failed to read characters directory: {}", e);
-                return index;
+                return (index, failures);
             }
         };
 
@@ -229,6 +619,7 @@ impl CharacterStore {
                 Ok(c) => c,
                 Err(e) => {
                     warn!("Failed to read character file {:?}: {}", path, e);
+                    failures += 1;
                     continue;
                 }
             };
@@ -236,22 +627,30 @@ impl CharacterStore {
                 Ok(f) => f,
                 Err(e) => {
                     warn!("Failed to parse character file {:?}: {}", path, e);
+                    failures += 1;
                     continue;
                 }
             };
-            if let Some(summary) = summary_from_file(&file) {
+            if let Some(summary) = summary_from_file(&file, &power_score) {
                 index.insert(
                     file.id,
                     CharacterIndex {
                         file_path: path,
                         summary,
+                        references: latest_references(&file),
                     },
                 );
             }
         }
 
         info!("Loaded {} character(s) from index", index.len());
-        index
+        (index, failures)
+    }
+
+    /// Waits for exclusive access to `id`'s file.
+    async fn lock_character(&self, id: Uuid) -> OwnedMutexGuard<()> {
+        let lock = self.file_locks.lock().await.entry(id).or_default().clone();
+        lock.lock_owned().await
     }
 
     async fn read_character_file(&self, id: Uuid) -> Option<(PathBuf, CharacterFile)> {
@@ -264,9 +663,74 @@ impl CharacterStore {
         Some((path, file))
     }
 
-    pub async fn get_all_summaries(&self) -> Vec<CharacterSummary> {
+    pub fn ruleset(&self) -> &Ruleset {
+        &self.ruleset
+    }
+
+    /// Write access to the index. Every mutation goes through here, so the
+    /// cached summaries never outlive a change.
+    async fn index_mut(&self) -> RwLockWriteGuard<'_, BTreeMap<Uuid, CharacterIndex>> {
+        let index = self.characters.write().await;
+        *self.summaries.lock().await = None;
+        index
+    }
+
+    /// Summaries of every character, shared until the index next changes.
+    pub async fn get_all_summaries(&self) -> Arc<Vec<CharacterSummary>> {
+        // The cache is filled while the index read lock is held, so a
+        // concurrent `index_mut` either sees the new cache and clears it or
+        // waits until it is built from the current index.
+        let index = self.characters.read().await;
+        let mut cached = self.summaries.lock().await;
+        cached
+            .get_or_insert_with(|| Arc::new(index.values().map(|ci| ci.summary.clone()).collect()))
+            .clone()
+    }
+
+    /// Up to `limit` summaries starting at `offset`, in index order, of the
+    /// characters whose name contains `query` (ignoring case). Also returns how
+    /// many characters match in total.
+    pub async fn get_summaries_page(
+        &self,
+        offset: usize,
+        limit: usize,
+        query: Option<&str>,
+    ) -> (Vec<CharacterSummary>, usize) {
+        let query = query.map(|q| q.trim().to_lowercase());
         let index = self.characters.read().await;
-        index.values().map(|ci| ci.summary.clone()).collect()
+        let matches: Vec<&CharacterSummary> = index
+            .values()
+            .map(|ci| &ci.summary)
+            .filter(|s| {
+                query
+                    .as_ref()
+                    .is_none_or(|q| s.name.to_lowercase().contains(q.as_str()))
+            })
+            .collect();
+        let page = matches
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|s| (*s).clone())
+            .collect();
+        (page, matches.len())
+    }
+
+    /// Summaries of characters whose latest version references `name` of `kind`.
+    /// Served from the reference sets kept in the index, without reading files.
+    pub async fn find_by_reference(
+        &self,
+        kind: ReferenceKind,
+        name: &str,
+    ) -> Vec<CharacterSummary> {
+        let key = (kind, name.to_string());
+        self.characters
+            .read()
+            .await
+            .values()
+            .filter(|ci| ci.references.contains(&key))
+            .map(|ci| ci.summary.clone())
+            .collect()
     }
 
     pub async fn character_name_exists(&self, name: &str) -> bool {
@@ -274,6 +738,19 @@ impl CharacterStore {
         index.values().any(|ci| ci.summary.name == name)
     }
 
+    /// With unique names on, whether a character other than `id` is already
+    /// called `name`, ignoring case and surrounding whitespace.
+    async fn name_taken(&self, name: &str, id: Uuid) -> bool {
+        if !self.unique_names {
+            return false;
+        }
+        let name = name.trim().to_lowercase();
+        let index = self.characters.read().await;
+        index
+            .iter()
+            .any(|(other, ci)| *other != id && ci.summary.name.trim().to_lowercase() == name)
+    }
+
     pub async fn get_version_list(&self, id: Uuid) -> Option<Vec<VersionSummary>> {
         let (_, file) = self.read_character_file(id).await?;
         Some(
@@ -283,6 +760,8 @@ impl CharacterStore {
                     version: v.version,
                     saved_at: v.saved_at,
                     level: v.character.level,
+                    saved_by: v.saved_by.clone(),
+                    label: v.label.clone(),
                 })
                 .collect(),
         )
@@ -300,26 +779,115 @@ impl CharacterStore {
         }
     }
 
+    /// Number of the newest version of a character.
+    pub async fn latest_version(&self, id: Uuid) -> Option<u32> {
+        let (_, file) = self.read_character_file(id).await?;
+        file.versions.last().map(|v| v.version)
+    }
+
+    /// A version (latest if `None`) bundled with the registry definitions it references.
+    pub async fn export_character(
+        &self,
+        id: Uuid,
+        version: Option<u32>,
+    ) -> Option<CharacterExport> {
+        let cv = self.get_character_version(id, version).await?;
+        let registries = self.registries();
+        Some(CharacterExport::new(
+            &cv,
+            &registries.traits,
+            &registries.weapons,
+            &registries.equipment,
+            &registries.items,
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         &self,
         name: String,
+        accent_color: Option<[u8; 3]>,
         race: Race,
         class: Class,
         stats: Characteristics,
         skills: Vec<CharacterSkill>,
         traits: Vec<String>,
-    ) -> CharacterSummary {
+    ) -> Result<CharacterSummary, NameTaken> {
         let mut character = Character::new(name);
+        if self.name_taken(&character.name, character.id).await {
+            return Err(NameTaken);
+        }
+        character.accent_color = accent_color;
         character.race = race;
         character.class = class;
         character.stats = stats;
         character.skills = skills;
         character.traits = traits;
         character.action_points = Resource::new(race.base_action_points());
+        Ok(self.insert_new(character, None).await)
+    }
+
+    /// Stores a character read from an export as a new file with a single
+    /// version. It keeps its id unless another character already uses it.
+    pub async fn import(
+        &self,
+        mut character: Character,
+        saved_by: Option<String>,
+    ) -> Result<CharacterSummary, NameTaken> {
+        if self.characters.read().await.contains_key(&character.id) {
+            character.id = Uuid::new_v4();
+        }
+        if self.name_taken(&character.name, character.id).await {
+            return Err(NameTaken);
+        }
+        Ok(self.insert_new(character, saved_by).await)
+    }
+
+    /// Copies the latest version of `id` into a new character called `new_name`,
+    /// with a fresh id and a single version. `None` if `id` is unknown.
+    pub async fn clone_character(
+        &self,
+        id: Uuid,
+        new_name: String,
+        saved_by: Option<String>,
+    ) -> Option<Result<CharacterSummary, NameTaken>> {
+        let mut character = self.get_character_version(id, None).await?.character;
+        character.id = Uuid::new_v4();
+        character.name = new_name;
+        if self.name_taken(&character.name, character.id).await {
+            return Some(Err(NameTaken));
+        }
+        Some(Ok(self.insert_new(character, saved_by).await))
+    }
+
+    /// Trait, weapon and equipment names `character` references that the
+    /// registries do not define.
+    pub fn unknown_references(&self, character: &Character) -> Vec<(ReferenceKind, String)> {
+        let registries = self.registries();
+        character
+            .references()
+            .into_iter()
+            .filter(|(kind, name)| match kind {
+                ReferenceKind::Trait => registries.traits.get(name).is_none(),
+                ReferenceKind::Weapon => registries.weapons.get(name).is_none(),
+                ReferenceKind::Equipment => registries.equipment.get(name).is_none(),
+                ReferenceKind::Item | ReferenceKind::Ability => false,
+            })
+            .collect()
+    }
+
+    /// Writes a new character file holding `character` as version 1 and indexes it.
+    async fn insert_new(
+        &self,
+        mut character: Character,
+        saved_by: Option<String>,
+    ) -> CharacterSummary {
+        let registries = self.registries();
         character.recalculate_effects(
-            &self.trait_registry,
-            &self.weapon_registry,
-            &self.equipment_registry,
+            &registries.traits,
+            &registries.weapons,
+            &registries.equipment,
+            &self.ruleset.campaign.effect_modifiers,
         );
 
         let now = current_timestamp();
@@ -329,30 +897,42 @@ impl CharacterStore {
                 version: 1,
                 saved_at: now,
                 character: character.clone(),
+                saved_by,
+                label: None,
             }],
+            owner_hash: None,
         };
 
         let filename = character_filename(&character.name, character.id);
         let file_path = self.characters_dir.join(&filename);
         write_character_file(&file_path, &file).await;
+        let saved_by = file.versions[0].saved_by.clone();
+        self.audit(character.id, AuditAction::Created, Some(1), saved_by);
 
+        let power_score = self.power_score(&character);
         let summary = CharacterSummary {
             id: character.id,
             name: character.name,
+            accent_color: character.accent_color,
             race: character.race,
             class: character.class,
             level: character.level,
             version_count: 1,
+            latest_version: 1,
+            power_score,
             last_updated: now,
+            tags: Vec::new(),
+            protected: false,
         };
 
         {
-            let mut index = self.characters.write().await;
+            let mut index = self.index_mut().await;
             index.insert(
                 summary.id,
                 CharacterIndex {
                     file_path,
                     summary: summary.clone(),
+                    references: latest_references(&file),
                 },
             );
         }
@@ -360,7 +940,89 @@ impl CharacterStore {
         summary
     }
 
+    /// Sets the label of one version; an empty label removes it. Labels are
+    /// notes about history, so no new version is created. `None` when the
+    /// character does not exist, `Some(false)` when the version does not.
+    pub async fn set_version_label(&self, id: Uuid, version: u32, label: String) -> Option<bool> {
+        let _lock = self.lock_character(id).await;
+        let (path, mut file) = self.read_character_file(id).await?;
+        let Some(entry) = file.versions.iter_mut().find(|v| v.version == version) else {
+            return Some(false);
+        };
+        let label = label.trim();
+        entry.label = (!label.is_empty()).then(|| label.to_string());
+        write_character_file(&path, &file).await;
+        Some(true)
+    }
+
+    /// Protects `id` with `password`: from now on only connections that
+    /// authenticate with it, or the admin, may edit it. False when the
+    /// character does not exist.
+    pub async fn set_owner_password(&self, id: Uuid, password: &str) -> bool {
+        let hash = hash_password(password).await;
+        let _lock = self.lock_character(id).await;
+        let Some((path, mut file)) = self.read_character_file(id).await else {
+            return false;
+        };
+        file.owner_hash = Some(hash);
+        write_character_file(&path, &file).await;
+        if let Some(ci) = self.index_mut().await.get_mut(&id) {
+            ci.summary.protected = true;
+        }
+        true
+    }
+
+    /// Whether `id` needs its password to be edited.
+    pub async fn is_protected(&self, id: Uuid) -> bool {
+        self.characters
+            .read()
+            .await
+            .get(&id)
+            .is_some_and(|ci| ci.summary.protected)
+    }
+
+    /// Whether `password` unlocks `id`; unprotected characters need none.
+    /// A hash in an older format is replaced once the password matches it.
+    /// `None` when the character does not exist.
+    pub async fn check_owner_password(&self, id: Uuid, password: &str) -> Option<bool> {
+        let (_, file) = self.read_character_file(id).await?;
+        let Some(stored) = file.owner_hash else {
+            return Some(true);
+        };
+        if !password_matches(&stored, password).await {
+            return Some(false);
+        }
+        if password::needs_rehash(&stored) {
+            let hash = hash_password(password).await;
+            let _lock = self.lock_character(id).await;
+            // Unless the password was changed meanwhile
+            if let Some((path, mut file)) = self.read_character_file(id).await {
+                if file.owner_hash.as_ref() == Some(&stored) {
+                    info!("Upgrading the password hash of character {}", id);
+                    file.owner_hash = Some(hash);
+                    write_character_file(&path, &file).await;
+                }
+            }
+        }
+        Some(true)
+    }
+
+    /// Drops all but the newest `keep` versions (never fewer than one), and
+    /// keeps labeled ones too when the store is set to. Returns how many were
+    /// dropped, `None` when the character does not exist.
+    pub async fn prune_versions(&self, id: Uuid, keep: usize) -> Option<usize> {
+        let _lock = self.lock_character(id).await;
+        let (path, mut file) = self.read_character_file(id).await?;
+        let pruned = prune_oldest(&mut file.versions, keep, self.keep_labeled_versions);
+        if pruned > 0 {
+            info!("Pruned {} old version(s) of character {}", pruned, id);
+            self.store_versions(&path, file).await;
+        }
+        Some(pruned)
+    }
+
     pub async fn delete_version(&self, id: Uuid, version: u32) -> Option<bool> {
+        let _lock = self.lock_character(id).await;
         let (path, mut file) = self.read_character_file(id).await?;
 
         // Prevent deleting the last remaining version
@@ -377,10 +1039,12 @@ impl CharacterStore {
 
         {
             write_character_file(&path, &file).await;
-            if let Some(summary) = summary_from_file(&file) {
-                let mut index = self.characters.write().await;
+            if let Some(mut summary) = summary_from_file(&file, |c| self.power_score(c)) {
+                let mut index = self.index_mut().await;
                 if let Some(ci) = index.get_mut(&id) {
+                    summary.tags = std::mem::take(&mut ci.summary.tags);
                     ci.summary = summary;
+                    ci.references = latest_references(&file);
                 }
             }
         }
@@ -388,9 +1052,14 @@ impl CharacterStore {
         Some(true)
     }
 
-    pub async fn delete(&self, id: Uuid) -> bool {
+    /// Deletes every version of a character; `deleted_by` is recorded in its
+    /// audit log, which stays behind.
+    pub async fn delete(&self, id: Uuid, deleted_by: Option<String>) -> bool {
+        // Waits out any save in progress, so it cannot rewrite the file after
+        // it is removed
+        let _lock = self.lock_character(id).await;
         let path = {
-            let mut index = self.characters.write().await;
+            let mut index = self.index_mut().await;
             match index.remove(&id) {
                 Some(ci) => ci.file_path,
                 None => return false,
@@ -399,78 +1068,461 @@ impl CharacterStore {
         if let Err(e) = tokio::fs::remove_file(&path).await {
             warn!("Failed to remove character file {:?}: {}", path, e);
         }
-        // Also remove the portrait if it exists
-        let portrait_path = self.portraits_dir.join(format!("{}.png", id));
-        let _ = tokio::fs::remove_file(&portrait_path).await;
+        // Also unlink the portrait; the gallery copy stays for reuse
+        self.remove_portrait_link(id).await;
+        self.save_tags().await;
+        self.remove_notes(&[id]).await;
+        self.audit(id, AuditAction::Deleted, None, deleted_by);
         true
     }
 
+    /// Deletes several characters, one at a time under each one's lock.
+    /// Returns the ids that were found and removed.
+    pub async fn delete_many(&self, ids: &BTreeSet<Uuid>, deleted_by: Option<String>) -> Vec<Uuid> {
+        let mut removed = Vec::new();
+        for &id in ids {
+            let _lock = self.lock_character(id).await;
+            let Some(ci) = self.index_mut().await.remove(&id) else {
+                continue;
+            };
+            if let Err(e) = tokio::fs::remove_file(&ci.file_path).await {
+                warn!("Failed to remove character file {:?}: {}", ci.file_path, e);
+            }
+            self.remove_portrait_link(id).await;
+            self.audit(id, AuditAction::Deleted, None, deleted_by.clone());
+            removed.push(id);
+        }
+        if !removed.is_empty() {
+            self.save_tags().await;
+            self.remove_notes(&removed).await;
+        }
+        removed
+    }
+
+    fn gallery_path(&self, hash: &str, suffix: &str) -> PathBuf {
+        self.portraits_dir
+            .join(GALLERY_DIR)
+            .join(format!("{}{}", hash, suffix))
+    }
+
+    /// File holding the content hash of a character's portrait.
+    fn portrait_link_path(&self, id: Uuid) -> PathBuf {
+        self.portraits_dir.join(format!("{}.hash", id))
+    }
+
+    async fn remove_portrait_link(&self, id: Uuid) {
+        let _ = tokio::fs::remove_file(self.portrait_link_path(id)).await;
+        // Portraits saved before the gallery existed
+        let _ = tokio::fs::remove_file(self.portraits_dir.join(format!("{}.png", id))).await;
+    }
+
+    /// Stores the image in the gallery (once per distinct content) and points
+    /// the character at it.
     pub async fn save_portrait(&self, id: Uuid, png_data: &[u8]) {
-        let path = self.portraits_dir.join(format!("{}.png", id));
-        if let Err(e) = tokio::fs::write(&path, png_data).await {
-            error!("Failed to save portrait for {}: {}", id, e);
+        let hash = content_hash(png_data);
+        let image_path = self.gallery_path(&hash, ".png");
+        if !image_path.exists() {
+            if let Err(e) = tokio::fs::write(&image_path, png_data).await {
+                error!("Failed to save portrait for {}: {}", id, e);
+                return;
+            }
+            match make_thumbnail(png_data) {
+                Some(thumb) => {
+                    let thumb_path = self.gallery_path(&hash, ".thumb.png");
+                    if let Err(e) = tokio::fs::write(&thumb_path, thumb).await {
+                        warn!("Failed to save portrait thumbnail {:?}: {}", thumb_path, e);
+                    }
+                }
+                None => warn!("Portrait {} could not be decoded for a thumbnail", hash),
+            }
+        }
+        self.remove_portrait_link(id).await;
+        if let Err(e) = tokio::fs::write(self.portrait_link_path(id), &hash).await {
+            error!("Failed to link portrait for {}: {}", id, e);
         }
     }
 
     pub async fn load_portrait(&self, id: Uuid) -> Option<Vec<u8>> {
-        let path = self.portraits_dir.join(format!("{}.png", id));
-        tokio::fs::read(&path).await.ok()
+        match tokio::fs::read_to_string(self.portrait_link_path(id)).await {
+            Ok(hash) => self.gallery_portrait(hash.trim()).await,
+            Err(_) => {
+                let path = self.portraits_dir.join(format!("{}.png", id));
+                tokio::fs::read(&path).await.ok()
+            }
+        }
+    }
+
+    /// Full image of a gallery portrait. Only hex hashes are accepted.
+    pub async fn gallery_portrait(&self, hash: &str) -> Option<Vec<u8>> {
+        if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        tokio::fs::read(self.gallery_path(hash, ".png")).await.ok()
+    }
+
+    /// Hashes and thumbnails of every gallery portrait, sorted by hash.
+    pub async fn list_portraits(&self) -> Vec<(String, Vec<u8>)> {
+        let mut portraits = Vec::new();
+        let Ok(mut entries) = tokio::fs::read_dir(self.portraits_dir.join(GALLERY_DIR)).await
+        else {
+            return portraits;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let file_name = entry.file_name();
+            let Some(hash) = file_name
+                .to_str()
+                .and_then(|n| n.strip_suffix(".thumb.png"))
+            else {
+                continue;
+            };
+            if let Ok(thumb) = tokio::fs::read(entry.path()).await {
+                portraits.push((hash.to_string(), thumb));
+            }
+        }
+        portraits.sort();
+        portraits
+    }
+
+    /// Saves `character` as a new version, tagged with the identity of the
+    /// connection that saved it. `Ok(None)` when the character does not exist.
+    pub async fn update(
+        &self,
+        character: Character,
+        saved_by: Option<String>,
+    ) -> Result<Option<CharacterSummary>, NameTaken> {
+        // Without a base version there is nothing to conflict with
+        self.update_from(character, None, saved_by)
+            .await
+            .map_err(|_| NameTaken)
     }
 
-    pub async fn update(&self, mut character: Character) -> Option<CharacterSummary> {
+    /// Like `update`, but refuses to save over versions the client has not
+    /// seen: if the latest version is no longer `base_version`, the save is
+    /// rejected with the stored latest. Saves that change nothing still
+    /// succeed either way.
+    pub async fn update_from(
+        &self,
+        character: Character,
+        base_version: Option<u32>,
+        saved_by: Option<String>,
+    ) -> Result<Option<CharacterSummary>, UpdateError> {
+        let _lock = self.lock_character(character.id).await;
+        self.update_locked(character, base_version, saved_by).await
+    }
+
+    /// `update_from` for callers already holding the character's lock.
+    async fn update_locked(
+        &self,
+        mut character: Character,
+        base_version: Option<u32>,
+        saved_by: Option<String>,
+    ) -> Result<Option<CharacterSummary>, UpdateError> {
+        if self.name_taken(&character.name, character.id).await {
+            return Err(UpdateError::NameTaken);
+        }
+        let registries = self.registries();
         character.recalculate_effects(
-            &self.trait_registry,
-            &self.weapon_registry,
-            &self.equipment_registry,
+            &registries.traits,
+            &registries.weapons,
+            &registries.equipment,
+            &self.ruleset.campaign.effect_modifiers,
         );
 
-        let (path, mut file) = self.read_character_file(character.id).await?;
+        let Some((path, mut file)) = self.read_character_file(character.id).await else {
+            return Ok(None);
+        };
 
         // Skip saving if nothing changed since the last version.
         // Compare with active_effects cleared because it is #[serde(skip)]
         // and will always be empty in the deserialized stored version.
+        let now = current_timestamp();
+        let mut incoming = character.clone();
+        incoming.active_effects.clear();
         if let Some(latest) = file.versions.last() {
-            let mut incoming = character.clone();
-            incoming.active_effects.clear();
             if latest.character == incoming {
                 info!("No changes for character {}, skipping save", character.id);
-                return summary_from_file(&file);
+                return Ok(summary_from_file(&file, |c| self.power_score(c)));
+            }
+            if base_version.is_some_and(|base| base != latest.version) {
+                info!(
+                    "Character {} was saved past version {:?}, refusing update",
+                    character.id, base_version
+                );
+                return Err(UpdateError::Conflict(Box::new(latest.clone())));
             }
         }
+        let version = match file.versions.last_mut() {
+            // Cosmetic edits rewrite the latest version rather than adding one
+            Some(latest)
+                if !significant_change(&latest.character, &incoming, &self.cosmetic_fields) =>
+            {
+                info!(
+                    "Cosmetic changes for character {}, updating in place",
+                    character.id
+                );
+                latest.character = incoming;
+                latest.saved_at = now;
+                latest.saved_by.clone_from(&saved_by);
+                latest.version
+            }
+            latest => {
+                let new_version_num = latest.map(|v| v.version + 1).unwrap_or(1);
+                file.versions.push(CharacterVersion {
+                    version: new_version_num,
+                    saved_at: now,
+                    character: character.clone(),
+                    saved_by: saved_by.clone(),
+                    label: None,
+                });
+                new_version_num
+            }
+        };
 
-        let now = current_timestamp();
-        let new_version_num = file.versions.last().map(|v| v.version + 1).unwrap_or(1);
+        let summary = self.store_versions(&path, file).await;
+        self.audit(character.id, AuditAction::Updated, Some(version), saved_by);
+        Ok(summary)
+    }
+
+    /// Saves a copy of `version` as the newest version, leaving the history
+    /// as it is. Unlike `update`, this always adds a version, even when the
+    /// copy matches the latest one. `Ok(None)` when the character or version
+    /// does not exist.
+    pub async fn rollback(
+        &self,
+        id: Uuid,
+        version: u32,
+        saved_by: Option<String>,
+    ) -> Result<Option<CharacterSummary>, NameTaken> {
+        let _lock = self.lock_character(id).await;
+        let Some((path, mut file)) = self.read_character_file(id).await else {
+            return Ok(None);
+        };
+        let Some(restored) = file.versions.iter().find(|v| v.version == version) else {
+            return Ok(None);
+        };
+        let character = restored.character.clone();
+        if self.name_taken(&character.name, id).await {
+            return Err(NameTaken);
+        }
+        let next_version = file.versions.last().map_or(1, |v| v.version + 1);
         file.versions.push(CharacterVersion {
-            version: new_version_num,
-            saved_at: now,
-            character: character.clone(),
+            version: next_version,
+            saved_at: current_timestamp(),
+            character,
+            saved_by: saved_by.clone(),
+            label: None,
         });
+        info!(
+            "Restored version {} of character {} as version {}",
+            version, id, next_version
+        );
+        let summary = self.store_versions(&path, file).await;
+        let action = AuditAction::RolledBack { from: version };
+        self.audit(id, action, Some(next_version), saved_by);
+        Ok(summary)
+    }
 
-        write_character_file(&path, &file).await;
+    /// Drops the oldest versions beyond the retention limit, writes the file
+    /// and refreshes its index entry. Returns the new summary.
+    async fn store_versions(
+        &self,
+        path: &Path,
+        mut file: CharacterFile,
+    ) -> Option<CharacterSummary> {
+        if let Some(max) = self.max_versions {
+            let excess = prune_oldest(&mut file.versions, max, self.keep_labeled_versions);
+            if excess > 0 {
+                info!("Pruned {} old version(s) of character {}", excess, file.id);
+            }
+        }
 
-        let summary = CharacterSummary {
-            id: character.id,
-            name: character.name,
-            race: character.race,
-            class: character.class,
-            level: character.level,
-            version_count: file.versions.len() as u32,
-            last_updated: now,
-        };
+        write_character_file(path, &file).await;
 
+        let mut summary = summary_from_file(&file, |c| self.power_score(c))?;
         {
-            let mut index = self.characters.write().await;
-            if let Some(ci) = index.get_mut(&character.id) {
+            let mut index = self.index_mut().await;
+            if let Some(ci) = index.get_mut(&file.id) {
+                summary.tags = std::mem::take(&mut ci.summary.tags);
                 ci.summary = summary.clone();
+                ci.references = latest_references(&file);
             }
         }
-
         Some(summary)
     }
 
-    /// Inserts a named item into a JSON array file.
-    /// Returns an error if an item with the same name already exists.
-    async fn insert_named_item<T>(&self, filename: &str, item: T) -> Result<(), String>
+    /// Recomputes effects and derived resources for the latest version of a
+    /// character and saves a repaired version if anything drifted.
+    /// Returns `Some(true)` when repaired, `Some(false)` when already correct,
+    /// `None` when the character does not exist.
+    pub async fn recompute(&self, id: Uuid) -> Option<bool> {
+        let _lock = self.lock_character(id).await;
+        let latest = self.get_character_version(id, None).await?;
+        let mut character = latest.character;
+        let registries = self.registries();
+        character.recalculate_effects(
+            &registries.traits,
+            &registries.weapons,
+            &registries.equipment,
+            &self.ruleset.campaign.effect_modifiers,
+        );
+        character.apply_encumbrance(
+            &registries.items,
+            &registries.equipment,
+            &registries.weapons,
+        );
+        let ranks_synced = character.sync_free_skill_ranks();
+        if !character.repair_resources(&self.ruleset.campaign) && !ranks_synced {
+            return Some(false);
+        }
+        info!("Repairing derived resources for character {}", id);
+        // The name is unchanged and the lock is held, so the save cannot fail
+        self.update_locked(character, Some(latest.version), None)
+            .await
+            .ok()??;
+        Some(true)
+    }
+
+    /// Runs `recompute` over every character. Returns the ids that were repaired.
+    pub async fn recompute_all(&self) -> Vec<Uuid> {
+        let ids: Vec<Uuid> = self.characters.read().await.keys().copied().collect();
+        let mut repaired = Vec::new();
+        for id in ids {
+            if self.recompute(id).await == Some(true) {
+                repaired.push(id);
+            }
+        }
+        repaired
+    }
+
+    /// Advisory power score of `character` under the loaded registries and ruleset.
+    fn power_score(&self, character: &Character) -> u32 {
+        let registries = self.registries();
+        character.power_score(
+            &registries.traits,
+            &registries.weapons,
+            &registries.equipment,
+            &self.ruleset,
+        )
+    }
+
+    /// Snapshot of the loaded registries; a reload swaps in a new one.
+    pub fn registries(&self) -> Arc<Registries> {
+        self.registries
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Re-reads the registry files from the data directory and swaps them in.
+    /// A file that cannot be read keeps its previous definitions. Logs the
+    /// entry count of every registry and whether it changed. When any did,
+    /// power scores are recomputed; the summaries rescored are returned.
+    pub async fn reload_registries(
+        &self,
+    ) -> (Arc<Registries>, Vec<RegistryReload>, Vec<CharacterSummary>) {
+        let previous = self.registries();
+        let (loaded, reports) = Registries::load(&self.data_dir, &previous);
+        for report in &reports {
+            match (&report.error, report.changed) {
+                (Some(e), _) => warn!("{} not reloaded, kept previous: {}", report.file, e),
+                (None, true) => info!("{} changed, {} entries", report.file, report.entries),
+                (None, false) => info!("{} unchanged, {} entries", report.file, report.entries),
+            }
+        }
+        let loaded = Arc::new(loaded);
+        *self
+            .registries
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = loaded.clone();
+        let rescored = if reports.iter().any(|r| r.changed) {
+            self.rescore_all().await
+        } else {
+            Vec::new()
+        };
+        (loaded, reports, rescored)
+    }
+
+    /// Recomputes the power score of every character against the current
+    /// registries. Returns the summaries whose score changed.
+    async fn rescore_all(&self) -> Vec<CharacterSummary> {
+        let ids: Vec<Uuid> = self.characters.read().await.keys().copied().collect();
+        let mut rescored = Vec::new();
+        for id in ids {
+            let Some((_, file)) = self.read_character_file(id).await else {
+                continue;
+            };
+            let Some(latest) = file.versions.last() else {
+                continue;
+            };
+            let score = self.power_score(&latest.character);
+            let mut index = self.index_mut().await;
+            if let Some(ci) = index.get_mut(&id) {
+                if ci.summary.power_score != score {
+                    ci.summary.power_score = score;
+                    rescored.push(ci.summary.clone());
+                }
+            }
+        }
+        rescored
+    }
+
+    /// Grants `amount` XP to the latest version of a character, applying the
+    /// level-ups it reaches, and saves the result as a new version.
+    pub async fn grant_xp(
+        &self,
+        id: Uuid,
+        amount: u32,
+        saved_by: Option<String>,
+    ) -> Option<CharacterSummary> {
+        let _lock = self.lock_character(id).await;
+        let latest = self.get_character_version(id, None).await?;
+        let mut character = latest.character;
+        let registries = self.registries();
+        character.recalculate_effects(
+            &registries.traits,
+            &registries.weapons,
+            &registries.equipment,
+            &self.ruleset.campaign.effect_modifiers,
+        );
+        character.grant_xp(amount, &self.ruleset);
+        // The name is unchanged and the lock is held, so the save cannot fail
+        self.update_locked(character, Some(latest.version), saved_by)
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Starts a new in-game day: resets the long rests of every character that
+    /// rested, saving a new version of each. Returns their ids.
+    pub async fn new_day(&self) -> Vec<Uuid> {
+        let ids: Vec<Uuid> = self.characters.read().await.keys().copied().collect();
+        let mut rested = Vec::new();
+        for id in ids {
+            let _lock = self.lock_character(id).await;
+            let Some(latest) = self.get_character_version(id, None).await else {
+                continue;
+            };
+            let mut character = latest.character;
+            if !character.new_day() {
+                continue;
+            }
+            // The name is unchanged and the lock is held, so the save cannot fail
+            let saved = self.update_locked(character, Some(latest.version), None);
+            if saved.await.ok().flatten().is_some() {
+                rested.push(id);
+            }
+        }
+        rested
+    }
+
+    /// Adds a named definition to a registry file. An entry with the same name
+    /// is an error unless `replace` is set, in which case it is overwritten.
+    async fn insert_named_item<T>(
+        &self,
+        filename: &str,
+        item: T,
+        replace: bool,
+    ) -> Result<(), String>
     where
         T: Named + serde::Serialize + DeserializeOwned,
     {
@@ -480,25 +1532,878 @@ impl CharacterStore {
             Err(_) => Vec::new(),
         };
         let name = item.name().to_string();
-        if items.iter().any(|existing| existing.name() == name) {
-            return Err(format!("Item with name \"{name}\" already exists"));
+        match items.iter().position(|existing| existing.name() == name) {
+            Some(idx) if replace => items[idx] = item,
+            Some(_) => return Err(format!("Item with name \"{name}\" already exists")),
+            None => items.push(item),
         }
-        items.push(item);
         let json = serde_json::to_string_pretty(&items).map_err(|e| e.to_string())?;
         tokio::fs::write(&path, json)
             .await
             .map_err(|e| e.to_string())
     }
 
-    pub async fn save_weapon(&self, weapon: Weapon) -> Result<(), String> {
-        self.insert_named_item("weapons.json", weapon).await
+    pub async fn save_weapon(&self, weapon: Weapon, replace: bool) -> Result<(), String> {
+        self.insert_named_item("weapons.json", weapon, replace)
+            .await
+    }
+
+    pub async fn save_equipment(&self, equipment: Equipment, replace: bool) -> Result<(), String> {
+        self.insert_named_item("equipment.json", equipment, replace)
+            .await
+    }
+
+    pub async fn save_item(&self, item: Item, replace: bool) -> Result<(), String> {
+        self.insert_named_item("items.json", item, replace).await
+    }
+
+    /// Removes the definition called `name` from a registry file. Returns
+    /// false if the file has no such entry.
+    async fn remove_named_item<T>(&self, filename: &str, name: &str) -> Result<bool, String>
+    where
+        T: Named + serde::Serialize + DeserializeOwned,
+    {
+        let path = self.data_dir.join(filename);
+        let mut items: Vec<T> = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| e.to_string())?,
+            Err(_) => return Ok(false),
+        };
+        let before = items.len();
+        items.retain(|existing| existing.name() != name);
+        if items.len() == before {
+            return Ok(false);
+        }
+        let json = serde_json::to_string_pretty(&items).map_err(|e| e.to_string())?;
+        tokio::fs::write(&path, json)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+
+    pub async fn delete_weapon(&self, name: &str) -> Result<bool, String> {
+        self.remove_named_item::<Weapon>("weapons.json", name).await
+    }
+
+    pub async fn delete_equipment(&self, name: &str) -> Result<bool, String> {
+        self.remove_named_item::<Equipment>("equipment.json", name)
+            .await
+    }
+
+    pub async fn delete_item(&self, name: &str) -> Result<bool, String> {
+        self.remove_named_item::<Item>("items.json", name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a store backed by a fresh temporary data directory.
+    async fn test_store() -> (CharacterStore, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("character-store-{}", Uuid::new_v4()));
+        let store = CharacterStore::new(dir.to_str().unwrap()).await;
+        (store, dir)
+    }
+
+    async fn create_named(store: &CharacterStore, name: &str) -> Uuid {
+        store
+            .create(
+                name.to_string(),
+                None,
+                Race::default(),
+                Class::default(),
+                Characteristics::default(),
+                Vec::new(),
+                Vec::new(),
+            )
+            .await
+            .unwrap()
+            .id
+    }
+
+    #[tokio::test]
+    async fn test_replace_overwrites_named_item() {
+        let (store, dir) = test_store().await;
+        let goblet = |description: &str| Item {
+            name: "Goblet".to_string(),
+            description: description.to_string(),
+            value: None,
+            weight: None,
+        };
+        store.save_item(goblet("Tin"), false).await.unwrap();
+        assert!(store.save_item(goblet("Silver"), false).await.is_err());
+        store.save_item(goblet("Silver"), true).await.unwrap();
+
+        let content = std::fs::read_to_string(dir.join("items.json")).unwrap();
+        let items: Vec<Item> = serde_json::from_str(&content).unwrap();
+        assert_eq!(items, vec![goblet("Silver")]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_delete_removes_only_named_item() {
+        let (store, dir) = test_store().await;
+        let item = |name: &str| Item {
+            name: name.to_string(),
+            description: String::new(),
+            value: None,
+            weight: None,
+        };
+        store.save_item(item("Goblet"), false).await.unwrap();
+        store.save_item(item("Candle"), false).await.unwrap();
+
+        assert_eq!(store.delete_item("Goblet").await, Ok(true));
+        assert_eq!(store.delete_item("Goblet").await, Ok(false));
+        let content = std::fs::read_to_string(dir.join("items.json")).unwrap();
+        let items: Vec<Item> = serde_json::from_str(&content).unwrap();
+        assert_eq!(items, vec![item("Candle")]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_tags_persist_and_filter() {
+        let (store, dir) = test_store().await;
+        let frodo = create_named(&store, "Frodo").await;
+        let sam = create_named(&store, "Sam").await;
+        create_named(&store, "Smeagol").await;
+
+        let summary = store
+            .set_tags(
+                frodo,
+                vec!["party".into(), " hobbit ".into(), "party".into()],
+            )
+            .await
+            .unwrap();
+        assert_eq!(summary.tags, vec!["hobbit", "party"]);
+        store.set_tags(sam, vec!["party".into()]).await.unwrap();
+        let versions = store.get_version_list(frodo).await.unwrap().len();
+        assert_eq!(versions, 1);
+
+        let reloaded = CharacterStore::new(dir.to_str().unwrap()).await;
+        let mut party: Vec<Uuid> = reloaded
+            .get_all_summaries()
+            .await
+            .iter()
+            .filter(|s| s.has_tag("party"))
+            .map(|s| s.id)
+            .collect();
+        party.sort();
+        let mut expected = vec![frodo, sam];
+        expected.sort();
+        assert_eq!(party, expected);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_reference_matches_trait() {
+        let (store, dir) = test_store().await;
+        let cursed = create_named(&store, "Isildur").await;
+        create_named(&store, "Elendil").await;
+
+        let mut character = store
+            .get_character_version(cursed, None)
+            .await
+            .unwrap()
+            .character;
+        character.traits.push("Ring-bearer".to_string());
+        store.update(character, None).await.unwrap();
+
+        let found = store
+            .find_by_reference(ReferenceKind::Trait, "Ring-bearer")
+            .await;
+        assert_eq!(found.iter().map(|s| s.id).collect::<Vec<_>>(), vec![cursed]);
+        assert!(store
+            .find_by_reference(ReferenceKind::Item, "Ring-bearer")
+            .await
+            .is_empty());
+
+        let reloaded = CharacterStore::new(dir.to_str().unwrap()).await;
+        let found = reloaded
+            .find_by_reference(ReferenceKind::Trait, "Ring-bearer")
+            .await;
+        assert_eq!(found.len(), 1);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_same_portrait_is_stored_once() {
+        let (store, dir) = test_store().await;
+        let a = create_named(&store, "Elrond").await;
+        let b = create_named(&store, "Elladan").await;
+
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbImage::from_pixel(128, 96, image::Rgb([200, 40, 40]))
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+        let png = png.into_inner();
+        store.save_portrait(a, &png).await;
+        store.save_portrait(b, &png).await;
+
+        let gallery = store.list_portraits().await;
+        assert_eq!(gallery.len(), 1);
+        assert_eq!(gallery[0].0, content_hash(&png));
+        assert_eq!(
+            content_hash(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        let thumb = image::load_from_memory(&gallery[0].1).unwrap();
+        assert!(thumb.width() <= THUMBNAIL_SIZE && thumb.height() <= THUMBNAIL_SIZE);
+        assert_eq!(store.load_portrait(a).await, Some(png.clone()));
+        assert_eq!(store.load_portrait(b).await, Some(png));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_delete_many_removes_only_given_ids() {
+        let (store, dir) = test_store().await;
+        let a = create_named(&store, "Aragorn").await;
+        let b = create_named(&store, "Boromir").await;
+        let c = create_named(&store, "Celeborn").await;
+
+        let ids = BTreeSet::from([a, c, Uuid::new_v4()]);
+        let removed: BTreeSet<Uuid> = store.delete_many(&ids, None).await.into_iter().collect();
+        assert_eq!(removed, BTreeSet::from([a, c]));
+
+        let remaining: Vec<Uuid> = store
+            .get_all_summaries()
+            .await
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(remaining, vec![b]);
+        assert!(store.get_character_version(a, None).await.is_none());
+        assert!(store.get_character_version(b, None).await.is_some());
+
+        let files = std::fs::read_dir(dir.join("characters")).unwrap().count();
+        assert_eq!(files, 1);
+        let _ = std::fs::remove_dir_all(dir);
     }
+    #[tokio::test]
+    async fn test_recompute_repairs_stale_action_point_max() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Gimli").await;
+
+        let mut stale = store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        let expected_max = stale.action_points.max;
+        stale.action_points.max = expected_max + 5;
+        stale.action_points.current = expected_max + 3;
+        store.update(stale, None).await.unwrap();
+        let versions_before = store.get_version_list(id).await.unwrap().len();
+
+        assert_eq!(store.recompute(id).await, Some(true));
+        let repaired = store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        assert_eq!(repaired.action_points.max, expected_max);
+        // Two points were spent before the repair and stay spent.
+        assert_eq!(repaired.action_points.current, expected_max - 2);
+        assert_eq!(
+            store.get_version_list(id).await.unwrap().len(),
+            versions_before + 1
+        );
 
-    pub async fn save_equipment(&self, equipment: Equipment) -> Result<(), String> {
-        self.insert_named_item("equipment.json", equipment).await
+        // Already correct: no new version.
+        assert_eq!(store.recompute_all().await, Vec::<Uuid>::new());
+        assert_eq!(
+            store.get_version_list(id).await.unwrap().len(),
+            versions_before + 1
+        );
+        assert_eq!(store.recompute(Uuid::new_v4()).await, None);
+        let _ = std::fs::remove_dir_all(dir);
     }
 
-    pub async fn save_item(&self, item: Item) -> Result<(), String> {
-        self.insert_named_item("items.json", item).await
+    #[tokio::test]
+    async fn test_max_versions_drops_oldest() {
+        let (store, dir) = test_store().await;
+        let store = store.with_max_versions(Some(3));
+        let id = create_named(&store, "Legolas").await;
+
+        for xp in 1..=3 {
+            let mut character = store
+                .get_character_version(id, None)
+                .await
+                .unwrap()
+                .character;
+            character.experience = xp;
+            store.update(character, None).await.unwrap();
+        }
+
+        let versions: Vec<u32> = store
+            .get_version_list(id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|v| v.version)
+            .collect();
+        assert_eq!(versions, vec![2, 3, 4]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_prune_versions_keeps_newest_and_labeled() {
+        let (store, dir) = test_store().await;
+        let store = store.with_keep_labeled_versions(true);
+        let id = create_named(&store, "Gimli").await;
+        for xp in 1..=4 {
+            let mut character = store
+                .get_character_version(id, None)
+                .await
+                .unwrap()
+                .character;
+            character.experience = xp;
+            store.update(character, None).await.unwrap();
+        }
+        store
+            .set_version_label(id, 2, "before the boss".to_string())
+            .await;
+        async fn versions(store: &CharacterStore, id: Uuid) -> Vec<u32> {
+            let list = store.get_version_list(id).await.unwrap();
+            list.iter().map(|v| v.version).collect()
+        }
+
+        assert_eq!(store.prune_versions(id, 2).await, Some(2));
+        assert_eq!(versions(&store, id).await, vec![2, 4, 5]);
+        // Never below a single version
+        let store = store.with_keep_labeled_versions(false);
+        assert_eq!(store.prune_versions(id, 0).await, Some(2));
+        assert_eq!(versions(&store, id).await, vec![5]);
+        assert_eq!(store.prune_versions(Uuid::new_v4(), 1).await, None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_cosmetic_edit_updates_latest_version_in_place() {
+        let (store, dir) = test_store().await;
+        let store = store.with_cosmetic_fields(CosmeticField::all());
+        let id = create_named(&store, "Scribe").await;
+        // Rearranging the hotbar and recolouring keep the single version
+        let mut character = store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        character.hotbar = vec!["Fireball".to_string()];
+        character.accent_color = Some([200, 40, 40]);
+        store.update(character, None).await.unwrap();
+        let stored = store.get_character_version(id, None).await.unwrap();
+        assert_eq!(stored.version, 1);
+        assert_eq!(stored.character.hotbar, vec!["Fireball".to_string()]);
+        assert_eq!(stored.character.accent_color, Some([200, 40, 40]));
+
+        // Anything else still adds a version
+        let mut character = stored.character;
+        character.experience = 5;
+        store.update(character, None).await.unwrap();
+        assert_eq!(
+            store.get_character_version(id, None).await.unwrap().version,
+            2
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_notes_only_edit_does_not_add_version() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Diarist").await;
+        // Without configured cosmetic fields every change is versioned
+        let mut character = store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        character.notes = "Owes the innkeeper".to_string();
+        store.update(character.clone(), None).await.unwrap();
+        assert_eq!(store.latest_version(id).await, Some(2));
+
+        let store = store.with_cosmetic_fields([CosmeticField::Notes].into());
+        character.notes.push_str(" 3 gold");
+        store
+            .update_from(character, None, Some("Mira".to_string()))
+            .await
+            .unwrap();
+        let stored = store.get_character_version(id, None).await.unwrap();
+        assert_eq!(stored.version, 2);
+        assert_eq!(stored.character.notes, "Owes the innkeeper 3 gold");
+        assert_eq!(stored.saved_by.as_deref(), Some("Mira"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_stale_update_is_refused_with_latest() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Contested").await;
+        let base = store.get_character_version(id, None).await.unwrap();
+
+        // Two editors start from version 1; the first one saves
+        let mut first = base.character.clone();
+        first.experience = 5;
+        let summary = store.update_from(first, Some(1), None).await.unwrap();
+        assert_eq!(summary.unwrap().latest_version, 2);
+
+        let mut second = base.character.clone();
+        second.level = 3;
+        match store.update_from(second.clone(), Some(1), None).await {
+            Err(UpdateError::Conflict(latest)) => {
+                assert_eq!(latest.version, 2);
+                assert_eq!(latest.character.experience, 5);
+            }
+            other => panic!("expected a conflict, got {other:?}"),
+        }
+        assert_eq!(store.latest_version(id).await, Some(2));
+
+        // Saving what is already stored is still a no-op, whatever the base
+        let stored = store.get_character_version(id, None).await.unwrap();
+        assert!(store
+            .update_from(stored.character, Some(1), None)
+            .await
+            .is_ok());
+        // Starting over from the latest version saves
+        assert!(store.update_from(second, Some(2), None).await.is_ok());
+        assert_eq!(store.latest_version(id).await, Some(3));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_saves_from_same_base_conflict_once() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Raced").await;
+        let base = store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+
+        // Both editors start from version 1 and save at the same time
+        let start = Arc::new(tokio::sync::Barrier::new(2));
+        let saves = (1..=2).map(|experience| {
+            let store = store.clone();
+            let start = start.clone();
+            let mut edited = base.clone();
+            edited.experience = experience;
+            tokio::spawn(async move {
+                start.wait().await;
+                store.update_from(edited, Some(1), None).await
+            })
+        });
+        let mut conflicts = 0;
+        for save in saves.collect::<Vec<_>>() {
+            if let Err(UpdateError::Conflict(_)) = save.await.unwrap() {
+                conflicts += 1;
+            }
+        }
+        assert_eq!(conflicts, 1);
+        assert_eq!(store.latest_version(id).await, Some(2));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_grant_during_save_keeps_both_changes() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Busy").await;
+        let mut edited = store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        edited.notes = "Met the baron".to_string();
+
+        let start = Arc::new(tokio::sync::Barrier::new(2));
+        let save = tokio::spawn({
+            let (store, start) = (store.clone(), start.clone());
+            async move {
+                start.wait().await;
+                store.update_from(edited, Some(1), None).await
+            }
+        });
+        let grant = tokio::spawn({
+            let store = store.clone();
+            async move {
+                start.wait().await;
+                store.grant_xp(id, 5, None).await
+            }
+        });
+        let saved = save.await.unwrap();
+        assert!(grant.await.unwrap().is_some());
+
+        // The grant is never lost; the player's edit either lands too or is
+        // refused as a conflict
+        let latest = store.get_character_version(id, None).await.unwrap();
+        assert_eq!(latest.character.experience, 5);
+        match saved {
+            Ok(_) => assert_eq!(latest.character.notes, "Met the baron"),
+            Err(e) => assert!(matches!(e, UpdateError::Conflict(_))),
+        }
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_delete_during_save_leaves_no_file() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Doomed").await;
+        let mut edited = store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        edited.experience = 3;
+
+        let start = Arc::new(tokio::sync::Barrier::new(2));
+        let save = tokio::spawn({
+            let (store, start) = (store.clone(), start.clone());
+            async move {
+                start.wait().await;
+                store.update(edited, None).await
+            }
+        });
+        let delete = tokio::spawn({
+            let store = store.clone();
+            async move {
+                start.wait().await;
+                store.delete(id, None).await
+            }
+        });
+        let _ = save.await.unwrap();
+        assert!(delete.await.unwrap());
+
+        let files = std::fs::read_dir(dir.join("characters")).unwrap().count();
+        assert_eq!(files, 0);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_owner_hash_verifies_and_is_upgraded() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Elder").await;
+        assert!(store.set_owner_password(id, "moss").await);
+        let (path, mut file) = store.read_character_file(id).await.unwrap();
+        // A salted SHA-256 as written before passwords were stretched
+        let salt = "0123456789abcdef";
+        let digest = hex(&Sha256::digest(format!("{salt}moss")));
+        file.owner_hash = Some(format!("{salt}${digest}"));
+        write_character_file(&path, &file).await;
+
+        assert_eq!(store.check_owner_password(id, "stone").await, Some(false));
+        assert_eq!(store.check_owner_password(id, "moss").await, Some(true));
+        let (_, file) = store.read_character_file(id).await.unwrap();
+        assert!(file.owner_hash.unwrap().starts_with("pbkdf2-sha256$"));
+        assert_eq!(store.check_owner_password(id, "moss").await, Some(true));
+        assert_eq!(store.check_owner_password(id, "stone").await, Some(false));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_replayed_update_saves_once() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Replayed").await;
+        let mut edited = store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        edited.experience = 7;
+
+        // A client replaying its queue after a reconnect sends the same save again
+        for _ in 0..2 {
+            let summary = store.update_from(edited.clone(), Some(1), None).await;
+            assert_eq!(summary.unwrap().unwrap().latest_version, 2);
+        }
+        assert_eq!(store.latest_version(id).await, Some(2));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_reload_registries_picks_up_edited_files() {
+        let (store, dir) = test_store().await;
+        assert!(store.registries().weapons.weapons.is_empty());
+
+        let weapons = include_str!("../../data/weapons.json");
+        std::fs::write(dir.join("weapons.json"), weapons).unwrap();
+        std::fs::write(dir.join("traits.json"), "not json").unwrap();
+        let (registries, reports, _) = store.reload_registries().await;
+
+        let expected = shared::WeaponRegistry::load_from_str(weapons).unwrap();
+        assert_eq!(registries.weapons, expected);
+        assert_eq!(store.registries().weapons, expected);
+        let weapons_report = reports.iter().find(|r| r.file == "weapons.json").unwrap();
+        assert!(weapons_report.changed);
+        assert_eq!(weapons_report.entries, expected.weapons.len());
+        // An unreadable file keeps the previous definitions
+        let traits_report = reports.iter().find(|r| r.file == "traits.json").unwrap();
+        assert!(traits_report.error.is_some());
+        assert!(!traits_report.changed);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_reload_registries_rescores_characters() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Knight").await;
+        let mut character = store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        // Plate is not defined yet, so it adds nothing to the score
+        character
+            .equipped_equipment
+            .insert(shared::EquipmentSlot::Armor, vec!["Plate".to_string()]);
+        store.update(character, None).await.unwrap();
+        let before = store.get_all_summaries().await[0].power_score;
+
+        std::fs::write(
+            dir.join("equipment.json"),
+            r#"[{ "name": "Plate", "description": "", "slot": "Armor",
+                  "effects": [{ "Armor": 6 }] }]"#,
+        )
+        .unwrap();
+        let (_, _, rescored) = store.reload_registries().await;
+
+        // 6 armor is worth 18 points
+        assert_eq!(rescored.len(), 1);
+        assert_eq!(rescored[0].power_score, before + 18);
+        assert_eq!(store.get_all_summaries().await[0].power_score, before + 18);
+        // Nothing changed on disk, so nothing is rescored
+        assert!(store.reload_registries().await.2.is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_appends_copy_of_old_version() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Turncoat").await;
+        let mut character = store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        character.experience = 7;
+        store.update(character, None).await.unwrap();
+
+        let summary = store.rollback(id, 1, None).await.unwrap().unwrap();
+        assert_eq!(summary.version_count, 3);
+        let latest = store.get_character_version(id, None).await.unwrap();
+        assert_eq!((latest.version, latest.character.experience), (3, 0));
+        // History is kept, and restoring the latest again still adds a version
+        assert_eq!(
+            store
+                .get_character_version(id, Some(2))
+                .await
+                .unwrap()
+                .character
+                .experience,
+            7
+        );
+        store.rollback(id, 3, None).await.unwrap().unwrap();
+        assert_eq!(
+            store.get_character_version(id, None).await.unwrap().version,
+            4
+        );
+        assert!(store.rollback(id, 9, None).await.unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_records_mutations_past_deletion() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Suspect").await;
+        let mut character = store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        character.experience = 3;
+        store
+            .update(character.clone(), Some(GM_AUTHOR.to_string()))
+            .await
+            .unwrap();
+        // Unchanged saves are not mutations
+        store.update(character, None).await.unwrap();
+        store.rollback(id, 1, None).await.unwrap().unwrap();
+        assert!(store.delete(id, Some(GM_AUTHOR.to_string())).await);
+
+        let log = store.audit_log(id).await;
+        let actions: Vec<(AuditAction, Option<u32>)> =
+            log.iter().map(|e| (e.action.clone(), e.version)).collect();
+        assert_eq!(
+            actions,
+            vec![
+                (AuditAction::Created, Some(1)),
+                (AuditAction::Updated, Some(2)),
+                (AuditAction::RolledBack { from: 1 }, Some(3)),
+                (AuditAction::Deleted, None),
+            ]
+        );
+        assert_eq!(log[1].by.as_deref(), Some(GM_AUTHOR));
+        assert!(store.audit_log(Uuid::new_v4()).await.is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_version_label_set_and_cleared_in_place() {
+        let (store, dir) = test_store().await;
+        let id = create_named(&store, "Scribe").await;
+
+        assert_eq!(
+            store
+                .set_version_label(id, 1, "  Before the dragon  ".to_string())
+                .await,
+            Some(true)
+        );
+        let versions = store.get_version_list(id).await.unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].label.as_deref(), Some("Before the dragon"));
+
+        store.set_version_label(id, 1, " ".to_string()).await;
+        assert_eq!(store.get_version_list(id).await.unwrap()[0].label, None);
+        assert_eq!(
+            store.set_version_label(id, 5, "Nope".to_string()).await,
+            Some(false)
+        );
+        assert_eq!(
+            store
+                .set_version_label(Uuid::new_v4(), 1, "Nope".to_string())
+                .await,
+            None
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_clone_writes_new_single_version_character() {
+        let (store, dir) = test_store().await;
+        let goblin = create_named(&store, "Goblin").await;
+        let mut character = store
+            .get_character_version(goblin, None)
+            .await
+            .unwrap()
+            .character;
+        character.level = 3;
+        store.update(character, None).await.unwrap();
+
+        let clone = store
+            .clone_character(goblin, "Goblin Chief".to_string(), None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_ne!(clone.id, goblin);
+        assert_eq!(clone.name, "Goblin Chief");
+        assert_eq!(clone.level, 3);
+        assert_eq!(clone.version_count, 1);
+        assert_eq!(store.get_version_list(goblin).await.unwrap().len(), 2);
+
+        let reloaded = CharacterStore::new(dir.to_str().unwrap()).await;
+        assert_eq!(reloaded.get_all_summaries().await.len(), 2);
+        let copied = reloaded
+            .get_character_version(clone.id, None)
+            .await
+            .unwrap()
+            .character;
+        assert_eq!(copied.id, clone.id);
+        assert!(store
+            .clone_character(Uuid::new_v4(), "Nobody".to_string(), None)
+            .await
+            .is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_summaries_page_filters_by_name_and_slices() {
+        let (store, dir) = test_store().await;
+        for name in ["Orc Archer", "Orc Shaman", "Elf Archer", "orc brute"] {
+            create_named(&store, name).await;
+        }
+
+        let (all, total) = store.get_summaries_page(0, 10, None).await;
+        assert_eq!((all.len(), total), (4, 4));
+
+        let (first, total) = store.get_summaries_page(0, 2, Some(" ORC ")).await;
+        assert_eq!(total, 3);
+        let (rest, _) = store.get_summaries_page(2, 2, Some("orc")).await;
+        assert_eq!(first.len(), 2);
+        assert_eq!(rest.len(), 1);
+        assert!(first
+            .iter()
+            .chain(&rest)
+            .all(|s| s.name.to_lowercase().contains("orc")));
+        assert!(!first.iter().any(|s| s.id == rest[0].id));
+
+        let (past_end, total) = store.get_summaries_page(8, 2, None).await;
+        assert!(past_end.is_empty());
+        assert_eq!(total, 4);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_summaries_cache_rebuilt_after_create() {
+        let (store, dir) = test_store().await;
+        let empty = store.get_all_summaries().await;
+        assert!(empty.is_empty());
+
+        let id = create_named(&store, "Cached").await;
+        let first = store.get_all_summaries().await;
+        assert!(!Arc::ptr_eq(&empty, &first));
+        assert_eq!(first.iter().map(|s| s.id).collect::<Vec<_>>(), vec![id]);
+
+        let second = store.get_all_summaries().await;
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_unique_names_reject_second_gandalf() {
+        let (store, dir) = test_store().await;
+        create_named(&store, "Gandalf").await;
+        create_named(&store, "Gandalf").await;
+        assert_eq!(store.get_all_summaries().await.len(), 2);
+
+        let strict = store.with_unique_names(true);
+        let third = strict
+            .create(
+                "gandalf ".to_string(),
+                None,
+                Race::default(),
+                Class::default(),
+                Characteristics::default(),
+                Vec::new(),
+                Vec::new(),
+            )
+            .await;
+        assert!(matches!(third, Err(NameTaken)));
+
+        // Renaming onto a taken name is rejected too
+        let saruman = create_named(&strict, "Saruman").await;
+        let mut character = strict
+            .get_character_version(saruman, None)
+            .await
+            .unwrap()
+            .character;
+        character.name = "GANDALF".to_string();
+        assert!(matches!(
+            strict.update(character, None).await,
+            Err(NameTaken)
+        ));
+
+        let _ = std::fs::remove_dir_all(dir);
     }
 }
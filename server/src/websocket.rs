@@ -5,50 +5,224 @@ use axum::{
     },
     response::Response,
 };
+use std::collections::BTreeSet;
+use std::time::Duration;
+
 use futures_util::{SinkExt, StreamExt};
-use shared::{deserialize, serialize, ClientMessage, ServerMessage};
+use sha2::{Digest, Sha256};
+use shared::{
+    deserialize, serialize, CharacterExport, ClientMessage, ConnectionId, ErrorCode, ReferenceKind,
+    ServerMessage, GM_AUTHOR,
+};
+use tokio::time::Instant;
 use tracing::{error, info, warn};
+use uuid::Uuid;
 
-use crate::storage::CharacterStore;
+use crate::storage::{CharacterStore, NameTaken, UpdateError};
+use crate::AppState;
 
 /// Maximum portrait size in bytes (512KB).
 const MAX_PORTRAIT_SIZE: usize = 512 * 1024;
 
-pub async fn ws_handler(ws: WebSocketUpgrade, State(store): State<CharacterStore>) -> Response {
-    ws.on_upgrade(|socket| handle_socket(socket, store))
+/// Most character summaries sent in one page.
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Time a closing connection gets to flush its last messages.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Per-connection caps on what a client may send.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// Largest message accepted, in bytes.
+    pub max_message: usize,
+    /// Mutations and roll broadcasts a connection may send in a burst.
+    pub burst: u32,
+    /// Mutations regained per second once the burst is spent.
+    pub per_second: f64,
+    /// Password attempts, admin or character, a connection may make in a burst.
+    pub login_burst: u32,
+    /// Password attempts regained per second once the burst is spent.
+    pub logins_per_second: f64,
+    /// Rejected messages and failed password attempts in a row after which
+    /// the connection is closed.
+    pub max_strikes: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_message: 1024 * 1024,
+            burst: 30,
+            per_second: 5.0,
+            login_burst: 5,
+            logins_per_second: 0.2,
+            max_strikes: 20,
+        }
+    }
+}
+
+impl Limits {
+    /// Defaults overridden by `WS_MAX_MESSAGE_BYTES`, `WS_MUTATION_BURST`,
+    /// `WS_MUTATIONS_PER_SECOND`, `WS_LOGIN_BURST`, `WS_LOGINS_PER_SECOND`
+    /// and `WS_MAX_STRIKES`.
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            let value = std::env::var(name).ok()?;
+            let parsed = value.parse().ok();
+            if parsed.is_none() {
+                warn!("Ignoring {name}={value:?}: not a number");
+            }
+            parsed
+        }
+        let defaults = Self::default();
+        Self {
+            max_message: var("WS_MAX_MESSAGE_BYTES").unwrap_or(defaults.max_message),
+            burst: var("WS_MUTATION_BURST").unwrap_or(defaults.burst),
+            per_second: var("WS_MUTATIONS_PER_SECOND").unwrap_or(defaults.per_second),
+            login_burst: var("WS_LOGIN_BURST").unwrap_or(defaults.login_burst),
+            logins_per_second: var("WS_LOGINS_PER_SECOND").unwrap_or(defaults.logins_per_second),
+            max_strikes: var("WS_MAX_STRIKES").unwrap_or(defaults.max_strikes),
+        }
+    }
+}
+
+/// Token bucket throttling one kind of message from a connection.
+struct RateLimiter {
+    tokens: f64,
+    burst: f64,
+    per_second: f64,
+    refilled: Instant,
+}
+
+impl RateLimiter {
+    fn new(burst: u32, per_second: f64, now: Instant) -> Self {
+        Self {
+            tokens: burst as f64,
+            burst: burst as f64,
+            per_second,
+            refilled: now,
+        }
+    }
+
+    /// Takes a token if one is left at `now`.
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.burst);
+        self.refilled = now;
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+/// One connection's rate limits and how many of its messages in a row were
+/// rejected.
+struct Throttle {
+    mutations: RateLimiter,
+    logins: RateLimiter,
+    strikes: u32,
+}
+
+impl Throttle {
+    fn new(limits: &Limits, now: Instant) -> Self {
+        Self {
+            mutations: RateLimiter::new(limits.burst, limits.per_second, now),
+            logins: RateLimiter::new(limits.login_burst, limits.logins_per_second, now),
+            strikes: 0,
+        }
+    }
 }
 
-async fn handle_socket(socket: WebSocket, store: CharacterStore) {
+fn is_login(msg: &ClientMessage) -> bool {
+    matches!(
+        msg,
+        ClientMessage::Authenticate { .. } | ClientMessage::AuthenticateCharacter { .. }
+    )
+}
+
+pub async fn ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    // Far larger messages are cut off before they are buffered; those just
+    // over the limit get an error reply instead
+    let hard_cap = state.limits.max_message.saturating_mul(4);
+    ws.max_message_size(hard_cap)
+        .on_upgrade(|socket| handle_socket(socket, state))
+}
+
+async fn handle_socket(socket: WebSocket, state: AppState) {
     let (mut sender, mut receiver) = socket.split();
+    let (conn_id, mut outbox) = state.hub.register().await;
 
-    info!("New WebSocket connection");
+    info!("New WebSocket connection {}", conn_id);
 
-    // Send character summaries on connect
-    let summaries = store.get_all_summaries().await;
-    let msg = ServerMessage::CharacterList {
-        characters: summaries,
-    };
-    if let Ok(bytes) = serialize(&msg) {
-        if sender.send(Message::Binary(bytes)).await.is_err() {
-            return;
+    // Everything addressed to this connection goes through the hub outbox,
+    // so other connections can push to it too.
+    let mut writer = tokio::spawn(async move {
+        while let Some(msg) = outbox.recv().await {
+            if let Ok(bytes) = serialize(&msg) {
+                if sender.send(Message::Binary(bytes)).await.is_err() {
+                    break;
+                }
+            }
         }
+    });
+
+    // Without a configured password everyone is admin
+    let admin = state.admin_password.is_none();
+    state.hub.set_admin(conn_id, admin).await;
+
+    // Send connection id, session, character summaries and rules on connect
+    let summaries = state.store.get_all_summaries().await;
+    let initial = [
+        ServerMessage::Welcome {
+            connection_id: conn_id,
+        },
+        ServerMessage::Session { admin },
+        ServerMessage::CharacterList {
+            characters: summaries.to_vec(),
+        },
+        ServerMessage::Ruleset {
+            rules: state.store.ruleset().clone(),
+        },
+    ];
+    for msg in initial {
+        state.hub.send(conn_id, msg).await;
+    }
+    if let Some(reason) = state.store.safe_mode() {
+        let reason = reason.to_string();
+        state
+            .hub
+            .send(conn_id, ServerMessage::SafeMode { reason })
+            .await;
     }
+    state.hub.broadcast_presence().await;
 
     // Handle incoming messages
+    let limits = state.limits;
+    let mut throttle = Throttle::new(&limits, Instant::now());
     while let Some(result) = receiver.next().await {
         match result {
             Ok(Message::Binary(data)) => {
-                if let Ok(client_msg) = deserialize::<ClientMessage>(&data) {
-                    let responses = handle_message(client_msg, &store).await;
-                    for response in responses {
-                        if let Ok(bytes) = serialize(&response) {
-                            if sender.send(Message::Binary(bytes)).await.is_err() {
-                                break;
-                            }
-                        }
-                    }
-                } else {
-                    warn!("Failed to deserialize client message");
+                for response in receive(&data, &mut throttle, &state, conn_id).await {
+                    state.hub.send(conn_id, response).await;
+                }
+                if throttle.strikes >= limits.max_strikes {
+                    warn!(
+                        "Closing connection {} after {} rejected messages",
+                        conn_id, throttle.strikes
+                    );
+                    state
+                        .hub
+                        .send(
+                            conn_id,
+                            ServerMessage::error(
+                                ErrorCode::RateLimited,
+                                "Too many rejected messages, closing the connection",
+                            ),
+                        )
+                        .await;
+                    break;
                 }
             }
             Ok(Message::Close(_)) => {
@@ -63,29 +237,137 @@ async fn handle_socket(socket: WebSocket, store: CharacterStore) {
         }
     }
 
-    info!("WebSocket connection closed");
+    // Unregistering closes the outbox, so the writer ends once it is flushed
+    state.hub.unregister(conn_id).await;
+    if tokio::time::timeout(FLUSH_TIMEOUT, &mut writer)
+        .await
+        .is_err()
+    {
+        writer.abort();
+    }
+    info!("WebSocket connection {} closed", conn_id);
 }
 
-async fn handle_message(msg: ClientMessage, store: &CharacterStore) -> Vec<ServerMessage> {
+/// Decodes and handles one message from `conn_id`, returning the replies.
+/// Oversized, malformed and throttled messages are refused; those and failed
+/// password attempts are strikes, anything else clears them.
+async fn receive(
+    data: &[u8],
+    throttle: &mut Throttle,
+    state: &AppState,
+    conn_id: ConnectionId,
+) -> Vec<ServerMessage> {
+    let max_message = state.limits.max_message;
+    if data.len() > max_message {
+        warn!("Connection {} sent {} bytes", conn_id, data.len());
+        throttle.strikes += 1;
+        return vec![ServerMessage::error(
+            ErrorCode::RateLimited,
+            format!("Message exceeds {} bytes", max_message),
+        )];
+    }
+    let Ok(msg) = deserialize::<ClientMessage>(data) else {
+        warn!("Failed to deserialize client message");
+        throttle.strikes += 1;
+        return vec![ServerMessage::error(
+            ErrorCode::Invalid,
+            "Malformed message",
+        )];
+    };
+    let now = Instant::now();
+    let login = is_login(&msg);
+    let throttled = if login {
+        (!throttle.logins.try_acquire(now)).then_some("Too many password attempts, slow down")
+    } else if msg.is_mutation() || matches!(msg, ClientMessage::BroadcastRoll { .. }) {
+        (!throttle.mutations.try_acquire(now)).then_some("Too many changes at once, slow down")
+    } else {
+        None
+    };
+    if let Some(reason) = throttled {
+        throttle.strikes += 1;
+        return vec![ServerMessage::error(ErrorCode::RateLimited, reason)];
+    }
+
+    let responses = handle_message(msg, state, conn_id).await;
+    let refused = responses.iter().any(|r| {
+        matches!(
+            r,
+            ServerMessage::Error {
+                code: ErrorCode::Unauthorized,
+                ..
+            }
+        )
+    });
+    if login && refused {
+        throttle.strikes += 1;
+    } else {
+        throttle.strikes = 0;
+    }
+    responses
+}
+
+async fn handle_message(
+    msg: ClientMessage,
+    state: &AppState,
+    conn_id: ConnectionId,
+) -> Vec<ServerMessage> {
+    let store = &state.store;
+    let _write = if msg.is_mutation() {
+        if let Some(reason) = store.safe_mode() {
+            return vec![ServerMessage::error(
+                ErrorCode::ReadOnly,
+                format!("Server is in safe mode: {}", reason),
+            )];
+        }
+        let Some(permit) = store.begin_write().await else {
+            return vec![ServerMessage::error(
+                ErrorCode::ReadOnly,
+                "Server is shutting down".to_string(),
+            )];
+        };
+        Some(permit)
+    } else {
+        None
+    };
     match msg {
         ClientMessage::RequestCharacterList => {
             let summaries = store.get_all_summaries().await;
             vec![ServerMessage::CharacterList {
-                characters: summaries,
+                characters: summaries.to_vec(),
+            }]
+        }
+        ClientMessage::RequestCharacterPage {
+            offset,
+            limit,
+            query,
+        } => {
+            let limit = limit.clamp(1, MAX_PAGE_SIZE);
+            let (characters, total) = store
+                .get_summaries_page(offset as usize, limit as usize, query.as_deref())
+                .await;
+            vec![ServerMessage::CharacterPage {
+                characters,
+                total: total as u32,
             }]
         }
         ClientMessage::RequestVersionList { id } => match store.get_version_list(id).await {
             Some(versions) => vec![ServerMessage::VersionList { id, versions }],
-            None => vec![ServerMessage::Error {
-                message: format!("Character {} not found", id),
-            }],
+            None => vec![ServerMessage::error(
+                ErrorCode::NotFound,
+                format!("Character {} not found", id),
+            )],
         },
         ClientMessage::RequestCharacterVersion { id, version } => {
             match store.get_character_version(id, version).await {
                 Some(cv) => {
+                    let latest_version = match version {
+                        Some(_) => store.latest_version(id).await.unwrap_or(cv.version),
+                        None => cv.version,
+                    };
                     let mut msgs = vec![ServerMessage::CharacterVersion {
                         id,
                         version: cv.version,
+                        latest_version,
                         saved_at: cv.saved_at,
                         character: Box::new(cv.character),
                     }];
@@ -95,90 +377,1364 @@ async fn handle_message(msg: ClientMessage, store: &CharacterStore) -> Vec<Serve
                     }
                     msgs
                 }
-                None => vec![ServerMessage::Error {
-                    message: "Version not found".to_string(),
+                None => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    "Version not found".to_string(),
+                )],
+            }
+        }
+        ClientMessage::RequestVersionDiff { id, from, to } => {
+            let old = store.get_character_version(id, Some(from)).await;
+            let new = store.get_character_version(id, Some(to)).await;
+            match old.zip(new) {
+                Some((old, new)) => vec![ServerMessage::VersionDiff {
+                    id,
+                    from,
+                    to,
+                    changes: shared::version::diff(&old.character, &new.character).changes,
                 }],
+                None => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    "Version not found".to_string(),
+                )],
+            }
+        }
+        ClientMessage::ExportCharacter { id, version } => {
+            match store.export_character(id, version).await {
+                Some(export) => match export.to_pretty_json() {
+                    Ok(json) => vec![ServerMessage::CharacterExport { id, json }],
+                    Err(e) => vec![ServerMessage::error(
+                        ErrorCode::Invalid,
+                        format!("Failed to serialize export: {}", e),
+                    )],
+                },
+                None => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    "Version not found".to_string(),
+                )],
             }
         }
         ClientMessage::CreateCharacter {
             name,
+            accent_color,
             race,
             class,
             stats,
             skills,
             traits,
+            password,
         } => {
             if name.trim().is_empty() {
-                return vec![ServerMessage::Error {
-                    message: "Character name cannot be empty".to_string(),
-                }];
+                return vec![ServerMessage::error(
+                    ErrorCode::Invalid,
+                    "Character name cannot be empty".to_string(),
+                )];
             }
             if name.len() > 100 {
-                return vec![ServerMessage::Error {
-                    message: "Character name cannot exceed 100 characters".to_string(),
-                }];
+                return vec![ServerMessage::error(
+                    ErrorCode::Invalid,
+                    "Character name cannot exceed 100 characters".to_string(),
+                )];
+            }
+            let password = password.filter(|p| !p.is_empty());
+            if password.as_ref().is_some_and(|p| p.len() > 100) {
+                return vec![ServerMessage::error(
+                    ErrorCode::Invalid,
+                    "Character password cannot exceed 100 characters".to_string(),
+                )];
             }
             let trimmed = name.trim().to_string();
             if store.character_name_exists(&trimmed).await {
-                return vec![ServerMessage::Error {
-                    message: format!("Character with name \"{}\" already exists", trimmed),
-                }];
+                return vec![name_taken(&trimmed)];
+            }
+            match store
+                .create(name, accent_color, race, class, stats, skills, traits)
+                .await
+            {
+                Ok(mut summary) => {
+                    if let Some(password) = password {
+                        summary.protected = store.set_owner_password(summary.id, &password).await;
+                        state.hub.unlock(conn_id, summary.id).await;
+                    }
+                    vec![ServerMessage::CharacterCreated { summary }]
+                }
+                Err(NameTaken) => vec![name_taken(&trimmed)],
+            }
+        }
+        ClientMessage::ImportCharacter { json } => {
+            let character = match CharacterExport::character_from_json(&json) {
+                Ok(character) => character,
+                Err(e) => {
+                    return vec![ServerMessage::error(
+                        ErrorCode::Invalid,
+                        format!("Invalid character JSON: {}", e),
+                    )]
+                }
+            };
+            let name = character.name.trim().to_string();
+            if name.is_empty() || name.len() > 100 {
+                return vec![ServerMessage::error(
+                    ErrorCode::Invalid,
+                    "Character name must be 1 to 100 characters".to_string(),
+                )];
             }
-            let summary = store.create(name, race, class, stats, skills, traits).await;
-            vec![ServerMessage::CharacterCreated { summary }]
+            let unknown = store.unknown_references(&character);
+            let missing = |kind: ReferenceKind| -> Vec<String> {
+                unknown
+                    .iter()
+                    .filter(|(k, _)| *k == kind)
+                    .map(|(_, name)| name.clone())
+                    .collect()
+            };
+            let saved_by = saved_by(state, conn_id).await;
+            match store.import(character, saved_by).await {
+                Ok(summary) => {
+                    info!("Imported character {} as {}", summary.name, summary.id);
+                    let result = ServerMessage::ImportResult {
+                        id: summary.id,
+                        missing_traits: missing(ReferenceKind::Trait),
+                        missing_weapons: missing(ReferenceKind::Weapon),
+                        missing_equipment: missing(ReferenceKind::Equipment),
+                    };
+                    vec![ServerMessage::CharacterCreated { summary }, result]
+                }
+                Err(NameTaken) => vec![name_taken(&name)],
+            }
+        }
+        ClientMessage::CloneCharacter { id, new_name } => {
+            let name = new_name.trim().to_string();
+            if name.is_empty() || name.len() > 100 {
+                return vec![ServerMessage::error(
+                    ErrorCode::Invalid,
+                    "Character name must be 1 to 100 characters".to_string(),
+                )];
+            }
+            if store.character_name_exists(&name).await {
+                return vec![name_taken(&name)];
+            }
+            let saved_by = saved_by(state, conn_id).await;
+            match store.clone_character(id, name.clone(), saved_by).await {
+                Some(Ok(summary)) => {
+                    info!("Cloned character {} as {}", id, summary.id);
+                    vec![ServerMessage::CharacterCreated { summary }]
+                }
+                Some(Err(NameTaken)) => vec![name_taken(&name)],
+                None => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    "Character not found".to_string(),
+                )],
+            }
+        }
+        ClientMessage::DeleteCharacter { id }
+        | ClientMessage::RollbackVersion { id, .. }
+        | ClientMessage::DeleteVersion { id, .. }
+        | ClientMessage::SetTags { id, .. }
+        | ClientMessage::UploadPortrait { id, .. }
+            if !may_edit(state, conn_id, id).await =>
+        {
+            vec![character_locked()]
+        }
+        ClientMessage::UpdateCharacter { ref character, .. }
+            if !may_edit(state, conn_id, character.id).await =>
+        {
+            vec![character_locked()]
+        }
+        ClientMessage::DeleteCharacters { ref ids }
+            if !may_edit_all(state, conn_id, &ids.iter().copied().collect()).await =>
+        {
+            vec![character_locked()]
         }
         ClientMessage::DeleteCharacter { id } => {
-            if store.delete(id).await {
+            let deleted_by = saved_by(state, conn_id).await;
+            if store.delete(id, deleted_by).await {
                 vec![ServerMessage::CharacterDeleted { id }]
             } else {
-                vec![ServerMessage::Error {
-                    message: format!("Character with id {} not found", id),
-                }]
+                vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    format!("Character with id {} not found", id),
+                )]
+            }
+        }
+        ClientMessage::DeleteCharacters { ids } => {
+            // A repeated id is deleted once, so it must be counted once too
+            let ids: BTreeSet<Uuid> = ids.into_iter().collect();
+            let deleted_by = saved_by(state, conn_id).await;
+            let removed = store.delete_many(&ids, deleted_by).await;
+            let missing = ids.len() - removed.len();
+            let mut msgs: Vec<ServerMessage> = removed
+                .into_iter()
+                .map(|id| ServerMessage::CharacterDeleted { id })
+                .collect();
+            if missing > 0 {
+                msgs.push(ServerMessage::error(
+                    ErrorCode::NotFound,
+                    format!("{} character(s) not found", missing),
+                ));
+            }
+            msgs
+        }
+        ClientMessage::SetTags { id, tags } => match store.set_tags(id, tags).await {
+            Some(summary) => vec![ServerMessage::CharacterUpdated { summary }],
+            None => vec![ServerMessage::error(
+                ErrorCode::NotFound,
+                format!("Character {} not found", id),
+            )],
+        },
+        ClientMessage::RollbackVersion { id, version } => {
+            let saved_by = saved_by(state, conn_id).await;
+            match store.rollback(id, version, saved_by).await {
+                Ok(Some(summary)) => {
+                    let changed = ServerMessage::CharacterChanged {
+                        summary: summary.clone(),
+                    };
+                    state.hub.notify_other_viewers(id, conn_id, changed).await;
+                    let mut msgs = vec![ServerMessage::CharacterUpdated { summary }];
+                    if let Some(versions) = store.get_version_list(id).await {
+                        msgs.push(ServerMessage::VersionList { id, versions });
+                    }
+                    msgs
+                }
+                Ok(None) => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    format!("Version {} not found", version),
+                )],
+                Err(NameTaken) => {
+                    let name = store
+                        .get_character_version(id, Some(version))
+                        .await
+                        .map(|cv| cv.character.name)
+                        .unwrap_or_default();
+                    vec![name_taken(name.trim())]
+                }
             }
         }
         ClientMessage::DeleteVersion { id, version } => {
             match store.delete_version(id, version).await {
                 Some(true) => vec![ServerMessage::VersionDeleted { id, version }],
-                Some(false) => vec![ServerMessage::Error {
-                    message: format!("Version {} not found", version),
-                }],
-                None => vec![ServerMessage::Error {
-                    message: "Character not found".to_string(),
+                Some(false) => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    format!("Version {} not found", version),
+                )],
+                None => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    "Character not found".to_string(),
+                )],
+            }
+        }
+        ClientMessage::UpdateCharacter {
+            character,
+            base_version,
+        } => {
+            let saved_by = saved_by(state, conn_id).await;
+            let name = character.name.clone();
+            match store.update_from(*character, base_version, saved_by).await {
+                Err(UpdateError::NameTaken) => vec![name_taken(name.trim())],
+                Err(UpdateError::Conflict(latest)) => vec![ServerMessage::UpdateConflict {
+                    id: latest.character.id,
+                    latest,
                 }],
+                Ok(Some(summary)) => {
+                    // Push the saved version to anyone following this editor
+                    if let Some(cv) = store.get_character_version(summary.id, None).await {
+                        state
+                            .hub
+                            .notify_followers(
+                                conn_id,
+                                ServerMessage::CharacterVersion {
+                                    id: summary.id,
+                                    version: cv.version,
+                                    latest_version: cv.version,
+                                    saved_at: cv.saved_at,
+                                    character: Box::new(cv.character),
+                                },
+                            )
+                            .await;
+                    }
+                    // Everyone else with the sheet open can reload it
+                    let changed = ServerMessage::CharacterChanged {
+                        summary: summary.clone(),
+                    };
+                    state
+                        .hub
+                        .notify_other_viewers(summary.id, conn_id, changed)
+                        .await;
+                    vec![ServerMessage::CharacterUpdated { summary }]
+                }
+                Ok(None) => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    "Character not found".to_string(),
+                )],
             }
         }
-        ClientMessage::UpdateCharacter { character } => match store.update(character).await {
-            Some(summary) => vec![ServerMessage::CharacterUpdated { summary }],
-            None => vec![ServerMessage::Error {
-                message: "Character not found".to_string(),
-            }],
-        },
-        ClientMessage::CreateWeapon { weapon } => match store.save_weapon(weapon).await {
-            Err(e) => vec![ServerMessage::Error { message: e }],
-            Ok(()) => vec![],
-        },
-        ClientMessage::CreateEquipment { equipment } => {
-            match store.save_equipment(equipment).await {
-                Err(e) => vec![ServerMessage::Error { message: e }],
+        // Overwriting a definition is as destructive as deleting it
+        ClientMessage::CreateWeapon { replace: true, .. }
+        | ClientMessage::CreateEquipment { replace: true, .. }
+        | ClientMessage::CreateItem { replace: true, .. }
+            if !state.hub.is_admin(conn_id).await =>
+        {
+            vec![admin_required()]
+        }
+        ClientMessage::CreateWeapon { weapon, replace } => {
+            match store.save_weapon(weapon, replace).await {
+                Err(e) => vec![ServerMessage::error(ErrorCode::Invalid, e)],
                 Ok(()) => vec![],
             }
         }
-        ClientMessage::CreateItem { item } => match store.save_item(item).await {
-            Err(e) => vec![ServerMessage::Error { message: e }],
+        ClientMessage::CreateEquipment { equipment, replace } => {
+            match store.save_equipment(equipment, replace).await {
+                Err(e) => vec![ServerMessage::error(ErrorCode::Invalid, e)],
+                Ok(()) => vec![],
+            }
+        }
+        ClientMessage::CreateItem { item, replace } => match store.save_item(item, replace).await {
+            Err(e) => vec![ServerMessage::error(ErrorCode::Invalid, e)],
             Ok(()) => vec![],
         },
         ClientMessage::UploadPortrait { id, png_data } => {
             if png_data.len() > MAX_PORTRAIT_SIZE {
-                return vec![ServerMessage::Error {
-                    message: "Portrait too large (max 512KB)".to_string(),
-                }];
+                return vec![ServerMessage::error(
+                    ErrorCode::Invalid,
+                    "Portrait too large (max 512KB)".to_string(),
+                )];
             }
             store.save_portrait(id, &png_data).await;
             vec![ServerMessage::PortraitData { id, png_data }]
         }
+        ClientMessage::ListPortraits => {
+            let (hashes, thumbnails) = store.list_portraits().await.into_iter().unzip();
+            vec![ServerMessage::PortraitList { hashes, thumbnails }]
+        }
+        ClientMessage::RequestGalleryPortrait { hash } => {
+            match store.gallery_portrait(&hash).await {
+                Some(png_data) => vec![ServerMessage::GalleryPortrait { hash, png_data }],
+                None => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    format!("Portrait {} not found", hash),
+                )],
+            }
+        }
         ClientMessage::RequestPortrait { id } => match store.load_portrait(id).await {
             Some(png_data) => vec![ServerMessage::PortraitData { id, png_data }],
             None => vec![],
         },
+        ClientMessage::Recompute { .. }
+        | ClientMessage::RecomputeAll
+        | ClientMessage::ReloadRegistries
+        | ClientMessage::DeleteWeapon { .. }
+        | ClientMessage::DeleteEquipment { .. }
+        | ClientMessage::DeleteItem { .. }
+        | ClientMessage::FindByReference { .. }
+        | ClientMessage::PushNote { .. }
+        | ClientMessage::NewDay
+        | ClientMessage::GrantXp { .. }
+        | ClientMessage::SetVersionLabel { .. }
+        | ClientMessage::PruneVersions { .. }
+        | ClientMessage::RequestAuditLog { .. }
+            if !state.hub.is_admin(conn_id).await =>
+        {
+            vec![admin_required()]
+        }
+        ClientMessage::RequestAuditLog { id } => {
+            let entries = store.audit_log(id).await;
+            vec![ServerMessage::AuditLog { id, entries }]
+        }
+        ClientMessage::SetVersionLabel { id, version, label } => {
+            if label.trim().len() > 100 {
+                return vec![ServerMessage::error(
+                    ErrorCode::Invalid,
+                    "Version label cannot exceed 100 characters".to_string(),
+                )];
+            }
+            match store.set_version_label(id, version, label).await {
+                Some(true) => match store.get_version_list(id).await {
+                    Some(versions) => vec![ServerMessage::VersionList { id, versions }],
+                    None => vec![],
+                },
+                Some(false) => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    format!("Version {} not found", version),
+                )],
+                None => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    "Character not found".to_string(),
+                )],
+            }
+        }
+        ClientMessage::PruneVersions { id, keep } => {
+            match store.prune_versions(id, keep as usize).await {
+                Some(_) => match store.get_version_list(id).await {
+                    Some(versions) => vec![ServerMessage::VersionList { id, versions }],
+                    None => vec![],
+                },
+                None => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    "Character not found".to_string(),
+                )],
+            }
+        }
+        ClientMessage::Recompute { id } => match store.recompute(id).await {
+            Some(repaired) => {
+                recompute_report(store, 1, if repaired { vec![id] } else { vec![] }).await
+            }
+            None => vec![ServerMessage::error(
+                ErrorCode::NotFound,
+                format!("Character with id {} not found", id),
+            )],
+        },
+        ClientMessage::PushNote { target_id, text } => {
+            let text = text.trim().to_string();
+            if text.is_empty() {
+                return vec![ServerMessage::error(
+                    ErrorCode::Invalid,
+                    "Note cannot be empty".to_string(),
+                )];
+            }
+            match store.push_note(target_id, text).await {
+                Some(note) => {
+                    let added = ServerMessage::NoteAdded {
+                        id: target_id,
+                        note,
+                    };
+                    state.hub.notify_viewers(target_id, added.clone()).await;
+                    // The GM sees the note even when not viewing the character
+                    if state.hub.viewing(conn_id).await == Some(target_id) {
+                        vec![]
+                    } else {
+                        vec![added]
+                    }
+                }
+                None => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    format!("Character {} not found", target_id),
+                )],
+            }
+        }
+        ClientMessage::FindByReference { kind, name } => {
+            let characters = store.find_by_reference(kind, &name).await;
+            vec![ServerMessage::ReferenceMatches {
+                kind,
+                name,
+                characters,
+            }]
+        }
+        ClientMessage::RecomputeAll => {
+            let checked = store.get_all_summaries().await.len() as u32;
+            let repaired = store.recompute_all().await;
+            recompute_report(store, checked, repaired).await
+        }
+        ClientMessage::DeleteWeapon { name } => {
+            let result = store.delete_weapon(&name).await;
+            registry_deletion(state, result, ServerMessage::WeaponDeleted { name }).await
+        }
+        ClientMessage::DeleteEquipment { name } => {
+            let result = store.delete_equipment(&name).await;
+            registry_deletion(state, result, ServerMessage::EquipmentDeleted { name }).await
+        }
+        ClientMessage::DeleteItem { name } => {
+            let result = store.delete_item(&name).await;
+            registry_deletion(state, result, ServerMessage::ItemDeleted { name }).await
+        }
+        ClientMessage::ReloadRegistries => {
+            let (registries, reports, rescored) = store.reload_registries().await;
+            state.hub.broadcast(registries.to_message()).await;
+            for summary in rescored {
+                let msg = ServerMessage::CharacterUpdated { summary };
+                state.hub.broadcast(msg).await;
+            }
+            let failed: Vec<String> = reports
+                .iter()
+                .filter_map(|r| Some(format!("{} ({})", r.file, r.error.as_ref()?)))
+                .collect();
+            if failed.is_empty() {
+                vec![]
+            } else {
+                vec![ServerMessage::error(
+                    ErrorCode::Invalid,
+                    format!("Kept previous definitions of {}", failed.join(", ")),
+                )]
+            }
+        }
+        ClientMessage::NewDay => {
+            let rested = store.new_day().await;
+            info!("New day started, {} character(s) had rested", rested.len());
+            state.hub.broadcast(ServerMessage::DayStarted).await;
+            let summaries = store.get_all_summaries().await;
+            summaries
+                .iter()
+                .filter(|s| rested.contains(&s.id))
+                .map(|summary| ServerMessage::CharacterUpdated {
+                    summary: summary.clone(),
+                })
+                .collect()
+        }
+        ClientMessage::GrantXp { ids, amount } => {
+            let saved_by = saved_by(state, conn_id).await;
+            let mut responses = Vec::new();
+            for id in ids {
+                let Some(summary) = store.grant_xp(id, amount, saved_by.clone()).await else {
+                    responses.push(ServerMessage::error(
+                        ErrorCode::NotFound,
+                        format!("Character with id {} not found", id),
+                    ));
+                    continue;
+                };
+                // Open sheets reload the levelled-up version
+                if let Some(cv) = store.get_character_version(id, None).await {
+                    state
+                        .hub
+                        .notify_viewers(
+                            id,
+                            ServerMessage::CharacterVersion {
+                                id,
+                                version: cv.version,
+                                latest_version: cv.version,
+                                saved_at: cv.saved_at,
+                                character: Box::new(cv.character),
+                            },
+                        )
+                        .await;
+                }
+                responses.push(ServerMessage::CharacterUpdated { summary });
+            }
+            responses
+        }
+        ClientMessage::Authenticate { password } => {
+            let granted = state
+                .admin_password
+                .as_deref()
+                .is_none_or(|expected| admin_password_matches(expected, &password));
+            if !granted {
+                warn!("Connection {} failed admin authentication", conn_id);
+                return vec![ServerMessage::error(
+                    ErrorCode::Unauthorized,
+                    "Invalid admin password".to_string(),
+                )];
+            }
+            state.hub.set_admin(conn_id, true).await;
+            vec![ServerMessage::Session { admin: true }]
+        }
+        ClientMessage::AuthenticateCharacter { id, password } => {
+            match store.check_owner_password(id, &password).await {
+                Some(true) => {
+                    state.hub.unlock(conn_id, id).await;
+                    vec![ServerMessage::CharacterUnlocked { id }]
+                }
+                Some(false) => {
+                    warn!("Connection {} failed to unlock character {}", conn_id, id);
+                    vec![ServerMessage::error(
+                        ErrorCode::Unauthorized,
+                        "Wrong password for this character".to_string(),
+                    )]
+                }
+                None => vec![ServerMessage::error(
+                    ErrorCode::NotFound,
+                    format!("Character {} not found", id),
+                )],
+            }
+        }
+        ClientMessage::Viewing { id } => {
+            state.hub.set_viewing(conn_id, id).await;
+            match id {
+                Some(id) => vec![ServerMessage::Notes {
+                    id,
+                    notes: store.notes(id).await,
+                }],
+                None => vec![],
+            }
+        }
+        ClientMessage::Follow { connection_id } => {
+            match state.hub.follow(conn_id, connection_id).await {
+                Ok(id) => vec![ServerMessage::FollowView { connection_id, id }],
+                Err((code, message)) => vec![ServerMessage::error(code, message)],
+            }
+        }
+        ClientMessage::Unfollow => {
+            state.hub.unfollow(conn_id).await;
+            vec![]
+        }
+        ClientMessage::BroadcastRoll {
+            label,
+            total,
+            detail,
+        } => {
+            state
+                .hub
+                .broadcast(ServerMessage::RollBroadcast {
+                    from: conn_id,
+                    label,
+                    total,
+                    detail,
+                })
+                .await;
+            vec![]
+        }
+    }
+}
+
+/// Updated summaries for repaired characters followed by the recompute report.
+async fn recompute_report(
+    store: &CharacterStore,
+    checked: u32,
+    repaired: Vec<Uuid>,
+) -> Vec<ServerMessage> {
+    let summaries = store.get_all_summaries().await;
+    let mut msgs: Vec<ServerMessage> = summaries
+        .iter()
+        .filter(|s| repaired.contains(&s.id))
+        .map(|summary| ServerMessage::CharacterUpdated {
+            summary: summary.clone(),
+        })
+        .collect();
+    msgs.push(ServerMessage::RecomputeReport { checked, repaired });
+    msgs
+}
+
+/// Tells every client a registry entry was removed so they can drop it too.
+async fn registry_deletion(
+    state: &AppState,
+    result: Result<bool, String>,
+    deleted: ServerMessage,
+) -> Vec<ServerMessage> {
+    match result {
+        Ok(true) => {
+            state.hub.broadcast(deleted).await;
+            vec![]
+        }
+        Ok(false) => vec![ServerMessage::error(
+            ErrorCode::NotFound,
+            "No such registry entry".to_string(),
+        )],
+        Err(e) => vec![ServerMessage::error(ErrorCode::Invalid, e)],
+    }
+}
+
+/// Identity recorded on versions a connection saves. The admin login is the
+/// only identity the server knows, so only authenticated GM saves are tagged.
+async fn saved_by(state: &AppState, conn_id: ConnectionId) -> Option<String> {
+    let authenticated = state.admin_password.is_some() && state.hub.is_admin(conn_id).await;
+    authenticated.then(|| GM_AUTHOR.to_string())
+}
+
+/// Whether a connection may change character `id`. Protected characters need
+/// their password on this connection, or the admin login to override it.
+async fn may_edit(state: &AppState, conn_id: ConnectionId, id: Uuid) -> bool {
+    !state.store.is_protected(id).await
+        || state.hub.has_unlocked(conn_id, id).await
+        || (state.admin_password.is_some() && state.hub.is_admin(conn_id).await)
+}
+
+async fn may_edit_all(state: &AppState, conn_id: ConnectionId, ids: &BTreeSet<Uuid>) -> bool {
+    for &id in ids {
+        if !may_edit(state, conn_id, id).await {
+            return false;
+        }
+    }
+    true
+}
+
+/// Whether `password` is the admin password. Their digests are compared, so
+/// the time taken does not tell how much of a guess was right.
+fn admin_password_matches(expected: &str, password: &str) -> bool {
+    Sha256::digest(expected) == Sha256::digest(password)
+}
+
+fn character_locked() -> ServerMessage {
+    ServerMessage::error(
+        ErrorCode::Unauthorized,
+        "This character is password protected".to_string(),
+    )
+}
+
+fn admin_required() -> ServerMessage {
+    ServerMessage::error(ErrorCode::Unauthorized, "Admin access required".to_string())
+}
+
+fn name_taken(name: &str) -> ServerMessage {
+    ServerMessage::error(
+        ErrorCode::NameTaken,
+        format!("Another character is already called \"{name}\""),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hub::Hub;
+
+    /// State over a fresh data directory, which the test removes when done.
+    async fn test_state(admin_password: Option<&str>) -> (AppState, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("websocket-{}", Uuid::new_v4()));
+        let state = AppState {
+            store: CharacterStore::new(dir.to_str().unwrap()).await,
+            hub: Hub::default(),
+            admin_password: admin_password.map(Into::into),
+            limits: Limits::default(),
+        };
+        (state, dir)
+    }
+
+    async fn create_test_character(store: &CharacterStore, name: &str) -> Uuid {
+        store
+            .create(
+                name.to_string(),
+                None,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Vec::new(),
+                Vec::new(),
+            )
+            .await
+            .unwrap()
+            .id
+    }
+
+    #[test]
+    fn test_rate_limiter_allows_burst_then_refills() {
+        let limits = Limits {
+            burst: 3,
+            per_second: 2.0,
+            ..Limits::default()
+        };
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(limits.burst, limits.per_second, start);
+
+        assert!((0..3).all(|_| limiter.try_acquire(start)));
+        assert!(!limiter.try_acquire(start));
+        // Half a second buys one mutation back at two per second
+        let later = start + Duration::from_millis(500);
+        assert!(limiter.try_acquire(later));
+        assert!(!limiter.try_acquire(later));
+        // A long pause refills no more than the burst
+        let much_later = later + Duration::from_secs(60);
+        assert!((0..3).all(|_| limiter.try_acquire(much_later)));
+        assert!(!limiter.try_acquire(much_later));
+    }
+
+    #[test]
+    fn test_admin_password_must_match_exactly() {
+        assert!(admin_password_matches("secret", "secret"));
+        assert!(!admin_password_matches("secret", "secreT"));
+        assert!(!admin_password_matches("secret", "secret "));
+        assert!(!admin_password_matches("secret", ""));
+    }
+
+    // The clock stands still, so slow password checks cannot refill the bucket
+    #[tokio::test(start_paused = true)]
+    async fn test_failed_character_logins_are_rate_limited() {
+        let (state, dir) = test_state(Some("secret")).await;
+        let (id, conn_id) = locked_character(&state).await;
+        let mut throttle = Throttle::new(&state.limits, Instant::now());
+        let guess = serialize(&ClientMessage::AuthenticateCharacter {
+            id,
+            password: "stone".to_string(),
+        })
+        .unwrap();
+        let code = |responses: Vec<ServerMessage>| match responses.as_slice() {
+            [ServerMessage::Error { code, .. }] => *code,
+            other => panic!("unexpected responses {other:?}"),
+        };
+
+        // Each wrong password is a strike; past the burst they are throttled
+        for _ in 0..state.limits.login_burst {
+            let responses = receive(&guess, &mut throttle, &state, conn_id).await;
+            assert_eq!(code(responses), ErrorCode::Unauthorized);
+        }
+        let responses = receive(&guess, &mut throttle, &state, conn_id).await;
+        assert_eq!(code(responses), ErrorCode::RateLimited);
+        assert_eq!(throttle.strikes, state.limits.login_burst + 1);
+        // Even the right password waits for the bucket to refill
+        let right = serialize(&ClientMessage::AuthenticateCharacter {
+            id,
+            password: "moss".to_string(),
+        })
+        .unwrap();
+        let responses = receive(&right, &mut throttle, &state, conn_id).await;
+        assert_eq!(code(responses), ErrorCode::RateLimited);
+        assert!(!may_edit(&state, conn_id, id).await);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_mutation_yields_error_code() {
+        let (state, dir) = test_state(Some("secret")).await;
+        let (conn_id, _inbox) = state.hub.register().await;
+
+        let responses = handle_message(ClientMessage::RecomputeAll, &state, conn_id).await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::Error {
+                code: ErrorCode::Unauthorized,
+                ..
+            }]
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_replacing_registry_entry_needs_admin() {
+        let (state, dir) = test_state(Some("secret")).await;
+        let (conn_id, _inbox) = state.hub.register().await;
+        let rope = |description: &str| shared::Item {
+            name: "Rope".to_string(),
+            description: description.to_string(),
+            value: None,
+            weight: None,
+        };
+
+        let create = ClientMessage::CreateItem {
+            item: rope("Hemp"),
+            replace: false,
+        };
+        assert!(handle_message(create, &state, conn_id).await.is_empty());
+        let replace = ClientMessage::CreateItem {
+            item: rope("Silk"),
+            replace: true,
+        };
+        assert!(matches!(
+            handle_message(replace.clone(), &state, conn_id)
+                .await
+                .as_slice(),
+            [ServerMessage::Error {
+                code: ErrorCode::Unauthorized,
+                ..
+            }]
+        ));
+
+        let password = "secret".to_string();
+        handle_message(ClientMessage::Authenticate { password }, &state, conn_id).await;
+        assert!(handle_message(replace, &state, conn_id).await.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_mostly_corrupt_index_enters_safe_mode() {
+        let (state, dir) = test_state(None).await;
+        let id = create_test_character(&state.store, "Survivor").await;
+        for i in 0..3 {
+            let path = dir.join("characters").join(format!("broken-{i}.json"));
+            std::fs::write(path, "{ not json").unwrap();
+        }
+
+        let state = AppState {
+            store: CharacterStore::new(dir.to_str().unwrap()).await,
+            ..state
+        };
+        assert!(state.store.safe_mode().is_some());
+        let (conn_id, _inbox) = state.hub.register().await;
+
+        let character = state
+            .store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        let responses = handle_message(
+            ClientMessage::UpdateCharacter {
+                character: Box::new(character),
+                base_version: None,
+            },
+            &state,
+            conn_id,
+        )
+        .await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::Error {
+                code: ErrorCode::ReadOnly,
+                ..
+            }]
+        ));
+        let versions = state.store.get_version_list(id).await.unwrap();
+        assert_eq!(versions.len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_finishes_started_saves_and_refuses_new_ones() {
+        let (state, dir) = test_state(None).await;
+        let id = create_test_character(&state.store, "Courier").await;
+        let (conn_id, _inbox) = state.hub.register().await;
+        let character = state
+            .store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+
+        // A save that took its permit before shutdown began
+        let permit = state.store.begin_write().await.unwrap();
+        let writer = {
+            let store = state.store.clone();
+            let mut character = character.clone();
+            tokio::spawn(async move {
+                let _permit = permit;
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                character.level += 1;
+                store.update(character, None).await.ok().flatten()
+            })
+        };
+        state.store.close().await;
+        // The save landed before close returned
+        assert_eq!(state.store.get_version_list(id).await.unwrap().len(), 2);
+        assert!(writer.await.unwrap().is_some());
+
+        let responses = handle_message(
+            ClientMessage::UpdateCharacter {
+                character: Box::new(character),
+                base_version: None,
+            },
+            &state,
+            conn_id,
+        )
+        .await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::Error {
+                code: ErrorCode::ReadOnly,
+                ..
+            }]
+        ));
+        assert_eq!(state.store.get_version_list(id).await.unwrap().len(), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_roll_reaches_other_connections() {
+        let (state, dir) = test_state(None).await;
+        let (roller, _roller_inbox) = state.hub.register().await;
+        let (_watcher, mut watcher_inbox) = state.hub.register().await;
+
+        let msg = ClientMessage::BroadcastRoll {
+            label: "Perception".to_string(),
+            total: 17,
+            detail: "d20 (14) + 3".to_string(),
+        };
+        assert!(handle_message(msg, &state, roller).await.is_empty());
+
+        let delivered = std::iter::from_fn(|| watcher_inbox.try_recv().ok())
+            .find(|msg| matches!(msg, ServerMessage::RollBroadcast { .. }));
+        assert!(matches!(
+            delivered,
+            Some(ServerMessage::RollBroadcast { from, total: 17, ref label, .. })
+                if from == roller && label == "Perception"
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_gm_note_reaches_target_journal() {
+        let (state, dir) = test_state(None).await;
+        let target = create_test_character(&state.store, "Scout").await;
+        let (gm, _gm_inbox) = state.hub.register().await;
+        state.hub.set_admin(gm, true).await;
+        let (player, mut player_inbox) = state.hub.register().await;
+        handle_message(ClientMessage::Viewing { id: Some(target) }, &state, player).await;
+
+        let msg = ClientMessage::PushNote {
+            target_id: target,
+            text: "You notice the door is trapped".to_string(),
+        };
+        handle_message(msg, &state, gm).await;
+
+        let notes = state.store.notes(target).await;
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].author, shared::GM_AUTHOR);
+        let delivered = std::iter::from_fn(|| player_inbox.try_recv().ok())
+            .find(|msg| matches!(msg, ServerMessage::NoteAdded { .. }));
+        assert!(matches!(
+            delivered,
+            Some(ServerMessage::NoteAdded { id, ref note })
+                if id == target && *note == notes[0]
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_save_reaches_other_viewers_only() {
+        let (state, dir) = test_state(None).await;
+        let id = create_test_character(&state.store, "Bard").await;
+        let (editor, mut editor_inbox) = state.hub.register().await;
+        let (viewer, mut viewer_inbox) = state.hub.register().await;
+        let (_elsewhere, mut elsewhere_inbox) = state.hub.register().await;
+        for conn in [editor, viewer] {
+            handle_message(ClientMessage::Viewing { id: Some(id) }, &state, conn).await;
+        }
+
+        let mut character = state
+            .store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        character.level += 1;
+        let save = ClientMessage::UpdateCharacter {
+            character: Box::new(character),
+            base_version: None,
+        };
+        handle_message(save, &state, editor).await;
+
+        let changed = |inbox: &mut tokio::sync::mpsc::UnboundedReceiver<ServerMessage>| {
+            std::iter::from_fn(|| inbox.try_recv().ok())
+                .find(|msg| matches!(msg, ServerMessage::CharacterChanged { .. }))
+        };
+        assert!(matches!(
+            changed(&mut viewer_inbox),
+            Some(ServerMessage::CharacterChanged { summary })
+                if summary.id == id && summary.latest_version == 2
+        ));
+        assert!(changed(&mut editor_inbox).is_none());
+        assert!(changed(&mut elsewhere_inbox).is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_authenticated_save_records_author() {
+        let (state, dir) = test_state(Some("secret")).await;
+        let id = create_test_character(&state.store, "Scribe").await;
+        let (conn_id, _inbox) = state.hub.register().await;
+        let password = "secret".to_string();
+        handle_message(ClientMessage::Authenticate { password }, &state, conn_id).await;
+
+        let mut character = state
+            .store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        character.level += 1;
+        let responses = handle_message(
+            ClientMessage::UpdateCharacter {
+                character: Box::new(character),
+                base_version: None,
+            },
+            &state,
+            conn_id,
+        )
+        .await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::CharacterUpdated { .. }]
+        ));
+
+        let versions = state.store.get_version_list(id).await.unwrap();
+        let saved_by: Vec<Option<&str>> = versions.iter().map(|v| v.saved_by.as_deref()).collect();
+        assert_eq!(saved_by, vec![None, Some(GM_AUTHOR)]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_version_diff_lists_changes_between_versions() {
+        let (state, dir) = test_state(None).await;
+        let id = create_test_character(&state.store, "Climber").await;
+        let mut character = state
+            .store
+            .get_character_version(id, None)
+            .await
+            .unwrap()
+            .character;
+        let level = character.level;
+        character.level += 1;
+        state.store.update(character, None).await.unwrap();
+        let (conn_id, _inbox) = state.hub.register().await;
+
+        let request = ClientMessage::RequestVersionDiff { id, from: 1, to: 2 };
+        let responses = handle_message(request, &state, conn_id).await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::VersionDiff { from: 1, to: 2, changes, .. }]
+                if *changes == vec![shared::DiffEntry::Level { from: level, to: level + 1 }]
+        ));
+
+        let request = ClientMessage::RequestVersionDiff { id, from: 1, to: 5 };
+        let responses = handle_message(request, &state, conn_id).await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::Error {
+                code: ErrorCode::NotFound,
+                ..
+            }]
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_protected_character_needs_its_password() {
+        let (state, dir) = test_state(Some("secret")).await;
+        let (owner, _owner_inbox) = state.hub.register().await;
+        let (other, _other_inbox) = state.hub.register().await;
+        let create = ClientMessage::CreateCharacter {
+            name: "Hermit".to_string(),
+            accent_color: None,
+            race: Default::default(),
+            class: Default::default(),
+            stats: Default::default(),
+            skills: Vec::new(),
+            traits: Vec::new(),
+            password: Some("moss".to_string()),
+        };
+        let id = match handle_message(create, &state, owner).await.as_slice() {
+            [ServerMessage::CharacterCreated { summary }] => {
+                assert!(summary.protected);
+                summary.id
+            }
+            other => panic!("unexpected response {other:?}"),
+        };
+        // The creator is unlocked; others are refused until they authenticate
+        let delete_version = ClientMessage::DeleteVersion { id, version: 1 };
+        let responses = handle_message(delete_version, &state, other).await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::Error {
+                code: ErrorCode::Unauthorized,
+                ..
+            }]
+        ));
+        let wrong = ClientMessage::AuthenticateCharacter {
+            id,
+            password: "stone".to_string(),
+        };
+        let responses = handle_message(wrong, &state, other).await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::Error {
+                code: ErrorCode::Unauthorized,
+                ..
+            }]
+        ));
+        assert!(!may_edit(&state, other, id).await);
+        assert!(may_edit(&state, owner, id).await);
+
+        let right = ClientMessage::AuthenticateCharacter {
+            id,
+            password: "moss".to_string(),
+        };
+        let responses = handle_message(right, &state, other).await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::CharacterUnlocked { id: unlocked }] if *unlocked == id
+        ));
+        assert!(may_edit(&state, other, id).await);
+
+        // The admin password overrides the character's
+        let (gm, _gm_inbox) = state.hub.register().await;
+        let password = "secret".to_string();
+        handle_message(ClientMessage::Authenticate { password }, &state, gm).await;
+        assert!(may_edit(&state, gm, id).await);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// A character protected by the password "moss", and a connection that
+    /// has not unlocked it.
+    async fn locked_character(state: &AppState) -> (Uuid, ConnectionId) {
+        let id = create_test_character(&state.store, "Warden").await;
+        assert!(state.store.set_owner_password(id, "moss").await);
+        let (conn_id, _inbox) = state.hub.register().await;
+        (id, conn_id)
+    }
+
+    #[tokio::test]
+    async fn test_locked_character_refuses_tags() {
+        let (state, dir) = test_state(Some("secret")).await;
+        let (id, conn_id) = locked_character(&state).await;
+
+        let tag = ClientMessage::SetTags {
+            id,
+            tags: vec!["villain".to_string()],
+        };
+        let responses = handle_message(tag, &state, conn_id).await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::Error {
+                code: ErrorCode::Unauthorized,
+                ..
+            }]
+        ));
+        let summaries = state.store.get_all_summaries().await;
+        assert!(summaries[0].tags.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_locked_character_refuses_portrait() {
+        let (state, dir) = test_state(Some("secret")).await;
+        let (id, conn_id) = locked_character(&state).await;
+
+        let upload = ClientMessage::UploadPortrait {
+            id,
+            png_data: b"not really a png".to_vec(),
+        };
+        let responses = handle_message(upload, &state, conn_id).await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::Error {
+                code: ErrorCode::Unauthorized,
+                ..
+            }]
+        ));
+        assert!(state.store.load_portrait(id).await.is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_delete_ignores_repeated_ids() {
+        let (state, dir) = test_state(None).await;
+        let id = create_test_character(&state.store, "Twice").await;
+        let (conn_id, _inbox) = state.hub.register().await;
+
+        let delete = ClientMessage::DeleteCharacters { ids: vec![id, id] };
+        let responses = handle_message(delete, &state, conn_id).await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::CharacterDeleted { id: deleted }] if *deleted == id
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_name_is_reported_as_taken() {
+        let (state, dir) = test_state(None).await;
+        let id = create_test_character(&state.store, "Twin").await;
+        let (conn_id, _inbox) = state.hub.register().await;
+        let code = |responses: Vec<ServerMessage>| match responses.as_slice() {
+            [ServerMessage::Error { code, .. }] => *code,
+            other => panic!("unexpected responses {other:?}"),
+        };
+
+        let create = ClientMessage::CreateCharacter {
+            name: " Twin ".to_string(),
+            accent_color: None,
+            race: Default::default(),
+            class: Default::default(),
+            stats: Default::default(),
+            skills: Vec::new(),
+            traits: Vec::new(),
+            password: None,
+        };
+        let responses = handle_message(create, &state, conn_id).await;
+        assert_eq!(code(responses), ErrorCode::NameTaken);
+
+        let clone = ClientMessage::CloneCharacter {
+            id,
+            new_name: "Twin".to_string(),
+        };
+        let responses = handle_message(clone, &state, conn_id).await;
+        assert_eq!(code(responses), ErrorCode::NameTaken);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_grant_xp_levels_party_identically() {
+        let (state, dir) = test_state(None).await;
+        let mut ids = Vec::new();
+        for name in ["Ash", "Birch", "Cedar"] {
+            let id = create_test_character(&state.store, name).await;
+            let mut character = state
+                .store
+                .get_character_version(id, None)
+                .await
+                .unwrap()
+                .character;
+            character.level = 1;
+            state.store.update(character, None).await.unwrap();
+            ids.push(id);
+        }
+        let (gm, _gm_inbox) = state.hub.register().await;
+        state.hub.set_admin(gm, true).await;
+        let (player, mut player_inbox) = state.hub.register().await;
+        handle_message(ClientMessage::Viewing { id: Some(ids[0]) }, &state, player).await;
+
+        let msg = ClientMessage::GrantXp {
+            ids: ids.clone(),
+            amount: 30,
+        };
+        let responses = handle_message(msg, &state, gm).await;
+        assert_eq!(responses.len(), 3);
+
+        let mut progress = Vec::new();
+        for &id in &ids {
+            let c = state
+                .store
+                .get_character_version(id, None)
+                .await
+                .unwrap()
+                .character;
+            progress.push((
+                c.level,
+                c.experience,
+                c.skill_points,
+                c.ability_points,
+                c.trait_points,
+            ));
+        }
+        // 20 XP reaches level 2, 10 carry over
+        assert_eq!(progress[0].0, 2);
+        assert_eq!(progress[0].1, 10);
+        assert!(progress.iter().all(|p| *p == progress[0]));
+
+        let pushed = std::iter::from_fn(|| player_inbox.try_recv().ok())
+            .any(|msg| matches!(msg, ServerMessage::CharacterVersion { id, .. } if id == ids[0]));
+        assert!(pushed);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_import_gets_fresh_id_and_reports_unknown_gear() {
+        let (state, dir) = test_state(None).await;
+        let original = create_test_character(&state.store, "Wanderer").await;
+        let mut character = state
+            .store
+            .get_character_version(original, None)
+            .await
+            .unwrap()
+            .character;
+        character.equipped_weapons.push("Moonblade".to_string());
+        let json = serde_json::to_string(&character).unwrap();
+        let (conn, _inbox) = state.hub.register().await;
+
+        let responses = handle_message(ClientMessage::ImportCharacter { json }, &state, conn).await;
+        let [ServerMessage::CharacterCreated { summary }, ServerMessage::ImportResult {
+            id,
+            missing_weapons,
+            ..
+        }] = responses.as_slice()
+        else {
+            panic!("unexpected responses: {responses:?}");
+        };
+        assert_eq!(summary.id, *id);
+        assert_ne!(*id, original);
+        assert_eq!(missing_weapons, &["Moonblade".to_string()]);
+        let imported = state.store.get_character_version(*id, None).await.unwrap();
+        assert_eq!(imported.character.equipped_weapons, ["Moonblade"]);
+
+        let garbage = ClientMessage::ImportCharacter {
+            json: "{ not json".to_string(),
+        };
+        let responses = handle_message(garbage, &state, conn).await;
+        assert!(matches!(
+            responses.as_slice(),
+            [ServerMessage::Error {
+                code: ErrorCode::Invalid,
+                ..
+            }]
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
@@ -0,0 +1,39 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::version::Timestamp;
+
+/// Kind of change an audit entry records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    Created,
+    Updated,
+    /// A copy of version `from` was saved as the newest version
+    RolledBack {
+        from: u32,
+    },
+    Deleted,
+}
+
+/// One mutation of a character, as appended to its audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub at: Timestamp,
+    pub action: AuditAction,
+    /// Version the mutation left as the newest; `None` for deletions
+    pub version: Option<u32>,
+    /// Identity of the connection, as on the versions it saves
+    pub by: Option<String>,
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditAction::Created => write!(f, "Created"),
+            AuditAction::Updated => write!(f, "Updated"),
+            AuditAction::RolledBack { from } => write!(f, "Rolled back to v{from}"),
+            AuditAction::Deleted => write!(f, "Deleted"),
+        }
+    }
+}
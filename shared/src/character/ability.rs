@@ -1,15 +1,15 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 
 use serde::Deserialize;
-use strum::Display;
+use strum::{Display, EnumIter};
 
 use super::character_trait::TraitCondition;
 use super::characteristic::CharacteristicKind;
 use super::class::Class;
 
 /// The type of an ability, determining how it behaves in gameplay.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumIter, Deserialize)]
 pub enum AbilityType {
     Stance,
     Attack,
@@ -56,6 +56,12 @@ pub struct AbilityRequirements {
     pub hp: Option<u32>,
     pub action_points: Option<u32>,
     pub range: Option<u32>,
+    /// Uses allowed between long rests; unlimited when absent.
+    #[serde(default)]
+    pub uses_per_rest: Option<u32>,
+    /// Turns to wait after a use before the ability can be used again.
+    #[serde(default)]
+    pub cooldown_turns: Option<u32>,
 }
 
 /// An upgrade to an ability, unlocked when a condition is met.
@@ -92,6 +98,59 @@ pub struct Ability {
     pub learn_screen_position: Option<LearnScreenPosition>,
     #[serde(default)]
     pub can_learn_after: Vec<String>,
+    /// Minimum character level required to learn the ability.
+    #[serde(default)]
+    pub min_level: Option<u32>,
+}
+
+/// Unmet requirements that keep an ability from being learned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbilityLock {
+    /// Prerequisite abilities, any one of which unlocks this ability.
+    /// Empty when the prerequisite requirement is already satisfied.
+    pub missing_prerequisites: Vec<String>,
+    /// Required character level, if not yet reached.
+    pub required_level: Option<u32>,
+}
+
+impl fmt::Display for AbilityLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = Vec::new();
+        match self.missing_prerequisites.as_slice() {
+            [] => {}
+            [single] => lines.push(format!("Requires: {single}")),
+            many => lines.push(format!("Requires one of: {}", many.join(", "))),
+        }
+        if let Some(level) = self.required_level {
+            lines.push(format!("Requires level {level}"));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Ability {
+    /// Returns what still blocks learning this ability, or `None` if it can be learned.
+    /// `can_learn_after` is satisfied when any one of the listed abilities is known.
+    pub fn lock(&self, known: &[String], level: u32) -> Option<AbilityLock> {
+        let prerequisites_met = self.can_learn_after.is_empty()
+            || self
+                .can_learn_after
+                .iter()
+                .any(|prereq| known.contains(prereq));
+        let missing_prerequisites = if prerequisites_met {
+            Vec::new()
+        } else {
+            self.can_learn_after.clone()
+        };
+        let required_level = self.min_level.filter(|&min| level < min);
+        if missing_prerequisites.is_empty() && required_level.is_none() {
+            return None;
+        }
+        Some(AbilityLock {
+            missing_prerequisites,
+            required_level,
+        })
+    }
 }
 
 /// Abilities for a single class, split into innate and acquirable.
@@ -138,6 +197,40 @@ impl AbilityRegistry {
     pub fn get_acquire(&self, class: &Class, name: &str) -> Option<&Ability> {
         self.classes.get(class)?.acquire.get(name)
     }
+
+    /// Get an innate or acquirable ability by class and name.
+    pub fn get(&self, class: &Class, name: &str) -> Option<&Ability> {
+        let abilities = self.classes.get(class)?;
+        abilities
+            .innate
+            .get(name)
+            .or_else(|| abilities.acquire.get(name))
+    }
+
+    /// Find an acquirable ability by name in any class.
+    pub fn find_acquire(&self, name: &str) -> Option<&Ability> {
+        self.classes.values().find_map(|ca| ca.acquire.get(name))
+    }
+
+    /// Whether a character can learn `name` now. Abilities in `unlocked`
+    /// (see `TraitRegistry::unlocked_abilities`) skip the class and lock checks.
+    pub fn can_learn(
+        &self,
+        class: &Class,
+        name: &str,
+        known: &[String],
+        level: u32,
+        unlocked: &BTreeSet<String>,
+    ) -> bool {
+        if known.iter().any(|k| k == name) {
+            return false;
+        }
+        if unlocked.contains(name) {
+            return self.find_acquire(name).is_some();
+        }
+        self.get_acquire(class, name)
+            .is_some_and(|ability| ability.lock(known, level).is_none())
+    }
 }
 
 #[cfg(test)]
@@ -271,4 +364,63 @@ mod tests {
         assert!(registry.get_innate(&Class::Bard, "Nonexistent").is_none());
         assert!(registry.get_acquire(&Class::Bard, "Nonexistent").is_none());
     }
+
+    #[test]
+    fn test_lock_names_unmet_prerequisite() {
+        let ability: Ability = serde_json::from_str(
+            r#"{
+                "description": "",
+                "type": "Passive",
+                "can_learn_after": ["Rebound"],
+                "min_level": 3
+            }"#,
+        )
+        .expect("failed to parse ability");
+
+        let lock = ability.lock(&[], 1).expect("ability should be locked");
+        assert_eq!(lock.missing_prerequisites, vec!["Rebound".to_string()]);
+        assert_eq!(lock.required_level, Some(3));
+        let tooltip = lock.to_string();
+        assert!(tooltip.contains("Rebound"));
+        assert!(tooltip.contains("level 3"));
+
+        // Learning the prerequisite leaves only the level requirement
+        let lock = ability.lock(&["Rebound".to_string()], 1).unwrap();
+        assert!(lock.missing_prerequisites.is_empty());
+
+        assert!(ability.lock(&["Rebound".to_string()], 3).is_none());
+    }
+
+    #[test]
+    fn test_unlocking_trait_allows_gated_ability() {
+        use super::super::character_trait::TraitRegistry;
+
+        let abilities = AbilityRegistry::load_from_str(
+            r#"{
+                "Bard": {
+                    "Acquire": {
+                        "Encore": {
+                            "description": "",
+                            "type": "Peaceful",
+                            "can_learn_after": ["Rebound"]
+                        }
+                    }
+                }
+            }"#,
+        )
+        .expect("failed to parse abilities");
+        let traits = TraitRegistry::load_from_str(
+            r#"{
+                "Stage Veteran": { "description": "", "unlocks_abilities": ["Encore"] }
+            }"#,
+        )
+        .expect("failed to parse traits");
+
+        let without = traits.unlocked_abilities(&[]);
+        assert!(!abilities.can_learn(&Class::Bard, "Encore", &[], 1, &without));
+
+        let with = traits.unlocked_abilities(&["Stage Veteran".to_string()]);
+        assert!(abilities.can_learn(&Class::Bard, "Encore", &[], 1, &with));
+        assert!(!abilities.can_learn(&Class::Bard, "Encore", &["Encore".to_string()], 1, &with));
+    }
 }
@@ -0,0 +1,140 @@
+//! Per-rest uses and cooldowns of abilities that limit how often they are used.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::AbilityRequirements;
+
+/// How much of an ability's per-rest uses and cooldown is spent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AbilityRuntime {
+    /// Uses since the last long rest.
+    #[serde(default)]
+    pub uses_spent: u32,
+    /// Turns left before the ability can be used again.
+    #[serde(default)]
+    pub cooldown: u32,
+}
+
+impl AbilityRuntime {
+    /// Why the ability can't be used right now, if it can't.
+    pub fn unavailable(&self, requirements: Option<&AbilityRequirements>) -> Option<String> {
+        if self.cooldown > 0 {
+            let turns = if self.cooldown == 1 { "turn" } else { "turns" };
+            return Some(format!("On cooldown for {} {turns}", self.cooldown));
+        }
+        let uses = requirements.and_then(|r| r.uses_per_rest)?;
+        (self.uses_spent >= uses).then(|| format!("All {uses} uses spent until the next rest"))
+    }
+
+    fn is_fresh(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Records a use of the ability `name`: counts it against its per-rest uses
+/// and starts its cooldown. Returns false, changing nothing, when the ability
+/// is on cooldown or out of uses.
+pub fn use_ability(
+    runtime: &mut BTreeMap<String, AbilityRuntime>,
+    name: &str,
+    requirements: Option<&AbilityRequirements>,
+) -> bool {
+    let current = runtime.get(name).copied().unwrap_or_default();
+    if current.unavailable(requirements).is_some() {
+        return false;
+    }
+    let uses_per_rest = requirements.and_then(|r| r.uses_per_rest);
+    let cooldown_turns = requirements.and_then(|r| r.cooldown_turns);
+    if uses_per_rest.is_none() && cooldown_turns.is_none() {
+        return true;
+    }
+    let entry = runtime.entry(name.to_string()).or_default();
+    if uses_per_rest.is_some() {
+        entry.uses_spent += 1;
+    }
+    entry.cooldown = cooldown_turns.unwrap_or(0);
+    true
+}
+
+/// Counts one turn off every cooldown. Returns true if any cooldown ended.
+pub fn tick_ability_cooldowns(runtime: &mut BTreeMap<String, AbilityRuntime>) -> bool {
+    let mut ended = false;
+    for entry in runtime.values_mut() {
+        if entry.cooldown == 1 {
+            ended = true;
+        }
+        entry.cooldown = entry.cooldown.saturating_sub(1);
+    }
+    runtime.retain(|_, entry| !entry.is_fresh());
+    ended
+}
+
+/// Makes every per-rest use available again. Cooldowns keep running.
+pub fn reset_ability_uses(runtime: &mut BTreeMap<String, AbilityRuntime>) {
+    for entry in runtime.values_mut() {
+        entry.uses_spent = 0;
+    }
+    runtime.retain(|_, entry| !entry.is_fresh());
+}
+
+/// Ends every running cooldown. Per-rest uses stay spent.
+pub fn clear_ability_cooldowns(runtime: &mut BTreeMap<String, AbilityRuntime>) {
+    for entry in runtime.values_mut() {
+        entry.cooldown = 0;
+    }
+    runtime.retain(|_, entry| !entry.is_fresh());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requirements(
+        uses_per_rest: Option<u32>,
+        cooldown_turns: Option<u32>,
+    ) -> AbilityRequirements {
+        AbilityRequirements {
+            mp: None,
+            hp: None,
+            action_points: None,
+            range: None,
+            uses_per_rest,
+            cooldown_turns,
+        }
+    }
+
+    #[test]
+    fn test_uses_run_out_until_rest() {
+        let reqs = requirements(Some(2), None);
+        let mut runtime = BTreeMap::new();
+
+        assert!(use_ability(&mut runtime, "Smite", Some(&reqs)));
+        assert!(use_ability(&mut runtime, "Smite", Some(&reqs)));
+        assert!(!use_ability(&mut runtime, "Smite", Some(&reqs)));
+        assert_eq!(runtime["Smite"].uses_spent, 2);
+
+        reset_ability_uses(&mut runtime);
+        assert!(runtime.is_empty());
+        assert!(use_ability(&mut runtime, "Smite", Some(&reqs)));
+    }
+
+    #[test]
+    fn test_cooldown_blocks_until_turns_pass() {
+        let reqs = requirements(None, Some(2));
+        let mut runtime = BTreeMap::new();
+
+        assert!(use_ability(&mut runtime, "Dash", Some(&reqs)));
+        assert!(!use_ability(&mut runtime, "Dash", Some(&reqs)));
+        assert!(!tick_ability_cooldowns(&mut runtime));
+        assert!(runtime["Dash"].unavailable(Some(&reqs)).is_some());
+        assert!(tick_ability_cooldowns(&mut runtime));
+        assert!(runtime.is_empty());
+        assert!(use_ability(&mut runtime, "Dash", Some(&reqs)));
+
+        // Unlimited abilities leave no trace
+        assert!(use_ability(&mut runtime, "Strike", None));
+        assert!(!runtime.contains_key("Strike"));
+    }
+}
@@ -1,12 +1,12 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use super::characteristic::CharacteristicKind;
 use super::effect::Effect;
 
 /// Condition required to learn a trait
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TraitCondition {
     CharacteristicsRequired {
         characteristic: CharacteristicKind,
@@ -15,13 +15,16 @@ pub enum TraitCondition {
 }
 
 /// A character trait definition
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CharacterTrait {
     pub description: String,
     #[serde(default)]
     pub effects: Vec<Effect>,
     #[serde(default)]
     pub condition: Option<TraitCondition>,
+    /// Abilities the trait makes learnable regardless of class or prerequisites.
+    #[serde(default)]
+    pub unlocks_abilities: Vec<String>,
 }
 
 /// Registry of all character traits
@@ -49,6 +52,15 @@ impl TraitRegistry {
     pub fn get(&self, name: &str) -> Option<&CharacterTrait> {
         self.traits.get(name)
     }
+
+    /// Abilities unlocked by the given traits.
+    pub fn unlocked_abilities(&self, trait_names: &[String]) -> BTreeSet<String> {
+        trait_names
+            .iter()
+            .filter_map(|name| self.get(name))
+            .flat_map(|t| t.unlocks_abilities.iter().cloned())
+            .collect()
+    }
 }
 
 #[cfg(test)]
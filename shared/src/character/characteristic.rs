@@ -75,6 +75,19 @@ impl Characteristics {
         }
     }
 
+    pub fn get_mut(&mut self, kind: CharacteristicKind) -> &mut Characteristic {
+        match kind {
+            CharacteristicKind::Strength => &mut self.strength,
+            CharacteristicKind::Dexterity => &mut self.dexterity,
+            CharacteristicKind::Endurance => &mut self.endurance,
+            CharacteristicKind::Perception => &mut self.perception,
+            CharacteristicKind::Magic => &mut self.magic,
+            CharacteristicKind::Willpower => &mut self.willpower,
+            CharacteristicKind::Intellect => &mut self.intellect,
+            CharacteristicKind::Charisma => &mut self.charisma,
+        }
+    }
+
     pub fn get_level(&self, kind: CharacteristicKind) -> u32 {
         match kind {
             CharacteristicKind::Strength => self.strength.level,
@@ -0,0 +1,119 @@
+//! Modifiers for skill and ability checks, and rolling them against a target.
+
+use super::ability::AbilityCheck;
+use super::skill::CharacterSkill;
+use super::{Character, CharacteristicKind, Effect};
+use crate::dice::CheckOutcome;
+use crate::rules::{Stacked, StackingRules};
+
+/// Skill level (free ranks included) plus `Effect::Skill` bonuses for it, up
+/// to the skill stacking cap. A skill the character has not learned starts from 0.
+pub fn skill_check_modifier(
+    skills: &[CharacterSkill],
+    effects: &[Effect],
+    skill_name: &str,
+    stacking: &StackingRules,
+) -> i32 {
+    let level = skills
+        .iter()
+        .find(|s| s.name == skill_name)
+        .map_or(0, |s| s.level);
+    let bonus: i32 = effects
+        .iter()
+        .filter_map(|e| match e {
+            Effect::Skill(name, v) if name == skill_name => Some(*v),
+            _ => None,
+        })
+        .sum();
+    level as i32 + Stacked::new(bonus, stacking.skill).value
+}
+
+/// Modifier for an ability's check: the skill modifier, or the effective
+/// characteristic level from `level_of`.
+pub fn ability_check_modifier(
+    check: &AbilityCheck,
+    skills: &[CharacterSkill],
+    effects: &[Effect],
+    stacking: &StackingRules,
+    level_of: impl Fn(CharacteristicKind) -> u32,
+) -> i32 {
+    match check {
+        AbilityCheck::Skill(name) => skill_check_modifier(skills, effects, name, stacking),
+        AbilityCheck::Characteristic(kind) => level_of(*kind) as i32,
+    }
+}
+
+impl Character {
+    /// See [`skill_check_modifier`]. Requires `active_effects` to be up to date.
+    pub fn skill_check_modifier(&self, skill_name: &str, stacking: &StackingRules) -> i32 {
+        skill_check_modifier(&self.skills, &self.active_effects, skill_name, stacking)
+    }
+
+    /// See [`ability_check_modifier`]. Requires `active_effects` to be up to date.
+    pub fn ability_check_modifier(&self, check: &AbilityCheck, stacking: &StackingRules) -> i32 {
+        ability_check_modifier(
+            check,
+            &self.skills,
+            &self.active_effects,
+            stacking,
+            |kind| self.effective_level(kind),
+        )
+    }
+
+    /// Rolls d20 plus the skill modifier against `target`.
+    pub fn roll_skill_check(
+        &self,
+        skill_name: &str,
+        target: i32,
+        stacking: &StackingRules,
+        rng: &mut impl FnMut() -> u32,
+    ) -> CheckOutcome {
+        CheckOutcome::roll(self.skill_check_modifier(skill_name, stacking), target, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice::SeededRng;
+
+    #[test]
+    fn test_skill_check_uses_level_and_bonuses() {
+        let mut character = Character::new("Lockpick".to_string());
+        let mut stealth = CharacterSkill::new("Stealth".to_string());
+        stealth.level = 4;
+        character.skills.push(stealth);
+        character.active_effects = vec![
+            Effect::Skill("Stealth".to_string(), 2),
+            Effect::Skill("Athletics".to_string(), 1),
+            Effect::Characteristic(CharacteristicKind::Dexterity, 3),
+        ];
+        character.stats.dexterity.level = 2;
+
+        let stacking = StackingRules::default();
+        assert_eq!(character.skill_check_modifier("Stealth", &stacking), 6);
+        assert_eq!(character.skill_check_modifier("Athletics", &stacking), 1);
+        assert_eq!(
+            character.ability_check_modifier(
+                &AbilityCheck::Characteristic(CharacteristicKind::Dexterity),
+                &stacking
+            ),
+            5
+        );
+        let capped = StackingRules {
+            skill: Some(1),
+            ..stacking
+        };
+        assert_eq!(character.skill_check_modifier("Stealth", &capped), 5);
+
+        // Same seed, same outcome; the margin follows the modifier
+        let roll = |target| {
+            let mut rng = SeededRng::new(7);
+            character.roll_skill_check("Stealth", target, &stacking, &mut || rng.next_u32())
+        };
+        let outcome = roll(15);
+        assert_eq!(outcome, roll(15));
+        assert_eq!(outcome.check.modifier, 6);
+        assert_eq!(outcome.margin(), outcome.check.natural as i32 + 6 - 15);
+    }
+}
@@ -0,0 +1,125 @@
+//! Derived combat numbers: attack bonus per weapon, base protections and defense.
+//! Every formula lives here so the numbers can be tuned in one spot.
+
+use super::effect::{Effect, Protection};
+use super::weapon::{Weapon, WeaponKind};
+use super::{Character, CharacteristicKind};
+
+/// Protection every character starts from before characteristics and effects.
+const BASE_PROTECTION: i32 = 10;
+
+/// Characteristics an attack with a weapon of `kind` is made with. Several are
+/// averaged, rounding down.
+pub fn attack_characteristics(kind: WeaponKind) -> &'static [CharacteristicKind] {
+    match kind {
+        WeaponKind::Melee(_) | WeaponKind::Shield => &[CharacteristicKind::Strength],
+        WeaponKind::Range(_) => &[
+            CharacteristicKind::Dexterity,
+            CharacteristicKind::Perception,
+        ],
+        WeaponKind::BardInstrument => &[CharacteristicKind::Charisma],
+    }
+}
+
+/// Attack bonus with `weapon`: its attack characteristics plus the weapon's own
+/// `attack`. `level` returns effective levels, so `Effect::Characteristic`
+/// bonuses and penalties count.
+pub fn attack_bonus(weapon: &Weapon, level: impl Fn(CharacteristicKind) -> u32) -> i32 {
+    let kinds = attack_characteristics(weapon.kind);
+    let total: u32 = kinds.iter().map(|&kind| level(kind)).sum();
+    (total / kinds.len() as u32) as i32 + weapon.attack
+}
+
+/// Protections derived from characteristics, applied before any effect.
+/// Range protection comes from the race's size instead.
+pub fn base_protections(level: impl Fn(CharacteristicKind) -> u32) -> [Effect; 4] {
+    let protection = |kind, characteristic| {
+        Effect::Protection(kind, BASE_PROTECTION + level(characteristic) as i32)
+    };
+    [
+        protection(Protection::Melee, CharacteristicKind::Dexterity),
+        protection(Protection::Magic, CharacteristicKind::Magic),
+        protection(Protection::Body, CharacteristicKind::Endurance),
+        protection(Protection::Mind, CharacteristicKind::Willpower),
+    ]
+}
+
+/// Defense against attacks: melee protection plus armor. `effects` must
+/// already include the [`base_protections`].
+pub fn defense(effects: &[Effect]) -> i32 {
+    effects
+        .iter()
+        .filter_map(|e| match e {
+            Effect::Protection(Protection::Melee, v) | Effect::Armor(v) => Some(*v),
+            _ => None,
+        })
+        .sum()
+}
+
+impl Character {
+    /// Attack bonus with `weapon` (see [`attack_bonus`]).
+    pub fn attack_bonus(&self, weapon: &Weapon) -> i32 {
+        attack_bonus(weapon, |kind| self.effective_level(kind))
+    }
+
+    /// Defense from the base protections and active effects (see [`defense`]).
+    pub fn defense(&self) -> i32 {
+        let mut effects = base_protections(|kind| self.effective_level(kind)).to_vec();
+        effects.extend(self.active_effects.iter().cloned());
+        defense(&effects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{MeleeKind, RangeKind, WeaponGrip};
+
+    fn weapon(kind: WeaponKind, attack: i32) -> Weapon {
+        Weapon {
+            name: "Test".to_string(),
+            description: String::new(),
+            damage: "1d6".to_string(),
+            attack,
+            kind,
+            grip: WeaponGrip::OneHanded,
+            range: 1,
+            effects: Vec::new(),
+            condition: None,
+            value: None,
+            weight: None,
+            damage_type: None,
+        }
+    }
+
+    #[test]
+    fn test_attack_bonus_uses_weapon_characteristics() {
+        let mut character = Character::new("Fighter".to_string());
+        character.stats.strength.level = 4;
+        character.stats.dexterity.level = 3;
+        character.stats.perception.level = 2;
+
+        let sword = weapon(WeaponKind::Melee(MeleeKind::Slashing), 1);
+        let bow = weapon(WeaponKind::Range(RangeKind::Bow), 2);
+        assert_eq!(character.attack_bonus(&sword), 5);
+        // (3 + 2) / 2 rounds down
+        assert_eq!(character.attack_bonus(&bow), 4);
+
+        character.active_effects = vec![Effect::Characteristic(CharacteristicKind::Strength, -2)];
+        assert_eq!(character.attack_bonus(&sword), 3);
+    }
+
+    #[test]
+    fn test_defense_adds_armor_to_melee_protection() {
+        let mut character = Character::new("Guard".to_string());
+        character.stats.dexterity.level = 2;
+        assert_eq!(character.defense(), BASE_PROTECTION + 2);
+
+        character.active_effects = vec![
+            Effect::Armor(3),
+            Effect::Protection(Protection::Melee, 1),
+            Effect::Protection(Protection::Magic, 5),
+        ];
+        assert_eq!(character.defense(), BASE_PROTECTION + 2 + 3 + 1);
+    }
+}
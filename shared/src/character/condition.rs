@@ -0,0 +1,68 @@
+//! Status conditions such as poisoned or stunned, tracked on the sheet until removed.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+
+use super::effect::{Effect, GetEffects, Protection};
+use super::CharacteristicKind;
+
+/// Action point penalty of [`Condition::Stunned`]: larger than any action point
+/// pool, so the maximum bottoms out at zero.
+const STUNNED_ACTION_POINTS: i32 = -100;
+
+/// A status condition affecting the character until it is removed by hand.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, EnumIter, Serialize, Deserialize,
+)]
+pub enum Condition {
+    Poisoned,
+    Stunned,
+    Prone,
+    Blinded,
+    Frightened,
+    Grappled,
+}
+
+impl GetEffects for Condition {
+    fn get_effects(&self) -> Vec<Effect> {
+        match self {
+            Condition::Poisoned => {
+                vec![Effect::Characteristic(CharacteristicKind::Endurance, -1)]
+            }
+            Condition::Stunned => vec![Effect::ActionPoints(STUNNED_ACTION_POINTS)],
+            Condition::Prone => vec![Effect::Protection(Protection::Melee, -2)],
+            Condition::Blinded => vec![Effect::Initiative(-5)],
+            Condition::Frightened | Condition::Grappled => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Character, EquipmentRegistry, TraitRegistry, WeaponRegistry};
+
+    #[test]
+    fn test_conditions_modify_derived_values() {
+        let mut character = Character::new("Ambushed".to_string());
+        let recalculate = |c: &mut Character| {
+            c.recalculate_effects(
+                &TraitRegistry::default(),
+                &WeaponRegistry::default(),
+                &EquipmentRegistry::default(),
+                &[],
+            )
+        };
+        recalculate(&mut character);
+        assert!(character.max_action_points() > 0);
+
+        character.conditions = vec![Condition::Stunned, Condition::Blinded];
+        recalculate(&mut character);
+        assert_eq!(character.max_action_points(), 0);
+        assert!(character.active_effects.contains(&Effect::Initiative(-5)));
+
+        character.conditions.clear();
+        recalculate(&mut character);
+        assert!(character.max_action_points() > 0);
+    }
+}
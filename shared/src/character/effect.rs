@@ -65,21 +65,148 @@ pub enum OnLvlUp {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Effect {
     Resist(Resist, i32),
+    /// Display-only bonus to a skill check; does not change the character's skill ranks.
     Skill(String, i32),
     Protection(Protection, i32),
     Initiative(i32),
     Characteristic(CharacteristicKind, i32),
     ActionPoints(i32),
     Armor(i32),
+    /// Flat bonus to max HP. However many curses stack, max HP stays at least 1.
+    MaxHp(i32),
+    /// Flat bonus to max mana. However many curses stack, max mana stays at least 1.
+    MaxMana(i32),
     /// Increases mana by an amount dependent on characteristic level
     Mana {
         dependent: CharacteristicKind,
         increase_per_point: i32,
     },
     OnLvlUp(OnLvlUp),
+    /// Free ranks added to the character's skill while the source is held.
+    /// Unlike `Skill`, these are real ranks and cost no skill points.
+    FreeSkillRank {
+        skill: String,
+        ranks: u32,
+    },
+}
+
+impl OnLvlUp {
+    fn magnitude(&self) -> i32 {
+        match self {
+            Self::AddSkillPoints(v)
+            | Self::AddAbilityPoints(v)
+            | Self::AddCharacteristicPoints(v) => *v,
+        }
+    }
+
+    fn with_magnitude(&self, v: i32) -> Self {
+        match self {
+            Self::AddSkillPoints(_) => Self::AddSkillPoints(v),
+            Self::AddAbilityPoints(_) => Self::AddAbilityPoints(v),
+            Self::AddCharacteristicPoints(_) => Self::AddCharacteristicPoints(v),
+        }
+    }
+}
+
+impl Effect {
+    /// Returns `true` when both effects modify the same target
+    /// (e.g. two `Armor` entries, or two `Fire` resists) and would stack.
+    pub fn conflicts_with(&self, other: &Effect) -> bool {
+        match (self, other) {
+            (Self::Resist(a, _), Self::Resist(b, _)) => a == b,
+            (Self::Skill(a, _), Self::Skill(b, _)) => a == b,
+            (Self::FreeSkillRank { skill: a, .. }, Self::FreeSkillRank { skill: b, .. }) => a == b,
+            (Self::Protection(a, _), Self::Protection(b, _)) => a == b,
+            (Self::Characteristic(a, _), Self::Characteristic(b, _)) => a == b,
+            (Self::Mana { dependent: a, .. }, Self::Mana { dependent: b, .. }) => a == b,
+            (Self::OnLvlUp(a), Self::OnLvlUp(b)) => {
+                std::mem::discriminant(a) == std::mem::discriminant(b)
+            }
+            (Self::Initiative(_), Self::Initiative(_))
+            | (Self::ActionPoints(_), Self::ActionPoints(_))
+            | (Self::Armor(_), Self::Armor(_))
+            | (Self::MaxHp(_), Self::MaxHp(_))
+            | (Self::MaxMana(_), Self::MaxMana(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Combines two conflicting effects into one by summing their magnitudes.
+    /// Returns `None` if the effects do not conflict.
+    pub fn merge(&self, other: &Effect) -> Option<Effect> {
+        if !self.conflicts_with(other) {
+            return None;
+        }
+        let merged = match (self, other) {
+            (Self::Resist(r, a), Self::Resist(_, b)) => Self::Resist(*r, a + b),
+            (Self::Skill(name, a), Self::Skill(_, b)) => Self::Skill(name.clone(), a + b),
+            (Self::FreeSkillRank { skill, ranks: a }, Self::FreeSkillRank { ranks: b, .. }) => {
+                Self::FreeSkillRank {
+                    skill: skill.clone(),
+                    ranks: a + b,
+                }
+            }
+            (Self::Protection(p, a), Self::Protection(_, b)) => Self::Protection(*p, a + b),
+            (Self::Initiative(a), Self::Initiative(b)) => Self::Initiative(a + b),
+            (Self::Characteristic(k, a), Self::Characteristic(_, b)) => {
+                Self::Characteristic(*k, a + b)
+            }
+            (Self::ActionPoints(a), Self::ActionPoints(b)) => Self::ActionPoints(a + b),
+            (Self::Armor(a), Self::Armor(b)) => Self::Armor(a + b),
+            (Self::MaxHp(a), Self::MaxHp(b)) => Self::MaxHp(a + b),
+            (Self::MaxMana(a), Self::MaxMana(b)) => Self::MaxMana(a + b),
+            (
+                Self::Mana {
+                    dependent,
+                    increase_per_point: a,
+                },
+                Self::Mana {
+                    increase_per_point: b,
+                    ..
+                },
+            ) => Self::Mana {
+                dependent: *dependent,
+                increase_per_point: a + b,
+            },
+            (Self::OnLvlUp(a), Self::OnLvlUp(b)) => {
+                Self::OnLvlUp(a.with_magnitude(a.magnitude() + b.magnitude()))
+            }
+            _ => return None,
+        };
+        Some(merged)
+    }
 }
 
 /// Trait for getting effects
 pub trait GetEffects {
     fn get_effects(&self) -> Vec<Effect>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_armor_conflicts_and_merges() {
+        let existing = Effect::Armor(2);
+        let added = Effect::Armor(-1);
+        assert!(existing.conflicts_with(&added));
+        assert_eq!(existing.merge(&added), Some(Effect::Armor(1)));
+    }
+
+    #[test]
+    fn test_different_targets_do_not_conflict() {
+        let fire = Effect::Resist(Resist::Fire, 2);
+        assert!(fire.conflicts_with(&Effect::Resist(Resist::Fire, 1)));
+        assert!(!fire.conflicts_with(&Effect::Resist(Resist::Ice, 1)));
+        assert!(!fire.conflicts_with(&Effect::Armor(1)));
+        assert_eq!(fire.merge(&Effect::Armor(1)), None);
+
+        let skill_points = Effect::OnLvlUp(OnLvlUp::AddSkillPoints(1));
+        assert!(!skill_points.conflicts_with(&Effect::OnLvlUp(OnLvlUp::AddAbilityPoints(1))));
+        assert_eq!(
+            skill_points.merge(&Effect::OnLvlUp(OnLvlUp::AddSkillPoints(2))),
+            Some(Effect::OnLvlUp(OnLvlUp::AddSkillPoints(3)))
+        );
+    }
+}
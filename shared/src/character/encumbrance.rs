@@ -0,0 +1,139 @@
+//! Carrying capacity from Strength, and the action-point penalty for going
+//! over it.
+
+use std::collections::BTreeMap;
+
+use super::equipment::{EquipmentRegistry, EquipmentSlot};
+use super::inventory::{InventoryItem, InventoryTotals};
+use super::item::ItemRegistry;
+use super::weapon::WeaponRegistry;
+use super::{Character, CharacteristicKind, Effect};
+
+/// Weight a character can carry per level of effective Strength.
+pub const CARRY_PER_STRENGTH: u32 = 5;
+
+/// Most weight carried without penalty at the given effective Strength.
+pub fn carry_capacity(strength: u32) -> u32 {
+    strength * CARRY_PER_STRENGTH
+}
+
+/// Weight of the inventory plus equipped weapons and equipment. Names without
+/// a registry weight count as zero.
+pub fn carried_load(
+    inventory: &[InventoryItem],
+    counts: &BTreeMap<String, u32>,
+    equipped_weapons: &[String],
+    equipped_equipment: &BTreeMap<EquipmentSlot, Vec<String>>,
+    items: &ItemRegistry,
+    equipment: &EquipmentRegistry,
+    weapons: &WeaponRegistry,
+) -> u32 {
+    let worn: u32 = equipped_weapons
+        .iter()
+        .filter_map(|name| weapons.get(name)?.weight)
+        .chain(
+            equipped_equipment
+                .values()
+                .flatten()
+                .filter_map(|name| equipment.get(name)?.weight),
+        )
+        .sum();
+    InventoryTotals::of(inventory, counts, items, equipment, weapons).weight + worn
+}
+
+/// `Effect::ActionPoints(-1)` while `load` is over `capacity`, `-2` once it is
+/// more than double. `None` within capacity.
+pub fn overload_penalty(load: u32, capacity: u32) -> Option<Effect> {
+    if load <= capacity {
+        None
+    } else if load > capacity.saturating_mul(2) {
+        Some(Effect::ActionPoints(-2))
+    } else {
+        Some(Effect::ActionPoints(-1))
+    }
+}
+
+impl Character {
+    /// Carrying capacity from effective Strength. Requires `active_effects`
+    /// to be up to date.
+    pub fn carry_capacity(&self) -> u32 {
+        carry_capacity(self.effective_level(CharacteristicKind::Strength))
+    }
+
+    /// Weight of everything carried, equipped gear included.
+    pub fn current_load(
+        &self,
+        items: &ItemRegistry,
+        equipment: &EquipmentRegistry,
+        weapons: &WeaponRegistry,
+    ) -> u32 {
+        carried_load(
+            &self.inventory,
+            &self.item_counts,
+            &self.equipped_weapons,
+            &self.equipped_equipment,
+            items,
+            equipment,
+            weapons,
+        )
+    }
+
+    /// Adds the overload penalty to the active effects, if the character
+    /// carries more than it can. Call after `recalculate_effects`.
+    pub fn apply_encumbrance(
+        &mut self,
+        items: &ItemRegistry,
+        equipment: &EquipmentRegistry,
+        weapons: &WeaponRegistry,
+    ) {
+        let load = self.current_load(items, equipment, weapons);
+        if let Some(penalty) = overload_penalty(load, self.carry_capacity()) {
+            self.active_effects.push(penalty);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overload_costs_action_points() {
+        let items = ItemRegistry::load_from_str(
+            r#"[
+                { "name": "Anvil", "description": "", "weight": 12 },
+                { "name": "Feather", "description": "" }
+            ]"#,
+        )
+        .unwrap();
+        let weapons = WeaponRegistry::load_from_str(
+            r#"[{ "name": "Maul", "damage": "2d6", "attack": 0, "kind": { "Melee": "Crushing" },
+                  "grip": "TwoHanded", "range": 1, "effects": [], "weight": 4 }]"#,
+        )
+        .unwrap();
+        let equipment = EquipmentRegistry::default();
+        let mut character = Character::new("Porter".to_string());
+        character.stats.strength.level = 2;
+        character
+            .inventory
+            .push(InventoryItem::Item("Feather".to_string()));
+        let base_ap = character.max_action_points();
+
+        // Legacy items without a weight weigh nothing
+        character.apply_encumbrance(&items, &equipment, &weapons);
+        assert_eq!(character.current_load(&items, &equipment, &weapons), 0);
+        assert_eq!(character.max_action_points(), base_ap);
+
+        character
+            .inventory
+            .push(InventoryItem::Item("Anvil".to_string()));
+        character.equipped_weapons.push("Maul".to_string());
+        assert_eq!(character.carry_capacity(), 10);
+        assert_eq!(character.current_load(&items, &equipment, &weapons), 16);
+        character.apply_encumbrance(&items, &equipment, &weapons);
+        assert_eq!(character.max_action_points(), base_ap - 1);
+
+        assert_eq!(overload_penalty(21, 10), Some(Effect::ActionPoints(-2)));
+        assert_eq!(overload_penalty(10, 10), None);
+    }
+}
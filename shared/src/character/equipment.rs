@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter};
 
 use super::effect::Effect;
+use super::inventory::InventoryItem;
+use super::wallet::Wallet;
+use crate::rules::EffectTotal;
 
 /// Equipment slot on a character's body.
 #[derive(
@@ -39,6 +42,12 @@ pub struct Equipment {
     pub description: String,
     pub slot: EquipmentSlot,
     pub effects: Vec<Effect>,
+    /// Full price; selling credits the campaign's resale fraction of it.
+    #[serde(default)]
+    pub value: Option<Wallet>,
+    /// Carry weight; missing weights count as zero in inventory totals.
+    #[serde(default)]
+    pub weight: Option<u32>,
 }
 
 /// Registry of all equipment, keyed by name.
@@ -66,4 +75,65 @@ impl EquipmentRegistry {
     pub fn get(&self, name: &str) -> Option<&Equipment> {
         self.equipment.get(name)
     }
+
+    /// Inventory index of the equipment for `slot` with the highest `metric`,
+    /// ties going to the alphabetically first name. Pieces that do not raise
+    /// the metric are never picked.
+    pub fn best_for_slot(
+        &self,
+        inventory: &[InventoryItem],
+        slot: EquipmentSlot,
+        metric: EffectTotal,
+    ) -> Option<usize> {
+        inventory
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, item)| match item {
+                InventoryItem::Equipment(name) => self.get(name).map(|e| (idx, e)),
+                _ => None,
+            })
+            .filter(|(_, e)| e.slot == slot)
+            .map(|(idx, e)| (idx, e.name.as_str(), metric.of(&e.effects)))
+            .filter(|&(_, _, score)| score > 0)
+            .max_by(|a, b| a.2.cmp(&b.2).then_with(|| b.1.cmp(a.1)))
+            .map(|(idx, ..)| idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::Resist;
+
+    #[test]
+    fn test_best_armor_picks_highest_armor_chest_piece() {
+        let registry = EquipmentRegistry::load_from_str(
+            r#"[
+                { "name": "Leather", "description": "", "slot": "Armor", "effects": [{ "Armor": 2 }] },
+                { "name": "Scale", "description": "", "slot": "Armor", "effects": [{ "Armor": 5 }] },
+                { "name": "Chain", "description": "", "slot": "Armor", "effects": [{ "Armor": 5 }] },
+                { "name": "Great Helm", "description": "", "slot": "Head",
+                  "effects": [{ "Armor": 9 }] },
+                { "name": "Robe", "description": "", "slot": "Armor",
+                  "effects": [{ "Resist": ["Fire", 3] }] }
+            ]"#,
+        )
+        .unwrap();
+        let inventory: Vec<InventoryItem> = ["Leather", "Great Helm", "Scale", "Robe", "Chain"]
+            .into_iter()
+            .map(|name| InventoryItem::Equipment(name.to_string()))
+            .collect();
+
+        // Scale and Chain tie on armor; Chain comes first by name
+        let best = registry.best_for_slot(&inventory, EquipmentSlot::Armor, EffectTotal::Armor);
+        assert_eq!(best, Some(4));
+        let best = registry.best_for_slot(
+            &inventory,
+            EquipmentSlot::Armor,
+            EffectTotal::Resist(Resist::Fire),
+        );
+        assert_eq!(best, Some(3));
+        let best = registry.best_for_slot(&inventory, EquipmentSlot::Ring, EffectTotal::Armor);
+        assert_eq!(best, None);
+    }
 }
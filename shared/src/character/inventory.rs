@@ -1,9 +1,333 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use super::equipment::EquipmentRegistry;
+use super::item::ItemRegistry;
+use super::wallet::Wallet;
+use super::weapon::WeaponRegistry;
+
 /// An item stored in the character's inventory.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum InventoryItem {
     Weapon(String),
     Equipment(String),
     Item(String),
 }
+
+impl InventoryItem {
+    /// Registry name of the item.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Weapon(name) | Self::Equipment(name) | Self::Item(name) => name,
+        }
+    }
+
+    /// Plain items stack in one inventory cell; weapons and equipment are
+    /// unique instances with a cell each.
+    pub fn is_stackable(&self) -> bool {
+        matches!(self, Self::Item(_))
+    }
+
+    /// Units held in this item's cell. Stack sizes above one are kept in
+    /// `counts` by item name.
+    pub fn quantity(&self, counts: &BTreeMap<String, u32>) -> u32 {
+        match self {
+            Self::Item(name) => counts.get(name).copied().unwrap_or(1),
+            _ => 1,
+        }
+    }
+
+    /// Registry weight of the item, if its definition has one.
+    pub fn weight(
+        &self,
+        items: &ItemRegistry,
+        equipment: &EquipmentRegistry,
+        weapons: &WeaponRegistry,
+    ) -> Option<u32> {
+        match self {
+            Self::Weapon(name) => weapons.get(name)?.weight,
+            Self::Equipment(name) => equipment.get(name)?.weight,
+            Self::Item(name) => items.get(name)?.weight,
+        }
+    }
+
+    /// Registry value of the item, if its definition has one.
+    pub fn value(
+        &self,
+        items: &ItemRegistry,
+        equipment: &EquipmentRegistry,
+        weapons: &WeaponRegistry,
+    ) -> Option<Wallet> {
+        match self {
+            Self::Weapon(name) => weapons.get(name)?.value,
+            Self::Equipment(name) => equipment.get(name)?.value,
+            Self::Item(name) => items.get(name)?.value,
+        }
+    }
+}
+
+/// Count, weight and value of a whole inventory, resolved against the registries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InventoryTotals {
+    pub count: usize,
+    pub weight: u32,
+    /// Full value; resale credits only the campaign's fraction of it.
+    pub value: Wallet,
+}
+
+impl InventoryTotals {
+    /// Every unit of a stack counts. Items without a registry weight or value
+    /// count as zero.
+    pub fn of(
+        inventory: &[InventoryItem],
+        counts: &BTreeMap<String, u32>,
+        items: &ItemRegistry,
+        equipment: &EquipmentRegistry,
+        weapons: &WeaponRegistry,
+    ) -> Self {
+        let mut totals = Self::default();
+        let mut value = 0;
+        for item in inventory {
+            let quantity = item.quantity(counts);
+            totals.count += quantity as usize;
+            totals.weight += item.weight(items, equipment, weapons).unwrap_or(0) * quantity;
+            value += item
+                .value(items, equipment, weapons)
+                .map_or(0, |v| v.total())
+                * u64::from(quantity);
+        }
+        totals.value = Wallet::from_total(value);
+        totals
+    }
+}
+
+/// Identical inventory entries counted together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InventoryStack {
+    pub item: InventoryItem,
+    pub quantity: u32,
+}
+
+/// Sorts `inventory` canonically (weapons, equipment, then items, each by
+/// name) and returns its stacks. Identical items end up next to each other,
+/// one stack per name; weapons and equipment are unique instances and each
+/// stay a stack of one.
+pub fn merge_duplicates(inventory: &mut [InventoryItem]) -> Vec<InventoryStack> {
+    inventory.sort();
+    let mut stacks: Vec<InventoryStack> = Vec::new();
+    for item in inventory.iter() {
+        match stacks.last_mut() {
+            Some(stack) if matches!(item, InventoryItem::Item(_)) && stack.item == *item => {
+                stack.quantity += 1;
+            }
+            _ => stacks.push(InventoryStack {
+                item: item.clone(),
+                quantity: 1,
+            }),
+        }
+    }
+    stacks
+}
+
+/// Adds `quantity` units of `item`. A stackable item already in the
+/// inventory grows its stack; anything else takes a new cell per unit.
+pub fn add_item(
+    inventory: &mut Vec<InventoryItem>,
+    counts: &mut BTreeMap<String, u32>,
+    item: InventoryItem,
+    quantity: u32,
+) {
+    if quantity == 0 {
+        return;
+    }
+    let InventoryItem::Item(name) = &item else {
+        inventory.extend(std::iter::repeat_n(item, quantity as usize));
+        return;
+    };
+    if inventory.contains(&item) {
+        let held = item.quantity(counts);
+        counts.insert(name.clone(), held + quantity);
+    } else {
+        if quantity > 1 {
+            counts.insert(name.clone(), quantity);
+        }
+        inventory.push(item);
+    }
+}
+
+/// Splits up to `quantity` units off the cell at `index`, removing the cell
+/// once its stack is empty. Returns the item and how many units were taken.
+pub fn take_items(
+    inventory: &mut Vec<InventoryItem>,
+    counts: &mut BTreeMap<String, u32>,
+    index: usize,
+    quantity: u32,
+) -> Option<(InventoryItem, u32)> {
+    let item = inventory.get(index)?.clone();
+    if quantity == 0 {
+        return None;
+    }
+    let held = item.quantity(counts);
+    let taken = quantity.min(held);
+    if let InventoryItem::Item(name) = &item {
+        match held - taken {
+            0 | 1 => counts.remove(name),
+            left => counts.insert(name.clone(), left),
+        };
+    }
+    if taken == held {
+        inventory.remove(index);
+    }
+    Some((item, taken))
+}
+
+/// Folds repeated cells of a stackable item into the first one's stack, for
+/// inventories saved before items stacked.
+pub fn stack_items(inventory: &mut Vec<InventoryItem>, counts: &mut BTreeMap<String, u32>) {
+    let mut stacked: Vec<InventoryItem> = Vec::with_capacity(inventory.len());
+    for item in inventory.drain(..) {
+        add_item(&mut stacked, counts, item, 1);
+    }
+    *inventory = stacked;
+    counts.retain(|name, _| inventory.contains(&InventoryItem::Item(name.clone())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_totals_sum_resolved_weights_and_values() {
+        let items = ItemRegistry::load_from_str(
+            r#"[
+                { "name": "Rope", "description": "", "value": 5, "weight": 3 },
+                { "name": "Feather", "description": "" }
+            ]"#,
+        )
+        .unwrap();
+        let equipment = EquipmentRegistry::load_from_str(
+            r#"[{ "name": "Helm", "description": "", "slot": "Head", "effects": [],
+                  "value": 40, "weight": 6 }]"#,
+        )
+        .unwrap();
+        let inventory = vec![
+            InventoryItem::Item("Rope".to_string()),
+            InventoryItem::Item("Rope".to_string()),
+            InventoryItem::Item("Feather".to_string()),
+            InventoryItem::Equipment("Helm".to_string()),
+            InventoryItem::Weapon("Unknown Blade".to_string()),
+        ];
+
+        let no_stacks = BTreeMap::new();
+        let totals = InventoryTotals::of(
+            &inventory,
+            &no_stacks,
+            &items,
+            &equipment,
+            &WeaponRegistry::default(),
+        );
+        assert_eq!(totals.count, 5);
+        assert_eq!(totals.weight, 3 + 3 + 6);
+        assert_eq!(totals.value, Wallet::from_total(5 + 5 + 40));
+
+        // Every unit of a stack counts
+        let stacked = BTreeMap::from([("Feather".to_string(), 4)]);
+        let totals = InventoryTotals::of(
+            &inventory,
+            &stacked,
+            &items,
+            &equipment,
+            &WeaponRegistry::default(),
+        );
+        assert_eq!(totals.count, 8);
+        assert_eq!(totals.weight, 3 + 3 + 6);
+    }
+
+    #[test]
+    fn test_stackable_items_share_a_cell() {
+        let arrow = InventoryItem::Item("Arrow".to_string());
+        let sword = InventoryItem::Weapon("Sword".to_string());
+        let mut inventory = Vec::new();
+        let mut counts = BTreeMap::new();
+
+        add_item(&mut inventory, &mut counts, arrow.clone(), 1);
+        add_item(&mut inventory, &mut counts, sword.clone(), 2);
+        add_item(&mut inventory, &mut counts, arrow.clone(), 19);
+        assert_eq!(inventory, vec![arrow.clone(), sword.clone(), sword.clone()]);
+        assert_eq!(arrow.quantity(&counts), 20);
+        assert_eq!(sword.quantity(&counts), 1);
+
+        // Removing takes one unit and keeps the cell
+        assert_eq!(
+            take_items(&mut inventory, &mut counts, 0, 1),
+            Some((arrow.clone(), 1))
+        );
+        assert_eq!(arrow.quantity(&counts), 19);
+        assert_eq!(inventory.len(), 3);
+
+        // Splitting off more than is held empties the stack and its cell
+        assert_eq!(
+            take_items(&mut inventory, &mut counts, 0, 18),
+            Some((arrow.clone(), 18))
+        );
+        assert_eq!(arrow.quantity(&counts), 1);
+        assert!(counts.is_empty());
+        assert_eq!(
+            take_items(&mut inventory, &mut counts, 0, 5),
+            Some((arrow.clone(), 1))
+        );
+        assert_eq!(inventory, vec![sword.clone(), sword.clone()]);
+        assert_eq!(take_items(&mut inventory, &mut counts, 5, 1), None);
+
+        // Weapons are taken one cell at a time
+        assert_eq!(
+            take_items(&mut inventory, &mut counts, 1, 3),
+            Some((sword.clone(), 1))
+        );
+        assert_eq!(inventory, vec![sword]);
+    }
+
+    #[test]
+    fn test_stack_items_folds_legacy_duplicates() {
+        let torch = InventoryItem::Item("Torch".to_string());
+        let helm = InventoryItem::Equipment("Helm".to_string());
+        let mut inventory = vec![torch.clone(), helm.clone(), torch.clone(), helm.clone()];
+        let mut counts = BTreeMap::from([("Lost".to_string(), 3)]);
+
+        stack_items(&mut inventory, &mut counts);
+        assert_eq!(inventory, vec![torch.clone(), helm.clone(), helm]);
+        assert_eq!(counts, BTreeMap::from([("Torch".to_string(), 2)]));
+    }
+
+    #[test]
+    fn test_merge_stacks_identical_items_only() {
+        let potion = InventoryItem::Item("Potion".to_string());
+        let sword = InventoryItem::Weapon("Sword".to_string());
+        let mut inventory = vec![
+            potion.clone(),
+            InventoryItem::Item("Rope".to_string()),
+            sword.clone(),
+            potion.clone(),
+            InventoryItem::Equipment("Helm".to_string()),
+            sword.clone(),
+            potion.clone(),
+        ];
+
+        let stacks = merge_duplicates(&mut inventory);
+        let counted: Vec<(&InventoryItem, u32)> =
+            stacks.iter().map(|s| (&s.item, s.quantity)).collect();
+        assert_eq!(
+            counted,
+            vec![
+                (&sword, 1),
+                (&sword, 1),
+                (&InventoryItem::Equipment("Helm".to_string()), 1),
+                (&potion, 3),
+                (&InventoryItem::Item("Rope".to_string()), 1),
+            ]
+        );
+        assert_eq!(inventory.len(), 7);
+        assert_eq!(inventory[3..6], [potion.clone(), potion.clone(), potion]);
+    }
+}
@@ -2,11 +2,19 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
+use super::wallet::Wallet;
+
 /// A generic item that can be carried in inventory.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Item {
     pub name: String,
     pub description: String,
+    /// Full price; selling credits the campaign's resale fraction of it.
+    #[serde(default)]
+    pub value: Option<Wallet>,
+    /// Carry weight; missing weights count as zero in inventory totals.
+    #[serde(default)]
+    pub weight: Option<u32>,
 }
 
 /// Registry of all items, keyed by name.
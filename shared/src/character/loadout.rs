@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::equipment::{EquipmentRegistry, EquipmentSlot};
+use super::inventory::InventoryItem;
+use super::weapon::WeaponRegistry;
+
+/// A saved set of equipped weapons and equipment (e.g. "combat", "social").
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Loadout {
+    #[serde(default)]
+    pub weapons: Vec<String>,
+    #[serde(default)]
+    pub equipment: BTreeMap<EquipmentSlot, Vec<String>>,
+}
+
+/// Removes the first inventory entry equal to `item`. Returns whether one was found.
+fn take_from_inventory(inventory: &mut Vec<InventoryItem>, item: &InventoryItem) -> bool {
+    match inventory.iter().position(|i| i == item) {
+        Some(idx) => {
+            inventory.remove(idx);
+            true
+        }
+        None => false,
+    }
+}
+
+impl Loadout {
+    /// Records what is currently equipped.
+    pub fn capture(weapons: &[String], equipment: &BTreeMap<EquipmentSlot, Vec<String>>) -> Self {
+        Self {
+            weapons: weapons.to_vec(),
+            equipment: equipment.clone(),
+        }
+    }
+
+    /// Moves everything currently equipped to the inventory, then equips the
+    /// saved set from the inventory. Items that are no longer in the inventory
+    /// or the registries are skipped; a warning is returned for each.
+    pub fn apply(
+        &self,
+        weapons: &mut Vec<String>,
+        equipment: &mut BTreeMap<EquipmentSlot, Vec<String>>,
+        inventory: &mut Vec<InventoryItem>,
+        weapon_registry: &WeaponRegistry,
+        equipment_registry: &EquipmentRegistry,
+    ) -> Vec<String> {
+        inventory.extend(weapons.drain(..).map(InventoryItem::Weapon));
+        inventory.extend(
+            std::mem::take(equipment)
+                .into_values()
+                .flatten()
+                .map(InventoryItem::Equipment),
+        );
+
+        let mut warnings = Vec::new();
+        for name in &self.weapons {
+            if weapon_registry.get(name).is_none() {
+                warnings.push(format!("Weapon \"{name}\" no longer exists"));
+            } else if !take_from_inventory(inventory, &InventoryItem::Weapon(name.clone())) {
+                warnings.push(format!("Weapon \"{name}\" is not in the inventory"));
+            } else {
+                weapons.push(name.clone());
+            }
+        }
+        for name in self.equipment.values().flatten() {
+            let Some(slot) = equipment_registry.get(name).map(|e| e.slot) else {
+                warnings.push(format!("Equipment \"{name}\" no longer exists"));
+                continue;
+            };
+            if !take_from_inventory(inventory, &InventoryItem::Equipment(name.clone())) {
+                warnings.push(format!("Equipment \"{name}\" is not in the inventory"));
+                continue;
+            }
+            let worn = equipment.entry(slot).or_default();
+            if slot != EquipmentSlot::Ring {
+                inventory.extend(worn.drain(..).map(InventoryItem::Equipment));
+            }
+            worn.push(name.clone());
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Character;
+
+    use super::*;
+
+    #[test]
+    fn test_reapplying_loadout_restores_equipped_set() {
+        let weapons = WeaponRegistry::load_from_str(
+            r#"[{ "name": "Short Bow", "damage": "1d6", "attack": 0, "kind": { "Range": "Bow" },
+                  "grip": "TwoHanded", "range": 6, "effects": [] }]"#,
+        )
+        .unwrap();
+        let equipment = EquipmentRegistry::load_from_str(
+            r#"[
+                { "name": "Iron Helm", "description": "", "slot": "Head", "effects": [] },
+                { "name": "Feathered Hat", "description": "", "slot": "Head", "effects": [] }
+            ]"#,
+        )
+        .unwrap();
+        let mut character = Character::new("Ranger".to_string());
+        character.equipped_weapons = vec!["Short Bow".to_string()];
+        character.equip(EquipmentSlot::Head, "Iron Helm".to_string());
+        character.inventory = vec![InventoryItem::Equipment("Feathered Hat".to_string())];
+        character.save_loadout("combat".to_string());
+
+        // Switch to a social outfit
+        character.equipped_weapons.clear();
+        character
+            .inventory
+            .push(InventoryItem::Weapon("Short Bow".to_string()));
+        character.equipped_equipment.clear();
+        character
+            .inventory
+            .retain(|i| *i != InventoryItem::Equipment("Feathered Hat".into()));
+        character
+            .inventory
+            .push(InventoryItem::Equipment("Iron Helm".to_string()));
+        character.equip(EquipmentSlot::Head, "Feathered Hat".to_string());
+
+        let warnings = character
+            .apply_loadout("combat", &weapons, &equipment)
+            .expect("loadout exists");
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(character.equipped_weapons, vec!["Short Bow".to_string()]);
+        assert_eq!(
+            character.equipped_equipment,
+            BTreeMap::from([(EquipmentSlot::Head, vec!["Iron Helm".to_string()])])
+        );
+        assert_eq!(
+            character.inventory,
+            vec![InventoryItem::Equipment("Feathered Hat".to_string())]
+        );
+    }
+}
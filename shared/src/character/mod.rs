@@ -1,37 +1,63 @@
 mod ability;
+mod ability_use;
 mod character_trait;
 mod characteristic;
+mod check;
 mod class;
+mod combat;
+mod condition;
 mod effect;
+mod encumbrance;
 mod equipment;
 mod inventory;
 mod item;
+mod loadout;
 mod race;
+mod reconcile;
 mod resource;
+mod rest;
 mod skill;
+mod temp_effect;
 mod wallet;
 mod weapon;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
-use strum::IntoEnumIterator;
+use strum::{Display, EnumIter, IntoEnumIterator};
 use uuid::Uuid;
 
+use crate::rules::{firing_modifiers, CampaignRules, EffectModifier, Stacked, StackingRules};
+
 pub use ability::{
-    Ability, AbilityCheck, AbilityRegistry, AbilityRequirements, AbilityType, AbilityUpgrade,
-    ClassAbilities, EnemyCheck, LearnScreenPosition,
+    Ability, AbilityCheck, AbilityLock, AbilityRegistry, AbilityRequirements, AbilityType,
+    AbilityUpgrade, ClassAbilities, EnemyCheck, LearnScreenPosition,
+};
+pub use ability_use::{
+    clear_ability_cooldowns, reset_ability_uses, tick_ability_cooldowns, use_ability,
+    AbilityRuntime,
 };
 pub use character_trait::{CharacterTrait, TraitCondition, TraitRegistry};
 pub use characteristic::{Characteristic, CharacteristicKind, Characteristics};
+pub use check::{ability_check_modifier, skill_check_modifier};
 pub use class::Class;
+pub use combat::{attack_bonus, attack_characteristics, base_protections, defense};
+pub use condition::Condition;
 pub use effect::{Effect, GetEffects, OnLvlUp, Protection, Resist};
+pub use encumbrance::{carried_load, carry_capacity, overload_penalty};
 pub use equipment::{Equipment, EquipmentRegistry, EquipmentSlot};
-pub use inventory::InventoryItem;
+pub use inventory::{
+    add_item, merge_duplicates, stack_items, take_items, InventoryItem, InventoryStack,
+    InventoryTotals,
+};
 pub use item::{Item, ItemRegistry};
+pub use loadout::Loadout;
 pub use race::{Race, Size};
-pub use resource::Resource;
-pub use skill::{CharacterSkill, Skill, SkillRegistry};
+pub use reconcile::{PointPool, ReconcileIssue, Reconciliation};
+pub use resource::{max_hp, HpDelta, Resource};
+pub use rest::{short_rest_heal, RestKind};
+pub use skill::{drop_foreign_skills, sync_free_ranks, CharacterSkill, Skill, SkillRegistry};
+pub use temp_effect::{active_temp_effects, tick_temp_effects, TempEffect};
 pub use wallet::Wallet;
 pub use weapon::{MeleeKind, RangeKind, Weapon, WeaponGrip, WeaponKind, WeaponRegistry};
 
@@ -58,18 +84,36 @@ impl Named for Item {
     }
 }
 
+/// Kind of registry entry a character can reference by name.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, EnumIter, Serialize, Deserialize,
+)]
+pub enum ReferenceKind {
+    Trait,
+    Weapon,
+    Equipment,
+    Item,
+    Ability,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Character {
     pub id: Uuid,
     pub name: String,
+    /// Party accent color (RGB) tinting the portrait border and identity bar.
+    #[serde(default)]
+    pub accent_color: Option<[u8; 3]>,
     pub race: Race,
     pub class: Class,
     pub level: u32,
     pub experience: u32,
-    /// Damage taken (max HP is computed from Endurance * 3 + 3).
+    /// Damage taken (max HP is computed by `Character::max_hp`).
     #[serde(default)]
     pub hp_spent: u32,
-    /// Mana spent (max Mana is computed from Willpower * 3 + 3).
+    /// Temporary HP on top of max HP. Absorbs damage first and does not stack.
+    #[serde(default)]
+    pub temp_hp: u32,
+    /// Mana spent (max Mana is computed by `CampaignRules::max_mana`).
     #[serde(default)]
     pub mana_spent: u32,
     pub action_points: Resource,
@@ -91,9 +135,38 @@ pub struct Character {
     pub equipped_equipment: BTreeMap<EquipmentSlot, Vec<String>>,
     #[serde(default)]
     pub inventory: Vec<InventoryItem>,
+    /// Units in each stack of plain items, by item name. Items missing here
+    /// hold a single unit.
+    #[serde(default)]
+    pub item_counts: BTreeMap<String, u32>,
     /// Character's currency purse.
     #[serde(default)]
     pub wallet: Wallet,
+    /// Named sets of equipped weapons and equipment to switch between.
+    #[serde(default)]
+    pub loadouts: BTreeMap<String, Loadout>,
+    /// Abilities pinned to the hotbar for quick activation, in slot order.
+    #[serde(default)]
+    pub hotbar: Vec<String>,
+    /// Free-form player notes.
+    #[serde(default)]
+    pub notes: String,
+    /// Long rests taken since the GM last started a new day.
+    #[serde(default)]
+    pub rests_used_today: u32,
+    /// Seeds this character's dice so a sequence of rolls can be replayed;
+    /// unseeded characters roll with system randomness.
+    #[serde(default)]
+    pub rng_seed: Option<u64>,
+    /// Effects added by hand for a number of turns, e.g. a combat stance.
+    #[serde(default)]
+    pub temporary_effects: Vec<TempEffect>,
+    /// Status conditions such as poisoned or stunned, in effect until removed.
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    /// Spent per-rest uses and running cooldowns, by ability name.
+    #[serde(default)]
+    pub ability_runtime: BTreeMap<String, AbilityRuntime>,
     #[serde(skip)]
     pub active_effects: Vec<Effect>,
 }
@@ -103,11 +176,13 @@ impl Character {
         let character = Self {
             id: Uuid::new_v4(),
             name,
+            accent_color: None,
             race: Race::default(),
             class: Class::default(),
             level: 0,
             experience: 0,
             hp_spent: 0,
+            temp_hp: 0,
             mana_spent: 0,
             action_points: Resource::new(Race::default().base_action_points()),
             stats: Characteristics::default(),
@@ -121,7 +196,16 @@ impl Character {
             equipped_weapons: Vec::new(),
             equipped_equipment: BTreeMap::new(),
             inventory: Vec::new(),
+            item_counts: BTreeMap::new(),
             wallet: Wallet::default(),
+            loadouts: BTreeMap::new(),
+            hotbar: Vec::new(),
+            notes: String::new(),
+            rests_used_today: 0,
+            rng_seed: None,
+            temporary_effects: Vec::new(),
+            conditions: Vec::new(),
+            ability_runtime: BTreeMap::new(),
             active_effects: Vec::new(),
         };
         // Effects will be calculated after traits are assigned
@@ -139,6 +223,31 @@ impl Character {
         }
     }
 
+    /// Saves what is currently equipped as the loadout `name`, replacing any
+    /// loadout with that name.
+    pub fn save_loadout(&mut self, name: String) {
+        let loadout = Loadout::capture(&self.equipped_weapons, &self.equipped_equipment);
+        self.loadouts.insert(name, loadout);
+    }
+
+    /// Equips the loadout `name`; see [`Loadout::apply`]. Returns `None` if there
+    /// is no such loadout, otherwise warnings about skipped items.
+    pub fn apply_loadout(
+        &mut self,
+        name: &str,
+        weapons: &WeaponRegistry,
+        equipment: &EquipmentRegistry,
+    ) -> Option<Vec<String>> {
+        let loadout = self.loadouts.get(name)?;
+        Some(loadout.apply(
+            &mut self.equipped_weapons,
+            &mut self.equipped_equipment,
+            &mut self.inventory,
+            weapons,
+            equipment,
+        ))
+    }
+
     /// Removes an item by name from the given slot.
     pub fn unequip(&mut self, slot: EquipmentSlot, name: &str) {
         if let Some(items) = self.equipped_equipment.get_mut(&slot) {
@@ -149,57 +258,184 @@ impl Character {
         }
     }
 
-    /// Recalculates active effects from all sources (race, traits, weapons, equipment).
+    /// Recalculates active effects from all sources (race, traits, weapons, equipment,
+    /// conditions), plus whatever the campaign's effect modifiers add on top and the
+    /// temporary effects that have turns left.
     pub fn recalculate_effects(
         &mut self,
         trait_registry: &TraitRegistry,
         weapon_registry: &WeaponRegistry,
         equipment_registry: &EquipmentRegistry,
+        modifiers: &[EffectModifier],
     ) {
         self.active_effects = collect_source_effects(
             self.race,
             &self.traits,
             &self.equipped_weapons,
             &self.equipped_equipment,
+            &self.conditions,
             trait_registry,
             weapon_registry,
             equipment_registry,
+            modifiers,
         );
+        self.active_effects
+            .extend(active_temp_effects(&self.temporary_effects));
     }
 
-    /// Aggregates effect values of a specific kind, summing magnitudes per key.
-    fn aggregate<K>(&self, extract: impl Fn(&Effect) -> Option<(K, i32)>) -> BTreeMap<K, i32>
+    /// Adds or removes free skill ranks to match `Effect::FreeSkillRank` in the
+    /// active effects. Call after `recalculate_effects`. Returns true if anything changed.
+    pub fn sync_free_skill_ranks(&mut self) -> bool {
+        skill::sync_free_ranks(&mut self.skills, &self.active_effects)
+    }
+
+    /// Pretty-printed JSON of the stored form (active effects are not serialized).
+    pub fn to_pretty_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Every registry entry the character references by name: traits, abilities,
+    /// equipped gear and inventory contents.
+    pub fn references(&self) -> BTreeSet<(ReferenceKind, String)> {
+        let mut refs: BTreeSet<(ReferenceKind, String)> = BTreeSet::new();
+        let tagged = |kind: ReferenceKind| move |name: &String| (kind, name.clone());
+        refs.extend(self.traits.iter().map(tagged(ReferenceKind::Trait)));
+        refs.extend(self.abilities.iter().map(tagged(ReferenceKind::Ability)));
+        refs.extend(
+            self.equipped_weapons
+                .iter()
+                .map(tagged(ReferenceKind::Weapon)),
+        );
+        refs.extend(
+            self.equipped_equipment
+                .values()
+                .flatten()
+                .map(tagged(ReferenceKind::Equipment)),
+        );
+        refs.extend(self.inventory.iter().map(|item| match item {
+            InventoryItem::Weapon(name) => (ReferenceKind::Weapon, name.clone()),
+            InventoryItem::Equipment(name) => (ReferenceKind::Equipment, name.clone()),
+            InventoryItem::Item(name) => (ReferenceKind::Item, name.clone()),
+        }));
+        refs
+    }
+
+    /// Effective characteristic level (base + active effect bonuses), minimum 0.
+    pub fn effective_level(&self, kind: CharacteristicKind) -> u32 {
+        let bonus: i32 = self
+            .active_effects
+            .iter()
+            .filter_map(|e| match e {
+                Effect::Characteristic(k, v) if *k == kind => Some(*v),
+                _ => None,
+            })
+            .sum();
+        (self.stats.get_level(kind) as i32 + bonus).max(0) as u32
+    }
+
+    /// Max HP: effective Endurance * 3 + 3, plus `Effect::MaxHp` bonuses.
+    pub fn max_hp(&self) -> u32 {
+        max_hp(
+            self.effective_level(CharacteristicKind::Endurance),
+            &self.active_effects,
+        )
+    }
+
+    /// Applies damage or healing. Damage drains `temp_hp` first; healing never restores it.
+    pub fn apply_hp_delta(&mut self, delta: HpDelta) {
+        let max = self.max_hp();
+        let current = max.saturating_sub(self.hp_spent);
+        let (current, temp) = delta.apply(current, self.temp_hp, max);
+        self.hp_spent = max - current;
+        self.temp_hp = temp;
+    }
+
+    /// Grants temporary HP. Grants don't stack: the higher value is kept.
+    pub fn grant_temp_hp(&mut self, amount: u32) {
+        self.temp_hp = self.temp_hp.max(amount);
+    }
+
+    /// Max Mana under the campaign's base-mana characteristic, plus `Effect::Mana`
+    /// and `Effect::MaxMana` bonuses.
+    pub fn max_mana(&self, campaign: &CampaignRules) -> u32 {
+        campaign.max_mana(&self.active_effects, |kind| self.effective_level(kind))
+    }
+
+    /// Max action points: race base + `Effect::ActionPoints` bonuses.
+    pub fn max_action_points(&self) -> u32 {
+        let bonus: i32 = self
+            .active_effects
+            .iter()
+            .filter_map(|e| match e {
+                Effect::ActionPoints(v) => Some(*v),
+                _ => None,
+            })
+            .sum();
+        (self.race.base_action_points() as i32 + bonus).max(0) as u32
+    }
+
+    /// Brings stored resources in line with the current effects: recomputes the
+    /// action-point max (keeping points spent) and clamps spent HP/Mana to their maxes.
+    /// Call after `recalculate_effects`. Returns true if anything changed.
+    pub fn repair_resources(&mut self, campaign: &CampaignRules) -> bool {
+        let before = (self.action_points, self.hp_spent, self.mana_spent);
+
+        let max_ap = self.max_action_points();
+        if self.action_points.max != max_ap {
+            let spent = self
+                .action_points
+                .max
+                .saturating_sub(self.action_points.current);
+            self.action_points.max = max_ap;
+            self.action_points.current = max_ap.saturating_sub(spent);
+        }
+        self.action_points.current = self.action_points.current.min(max_ap);
+        self.hp_spent = self.hp_spent.min(self.max_hp());
+        self.mana_spent = self.mana_spent.min(self.max_mana(campaign));
+
+        before != (self.action_points, self.hp_spent, self.mana_spent)
+    }
+
+    /// Aggregates effect values of a specific kind, summing magnitudes per key
+    /// and clipping each total to `cap`.
+    fn aggregate<K>(
+        &self,
+        cap: Option<i32>,
+        extract: impl Fn(&Effect) -> Option<(K, i32)>,
+    ) -> BTreeMap<K, Stacked>
     where
         K: Ord + Copy + IntoEnumIterator,
     {
-        let mut result: BTreeMap<K, i32> = K::iter().map(|k| (k, 0)).collect();
+        let mut totals: BTreeMap<K, i32> = K::iter().map(|k| (k, 0)).collect();
         for effect in &self.active_effects {
             if let Some((key, magnitude)) = extract(effect) {
-                *result.entry(key).or_insert(0) += magnitude;
+                *totals.entry(key).or_insert(0) += magnitude;
             }
         }
-        result
+        totals
+            .into_iter()
+            .map(|(key, total)| (key, Stacked::new(total, cap)))
+            .collect()
     }
 
     /// Aggregates resist values from active effects, summing magnitudes per resist type.
-    pub fn get_resists(&self) -> BTreeMap<Resist, i32> {
-        self.aggregate(|e| match e {
+    pub fn get_resists(&self, stacking: &StackingRules) -> BTreeMap<Resist, Stacked> {
+        self.aggregate(stacking.resist, |e| match e {
             Effect::Resist(r, m) => Some((*r, *m)),
             _ => None,
         })
     }
 
     /// Aggregates protection values from active effects, summing magnitudes per protection type.
-    pub fn get_protections(&self) -> BTreeMap<Protection, i32> {
-        self.aggregate(|e| match e {
+    pub fn get_protections(&self, stacking: &StackingRules) -> BTreeMap<Protection, Stacked> {
+        self.aggregate(stacking.protection, |e| match e {
             Effect::Protection(p, m) => Some((*p, *m)),
             _ => None,
         })
     }
 
-    /// Calculates initiative as Perception level + sum of Initiative effects.
-    pub fn get_initiative(&self) -> i32 {
-        let perception = self.stats.perception.level as i32;
+    /// Calculates initiative from the campaign's formula + sum of Initiative effects.
+    pub fn get_initiative(&self, campaign: &CampaignRules) -> i32 {
         let bonus: i32 = self
             .active_effects
             .iter()
@@ -208,44 +444,121 @@ impl Character {
                 _ => None,
             })
             .sum();
-        perception + bonus
+        campaign
+            .initiative
+            .initiative(|kind| self.stats.get_level(kind), bonus)
     }
 }
 
-/// Collects effects from all sources: race, size, traits, weapons, equipment.
-pub fn collect_source_effects(
+/// Where a group of active effects comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EffectSource {
+    Race(Race),
+    Size(Size),
+    Trait(String),
+    Weapon(String),
+    Equipment(String),
+    Condition(Condition),
+    /// A campaign effect modifier (house rule).
+    Rule(String),
+    /// Effects added by hand for a number of turns.
+    Temporary,
+}
+
+impl std::fmt::Display for EffectSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Race(race) => write!(f, "Race: {race}"),
+            Self::Size(size) => write!(f, "Size: {size:?}"),
+            Self::Trait(name) => write!(f, "Trait: {name}"),
+            Self::Weapon(name) => write!(f, "Weapon: {name}"),
+            Self::Equipment(name) => write!(f, "Equipment: {name}"),
+            Self::Condition(condition) => write!(f, "Condition: {condition}"),
+            Self::Rule(name) => write!(f, "Rule: {name}"),
+            Self::Temporary => write!(f, "Temporary"),
+        }
+    }
+}
+
+/// Collects effects from all sources (race, size, traits, weapons, equipment,
+/// conditions), grouped by the source that grants them. Sources without effects are skipped.
+/// Effect modifiers are evaluated last, in one pass over those effects.
+#[allow(clippy::too_many_arguments)]
+pub fn collect_attributed_effects(
     race: Race,
     trait_names: &[String],
     weapon_names: &[String],
     equipped_equipment: &BTreeMap<EquipmentSlot, Vec<String>>,
+    conditions: &[Condition],
     trait_registry: &TraitRegistry,
     weapon_registry: &WeaponRegistry,
     equipment_registry: &EquipmentRegistry,
-) -> Vec<Effect> {
-    let mut effects = Vec::new();
-    effects.extend(race.get_effects());
-    effects.extend(race.size().get_effects());
+    modifiers: &[EffectModifier],
+) -> Vec<(EffectSource, Vec<Effect>)> {
+    let mut groups = vec![
+        (EffectSource::Race(race), race.get_effects()),
+        (EffectSource::Size(race.size()), race.size().get_effects()),
+    ];
     for name in trait_names {
         if let Some(ct) = trait_registry.get(name) {
-            effects.extend(ct.effects.iter().cloned());
+            groups.push((EffectSource::Trait(name.clone()), ct.effects.clone()));
         }
     }
     for name in weapon_names {
         if let Some(w) = weapon_registry.get(name) {
-            effects.extend(w.effects.iter().cloned());
+            groups.push((EffectSource::Weapon(name.clone()), w.effects.clone()));
         }
     }
     for names in equipped_equipment.values() {
         for name in names {
             if let Some(eq) = equipment_registry.get(name) {
-                effects.extend(eq.effects.iter().cloned());
+                groups.push((EffectSource::Equipment(name.clone()), eq.effects.clone()));
             }
         }
     }
-    effects
+    for condition in conditions {
+        groups.push((EffectSource::Condition(*condition), condition.get_effects()));
+    }
+    groups.retain(|(_, effects)| !effects.is_empty());
+
+    let collected: Vec<Effect> = groups.iter().flat_map(|(_, e)| e.clone()).collect();
+    for modifier in firing_modifiers(modifiers, &collected) {
+        if !modifier.effects.is_empty() {
+            groups.push((
+                EffectSource::Rule(modifier.name.clone()),
+                modifier.effects.clone(),
+            ));
+        }
+    }
+    groups
 }
 
-/// XP required to advance from `level` to `level + 1`.
-pub fn xp_to_next_level(level: u32) -> u32 {
-    (level + 1) * 10
+/// Collects effects from all sources: race, size, traits, weapons, equipment,
+/// conditions, then the effect modifiers that fire on them.
+#[allow(clippy::too_many_arguments)]
+pub fn collect_source_effects(
+    race: Race,
+    trait_names: &[String],
+    weapon_names: &[String],
+    equipped_equipment: &BTreeMap<EquipmentSlot, Vec<String>>,
+    conditions: &[Condition],
+    trait_registry: &TraitRegistry,
+    weapon_registry: &WeaponRegistry,
+    equipment_registry: &EquipmentRegistry,
+    modifiers: &[EffectModifier],
+) -> Vec<Effect> {
+    collect_attributed_effects(
+        race,
+        trait_names,
+        weapon_names,
+        equipped_equipment,
+        conditions,
+        trait_registry,
+        weapon_registry,
+        equipment_registry,
+        modifiers,
+    )
+    .into_iter()
+    .flat_map(|(_, effects)| effects)
+    .collect()
 }
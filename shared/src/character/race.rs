@@ -4,7 +4,20 @@ use strum::{Display, EnumIter};
 use super::effect::{Effect, GetEffects, Protection, Resist};
 
 /// Character race
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Display, EnumIter, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+    Display,
+    EnumIter,
+    Serialize,
+    Deserialize,
+)]
 pub enum Race {
     #[default]
     #[strum(serialize = "Half-Elf")]
@@ -0,0 +1,199 @@
+//! Sanity checks of stored point pools against what the rules allow, for
+//! characters saved under older rules or edited by hand.
+
+use strum::Display;
+
+use super::effect::{Effect, OnLvlUp};
+use super::Character;
+use crate::rules::Ruleset;
+
+/// Point pool a reconciliation issue is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display)]
+pub enum PointPool {
+    #[strum(serialize = "characteristic points")]
+    Characteristic,
+    #[strum(serialize = "skill points")]
+    Skill,
+    #[strum(serialize = "ability points")]
+    Ability,
+    #[strum(serialize = "trait points")]
+    Trait,
+}
+
+/// A pool holding more unspent points than the character could have earned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconcileIssue {
+    pub pool: PointPool,
+    pub message: String,
+    /// Unspent points stored in the file.
+    pub stored: u32,
+    /// Proposed unspent points: the most the rules allow.
+    pub proposed: u32,
+}
+
+/// Result of [`Character::reconcile`]; empty when the character looks consistent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reconciliation {
+    pub issues: Vec<ReconcileIssue>,
+}
+
+impl Reconciliation {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Applies every proposed correction.
+    pub fn apply(&self, character: &mut Character) {
+        for issue in &self.issues {
+            *pool_mut(character, issue.pool) = issue.proposed;
+        }
+    }
+}
+
+fn pool_mut(character: &mut Character, pool: PointPool) -> &mut u32 {
+    match pool {
+        PointPool::Characteristic => &mut character.characteristic_points,
+        PointPool::Skill => &mut character.skill_points,
+        PointPool::Ability => &mut character.ability_points,
+        PointPool::Trait => &mut character.trait_points,
+    }
+}
+
+/// Points per level granted by the `OnLvlUp` effects matching `pick`.
+fn per_level(effects: &[Effect], pick: impl Fn(&OnLvlUp) -> Option<i32>) -> u32 {
+    let total: i32 = effects
+        .iter()
+        .filter_map(|e| match e {
+            Effect::OnLvlUp(on_lvl_up) => pick(on_lvl_up),
+            _ => None,
+        })
+        .sum();
+    total.max(0) as u32
+}
+
+impl Character {
+    /// Compares the stored point pools with the most the rules could have
+    /// granted by the current level. Budgets are generous upper bounds
+    /// (current characteristics and effects are assumed to have applied from
+    /// the start), so only totals that are impossible are reported.
+    /// Requires `active_effects` to be up to date.
+    pub fn reconcile(&self, rules: &Ruleset) -> Reconciliation {
+        let level = self.level;
+        let effects = &self.active_effects;
+        let mut issues = Vec::new();
+        let mut check = |pool: PointPool, stored: u32, spent: u32, budget: u32| {
+            if spent + stored > budget {
+                issues.push(ReconcileIssue {
+                    pool,
+                    message: format!(
+                        "{stored} unspent {pool} plus {spent} spent exceeds the {budget} \
+                         level {level} allows"
+                    ),
+                    stored,
+                    proposed: budget.saturating_sub(spent),
+                });
+            }
+        };
+
+        // Creation points are spent before the character is saved, so only
+        // level-ups can leave characteristic points unspent.
+        let characteristic_budget = level / 5 * 2
+            + level
+                * per_level(effects, |e| match e {
+                    OnLvlUp::AddCharacteristicPoints(v) => Some(*v),
+                    _ => None,
+                });
+        check(
+            PointPool::Characteristic,
+            self.characteristic_points,
+            0,
+            characteristic_budget,
+        );
+
+        // Every skill rank bought costs its level. Intellect raises after
+        // creation grant a point each, covered by counting the creation bonus
+        // on the current Intellect.
+        let spent_on_skills: u32 = self
+            .skills
+            .iter()
+            .map(|s| s.level.saturating_sub(s.free_ranks))
+            .map(|bought| bought * (bought + 1) / 2)
+            .sum();
+        let intellect = self.stats.intellect.level;
+        let per_intellect = rules.creation.skill_points_per_intellect.max(1);
+        let class_rules = rules.classes.get(&self.class);
+        let skill_budget = rules.creation.skill_points
+            + per_intellect * intellect
+            + class_rules.map_or(0, |r| {
+                r.skill_points_for_levels(level, |k| self.effective_level(k))
+            })
+            + level
+                * per_level(effects, |e| match e {
+                    OnLvlUp::AddSkillPoints(v) => Some(*v),
+                    _ => None,
+                });
+        check(
+            PointPool::Skill,
+            self.skill_points,
+            spent_on_skills,
+            skill_budget,
+        );
+
+        let ability_budget = class_rules.map_or(0, |r| r.ability_points.points_between(0, level))
+            + level
+                * per_level(effects, |e| match e {
+                    OnLvlUp::AddAbilityPoints(v) => Some(*v),
+                    _ => None,
+                });
+        check(PointPool::Ability, self.ability_points, 0, ability_budget);
+
+        check(PointPool::Trait, self.trait_points, 0, level / 3);
+
+        Reconciliation { issues }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::CharacterSkill;
+
+    #[test]
+    fn test_impossible_point_totals_are_reported() {
+        let mut character = Character::new("Drifter".to_string());
+        character.level = 4;
+        character.stats.intellect.level = 2;
+        // Budget: 10 + 3 * 2 = 16 skill points; ranks 3 and 2 cost 6 + 3
+        character.skills = vec![
+            CharacterSkill {
+                name: "Arcana".to_string(),
+                level: 3,
+                free_ranks: 0,
+            },
+            CharacterSkill {
+                name: "History".to_string(),
+                level: 2,
+                free_ranks: 0,
+            },
+        ];
+        character.skill_points = 7;
+        let rules = Ruleset::default();
+        assert!(character.reconcile(&rules).is_clean());
+
+        // Edited JSON: more skill points than level 4 allows, and trait
+        // points without ever reaching level 3 twice
+        character.skill_points = 20;
+        character.trait_points = 2;
+        let reconciliation = character.reconcile(&rules);
+        let pools: Vec<(PointPool, u32)> = reconciliation
+            .issues
+            .iter()
+            .map(|i| (i.pool, i.proposed))
+            .collect();
+        assert_eq!(pools, vec![(PointPool::Skill, 7), (PointPool::Trait, 1)]);
+
+        reconciliation.apply(&mut character);
+        assert_eq!((character.skill_points, character.trait_points), (7, 1));
+        assert!(character.reconcile(&rules).is_clean());
+    }
+}
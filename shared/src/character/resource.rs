@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::effect::Effect;
+
 /// A consumable resource with current and maximum values
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Resource {
@@ -33,6 +35,36 @@ impl Resource {
     }
 }
 
+/// A change to hit points: negative values are damage, positive values healing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HpDelta(pub i32);
+
+impl HpDelta {
+    /// Applies the change to `current` and `temp` HP, returning the new pair.
+    /// Damage drains temporary HP before current HP; healing only restores
+    /// current HP, up to `max`.
+    pub fn apply(self, current: u32, temp: u32, max: u32) -> (u32, u32) {
+        if self.0 >= 0 {
+            return (current.saturating_add(self.0 as u32).min(max), temp);
+        }
+        let damage = self.0.unsigned_abs();
+        let absorbed = damage.min(temp);
+        (current.saturating_sub(damage - absorbed), temp - absorbed)
+    }
+}
+
+/// Max HP: `endurance` * 3 + 3, plus every `Effect::MaxHp` bonus. Never below 1.
+pub fn max_hp(endurance: u32, effects: &[Effect]) -> u32 {
+    let bonus: i32 = effects
+        .iter()
+        .filter_map(|e| match e {
+            Effect::MaxHp(v) => Some(*v),
+            _ => None,
+        })
+        .sum();
+    ((endurance * 3 + 3) as i32 + bonus).max(1) as u32
+}
+
 impl Default for Resource {
     fn default() -> Self {
         Self::new(10)
@@ -64,6 +96,16 @@ mod tests {
         assert_eq!(resource.current, 10);
     }
 
+    #[test]
+    fn test_max_hp_effects_add_flat_bonus_with_floor() {
+        let base = max_hp(2, &[]);
+        assert_eq!(base, 9);
+        assert_eq!(max_hp(2, &[Effect::MaxHp(10)]), base + 10);
+        assert_eq!(max_hp(2, &[Effect::MaxHp(10), Effect::MaxHp(-50)]), 1);
+        // Max mana bonuses don't touch HP
+        assert_eq!(max_hp(2, &[Effect::MaxMana(5)]), base);
+    }
+
     #[test]
     fn test_resource_spend_success() {
         let mut resource = Resource::new(10);
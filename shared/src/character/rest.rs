@@ -0,0 +1,116 @@
+//! Short and long rests, and the per-day limit some campaigns put on long rests.
+
+use serde::{Deserialize, Serialize};
+
+use super::{clear_ability_cooldowns, reset_ability_uses, Character};
+use crate::rules::CampaignRules;
+
+/// How long the character rests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RestKind {
+    /// Restores action points and half of max HP; not limited per day.
+    Short,
+    /// Restores HP, mana, action points and per-rest ability uses.
+    Long,
+}
+
+/// HP a short rest restores: half of `max_hp`, rounded up.
+pub fn short_rest_heal(max_hp: u32) -> u32 {
+    max_hp.div_ceil(2)
+}
+
+impl Character {
+    /// Takes a rest of the given kind; see [`Self::short_rest`] and
+    /// [`Self::long_rest`]. Returns false when nothing happened.
+    pub fn rest(&mut self, kind: RestKind, rules: &CampaignRules) -> bool {
+        match kind {
+            RestKind::Short => {
+                self.short_rest();
+                true
+            }
+            RestKind::Long => self.long_rest(rules),
+        }
+    }
+
+    /// Restores action points and heals [`short_rest_heal`] HP. Ends ability
+    /// cooldowns, but per-rest uses stay spent.
+    pub fn short_rest(&mut self) {
+        let heal = short_rest_heal(self.max_hp());
+        self.hp_spent = self.hp_spent.saturating_sub(heal);
+        self.action_points.restore_full();
+        clear_ability_cooldowns(&mut self.ability_runtime);
+    }
+
+    /// Whether the campaign's daily limit leaves a long rest for today.
+    pub fn can_long_rest(&self, rules: &CampaignRules) -> bool {
+        rules.long_rest_available(self.rests_used_today)
+    }
+
+    /// Restores HP, mana, action points and per-rest ability uses, ends ability
+    /// cooldowns and counts the rest against today's limit. Returns false,
+    /// changing nothing, when no rest is left.
+    pub fn long_rest(&mut self, rules: &CampaignRules) -> bool {
+        if !self.can_long_rest(rules) {
+            return false;
+        }
+        self.hp_spent = 0;
+        self.mana_spent = 0;
+        self.action_points.restore_full();
+        reset_ability_uses(&mut self.ability_runtime);
+        clear_ability_cooldowns(&mut self.ability_runtime);
+        self.rests_used_today += 1;
+        true
+    }
+
+    /// Starts a new in-game day, making every long rest available again.
+    /// Returns true if the character had rested today.
+    pub fn new_day(&mut self) -> bool {
+        std::mem::take(&mut self.rests_used_today) > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_daily_long_rest_limit_until_new_day() {
+        let rules = CampaignRules::load_from_str(r#"{ "long_rests_per_day": 1 }"#).unwrap();
+        let mut character = Character::new("Weary".to_string());
+        character.hp_spent = 4;
+
+        assert!(character.long_rest(&rules));
+        assert_eq!((character.hp_spent, character.rests_used_today), (0, 1));
+
+        // The second rest of the day is refused and changes nothing
+        character.hp_spent = 2;
+        assert!(!character.can_long_rest(&rules));
+        assert!(!character.long_rest(&rules));
+        assert_eq!((character.hp_spent, character.rests_used_today), (2, 1));
+
+        assert!(character.new_day());
+        assert!(character.long_rest(&rules));
+        assert_eq!(character.hp_spent, 0);
+
+        // Campaigns without a limit never block rests
+        let unlimited = CampaignRules::default();
+        assert!(character.long_rest(&unlimited));
+        assert_eq!(character.rests_used_today, 2);
+    }
+
+    #[test]
+    fn test_short_rest_heals_half_and_restores_action_points() {
+        let rules = CampaignRules::load_from_str(r#"{ "long_rests_per_day": 0 }"#).unwrap();
+        let mut character = Character::new("Winded".to_string());
+        let max_hp = character.max_hp();
+        character.hp_spent = max_hp;
+        character.action_points.current = 0;
+
+        assert!(character.rest(RestKind::Short, &rules));
+        assert_eq!(character.hp_spent, max_hp - short_rest_heal(max_hp));
+        assert_eq!(character.action_points.current, character.action_points.max);
+        // Short rests don't count against the long rest limit
+        assert_eq!(character.rests_used_today, 0);
+        assert!(!character.rest(RestKind::Long, &rules));
+    }
+}
@@ -3,6 +3,7 @@ use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 
 use super::class::Class;
+use super::effect::Effect;
 use super::CharacteristicKind;
 
 /// Skill definition - only dependency on characteristic
@@ -16,16 +17,24 @@ pub struct Skill {
 pub struct CharacterSkill {
     pub name: String,
     pub level: u32,
+    /// Part of `level` granted by `Effect::FreeSkillRank` sources, not bought with points.
+    #[serde(default)]
+    pub free_ranks: u32,
 }
 
 impl CharacterSkill {
     pub fn new(name: String) -> Self {
-        Self { name, level: 1 }
+        Self {
+            name,
+            level: 1,
+            free_ranks: 0,
+        }
     }
 
     /// Attempt to raise skill level
     /// - available_points: available skill_points of the character
     /// - max_level: level of the dependent characteristic (skill cannot be higher)
+    ///
     /// Returns the number of points to spend, or 0 if cannot raise
     pub fn up(&mut self, available_points: u32, max_level: u32) -> u32 {
         // Check that we won't exceed the characteristic level
@@ -45,6 +54,66 @@ impl CharacterSkill {
     }
 }
 
+/// Brings free skill ranks in line with `effects`: adds ranks for newly gained
+/// `Effect::FreeSkillRank` sources and removes ranks whose source is gone.
+/// Ranks bought with skill points are never touched; a skill entry that only
+/// existed for its free ranks is dropped. Returns true if anything changed.
+pub fn sync_free_ranks(skills: &mut Vec<CharacterSkill>, effects: &[Effect]) -> bool {
+    let mut granted: BTreeMap<&str, u32> = BTreeMap::new();
+    for effect in effects {
+        if let Effect::FreeSkillRank { skill, ranks } = effect {
+            *granted.entry(skill.as_str()).or_default() += ranks;
+        }
+    }
+
+    let mut changed = false;
+    skills.retain_mut(|skill| {
+        let target = granted.remove(skill.name.as_str()).unwrap_or(0);
+        if skill.free_ranks == target {
+            return true;
+        }
+        changed = true;
+        let bought = skill.level.saturating_sub(skill.free_ranks);
+        skill.level = bought + target;
+        skill.free_ranks = target;
+        skill.level > 0
+    });
+    for (name, ranks) in granted {
+        if ranks > 0 {
+            skills.push(CharacterSkill {
+                name: name.to_string(),
+                level: ranks,
+                free_ranks: ranks,
+            });
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Removes the bought ranks of every skill `keep` rejects, e.g. skills the
+/// character's new class doesn't have, and returns their cost to
+/// `skill_points`. Ranks granted by effects stay. Returns the names of the
+/// skills that lost ranks.
+pub fn drop_foreign_skills(
+    skills: &mut Vec<CharacterSkill>,
+    skill_points: &mut u32,
+    keep: impl Fn(&str) -> bool,
+) -> Vec<String> {
+    let mut dropped = Vec::new();
+    skills.retain_mut(|skill| {
+        let bought = skill.level.saturating_sub(skill.free_ranks);
+        if keep(&skill.name) || bought == 0 {
+            return true;
+        }
+        *skill_points += bought * (bought + 1) / 2;
+        skill.level = skill.free_ranks;
+        dropped.push(skill.name.clone());
+        skill.level > 0
+    });
+    dropped
+}
+
 /// Registry of skills by class
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct SkillRegistry {
@@ -82,6 +151,40 @@ impl SkillRegistry {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_drop_foreign_skills_refunds_bought_ranks() {
+        let mut skills = vec![
+            CharacterSkill {
+                name: "Eloquence".to_string(),
+                level: 3,
+                free_ranks: 0,
+            },
+            CharacterSkill {
+                name: "Stealth".to_string(),
+                level: 2,
+                free_ranks: 0,
+            },
+            CharacterSkill {
+                name: "Alchemy".to_string(),
+                level: 3,
+                free_ranks: 1,
+            },
+        ];
+        let mut points = 0;
+
+        let dropped = drop_foreign_skills(&mut skills, &mut points, |name| name == "Stealth");
+
+        assert_eq!(
+            dropped,
+            vec!["Eloquence".to_string(), "Alchemy".to_string()]
+        );
+        assert_eq!(points, 6 + 3);
+        assert_eq!(skills.len(), 2);
+        assert_eq!(skills[0].name, "Stealth");
+        assert_eq!(skills[1].level, 1);
+        assert_eq!(skills[1].free_ranks, 1);
+    }
+
     #[test]
     fn test_skill_registry_load() {
         let json = r#"{
@@ -0,0 +1,78 @@
+//! Ad-hoc effects that last a number of turns, such as a combat stance.
+
+use serde::{Deserialize, Serialize};
+
+use super::{Character, Effect};
+
+/// An effect added by hand that wears off after `remaining_turns` turns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TempEffect {
+    pub effect: Effect,
+    pub remaining_turns: u32,
+}
+
+/// Effects of the temporary effects that have turns left.
+pub fn active_temp_effects(temp: &[TempEffect]) -> impl Iterator<Item = Effect> + '_ {
+    temp.iter()
+        .filter(|t| t.remaining_turns > 0)
+        .map(|t| t.effect.clone())
+}
+
+/// Counts one turn off every temporary effect and drops the ones that ran
+/// out. Returns true if any effect expired.
+pub fn tick_temp_effects(temp: &mut Vec<TempEffect>) -> bool {
+    let before = temp.len();
+    for t in temp.iter_mut() {
+        t.remaining_turns = t.remaining_turns.saturating_sub(1);
+    }
+    temp.retain(|t| t.remaining_turns > 0);
+    temp.len() != before
+}
+
+impl Character {
+    /// Ends a turn for the temporary effects; see [`tick_temp_effects`].
+    /// Call `recalculate_effects` afterwards when this returns true.
+    pub fn tick_effects(&mut self) -> bool {
+        tick_temp_effects(&mut self.temporary_effects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{EquipmentRegistry, TraitRegistry, WeaponRegistry};
+
+    #[test]
+    fn test_temp_effect_applies_until_it_runs_out() {
+        let mut character = Character::new("Duelist".to_string());
+        character.temporary_effects.push(TempEffect {
+            effect: Effect::Initiative(2),
+            remaining_turns: 2,
+        });
+        let recalculate = |c: &mut Character| {
+            c.recalculate_effects(
+                &TraitRegistry::default(),
+                &WeaponRegistry::default(),
+                &EquipmentRegistry::default(),
+                &[],
+            )
+        };
+        let stance = |c: &Character| c.active_effects.contains(&Effect::Initiative(2));
+
+        recalculate(&mut character);
+        assert!(stance(&character));
+        assert!(!character.tick_effects());
+        recalculate(&mut character);
+        assert!(stance(&character));
+        assert!(character.tick_effects());
+        recalculate(&mut character);
+        assert!(!stance(&character));
+        assert!(character.temporary_effects.is_empty());
+
+        // Characters saved before temporary effects existed still load
+        let mut json: serde_json::Value = serde_json::to_value(&character).unwrap();
+        json.as_object_mut().unwrap().remove("temporary_effects");
+        let loaded: Character = serde_json::from_value(json).unwrap();
+        assert!(loaded.temporary_effects.is_empty());
+    }
+}
@@ -1,29 +1,49 @@
 use serde::{Deserialize, Serialize};
 
-/// Character's currency, stored as a single total value in base (copper) units.
+use crate::rules::CurrencyScheme;
+
+/// Character's currency, stored as a single total value in base units.
 ///
-/// Conversion rates: 1 gold = 1000 copper, 1 silver = 10 copper.
-/// Display breakdown: gold = total / 1000, silver = (total % 1000) / 10, copper = total % 10.
+/// Denominations come from the campaign's [`CurrencyScheme`]; the gold/silver/copper
+/// helpers use the default scheme (1 gold = 1000 copper, 1 silver = 10 copper).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct Wallet(u64);
 
 impl Wallet {
-    /// Creates a wallet from individual currency amounts.
+    /// Creates a wallet from gold/silver/copper amounts under the default scheme.
     pub fn new(gold: u32, silver: u32, copper: u32) -> Self {
-        Self(gold as u64 * 1000 + silver as u64 * 10 + copper as u64)
+        Self::from_parts(
+            &CurrencyScheme::default(),
+            &[gold as u64, silver as u64, copper as u64],
+        )
+    }
+
+    /// Creates a wallet holding `total` base units.
+    pub fn from_total(total: u64) -> Self {
+        Self(total)
+    }
+
+    /// Creates a wallet from per-denomination amounts, highest first.
+    pub fn from_parts(scheme: &CurrencyScheme, amounts: &[u64]) -> Self {
+        Self(scheme.combine(amounts))
+    }
+
+    /// Per-denomination breakdown, highest first.
+    pub fn parts(&self, scheme: &CurrencyScheme) -> Vec<u64> {
+        scheme.split(self.0)
     }
 
     pub fn gold(&self) -> u32 {
-        (self.0 / 1000) as u32
+        self.parts(&CurrencyScheme::default())[0] as u32
     }
 
     pub fn silver(&self) -> u32 {
-        ((self.0 % 1000) / 10) as u32
+        self.parts(&CurrencyScheme::default())[1] as u32
     }
 
     pub fn copper(&self) -> u32 {
-        (self.0 % 10) as u32
+        self.parts(&CurrencyScheme::default())[2] as u32
     }
 
     /// Returns the total value in base (copper) units.
@@ -31,9 +51,34 @@ impl Wallet {
         self.0
     }
 
-    /// Adds a signed delta in base currency units, clamping at zero.
+    /// Adds a signed delta in base currency units, clamping at zero and at
+    /// the largest representable total.
     pub fn add(&mut self, delta: i64) {
-        self.0 = (self.0 as i64 + delta).max(0) as u64;
+        self.0 = self.0.saturating_add_signed(delta);
+    }
+
+    /// Adds a signed number of coins of the denomination at `index`, highest
+    /// first. The total is kept in base units, so coins carry into higher
+    /// denominations (and borrow from them) on their own.
+    pub fn add_coins(&mut self, scheme: &CurrencyScheme, index: usize, count: i64) {
+        let unit = scheme.unit_values().get(index).copied().unwrap_or(1);
+        let unit = i64::try_from(unit).unwrap_or(i64::MAX);
+        self.add(count.saturating_mul(unit));
+    }
+
+    /// Adds signed gold coins under the default scheme.
+    pub fn add_gold(&mut self, count: i64) {
+        self.add_coins(&CurrencyScheme::default(), 0, count);
+    }
+
+    /// Adds signed silver coins under the default scheme.
+    pub fn add_silver(&mut self, count: i64) {
+        self.add_coins(&CurrencyScheme::default(), 1, count);
+    }
+
+    /// Adds signed copper coins under the default scheme.
+    pub fn add_copper(&mut self, count: i64) {
+        self.add_coins(&CurrencyScheme::default(), 2, count);
     }
 }
 
@@ -57,6 +102,33 @@ mod tests {
         assert_eq!(w.total(), 0);
     }
 
+    #[test]
+    fn test_coins_carry_between_denominations() {
+        // Default scheme: 10 copper to the silver, 100 silver to the gold
+        let mut w = Wallet::new(0, 99, 9);
+        w.add_copper(1);
+        assert_eq!((w.gold(), w.silver(), w.copper()), (1, 0, 0));
+        w.add_silver(-1);
+        assert_eq!((w.gold(), w.silver(), w.copper()), (0, 99, 0));
+        w.add_gold(2);
+        assert_eq!((w.gold(), w.silver(), w.copper()), (2, 99, 0));
+    }
+
+    #[test]
+    fn test_coins_clamp_at_zero_and_overflow() {
+        let mut w = Wallet::new(1, 0, 0);
+        w.add_gold(-5);
+        assert_eq!(w.total(), 0);
+
+        let mut rich = Wallet::from_total(u64::MAX - 5);
+        rich.add_copper(10);
+        assert_eq!(rich.total(), u64::MAX);
+        rich.add_gold(i64::MAX);
+        assert_eq!(rich.total(), u64::MAX);
+        rich.add_gold(-1);
+        assert_eq!(rich.total(), u64::MAX - 1000);
+    }
+
     #[test]
     fn test_wallet_default_is_zero() {
         let w = Wallet::default();
@@ -3,7 +3,8 @@ use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter};
 
-use super::effect::Effect;
+use super::effect::{Effect, Resist};
+use super::wallet::Wallet;
 
 /// Ranged weapon subtypes.
 #[derive(
@@ -99,6 +100,15 @@ pub struct Weapon {
     /// Optional extra condition or note (free-form text).
     #[serde(default)]
     pub condition: Option<String>,
+    /// Full price; selling credits the campaign's resale fraction of it.
+    #[serde(default)]
+    pub value: Option<Wallet>,
+    /// Carry weight; missing weights count as zero in inventory totals.
+    #[serde(default)]
+    pub weight: Option<u32>,
+    /// Element of the weapon's damage, checked against the target's resist.
+    #[serde(default)]
+    pub damage_type: Option<Resist>,
 }
 
 /// Registry of all weapons, keyed by name.
@@ -0,0 +1,401 @@
+//! Dice rolling. The caller supplies the randomness, so rolls are reproducible
+//! in tests and `shared` needs no RNG dependency.
+
+use std::ops::RangeInclusive;
+
+/// `count` dice with `sides` faces each, of which the `drop_lowest` lowest
+/// are discarded before summing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dice {
+    pub count: u32,
+    pub sides: u32,
+    pub drop_lowest: u32,
+}
+
+/// Result of rolling `Dice`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceRoll {
+    /// Every die rolled, highest first; the last `dropped` ones are not counted.
+    pub faces: Vec<u32>,
+    pub dropped: usize,
+    pub total: u32,
+}
+
+impl Dice {
+    /// 4d6, dropping the lowest die.
+    pub const STAT: Self = Self {
+        count: 4,
+        sides: 6,
+        drop_lowest: 1,
+    };
+
+    /// A single d20.
+    pub const D20: Self = Self {
+        count: 1,
+        sides: 20,
+        drop_lowest: 0,
+    };
+
+    fn kept(&self) -> u32 {
+        self.count.saturating_sub(self.drop_lowest)
+    }
+
+    /// Lowest possible total (every kept die shows 1).
+    pub fn min_total(&self) -> u32 {
+        self.kept()
+    }
+
+    /// Highest possible total (every kept die shows its top face).
+    pub fn max_total(&self) -> u32 {
+        self.kept() * self.sides
+    }
+
+    /// Rolls the dice. `rng` returns uniformly random words.
+    pub fn roll(&self, rng: &mut impl FnMut() -> u32) -> DiceRoll {
+        let sides = self.sides.max(1);
+        let mut faces: Vec<u32> = (0..self.count).map(|_| rng() % sides + 1).collect();
+        faces.sort_unstable_by(|a, b| b.cmp(a));
+        let kept = self.kept() as usize;
+        DiceRoll {
+            total: faces[..kept].iter().sum(),
+            dropped: faces.len() - kept,
+            faces,
+        }
+    }
+
+    /// Rolls the dice and maps the total linearly onto `levels`, so the lowest
+    /// possible total gives the start of the range and the highest its end.
+    pub fn roll_level(&self, rng: &mut impl FnMut() -> u32, levels: RangeInclusive<u32>) -> u32 {
+        let (low, high) = (*levels.start(), *levels.end().max(levels.start()));
+        let total = self.roll(rng).total;
+        let span = self.max_total() - self.min_total();
+        if span == 0 {
+            return low;
+        }
+        let offset = total - self.min_total();
+        // Rounded to the nearest level
+        low + ((offset * (high - low) * 2 + span) / (span * 2))
+    }
+}
+
+/// Deterministic word source (SplitMix64) for characters with an
+/// `rng_seed`, so a GM can replay the same sequence of rolls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeededRng {
+    seed: u64,
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    /// The seed this sequence started from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        ((z ^ (z >> 31)) >> 32) as u32
+    }
+}
+
+/// A d20 check: the natural roll plus a flat modifier (e.g. a skill level).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Check {
+    pub natural: u32,
+    pub modifier: i32,
+}
+
+impl Check {
+    pub fn roll(modifier: i32, rng: &mut impl FnMut() -> u32) -> Self {
+        Self {
+            natural: Dice::D20.roll(rng).total,
+            modifier,
+        }
+    }
+
+    pub fn total(&self) -> i32 {
+        self.natural as i32 + self.modifier
+    }
+
+    /// Natural 20.
+    pub fn critical_success(&self) -> bool {
+        self.natural == Dice::D20.sides
+    }
+
+    /// Natural 1.
+    pub fn critical_failure(&self) -> bool {
+        self.natural == 1
+    }
+
+    /// Human-readable breakdown, e.g. "d20 (14) + 3", noting criticals.
+    pub fn detail(&self) -> String {
+        let sign = if self.modifier < 0 { '-' } else { '+' };
+        let mut detail = format!(
+            "d20 ({}) {} {}",
+            self.natural,
+            sign,
+            self.modifier.unsigned_abs()
+        );
+        if self.critical_success() {
+            detail.push_str(" — critical success");
+        } else if self.critical_failure() {
+            detail.push_str(" — critical failure");
+        }
+        detail
+    }
+}
+
+/// A d20 check rolled against a target number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckOutcome {
+    pub check: Check,
+    pub target: i32,
+}
+
+impl CheckOutcome {
+    /// Rolls d20 + `modifier` against `target`.
+    pub fn roll(modifier: i32, target: i32, rng: &mut impl FnMut() -> u32) -> Self {
+        Self {
+            check: Check::roll(modifier, rng),
+            target,
+        }
+    }
+
+    /// Meets the target. A natural 20 always succeeds and a natural 1 always fails.
+    pub fn success(&self) -> bool {
+        if self.check.critical_success() {
+            true
+        } else if self.check.critical_failure() {
+            false
+        } else {
+            self.margin() >= 0
+        }
+    }
+
+    /// How far the total landed above (positive) or below (negative) the target.
+    pub fn margin(&self) -> i32 {
+        self.check.total() - self.target
+    }
+}
+
+/// Most dice or sides a damage expression may have.
+const MAX_DICE: u32 = 100;
+
+/// A damage expression such as "2d6+1": some dice plus a flat modifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiceExpr {
+    pub dice: Dice,
+    pub modifier: i32,
+}
+
+/// Why a string is not a [`DiceExpr`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiceError {
+    Empty,
+    /// Not of the form `NdS`, `dS`, `NdS+M` or `NdS-M`.
+    Malformed(String),
+    /// More than 100 dice, or dice with more than 100 sides.
+    TooLarge(String),
+}
+
+impl std::fmt::Display for DiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no dice to roll"),
+            Self::Malformed(s) => write!(f, "\"{s}\" is not a dice expression"),
+            Self::TooLarge(s) => write!(f, "\"{s}\" has too many dice or sides"),
+        }
+    }
+}
+
+/// Result of rolling a [`DiceExpr`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollResult {
+    /// Dice plus modifier; a negative modifier can take it below zero.
+    pub total: i32,
+    /// Every die rolled, highest first.
+    pub rolls: Vec<u32>,
+    pub modifier: i32,
+}
+
+impl RollResult {
+    /// Human-readable breakdown, e.g. "[5, 2] + 1 = 8".
+    pub fn detail(&self) -> String {
+        let faces: Vec<String> = self.rolls.iter().map(u32::to_string).collect();
+        let mut detail = format!("[{}]", faces.join(", "));
+        if self.modifier != 0 {
+            let sign = if self.modifier < 0 { '-' } else { '+' };
+            detail.push_str(&format!(" {sign} {}", self.modifier.unsigned_abs()));
+        }
+        detail.push_str(&format!(" = {}", self.total));
+        detail
+    }
+}
+
+/// Parses a damage string like "1d20", "3d6+2" or "d8". Whitespace is
+/// ignored and the count defaults to 1.
+pub fn parse_dice(s: &str) -> Result<DiceExpr, DiceError> {
+    let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    if compact.is_empty() {
+        return Err(DiceError::Empty);
+    }
+    let malformed = || DiceError::Malformed(s.trim().to_string());
+    let lower = compact.to_ascii_lowercase();
+    let (count, rest) = lower.split_once('d').ok_or_else(malformed)?;
+    let (sides, modifier) = rest.split_at(rest.find(['+', '-']).unwrap_or(rest.len()));
+    let number = |digits: &str| {
+        (!digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| digits.parse::<u32>().ok())
+            .flatten()
+    };
+    let count = if count.is_empty() {
+        1
+    } else {
+        number(count).ok_or_else(malformed)?
+    };
+    let sides = number(sides).ok_or_else(malformed)?;
+    let modifier = match modifier.split_at_checked(1) {
+        Some((sign, digits)) => {
+            let value = number(digits).ok_or_else(malformed)? as i64;
+            let value = if sign == "-" { -value } else { value };
+            i32::try_from(value).map_err(|_| malformed())?
+        }
+        None => 0,
+    };
+    if count == 0 || sides == 0 {
+        return Err(malformed());
+    }
+    if count > MAX_DICE || sides > MAX_DICE {
+        return Err(DiceError::TooLarge(s.trim().to_string()));
+    }
+    Ok(DiceExpr {
+        dice: Dice {
+            count,
+            sides,
+            drop_lowest: 0,
+        },
+        modifier,
+    })
+}
+
+impl DiceExpr {
+    /// Rolls the dice and adds the modifier. `rng` returns uniformly random words.
+    pub fn roll(&self, rng: &mut impl FnMut() -> u32) -> RollResult {
+        let roll = self.dice.roll(rng);
+        RollResult {
+            total: roll.total as i32 + self.modifier,
+            rolls: roll.faces,
+            modifier: self.modifier,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolled_levels_stay_in_bounds() {
+        // xorshift, so the sequence is fixed
+        let mut state = 0x2545_f491_u32;
+        let mut rng = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        let levels: Vec<u32> = (0..1000)
+            .map(|_| Dice::STAT.roll_level(&mut rng, 1..=5))
+            .collect();
+        assert!(levels.iter().all(|level| (1..=5).contains(level)));
+        assert!(levels.contains(&1) || levels.contains(&2));
+        assert!(levels.contains(&5));
+
+        // Extremes map onto the ends of the range
+        assert_eq!(Dice::STAT.roll_level(&mut || 0, 1..=5), 1);
+        assert_eq!(Dice::STAT.roll_level(&mut || 5, 1..=5), 5);
+        let roll = Dice::STAT.roll(&mut || 5);
+        assert_eq!((roll.faces.len(), roll.dropped, roll.total), (4, 1, 18));
+    }
+
+    #[test]
+    fn test_skill_check_adds_level_to_d20() {
+        // A word of 13 lands on face 14
+        let check = Check::roll(3, &mut || 13);
+        assert_eq!(check.natural, 14);
+        assert_eq!(check.total(), 17);
+        assert_eq!(check.detail(), "d20 (14) + 3");
+
+        let natural_20 = Check::roll(3, &mut || 19);
+        assert!(natural_20.critical_success());
+        assert_eq!(natural_20.detail(), "d20 (20) + 3 — critical success");
+        assert!(Check::roll(-1, &mut || 0).critical_failure());
+    }
+
+    #[test]
+    fn test_check_outcome_margin_and_criticals() {
+        // Words land on face word + 1
+        let hit = CheckOutcome::roll(3, 15, &mut || 11);
+        assert_eq!((hit.check.total(), hit.margin()), (15, 0));
+        assert!(hit.success());
+        let miss = CheckOutcome::roll(3, 15, &mut || 10);
+        assert_eq!(miss.margin(), -1);
+        assert!(!miss.success());
+
+        assert!(CheckOutcome::roll(-5, 30, &mut || 19).success());
+        assert!(!CheckOutcome::roll(20, 5, &mut || 0).success());
+    }
+
+    #[test]
+    fn test_parse_damage_strings() {
+        let d20 = parse_dice("1d20").unwrap();
+        assert_eq!((d20.dice, d20.modifier), (Dice::D20, 0));
+        let expr = parse_dice(" 3d6 + 2 ").unwrap();
+        assert_eq!((expr.dice.count, expr.dice.sides, expr.modifier), (3, 6, 2));
+        let d8 = parse_dice("d8").unwrap();
+        assert_eq!((d8.dice.count, d8.dice.sides), (1, 8));
+        assert_eq!(parse_dice("2D4-1").unwrap().modifier, -1);
+
+        assert_eq!(parse_dice("  "), Err(DiceError::Empty));
+        for bad in [
+            "sword", "2d", "d", "2x6", "1d6+", "1d6+x", "0d6", "1d0", "1d6+2+3", "-1d6",
+        ] {
+            assert!(
+                matches!(parse_dice(bad), Err(DiceError::Malformed(_))),
+                "{bad}"
+            );
+        }
+        assert!(matches!(parse_dice("1000d6"), Err(DiceError::TooLarge(_))));
+    }
+
+    #[test]
+    fn test_dice_expression_rolls_every_die() {
+        // Words 4 and 1 land on faces 5 and 2
+        let mut words = [4, 1].into_iter();
+        let roll = parse_dice("2d6+1")
+            .unwrap()
+            .roll(&mut || words.next().unwrap());
+        assert_eq!(roll.rolls, vec![5, 2]);
+        assert_eq!(roll.total, 8);
+        assert_eq!(roll.detail(), "[5, 2] + 1 = 8");
+    }
+
+    #[test]
+    fn test_same_seed_replays_same_rolls() {
+        let rolls = |seed: u64| {
+            let mut rng = SeededRng::new(seed);
+            let mut next = || rng.next_u32();
+            let checks: Vec<Check> = (0..20).map(|_| Check::roll(2, &mut next)).collect();
+            (checks, Dice::STAT.roll(&mut next))
+        };
+        assert_eq!(rolls(42), rolls(42));
+        assert_ne!(rolls(42), rolls(43));
+    }
+}
@@ -0,0 +1,139 @@
+//! Self-contained JSON export of a single character version, for backups
+//! and sharing outside the server.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::character::{
+    CharacterTrait, Equipment, EquipmentRegistry, Item, ItemRegistry, ReferenceKind, TraitRegistry,
+    Weapon, WeaponRegistry,
+};
+use crate::version::{CharacterVersion, Timestamp};
+use crate::Character;
+
+/// A character version plus the definitions of every trait, weapon,
+/// equipment piece and item it references, keyed by name, so the export
+/// reads without the server's registries. Names the registries no longer
+/// define are simply left out of the maps.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharacterExport {
+    pub version: u32,
+    pub saved_at: Timestamp,
+    pub character: Character,
+    pub traits: BTreeMap<String, CharacterTrait>,
+    pub weapons: BTreeMap<String, Weapon>,
+    pub equipment: BTreeMap<String, Equipment>,
+    pub items: BTreeMap<String, Item>,
+}
+
+impl CharacterExport {
+    pub fn new(
+        version: &CharacterVersion,
+        trait_registry: &TraitRegistry,
+        weapon_registry: &WeaponRegistry,
+        equipment_registry: &EquipmentRegistry,
+        item_registry: &ItemRegistry,
+    ) -> Self {
+        let mut export = Self {
+            version: version.version,
+            saved_at: version.saved_at,
+            character: version.character.clone(),
+            traits: BTreeMap::new(),
+            weapons: BTreeMap::new(),
+            equipment: BTreeMap::new(),
+            items: BTreeMap::new(),
+        };
+        for (kind, name) in version.character.references() {
+            match kind {
+                ReferenceKind::Trait => {
+                    if let Some(t) = trait_registry.get(&name) {
+                        export.traits.insert(name, t.clone());
+                    }
+                }
+                ReferenceKind::Weapon => {
+                    if let Some(w) = weapon_registry.get(&name) {
+                        export.weapons.insert(name, w.clone());
+                    }
+                }
+                ReferenceKind::Equipment => {
+                    if let Some(e) = equipment_registry.get(&name) {
+                        export.equipment.insert(name, e.clone());
+                    }
+                }
+                ReferenceKind::Item => {
+                    if let Some(i) = item_registry.get(&name) {
+                        export.items.insert(name, i.clone());
+                    }
+                }
+                // Abilities depend on the class registry and stay names only
+                ReferenceKind::Ability => {}
+            }
+        }
+        export
+    }
+
+    pub fn to_pretty_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Reads the character out of an export, or out of bare `Character` JSON.
+    pub fn character_from_json(json: &str) -> Result<Character, serde_json::Error> {
+        match serde_json::from_str::<Self>(json) {
+            Ok(export) => Ok(export.character),
+            Err(_) => serde_json::from_str(json),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InventoryItem;
+
+    #[test]
+    fn test_export_carries_referenced_definitions() {
+        let weapons = WeaponRegistry::load_from_str(
+            r#"[{ "name": "Dagger", "damage": "1d4", "attack": 0, "kind": { "Range": "Bow" },
+                  "grip": "OneHanded", "range": 1, "effects": [] }]"#,
+        )
+        .unwrap();
+        let items =
+            ItemRegistry::load_from_str(r#"[{ "name": "Rope", "description": "Fifty feet" }]"#)
+                .unwrap();
+        let mut character = Character::new("Packer".to_string());
+        character.equipped_weapons.push("Dagger".to_string());
+        character
+            .inventory
+            .push(InventoryItem::Item("Rope".to_string()));
+        character
+            .inventory
+            .push(InventoryItem::Item("Lost map".to_string()));
+        let version = CharacterVersion {
+            version: 3,
+            saved_at: 1_700_000_000,
+            character,
+            saved_by: None,
+            label: None,
+        };
+
+        let export = CharacterExport::new(
+            &version,
+            &TraitRegistry::default(),
+            &weapons,
+            &EquipmentRegistry::default(),
+            &items,
+        );
+        assert_eq!(export.weapons.keys().collect::<Vec<_>>(), ["Dagger"]);
+        // Unknown names are left to the character alone
+        assert_eq!(export.items.keys().collect::<Vec<_>>(), ["Rope"]);
+
+        let json = export.to_pretty_json().unwrap();
+        let read_back: CharacterExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(read_back, export);
+        assert_eq!(
+            CharacterExport::character_from_json(&json).unwrap(),
+            version.character
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! Best-effort import of character sheets exported by other tools.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::{add_item, Character, InventoryItem};
+
+/// Why an external sheet could not be imported at all.
+#[derive(Debug)]
+pub enum ImportError {
+    /// The input is not valid JSON.
+    Json(serde_json::Error),
+    /// The top level is not a JSON object.
+    NotAnObject,
+    /// There is no usable `name`.
+    MissingName,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "Invalid JSON: {e}"),
+            Self::NotAnObject => write!(f, "Expected a JSON object"),
+            Self::MissingName => write!(f, "Character has no name"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// An imported character plus notes about everything that was dropped.
+#[derive(Debug)]
+pub struct Imported {
+    pub character: Character,
+    pub warnings: Vec<String>,
+}
+
+/// Maps a common external character JSON shape into a `Character`.
+///
+/// Field mapping:
+/// - `name` (string, required) → `name`
+/// - `level` (number) → `level`
+/// - `abilities` (object) → characteristics; each value is a score or `{ "score": n }`:
+///   `str` → STR, `dex` → DEX, `con` → END, `int` → INT, `wis` → WIL, `cha` → CHA.
+///   PER and MAG have no counterpart and keep their defaults.
+/// - `inventory` (array of names or `{ "name", "quantity" }`) → plain inventory items,
+///   stacked `quantity` high.
+///
+/// Everything else is dropped with a warning. Scores are used as levels as-is
+/// (negative values become 0), since both scales center on 10.
+pub fn import_character(json: &str) -> Result<Imported, ImportError> {
+    let value: Value = serde_json::from_str(json).map_err(ImportError::Json)?;
+    let Value::Object(fields) = value else {
+        return Err(ImportError::NotAnObject);
+    };
+    let name = fields
+        .get("name")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .ok_or(ImportError::MissingName)?;
+
+    let mut character = Character::new(name.to_string());
+    let mut warnings = Vec::new();
+    for (key, value) in &fields {
+        match key.as_str() {
+            "name" => {}
+            "level" => match value.as_u64() {
+                Some(level) => character.level = level.min(u32::MAX as u64) as u32,
+                None => warnings.push(format!("Ignored non-numeric level {value}")),
+            },
+            "abilities" => match value {
+                Value::Object(scores) => import_abilities(&mut character, scores, &mut warnings),
+                _ => warnings.push("Ignored `abilities`: expected an object".to_string()),
+            },
+            "inventory" => match value {
+                Value::Array(entries) => import_inventory(&mut character, entries, &mut warnings),
+                _ => warnings.push("Ignored `inventory`: expected an array".to_string()),
+            },
+            other => warnings.push(format!("Dropped unmapped field `{other}`")),
+        }
+    }
+    Ok(Imported {
+        character,
+        warnings,
+    })
+}
+
+fn import_abilities(
+    character: &mut Character,
+    scores: &Map<String, Value>,
+    warnings: &mut Vec<String>,
+) {
+    for (key, value) in scores {
+        let score = value
+            .as_i64()
+            .or_else(|| value.get("score").and_then(Value::as_i64));
+        let Some(score) = score else {
+            warnings.push(format!("Ignored ability `{key}`: no numeric score"));
+            continue;
+        };
+        let stat = match key.to_ascii_lowercase().as_str() {
+            "str" | "strength" => &mut character.stats.strength,
+            "dex" | "dexterity" => &mut character.stats.dexterity,
+            "con" | "constitution" => &mut character.stats.endurance,
+            "int" | "intelligence" => &mut character.stats.intellect,
+            "wis" | "wisdom" => &mut character.stats.willpower,
+            "cha" | "charisma" => &mut character.stats.charisma,
+            _ => {
+                warnings.push(format!("Dropped unmapped ability `{key}`"));
+                continue;
+            }
+        };
+        stat.level = score.clamp(0, u32::MAX as i64) as u32;
+    }
+}
+
+fn import_inventory(character: &mut Character, entries: &[Value], warnings: &mut Vec<String>) {
+    for entry in entries {
+        let (name, quantity) = match entry {
+            Value::String(name) => (Some(name.as_str()), 1),
+            Value::Object(item) => (
+                item.get("name").and_then(Value::as_str),
+                item.get("quantity").and_then(Value::as_u64).unwrap_or(1),
+            ),
+            _ => (None, 0),
+        };
+        let Some(name) = name.map(str::trim).filter(|n| !n.is_empty()) else {
+            warnings.push(format!("Ignored inventory entry without a name: {entry}"));
+            continue;
+        };
+        add_item(
+            &mut character.inventory,
+            &mut character.item_counts,
+            InventoryItem::Item(name.to_string()),
+            quantity.min(u64::from(u32::MAX)) as u32,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_maps_sample_sheet() {
+        let json = r#"{
+            "name": "Mira Ashvale",
+            "level": 3,
+            "race": "Half-Elf",
+            "abilities": { "str": 8, "dex": { "score": 16 }, "con": 12, "wis": 14, "luck": 3 },
+            "inventory": ["Rope", { "name": "Torch", "quantity": 2 }, { "weight": 1 }]
+        }"#;
+        let Imported {
+            character,
+            warnings,
+        } = import_character(json).unwrap();
+
+        assert_eq!(character.name, "Mira Ashvale");
+        assert_eq!(character.level, 3);
+        assert_eq!(character.stats.strength.level, 8);
+        assert_eq!(character.stats.dexterity.level, 16);
+        assert_eq!(character.stats.endurance.level, 12);
+        assert_eq!(character.stats.willpower.level, 14);
+        assert_eq!(character.stats.magic.level, 10);
+        assert_eq!(
+            character.inventory,
+            vec![
+                InventoryItem::Item("Rope".to_string()),
+                InventoryItem::Item("Torch".to_string()),
+            ]
+        );
+        assert_eq!(character.item_counts.get("Torch"), Some(&2));
+        assert_eq!(warnings.len(), 3, "{warnings:?}");
+
+        let json = serde_json::to_string(&character).unwrap();
+        let decoded: Character = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, character);
+    }
+
+    #[test]
+    fn test_import_requires_name() {
+        assert!(matches!(
+            import_character(r#"{ "level": 2 }"#),
+            Err(ImportError::MissingName)
+        ));
+    }
+}
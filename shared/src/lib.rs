@@ -1,18 +1,49 @@
+pub mod audit;
 pub mod character;
+pub mod dice;
+pub mod export;
+pub mod import;
 pub mod messages;
+pub mod notes;
+pub mod presence;
+pub mod rules;
 pub mod version;
 
+pub use audit::{AuditAction, AuditEntry};
 pub use character::{
-    collect_source_effects, xp_to_next_level, Ability, AbilityCheck, AbilityRegistry,
-    AbilityRequirements, AbilityType, AbilityUpgrade, Character, CharacterSkill, CharacterTrait,
-    Characteristic, CharacteristicKind, Characteristics, Class, ClassAbilities, Effect, EnemyCheck,
-    Equipment, EquipmentRegistry, EquipmentSlot, GetEffects, InventoryItem, Item, ItemRegistry,
-    LearnScreenPosition, MeleeKind, Named, Protection, Race, RangeKind, Resist, Resource, Size,
-    Skill, SkillRegistry, TraitCondition, TraitRegistry, Wallet, Weapon, WeaponGrip, WeaponKind,
+    ability_check_modifier, active_temp_effects, add_item, attack_bonus, attack_characteristics,
+    base_protections, carried_load, carry_capacity, clear_ability_cooldowns,
+    collect_attributed_effects, collect_source_effects, defense, drop_foreign_skills, max_hp,
+    merge_duplicates, overload_penalty, reset_ability_uses, short_rest_heal, skill_check_modifier,
+    stack_items, sync_free_ranks, take_items, tick_ability_cooldowns, tick_temp_effects,
+    use_ability, Ability, AbilityCheck, AbilityLock, AbilityRegistry, AbilityRequirements,
+    AbilityRuntime, AbilityType, AbilityUpgrade, Character, CharacterSkill, CharacterTrait,
+    Characteristic, CharacteristicKind, Characteristics, Class, ClassAbilities, Condition, Effect,
+    EffectSource, EnemyCheck, Equipment, EquipmentRegistry, EquipmentSlot, GetEffects, HpDelta,
+    InventoryItem, InventoryStack, InventoryTotals, Item, ItemRegistry, LearnScreenPosition,
+    Loadout, MeleeKind, Named, PointPool, Protection, Race, RangeKind, ReconcileIssue,
+    Reconciliation, ReferenceKind, Resist, Resource, RestKind, Size, Skill, SkillRegistry,
+    TempEffect, TraitCondition, TraitRegistry, Wallet, Weapon, WeaponGrip, WeaponKind,
     WeaponRegistry,
 };
-pub use messages::{ClientMessage, ServerMessage};
-pub use version::{CharacterFile, CharacterSummary, CharacterVersion, Timestamp, VersionSummary};
+pub use dice::{
+    parse_dice, Check, CheckOutcome, Dice, DiceError, DiceExpr, DiceRoll, RollResult, SeededRng,
+};
+pub use export::CharacterExport;
+pub use messages::{ClientMessage, ErrorCode, ServerMessage};
+pub use notes::{GmNote, GM_AUTHOR};
+pub use presence::{ConnectionId, PresenceEntry};
+pub use rules::{
+    plan_allocation, respec_points, AbilityPointCadence, Allocation, AllocationWeights,
+    CampaignRules, ClassRules, ClassRulesRegistry, CreationRules, CurrencyScheme, EffectCondition,
+    EffectModifier, EffectTotal, InitiativeFormula, LevelUpPools, PowerWeights, RaceRules,
+    RaceRulesRegistry, Ruleset, SkillPointFormula, Stacked, StackingRules, XpCurve,
+    DEFAULT_STAT_FLOOR,
+};
+pub use version::{
+    significant_change, CharacterDiff, CharacterFile, CharacterSummary, CharacterVersion,
+    CosmeticField, DiffEntry, Timestamp, VersionSummary,
+};
 
 /// Serialize a message to bincode bytes
 pub fn serialize<T: serde::Serialize>(msg: &T) -> Result<Vec<u8>, bincode::Error> {
@@ -36,15 +67,104 @@ mod tests {
         assert_eq!(character, decoded);
     }
 
+    #[test]
+    fn test_pretty_json_roundtrip() {
+        let mut character = Character::new("Inspected".to_string());
+        character.traits = vec!["Light Step".to_string()];
+        character.equip(EquipmentSlot::Ring, "Copper Ring".to_string());
+        let json = character.to_pretty_json().unwrap();
+        let decoded: Character = serde_json::from_str(&json).unwrap();
+        assert_eq!(character, decoded);
+    }
+
+    #[test]
+    fn test_damage_consumes_temp_hp_first() {
+        let mut character = Character::new("Tank".to_string());
+        character.hp_spent = 1;
+        character.grant_temp_hp(4);
+
+        character.apply_hp_delta(HpDelta(-3));
+        assert_eq!((character.temp_hp, character.hp_spent), (1, 1));
+
+        character.apply_hp_delta(HpDelta(-2));
+        assert_eq!((character.temp_hp, character.hp_spent), (0, 2));
+
+        character.apply_hp_delta(HpDelta(5));
+        assert_eq!((character.temp_hp, character.hp_spent), (0, 0));
+    }
+
+    #[test]
+    fn test_smaller_temp_hp_grant_keeps_larger() {
+        let mut character = Character::new("Tank".to_string());
+        character.grant_temp_hp(6);
+        character.grant_temp_hp(2);
+        assert_eq!(character.temp_hp, 6);
+        character.grant_temp_hp(8);
+        assert_eq!(character.temp_hp, 8);
+    }
+
+    #[test]
+    fn test_accent_color_roundtrip() {
+        let mut character = Character::new("Painted".to_string());
+        character.accent_color = Some([0x40, 0x90, 0xE0]);
+        let decoded: Character = deserialize(&serialize(&character).unwrap()).unwrap();
+        assert_eq!(decoded.accent_color, Some([0x40, 0x90, 0xE0]));
+        let json = character.to_pretty_json().unwrap();
+        let decoded: Character = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.accent_color, Some([0x40, 0x90, 0xE0]));
+    }
+
+    #[test]
+    fn test_free_skill_rank_follows_trait() {
+        let traits = TraitRegistry::load_from_str(
+            r#"{
+                "Street Smart": {
+                    "description": "",
+                    "effects": [{ "FreeSkillRank": { "skill": "Stealth", "ranks": 2 } }]
+                }
+            }"#,
+        )
+        .unwrap();
+        let mut character = Character::new("Rogue".to_string());
+        let mut stealth = CharacterSkill::new("Stealth".to_string());
+        stealth.level = 3;
+        character.skills = vec![stealth];
+
+        character.traits = vec!["Street Smart".to_string()];
+        character.recalculate_effects(
+            &traits,
+            &WeaponRegistry::default(),
+            &EquipmentRegistry::default(),
+            &[],
+        );
+        assert!(character.sync_free_skill_ranks());
+        assert_eq!(character.skills[0].level, 5);
+        assert_eq!(character.skills[0].free_ranks, 2);
+        assert!(!character.sync_free_skill_ranks());
+
+        character.traits.clear();
+        character.recalculate_effects(
+            &traits,
+            &WeaponRegistry::default(),
+            &EquipmentRegistry::default(),
+            &[],
+        );
+        assert!(character.sync_free_skill_ranks());
+        assert_eq!(character.skills[0].level, 3);
+        assert_eq!(character.skills[0].free_ranks, 0);
+    }
+
     #[test]
     fn test_client_message_serialization() {
         let msg = ClientMessage::CreateCharacter {
             name: "Gandalf".to_string(),
+            accent_color: None,
             race: Race::default(),
             class: Class::default(),
             stats: Characteristics::default(),
             skills: Vec::new(),
             traits: Vec::new(),
+            password: None,
         };
         let bytes = serialize(&msg).unwrap();
         let decoded: ClientMessage = deserialize(&bytes).unwrap();
@@ -66,14 +186,18 @@ mod tests {
             range: 0,
             effects: vec![Effect::Armor(2)],
             condition: None,
+            value: None,
+            weight: None,
+            damage_type: None,
         };
         let msg = ClientMessage::CreateWeapon {
             weapon: weapon.clone(),
+            replace: false,
         };
         let bytes = serialize(&msg).unwrap();
         let decoded: ClientMessage = deserialize(&bytes).unwrap();
         match decoded {
-            ClientMessage::CreateWeapon { weapon: w } => {
+            ClientMessage::CreateWeapon { weapon: w, .. } => {
                 assert_eq!(w.name, "Tower Shield");
                 assert_eq!(w.kind, WeaponKind::Shield);
             }
@@ -93,14 +217,18 @@ mod tests {
             range: 0,
             effects: vec![],
             condition: None,
+            value: None,
+            weight: None,
+            damage_type: None,
         };
         let msg = ClientMessage::CreateWeapon {
             weapon: weapon.clone(),
+            replace: false,
         };
         let bytes = serialize(&msg).unwrap();
         let decoded: ClientMessage = deserialize(&bytes).unwrap();
         match decoded {
-            ClientMessage::CreateWeapon { weapon: w } => {
+            ClientMessage::CreateWeapon { weapon: w, .. } => {
                 assert_eq!(w.name, "Lute");
                 assert_eq!(w.kind, WeaponKind::BardInstrument);
             }
@@ -113,11 +241,16 @@ mod tests {
         let summary = CharacterSummary {
             id: uuid::Uuid::new_v4(),
             name: "Frodo".to_string(),
+            accent_color: None,
             race: Race::default(),
             class: Class::default(),
             level: 1,
             version_count: 1,
+            latest_version: 1,
+            power_score: 0,
             last_updated: 0,
+            tags: Vec::new(),
+            protected: false,
         };
         let msg = ServerMessage::CharacterList {
             characters: vec![summary],
@@ -1,9 +1,35 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
+use strum::Display;
 use uuid::Uuid;
 
+use crate::audit::AuditEntry;
 use crate::character::CharacterSkill;
-use crate::version::{CharacterSummary, Timestamp, VersionSummary};
-use crate::{Character, Characteristics, Class, Equipment, Item, Race, Weapon};
+use crate::notes::GmNote;
+use crate::presence::{ConnectionId, PresenceEntry};
+use crate::rules::Ruleset;
+use crate::version::{CharacterSummary, CharacterVersion, DiffEntry, Timestamp, VersionSummary};
+use crate::{
+    Character, CharacterTrait, Characteristics, Class, Equipment, Item, Race, ReferenceKind, Weapon,
+};
+
+/// Category of a `ServerMessage::Error`, for programmatic handling on the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize, Deserialize)]
+pub enum ErrorCode {
+    /// The connection lacks the rights for the request
+    Unauthorized,
+    /// The referenced character, version or connection does not exist
+    NotFound,
+    /// The request was malformed or rejected by validation
+    Invalid,
+    /// The server is in safe mode and refuses changes
+    ReadOnly,
+    /// Another character already uses the name (only with unique names on)
+    NameTaken,
+    /// The connection sent too much, too fast or too large
+    RateLimited,
+}
 
 /// Messages sent from client to server
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,45 +37,157 @@ pub enum ClientMessage {
     /// Request the summary list of all characters
     RequestCharacterList,
 
+    /// Request `limit` summaries starting at `offset`, optionally only those
+    /// whose name contains `query` (case-insensitive)
+    RequestCharacterPage {
+        offset: u32,
+        limit: u32,
+        query: Option<String>,
+    },
+
     /// Request the version list for a specific character
     RequestVersionList { id: Uuid },
 
     /// Request a specific version of a character (latest if version is None)
     RequestCharacterVersion { id: Uuid, version: Option<u32> },
 
+    /// Unlock editing a password-protected character on this connection
+    AuthenticateCharacter { id: Uuid, password: String },
+
+    /// Request the changes between two versions of a character
+    RequestVersionDiff { id: Uuid, from: u32, to: u32 },
+
+    /// Request every recorded mutation of a character (admin only)
+    RequestAuditLog { id: Uuid },
+
+    /// Request a self-contained JSON export of a version (latest if version is None)
+    ExportCharacter { id: Uuid, version: Option<u32> },
+
+    /// Create a character from an export or bare character JSON
+    ImportCharacter { json: String },
+
     /// Create a new character with customized data
     CreateCharacter {
         name: String,
+        accent_color: Option<[u8; 3]>,
         race: Race,
         class: Class,
         stats: Characteristics,
         skills: Vec<CharacterSkill>,
         traits: Vec<String>,
+        /// Protects the character: only connections that authenticate with
+        /// it (or the admin) may edit or delete it.
+        password: Option<String>,
     },
 
+    /// Copy the latest version of a character into a new character
+    CloneCharacter { id: Uuid, new_name: String },
+
     /// Delete a character by ID (all versions)
     DeleteCharacter { id: Uuid },
 
-    /// Update a character (creates a new version)
-    UpdateCharacter { character: Character },
+    /// Delete several characters at once (all versions of each)
+    DeleteCharacters { ids: Vec<Uuid> },
+
+    /// Annotate a version; an empty label removes it (admin only)
+    SetVersionLabel {
+        id: Uuid,
+        version: u32,
+        label: String,
+    },
+
+    /// Save a copy of an old version as the newest version
+    RollbackVersion { id: Uuid, version: u32 },
+
+    /// Update a character (creates a new version). `base_version` is the
+    /// newest version the edit started from; the save is refused with
+    /// `UpdateConflict` once someone else has saved past it. `None` skips
+    /// the check.
+    UpdateCharacter {
+        character: Box<Character>,
+        base_version: Option<u32>,
+    },
+
+    /// Replace a character's tags (metadata only, no new version)
+    SetTags { id: Uuid, tags: Vec<String> },
 
     /// Delete a specific version of a character
     DeleteVersion { id: Uuid, version: u32 },
 
-    /// Register a new weapon definition
-    CreateWeapon { weapon: Weapon },
+    /// Delete all but the newest `keep` versions of a character (admin only)
+    PruneVersions { id: Uuid, keep: u32 },
+
+    /// Register a new weapon definition (`replace`, admin only, overwrites the one of the same name)
+    CreateWeapon { weapon: Weapon, replace: bool },
 
-    /// Register a new equipment definition
-    CreateEquipment { equipment: Equipment },
+    /// Register a new equipment definition (`replace`, admin only, overwrites the one of the same name)
+    CreateEquipment { equipment: Equipment, replace: bool },
 
-    /// Register a new item definition
-    CreateItem { item: Item },
+    /// Register a new item definition (`replace`, admin only, overwrites the one of the same name)
+    CreateItem { item: Item, replace: bool },
+
+    /// Remove a weapon definition from the registry (requires admin)
+    DeleteWeapon { name: String },
+
+    /// Remove an equipment definition from the registry (requires admin)
+    DeleteEquipment { name: String },
+
+    /// Remove an item definition from the registry (requires admin)
+    DeleteItem { name: String },
 
     /// Upload a portrait image for a character (resized PNG bytes)
     UploadPortrait { id: Uuid, png_data: Vec<u8> },
 
     /// Request a character's portrait
     RequestPortrait { id: Uuid },
+
+    /// Recompute effects and resource maxes for a character, saving a repaired
+    /// version only if something drifted
+    Recompute { id: Uuid },
+
+    /// Run `Recompute` over every stored character
+    RecomputeAll,
+
+    /// Re-read the trait, weapon, equipment and item files and send them to
+    /// every client (admin only)
+    ReloadRegistries,
+
+    /// Log in as admin with the server's admin password
+    Authenticate { password: String },
+
+    /// Announce which character this client has open (None when leaving the sheet)
+    Viewing { id: Option<Uuid> },
+
+    /// Mirror another connection's view until `Unfollow`
+    Follow { connection_id: ConnectionId },
+
+    /// Stop following and return control to this client
+    Unfollow,
+
+    /// Find characters whose latest version references a registry entry (admin only)
+    FindByReference { kind: ReferenceKind, name: String },
+
+    /// Leave a GM note on a character, shown live to its viewers (admin only)
+    PushNote { target_id: Uuid, text: String },
+
+    /// Request thumbnails of every stored portrait
+    ListPortraits,
+
+    /// Request the full image of a stored portrait by content hash
+    RequestGalleryPortrait { hash: String },
+
+    /// Share a dice roll with every live connection
+    BroadcastRoll {
+        label: String,
+        total: i32,
+        detail: String,
+    },
+
+    /// Start a new in-game day, resetting every character's long rests (admin only)
+    NewDay,
+
+    /// Award XP to several characters, applying their level-ups (admin only)
+    GrantXp { ids: Vec<Uuid>, amount: u32 },
 }
 
 /// Messages sent from server to client
@@ -58,26 +196,67 @@ pub enum ServerMessage {
     /// List of character summaries (for character select screen)
     CharacterList { characters: Vec<CharacterSummary> },
 
+    /// One page of character summaries; `total` counts every match
+    CharacterPage {
+        characters: Vec<CharacterSummary>,
+        total: u32,
+    },
+
     /// Version list for a specific character (for version select screen)
     VersionList {
         id: Uuid,
         versions: Vec<VersionSummary>,
     },
 
-    /// Full character data for a specific version
+    /// Full character data for a specific version. `latest_version` is the
+    /// newest version at the time, the base for the next `UpdateCharacter`.
     CharacterVersion {
         id: Uuid,
         version: u32,
+        latest_version: u32,
         saved_at: Timestamp,
         character: Box<Character>,
     },
 
+    /// Field-level changes from version `from` to version `to`
+    VersionDiff {
+        id: Uuid,
+        from: u32,
+        to: u32,
+        changes: Vec<DiffEntry>,
+    },
+
+    /// Recorded mutations of a character, oldest first
+    AuditLog { id: Uuid, entries: Vec<AuditEntry> },
+
+    /// Pretty JSON of a `CharacterExport`, ready to be saved to a file
+    CharacterExport { id: Uuid, json: String },
+
+    /// An imported character was created; lists the names it references
+    /// that the server registries do not define
+    ImportResult {
+        id: Uuid,
+        missing_traits: Vec<String>,
+        missing_weapons: Vec<String>,
+        missing_equipment: Vec<String>,
+    },
+
     /// A new character was created
     CharacterCreated { summary: CharacterSummary },
 
     /// A character was updated (new version created)
     CharacterUpdated { summary: CharacterSummary },
 
+    /// Another connection saved a character this one is viewing
+    CharacterChanged { summary: CharacterSummary },
+
+    /// An update was refused because the character was saved by someone
+    /// else since the client's base version; `latest` is the stored version
+    UpdateConflict {
+        id: Uuid,
+        latest: Box<CharacterVersion>,
+    },
+
     /// A character was deleted
     CharacterDeleted { id: Uuid },
 
@@ -87,6 +266,128 @@ pub enum ServerMessage {
     /// Portrait image data for a character
     PortraitData { id: Uuid, png_data: Vec<u8> },
 
-    /// An error occurred
-    Error { message: String },
+    /// All GM notes of a character (sent when it is opened)
+    Notes { id: Uuid, notes: Vec<GmNote> },
+
+    /// A GM note was just added to a character
+    NoteAdded { id: Uuid, note: GmNote },
+
+    /// Stored portraits: content hashes with matching downscaled PNG thumbnails
+    PortraitList {
+        hashes: Vec<String>,
+        thumbnails: Vec<Vec<u8>>,
+    },
+
+    /// Full image of a stored portrait
+    GalleryPortrait { hash: String, png_data: Vec<u8> },
+
+    /// Every campaign rule: XP curve, creation, class, race and campaign rules (sent on connect)
+    Ruleset { rules: Ruleset },
+
+    /// Registry definitions after a reload, replacing the client's own
+    Registries {
+        traits: BTreeMap<String, CharacterTrait>,
+        weapons: BTreeMap<String, Weapon>,
+        equipment: BTreeMap<String, Equipment>,
+        items: BTreeMap<String, Item>,
+    },
+
+    /// A weapon definition was removed from the registry
+    WeaponDeleted { name: String },
+
+    /// An equipment definition was removed from the registry
+    EquipmentDeleted { name: String },
+
+    /// An item definition was removed from the registry
+    ItemDeleted { name: String },
+
+    /// Result of a recompute: how many characters were checked and which were repaired
+    RecomputeReport { checked: u32, repaired: Vec<Uuid> },
+
+    /// Identifier assigned to this connection (sent on connect)
+    Welcome { connection_id: ConnectionId },
+
+    /// Rights of this connection (sent on connect and after authenticating)
+    Session { admin: bool },
+
+    /// This connection may now edit the password-protected character `id`
+    CharacterUnlocked { id: Uuid },
+
+    /// Every live connection and what it is viewing
+    Presence { connections: Vec<PresenceEntry> },
+
+    /// The followed connection switched characters (None when it left the sheet)
+    FollowView {
+        connection_id: ConnectionId,
+        id: Option<Uuid>,
+    },
+
+    /// Follow mode ended because the followed connection went away
+    FollowEnded { connection_id: ConnectionId },
+
+    /// Characters whose latest version references `name` of `kind`
+    ReferenceMatches {
+        kind: ReferenceKind,
+        name: String,
+        characters: Vec<CharacterSummary>,
+    },
+
+    /// A connection rolled dice; `detail` is the human-readable breakdown
+    RollBroadcast {
+        from: ConnectionId,
+        label: String,
+        total: i32,
+        detail: String,
+    },
+
+    /// The GM started a new in-game day; long rests are available again
+    DayStarted,
+
+    /// The server loaded its data only partially and is read-only until restarted
+    SafeMode { reason: String },
+
+    /// A request failed
+    Error { code: ErrorCode, message: String },
+}
+
+impl ClientMessage {
+    /// Whether the request changes stored data. The server refuses these in
+    /// safe mode; the client keeps them across restarts while offline.
+    pub fn is_mutation(&self) -> bool {
+        matches!(
+            self,
+            ClientMessage::CreateCharacter { .. }
+                | ClientMessage::ImportCharacter { .. }
+                | ClientMessage::CloneCharacter { .. }
+                | ClientMessage::DeleteCharacter { .. }
+                | ClientMessage::DeleteCharacters { .. }
+                | ClientMessage::SetTags { .. }
+                | ClientMessage::DeleteVersion { .. }
+                | ClientMessage::PruneVersions { .. }
+                | ClientMessage::RollbackVersion { .. }
+                | ClientMessage::SetVersionLabel { .. }
+                | ClientMessage::UpdateCharacter { .. }
+                | ClientMessage::CreateWeapon { .. }
+                | ClientMessage::CreateEquipment { .. }
+                | ClientMessage::CreateItem { .. }
+                | ClientMessage::DeleteWeapon { .. }
+                | ClientMessage::DeleteEquipment { .. }
+                | ClientMessage::DeleteItem { .. }
+                | ClientMessage::UploadPortrait { .. }
+                | ClientMessage::PushNote { .. }
+                | ClientMessage::Recompute { .. }
+                | ClientMessage::RecomputeAll
+                | ClientMessage::NewDay
+                | ClientMessage::GrantXp { .. }
+        )
+    }
+}
+
+impl ServerMessage {
+    pub fn error(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::Error {
+            code,
+            message: message.into(),
+        }
+    }
 }
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use crate::version::Timestamp;
+
+/// Author shown on notes pushed by an admin.
+pub const GM_AUTHOR: &str = "GM";
+
+/// A note left on a character by the GM. Kept outside the versioned character.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GmNote {
+    pub author: String,
+    pub text: String,
+    pub created_at: Timestamp,
+}
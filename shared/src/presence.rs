@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Server-assigned identifier of a live websocket connection.
+pub type ConnectionId = u64;
+
+/// What a connected client is currently looking at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceEntry {
+    pub connection_id: ConnectionId,
+    /// Character open on the client's sheet, if any.
+    pub viewing: Option<Uuid>,
+}
@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::character::{CharacterSkill, CharacteristicKind, Characteristics, Skill};
+
+/// Per-class preferences for auto-allocating characteristic and skill points.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllocationWeights {
+    /// Relative preference per characteristic. Missing characteristics weigh 1,
+    /// so leftover points still go somewhere; 0 excludes a characteristic.
+    /// Skills are weighted by the characteristic they depend on.
+    #[serde(default)]
+    pub characteristics: BTreeMap<CharacteristicKind, u32>,
+    /// Highest level auto-allocation raises a characteristic to.
+    #[serde(default)]
+    pub characteristic_cap: Option<u32>,
+}
+
+impl AllocationWeights {
+    fn weight(&self, kind: CharacteristicKind) -> u32 {
+        self.characteristics.get(&kind).copied().unwrap_or(1)
+    }
+}
+
+/// One point purchase chosen by auto-allocation, in the order it should be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Allocation {
+    Characteristic(CharacteristicKind),
+    Skill(String),
+}
+
+/// Picks the candidate with the best weight per point of cost.
+fn best<T>(candidates: impl Iterator<Item = (T, u32, u32)>) -> Option<(T, u32)> {
+    candidates
+        .filter(|&(_, weight, _)| weight > 0)
+        .fold(None, |best: Option<(T, u32, u32)>, (item, weight, cost)| {
+            match &best {
+                // weight / cost > best_weight / best_cost, without division
+                Some((_, bw, bc))
+                    if (weight as u64) * (*bc as u64) <= (*bw as u64) * (cost as u64) =>
+                {
+                    best
+                }
+                _ => Some((item, weight, cost)),
+            }
+        })
+        .map(|(item, _, cost)| (item, cost))
+}
+
+/// Plans how to spend all characteristic points, then all skill points, favoring
+/// the class weights. Mirrors the upgrade rules: raising a characteristic from
+/// N costs N + 1 (Intellect also grants a skill point), a skill costs its next
+/// level and cannot exceed its dependency's level plus `bonuses`. Stops when
+/// nothing affordable is left.
+pub fn plan_allocation(
+    stats: &Characteristics,
+    mut characteristic_points: u32,
+    skills: &[CharacterSkill],
+    mut skill_points: u32,
+    class_skills: &BTreeMap<String, Skill>,
+    bonuses: &BTreeMap<CharacteristicKind, i32>,
+    weights: &AllocationWeights,
+) -> Vec<Allocation> {
+    let mut stats = *stats;
+    let mut plan = Vec::new();
+
+    loop {
+        let candidates = CharacteristicKind::iter().filter_map(|kind| {
+            let level = stats.get_level(kind);
+            let cost = level + 1;
+            let capped = weights.characteristic_cap.is_some_and(|cap| level >= cap);
+            (!capped && cost <= characteristic_points).then(|| (kind, weights.weight(kind), cost))
+        });
+        let Some((kind, cost)) = best(candidates) else {
+            break;
+        };
+        stats.get_mut(kind).level += 1;
+        characteristic_points -= cost;
+        if kind == CharacteristicKind::Intellect {
+            skill_points += 1;
+        }
+        plan.push(Allocation::Characteristic(kind));
+    }
+
+    let mut levels: BTreeMap<&str, u32> =
+        skills.iter().map(|s| (s.name.as_str(), s.level)).collect();
+    loop {
+        let candidates = class_skills.iter().filter_map(|(name, skill)| {
+            let bonus = bonuses.get(&skill.dependency).copied().unwrap_or(0);
+            let max_level = (stats.get_level(skill.dependency) as i32 + bonus).max(0) as u32;
+            let level = levels.get(name.as_str()).copied().unwrap_or(0);
+            let cost = level + 1;
+            (level < max_level && cost <= skill_points)
+                .then(|| (name.as_str(), weights.weight(skill.dependency), cost))
+        });
+        let Some((name, cost)) = best(candidates) else {
+            break;
+        };
+        *levels.entry(name).or_insert(0) += 1;
+        skill_points -= cost;
+        plan.push(Allocation::Skill(name.to_string()));
+    }
+
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_allocation_spends_all_points_under_cap() {
+        let weights = AllocationWeights {
+            characteristics: BTreeMap::from([
+                (CharacteristicKind::Magic, 5),
+                (CharacteristicKind::Willpower, 3),
+            ]),
+            characteristic_cap: Some(11),
+        };
+        let class_skills = BTreeMap::from([(
+            "Arcana".to_string(),
+            Skill {
+                dependency: CharacteristicKind::Magic,
+            },
+        )]);
+        let stats = Characteristics::default();
+
+        let plan = plan_allocation(
+            &stats,
+            44,
+            &[],
+            3,
+            &class_skills,
+            &BTreeMap::new(),
+            &weights,
+        );
+
+        let mut spent_stats = stats;
+        let mut char_spent = 0;
+        let mut skill_ranks = 0;
+        for step in &plan {
+            match step {
+                Allocation::Characteristic(kind) => {
+                    let c = spent_stats.get_mut(*kind);
+                    char_spent += c.level + 1;
+                    c.level += 1;
+                }
+                Allocation::Skill(_) => skill_ranks += 1,
+            }
+        }
+        // Each raise from 10 costs 11: Magic first, then Willpower, then the
+        // unweighted ones, since Magic is already at the cap
+        assert_eq!(char_spent, 44);
+        assert_eq!(spent_stats.magic.level, 11);
+        assert_eq!(spent_stats.willpower.level, 11);
+        assert!(CharacteristicKind::iter().all(|k| spent_stats.get_level(k) <= 11));
+        // Arcana 0 -> 1 -> 2 costs 1 + 2 = 3 skill points
+        assert_eq!(skill_ranks, 2);
+    }
+}
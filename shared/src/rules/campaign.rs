@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+
+use super::{CurrencyScheme, EffectModifier, EffectTotal, InitiativeFormula};
+use crate::character::{Character, CharacteristicKind, Effect, HpDelta, Resist, Wallet, Weapon};
+
+fn default_base_mana() -> CharacteristicKind {
+    CharacteristicKind::Willpower
+}
+
+fn default_resale_percent() -> u32 {
+    50
+}
+
+fn default_min_damage() -> u32 {
+    1
+}
+
+/// Campaign-wide options that apply to every character.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignRules {
+    /// Characteristic the base mana formula (`level * 3 + 3`) is built on.
+    #[serde(default = "default_base_mana")]
+    pub base_mana: CharacteristicKind,
+    /// Currency denominations used by wallets.
+    #[serde(default)]
+    pub currency: CurrencyScheme,
+    /// Base of initiative, before `Effect::Initiative` bonuses.
+    #[serde(default)]
+    pub initiative: InitiativeFormula,
+    /// Percentage of an item's value credited when it is sold.
+    #[serde(default = "default_resale_percent")]
+    pub resale_percent: u32,
+    /// House rules adding effects when collected totals cross a threshold.
+    #[serde(default)]
+    pub effect_modifiers: Vec<EffectModifier>,
+    /// Least damage a hit deals after the target's resist is subtracted.
+    #[serde(default = "default_min_damage")]
+    pub min_damage: u32,
+    /// Long rests a character may take per in-game day; unlimited when unset.
+    #[serde(default)]
+    pub long_rests_per_day: Option<u32>,
+}
+
+impl Default for CampaignRules {
+    fn default() -> Self {
+        Self {
+            base_mana: default_base_mana(),
+            currency: CurrencyScheme::default(),
+            initiative: InitiativeFormula::default(),
+            resale_percent: default_resale_percent(),
+            effect_modifiers: Vec::new(),
+            min_damage: default_min_damage(),
+            long_rests_per_day: None,
+        }
+    }
+}
+
+impl CampaignRules {
+    pub fn load_from_str(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_from_file(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::load_from_str(&content)?)
+    }
+
+    /// Amount, in base currency units, credited for selling an item worth `value`.
+    pub fn resale_price(&self, value: Wallet) -> u64 {
+        value.total() * self.resale_percent as u64 / 100
+    }
+
+    /// Max mana: the base formula plus every `Effect::Mana` contribution and
+    /// flat `Effect::MaxMana` bonus, never below 1.
+    /// Mana effects stack, so hybrid casters can draw on several characteristics.
+    /// `level_of` returns the (effective) level of a characteristic.
+    pub fn max_mana(
+        &self,
+        effects: &[Effect],
+        level_of: impl Fn(CharacteristicKind) -> u32,
+    ) -> u32 {
+        let base = (level_of(self.base_mana) * 3 + 3) as i32;
+        let bonus: i32 = effects
+            .iter()
+            .filter_map(|e| match e {
+                Effect::Mana {
+                    dependent,
+                    increase_per_point,
+                } => Some(level_of(*dependent) as i32 * increase_per_point),
+                Effect::MaxMana(v) => Some(*v),
+                _ => None,
+            })
+            .sum();
+        (base + bonus).max(1) as u32
+    }
+
+    /// Whether a character that took `rests_used` long rests today may take another.
+    pub fn long_rest_available(&self, rests_used: u32) -> bool {
+        self.long_rests_per_day
+            .is_none_or(|limit| rests_used < limit)
+    }
+
+    /// Damage left of a `raw` hit of `damage_type` after the target's matching
+    /// resist, never below `min_damage`. Negative resists add damage; a hit of
+    /// 0 stays 0. Requires the target's `active_effects` to be up to date.
+    pub fn resolve_damage(&self, raw: u32, damage_type: Resist, target: &Character) -> u32 {
+        if raw == 0 {
+            return 0;
+        }
+        let resist = EffectTotal::Resist(damage_type).of(&target.active_effects);
+        (raw as i64 - resist as i64).max(self.min_damage.min(raw) as i64) as u32
+    }
+
+    /// HP change for a `raw` hit with `weapon`. Untyped weapons deal `raw` in full.
+    pub fn weapon_hit(&self, weapon: &Weapon, raw: u32, target: &Character) -> HpDelta {
+        let damage = match weapon.damage_type {
+            Some(damage_type) => self.resolve_damage(raw, damage_type, target),
+            None => raw,
+        };
+        HpDelta(-(damage.min(i32::MAX as u32) as i32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Characteristics, WeaponGrip, WeaponKind};
+
+    fn fire_sword() -> Weapon {
+        Weapon {
+            name: "Flame Tongue".to_string(),
+            description: String::new(),
+            damage: "1d8".to_string(),
+            attack: 0,
+            kind: WeaponKind::Melee(crate::character::MeleeKind::Slashing),
+            grip: WeaponGrip::OneHanded,
+            range: 1,
+            effects: vec![],
+            condition: None,
+            value: None,
+            weight: None,
+            damage_type: Some(Resist::Fire),
+        }
+    }
+
+    #[test]
+    fn test_fire_hit_reduced_by_fire_resist() {
+        let mut target = Character::new("Salamander".to_string());
+        target.active_effects = vec![
+            Effect::Resist(Resist::Fire, 3),
+            Effect::Resist(Resist::Ice, 5),
+        ];
+        let rules = CampaignRules::default();
+
+        assert_eq!(rules.weapon_hit(&fire_sword(), 8, &target), HpDelta(-5));
+        // Resist larger than the hit still lets the minimum through
+        assert_eq!(rules.resolve_damage(2, Resist::Fire, &target), 1);
+        // Unresisted type, and untyped weapons, deal full damage
+        assert_eq!(rules.resolve_damage(8, Resist::Poison, &target), 8);
+        let plain = Weapon {
+            damage_type: None,
+            ..fire_sword()
+        };
+        assert_eq!(rules.weapon_hit(&plain, 8, &target), HpDelta(-8));
+
+        let no_floor = CampaignRules::load_from_str(r#"{ "min_damage": 0 }"#).unwrap();
+        assert_eq!(no_floor.resolve_damage(2, Resist::Fire, &target), 0);
+    }
+
+    #[test]
+    fn test_mana_effects_stack() {
+        let mut stats = Characteristics::default();
+        stats.willpower.level = 2;
+        stats.magic.level = 3;
+        let effects = vec![
+            Effect::Mana {
+                dependent: CharacteristicKind::Magic,
+                increase_per_point: 1,
+            },
+            Effect::Mana {
+                dependent: CharacteristicKind::Magic,
+                increase_per_point: 2,
+            },
+        ];
+
+        // 2 * 3 + 3 = 9 base, plus 3 * 1 + 3 * 2 = 9 from the two sources
+        let max = CampaignRules::default().max_mana(&effects, |k| stats.get_level(k));
+        assert_eq!(max, 18);
+    }
+
+    #[test]
+    fn test_willpower_intellect_hybrid_mana() {
+        let mut stats = Characteristics::default();
+        stats.willpower.level = 4;
+        stats.intellect.level = 5;
+        let effects = vec![Effect::Mana {
+            dependent: CharacteristicKind::Intellect,
+            increase_per_point: 2,
+        }];
+
+        // Willpower base: 4 * 3 + 3 = 15, plus Intellect 5 * 2 = 10
+        let willpower_based = CampaignRules::default();
+        assert_eq!(
+            willpower_based.max_mana(&effects, |k| stats.get_level(k)),
+            25
+        );
+
+        // Campaign basing mana on Intellect: 5 * 3 + 3 = 18, plus 10
+        let intellect_based =
+            CampaignRules::load_from_str(r#"{ "base_mana": "Intellect" }"#).unwrap();
+        assert_eq!(
+            intellect_based.max_mana(&effects, |k| stats.get_level(k)),
+            28
+        );
+    }
+}
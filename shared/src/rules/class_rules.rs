@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::AllocationWeights;
+use crate::character::{CharacteristicKind, Class};
+
+/// Baseline skill points granted per level: `base + characteristic level / divisor`.
+/// The default formula grants nothing, leaving skill points to `OnLvlUp` effects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillPointFormula {
+    /// Flat points granted every level.
+    #[serde(default)]
+    pub base: u32,
+    /// Characteristic whose level contributes to the formula, if any.
+    #[serde(default)]
+    pub characteristic: Option<CharacteristicKind>,
+    /// The characteristic level is divided by this value (0 disables the term).
+    #[serde(default)]
+    pub divisor: u32,
+}
+
+impl SkillPointFormula {
+    /// Points granted for a single level-up.
+    /// `level_of` returns the (effective) level of a characteristic.
+    pub fn points_per_level(&self, level_of: impl Fn(CharacteristicKind) -> u32) -> u32 {
+        let scaled = match self.characteristic {
+            Some(kind) if self.divisor > 0 => level_of(kind) / self.divisor,
+            _ => 0,
+        };
+        self.base + scaled
+    }
+}
+
+/// Baseline ability points: `points` for every level divisible by `every`.
+/// The default cadence grants nothing, leaving ability points to `OnLvlUp` effects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbilityPointCadence {
+    #[serde(default)]
+    pub points: u32,
+    /// Level interval (0 disables the cadence).
+    #[serde(default)]
+    pub every: u32,
+}
+
+impl AbilityPointCadence {
+    /// Points granted for every multiple of `every` crossed going from
+    /// `prev_level` to `level`.
+    pub fn points_between(&self, prev_level: u32, level: u32) -> u32 {
+        if self.every == 0 || level <= prev_level {
+            return 0;
+        }
+        (level / self.every - prev_level / self.every) * self.points
+    }
+}
+
+/// Progression rules for a single class.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassRules {
+    #[serde(default)]
+    pub skill_points: SkillPointFormula,
+    #[serde(default)]
+    pub ability_points: AbilityPointCadence,
+    /// Preferences used by auto-allocation (e.g. a Mage favors Magic/Willpower).
+    #[serde(default)]
+    pub allocation: AllocationWeights,
+}
+
+impl ClassRules {
+    /// Baseline skill points granted for `levels` crossed levels,
+    /// applied in addition to `OnLvlUp::AddSkillPoints` effects.
+    pub fn skill_points_for_levels(
+        &self,
+        levels: u32,
+        level_of: impl Fn(CharacteristicKind) -> u32,
+    ) -> u32 {
+        self.skill_points.points_per_level(level_of) * levels
+    }
+}
+
+/// Class rules keyed by class, loaded by the server and shipped to clients.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ClassRulesRegistry {
+    pub classes: BTreeMap<Class, ClassRules>,
+}
+
+impl ClassRulesRegistry {
+    pub fn load_from_str(json: &str) -> Result<Self, serde_json::Error> {
+        let classes: BTreeMap<Class, ClassRules> = serde_json::from_str(json)?;
+        Ok(Self { classes })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_from_file(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::load_from_str(&content)?)
+    }
+
+    pub fn get(&self, class: &Class) -> Option<&ClassRules> {
+        self.classes.get(class)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::Characteristics;
+
+    #[test]
+    fn test_default_formula_grants_nothing() {
+        let rules = ClassRules::default();
+        let stats = Characteristics::default();
+        assert_eq!(rules.skill_points_for_levels(3, |k| stats.get_level(k)), 0);
+    }
+
+    #[test]
+    fn test_class_formula_over_three_levels() {
+        let registry = ClassRulesRegistry::load_from_str(
+            r#"{
+                "Bard": {
+                    "skill_points": { "base": 2, "characteristic": "Intellect", "divisor": 4 }
+                }
+            }"#,
+        )
+        .expect("failed to parse class rules");
+        let rules = registry.get(&Class::Bard).expect("Bard rules not found");
+
+        let mut stats = Characteristics::default();
+        stats.intellect.level = 9;
+
+        // 2 + 9 / 4 = 4 points per level
+        assert_eq!(rules.skill_points_for_levels(3, |k| stats.get_level(k)), 12);
+    }
+
+    #[test]
+    fn test_ability_cadence_counts_crossings_over_level_jump() {
+        let registry = ClassRulesRegistry::load_from_str(
+            r#"{ "Bard": { "ability_points": { "points": 1, "every": 2 } } }"#,
+        )
+        .expect("failed to parse class rules");
+        let rules = registry.get(&Class::Bard).expect("Bard rules not found");
+
+        // Levels 2, 4 and 6 are crossed going from 1 to 6
+        assert_eq!(rules.ability_points.points_between(1, 6), 3);
+        // From 2 to 7 only 4 and 6 count; 2 was granted before
+        assert_eq!(rules.ability_points.points_between(2, 7), 2);
+        assert_eq!(ClassRules::default().ability_points.points_between(1, 6), 0);
+    }
+}
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+
+/// Currency denominations, highest first. `ratios[i]` is how many `names[i + 1]`
+/// make one `names[i]`; the last name is the base unit wallets are stored in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "CurrencyTiers")]
+pub struct CurrencyScheme {
+    pub names: Vec<String>,
+    pub ratios: Vec<u32>,
+}
+
+/// [`CurrencyScheme`] as written in a rules file, before its worths are checked.
+#[derive(Deserialize)]
+struct CurrencyTiers {
+    names: Vec<String>,
+    ratios: Vec<u32>,
+}
+
+impl TryFrom<CurrencyTiers> for CurrencyScheme {
+    type Error = String;
+
+    /// Refuses schemes whose highest denomination is worth more base units
+    /// than a wallet can hold.
+    fn try_from(tiers: CurrencyTiers) -> Result<Self, String> {
+        let scheme = Self {
+            names: tiers.names,
+            ratios: tiers.ratios,
+        };
+        let fits = scheme
+            .ratios()
+            .try_fold(1u64, |value, ratio| value.checked_mul(ratio))
+            .is_some();
+        if fits {
+            Ok(scheme)
+        } else {
+            Err(format!(
+                "currency ratios {:?} overflow the base unit",
+                scheme.ratios
+            ))
+        }
+    }
+}
+
+impl Default for CurrencyScheme {
+    /// Gold / silver / copper: 1 gold = 100 silver, 1 silver = 10 copper.
+    fn default() -> Self {
+        Self {
+            names: vec!["Gold".into(), "Silver".into(), "Copper".into()],
+            ratios: vec![100, 10],
+        }
+    }
+}
+
+impl CurrencyScheme {
+    /// Ratio between each denomination and the next, highest first. Missing
+    /// ratios count as 1.
+    fn ratios(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.names.len().saturating_sub(1))
+            .map(|i| self.ratios.get(i).copied().unwrap_or(1).max(1) as u64)
+    }
+
+    /// Worth of one unit of each denomination in base units, highest first.
+    /// Saturates rather than overflows; loaded schemes never need to.
+    pub fn unit_values(&self) -> Vec<u64> {
+        let ratios: Vec<u64> = self.ratios().collect();
+        let mut values = vec![1u64; self.names.len()];
+        for (i, ratio) in ratios.into_iter().enumerate().rev() {
+            values[i] = values[i + 1].saturating_mul(ratio);
+        }
+        values
+    }
+
+    /// Breaks a base-unit total into per-denomination amounts, highest first.
+    pub fn split(&self, total: u64) -> Vec<u64> {
+        let mut rest = total;
+        self.unit_values()
+            .into_iter()
+            .map(|unit| {
+                let amount = rest / unit;
+                rest %= unit;
+                amount
+            })
+            .collect()
+    }
+
+    /// Human-readable amount, e.g. "1 Gold 5 Copper". Zero shows in the base unit.
+    pub fn format(&self, total: u64) -> String {
+        let parts: Vec<String> = self
+            .split(total)
+            .into_iter()
+            .zip(&self.names)
+            .filter(|(amount, _)| *amount > 0)
+            .map(|(amount, name)| format!("{amount} {name}"))
+            .collect();
+        if parts.is_empty() {
+            let base = self.names.last().map(String::as_str).unwrap_or_default();
+            format!("0 {base}")
+        } else {
+            parts.join(" ")
+        }
+    }
+
+    /// Converts per-denomination amounts (highest first) into a base-unit total.
+    pub fn combine(&self, amounts: &[u64]) -> u64 {
+        self.unit_values()
+            .iter()
+            .zip(amounts)
+            .map(|(unit, amount)| unit * amount)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_scheme_matches_legacy_rates() {
+        let scheme = CurrencyScheme::default();
+        assert_eq!(scheme.unit_values(), vec![1000, 10, 1]);
+        assert_eq!(scheme.split(1234), vec![1, 23, 4]);
+        assert_eq!(scheme.format(1004), "1 Gold 4 Copper");
+        assert_eq!(scheme.format(0), "0 Copper");
+    }
+
+    #[test]
+    fn test_four_tier_scheme_conversion() {
+        let scheme = CurrencyScheme {
+            names: vec![
+                "Platinum".into(),
+                "Gold".into(),
+                "Silver".into(),
+                "Copper".into(),
+            ],
+            ratios: vec![10, 10, 10],
+        };
+        assert_eq!(scheme.unit_values(), vec![1000, 100, 10, 1]);
+        assert_eq!(scheme.split(12_345), vec![12, 3, 4, 5]);
+        assert_eq!(scheme.combine(&[12, 3, 4, 5]), 12_345);
+    }
+
+    #[test]
+    fn test_overflowing_scheme_is_rejected_on_load() {
+        let json =
+            r#"{ "names": ["A", "B", "C", "D"], "ratios": [4294967295, 4294967295, 4294967295] }"#;
+        assert!(serde_json::from_str::<CurrencyScheme>(json).is_err());
+
+        // Built in code instead, its worths stop at the largest wallet
+        let scheme = CurrencyScheme {
+            names: vec!["A".into(), "B".into(), "C".into(), "D".into()],
+            ratios: vec![u32::MAX; 3],
+        };
+        assert_eq!(scheme.unit_values()[0], u64::MAX);
+        assert_eq!(scheme.unit_values()[2], u32::MAX as u64);
+
+        let json = r#"{ "names": ["A", "B"], "ratios": [4294967295] }"#;
+        let scheme: CurrencyScheme = serde_json::from_str(json).unwrap();
+        assert_eq!(scheme.unit_values(), vec![u32::MAX as u64, 1]);
+    }
+}
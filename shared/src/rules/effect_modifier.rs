@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+
+use crate::character::{CharacteristicKind, Effect, Protection, Resist};
+
+/// Aggregated effect total a house rule can test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EffectTotal {
+    Armor,
+    Initiative,
+    Resist(Resist),
+    Protection(Protection),
+    /// Summed `Effect::Characteristic` bonus, not the characteristic's level.
+    Characteristic(CharacteristicKind),
+}
+
+impl EffectTotal {
+    /// Sum of the matching effect magnitudes.
+    pub fn of(self, effects: &[Effect]) -> i32 {
+        effects
+            .iter()
+            .filter_map(|e| match (self, e) {
+                (Self::Armor, Effect::Armor(v)) | (Self::Initiative, Effect::Initiative(v)) => {
+                    Some(*v)
+                }
+                (Self::Resist(a), Effect::Resist(b, v)) if a == *b => Some(*v),
+                (Self::Protection(a), Effect::Protection(b, v)) if a == *b => Some(*v),
+                (Self::Characteristic(a), Effect::Characteristic(b, v)) if a == *b => Some(*v),
+                _ => None,
+            })
+            .sum()
+    }
+}
+
+/// Holds when `total` is strictly above `above` and strictly below `below`
+/// (each bound only checked when set).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EffectCondition {
+    pub total: EffectTotal,
+    #[serde(default)]
+    pub above: Option<i32>,
+    #[serde(default)]
+    pub below: Option<i32>,
+}
+
+impl EffectCondition {
+    pub fn holds(&self, effects: &[Effect]) -> bool {
+        let value = self.total.of(effects);
+        self.above.is_none_or(|above| value > above) && self.below.is_none_or(|below| value < below)
+    }
+}
+
+/// A house rule: extra effects granted while a condition on the collected
+/// effects holds, e.g. "armor above 10 gives -2 initiative".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EffectModifier {
+    pub name: String,
+    pub when: EffectCondition,
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+}
+
+/// Rules whose condition holds for `effects`, in declaration order. Every rule
+/// is checked against the same effects in a single pass, so effects granted by
+/// one rule never trigger another.
+pub fn firing_modifiers<'a>(
+    modifiers: &'a [EffectModifier],
+    effects: &[Effect],
+) -> Vec<&'a EffectModifier> {
+    modifiers.iter().filter(|m| m.when.holds(effects)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::character::{
+        collect_source_effects, EquipmentRegistry, EquipmentSlot, Race, TraitRegistry,
+        WeaponRegistry,
+    };
+
+    #[test]
+    fn test_heavy_armor_rule_reduces_initiative() {
+        let modifiers: Vec<EffectModifier> = serde_json::from_str(
+            r#"[
+                { "name": "Heavy armor", "when": { "total": "Armor", "above": 10 },
+                  "effects": [{ "Initiative": -2 }] },
+                { "name": "Chain reaction", "when": { "total": "Initiative", "below": 0 },
+                  "effects": [{ "Armor": 5 }] }
+            ]"#,
+        )
+        .unwrap();
+        let equipment = EquipmentRegistry::load_from_str(
+            r#"[
+                { "name": "Plate", "description": "", "slot": "Armor", "effects": [{ "Armor": 8 }] },
+                { "name": "Great Helm", "description": "", "slot": "Head",
+                  "effects": [{ "Armor": 3 }] }
+            ]"#,
+        )
+        .unwrap();
+        let collect = |worn: &[(EquipmentSlot, &str)]| {
+            let worn: BTreeMap<EquipmentSlot, Vec<String>> = worn
+                .iter()
+                .map(|(slot, name)| (*slot, vec![name.to_string()]))
+                .collect();
+            collect_source_effects(
+                Race::default(),
+                &[],
+                &[],
+                &worn,
+                &[],
+                &TraitRegistry::default(),
+                &WeaponRegistry::default(),
+                &equipment,
+                &modifiers,
+            )
+        };
+        let base_initiative = EffectTotal::Initiative.of(&collect(&[]));
+
+        // Armor 8: the rule stays quiet
+        let light = collect(&[(EquipmentSlot::Armor, "Plate")]);
+        assert_eq!(EffectTotal::Initiative.of(&light), base_initiative);
+
+        // Armor 11: -2 initiative; the penalty does not trigger the second rule
+        let heavy = collect(&[
+            (EquipmentSlot::Armor, "Plate"),
+            (EquipmentSlot::Head, "Great Helm"),
+        ]);
+        assert_eq!(EffectTotal::Initiative.of(&heavy), base_initiative - 2);
+        assert_eq!(EffectTotal::Armor.of(&heavy), 11);
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+use crate::character::CharacteristicKind;
+
+/// Characteristics a character's initiative is based on.
+/// `Effect::Initiative` bonuses are added on top of the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InitiativeFormula {
+    /// Level of a single characteristic.
+    Characteristic(CharacteristicKind),
+    /// Average of two characteristic levels, rounded down.
+    Average(CharacteristicKind, CharacteristicKind),
+}
+
+impl Default for InitiativeFormula {
+    fn default() -> Self {
+        Self::Characteristic(CharacteristicKind::Perception)
+    }
+}
+
+impl InitiativeFormula {
+    /// Initiative before effects. `level_of` returns the level of a characteristic.
+    pub fn base(&self, level_of: impl Fn(CharacteristicKind) -> u32) -> i32 {
+        match *self {
+            Self::Characteristic(kind) => level_of(kind) as i32,
+            Self::Average(a, b) => ((level_of(a) + level_of(b)) / 2) as i32,
+        }
+    }
+
+    /// Initiative with the summed `Effect::Initiative` bonus applied.
+    pub fn initiative(&self, level_of: impl Fn(CharacteristicKind) -> u32, bonus: i32) -> i32 {
+        self.base(level_of) + bonus
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Character, Effect};
+    use crate::rules::CampaignRules;
+
+    #[test]
+    fn test_dexterity_formula_for_high_dex_character() {
+        let rules =
+            CampaignRules::load_from_str(r#"{ "initiative": { "Characteristic": "Dexterity" } }"#)
+                .expect("failed to parse campaign rules");
+        assert_eq!(
+            rules.initiative,
+            InitiativeFormula::Characteristic(CharacteristicKind::Dexterity)
+        );
+
+        let mut character = Character::new("Quickfoot".to_string());
+        character.stats.dexterity.level = 8;
+        character.stats.perception.level = 2;
+        character.active_effects = vec![Effect::Initiative(1)];
+
+        assert_eq!(character.get_initiative(&rules), 9);
+        assert_eq!(character.get_initiative(&CampaignRules::default()), 3);
+    }
+}
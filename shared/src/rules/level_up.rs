@@ -0,0 +1,140 @@
+//! Experience and the points earned on level-up, shared by the sheet and the
+//! server's bulk XP grants so both level characters identically.
+
+use super::{Ruleset, XpCurve};
+use crate::character::{Character, CharacteristicKind, Class, Effect, OnLvlUp};
+
+/// Unspent point pools a level-up adds to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LevelUpPools {
+    pub ability: u32,
+    pub skill: u32,
+    pub characteristic: u32,
+    pub traits: u32,
+}
+
+impl XpCurve {
+    /// Adds `amount` XP, advancing `level` while enough has been gathered.
+    /// `experience` keeps the XP towards the next level. Returns the levels gained.
+    pub fn gain(&self, level: &mut u32, experience: &mut u32, amount: u32) -> u32 {
+        let start = *level;
+        *experience = experience.saturating_add(amount);
+        loop {
+            let needed = self.to_next_level(*level);
+            if *experience < needed {
+                break;
+            }
+            *experience -= needed;
+            *level += 1;
+        }
+        *level - start
+    }
+}
+
+impl Ruleset {
+    /// Adds the points earned going from `prev_level` to `level`: every
+    /// `OnLvlUp` effect once per level, the class skill-point formula and
+    /// ability-point cadence, 1 trait point every 3 levels and 2
+    /// characteristic points every 5. `level_of` returns effective levels.
+    pub fn apply_level_ups(
+        &self,
+        pools: &mut LevelUpPools,
+        prev_level: u32,
+        level: u32,
+        class: Class,
+        effects: &[Effect],
+        level_of: impl Fn(CharacteristicKind) -> u32,
+    ) {
+        let level_ups = level.saturating_sub(prev_level);
+        if level_ups == 0 {
+            return;
+        }
+        let add = |pool: &mut u32, v: i32| *pool = (*pool as i32 + v).max(0) as u32;
+        for _ in 0..level_ups {
+            for effect in effects {
+                if let Effect::OnLvlUp(on_lvl_up) = effect {
+                    match on_lvl_up {
+                        OnLvlUp::AddAbilityPoints(v) => add(&mut pools.ability, *v),
+                        OnLvlUp::AddSkillPoints(v) => add(&mut pools.skill, *v),
+                        OnLvlUp::AddCharacteristicPoints(v) => add(&mut pools.characteristic, *v),
+                    }
+                }
+            }
+        }
+        // Class baseline skill and ability points, granted on top of OnLvlUp effects.
+        if let Some(rules) = self.classes.get(&class) {
+            pools.skill += rules.skill_points_for_levels(level_ups, level_of);
+            pools.ability += rules.ability_points.points_between(prev_level, level);
+        }
+        pools.traits += level / 3 - prev_level / 3;
+        pools.characteristic += (level / 5 - prev_level / 5) * 2;
+    }
+}
+
+impl Character {
+    /// Adds `amount` XP and applies every level-up it reaches.
+    /// Requires `active_effects` to be up to date. Returns the levels gained.
+    pub fn grant_xp(&mut self, amount: u32, rules: &Ruleset) -> u32 {
+        let prev_level = self.level;
+        let gained = rules.xp.gain(&mut self.level, &mut self.experience, amount);
+        let mut pools = LevelUpPools {
+            ability: self.ability_points,
+            skill: self.skill_points,
+            characteristic: self.characteristic_points,
+            traits: self.trait_points,
+        };
+        rules.apply_level_ups(
+            &mut pools,
+            prev_level,
+            self.level,
+            self.class,
+            &self.active_effects,
+            |k| self.effective_level(k),
+        );
+        self.ability_points = pools.ability;
+        self.skill_points = pools.skill;
+        self.characteristic_points = pools.characteristic;
+        self.trait_points = pools.traits;
+        gained
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::ClassRulesRegistry;
+
+    #[test]
+    fn test_xp_carries_over_several_levels() {
+        let rules = Ruleset {
+            classes: ClassRulesRegistry::load_from_str(
+                r#"{ "Bard": { "skill_points": { "base": 2, "characteristic": "Intellect", "divisor": 4 } } }"#,
+            )
+            .unwrap(),
+            ..Default::default()
+        };
+        let mut character = Character::new("Squire".to_string());
+        character.class = Class::Bard;
+        character.level = 1;
+        character.stats.intellect.level = 9;
+        character.active_effects = vec![Effect::OnLvlUp(OnLvlUp::AddSkillPoints(1))];
+
+        // Level 1 -> 2 needs 20, 2 -> 3 needs 30
+        assert_eq!(character.grant_xp(55, &rules), 2);
+        assert_eq!((character.level, character.experience), (3, 5));
+        // Crossed level 3: one trait point. Each level adds 2 + 9 / 4 class
+        // skill points and one from the OnLvlUp effect
+        assert_eq!(character.trait_points, 1);
+        assert_eq!(character.skill_points, 2 * (4 + 1));
+    }
+
+    #[test]
+    fn test_huge_grant_does_not_overflow() {
+        let rules = Ruleset::default();
+        let mut character = Character::new("Hoarder".to_string());
+        character.level = 1;
+        character.experience = 10;
+        assert!(character.grant_xp(u32::MAX, &rules) > 0);
+        assert!(character.experience < rules.xp.to_next_level(character.level));
+    }
+}
@@ -0,0 +1,25 @@
+mod allocation;
+mod campaign;
+mod class_rules;
+mod currency;
+mod effect_modifier;
+mod initiative;
+mod level_up;
+mod power;
+mod race_rules;
+mod respec;
+mod ruleset;
+mod stacking;
+
+pub use allocation::{plan_allocation, Allocation, AllocationWeights};
+pub use campaign::CampaignRules;
+pub use class_rules::{AbilityPointCadence, ClassRules, ClassRulesRegistry, SkillPointFormula};
+pub use currency::CurrencyScheme;
+pub use effect_modifier::{firing_modifiers, EffectCondition, EffectModifier, EffectTotal};
+pub use initiative::InitiativeFormula;
+pub use level_up::LevelUpPools;
+pub use power::PowerWeights;
+pub use race_rules::{RaceRules, RaceRulesRegistry, DEFAULT_STAT_FLOOR};
+pub use respec::respec_points;
+pub use ruleset::{CreationRules, Ruleset, XpCurve};
+pub use stacking::{Stacked, StackingRules};
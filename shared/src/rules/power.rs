@@ -0,0 +1,168 @@
+//! A rough power score for GMs balancing encounters. Advisory only: no game
+//! rule reads it.
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use super::{EffectTotal, Ruleset};
+use crate::character::{
+    collect_source_effects, Character, CharacterSkill, CharacteristicKind, Characteristics, Effect,
+    EquipmentRegistry, TraitRegistry, WeaponRegistry,
+};
+
+fn default_level_weight() -> u32 {
+    10
+}
+
+fn default_characteristic_weight() -> u32 {
+    1
+}
+
+fn default_skill_rank_weight() -> u32 {
+    2
+}
+
+fn default_armor_weight() -> u32 {
+    3
+}
+
+fn default_effect_weight() -> u32 {
+    2
+}
+
+/// Points each part of a character adds to its power score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerWeights {
+    /// Per character level.
+    #[serde(default = "default_level_weight")]
+    pub level: u32,
+    /// Per effective characteristic level, summed over all characteristics.
+    #[serde(default = "default_characteristic_weight")]
+    pub characteristic: u32,
+    /// Per skill rank, free ranks included.
+    #[serde(default = "default_skill_rank_weight")]
+    pub skill_rank: u32,
+    /// Per point of total armor.
+    #[serde(default = "default_armor_weight")]
+    pub armor: u32,
+    /// Per point of resist, protection, initiative, action point, max HP and
+    /// max mana effects. Penalties count against the score.
+    #[serde(default = "default_effect_weight")]
+    pub effect: u32,
+}
+
+impl Default for PowerWeights {
+    fn default() -> Self {
+        Self {
+            level: default_level_weight(),
+            characteristic: default_characteristic_weight(),
+            skill_rank: default_skill_rank_weight(),
+            armor: default_armor_weight(),
+            effect: default_effect_weight(),
+        }
+    }
+}
+
+impl PowerWeights {
+    /// Scores a character from its parts, `effects` being its active effects.
+    pub fn score(
+        &self,
+        level: u32,
+        stats: &Characteristics,
+        skills: &[CharacterSkill],
+        effects: &[Effect],
+    ) -> u32 {
+        let characteristics: i64 = CharacteristicKind::iter()
+            .map(|kind| {
+                let bonus = EffectTotal::Characteristic(kind).of(effects) as i64;
+                (stats.get_level(kind) as i64 + bonus).max(0)
+            })
+            .sum();
+        let skill_ranks: i64 = skills.iter().map(|s| s.level as i64).sum();
+        let armor = EffectTotal::Armor.of(effects).max(0) as i64;
+        let notable: i64 = effects
+            .iter()
+            .filter_map(|e| match e {
+                Effect::Resist(_, v)
+                | Effect::Protection(_, v)
+                | Effect::Initiative(v)
+                | Effect::ActionPoints(v)
+                | Effect::MaxHp(v)
+                | Effect::MaxMana(v) => Some(*v as i64),
+                _ => None,
+            })
+            .sum();
+        let total = level as i64 * self.level as i64
+            + characteristics * self.characteristic as i64
+            + skill_ranks * self.skill_rank as i64
+            + armor * self.armor as i64
+            + notable * self.effect as i64;
+        total.clamp(0, u32::MAX as i64) as u32
+    }
+}
+
+impl Character {
+    /// Heuristic power score from level, characteristics, skill ranks, armor
+    /// and notable effects, weighted by the ruleset. Effects are collected
+    /// from the registries, so `active_effects` need not be up to date.
+    /// Conditions are left out: they wear off, the character's power does not.
+    pub fn power_score(
+        &self,
+        trait_registry: &TraitRegistry,
+        weapon_registry: &WeaponRegistry,
+        equipment_registry: &EquipmentRegistry,
+        rules: &Ruleset,
+    ) -> u32 {
+        let effects = collect_source_effects(
+            self.race,
+            &self.traits,
+            &self.equipped_weapons,
+            &self.equipped_equipment,
+            &[],
+            trait_registry,
+            weapon_registry,
+            equipment_registry,
+            &rules.campaign.effect_modifiers,
+        );
+        rules
+            .power
+            .score(self.level, &self.stats, &self.skills, &effects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::EquipmentSlot;
+
+    #[test]
+    fn test_stronger_character_scores_higher() {
+        let rules = Ruleset::default();
+        let traits = TraitRegistry::default();
+        let weapons = WeaponRegistry::default();
+        let equipment = EquipmentRegistry::load_from_str(
+            r#"[{ "name": "Plate", "description": "", "slot": "Armor",
+                  "effects": [{ "Armor": 6 }, { "Resist": ["Fire", 2] }] }]"#,
+        )
+        .unwrap();
+        let score = |c: &Character| c.power_score(&traits, &weapons, &equipment, &rules);
+
+        let baseline = Character::new("Recruit".to_string());
+        let mut veteran = baseline.clone();
+        veteran.level = 5;
+        veteran.skills.push(CharacterSkill {
+            name: "Athletics".to_string(),
+            level: 3,
+            free_ranks: 0,
+        });
+        veteran
+            .equipped_equipment
+            .insert(EquipmentSlot::Armor, vec!["Plate".to_string()]);
+
+        assert!(score(&veteran) > score(&baseline));
+        // 5 levels, 3 ranks, 6 armor and 2 resist on top of the baseline
+        assert_eq!(score(&veteran) - score(&baseline), 50 + 6 + 18 + 4);
+        // Same character, same score
+        assert_eq!(score(&veteran), score(&veteran.clone()));
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::character::{CharacteristicKind, Race};
+
+/// Starting level of a characteristic when a race sets no floor for it.
+pub const DEFAULT_STAT_FLOOR: u32 = 1;
+
+/// Character creation rules for a single race.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaceRules {
+    /// Lowest starting level per characteristic. Creation starts every
+    /// characteristic at its floor and cannot lower it further.
+    #[serde(default)]
+    pub stat_floors: BTreeMap<CharacteristicKind, u32>,
+}
+
+impl RaceRules {
+    pub fn stat_floor(&self, kind: CharacteristicKind) -> u32 {
+        self.stat_floors
+            .get(&kind)
+            .copied()
+            .unwrap_or(DEFAULT_STAT_FLOOR)
+    }
+}
+
+/// Race rules keyed by race, loaded by the server and shipped to clients.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RaceRulesRegistry {
+    pub races: BTreeMap<Race, RaceRules>,
+}
+
+impl RaceRulesRegistry {
+    pub fn load_from_str(json: &str) -> Result<Self, serde_json::Error> {
+        let races: BTreeMap<Race, RaceRules> = serde_json::from_str(json)?;
+        Ok(Self { races })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_from_file(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::load_from_str(&content)?)
+    }
+
+    pub fn get(&self, race: &Race) -> Option<&RaceRules> {
+        self.races.get(race)
+    }
+
+    /// Floor of `kind` for `race`; races without rules use the default floor.
+    pub fn stat_floor(&self, race: &Race, kind: CharacteristicKind) -> u32 {
+        self.get(race)
+            .map_or(DEFAULT_STAT_FLOOR, |rules| rules.stat_floor(kind))
+    }
+}
@@ -0,0 +1,121 @@
+//! Undoing spent characteristic and skill points.
+
+use strum::IntoEnumIterator;
+
+use super::Ruleset;
+use crate::character::{
+    Character, CharacterSkill, CharacteristicKind, Characteristics, EquipmentRegistry,
+    TraitRegistry, WeaponRegistry,
+};
+
+/// Resets every characteristic to `floor` and every skill to its free ranks,
+/// adding the points spent on them back to the pools. Raising a
+/// characteristic from N costs N + 1 and a bought skill rank costs its level.
+/// Each Intellect level dropped takes back the skill point its upgrade
+/// granted. Characteristics rolled at creation are refunded as if bought.
+pub fn respec_points(
+    stats: &mut Characteristics,
+    characteristic_points: &mut u32,
+    skills: &mut Vec<CharacterSkill>,
+    skill_points: &mut u32,
+    floor: impl Fn(CharacteristicKind) -> u32,
+) {
+    let intellect_raises = stats
+        .intellect
+        .level
+        .saturating_sub(floor(CharacteristicKind::Intellect));
+    for kind in CharacteristicKind::iter() {
+        let floor = floor(kind);
+        let stat = stats.get_mut(kind);
+        if stat.level > floor {
+            *characteristic_points += (floor + 1..=stat.level).sum::<u32>();
+            stat.level = floor;
+        }
+    }
+
+    *skill_points += skills
+        .iter()
+        .map(|s| s.level.saturating_sub(s.free_ranks))
+        .map(|bought| bought * (bought + 1) / 2)
+        .sum::<u32>();
+    *skill_points = skill_points.saturating_sub(intellect_raises);
+    skills.retain_mut(|s| {
+        s.level = s.free_ranks;
+        s.level > 0
+    });
+}
+
+impl Character {
+    /// Refunds all spent characteristic and skill points (see
+    /// [`respec_points`]) down to the race's floors, then recalculates
+    /// effects. Points earned from levels are kept in the pools.
+    pub fn respec(
+        &mut self,
+        rules: &Ruleset,
+        trait_registry: &TraitRegistry,
+        weapon_registry: &WeaponRegistry,
+        equipment_registry: &EquipmentRegistry,
+    ) {
+        let race = self.race;
+        respec_points(
+            &mut self.stats,
+            &mut self.characteristic_points,
+            &mut self.skills,
+            &mut self.skill_points,
+            |kind| rules.races.stat_floor(&race, kind),
+        );
+        self.recalculate_effects(
+            trait_registry,
+            weapon_registry,
+            equipment_registry,
+            &rules.campaign.effect_modifiers,
+        );
+        self.sync_free_skill_ranks();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_respec_refunds_spent_points() {
+        let rules = Ruleset::default();
+        let mut character = Character::new("Waverer".to_string());
+        for kind in CharacteristicKind::iter() {
+            character.stats.get_mut(kind).level = 1;
+        }
+        character.stats.strength.level = 3;
+        character.stats.intellect.level = 2;
+        character.characteristic_points = 1;
+        character.skills = vec![
+            CharacterSkill {
+                name: "Athletics".to_string(),
+                level: 2,
+                free_ranks: 0,
+            },
+            CharacterSkill {
+                name: "Lore".to_string(),
+                level: 3,
+                free_ranks: 1,
+            },
+        ];
+        character.skill_points = 4;
+
+        character.respec(
+            &rules,
+            &TraitRegistry::default(),
+            &WeaponRegistry::default(),
+            &EquipmentRegistry::default(),
+        );
+
+        assert_eq!(character.stats.strength.level, 1);
+        assert_eq!(character.stats.intellect.level, 1);
+        // 2 + 3 for Strength, 2 for Intellect
+        assert_eq!(character.characteristic_points, 1 + 5 + 2);
+        // 3 + 3 for the bought ranks, less the point the Intellect raise gave
+        assert_eq!(character.skill_points, 4 + 6 - 1);
+        // Lore keeps its free rank only while an effect grants it
+        assert!(character.skills.is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+use super::{CampaignRules, ClassRulesRegistry, PowerWeights, RaceRulesRegistry, StackingRules};
+
+fn default_xp_per_level() -> u32 {
+    10
+}
+
+/// XP needed to advance from `level` to `level + 1`: `base + (level + 1) * per_level`,
+/// never below 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct XpCurve {
+    #[serde(default)]
+    pub base: u32,
+    #[serde(default = "default_xp_per_level")]
+    pub per_level: u32,
+}
+
+impl Default for XpCurve {
+    fn default() -> Self {
+        Self {
+            base: 0,
+            per_level: default_xp_per_level(),
+        }
+    }
+}
+
+impl XpCurve {
+    pub fn to_next_level(&self, level: u32) -> u32 {
+        (self.base + (level + 1) * self.per_level).max(1)
+    }
+}
+
+fn default_characteristic_points() -> u32 {
+    18
+}
+
+fn default_skill_points() -> u32 {
+    10
+}
+
+fn default_skill_points_per_intellect() -> u32 {
+    3
+}
+
+/// Point budgets of a new character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreationRules {
+    /// Characteristic points to spend on top of the race floors.
+    #[serde(default = "default_characteristic_points")]
+    pub characteristic_points: u32,
+    /// Skill points before the Intellect bonus.
+    #[serde(default = "default_skill_points")]
+    pub skill_points: u32,
+    /// Extra skill points per Intellect level.
+    #[serde(default = "default_skill_points_per_intellect")]
+    pub skill_points_per_intellect: u32,
+}
+
+impl Default for CreationRules {
+    fn default() -> Self {
+        Self {
+            characteristic_points: default_characteristic_points(),
+            skill_points: default_skill_points(),
+            skill_points_per_intellect: default_skill_points_per_intellect(),
+        }
+    }
+}
+
+impl CreationRules {
+    /// Starting skill points for a character with the given Intellect level.
+    pub fn skill_points_for(&self, intellect: u32) -> u32 {
+        self.skill_points + self.skill_points_per_intellect * intellect
+    }
+}
+
+/// Every tunable rule of a campaign, loaded from one file by the server and
+/// shipped to clients in a single message. Missing sections keep their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ruleset {
+    #[serde(default)]
+    pub xp: XpCurve,
+    #[serde(default)]
+    pub creation: CreationRules,
+    #[serde(default)]
+    pub classes: ClassRulesRegistry,
+    #[serde(default)]
+    pub races: RaceRulesRegistry,
+    /// Mana, currency, initiative, resale and effect house rules.
+    #[serde(default)]
+    pub campaign: CampaignRules,
+    /// Weights of the advisory power score shown to GMs.
+    #[serde(default)]
+    pub power: PowerWeights,
+    /// Caps on stacked effect bonuses; nothing is capped unless configured.
+    #[serde(default)]
+    pub stacking: StackingRules,
+}
+
+impl Ruleset {
+    pub fn load_from_str(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_from_file(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::load_from_str(&content)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::Class;
+
+    #[test]
+    fn test_ruleset_overrides_xp_curve_and_creation_points() {
+        let ruleset = Ruleset::load_from_str(
+            r#"{
+                "xp": { "per_level": 25 },
+                "creation": { "characteristic_points": 24 },
+                "classes": { "Bard": { "ability_points": { "points": 1, "every": 2 } } }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(ruleset.xp.to_next_level(1), 50);
+        assert_eq!(ruleset.creation.characteristic_points, 24);
+        // Unset fields and sections keep the defaults
+        assert_eq!(ruleset.creation.skill_points_for(2), 16);
+        assert!(ruleset.classes.get(&Class::Bard).is_some());
+        assert_eq!(ruleset.campaign, CampaignRules::default());
+
+        let defaults = Ruleset::default();
+        assert_eq!(defaults.xp.to_next_level(1), 20);
+        assert_eq!(defaults.creation.characteristic_points, 18);
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+/// Ceilings on how far effects of one kind stack. An unset cap leaves the
+/// summed bonuses unbounded, which is the default for every kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StackingRules {
+    /// Highest total of each resist.
+    #[serde(default)]
+    pub resist: Option<i32>,
+    /// Highest total of each protection.
+    #[serde(default)]
+    pub protection: Option<i32>,
+    /// Highest summed `Effect::Characteristic` bonus to one characteristic.
+    #[serde(default)]
+    pub characteristic: Option<i32>,
+    /// Highest summed `Effect::Skill` bonus to one skill.
+    #[serde(default)]
+    pub skill: Option<i32>,
+}
+
+/// A stacked effect total after its cap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stacked {
+    pub value: i32,
+    /// The total before the cap, when the cap clipped it.
+    pub uncapped: Option<i32>,
+}
+
+impl Stacked {
+    /// `total` clipped to `cap`, if there is one.
+    pub fn new(total: i32, cap: Option<i32>) -> Self {
+        match cap {
+            Some(cap) if total > cap => Self {
+                value: cap,
+                uncapped: Some(total),
+            },
+            _ => Self {
+                value: total,
+                uncapped: None,
+            },
+        }
+    }
+
+    pub fn is_capped(&self) -> bool {
+        self.uncapped.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Character, Effect, Resist};
+
+    #[test]
+    fn test_resists_stop_at_cap() {
+        let mut character = Character::new("Ringbearer".to_string());
+        character.active_effects = vec![Effect::Resist(Resist::Fire, 5); 10];
+        character
+            .active_effects
+            .push(Effect::Resist(Resist::Ice, -3));
+
+        let uncapped = character.get_resists(&StackingRules::default());
+        assert_eq!(uncapped[&Resist::Fire], Stacked::new(50, None));
+
+        let stacking = StackingRules {
+            resist: Some(20),
+            ..StackingRules::default()
+        };
+        let capped = character.get_resists(&stacking);
+        assert_eq!(capped[&Resist::Fire].value, 20);
+        assert_eq!(capped[&Resist::Fire].uncapped, Some(50));
+        // Penalties and totals under the cap are untouched
+        assert!(!capped[&Resist::Ice].is_capped());
+        assert_eq!(capped[&Resist::Ice].value, -3);
+        assert!(!capped[&Resist::Lightning].is_capped());
+    }
+}
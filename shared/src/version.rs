@@ -1,7 +1,11 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
 use uuid::Uuid;
 
-use crate::character::{Character, Class, Race};
+use crate::character::{Character, CharacteristicKind, Class, InventoryItem, Race};
 
 /// Unix timestamp in seconds (UTC).
 pub type Timestamp = i64;
@@ -15,6 +19,223 @@ pub struct CharacterVersion {
     pub saved_at: Timestamp,
     /// The full character data at this point in time.
     pub character: Character,
+    /// Identity of the authenticated connection that saved this version
+    /// (`None` for anonymous saves).
+    #[serde(default)]
+    pub saved_by: Option<String>,
+    /// Player annotation such as "before the boss fight".
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Character fields that only change how the sheet looks or is organised.
+/// Saves that differ from the latest version only in these fields update it
+/// in place instead of adding a version.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Display, EnumIter, EnumString,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum CosmeticField {
+    AccentColor,
+    Hotbar,
+    Notes,
+}
+
+impl CosmeticField {
+    pub fn all() -> BTreeSet<Self> {
+        Self::iter().collect()
+    }
+}
+
+/// Whether `new` differs from `old` in anything other than the `cosmetic`
+/// fields. Derived `active_effects` are ignored.
+pub fn significant_change(
+    old: &Character,
+    new: &Character,
+    cosmetic: &BTreeSet<CosmeticField>,
+) -> bool {
+    let mut new = new.clone();
+    new.active_effects.clone_from(&old.active_effects);
+    for field in cosmetic {
+        match field {
+            CosmeticField::AccentColor => new.accent_color = old.accent_color,
+            CosmeticField::Hotbar => new.hotbar.clone_from(&old.hotbar),
+            CosmeticField::Notes => new.notes.clone_from(&old.notes),
+        }
+    }
+    *old != new
+}
+
+/// One field-level change between two versions of a character.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiffEntry {
+    Level {
+        from: u32,
+        to: u32,
+    },
+    Stat {
+        kind: CharacteristicKind,
+        from: u32,
+        to: u32,
+    },
+    SkillAdded {
+        name: String,
+        level: u32,
+    },
+    SkillRemoved {
+        name: String,
+        level: u32,
+    },
+    SkillChanged {
+        name: String,
+        from: u32,
+        to: u32,
+    },
+    TraitAdded(String),
+    TraitRemoved(String),
+    AbilityAdded(String),
+    AbilityRemoved(String),
+    ItemAdded(InventoryItem),
+    ItemRemoved(InventoryItem),
+    /// Units in a stack of plain items changed.
+    ItemCount {
+        name: String,
+        from: u32,
+        to: u32,
+    },
+}
+
+impl fmt::Display for DiffEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffEntry::Level { from, to } => write!(f, "Level {from} → {to}"),
+            DiffEntry::Stat { kind, from, to } => write!(f, "{kind} {from} → {to}"),
+            DiffEntry::SkillAdded { name, level } => write!(f, "+ Skill {name} ({level})"),
+            DiffEntry::SkillRemoved { name, level } => write!(f, "- Skill {name} ({level})"),
+            DiffEntry::SkillChanged { name, from, to } => {
+                write!(f, "Skill {name} {from} → {to}")
+            }
+            DiffEntry::TraitAdded(name) => write!(f, "+ Trait {name}"),
+            DiffEntry::TraitRemoved(name) => write!(f, "- Trait {name}"),
+            DiffEntry::AbilityAdded(name) => write!(f, "+ Ability {name}"),
+            DiffEntry::AbilityRemoved(name) => write!(f, "- Ability {name}"),
+            DiffEntry::ItemAdded(item) => write!(f, "+ {}", item.name()),
+            DiffEntry::ItemRemoved(item) => write!(f, "- {}", item.name()),
+            DiffEntry::ItemCount { name, from, to } => write!(f, "{name} ×{from} → ×{to}"),
+        }
+    }
+}
+
+/// Changes that turn one version of a character into another, see [`diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharacterDiff {
+    pub changes: Vec<DiffEntry>,
+}
+
+impl CharacterDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Field-level changes from `a` to `b`: level, characteristics, skills,
+/// traits, abilities and inventory, in that order.
+pub fn diff(a: &Character, b: &Character) -> CharacterDiff {
+    let mut changes = Vec::new();
+    if a.level != b.level {
+        changes.push(DiffEntry::Level {
+            from: a.level,
+            to: b.level,
+        });
+    }
+    for kind in CharacteristicKind::iter() {
+        let (from, to) = (a.stats.get_level(kind), b.stats.get_level(kind));
+        if from != to {
+            changes.push(DiffEntry::Stat { kind, from, to });
+        }
+    }
+
+    let skills = |c: &Character| -> BTreeMap<String, u32> {
+        c.skills.iter().map(|s| (s.name.clone(), s.level)).collect()
+    };
+    let (old_skills, new_skills) = (skills(a), skills(b));
+    for (name, &level) in &old_skills {
+        match new_skills.get(name) {
+            None => changes.push(DiffEntry::SkillRemoved {
+                name: name.clone(),
+                level,
+            }),
+            Some(&to) if to != level => changes.push(DiffEntry::SkillChanged {
+                name: name.clone(),
+                from: level,
+                to,
+            }),
+            Some(_) => {}
+        }
+    }
+    for (name, &level) in &new_skills {
+        if !old_skills.contains_key(name) {
+            changes.push(DiffEntry::SkillAdded {
+                name: name.clone(),
+                level,
+            });
+        }
+    }
+
+    let (removed, added) = list_changes(&a.traits, &b.traits);
+    changes.extend(removed.into_iter().map(DiffEntry::TraitRemoved));
+    changes.extend(added.into_iter().map(DiffEntry::TraitAdded));
+    let (removed, added) = list_changes(&a.abilities, &b.abilities);
+    changes.extend(removed.into_iter().map(DiffEntry::AbilityRemoved));
+    changes.extend(added.into_iter().map(DiffEntry::AbilityAdded));
+    let (removed, added) = list_changes(&a.inventory, &b.inventory);
+    changes.extend(removed.into_iter().map(DiffEntry::ItemRemoved));
+    changes.extend(added.into_iter().map(DiffEntry::ItemAdded));
+
+    // Stacks only count while the item is carried in both versions
+    let carried = |c: &Character| -> BTreeSet<String> {
+        c.inventory
+            .iter()
+            .filter_map(|item| match item {
+                InventoryItem::Item(name) => Some(name.clone()),
+                _ => None,
+            })
+            .collect()
+    };
+    for name in carried(a).intersection(&carried(b)) {
+        let count = |c: &Character| c.item_counts.get(name).copied().unwrap_or(1);
+        let (from, to) = (count(a), count(b));
+        if from != to {
+            changes.push(DiffEntry::ItemCount {
+                name: name.clone(),
+                from,
+                to,
+            });
+        }
+    }
+
+    CharacterDiff { changes }
+}
+
+/// Entries of `old` missing from `new` and entries of `new` missing from
+/// `old`, counting duplicates.
+fn list_changes<T: Ord + Clone>(old: &[T], new: &[T]) -> (Vec<T>, Vec<T>) {
+    let mut counts: BTreeMap<&T, i64> = BTreeMap::new();
+    for entry in old {
+        *counts.entry(entry).or_default() -= 1;
+    }
+    for entry in new {
+        *counts.entry(entry).or_default() += 1;
+    }
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for (entry, count) in counts {
+        let target = if count < 0 { &mut removed } else { &mut added };
+        for _ in 0..count.unsigned_abs() {
+            target.push(entry.clone());
+        }
+    }
+    (removed, added)
 }
 
 /// The on-disk format for a single character's file.
@@ -25,6 +246,10 @@ pub struct CharacterFile {
     pub id: Uuid,
     /// Versions ordered oldest-first. The last element is the latest version.
     pub versions: Vec<CharacterVersion>,
+    /// Salted hash of the password needed to edit this character, if the
+    /// creator set one. The admin password overrides it.
+    #[serde(default)]
+    pub owner_hash: Option<String>,
 }
 
 /// Lightweight summary sent to clients for the character list screen.
@@ -32,12 +257,32 @@ pub struct CharacterFile {
 pub struct CharacterSummary {
     pub id: Uuid,
     pub name: String,
+    #[serde(default)]
+    pub accent_color: Option<[u8; 3]>,
     pub race: Race,
     pub class: Class,
     pub level: u32,
     pub version_count: u32,
+    /// Number of the newest version.
+    #[serde(default)]
+    pub latest_version: u32,
+    /// Advisory power score of the latest version, see `Character::power_score`.
+    #[serde(default)]
+    pub power_score: u32,
     /// Timestamp of the most recent version.
     pub last_updated: Timestamp,
+    /// Free-form labels kept outside the versioned character.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Edits need the character's password, see `CharacterFile::owner_hash`.
+    #[serde(default)]
+    pub protected: bool,
+}
+
+impl CharacterSummary {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
 }
 
 /// Lightweight info about a single version, sent when the client asks for
@@ -47,4 +292,152 @@ pub struct VersionSummary {
     pub version: u32,
     pub saved_at: Timestamp,
     pub level: u32,
+    pub saved_by: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::CharacterSkill;
+
+    fn skill(name: &str, level: u32) -> CharacterSkill {
+        CharacterSkill {
+            level,
+            ..CharacterSkill::new(name.to_string())
+        }
+    }
+
+    #[test]
+    fn test_diff_of_identical_versions_is_empty() {
+        let character = Character::new("Same".to_string());
+        assert!(diff(&character, &character.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_level_and_stats() {
+        let old = Character::new("Hero".to_string());
+        let mut new = old.clone();
+        new.level = 3;
+        new.stats.strength.level = 12;
+        new.stats.charisma.level = 9;
+
+        assert_eq!(
+            diff(&old, &new).changes,
+            vec![
+                DiffEntry::Level { from: 0, to: 3 },
+                DiffEntry::Stat {
+                    kind: CharacteristicKind::Strength,
+                    from: 10,
+                    to: 12
+                },
+                DiffEntry::Stat {
+                    kind: CharacteristicKind::Charisma,
+                    from: 10,
+                    to: 9
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_skills_added_removed_and_changed() {
+        let mut old = Character::new("Hero".to_string());
+        old.skills = vec![skill("Stealth", 2), skill("Athletics", 1)];
+        let mut new = old.clone();
+        new.skills = vec![skill("Stealth", 4), skill("Lore", 1)];
+
+        assert_eq!(
+            diff(&old, &new).changes,
+            vec![
+                DiffEntry::SkillRemoved {
+                    name: "Athletics".to_string(),
+                    level: 1
+                },
+                DiffEntry::SkillChanged {
+                    name: "Stealth".to_string(),
+                    from: 2,
+                    to: 4
+                },
+                DiffEntry::SkillAdded {
+                    name: "Lore".to_string(),
+                    level: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_traits_and_abilities() {
+        let mut old = Character::new("Hero".to_string());
+        old.traits = vec!["Brave".to_string()];
+        old.abilities = vec!["Fireball".to_string()];
+        let mut new = old.clone();
+        new.traits = vec!["Cunning".to_string()];
+        new.abilities = vec!["Fireball".to_string(), "Heal".to_string()];
+
+        assert_eq!(
+            diff(&old, &new).changes,
+            vec![
+                DiffEntry::TraitRemoved("Brave".to_string()),
+                DiffEntry::TraitAdded("Cunning".to_string()),
+                DiffEntry::AbilityAdded("Heal".to_string()),
+            ]
+        );
+        // Reordering alone is not a change
+        let mut reordered = new.clone();
+        reordered.abilities.reverse();
+        assert!(diff(&new, &reordered).is_empty());
+    }
+
+    #[test]
+    fn test_diff_inventory_counts_duplicates_and_stacks() {
+        let sword = InventoryItem::Weapon("Sword".to_string());
+        let rope = InventoryItem::Item("Rope".to_string());
+        let mut old = Character::new("Hero".to_string());
+        old.inventory = vec![sword.clone(), rope.clone()];
+        let mut new = old.clone();
+        new.inventory = vec![sword.clone(), sword.clone(), rope.clone()];
+        new.item_counts.insert("Rope".to_string(), 3);
+
+        assert_eq!(
+            diff(&old, &new).changes,
+            vec![
+                DiffEntry::ItemAdded(sword.clone()),
+                DiffEntry::ItemCount {
+                    name: "Rope".to_string(),
+                    from: 1,
+                    to: 3
+                },
+            ]
+        );
+
+        // A dropped stack is a removal, not a count change
+        let mut dropped = new.clone();
+        dropped.inventory = vec![sword.clone(), sword];
+        assert_eq!(
+            diff(&new, &dropped).changes,
+            vec![DiffEntry::ItemRemoved(rope)]
+        );
+    }
+
+    #[test]
+    fn test_diff_entries_read_as_changelog_lines() {
+        let entries = [
+            DiffEntry::Level { from: 1, to: 2 },
+            DiffEntry::Stat {
+                kind: CharacteristicKind::Dexterity,
+                from: 10,
+                to: 11,
+            },
+            DiffEntry::TraitRemoved("Brave".to_string()),
+            DiffEntry::ItemAdded(InventoryItem::Item("Rope".to_string())),
+        ];
+        let lines: Vec<String> = entries.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            vec!["Level 1 → 2", "DEX 10 → 11", "- Trait Brave", "+ Rope"]
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use crate::colors::STROKE_COLOR;
+use crate::colors::palette;
 use crate::egui::{self, Align, Align2, Rect};
 use crate::traits::WithText;
 
@@ -24,7 +24,7 @@ impl Text {
     pub fn new(content: impl Into<String>) -> Self {
         Self {
             content: content.into(),
-            color: STROKE_COLOR,
+            color: palette().stroke,
             size: 14.0,
             align: Align2::CENTER_CENTER,
             angle: 0.0,
@@ -1,14 +1,91 @@
+use std::sync::{PoisonError, RwLock};
+
 use crate::egui::Color32;
 
-pub const MAIN_COLOR: Color32 = Color32::from_rgb(0xFF, 0xFF, 0xFF);
-pub const SECONDARY_COLOR: Color32 = Color32::from_rgb(0xF5, 0xF5, 0xFA);
 pub const HP_COLOR: Color32 = Color32::from_rgb(0xFA, 0x32, 0x4D);
-pub const HP_SPENT_COLOR: Color32 = Color32::from_rgb(0xFA, 0xC8, 0xD0);
+pub const TEMP_HP_COLOR: Color32 = Color32::from_rgb(0xFA, 0xB4, 0x32);
 pub const MP_COLOR: Color32 = Color32::from_rgb(0x32, 0x75, 0xFA);
-pub const MP_SPENT_COLOR: Color32 = Color32::from_rgb(0xC8, 0xD9, 0xFA);
 pub const AP_COLOR: Color32 = Color32::from_rgb(0x32, 0xFA, 0xBE);
-pub const AP_SPENT_COLOR: Color32 = Color32::from_rgb(0xC8, 0xFA, 0xEB);
-pub const STROKE_COLOR: Color32 = Color32::from_rgb(0xDD, 0xDD, 0xEB);
-pub const TEXT_COLOR: Color32 = Color32::from_rgb(0x1D, 0x1D, 0x1F);
-pub const UPGRADE_COLOR: Color32 = Color32::from_rgb(0xD4, 0xFA, 0xD4);
-pub const ERROR_COLOR: Color32 = Color32::from_rgb(0xFA, 0xD4, 0xD4);
+pub const CAP_PROGRESS_COLOR: Color32 = Color32::from_rgb(0x8C, 0xA0, 0xDC);
+pub const CAP_REACHED_COLOR: Color32 = Color32::from_rgb(0xFA, 0xB4, 0x32);
+
+/// Color scheme of the whole UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub fn palette(self) -> ThemePalette {
+        match self {
+            Theme::Light => ThemePalette::LIGHT,
+            Theme::Dark => ThemePalette::DARK,
+        }
+    }
+
+    /// The other theme.
+    pub fn toggled(self) -> Self {
+        match self {
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::Light,
+        }
+    }
+}
+
+/// Colors that change with the [`Theme`]. Resource bar colors stay the same.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThemePalette {
+    /// Whether egui's dark defaults apply underneath.
+    pub dark_mode: bool,
+    pub main: Color32,
+    pub secondary: Color32,
+    pub stroke: Color32,
+    pub text: Color32,
+    pub hp_spent: Color32,
+    pub mp_spent: Color32,
+    pub ap_spent: Color32,
+    pub upgrade: Color32,
+    pub error: Color32,
+}
+
+impl ThemePalette {
+    pub const LIGHT: Self = Self {
+        dark_mode: false,
+        main: Color32::from_rgb(0xFF, 0xFF, 0xFF),
+        secondary: Color32::from_rgb(0xF5, 0xF5, 0xFA),
+        stroke: Color32::from_rgb(0xDD, 0xDD, 0xEB),
+        text: Color32::from_rgb(0x1D, 0x1D, 0x1F),
+        hp_spent: Color32::from_rgb(0xFA, 0xC8, 0xD0),
+        mp_spent: Color32::from_rgb(0xC8, 0xD9, 0xFA),
+        ap_spent: Color32::from_rgb(0xC8, 0xFA, 0xEB),
+        upgrade: Color32::from_rgb(0xD4, 0xFA, 0xD4),
+        error: Color32::from_rgb(0xFA, 0xD4, 0xD4),
+    };
+
+    pub const DARK: Self = Self {
+        dark_mode: true,
+        main: Color32::from_rgb(0x1E, 0x1E, 0x24),
+        secondary: Color32::from_rgb(0x27, 0x27, 0x2F),
+        stroke: Color32::from_rgb(0x3C, 0x3C, 0x4A),
+        text: Color32::from_rgb(0xE6, 0xE6, 0xEC),
+        hp_spent: Color32::from_rgb(0x5A, 0x2A, 0x33),
+        mp_spent: Color32::from_rgb(0x2A, 0x38, 0x5A),
+        ap_spent: Color32::from_rgb(0x2A, 0x55, 0x4A),
+        upgrade: Color32::from_rgb(0x2E, 0x4D, 0x2E),
+        error: Color32::from_rgb(0x55, 0x2E, 0x2E),
+    };
+}
+
+static PALETTE: RwLock<ThemePalette> = RwLock::new(ThemePalette::LIGHT);
+
+/// The palette every widget paints with, switched by
+/// [`UiStyle::apply_global_style`](crate::styles::UiStyle::apply_global_style).
+pub fn palette() -> ThemePalette {
+    *PALETTE.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub(crate) fn set_palette(palette: ThemePalette) {
+    *PALETTE.write().unwrap_or_else(PoisonError::into_inner) = palette;
+}
@@ -1,3 +1,4 @@
+use crate::colors::palette;
 use crate::egui::{self, CornerRadius, TextureId};
 use crate::molecules::{AbilityCard, TitledBox};
 use crate::styles::UiStyle;
@@ -14,26 +15,36 @@ pub struct AbilityEntry {
     pub ability_type: String,
     pub check: String,
     pub enemy_check: String,
+    /// Why the ability can't be used now (cooldown, no uses left); the card
+    /// is grayed out and can't be activated.
+    pub unavailable: Option<String>,
+}
+
+/// What the user did with the ability cards.
+#[derive(Default)]
+pub struct AbilitiesResponse {
+    /// Ability whose card was clicked.
+    pub activated: Option<String>,
+    /// Ability whose check was rolled.
+    pub roll: Option<String>,
 }
 
 /// Displays the character's learned abilities as a 2-column grid of cards.
 ///
-/// Returns `Some(new_mp)` when an ability is clicked and enough mana is available.
+/// Clicking a card activates the ability unless it is unavailable; cards with
+/// a check get a dice button that rolls it. Cards can be dragged onto a `Hotbar`, carrying the
+/// ability name as the drag payload.
 pub struct Abilities {
     entries: Vec<AbilityEntry>,
-    current_mp: u32,
 }
 
 impl Abilities {
-    pub fn new(entries: Vec<AbilityEntry>, current_mp: u32) -> Self {
-        Self {
-            entries,
-            current_mp,
-        }
+    pub fn new(entries: Vec<AbilityEntry>) -> Self {
+        Self { entries }
     }
 
-    pub fn show(self, ui: &mut egui::Ui) -> Option<u32> {
-        let mut result = None;
+    pub fn show(self, ui: &mut egui::Ui) -> AbilitiesResponse {
+        let mut result = AbilitiesResponse::default();
 
         TitledBox::new("Abilities")
             .rounding(CornerRadius::same(16))
@@ -63,7 +74,7 @@ impl Abilities {
                             egui::Sense::hover(),
                         );
                         let origin = content_rect.min;
-                        let painter = ui.painter();
+                        let painter = ui.painter().clone();
 
                         for (i, entry) in self.entries.into_iter().enumerate() {
                             let col = i % cols;
@@ -75,8 +86,9 @@ impl Abilities {
                                 egui::vec2(card_width, card_height),
                             );
 
-                            let mp_cost = entry.mp_cost.unwrap_or(0);
-
+                            let name = entry.name.clone();
+                            let has_check = !entry.check.is_empty();
+                            let unavailable = entry.unavailable;
                             AbilityCard::new(entry.image, entry.description)
                                 .name(entry.name)
                                 .mp_cost(entry.mp_cost)
@@ -86,13 +98,43 @@ impl Abilities {
                                 .ability_type(entry.ability_type)
                                 .check(entry.check)
                                 .enemy_check(entry.enemy_check)
-                                .paint(painter, rect);
+                                .paint(&painter, rect);
 
                             let card_id = ui.id().with("ability_card").with(i);
-                            let response = ui.interact(rect, card_id, egui::Sense::click());
-                            if response.clicked() && self.current_mp >= mp_cost {
-                                result = Some(self.current_mp - mp_cost);
+                            let mut response =
+                                ui.interact(rect, card_id, egui::Sense::click_and_drag());
+                            match &unavailable {
+                                Some(reason) => {
+                                    painter.rect_filled(
+                                        rect,
+                                        CornerRadius::same(12),
+                                        palette().secondary.gamma_multiply(0.75),
+                                    );
+                                    response = response.on_hover_text(reason);
+                                }
+                                None => {
+                                    if response.clicked() {
+                                        result.activated = Some(name.clone());
+                                    }
+                                }
+                            }
+                            if has_check {
+                                let size = egui::vec2(22.0, 22.0);
+                                let button_rect = egui::Rect::from_min_size(
+                                    egui::pos2(
+                                        rect.max.x - pad * 0.5 - size.x,
+                                        rect.min.y + pad * 0.5,
+                                    ),
+                                    size,
+                                );
+                                let roll = ui
+                                    .put(button_rect, egui::Button::new("🎲").small())
+                                    .on_hover_text("Roll check");
+                                if roll.clicked() {
+                                    result.roll = Some(name.clone());
+                                }
                             }
+                            response.dnd_set_drag_payload(name);
                         }
                     });
             });
@@ -1,5 +1,5 @@
 use crate::atoms::{Shape, ShapeBox, Text};
-use crate::colors::{MAIN_COLOR, STROKE_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, Align2, CornerRadius, Stroke, Widget};
 use crate::molecules::TitledBox;
 use crate::traits::Roundable;
@@ -53,7 +53,7 @@ impl Widget for Traits {
 
                         // Card background (no stroke)
                         ShapeBox::new(Shape::Rectangle)
-                            .fill(MAIN_COLOR)
+                            .fill(palette().main)
                             .stroke(Stroke::NONE)
                             .set_rounding(CornerRadius::same(12))
                             .paint(painter, rect);
@@ -68,7 +68,7 @@ impl Widget for Traits {
                             egui::vec2(inner.width(), name_height),
                         );
                         Text::new(&entry.name)
-                            .color(TEXT_COLOR)
+                            .color(palette().text)
                             .size(16.0)
                             .bold()
                             .align(Align2::LEFT_TOP)
@@ -82,11 +82,11 @@ impl Widget for Traits {
                         let galley = painter.layout(
                             entry.description.clone(),
                             font,
-                            TEXT_COLOR,
+                            palette().text,
                             desc_rect.width(),
                         );
                         let clipped = painter.with_clip_rect(desc_rect);
-                        clipped.galley(desc_rect.min, galley, TEXT_COLOR);
+                        clipped.galley(desc_rect.min, galley, palette().text);
 
                         // Effects tooltip on hover
                         if response.hovered() && !entry.effects.is_empty() {
@@ -97,8 +97,8 @@ impl Widget for Traits {
                                 .fixed_pos(tooltip_pos)
                                 .show(ui.ctx(), |ui| {
                                     egui::Frame::NONE
-                                        .fill(MAIN_COLOR)
-                                        .stroke(Stroke::new(0.5, STROKE_COLOR))
+                                        .fill(palette().main)
+                                        .stroke(Stroke::new(0.5, palette().stroke))
                                         .corner_radius(CornerRadius::same(8))
                                         .inner_margin(8.0)
                                         .show(ui, |ui| {
@@ -106,14 +106,14 @@ impl Widget for Traits {
                                                 egui::RichText::new(&entry.name)
                                                     .strong()
                                                     .size(14.0)
-                                                    .color(TEXT_COLOR),
+                                                    .color(palette().text),
                                             );
                                             ui.separator();
                                             for effect in &entry.effects {
                                                 ui.label(
                                                     egui::RichText::new(effect.as_str())
                                                         .size(12.0)
-                                                        .color(TEXT_COLOR),
+                                                        .color(palette().text),
                                                 );
                                             }
                                         });
@@ -1,8 +1,9 @@
 use std::cell::Cell;
 
 use crate::atoms::{Shape, ShapeBox, Text};
-use crate::colors::{MAIN_COLOR, SECONDARY_COLOR, TEXT_COLOR, UPGRADE_COLOR};
+use crate::colors::{palette, CAP_PROGRESS_COLOR, CAP_REACHED_COLOR};
 use crate::egui::{self, Align2, CornerRadius, Rect, Stroke};
+use crate::molecules::ProgressBar;
 use crate::traits::{Roundable, WithText};
 
 /// Action returned by grid widgets (Characteristics, Skills) in edit mode.
@@ -10,6 +11,8 @@ use crate::traits::{Roundable, WithText};
 pub enum GridAction {
     Upgrade(usize),
     Downgrade(usize),
+    /// Clicked outside edit mode, e.g. to roll a check.
+    Roll(usize),
 }
 
 /// Displays the character's primary characteristics as 8 boxes
@@ -25,6 +28,7 @@ pub struct Characteristics {
     values: Vec<(String, u32)>,
     edit_mode: bool,
     available_points: u32,
+    softcap: Option<u32>,
 }
 
 impl Characteristics {
@@ -33,9 +37,16 @@ impl Characteristics {
             values,
             edit_mode: false,
             available_points: 0,
+            softcap: None,
         }
     }
 
+    /// In edit mode, shows a slim bar of each value against `cap`.
+    pub fn softcap(mut self, cap: Option<u32>) -> Self {
+        self.softcap = cap;
+        self
+    }
+
     pub fn edit_mode(mut self, enabled: bool, available_points: u32) -> Self {
         self.edit_mode = enabled;
         self.available_points = available_points;
@@ -103,9 +114,9 @@ impl Characteristics {
 
                         // Background — green if upgradeable
                         let bg = if can_upgrade {
-                            UPGRADE_COLOR
+                            palette().upgrade
                         } else {
-                            SECONDARY_COLOR
+                            palette().secondary
                         };
                         let clipped = painter.with_clip_rect(rect);
                         clipped.rect_filled(rect, rounding, bg);
@@ -115,7 +126,7 @@ impl Characteristics {
                         let text_rect =
                             Rect::from_min_max(egui::pos2(text_x, rect.min.y), rect.max);
                         Text::new(label)
-                            .color(TEXT_COLOR)
+                            .color(palette().text)
                             .size(12.0)
                             .align(Align2::LEFT_CENTER)
                             .paint(painter, text_rect);
@@ -129,13 +140,27 @@ impl Characteristics {
                         );
 
                         let shape = ShapeBox::new(Shape::Rectangle)
-                            .fill(MAIN_COLOR)
+                            .fill(palette().main)
                             .stroke(Stroke::NONE)
                             .set_text(value.to_string())
-                            .set_text_color(TEXT_COLOR)
+                            .set_text_color(palette().text)
                             .set_rounding(CornerRadius::same(12));
                         shape.paint(painter, box_rect);
 
+                        if let Some(cap) = self.softcap.filter(|_| self.edit_mode) {
+                            let color = if *value >= cap {
+                                CAP_REACHED_COLOR
+                            } else {
+                                CAP_PROGRESS_COLOR
+                            };
+                            let bar_rect = Rect::from_min_max(
+                                egui::pos2(text_x, rect.max.y - pad - 4.0),
+                                egui::pos2(box_rect.min.x - pad * 2.0, rect.max.y - pad - 1.0),
+                            );
+                            ProgressBar::new("", *value, cap, color, palette().stroke)
+                                .paint(painter, bar_rect);
+                        }
+
                         idx += 1;
                     }
                 });
@@ -0,0 +1,82 @@
+use crate::colors::palette;
+use crate::egui::{self, CornerRadius, RichText, Stroke};
+
+/// A status condition shown as a chip.
+pub struct ConditionChip {
+    pub name: String,
+    /// Formatted effects, listed in the chip's tooltip.
+    pub effects: Vec<String>,
+}
+
+/// What the player did on the conditions row.
+pub enum ConditionsAction {
+    /// Index into the conditions that can still be added.
+    Add(usize),
+    /// Index into the active chips.
+    Remove(usize),
+}
+
+/// A row of active status conditions. Clicking a chip removes it; the "+"
+/// menu lists the conditions that can be added.
+pub struct Conditions {
+    active: Vec<ConditionChip>,
+    available: Vec<String>,
+}
+
+impl Conditions {
+    pub fn new(active: Vec<ConditionChip>, available: Vec<String>) -> Self {
+        Self { active, available }
+    }
+
+    pub fn show(self, ui: &mut egui::Ui) -> Option<ConditionsAction> {
+        let rect = ui.available_rect_before_wrap();
+        ui.painter()
+            .rect_filled(rect, CornerRadius::same(8), palette().secondary);
+        let text_size = (rect.height() * 0.45).clamp(9.0, 12.0);
+
+        let mut action = None;
+        let mut row = ui.new_child(
+            egui::UiBuilder::new()
+                .max_rect(rect.shrink2(egui::vec2(6.0, 3.0)))
+                .layout(egui::Layout::left_to_right(egui::Align::Center)),
+        );
+        row.spacing_mut().item_spacing = egui::vec2(4.0, 0.0);
+
+        for (i, chip) in self.active.iter().enumerate() {
+            let tooltip = if chip.effects.is_empty() {
+                format!("{}\nClick to remove", chip.name)
+            } else {
+                format!(
+                    "{}\n{}\nClick to remove",
+                    chip.name,
+                    chip.effects.join("\n")
+                )
+            };
+            let button = egui::Button::new(
+                RichText::new(&chip.name)
+                    .size(text_size)
+                    .color(palette().text),
+            )
+            .fill(palette().error)
+            .stroke(Stroke::new(1.0, palette().stroke))
+            .corner_radius(CornerRadius::same(10));
+            if row.add(button).on_hover_text(tooltip).clicked() {
+                action = Some(ConditionsAction::Remove(i));
+            }
+        }
+
+        if !self.available.is_empty() {
+            row.menu_button(RichText::new("+ Condition").size(text_size), |ui| {
+                for (i, name) in self.available.iter().enumerate() {
+                    if ui.button(name).clicked() {
+                        action = Some(ConditionsAction::Add(i));
+                        ui.close();
+                    }
+                }
+            });
+        }
+
+        ui.allocate_rect(rect, egui::Sense::hover());
+        action
+    }
+}
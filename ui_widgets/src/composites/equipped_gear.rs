@@ -1,48 +1,457 @@
 use std::cell::Cell;
+use std::collections::BTreeSet;
 
-use crate::colors::SECONDARY_COLOR;
-use crate::egui::{self, CornerRadius, TextureId};
-use crate::molecules::{InventoryTable, InventoryTooltip, TitledBox};
+use crate::atoms::{Shape, ShapeBox};
+use crate::colors::palette;
+use crate::egui::{self, Align2, CornerRadius, FontId, Rect, Stroke, TextureId};
+use crate::molecules::{InventoryCell, InventoryTooltip, TitledBox};
+use crate::styles::UiStyle;
+use crate::traits::Roundable;
 
-/// Displays the character's currently equipped gear slots as a 3x4 grid.
-/// Right-clicking an equipped item shows an "Unequip" context menu.
+const COLS: usize = 3;
+const ROWS: usize = 4;
+
+/// Fixed anatomical layout: slot label and its (row, col) cell in the grid.
+/// Labels match the `Display` names of the equipment slots.
+const SLOT_LAYOUT: [(&str, usize, usize); 9] = [
+    ("Cloak", 0, 0),
+    ("Head", 0, 1),
+    ("Necklace", 0, 2),
+    ("Gloves", 1, 0),
+    ("Armor", 1, 1),
+    ("Ring", 1, 2),
+    ("Suit", 2, 1),
+    ("Pants", 3, 1),
+    ("Any", 3, 2),
+];
+
+/// Slot that catches items whose slot label is not in the layout.
+const FALLBACK_SLOT: &str = "Any";
+
+/// What the user chose in a gear cell's context menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GearAction {
+    /// Unequip the `index`-th item worn in `slot`.
+    Unequip { slot: String, index: usize },
+    /// Unequip several worn items at once, as `(slot, index)` pairs in slot
+    /// order. Indices refer to the gear as it was before any is removed.
+    UnequipMany(Vec<(String, usize)>),
+    /// Equip the `option`-th entry offered for `slot` (see [`EquippedGear::options`]).
+    Equip { slot: String, option: usize },
+    /// Equip the best piece for `slot` by the `metric`-th entry of
+    /// [`EquippedGear::auto_metrics`].
+    AutoEquip { slot: String, metric: usize },
+}
+
+struct GearSlot {
+    label: &'static str,
+    row: usize,
+    col: usize,
+    items: Vec<InventoryTooltip>,
+    options: Vec<String>,
+}
+
+/// Displays the character's equipped gear as slot-labeled cells in a fixed
+/// anatomical 3x4 layout. Right-clicking a worn item offers "Unequip";
+/// clicking an empty slot offers the equippable options for that slot.
+/// Slots with options also get a small "auto" button that equips the best
+/// piece by a chosen metric. Shift-clicking worn cells selects them, and any
+/// right-click menu on the panel can unequip the selection or everything.
 pub struct EquippedGear {
     image: TextureId,
-    items: Vec<Option<InventoryTooltip>>,
+    slots: Vec<GearSlot>,
+    metrics: Vec<String>,
 }
 
 impl EquippedGear {
     pub fn new(image: TextureId) -> Self {
+        let slots = SLOT_LAYOUT
+            .iter()
+            .map(|&(label, row, col)| GearSlot {
+                label,
+                row,
+                col,
+                items: Vec::new(),
+                options: Vec::new(),
+            })
+            .collect();
         Self {
             image,
-            items: Vec::new(),
+            slots,
+            metrics: Vec::new(),
         }
     }
 
-    pub fn items(mut self, items: Vec<Option<InventoryTooltip>>) -> Self {
-        self.items = items;
+    fn slot_mut(&mut self, label: &str) -> &mut GearSlot {
+        let idx = self
+            .slots
+            .iter()
+            .position(|s| s.label == label)
+            .or_else(|| self.slots.iter().position(|s| s.label == FALLBACK_SLOT))
+            .expect("fallback slot is part of the layout");
+        &mut self.slots[idx]
+    }
+
+    /// Adds a worn item to the given slot. Slots such as Ring can hold several.
+    pub fn item(mut self, slot: &str, tooltip: InventoryTooltip) -> Self {
+        self.slot_mut(slot).items.push(tooltip);
         self
     }
 
-    /// Renders the equipped gear and returns the index of the item the user chose to unequip.
-    pub fn show(self, ui: &mut egui::Ui) -> Option<usize> {
+    /// Names offered when the user clicks the slot while it is empty.
+    pub fn options(mut self, slot: &str, names: Vec<String>) -> Self {
+        self.slot_mut(slot).options = names;
+        self
+    }
+
+    /// Metrics offered by the per-slot "auto" button, e.g. "Armor".
+    pub fn auto_metrics(mut self, labels: Vec<String>) -> Self {
+        self.metrics = labels;
+        self
+    }
+
+    fn slot_at(&self, row: usize, col: usize) -> Option<&GearSlot> {
+        self.slots.iter().find(|s| s.row == row && s.col == col)
+    }
+
+    /// Every worn item as a `(slot, index)` pair.
+    fn worn(&self) -> Vec<(String, usize)> {
+        self.slots
+            .iter()
+            .flat_map(|s| (0..s.items.len()).map(|i| (s.label.to_string(), i)))
+            .collect()
+    }
+
+    /// Renders the equipped gear and returns the action chosen by the user.
+    pub fn show(self, ui: &mut egui::Ui) -> Option<GearAction> {
         let action = Cell::new(None);
         TitledBox::new("Equipped")
-            .fill(SECONDARY_COLOR)
+            .fill(palette().secondary)
             .rounding(CornerRadius::same(16))
             .header_ratio(0.035)
             .show(ui, |ui| {
                 let rect = ui.max_rect();
-                let result = InventoryTable::new(self.image, 3, 4)
-                    .id_salt("equipped_gear")
-                    .context_label("Unequip")
-                    .items(self.items)
-                    .paint(ui, rect);
-                // Only primary action (Unequip) is relevant here.
-                if let Some(crate::molecules::CellAction::Primary(i)) = result {
-                    action.set(Some(i));
+                action.set(self.paint(ui, rect));
+            });
+        action.into_inner()
+    }
+
+    fn paint(&self, ui: &mut egui::Ui, rect: Rect) -> Option<GearAction> {
+        let pad = UiStyle::content_padding(ui);
+        let cell_width = (rect.width() - pad * (COLS as f32 + 1.0)) / COLS as f32;
+        let cell_height = (rect.height() - pad * (ROWS as f32 + 1.0)) / ROWS as f32;
+        let id_salt = egui::Id::new("equipped_gear");
+        let selection_id = id_salt.with("selection");
+        let mut selected: BTreeSet<(String, usize)> =
+            ui.data(|d| d.get_temp(selection_id)).unwrap_or_default();
+        let bulk = BulkUnequip {
+            selected: selected.iter().cloned().collect(),
+            worn: self.worn(),
+        };
+        let mut action = None;
+
+        let panel = ui.interact(rect, id_salt.with("panel"), egui::Sense::click());
+        panel.context_menu(|ui| {
+            if let Some(a) = bulk.buttons(ui) {
+                action = Some(a);
+            }
+        });
+
+        for row in 0..ROWS {
+            for col in 0..COLS {
+                let Some(slot) = self.slot_at(row, col) else {
+                    continue;
+                };
+                let x = rect.min.x + pad + (cell_width + pad) * col as f32;
+                let y = rect.min.y + pad + (cell_height + pad) * row as f32;
+                let cell_rect =
+                    Rect::from_min_size(egui::pos2(x, y), egui::vec2(cell_width, cell_height));
+                let response = ui.interact(
+                    cell_rect,
+                    id_salt.with(slot.label),
+                    egui::Sense::click() | egui::Sense::hover(),
+                );
+
+                let picked: Vec<(String, usize)> = (0..slot.items.len())
+                    .map(|i| (slot.label.to_string(), i))
+                    .collect();
+                if !picked.is_empty() && response.clicked() && ui.input(|i| i.modifiers.shift) {
+                    if picked.iter().all(|p| selected.contains(p)) {
+                        picked.iter().for_each(|p| {
+                            selected.remove(p);
+                        });
+                    } else {
+                        selected.extend(picked.iter().cloned());
+                    }
+                }
+                let is_selected = picked.iter().any(|p| selected.contains(p));
+
+                if let Some(a) = paint_slot(
+                    ui,
+                    slot,
+                    cell_rect,
+                    self.image,
+                    &response,
+                    &bulk,
+                    is_selected,
+                ) {
+                    action = Some(a);
+                }
+                if let Some(a) = auto_button(ui, slot, cell_rect, &self.metrics, id_salt) {
+                    action = Some(a);
+                }
+            }
+        }
+
+        if matches!(
+            action,
+            Some(GearAction::Unequip { .. } | GearAction::UnequipMany(_))
+        ) {
+            selected.clear();
+        }
+        ui.data_mut(|d| d.insert_temp(selection_id, selected));
+        action
+    }
+}
+
+/// The "unequip selected" and "unequip all" entries shared by every
+/// right-click menu on the panel.
+struct BulkUnequip {
+    selected: Vec<(String, usize)>,
+    worn: Vec<(String, usize)>,
+}
+
+impl BulkUnequip {
+    fn buttons(&self, ui: &mut egui::Ui) -> Option<GearAction> {
+        let mut action = None;
+        if !self.selected.is_empty()
+            && ui
+                .button(format!("Unequip selected ({})", self.selected.len()))
+                .clicked()
+        {
+            action = Some(GearAction::UnequipMany(self.selected.clone()));
+            ui.close();
+        }
+        if ui
+            .add_enabled(!self.worn.is_empty(), egui::Button::new("Unequip all"))
+            .clicked()
+        {
+            action = Some(GearAction::UnequipMany(self.worn.clone()));
+            ui.close();
+        }
+        action
+    }
+}
+
+fn paint_slot(
+    ui: &egui::Ui,
+    slot: &GearSlot,
+    cell_rect: Rect,
+    image: TextureId,
+    response: &egui::Response,
+    bulk: &BulkUnequip,
+    selected: bool,
+) -> Option<GearAction> {
+    let painter = ui.painter();
+    let hint_color = egui::Color32::from_rgb(0x88, 0x88, 0x99);
+    let font_size = (cell_rect.height() * 0.14).clamp(8.0, 12.0);
+    let mut action = None;
+
+    if slot.items.is_empty() {
+        ShapeBox::new(Shape::Rectangle)
+            .fill(palette().main)
+            .stroke(Stroke::new(1.0, palette().stroke))
+            .set_rounding(CornerRadius::same(14))
+            .paint(painter, cell_rect);
+        painter.text(
+            cell_rect.center(),
+            Align2::CENTER_CENTER,
+            slot.label,
+            FontId::proportional(font_size),
+            hint_color,
+        );
+
+        if !slot.options.is_empty() {
+            egui::Popup::menu(response).show(|ui| {
+                for (i, name) in slot.options.iter().enumerate() {
+                    if ui.button(format!("Equip {name}")).clicked() {
+                        action = Some(GearAction::Equip {
+                            slot: slot.label.to_string(),
+                            option: i,
+                        });
+                        ui.close();
+                    }
                 }
             });
-        action.get()
+        }
+        response.context_menu(|ui| {
+            if let Some(a) = bulk.buttons(ui) {
+                action = Some(a);
+            }
+        });
+        return action;
+    }
+
+    InventoryCell::new(image).paint(painter, cell_rect);
+    if selected {
+        painter.rect_stroke(
+            cell_rect,
+            CornerRadius::same(14),
+            Stroke::new(2.0, palette().text),
+            egui::StrokeKind::Inside,
+        );
+    }
+    painter.text(
+        cell_rect.center_top() + egui::vec2(0.0, cell_rect.height() * 0.06),
+        Align2::CENTER_TOP,
+        slot.label,
+        FontId::proportional(font_size),
+        hint_color,
+    );
+    if slot.items.len() > 1 {
+        painter.text(
+            cell_rect.right_bottom() - egui::vec2(8.0, 6.0),
+            Align2::RIGHT_BOTTOM,
+            format!("×{}", slot.items.len()),
+            FontId::proportional(font_size),
+            palette().text,
+        );
+    }
+
+    let menu_open = response
+        .context_menu(|ui| {
+            for (i, item) in slot.items.iter().enumerate() {
+                let label = if slot.items.len() > 1 {
+                    format!("Unequip {}", item.name())
+                } else {
+                    "Unequip".to_string()
+                };
+                if ui.button(label).clicked() {
+                    action = Some(GearAction::Unequip {
+                        slot: slot.label.to_string(),
+                        index: i,
+                    });
+                    ui.close();
+                }
+            }
+            ui.separator();
+            if let Some(a) = bulk.buttons(ui) {
+                action = Some(a);
+            }
+        })
+        .is_some();
+
+    if response.hovered() && !menu_open {
+        let pos = response.hover_pos().unwrap_or(cell_rect.right_bottom()) + egui::vec2(8.0, 8.0);
+        slot.items[0].show_at(ui.ctx(), response.id, pos);
+    }
+
+    action
+}
+
+/// Small button in the cell's top-right corner listing the metrics to
+/// auto-equip by. Only shown when the slot has pieces to choose from.
+fn auto_button(
+    ui: &egui::Ui,
+    slot: &GearSlot,
+    cell_rect: Rect,
+    metrics: &[String],
+    id_salt: egui::Id,
+) -> Option<GearAction> {
+    if slot.options.is_empty() || metrics.is_empty() {
+        return None;
+    }
+    let font_size = (cell_rect.height() * 0.12).clamp(7.0, 10.0);
+    let size = egui::vec2(font_size * 3.0, font_size * 1.6);
+    let rect = Rect::from_min_size(cell_rect.right_top() + egui::vec2(-size.x - 4.0, 4.0), size);
+    let response = ui.interact(
+        rect,
+        id_salt.with((slot.label, "auto")),
+        egui::Sense::click(),
+    );
+    let painter = ui.painter();
+    let fill = if response.hovered() {
+        palette().secondary
+    } else {
+        palette().main
+    };
+    painter.rect_filled(rect, CornerRadius::same(4), fill);
+    painter.text(
+        rect.center(),
+        Align2::CENTER_CENTER,
+        "auto",
+        FontId::proportional(font_size),
+        palette().text,
+    );
+
+    let mut action = None;
+    egui::Popup::menu(&response).show(|ui| {
+        for (i, metric) in metrics.iter().enumerate() {
+            if ui.button(format!("Best {metric}")).clicked() {
+                action = Some(GearAction::AutoEquip {
+                    slot: slot.label.to_string(),
+                    metric: i,
+                });
+                ui.close();
+            }
+        }
+    });
+    action
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn helmet() -> InventoryTooltip {
+        InventoryTooltip::Equipment {
+            name: "Iron Helm".to_string(),
+            slot: "Head".to_string(),
+            description: String::new(),
+            effects: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_helmet_fills_head_cell_only() {
+        let gear = EquippedGear::new(TextureId::default()).item("Head", helmet());
+
+        for row in 0..ROWS {
+            for col in 0..COLS {
+                let Some(slot) = gear.slot_at(row, col) else {
+                    continue;
+                };
+                let names: Vec<&str> = slot.items.iter().map(|i| i.name()).collect();
+                if slot.label == "Head" {
+                    assert_eq!((row, col), (0, 1));
+                    assert_eq!(names, vec!["Iron Helm"]);
+                } else {
+                    assert!(names.is_empty(), "{} should be empty", slot.label);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_worn_lists_every_ring() {
+        let ring = |name: &str| InventoryTooltip::Equipment {
+            name: name.to_string(),
+            slot: "Ring".to_string(),
+            description: String::new(),
+            effects: Vec::new(),
+        };
+        let gear = EquippedGear::new(TextureId::default())
+            .item("Head", helmet())
+            .item("Ring", ring("Gold Ring"))
+            .item("Ring", ring("Silver Ring"));
+
+        assert_eq!(
+            gear.worn(),
+            vec![
+                ("Head".to_string(), 0),
+                ("Ring".to_string(), 0),
+                ("Ring".to_string(), 1),
+            ]
+        );
     }
 }
@@ -0,0 +1,123 @@
+use crate::atoms::{Shape, ShapeBox, Text};
+use crate::colors::palette;
+use crate::egui::{self, Align2, CornerRadius, Rect, Stroke};
+use crate::traits::Roundable;
+
+/// An ability pinned to the hotbar.
+pub struct HotbarEntry {
+    pub name: String,
+    pub mp_cost: Option<u32>,
+    pub ap_cost: Option<u32>,
+}
+
+/// What the player did on the hotbar.
+pub enum HotbarAction {
+    /// An ability card from the abilities panel was dropped on the strip.
+    Pin(String),
+    Unpin(String),
+    Activate(String),
+}
+
+/// A strip of `slots` ability slots. Filled slots show the ability and its
+/// cost; clicking one activates it and its context menu unpins it. Ability
+/// cards dragged from the `Abilities` panel (payload: the ability name) are
+/// pinned when dropped anywhere on the strip.
+pub struct Hotbar {
+    entries: Vec<HotbarEntry>,
+    slots: usize,
+}
+
+impl Hotbar {
+    pub fn new(entries: Vec<HotbarEntry>, slots: usize) -> Self {
+        Self { entries, slots }
+    }
+
+    pub fn show(self, ui: &mut egui::Ui) -> Option<HotbarAction> {
+        let size = ui.available_size();
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let mut action = None;
+
+        let dragging = response.dnd_hover_payload::<String>().is_some();
+        if let Some(name) = response.dnd_release_payload::<String>() {
+            action = Some(HotbarAction::Pin((*name).clone()));
+        }
+
+        let painter = ui.painter();
+        painter.rect_filled(
+            rect,
+            CornerRadius::same(8),
+            if dragging {
+                palette().upgrade
+            } else {
+                palette().secondary
+            },
+        );
+
+        let slots = self.slots.max(self.entries.len()).max(1);
+        let gap = 4.0;
+        let slot_width = (rect.width() - gap * (slots as f32 + 1.0)) / slots as f32;
+        let slot_height = rect.height() - gap * 2.0;
+        let text_size = (slot_height * 0.32).min(11.0);
+
+        let mut entries = self.entries.into_iter();
+        for i in 0..slots {
+            let slot_rect = Rect::from_min_size(
+                egui::pos2(
+                    rect.min.x + gap + (slot_width + gap) * i as f32,
+                    rect.min.y + gap,
+                ),
+                egui::vec2(slot_width, slot_height),
+            );
+            ShapeBox::new(Shape::Rectangle)
+                .fill(palette().main)
+                .stroke(Stroke::new(1.0, palette().stroke))
+                .set_rounding(CornerRadius::same(6))
+                .paint(painter, slot_rect);
+
+            let Some(entry) = entries.next() else {
+                continue;
+            };
+            let (name_rect, cost_rect) = slot_rect.split_top_bottom_at_fraction(0.55);
+            Text::new(&entry.name)
+                .color(palette().text)
+                .size(text_size)
+                .bold()
+                .align(Align2::CENTER_CENTER)
+                .paint(painter, name_rect.shrink(2.0));
+            let cost = [
+                entry.mp_cost.map(|mp| format!("{mp} MP")),
+                entry.ap_cost.map(|ap| format!("{ap} AP")),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ");
+            if !cost.is_empty() {
+                Text::new(cost)
+                    .color(palette().text)
+                    .size(text_size)
+                    .align(Align2::CENTER_CENTER)
+                    .paint(painter, cost_rect.shrink(2.0));
+            }
+
+            let slot_response = ui
+                .interact(
+                    slot_rect,
+                    ui.id().with("hotbar_slot").with(i),
+                    egui::Sense::click(),
+                )
+                .on_hover_text(&entry.name);
+            if slot_response.clicked() {
+                action = Some(HotbarAction::Activate(entry.name.clone()));
+            }
+            slot_response.context_menu(|ui| {
+                if ui.button("Unpin").clicked() {
+                    action = Some(HotbarAction::Unpin(entry.name.clone()));
+                    ui.close();
+                }
+            });
+        }
+
+        action
+    }
+}
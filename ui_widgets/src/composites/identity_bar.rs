@@ -1,6 +1,48 @@
 use crate::atoms::Text;
-use crate::colors::{SECONDARY_COLOR, STROKE_COLOR, TEXT_COLOR};
-use crate::egui::{self, CornerRadius, Pos2, Rect, Stroke, StrokeKind, Vec2, Widget};
+use crate::colors::palette;
+use crate::egui::{self, Color32, CornerRadius, Pos2, Rect, Stroke, StrokeKind, Vec2, Widget};
+
+/// Fill and outline of the identity bar boxes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdentityBarStyle {
+    pub fill: Color32,
+    pub stroke: Stroke,
+}
+
+impl IdentityBarStyle {
+    /// Neutral styling, or a faint tint and an accent outline when `accent` is set.
+    pub fn new(accent: Option<Color32>) -> Self {
+        match accent {
+            Some(color) => Self {
+                fill: palette().secondary.lerp_to_gamma(color, 0.12),
+                stroke: Stroke::new(2.0, color),
+            },
+            None => Self {
+                fill: palette().secondary,
+                stroke: Stroke::new(1.0, palette().stroke),
+            },
+        }
+    }
+}
+
+/// Race and class choices offered while the identity bar is editable.
+struct IdentityOptions {
+    races: Vec<String>,
+    race: usize,
+    classes: Vec<String>,
+    class: usize,
+}
+
+/// Changes made on an editable [`IdentityBar`].
+pub struct IdentityBarResponse {
+    pub response: egui::Response,
+    /// New name, once a name edit is committed with Enter or by clicking away.
+    pub name: Option<String>,
+    /// Index of a newly picked race.
+    pub race: Option<usize>,
+    /// Index of a newly picked class.
+    pub class: Option<usize>,
+}
 
 /// Displays the character's name and core identity information.
 ///
@@ -9,10 +51,16 @@ use crate::egui::{self, CornerRadius, Pos2, Rect, Stroke, StrokeKind, Vec2, Widg
 /// - Gap: 4% of total height.
 /// - Bottom row: two side-by-side ShapeBoxes occupying 44% of total height,
 ///   separated by a 1% horizontal gap.
+///
+/// When [`editable`](Self::editable), clicking the name edits it and race and
+/// class become combo boxes.
 pub struct IdentityBar {
     name: String,
     race: String,
     class: String,
+    style: IdentityBarStyle,
+    power_score: Option<u32>,
+    options: Option<IdentityOptions>,
 }
 
 impl IdentityBar {
@@ -21,14 +69,92 @@ impl IdentityBar {
             name: name.into(),
             race: race.into(),
             class: class.into(),
+            style: IdentityBarStyle::new(None),
+            power_score: None,
+            options: None,
         }
     }
+
+    /// Makes the name editable and offers `races` and `classes` to pick
+    /// from, with the current ones at index `race` and `class`.
+    pub fn editable(
+        mut self,
+        races: Vec<String>,
+        race: usize,
+        classes: Vec<String>,
+        class: usize,
+    ) -> Self {
+        self.options = Some(IdentityOptions {
+            races,
+            race,
+            classes,
+            class,
+        });
+        self
+    }
+
+    /// Tints the bar with the character's accent color.
+    pub fn accent(mut self, accent: Option<Color32>) -> Self {
+        self.style = IdentityBarStyle::new(accent);
+        self
+    }
+
+    /// Shows an advisory power score in the corner of the name box.
+    pub fn power_score(mut self, score: u32) -> Self {
+        self.power_score = Some(score);
+        self
+    }
 }
 
 impl Widget for IdentityBar {
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        self.show(ui).response
+    }
+}
+
+/// Combo box filling `rect`, returning the index picked this frame.
+fn identity_combo(
+    ui: &mut egui::Ui,
+    rect: Rect,
+    id: egui::Id,
+    options: &[String],
+    selected: usize,
+) -> Option<usize> {
+    let mut picked = selected;
+    let mut child = ui.new_child(egui::UiBuilder::new().max_rect(rect.shrink(4.0)).layout(
+        egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
+    ));
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(
+            options
+                .get(selected)
+                .map(String::as_str)
+                .unwrap_or_default(),
+        )
+        .width(rect.width() - 8.0)
+        .show_ui(&mut child, |ui| {
+            for (i, option) in options.iter().enumerate() {
+                ui.selectable_value(&mut picked, i, option.as_str());
+            }
+        });
+    (picked != selected).then_some(picked)
+}
+
+impl IdentityBar {
+    pub fn show(self, ui: &mut egui::Ui) -> IdentityBarResponse {
         let available = ui.available_size();
-        let (rect, response) = ui.allocate_exact_size(available, egui::Sense::hover());
+        let sense = if self.options.is_some() {
+            egui::Sense::click()
+        } else {
+            egui::Sense::hover()
+        };
+        let (rect, response) = ui.allocate_exact_size(available, sense);
+        let mut changes = IdentityBarResponse {
+            response: response.clone(),
+            name: None,
+            race: None,
+            class: None,
+        };
 
         let total_w = rect.width();
         let total_h = rect.height();
@@ -55,8 +181,19 @@ impl Widget for IdentityBar {
             Vec2::new(bottom_col_w, bottom_h),
         );
 
-        let painter = ui.painter();
-        let stroke = Stroke::new(1.0, STROKE_COLOR);
+        let painter = ui.painter().clone();
+        let IdentityBarStyle { fill, stroke } = self.style;
+        let draft_id = response.id.with("name_draft");
+        let mut name_draft: Option<String> = ui.data(|d| d.get_temp(draft_id));
+        if self.options.is_some()
+            && name_draft.is_none()
+            && response.clicked()
+            && response
+                .interact_pointer_pos()
+                .is_some_and(|pos| top_rect.contains(pos))
+        {
+            name_draft = Some(self.name.clone());
+        }
 
         // Top box: top corners 16, bottom corners 4
         let top_rounding = CornerRadius {
@@ -65,18 +202,45 @@ impl Widget for IdentityBar {
             sw: 4,
             se: 4,
         };
-        painter.rect(
-            top_rect,
-            top_rounding,
-            SECONDARY_COLOR,
-            stroke,
-            StrokeKind::Inside,
-        );
-        Text::new(&self.name)
-            .color(TEXT_COLOR)
-            .size(20.0)
-            .bold()
-            .paint(painter, top_rect);
+        painter.rect(top_rect, top_rounding, fill, stroke, StrokeKind::Inside);
+        if let Some(draft) = &mut name_draft {
+            let edit = ui.put(
+                top_rect.shrink2(Vec2::new(16.0, top_rect.height() * 0.2)),
+                egui::TextEdit::singleline(draft)
+                    .font(egui::FontId::proportional(18.0))
+                    .horizontal_align(egui::Align::Center),
+            );
+            if !edit.has_focus() && !edit.lost_focus() {
+                edit.request_focus();
+            }
+            if edit.lost_focus() {
+                let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+                let name = draft.trim();
+                if !cancelled && !name.is_empty() && name != self.name {
+                    changes.name = Some(name.to_string());
+                }
+                name_draft = None;
+            }
+        } else {
+            Text::new(&self.name)
+                .color(palette().text)
+                .size(20.0)
+                .bold()
+                .paint(&painter, top_rect);
+        }
+        ui.data_mut(|d| match name_draft {
+            Some(draft) => d.insert_temp(draft_id, draft),
+            None => d.remove::<String>(draft_id),
+        });
+        if let Some(score) = self.power_score {
+            painter.text(
+                top_rect.right_center() - Vec2::new(14.0, 0.0),
+                egui::Align2::RIGHT_CENTER,
+                format!("⚔ {score}"),
+                egui::FontId::proportional(12.0),
+                palette().stroke,
+            );
+        }
 
         // Bottom-left box: bottom-left corner 12, rest 4
         let bl_rounding = CornerRadius {
@@ -88,13 +252,24 @@ impl Widget for IdentityBar {
         painter.rect(
             bottom_left_rect,
             bl_rounding,
-            SECONDARY_COLOR,
+            fill,
             stroke,
             StrokeKind::Inside,
         );
-        Text::new(&self.race)
-            .color(TEXT_COLOR)
-            .paint(painter, bottom_left_rect);
+        match &self.options {
+            Some(options) => {
+                changes.race = identity_combo(
+                    ui,
+                    bottom_left_rect,
+                    response.id.with("race"),
+                    &options.races,
+                    options.race,
+                );
+            }
+            None => Text::new(&self.race)
+                .color(palette().text)
+                .paint(&painter, bottom_left_rect),
+        }
 
         // Bottom-right box: bottom-right corner 12, rest 4
         let br_rounding = CornerRadius {
@@ -106,14 +281,46 @@ impl Widget for IdentityBar {
         painter.rect(
             bottom_right_rect,
             br_rounding,
-            SECONDARY_COLOR,
+            fill,
             stroke,
             StrokeKind::Inside,
         );
-        Text::new(&self.class)
-            .color(TEXT_COLOR)
-            .paint(painter, bottom_right_rect);
+        match &self.options {
+            Some(options) => {
+                changes.class = identity_combo(
+                    ui,
+                    bottom_right_rect,
+                    response.id.with("class"),
+                    &options.classes,
+                    options.class,
+                );
+            }
+            None => Text::new(&self.class)
+                .color(palette().text)
+                .paint(&painter, bottom_right_rect),
+        }
+
+        if self.power_score.is_some() {
+            changes.response =
+                response.on_hover_text("Power score: a rough estimate for balancing encounters");
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accent_tints_identity_bar() {
+        let accent = Color32::from_rgb(0x40, 0x90, 0xE0);
+        let bar = IdentityBar::new("Painted", "Human", "Bard").accent(Some(accent));
+        assert_eq!(bar.style.stroke.color, accent);
+        assert_ne!(bar.style.fill, palette().secondary);
 
-        response
+        let neutral = IdentityBar::new("Plain", "Human", "Bard").accent(None);
+        assert_eq!(neutral.style, IdentityBarStyle::new(None));
+        assert_eq!(neutral.style.stroke.color, palette().stroke);
     }
 }
@@ -1,15 +1,18 @@
 use std::cell::Cell;
 
-use crate::colors::SECONDARY_COLOR;
+use crate::colors::palette;
 use crate::egui::{self, CornerRadius, TextureId};
 use crate::molecules::{CellAction, InventoryTable, InventoryTooltip, TitledBox};
 
 /// Displays the character's inventory as a 5x8 grid of [`InventoryCell`] items
 /// inside a [`TitledBox`]. Hovering over a filled cell shows a tooltip popup.
-/// Right-clicking an item shows a context menu with "Equip" and "Remove".
+/// Right-clicking an item shows a context menu with "Equip", "Sell", "Edit item"
+/// and "Remove", plus "Delete from registry" for the cells marked deletable.
 pub struct Inventory {
     image: TextureId,
     items: Vec<Option<InventoryTooltip>>,
+    quantities: Vec<u32>,
+    deletable: Vec<bool>,
 }
 
 impl Inventory {
@@ -17,6 +20,8 @@ impl Inventory {
         Self {
             image,
             items: Vec::new(),
+            quantities: Vec::new(),
+            deletable: Vec::new(),
         }
     }
 
@@ -25,11 +30,22 @@ impl Inventory {
         self
     }
 
+    /// Stack size per item; a count badge shows on stacks of more than one.
+    pub fn quantities(mut self, quantities: Vec<u32>) -> Self {
+        self.quantities = quantities;
+        self
+    }
+
+    pub fn deletable(mut self, deletable: Vec<bool>) -> Self {
+        self.deletable = deletable;
+        self
+    }
+
     /// Renders the inventory and returns the action the user triggered, if any.
     pub fn show(self, ui: &mut egui::Ui) -> Option<CellAction> {
         let action: Cell<Option<CellAction>> = Cell::new(None);
         TitledBox::new("Inventory")
-            .fill(SECONDARY_COLOR)
+            .fill(palette().secondary)
             .rounding(CornerRadius::same(16))
             .header_ratio(0.035)
             .show(ui, |ui| {
@@ -37,8 +53,12 @@ impl Inventory {
                 let result = InventoryTable::new(self.image, 5, 8)
                     .id_salt("inventory")
                     .context_label("Equip")
+                    .show_sell(true)
+                    .show_edit(true)
                     .show_remove(true)
                     .items(self.items)
+                    .quantities(self.quantities)
+                    .deletable(self.deletable)
                     .paint(ui, rect);
                 action.set(result);
             });
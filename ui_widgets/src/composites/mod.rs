@@ -1,7 +1,9 @@
 mod abilities;
 mod character_traits;
 mod characteristics;
+mod conditions;
 mod equipped_gear;
+mod hotbar;
 mod identity_bar;
 mod inventory;
 mod points;
@@ -12,16 +14,18 @@ mod status_bar;
 mod wallet;
 mod weapon;
 
-pub use abilities::{Abilities, AbilityEntry};
+pub use abilities::{Abilities, AbilitiesResponse, AbilityEntry};
 pub use character_traits::{TraitEntry, Traits};
 pub use characteristics::{Characteristics, GridAction};
-pub use equipped_gear::EquippedGear;
-pub use identity_bar::IdentityBar;
+pub use conditions::{ConditionChip, Conditions, ConditionsAction};
+pub use equipped_gear::{EquippedGear, GearAction};
+pub use hotbar::{Hotbar, HotbarAction, HotbarEntry};
+pub use identity_bar::{IdentityBar, IdentityBarResponse, IdentityBarStyle};
 pub use inventory::Inventory;
 pub use points::{Points, PointsResponse};
 pub use portrait::{AddItemMenu, AddItemSelection, Portrait, PortraitResponse};
-pub use skills::{SkillEntry, Skills};
+pub use skills::{group_skills, SkillEntry, SkillGroup, Skills};
 pub use stats::Stats;
-pub use status_bar::{StatusBar, StatusBarResponse};
+pub use status_bar::{StatusBar, StatusBarResponse, ValueEdit};
 pub use wallet::{Wallet, WalletResponse};
-pub use weapon::{Weapon, WeaponSlot};
+pub use weapon::{Weapon, WeaponResponse, WeaponSlot};
@@ -1,5 +1,5 @@
 use crate::atoms::{Shape, ShapeBox, Text};
-use crate::colors::{ERROR_COLOR, MAIN_COLOR, SECONDARY_COLOR, STROKE_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, Align2, CornerRadius, Rect, Stroke, Widget};
 use crate::traits::{Roundable, WithText};
 
@@ -63,13 +63,13 @@ impl Points {
 
                 // Background
                 let clipped = painter.with_clip_rect(rect);
-                clipped.rect_filled(rect, bg_rounding, SECONDARY_COLOR);
+                clipped.rect_filled(rect, bg_rounding, palette().secondary);
 
                 // Label text on the left
                 let text_x = rect.min.x + rect.width() * 0.07;
                 let text_rect = Rect::from_min_max(egui::pos2(text_x, rect.min.y), rect.max);
                 Text::new(label)
-                    .color(TEXT_COLOR)
+                    .color(palette().text)
                     .size(12.0)
                     .align(Align2::LEFT_CENTER)
                     .paint(painter, text_rect);
@@ -98,7 +98,7 @@ impl Points {
 
                     // Measure text width to size the box dynamically
                     let font = egui::FontId::proportional(12.0);
-                    let galley = painter.layout_no_wrap(text.clone(), font.clone(), TEXT_COLOR);
+                    let galley = painter.layout_no_wrap(text.clone(), font.clone(), palette().text);
                     let text_w = galley.size().x;
                     let h_pad = 8.0;
                     let box_w = text_w.max(box_side) + h_pad * 2.0;
@@ -108,12 +108,16 @@ impl Points {
                     );
 
                     // Draw ShapeBox-style background
-                    let box_fill = if *value < 0 { ERROR_COLOR } else { MAIN_COLOR };
+                    let box_fill = if *value < 0 {
+                        palette().error
+                    } else {
+                        palette().main
+                    };
                     painter.rect_filled(box_rect, CornerRadius::same(8), box_fill);
                     painter.rect_stroke(
                         box_rect,
                         CornerRadius::same(8),
-                        Stroke::new(1.0, STROKE_COLOR),
+                        Stroke::new(1.0, palette().stroke),
                         egui::StrokeKind::Inside,
                     );
 
@@ -126,7 +130,7 @@ impl Points {
                     let edit = egui::TextEdit::singleline(&mut text)
                         .horizontal_align(egui::Align::Center)
                         .font(font)
-                        .text_color(TEXT_COLOR)
+                        .text_color(palette().text)
                         .frame(false)
                         .margin(egui::Margin::ZERO);
 
@@ -145,10 +149,10 @@ impl Points {
                     });
                 } else {
                     let shape = ShapeBox::new(Shape::Rectangle)
-                        .fill(MAIN_COLOR)
-                        .stroke(Stroke::new(1.0, STROKE_COLOR))
+                        .fill(palette().main)
+                        .stroke(Stroke::new(1.0, palette().stroke))
                         .set_text(value.to_string())
-                        .set_text_color(TEXT_COLOR)
+                        .set_text_color(palette().text)
                         .set_rounding(CornerRadius::same(8));
                     shape.paint(painter, box_rect);
                 }
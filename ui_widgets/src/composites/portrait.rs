@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 
 use crate::atoms::{Shape, ShapeBox};
-use crate::colors::{MAIN_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, Align2, FontId, Stroke};
 use crate::molecules::InventoryTooltip;
 
@@ -37,6 +37,33 @@ pub struct PortraitResponse {
     pub save: bool,
     pub back: bool,
     pub upload_portrait: bool,
+    /// Pick a previously uploaded portrait instead of a file.
+    pub open_gallery: bool,
+    /// Save the current gear under this name.
+    pub save_loadout: Option<String>,
+    /// Equip the saved loadout with this name.
+    pub apply_loadout: Option<String>,
+    pub inspect_json: bool,
+    pub open_effects: bool,
+    /// Open the window for temporary effects and ending the turn.
+    pub open_buffs: bool,
+    /// Open the character's free-form notes.
+    pub open_notes: bool,
+    /// Save a PNG screenshot of the sheet.
+    pub screenshot: bool,
+    /// Download the saved character as self-contained JSON.
+    pub export: bool,
+    /// Spend every unspent characteristic/skill point by class preference.
+    pub auto_allocate: bool,
+    pub simulate: bool,
+    /// Refund all spent characteristic and skill points.
+    pub respec: bool,
+    /// New accent color picked from the context menu; `Some(None)` resets it.
+    pub accent: Option<Option<egui::Color32>>,
+    /// Switch between the light and dark theme.
+    pub toggle_theme: bool,
+    /// Turn the confirmation before removing items on or off.
+    pub toggle_confirm_destructive: bool,
 }
 
 /// Character portrait display area.
@@ -55,9 +82,17 @@ pub struct Portrait {
     armor: i32,
     add_item_menu: Option<AddItemMenu>,
     avatar_size: Option<[f32; 2]>,
+    inspect_json: bool,
+    simulating: bool,
+    accent: Option<egui::Color32>,
+    loadouts: Vec<String>,
+    screenshot: bool,
+    respec: bool,
+    confirm_destructive: bool,
 }
 
 impl Portrait {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         border_1: egui::TextureId,
         border_2: egui::TextureId,
@@ -83,6 +118,13 @@ impl Portrait {
             armor: 0,
             add_item_menu: None,
             avatar_size: None,
+            inspect_json: false,
+            simulating: false,
+            accent: None,
+            loadouts: Vec::new(),
+            screenshot: false,
+            respec: false,
+            confirm_destructive: false,
         }
     }
 
@@ -112,6 +154,49 @@ impl Portrait {
         self
     }
 
+    /// Adds an "Inspect JSON" debugging entry to the context menu.
+    pub fn inspect_json(mut self, enabled: bool) -> Self {
+        self.inspect_json = enabled;
+        self
+    }
+
+    /// Enables the "Save screenshot" entry (shown disabled otherwise).
+    pub fn screenshot(mut self, enabled: bool) -> Self {
+        self.screenshot = enabled;
+        self
+    }
+
+    /// Enables the edit-mode "Respec" entry (shown disabled otherwise).
+    pub fn respec(mut self, enabled: bool) -> Self {
+        self.respec = enabled;
+        self
+    }
+
+    /// Checks the "Confirm item removal" entry.
+    pub fn confirm_destructive(mut self, enabled: bool) -> Self {
+        self.confirm_destructive = enabled;
+        self
+    }
+
+    /// Marks the sheet as a respec simulation: "Save" is disabled and
+    /// "Simulate respec" is replaced by the simulation's own commit/discard controls.
+    pub fn simulating(mut self, simulating: bool) -> Self {
+        self.simulating = simulating;
+        self
+    }
+
+    /// Tints the portrait border with the character's accent color.
+    pub fn accent(mut self, accent: Option<egui::Color32>) -> Self {
+        self.accent = accent;
+        self
+    }
+
+    /// Names of the saved loadouts offered in the "Loadouts" menu.
+    pub fn loadouts(mut self, names: Vec<String>) -> Self {
+        self.loadouts = names;
+        self
+    }
+
     /// Renders the portrait and returns actions from the context menu.
     pub fn show(self, ui: &mut egui::Ui) -> PortraitResponse {
         let size = ui.available_size();
@@ -127,8 +212,9 @@ impl Portrait {
         let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
         let painter = ui.painter_at(rect);
 
-        painter.image(self.border_1, portrait_rect, uv, egui::Color32::WHITE);
-        painter.image(self.border_2, portrait_rect, uv, egui::Color32::WHITE);
+        let border_tint = self.accent.unwrap_or(egui::Color32::WHITE);
+        painter.image(self.border_1, portrait_rect, uv, border_tint);
+        painter.image(self.border_2, portrait_rect, uv, border_tint);
 
         let border = size.y * 0.025;
         let clip_rect = portrait_rect.shrink(border);
@@ -169,7 +255,7 @@ impl Portrait {
 
         // Inner fill (covers arc zone so unfilled area looks thin)
         ShapeBox::new(Shape::Circle)
-            .fill(MAIN_COLOR)
+            .fill(palette().main)
             .stroke(Stroke::NONE)
             .paint(&painter, arc_rect);
 
@@ -184,7 +270,7 @@ impl Portrait {
             Align2::CENTER_CENTER,
             level_text,
             FontId::proportional(font_size),
-            TEXT_COLOR,
+            palette().text,
         );
 
         // Hover tooltip for XP on level circle
@@ -195,7 +281,7 @@ impl Portrait {
                     .fixed_pos(pos + egui::vec2(12.0, 12.0))
                     .show(ui.ctx(), |ui| {
                         egui::Frame::new()
-                            .fill(MAIN_COLOR)
+                            .fill(palette().main)
                             .corner_radius(4.0)
                             .inner_margin(egui::Margin::symmetric(8, 4))
                             .show(ui, |ui| {
@@ -204,7 +290,7 @@ impl Portrait {
                                         "EXP: {} / {}",
                                         self.xp_current, self.xp_next
                                     ))
-                                    .color(TEXT_COLOR)
+                                    .color(palette().text)
                                     .size(13.0),
                                 );
                             });
@@ -230,7 +316,7 @@ impl Portrait {
                 Align2::CENTER_CENTER,
                 armor_text,
                 FontId::proportional(armor_font_size),
-                TEXT_COLOR,
+                palette().text,
             );
         }
 
@@ -244,9 +330,28 @@ impl Portrait {
         let mut save_clicked = false;
         let mut back_clicked = false;
         let mut upload_portrait = false;
+        let mut open_gallery = false;
+        let mut save_loadout = None;
+        let mut apply_loadout = None;
+        let loadout_name_id = ui.id().with("loadout_name");
+        let mut inspect_json = false;
+        let mut open_effects = false;
+        let mut open_buffs = false;
+        let mut open_notes = false;
+        let mut screenshot = false;
+        let mut export = false;
+        let mut auto_allocate = false;
+        let mut simulate = false;
+        let mut respec = false;
+        let mut accent = None;
+        let mut toggle_theme = false;
+        let mut toggle_confirm_destructive = false;
         let add_item_menu = self.add_item_menu;
         response.context_menu(|ui| {
-            if ui.button("Save").clicked() {
+            if ui
+                .add_enabled(!self.simulating, egui::Button::new("Save"))
+                .clicked()
+            {
                 save_clicked = true;
                 ui.close();
             }
@@ -259,6 +364,10 @@ impl Portrait {
                 upload_portrait = true;
                 ui.close();
             }
+            if ui.button("Portrait Gallery").clicked() {
+                open_gallery = true;
+                ui.close();
+            }
             if ui.button("Add EXP").clicked() {
                 ui.data_mut(|d| {
                     d.insert_temp(
@@ -280,6 +389,37 @@ impl Portrait {
                 toggle_edit = true;
                 ui.close();
             }
+            if self.edit_mode {
+                if ui.button("Auto-allocate points").clicked() {
+                    auto_allocate = true;
+                    ui.close();
+                }
+                if ui
+                    .add_enabled(self.respec, egui::Button::new("Respec"))
+                    .on_disabled_hover_text("Requires admin rights")
+                    .clicked()
+                {
+                    respec = true;
+                    ui.close();
+                }
+                ui.menu_button("Accent color", |ui| {
+                    let mut color = self.accent.unwrap_or(egui::Color32::WHITE);
+                    if egui::color_picker::color_picker_color32(
+                        ui,
+                        &mut color,
+                        egui::color_picker::Alpha::Opaque,
+                    ) {
+                        accent = Some(Some(color));
+                    }
+                    if ui
+                        .add_enabled(self.accent.is_some(), egui::Button::new("Reset"))
+                        .clicked()
+                    {
+                        accent = Some(None);
+                        ui.close();
+                    }
+                });
+            }
             let has_points = self.ability_points > 0;
             if ui
                 .add_enabled(has_points, egui::Button::new("Learn ability"))
@@ -296,6 +436,75 @@ impl Portrait {
                 open_learn_trait = true;
                 ui.close();
             }
+            if ui
+                .add_enabled(!self.simulating, egui::Button::new("Simulate respec"))
+                .clicked()
+            {
+                simulate = true;
+                ui.close();
+            }
+            ui.menu_button("Loadouts", |ui| {
+                for name in &self.loadouts {
+                    if ui.button(format!("Equip \"{name}\"")).clicked() {
+                        apply_loadout = Some(name.clone());
+                        ui.close();
+                    }
+                }
+                if !self.loadouts.is_empty() {
+                    ui.separator();
+                }
+                let mut name: String = ui.data(|d| d.get_temp(loadout_name_id)).unwrap_or_default();
+                ui.add(egui::TextEdit::singleline(&mut name).hint_text("Loadout name"));
+                let trimmed = name.trim().to_string();
+                if ui
+                    .add_enabled(!trimmed.is_empty(), egui::Button::new("Save current gear"))
+                    .clicked()
+                {
+                    save_loadout = Some(trimmed);
+                    name.clear();
+                    ui.close();
+                }
+                ui.data_mut(|d| d.insert_temp(loadout_name_id, name));
+            });
+            if ui.button("Effects").clicked() {
+                open_effects = true;
+                ui.close();
+            }
+            if ui.button("Buffs").clicked() {
+                open_buffs = true;
+                ui.close();
+            }
+            if ui.button("Notes").clicked() {
+                open_notes = true;
+                ui.close();
+            }
+            if ui
+                .add_enabled(self.screenshot, egui::Button::new("Save screenshot"))
+                .clicked()
+            {
+                screenshot = true;
+                ui.close();
+            }
+            let theme_label = if palette().dark_mode {
+                "Light theme"
+            } else {
+                "Dark theme"
+            };
+            if ui.button(theme_label).clicked() {
+                toggle_theme = true;
+                ui.close();
+            }
+            let mut confirm_destructive = self.confirm_destructive;
+            if ui
+                .checkbox(&mut confirm_destructive, "Confirm item removal")
+                .clicked()
+            {
+                toggle_confirm_destructive = true;
+            }
+            if ui.button("Export JSON").clicked() {
+                export = true;
+                ui.close();
+            }
             if ui.button("Create item").clicked() {
                 open_create_item = true;
                 ui.close();
@@ -353,6 +562,13 @@ impl Portrait {
                     }
                 });
             }
+            if self.inspect_json {
+                ui.separator();
+                if ui.button("Inspect JSON").clicked() {
+                    inspect_json = true;
+                    ui.close();
+                }
+            }
         });
 
         // "Add Experience" popup window
@@ -414,6 +630,21 @@ impl Portrait {
             save: save_clicked,
             back: back_clicked,
             upload_portrait,
+            open_gallery,
+            save_loadout,
+            apply_loadout,
+            inspect_json,
+            open_effects,
+            open_buffs,
+            open_notes,
+            screenshot,
+            export,
+            auto_allocate,
+            simulate,
+            respec,
+            accent,
+            toggle_theme,
+            toggle_confirm_destructive,
         }
     }
 }
@@ -1,9 +1,16 @@
 use std::cell::Cell;
+use std::collections::BTreeMap;
 
-use crate::colors::{ERROR_COLOR, MAIN_COLOR, STROKE_COLOR, UPGRADE_COLOR};
+use crate::atoms::Text;
+use crate::colors::{palette, CAP_PROGRESS_COLOR, CAP_REACHED_COLOR};
 use crate::composites::GridAction;
-use crate::egui::{self, CornerRadius, Rect, Stroke};
-use crate::molecules::{LabeledValue, TitledBox};
+use crate::egui::{self, Align2, CornerRadius, Rect, Stroke};
+use crate::molecules::{LabeledValue, ProgressBar, TitledBox};
+
+const COLS: usize = 3;
+const ROWS: usize = 4;
+/// Height of a group header relative to a skill cell.
+const HEADER_RATIO: f32 = 0.45;
 
 /// A single skill entry for display.
 pub struct SkillEntry {
@@ -14,10 +21,50 @@ pub struct SkillEntry {
     pub max_level: u32,
 }
 
+/// Skills sharing a governing characteristic, with their summed ranks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillGroup {
+    pub dependency: String,
+    pub subtotal: i32,
+    /// Indices into the entries passed to [`Skills::new`].
+    pub indices: Vec<usize>,
+}
+
+/// Clusters entries by their `dependency`, ordered by dependency name.
+pub fn group_skills(entries: &[SkillEntry]) -> Vec<SkillGroup> {
+    let mut groups: BTreeMap<&str, SkillGroup> = BTreeMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let group = groups
+            .entry(&entry.dependency)
+            .or_insert_with(|| SkillGroup {
+                dependency: entry.dependency.clone(),
+                subtotal: 0,
+                indices: Vec::new(),
+            });
+        group.subtotal += entry.level;
+        group.indices.push(i);
+    }
+    groups.into_values().collect()
+}
+
+/// Slim bar showing how close a skill is to its `max_level`.
+fn cap_bar(entry: &SkillEntry) -> ProgressBar {
+    let level = entry.level.max(0) as u32;
+    let color = if entry.max_level > 0 && level >= entry.max_level {
+        CAP_REACHED_COLOR
+    } else {
+        CAP_PROGRESS_COLOR
+    };
+    ProgressBar::new("", level, entry.max_level, color, palette().stroke)
+}
+
 /// Displays the character's learned skills and their levels.
 ///
 /// Renders as a [`TitledBox`] with vertical "Skills" label on the left.
-/// The content area contains a 3x4 grid of [`LabeledValue`] rows.
+/// The content area contains a 3x4 grid of [`LabeledValue`] rows. Clicking
+/// the title strip toggles grouping by governing characteristic; the choice
+/// is kept in egui temp data. In edit mode each cell also shows a slim bar of
+/// its level against `max_level`; outside it, clicking a cell asks for a roll.
 pub struct Skills {
     entries: Vec<SkillEntry>,
     edit_mode: bool,
@@ -39,34 +86,93 @@ impl Skills {
         self
     }
 
-    /// Renders the skills grid. Returns `Some(GridAction)` if a skill was clicked in edit mode.
+    /// Renders the skills grid. Returns `Some(GridAction)` if a skill was clicked:
+    /// upgrade/downgrade in edit mode, roll otherwise.
+    /// Action indices always refer to the entries passed to [`Skills::new`].
     pub fn show(self, ui: &mut egui::Ui) -> Option<GridAction> {
         let clicked: Cell<Option<GridAction>> = Cell::new(None);
         let edit_mode = self.edit_mode;
         let available_points = self.available_points;
 
+        let grouped_id = ui.id().with("skills_grouped");
+        let grouped: bool = ui.data(|d| d.get_temp(grouped_id)).unwrap_or(false);
+        let outer = ui.available_rect_before_wrap();
+        let header_ratio = 0.035;
+        let title_rect = Rect::from_min_size(
+            outer.min,
+            egui::vec2((outer.width() * header_ratio).max(20.0), outer.height()),
+        );
+
         TitledBox::new("Skills")
             .rounding(CornerRadius::same(16))
-            .header_ratio(0.035)
-            .content_fill(MAIN_COLOR)
+            .header_ratio(header_ratio)
+            .content_fill(palette().main)
             .content_rounding(CornerRadius::same(14))
             .show(ui, |ui| {
                 let available = ui.available_size();
-                let cols = 3;
-                let rows = 4;
                 let gap = available.y * 0.02;
-                let cell_width = (available.x - gap * (cols as f32 + 1.0)) / cols as f32;
-                let cell_height = (available.y - gap * (rows as f32 + 1.0)) / rows as f32;
+                let cell_width = (available.x - gap * (COLS as f32 + 1.0)) / COLS as f32;
                 let origin = ui.min_rect().min;
 
-                for (i, entry) in self.entries.iter().take(cols * rows).enumerate() {
-                    let col = i % cols;
-                    let row = i / cols;
-                    let x = origin.x + gap + (cell_width + gap) * col as f32;
-                    let y = origin.y + gap + (cell_height + gap) * row as f32;
-                    let cell_rect =
-                        Rect::from_min_size(egui::pos2(x, y), egui::vec2(cell_width, cell_height));
+                let cells = if grouped {
+                    let groups = group_skills(&self.entries);
+                    let rows: usize = groups.iter().map(|g| g.indices.len().div_ceil(COLS)).sum();
+                    let units = rows as f32 + groups.len() as f32 * HEADER_RATIO;
+                    let gaps = (rows + groups.len() + 1) as f32 * gap;
+                    let cell_height = (available.y - gaps) / units.max(1.0);
+                    let header_height = cell_height * HEADER_RATIO;
 
+                    let mut cells = Vec::new();
+                    let mut y = origin.y + gap;
+                    for group in &groups {
+                        let header_rect = Rect::from_min_size(
+                            egui::pos2(origin.x + gap, y),
+                            egui::vec2(available.x - gap * 2.0, header_height),
+                        );
+                        Text::new(format!("{} skills · {}", group.dependency, group.subtotal))
+                            .color(palette().text)
+                            .size((header_height * 0.8).min(13.0))
+                            .bold()
+                            .align(Align2::LEFT_CENTER)
+                            .paint(ui.painter(), header_rect);
+                        y += header_height + gap;
+                        for (pos, &i) in group.indices.iter().enumerate() {
+                            let col = pos % COLS;
+                            let row = pos / COLS;
+                            let x = origin.x + gap + (cell_width + gap) * col as f32;
+                            let cell_y = y + (cell_height + gap) * row as f32;
+                            cells.push((
+                                i,
+                                Rect::from_min_size(
+                                    egui::pos2(x, cell_y),
+                                    egui::vec2(cell_width, cell_height),
+                                ),
+                            ));
+                        }
+                        y += (cell_height + gap) * group.indices.len().div_ceil(COLS) as f32;
+                    }
+                    cells
+                } else {
+                    let cell_height = (available.y - gap * (ROWS as f32 + 1.0)) / ROWS as f32;
+                    (0..self.entries.len().min(COLS * ROWS))
+                        .map(|i| {
+                            let col = i % COLS;
+                            let row = i / COLS;
+                            let x = origin.x + gap + (cell_width + gap) * col as f32;
+                            let y = origin.y + gap + (cell_height + gap) * row as f32;
+                            (
+                                i,
+                                Rect::from_min_size(
+                                    egui::pos2(x, y),
+                                    egui::vec2(cell_width, cell_height),
+                                ),
+                            )
+                        })
+                        .collect()
+                };
+
+                for (i, cell_rect) in cells {
+                    let entry = &self.entries[i];
                     let cost = entry.level as u32 + 1;
                     let over_limit = entry.level > 0 && entry.level as u32 > entry.max_level;
                     let can_upgrade = edit_mode
@@ -76,21 +182,28 @@ impl Skills {
                     // Draw highlight background
                     if over_limit {
                         let painter = ui.painter();
-                        painter.rect_filled(cell_rect, CornerRadius::same(12), ERROR_COLOR);
+                        painter.rect_filled(cell_rect, CornerRadius::same(12), palette().error);
                     } else if can_upgrade {
                         let painter = ui.painter();
-                        painter.rect_filled(cell_rect, CornerRadius::same(12), UPGRADE_COLOR);
+                        painter.rect_filled(cell_rect, CornerRadius::same(12), palette().upgrade);
                     }
 
-                    LabeledValue::new(&entry.name, &entry.dependency, &entry.level.to_string())
+                    LabeledValue::new(&entry.name, &entry.dependency, entry.level.to_string())
                         .text_size(14.0)
-                        .stroke(Stroke::new(1.0, STROKE_COLOR))
+                        .stroke(Stroke::new(1.0, palette().stroke))
                         .rounding(CornerRadius::same(12))
                         .box_rounding(CornerRadius::same(10))
                         .paint(ui.painter(), cell_rect);
 
                     // Handle clicks in edit mode
                     if edit_mode {
+                        let inset = cell_rect.height() * 0.3;
+                        let bar_rect = Rect::from_min_max(
+                            egui::pos2(cell_rect.min.x + inset, cell_rect.max.y - 5.0),
+                            egui::pos2(cell_rect.max.x - inset, cell_rect.max.y - 2.0),
+                        );
+                        cap_bar(entry).paint(ui.painter(), bar_rect);
+
                         let response = ui.allocate_rect(cell_rect, egui::Sense::click());
                         if can_upgrade && response.clicked() {
                             clicked.set(Some(GridAction::Upgrade(i)));
@@ -98,10 +211,74 @@ impl Skills {
                         if response.secondary_clicked() {
                             clicked.set(Some(GridAction::Downgrade(i)));
                         }
+                    } else {
+                        let response = ui
+                            .allocate_rect(cell_rect, egui::Sense::click())
+                            .on_hover_cursor(egui::CursorIcon::PointingHand)
+                            .on_hover_text(format!("Roll d20 {:+}", entry.level));
+                        if response.clicked() {
+                            clicked.set(Some(GridAction::Roll(i)));
+                        }
                     }
                 }
             });
 
+        let toggle = ui
+            .interact(title_rect, grouped_id, egui::Sense::click())
+            .on_hover_text(if grouped {
+                "Show skills in registry order"
+            } else {
+                "Group skills by characteristic"
+            });
+        if toggle.clicked() {
+            ui.data_mut(|d| d.insert_temp(grouped_id, !grouped));
+        }
+
         clicked.get()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, dependency: &str, level: i32) -> SkillEntry {
+        SkillEntry {
+            name: name.to_string(),
+            dependency: dependency.to_string(),
+            level,
+            max_level: 5,
+        }
+    }
+
+    #[test]
+    fn test_dex_skill_grouped_under_dex() {
+        let entries = vec![
+            entry("Eloquence", "CHA", 2),
+            entry("MeleeAttack", "DEX", 3),
+            entry("Stealth", "DEX", 1),
+        ];
+        let groups = group_skills(&entries);
+
+        let dex = groups
+            .iter()
+            .find(|g| g.dependency == "DEX")
+            .expect("DEX group exists");
+        assert_eq!(dex.indices, vec![1, 2]);
+        assert_eq!(dex.subtotal, 4);
+        let cha = groups.iter().find(|g| g.dependency == "CHA").unwrap();
+        assert_eq!(cha.indices, vec![0]);
+    }
+
+    #[test]
+    fn test_cap_bar_shows_level_fraction() {
+        let bar = cap_bar(&entry("Stealth", "DEX", 3));
+        assert!((bar.fraction() - 0.6).abs() < f32::EPSILON);
+
+        let untrainable = SkillEntry {
+            max_level: 0,
+            ..entry("Arcana", "MAG", 0)
+        };
+        assert_eq!(cap_bar(&untrainable).fraction(), 0.0);
+    }
+}
@@ -1,7 +1,7 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::atoms::{Shape, ShapeBox};
-use crate::colors::{MAIN_COLOR, SECONDARY_COLOR, STROKE_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, Color32, CornerRadius, Stroke, TextureId, Widget};
 use crate::molecules::{TitlePosition, TitledBox};
 use crate::traits::{Roundable, WithText};
@@ -10,6 +10,7 @@ use crate::traits::{Roundable, WithText};
 pub struct Stats {
     resists: BTreeMap<String, (TextureId, i32)>,
     protections: BTreeMap<String, (TextureId, i32)>,
+    capped: BTreeSet<String>,
 }
 
 impl Stats {
@@ -20,8 +21,15 @@ impl Stats {
         Self {
             resists,
             protections,
+            capped: BTreeSet::new(),
         }
     }
+
+    /// Labels of the values a stacking cap clipped, shown as "+20 (capped)".
+    pub fn capped(mut self, capped: BTreeSet<String>) -> Self {
+        self.capped = capped;
+        self
+    }
 }
 
 impl Widget for Stats {
@@ -42,12 +50,12 @@ impl Widget for Stats {
                 egui::Layout::left_to_right(egui::Align::Center),
                 |ui| {
                     TitledBox::new("Defense")
-                        .fill(SECONDARY_COLOR)
+                        .fill(palette().secondary)
                         .rounding(16)
-                        .content_fill(MAIN_COLOR)
+                        .content_fill(palette().main)
                         .content_rounding(14)
                         .show(ui, |ui| {
-                            inner_titled_boxes_with_icons(ui, &self.protections, 16);
+                            inner_titled_boxes_with_icons(ui, &self.protections, &self.capped, 16);
                         });
                 },
             );
@@ -60,12 +68,12 @@ impl Widget for Stats {
                 egui::Layout::left_to_right(egui::Align::Center),
                 |ui| {
                     TitledBox::new("Resist")
-                        .fill(SECONDARY_COLOR)
+                        .fill(palette().secondary)
                         .rounding(16)
-                        .content_fill(MAIN_COLOR)
+                        .content_fill(palette().main)
                         .content_rounding(14)
                         .show(ui, |ui| {
-                            inner_titled_boxes_with_icons(ui, &self.resists, 12);
+                            inner_titled_boxes_with_icons(ui, &self.resists, &self.capped, 12);
                         });
                 },
             );
@@ -78,6 +86,7 @@ impl Widget for Stats {
 fn inner_titled_boxes_with_icons(
     ui: &mut egui::Ui,
     values: &BTreeMap<String, (TextureId, i32)>,
+    capped: &BTreeSet<String>,
     rounding: u8,
 ) {
     let count = values.len() as f32;
@@ -96,7 +105,10 @@ fn inner_titled_boxes_with_icons(
             ui.add_space(pad_x);
             ui.spacing_mut().item_spacing = egui::vec2(spacing, 0.0);
             for (label, (icon, value)) in values {
-                let text = format_signed(*value);
+                let mut text = format_signed(*value);
+                if capped.contains(label) {
+                    text.push_str(" (capped)");
+                }
 
                 ui.allocate_ui_with_layout(
                     egui::vec2(item_width, inner_height),
@@ -109,10 +121,10 @@ fn inner_titled_boxes_with_icons(
                             .show(ui, |ui| {
                                 let shape = ShapeBox::new(Shape::Rectangle)
                                     .fill(Color32::TRANSPARENT)
-                                    .stroke(Stroke::new(1.0, STROKE_COLOR))
+                                    .stroke(Stroke::new(1.0, palette().stroke))
                                     .icon(*icon)
                                     .set_text(text.clone())
-                                    .set_text_color(TEXT_COLOR)
+                                    .set_text_color(palette().text)
                                     .set_rounding(CornerRadius::same(rounding));
                                 ui.add(shape);
                             });
@@ -1,27 +1,59 @@
-use crate::colors::{
-    AP_COLOR, AP_SPENT_COLOR, HP_COLOR, HP_SPENT_COLOR, MAIN_COLOR, MP_COLOR, MP_SPENT_COLOR,
-    STROKE_COLOR,
-};
+use crate::colors::{palette, AP_COLOR, HP_COLOR, MP_COLOR, TEMP_HP_COLOR};
 use crate::egui::{self, CornerRadius, Rect, Stroke};
 use crate::molecules::{LabeledValue, ProgressBar};
 
-/// Result of rendering a `StatusBar`. Each field is `Some(new_value)` when
-/// the corresponding progress bar was clicked.
+/// A value typed into a bar's numeric editor: an exact value, or a change
+/// written with an explicit sign ("+5", "-3").
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueEdit {
+    Set(u32),
+    Delta(i32),
+}
+
+impl ValueEdit {
+    /// Parses editor input; `None` for anything else, which reverts the edit.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.starts_with(['+', '-']) {
+            text.parse().ok().map(Self::Delta)
+        } else {
+            text.parse().ok().map(Self::Set)
+        }
+    }
+}
+
+/// Result of rendering a `StatusBar`. `hp`, `mp` and `ap` are `Some(new_value)`
+/// when the corresponding progress bar was clicked.
+#[derive(Default)]
 pub struct StatusBarResponse {
     pub hp: Option<u32>,
     pub mp: Option<u32>,
     pub ap: Option<u32>,
+    /// Damage (negative) or healing (positive) chosen in the HP bar's context menu.
+    pub hp_delta: Option<i32>,
+    /// Temporary HP granted from the HP bar's context menu.
+    pub temp_hp: Option<u32>,
+    /// Values committed in the numeric editors opened by double-clicking a bar.
+    pub hp_edit: Option<ValueEdit>,
+    pub mp_edit: Option<ValueEdit>,
+    pub ap_edit: Option<ValueEdit>,
+    /// "Short rest" was picked in the HP bar's context menu.
+    pub short_rest: bool,
+    /// "Long rest" was picked in the HP bar's context menu.
+    pub long_rest: bool,
 }
 
 /// Displays the character's HP, MP, and AP as three horizontal progress bars.
 pub struct StatusBar {
     hp_current: u32,
     hp_max: u32,
+    temp_hp: u32,
     mp_current: u32,
     mp_max: u32,
     ap_current: u32,
     ap_max: u32,
     initiative: i32,
+    rests: Option<(bool, String)>,
 }
 
 impl StatusBar {
@@ -37,14 +69,30 @@ impl StatusBar {
         Self {
             hp_current,
             hp_max,
+            temp_hp: 0,
             mp_current,
             mp_max,
             ap_current,
             ap_max,
             initiative,
+            rests: None,
         }
     }
 
+    /// Temporary HP, drawn as a separate segment after max HP.
+    pub fn temp_hp(mut self, temp_hp: u32) -> Self {
+        self.temp_hp = temp_hp;
+        self
+    }
+
+    /// Adds "Short rest" and "Long rest" entries to the HP bar's menu. The long
+    /// rest is enabled when `long_available`, with `status` (e.g. "1 of 2
+    /// rests left today") shown above it.
+    pub fn rests(mut self, long_available: bool, status: impl Into<String>) -> Self {
+        self.rests = Some((long_available, status.into()));
+        self
+    }
+
     pub fn show(self, ui: &mut egui::Ui) -> StatusBarResponse {
         let available_width = ui.available_width();
         let available_height = ui.available_height();
@@ -57,8 +105,8 @@ impl StatusBar {
         // Draw background
         {
             let painter = ui.painter();
-            painter.rect_filled(rect, CornerRadius::same(12), MAIN_COLOR);
-            let stroke = Stroke::new(1.0, STROKE_COLOR);
+            painter.rect_filled(rect, CornerRadius::same(12), palette().main);
+            let stroke = Stroke::new(1.0, palette().stroke);
             if stroke.width > 0.0 {
                 painter.rect_stroke(
                     rect,
@@ -100,21 +148,44 @@ impl StatusBar {
             egui::vec2(inner_rect.width(), bar_height),
         );
 
-        let hp = {
+        let (hp, hp_response) = {
             let mut bar_ui = ui.new_child(egui::UiBuilder::new().max_rect(hp_rect));
-            ProgressBar::new("HP", self.hp_current, self.hp_max, HP_COLOR, HP_SPENT_COLOR)
-                .show(&mut bar_ui)
+            ProgressBar::new(
+                "HP",
+                self.hp_current,
+                self.hp_max,
+                HP_COLOR,
+                palette().hp_spent,
+            )
+            .bonus(self.temp_hp, TEMP_HP_COLOR)
+            .show_with_response(&mut bar_ui)
         };
-        let mp = {
+        let (hp_delta, temp_hp, rest) = hp_menu(&hp_response, self.rests.as_ref());
+        let hp_edit = value_editor(ui, &hp_response, hp_rect, self.hp_current);
+        let (mp, mp_response) = {
             let mut bar_ui = ui.new_child(egui::UiBuilder::new().max_rect(mp_rect));
-            ProgressBar::new("MP", self.mp_current, self.mp_max, MP_COLOR, MP_SPENT_COLOR)
-                .show(&mut bar_ui)
+            ProgressBar::new(
+                "MP",
+                self.mp_current,
+                self.mp_max,
+                MP_COLOR,
+                palette().mp_spent,
+            )
+            .show_with_response(&mut bar_ui)
         };
-        let ap = {
+        let mp_edit = value_editor(ui, &mp_response, mp_rect, self.mp_current);
+        let (ap, ap_response) = {
             let mut bar_ui = ui.new_child(egui::UiBuilder::new().max_rect(ap_rect));
-            ProgressBar::new("AP", self.ap_current, self.ap_max, AP_COLOR, AP_SPENT_COLOR)
-                .show(&mut bar_ui)
+            ProgressBar::new(
+                "AP",
+                self.ap_current,
+                self.ap_max,
+                AP_COLOR,
+                palette().ap_spent,
+            )
+            .show_with_response(&mut bar_ui)
         };
+        let ap_edit = value_editor(ui, &ap_response, ap_rect, self.ap_current);
 
         // Initiative sub-widget below progress bars
         {
@@ -122,10 +193,115 @@ impl StatusBar {
                 egui::pos2(inner_rect.min.x, inner_rect.max.y),
                 egui::pos2(inner_rect.max.x, rect.max.y - pad),
             );
-            LabeledValue::new("Initiative", "Per", &self.initiative.to_string())
+            LabeledValue::new("Initiative", "Per", self.initiative.to_string())
                 .paint(ui.painter(), init_rect);
         }
 
-        StatusBarResponse { hp, mp, ap }
+        StatusBarResponse {
+            hp,
+            mp,
+            ap,
+            hp_delta,
+            temp_hp,
+            hp_edit,
+            mp_edit,
+            ap_edit,
+            short_rest: rest == Some(Rest::Short),
+            long_rest: rest == Some(Rest::Long),
+        }
+    }
+}
+
+/// Double-clicking a bar swaps it for a text field prefilled with `current`.
+/// Enter commits the typed value; Escape, clicking away or invalid input
+/// reverts. Single clicks on the cells keep working while no editor is open.
+fn value_editor(
+    ui: &mut egui::Ui,
+    response: &egui::Response,
+    rect: Rect,
+    current: u32,
+) -> Option<ValueEdit> {
+    let text_id = response.id.with("value_edit");
+    let mut text: Option<String> = ui.data(|d| d.get_temp(text_id));
+    let opened = text.is_none() && response.double_clicked();
+    if opened {
+        text = Some(current.to_string());
     }
+    let mut text = text?;
+
+    let mut bar_ui = ui.new_child(egui::UiBuilder::new().max_rect(rect));
+    let edit = bar_ui.add_sized(
+        rect.size(),
+        egui::TextEdit::singleline(&mut text)
+            .id(text_id.with("field"))
+            .horizontal_align(egui::Align::Center)
+            .vertical_align(egui::Align::Center),
+    );
+    if opened {
+        edit.request_focus();
+    }
+    if !edit.lost_focus() {
+        ui.data_mut(|d| d.insert_temp(text_id, text));
+        return None;
+    }
+    ui.data_mut(|d| d.remove::<String>(text_id));
+    if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+        ValueEdit::parse(&text)
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Rest {
+    Short,
+    Long,
+}
+
+/// Context menu on the HP bar: apply damage or healing, grant temporary HP,
+/// or take a short or long rest when `rests` is set.
+fn hp_menu(
+    response: &egui::Response,
+    rests: Option<&(bool, String)>,
+) -> (Option<i32>, Option<u32>, Option<Rest>) {
+    let mut hp_delta = None;
+    let mut temp_hp = None;
+    let mut rest = None;
+    response.context_menu(|ui| {
+        let amount_id = response.id.with("hp_amount");
+        let mut amount: u32 = ui.data(|d| d.get_temp(amount_id)).unwrap_or(1);
+        ui.horizontal(|ui| {
+            ui.label("Amount");
+            ui.add(egui::DragValue::new(&mut amount).range(1..=999));
+        });
+        ui.data_mut(|d| d.insert_temp(amount_id, amount));
+        if ui.button("Damage").clicked() {
+            hp_delta = Some(-(amount as i32));
+            ui.close();
+        }
+        if ui.button("Heal").clicked() {
+            hp_delta = Some(amount as i32);
+            ui.close();
+        }
+        if ui.button("Grant temp HP").clicked() {
+            temp_hp = Some(amount);
+            ui.close();
+        }
+        if let Some((long_available, status)) = rests {
+            ui.separator();
+            if ui.button("Short rest").clicked() {
+                rest = Some(Rest::Short);
+                ui.close();
+            }
+            ui.label(status);
+            if ui
+                .add_enabled(*long_available, egui::Button::new("Long rest"))
+                .clicked()
+            {
+                rest = Some(Rest::Long);
+                ui.close();
+            }
+        }
+    });
+    (hp_delta, temp_hp, rest)
 }
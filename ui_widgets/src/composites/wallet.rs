@@ -1,94 +1,59 @@
 use crate::atoms::{Shape, ShapeBox};
-use crate::colors::{MAIN_COLOR, SECONDARY_COLOR, STROKE_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, Color32, CornerRadius, Stroke, TextureId};
 use crate::molecules::{TitlePosition, TitledBox};
 use crate::traits::{Roundable, WithText};
 
-/// Result of rendering the wallet. Each field is `Some(delta)` when
-/// the corresponding currency cell was clicked.
-/// Left-click = positive delta, right-click = negative delta.
+/// Result of rendering the wallet. `coins` is `Some((row, count))` when a
+/// currency cell was clicked: the denomination's row, highest first, and
+/// `1` for a left-click or `-1` for a right-click.
 pub struct WalletResponse {
-    pub gold: Option<i64>,
-    pub silver: Option<i64>,
-    pub copper: Option<i64>,
+    pub coins: Option<(usize, i64)>,
 }
 
 /// A single currency entry (label, amount, icon).
 struct CurrencyEntry {
-    label: &'static str,
-    amount: u32,
+    label: String,
+    amount: u64,
     icon: TextureId,
-    /// Value of one unit in base currency.
-    unit_value: i64,
 }
 
-/// Displays the character's currency and wealth.
+/// Displays the character's currency and wealth, one cell per denomination.
+#[derive(Default)]
 pub struct Wallet {
-    gold: u32,
-    silver: u32,
-    copper: u32,
-    gold_icon: TextureId,
-    silver_icon: TextureId,
-    copper_icon: TextureId,
+    entries: Vec<CurrencyEntry>,
 }
 
 impl Wallet {
-    pub fn new(
-        gold: u32,
-        silver: u32,
-        copper: u32,
-        gold_icon: TextureId,
-        silver_icon: TextureId,
-        copper_icon: TextureId,
-    ) -> Self {
-        Self {
-            gold,
-            silver,
-            copper,
-            gold_icon,
-            silver_icon,
-            copper_icon,
-        }
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    pub fn show(self, ui: &mut egui::Ui) -> WalletResponse {
-        let mut response = WalletResponse {
-            gold: None,
-            silver: None,
-            copper: None,
-        };
+    /// Adds a denomination cell (highest first). Clicks on it add or remove
+    /// one coin of this denomination.
+    pub fn denomination(mut self, label: impl Into<String>, amount: u64, icon: TextureId) -> Self {
+        self.entries.push(CurrencyEntry {
+            label: label.into(),
+            amount,
+            icon,
+        });
+        self
+    }
 
-        let entries = [
-            CurrencyEntry {
-                label: "Gold",
-                amount: self.gold,
-                icon: self.gold_icon,
-                unit_value: 1000,
-            },
-            CurrencyEntry {
-                label: "Silver",
-                amount: self.silver,
-                icon: self.silver_icon,
-                unit_value: 10,
-            },
-            CurrencyEntry {
-                label: "Copper",
-                amount: self.copper,
-                icon: self.copper_icon,
-                unit_value: 1,
-            },
-        ];
+    pub fn show(self, ui: &mut egui::Ui) -> WalletResponse {
+        let mut response = WalletResponse { coins: None };
 
         TitledBox::new("Wallet")
-            .fill(SECONDARY_COLOR)
+            .fill(palette().secondary)
             .rounding(16)
-            .content_fill(MAIN_COLOR)
+            .content_fill(palette().main)
             .content_rounding(14)
             .show(ui, |ui| {
-                let results = inner_currency_boxes(ui, &entries);
-                response.gold = results[0];
-                response.silver = results[1];
-                response.copper = results[2];
+                let results = inner_currency_boxes(ui, &self.entries);
+                response.coins = results
+                    .into_iter()
+                    .enumerate()
+                    .find_map(|(row, count)| Some((row, count?)));
             });
 
         response
@@ -96,7 +61,7 @@ impl Wallet {
 }
 
 /// Lays out a row of equally-spaced currency [`TitledBox`] widgets.
-/// Returns a delta for each entry: `Some(+unit)` on left-click, `Some(-unit)` on right-click.
+/// Returns a coin count for each entry: `Some(1)` on left-click, `Some(-1)` on right-click.
 fn inner_currency_boxes(ui: &mut egui::Ui, entries: &[CurrencyEntry]) -> Vec<Option<i64>> {
     let count = entries.len() as f32;
     let spacing = 4.0;
@@ -122,7 +87,7 @@ fn inner_currency_boxes(ui: &mut egui::Ui, entries: &[CurrencyEntry]) -> Vec<Opt
                     egui::vec2(item_width, inner_height),
                     egui::Layout::left_to_right(egui::Align::Center),
                     |ui| {
-                        TitledBox::new(entry.label)
+                        TitledBox::new(&entry.label)
                             .title_position(TitlePosition::Top)
                             .fill(Color32::TRANSPARENT)
                             .rounding(8)
@@ -133,17 +98,17 @@ fn inner_currency_boxes(ui: &mut egui::Ui, entries: &[CurrencyEntry]) -> Vec<Opt
 
                                 let shape = ShapeBox::new(Shape::Rectangle)
                                     .fill(Color32::TRANSPARENT)
-                                    .stroke(Stroke::new(1.0, STROKE_COLOR))
+                                    .stroke(Stroke::new(1.0, palette().stroke))
                                     .icon(entry.icon)
                                     .set_text(text.clone())
-                                    .set_text_color(TEXT_COLOR)
+                                    .set_text_color(palette().text)
                                     .set_rounding(CornerRadius::same(12));
                                 shape.paint(ui.painter(), rect);
 
                                 if resp.clicked() {
-                                    results[i] = Some(entry.unit_value);
+                                    results[i] = Some(1);
                                 } else if resp.secondary_clicked() {
-                                    results[i] = Some(-entry.unit_value);
+                                    results[i] = Some(-1);
                                 }
                             });
                     },
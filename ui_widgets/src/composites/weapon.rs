@@ -1,6 +1,6 @@
 use std::cell::Cell;
 
-use crate::colors::{MAIN_COLOR, SECONDARY_COLOR, STROKE_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, CornerRadius, RichText, Stroke, TextureId};
 use crate::molecules::{TitledBox, WeaponEntry};
 
@@ -11,29 +11,58 @@ pub struct WeaponSlot {
     pub name: String,
     pub description: String,
     pub kind: String,
+    /// Total attack bonus with the weapon, characteristics included.
     pub attack: String,
     pub damage: String,
     pub range: String,
     pub condition: String,
+    /// Whether clicking the slot rolls its damage.
+    pub can_roll: bool,
+    /// The last damage roll, or why the damage can't be rolled; shown in the tooltip.
+    pub roll_note: String,
+}
+
+/// What the user did with the weapon slots.
+#[derive(Default, Clone, Copy)]
+pub struct WeaponResponse {
+    /// Slot whose weapon was unequipped.
+    pub unequip: Option<usize>,
+    /// Slot whose damage was rolled.
+    pub roll: Option<usize>,
 }
 
 /// Displays equipped weapon slots.
-/// Right-clicking a filled slot shows an "Unequip" context menu.
+/// Clicking a filled slot rolls its damage; right-clicking shows an "Unequip" context menu.
 pub struct Weapon {
     icon: TextureId,
     slots: Vec<WeaponSlot>,
+    defense: Option<i32>,
 }
 
 impl Weapon {
     pub fn new(icon: TextureId, slots: Vec<WeaponSlot>) -> Self {
-        Self { icon, slots }
+        Self {
+            icon,
+            slots,
+            defense: None,
+        }
     }
 
-    /// Renders the weapon slots and returns the index of the weapon the user chose to unequip.
-    pub fn show(self, ui: &mut egui::Ui) -> Option<usize> {
-        let action = Cell::new(None);
-        TitledBox::new("Weapon")
-            .fill(SECONDARY_COLOR)
+    /// Shows the character's defense in the title.
+    pub fn defense(mut self, defense: i32) -> Self {
+        self.defense = Some(defense);
+        self
+    }
+
+    /// Renders the weapon slots and returns what the user clicked.
+    pub fn show(self, ui: &mut egui::Ui) -> WeaponResponse {
+        let action = Cell::new(WeaponResponse::default());
+        let title = match self.defense {
+            Some(defense) => format!("Weapon · Defense {defense}"),
+            None => "Weapon".to_string(),
+        };
+        TitledBox::new(title)
+            .fill(palette().secondary)
             .rounding(16)
             .content_rounding(14)
             .show(ui, |ui| {
@@ -44,8 +73,8 @@ impl Weapon {
     }
 }
 
-/// Lays out weapon entry slots vertically. Returns index of unequipped weapon if any.
-fn inner_weapon_slots(ui: &mut egui::Ui, icon: TextureId, slots: &[WeaponSlot]) -> Option<usize> {
+/// Lays out weapon entry slots vertically. Returns the unequipped or rolled slot, if any.
+fn inner_weapon_slots(ui: &mut egui::Ui, icon: TextureId, slots: &[WeaponSlot]) -> WeaponResponse {
     let count = SLOT_COUNT as f32;
     let spacing = 4.0;
     let available_width = ui.available_width();
@@ -55,7 +84,7 @@ fn inner_weapon_slots(ui: &mut egui::Ui, icon: TextureId, slots: &[WeaponSlot])
     let inner_height = available_height - pad * 2.0;
     let item_height = (inner_height - spacing * (count - 1.0)) / count;
 
-    let mut action = None;
+    let mut action = WeaponResponse::default();
 
     ui.vertical(|ui| {
         ui.add_space(pad);
@@ -90,14 +119,22 @@ fn inner_weapon_slots(ui: &mut egui::Ui, icon: TextureId, slots: &[WeaponSlot])
                         menu_open = response
                             .context_menu(|ui| {
                                 if ui.button("Unequip").clicked() {
-                                    action = Some(i);
+                                    action.unequip = Some(i);
                                     ui.close();
                                 }
                             })
                             .is_some();
                     }
                     if let Some(slot) = slots.get(i) {
-                        let has_tooltip = !slot.description.is_empty() || !slot.condition.is_empty();
+                        if slot.can_roll && response.clicked() {
+                            action.roll = Some(i);
+                        }
+                        if slot.can_roll && response.hovered() {
+                            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                        }
+                        let has_tooltip = !slot.description.is_empty()
+                            || !slot.condition.is_empty()
+                            || !slot.roll_note.is_empty();
                     if response.hovered() && !menu_open && has_tooltip {
                             let pos = response.hover_pos().unwrap_or(rect.right_top())
                                 + egui::vec2(8.0, 8.0);
@@ -106,8 +143,8 @@ fn inner_weapon_slots(ui: &mut egui::Ui, icon: TextureId, slots: &[WeaponSlot])
                                 .fixed_pos(pos)
                                 .show(ui.ctx(), |ui| {
                                     egui::Frame::NONE
-                                        .fill(MAIN_COLOR)
-                                        .stroke(Stroke::new(0.5, STROKE_COLOR))
+                                        .fill(palette().main)
+                                        .stroke(Stroke::new(0.5, palette().stroke))
                                         .corner_radius(CornerRadius::same(6))
                                         .inner_margin(6.0)
                                         .show(ui, |ui| {
@@ -115,7 +152,7 @@ fn inner_weapon_slots(ui: &mut egui::Ui, icon: TextureId, slots: &[WeaponSlot])
                                                 ui.label(
                                                     RichText::new(&slot.description)
                                                         .size(11.0)
-                                                        .color(crate::colors::TEXT_COLOR),
+                                                        .color(crate::colors::palette().text),
                                                 );
                                             }
                                             if !slot.description.is_empty() && !slot.condition.is_empty() {
@@ -126,7 +163,20 @@ fn inner_weapon_slots(ui: &mut egui::Ui, icon: TextureId, slots: &[WeaponSlot])
                                                     RichText::new(&slot.condition)
                                                         .size(11.0)
                                                         .italics()
-                                                        .color(crate::colors::TEXT_COLOR),
+                                                        .color(crate::colors::palette().text),
+                                                );
+                                            }
+                                            if !slot.roll_note.is_empty() {
+                                                if !slot.description.is_empty()
+                                                    || !slot.condition.is_empty()
+                                                {
+                                                    ui.separator();
+                                                }
+                                                ui.label(
+                                                    RichText::new(&slot.roll_note)
+                                                        .size(11.0)
+                                                        .strong()
+                                                        .color(crate::colors::palette().text),
                                                 );
                                             }
                                         });
@@ -1,5 +1,5 @@
 use crate::atoms::{Shape, ShapeBox, Text};
-use crate::colors::{MAIN_COLOR, STROKE_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, Align2, Color32, CornerRadius, Rect, Stroke, TextureId};
 use crate::traits::{Roundable, WithText};
 
@@ -114,11 +114,11 @@ impl AbilityCard {
             );
 
             ShapeBox::new(Shape::Rectangle)
-                .fill(MAIN_COLOR)
-                .stroke(Stroke::new(1.0, STROKE_COLOR))
+                .fill(palette().main)
+                .stroke(Stroke::new(1.0, palette().stroke))
                 .set_rounding(CornerRadius::same(8))
                 .set_text(mp.to_string())
-                .set_text_color(TEXT_COLOR)
+                .set_text_color(palette().text)
                 .set_text_align(Align2::CENTER_CENTER)
                 .paint(painter, badge_rect);
         }
@@ -133,7 +133,7 @@ impl AbilityCard {
         let name_rect = Rect::from_min_size(info_rect.min, egui::vec2(info_rect.width(), line_h));
         let font_size = line_h * 0.62;
         Text::new(&self.name)
-            .color(TEXT_COLOR)
+            .color(palette().text)
             .size(font_size + font_size * 0.25)
             .bold()
             .align(Align2::LEFT_CENTER)
@@ -160,7 +160,7 @@ impl AbilityCard {
         }
         let line2_text = line2_parts.join("     ");
         Text::new(line2_text)
-            .color(TEXT_COLOR)
+            .color(palette().text)
             .size(font_size)
             .align(Align2::LEFT_CENTER)
             .paint(&info_clipped, line2_rect);
@@ -174,7 +174,7 @@ impl AbilityCard {
             );
             let prefix = "Check: ";
             Text::new(prefix)
-                .color(TEXT_COLOR)
+                .color(palette().text)
                 .size(font_size)
                 .align(Align2::LEFT_CENTER)
                 .paint(&info_clipped, line3_rect);
@@ -182,7 +182,7 @@ impl AbilityCard {
                 .layout_no_wrap(
                     prefix.to_string(),
                     egui::FontId::proportional(font_size),
-                    TEXT_COLOR,
+                    palette().text,
                 )
                 .size()
                 .x;
@@ -198,7 +198,7 @@ impl AbilityCard {
                 format!("{} vs {}", self.check, self.enemy_check)
             };
             Text::new(check_value)
-                .color(TEXT_COLOR)
+                .color(palette().text)
                 .size(font_size)
                 .bold()
                 .align(Align2::LEFT_CENTER)
@@ -211,8 +211,8 @@ impl AbilityCard {
             egui::pos2(rect.max.x - pad, rect.max.y - pad),
         );
         let font = egui::FontId::proportional(12.0);
-        let galley = painter.layout(self.description, font, TEXT_COLOR, inner_desc.width());
+        let galley = painter.layout(self.description, font, palette().text, inner_desc.width());
         let clipped = painter.with_clip_rect(inner_desc);
-        clipped.galley(inner_desc.min, galley, TEXT_COLOR);
+        clipped.galley(inner_desc.min, galley, palette().text);
     }
 }
@@ -1,4 +1,4 @@
-use crate::colors::{MAIN_COLOR, STROKE_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, CornerRadius, RichText, Stroke};
 
 /// Data for displaying an equipment popup card.
@@ -41,8 +41,8 @@ impl EquipmentCard {
             .fixed_pos(pos)
             .show(ctx, |ui| {
                 egui::Frame::NONE
-                    .fill(MAIN_COLOR)
-                    .stroke(Stroke::new(0.5, STROKE_COLOR))
+                    .fill(palette().main)
+                    .stroke(Stroke::new(0.5, palette().stroke))
                     .corner_radius(CornerRadius::same(10))
                     .inner_margin(10.0)
                     .show(ui, |ui| {
@@ -54,7 +54,7 @@ impl EquipmentCard {
                                 RichText::new(&self.name)
                                     .strong()
                                     .size(15.0)
-                                    .color(TEXT_COLOR),
+                                    .color(palette().text),
                             );
                             if !self.slot.is_empty() {
                                 ui.with_layout(
@@ -63,7 +63,7 @@ impl EquipmentCard {
                                         ui.label(
                                             RichText::new(&self.slot)
                                                 .size(12.0)
-                                                .color(STROKE_COLOR),
+                                                .color(palette().stroke),
                                         );
                                     },
                                 );
@@ -77,7 +77,7 @@ impl EquipmentCard {
                             ui.label(
                                 RichText::new(&self.description)
                                     .size(12.0)
-                                    .color(TEXT_COLOR),
+                                    .color(palette().text),
                             );
                             ui.add_space(4.0);
                         }
@@ -87,7 +87,9 @@ impl EquipmentCard {
                             ui.add_space(2.0);
                             for effect in &self.effects {
                                 ui.label(
-                                    RichText::new(effect.as_str()).size(11.0).color(TEXT_COLOR),
+                                    RichText::new(effect.as_str())
+                                        .size(11.0)
+                                        .color(palette().text),
                                 );
                             }
                         }
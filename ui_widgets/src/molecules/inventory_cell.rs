@@ -1,22 +1,29 @@
 use crate::atoms::{Shape, ShapeBox};
-use crate::colors::MAIN_COLOR;
+use crate::colors::palette;
 use crate::egui::{self, Color32, CornerRadius, Rect, Stroke, TextureId};
 use crate::traits::Roundable;
 
-/// A single inventory slot: a rounded white box with an item image inside.
+/// A single inventory slot: a rounded white box with an item image inside,
+/// and a count badge in the corner for stacks of more than one.
 pub struct InventoryCell {
     image: TextureId,
+    count: u32,
 }
 
 impl InventoryCell {
     pub fn new(image: TextureId) -> Self {
-        Self { image }
+        Self { image, count: 1 }
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = count;
+        self
     }
 
     /// Paints the inventory cell into the given rect.
     pub fn paint(&self, painter: &egui::Painter, rect: Rect) {
         ShapeBox::new(Shape::Rectangle)
-            .fill(MAIN_COLOR)
+            .fill(palette().main)
             .stroke(Stroke::NONE)
             .set_rounding(CornerRadius::same(14))
             .paint(painter, rect);
@@ -25,5 +32,16 @@ impl InventoryCell {
         let img_rect = Rect::from_center_size(rect.center(), img_size);
         let uv = Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
         painter.image(self.image, img_rect, uv, Color32::WHITE);
+
+        if self.count > 1 {
+            let inset = rect.height() * 0.08;
+            painter.text(
+                rect.right_top() + egui::vec2(-inset, inset),
+                egui::Align2::RIGHT_TOP,
+                self.count.to_string(),
+                egui::FontId::proportional((rect.height() * 0.22).max(9.0)),
+                palette().text,
+            );
+        }
     }
 }
@@ -87,6 +87,26 @@ pub enum CellAction {
     Primary(usize),
     /// The "Remove" button was clicked.
     Remove(usize),
+    /// The "Sell" button was clicked.
+    Sell(usize),
+    /// The "Edit item" button was clicked.
+    Edit(usize),
+    /// The "Delete from registry" button was clicked.
+    DeleteFromRegistry(usize),
+}
+
+impl CellAction {
+    /// The same action on the cell `f` maps this one's index to, e.g. from a
+    /// filtered grid back to the full item list. `None` if `f` finds no cell.
+    pub fn map_index(self, f: impl FnOnce(usize) -> Option<usize>) -> Option<Self> {
+        Some(match self {
+            Self::Primary(i) => Self::Primary(f(i)?),
+            Self::Remove(i) => Self::Remove(f(i)?),
+            Self::Sell(i) => Self::Sell(f(i)?),
+            Self::Edit(i) => Self::Edit(f(i)?),
+            Self::DeleteFromRegistry(i) => Self::DeleteFromRegistry(f(i)?),
+        })
+    }
 }
 
 /// A grid of [`InventoryCell`] items with configurable column and row counts.
@@ -99,6 +119,10 @@ pub struct InventoryTable {
     id_salt: egui::Id,
     context_label: Option<String>,
     show_remove: bool,
+    show_sell: bool,
+    show_edit: bool,
+    quantities: Vec<u32>,
+    deletable: Vec<bool>,
 }
 
 impl InventoryTable {
@@ -111,6 +135,10 @@ impl InventoryTable {
             id_salt: egui::Id::NULL,
             context_label: None,
             show_remove: false,
+            show_sell: false,
+            show_edit: false,
+            quantities: Vec::new(),
+            deletable: Vec::new(),
         }
     }
 
@@ -134,6 +162,28 @@ impl InventoryTable {
         self
     }
 
+    pub fn show_sell(mut self, show: bool) -> Self {
+        self.show_sell = show;
+        self
+    }
+
+    pub fn show_edit(mut self, show: bool) -> Self {
+        self.show_edit = show;
+        self
+    }
+
+    /// Stack size per cell, by index; cells past the end hold one.
+    pub fn quantities(mut self, quantities: Vec<u32>) -> Self {
+        self.quantities = quantities;
+        self
+    }
+
+    /// Which cells offer "Delete from registry", by index.
+    pub fn deletable(mut self, deletable: Vec<bool>) -> Self {
+        self.deletable = deletable;
+        self
+    }
+
     /// Paints the grid into the given rect.
     /// Returns a [`CellAction`] if a context menu action was triggered.
     pub fn paint(&self, ui: &mut egui::Ui, rect: Rect) -> Option<CellAction> {
@@ -141,7 +191,8 @@ impl InventoryTable {
         let cell_width = (rect.width() - pad * (self.cols as f32 + 1.0)) / self.cols as f32;
         let cell_height = (rect.height() - pad * (self.rows as f32 + 1.0)) / self.rows as f32;
 
-        let has_context = self.context_label.is_some() || self.show_remove;
+        let has_context =
+            self.context_label.is_some() || self.show_remove || self.show_sell || self.show_edit;
         let mut action = None;
 
         for i in 0..(self.cols * self.rows) {
@@ -162,7 +213,10 @@ impl InventoryTable {
 
             let response = ui.interact(cell_rect, self.id_salt.with(("inv_cell", i)), sense);
 
-            InventoryCell::new(self.image).paint(ui.painter(), cell_rect);
+            let count = self.quantities.get(i).copied().unwrap_or(1);
+            InventoryCell::new(self.image)
+                .count(count)
+                .paint(ui.painter(), cell_rect);
 
             if has_item {
                 let dot_radius = 2.5;
@@ -171,7 +225,7 @@ impl InventoryTable {
                     cell_rect.max.y - cell_rect.height() * 0.12,
                 );
                 ui.painter()
-                    .circle_filled(dot_center, dot_radius, crate::colors::STROKE_COLOR);
+                    .circle_filled(dot_center, dot_radius, crate::colors::palette().stroke);
             }
 
             let mut menu_open = false;
@@ -184,11 +238,23 @@ impl InventoryTable {
                                 ui.close();
                             }
                         }
-                        if self.show_remove {
-                            if ui.button("Remove").clicked() {
-                                action = Some(CellAction::Remove(i));
-                                ui.close();
-                            }
+                        if self.show_sell && ui.button("Sell").clicked() {
+                            action = Some(CellAction::Sell(i));
+                            ui.close();
+                        }
+                        if self.show_edit && ui.button("Edit item").clicked() {
+                            action = Some(CellAction::Edit(i));
+                            ui.close();
+                        }
+                        if self.show_remove && ui.button("Remove").clicked() {
+                            action = Some(CellAction::Remove(i));
+                            ui.close();
+                        }
+                        if self.deletable.get(i).copied().unwrap_or(false)
+                            && ui.button("Delete from registry").clicked()
+                        {
+                            action = Some(CellAction::DeleteFromRegistry(i));
+                            ui.close();
                         }
                     })
                     .is_some();
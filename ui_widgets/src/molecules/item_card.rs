@@ -1,4 +1,4 @@
-use crate::colors::{MAIN_COLOR, STROKE_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, CornerRadius, RichText, Stroke};
 
 /// Data for displaying an item popup card.
@@ -27,8 +27,8 @@ impl ItemCard {
             .fixed_pos(pos)
             .show(ctx, |ui| {
                 egui::Frame::NONE
-                    .fill(MAIN_COLOR)
-                    .stroke(Stroke::new(0.5, STROKE_COLOR))
+                    .fill(palette().main)
+                    .stroke(Stroke::new(0.5, palette().stroke))
                     .corner_radius(CornerRadius::same(10))
                     .inner_margin(10.0)
                     .show(ui, |ui| {
@@ -38,7 +38,7 @@ impl ItemCard {
                             RichText::new(&self.name)
                                 .strong()
                                 .size(15.0)
-                                .color(TEXT_COLOR),
+                                .color(palette().text),
                         );
 
                         if !self.description.is_empty() {
@@ -46,7 +46,7 @@ impl ItemCard {
                             ui.label(
                                 RichText::new(&self.description)
                                     .size(12.0)
-                                    .color(TEXT_COLOR),
+                                    .color(palette().text),
                             );
                         }
                     });
@@ -1,5 +1,5 @@
 use crate::atoms::{Shape, ShapeBox, Text};
-use crate::colors::{SECONDARY_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, Align2, CornerRadius, Rect, Stroke, StrokeKind};
 use crate::traits::{Roundable, WithText};
 
@@ -67,11 +67,11 @@ impl LabeledValue {
         );
 
         ShapeBox::new(Shape::Rectangle)
-            .fill(SECONDARY_COLOR)
+            .fill(palette().secondary)
             .stroke(Stroke::NONE)
             .set_rounding(self.box_rounding)
             .set_text(&self.value)
-            .set_text_color(TEXT_COLOR)
+            .set_text_color(palette().text)
             .set_text_size(text_size)
             .paint(painter, box_rect);
 
@@ -80,7 +80,7 @@ impl LabeledValue {
             egui::pos2(box_rect.min.x - 6.0, box_rect.max.y),
         );
         Text::new(&self.tag)
-            .color(TEXT_COLOR)
+            .color(palette().text)
             .size(text_size)
             .align(Align2::RIGHT_CENTER)
             .paint(painter, tag_rect);
@@ -90,7 +90,7 @@ impl LabeledValue {
             egui::pos2(tag_rect.max.x, box_rect.max.y),
         );
         Text::new(&self.label)
-            .color(TEXT_COLOR)
+            .color(palette().text)
             .size(text_size)
             .align(Align2::LEFT_CENTER)
             .paint(painter, label_rect);
@@ -4,6 +4,7 @@ mod inventory_cell;
 mod inventory_table;
 mod item_card;
 mod labeled_value;
+mod points_badge;
 mod progress_bar;
 mod small_ability;
 mod titled_box;
@@ -16,6 +17,7 @@ pub use inventory_cell::InventoryCell;
 pub use inventory_table::{CellAction, InventoryTable, InventoryTooltip};
 pub use item_card::ItemCard;
 pub use labeled_value::LabeledValue;
+pub use points_badge::PointsBadge;
 pub use progress_bar::ProgressBar;
 pub use small_ability::SmallAbility;
 pub use titled_box::{TitlePosition, TitledBox};
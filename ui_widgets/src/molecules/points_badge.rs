@@ -0,0 +1,97 @@
+use crate::colors::palette;
+use crate::egui::{self, Align2, FontId, Rect, Stroke};
+
+/// Length of one pulse, in seconds.
+const PULSE_SECS: f64 = 1.0;
+/// Frames drawn per pulse; enough for a smooth ring without repainting
+/// every frame.
+const PULSE_FRAMES: u32 = 20;
+
+/// Round badge with the total of unspent point pools. Pulses while points are
+/// available; clicking it opens a per-pool breakdown. Nothing is drawn when all
+/// pools are empty.
+pub struct PointsBadge {
+    characteristic: u32,
+    skill: u32,
+    ability: u32,
+    trait_points: u32,
+}
+
+impl PointsBadge {
+    pub fn new(characteristic: u32, skill: u32, ability: u32, trait_points: u32) -> Self {
+        Self {
+            characteristic,
+            skill,
+            ability,
+            trait_points,
+        }
+    }
+
+    /// Sum of all four point pools.
+    pub fn total(&self) -> u32 {
+        self.characteristic + self.skill + self.ability + self.trait_points
+    }
+
+    /// Paints the badge centered at `center` with the given radius.
+    pub fn show(&self, ui: &mut egui::Ui, center: egui::Pos2, radius: f32) {
+        let total = self.total();
+        if total == 0 {
+            return;
+        }
+
+        let rect = Rect::from_center_size(center, egui::vec2(radius * 2.0, radius * 2.0));
+        let response = ui
+            .interact(rect, ui.id().with("points_badge"), egui::Sense::click())
+            .on_hover_text("Unspent points");
+
+        // Pulse: ring grows and fades once per `PULSE_SECS`.
+        let time = ui.input(|i| i.time);
+        let phase = ((time % PULSE_SECS) / PULSE_SECS) as f32;
+        let frame = std::time::Duration::from_secs_f64(PULSE_SECS / PULSE_FRAMES as f64);
+        ui.ctx().request_repaint_after(frame);
+
+        let painter = ui.painter();
+        let pulse_alpha = ((1.0 - phase) * 160.0) as u8;
+        painter.circle_stroke(
+            center,
+            radius * (1.0 + phase * 0.6),
+            Stroke::new(2.0, palette().upgrade.gamma_multiply_u8(pulse_alpha)),
+        );
+        painter.circle(
+            center,
+            radius,
+            palette().upgrade,
+            Stroke::new(1.0, palette().stroke),
+        );
+        painter.text(
+            center,
+            Align2::CENTER_CENTER,
+            total.to_string(),
+            FontId::proportional(radius * 1.1),
+            palette().text,
+        );
+
+        egui::Popup::menu(&response).show(|ui| {
+            for (label, points) in [
+                ("Characteristic points", self.characteristic),
+                ("Skill points", self.skill),
+                ("Ability points", self.ability),
+                ("Trait points", self.trait_points),
+            ] {
+                ui.label(format!("{label}: {points}"));
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_sums_all_pools() {
+        let badge = PointsBadge::new(2, 5, 1, 3);
+        assert_eq!(badge.total(), 11);
+        assert_eq!(PointsBadge::new(0, 0, 0, 0).total(), 0);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::atoms::{Shape, ShapeBox, Text};
-use crate::colors::TEXT_COLOR;
+use crate::colors::palette;
 use crate::egui::{self, Align2, Color32, CornerRadius, Rect, Stroke};
 use crate::traits::Roundable;
 
@@ -7,13 +7,16 @@ use crate::traits::Roundable;
 ///
 /// Boxes from the left up to `current` are filled with `active_color`;
 /// the remaining boxes use `spent_color`. A label is shown to the left
-/// and a "current/max" counter to the right.
+/// and a "current/max" counter to the right. An optional bonus segment
+/// (e.g. temporary HP) is drawn after `max` in its own color.
 pub struct ProgressBar {
     label: String,
     current: u32,
     max: u32,
     active_color: Color32,
     spent_color: Color32,
+    bonus: u32,
+    bonus_color: Color32,
 }
 
 impl ProgressBar {
@@ -30,12 +33,86 @@ impl ProgressBar {
             max,
             active_color,
             spent_color,
+            bonus: 0,
+            bonus_color: active_color,
         }
     }
 
+    /// Appends `amount` extra boxes after `max`, filled with `color`.
+    pub fn bonus(mut self, amount: u32, color: Color32) -> Self {
+        self.bonus = amount;
+        self.bonus_color = color;
+        self
+    }
+
+    /// Share of `max` that `current` covers, in `0.0..=1.0`; bonus boxes are ignored.
+    /// A bar with no maximum is empty.
+    pub fn fraction(&self) -> f32 {
+        if self.max == 0 {
+            return 0.0;
+        }
+        self.current.min(self.max) as f32 / self.max as f32
+    }
+
+    /// Paints only the boxes into `rect`, without label, counter or interaction.
+    /// Used for slim indicator bars; a bar with no maximum is drawn faded.
+    pub fn paint(&self, painter: &egui::Painter, rect: Rect) {
+        if self.max + self.bonus == 0 {
+            ShapeBox::new(Shape::Rectangle)
+                .fill(self.spent_color.gamma_multiply(0.4))
+                .stroke(Stroke::NONE)
+                .set_rounding(CornerRadius::same(4))
+                .paint(painter, rect);
+            return;
+        }
+        self.paint_boxes(painter, rect);
+    }
+
+    /// Paints one box per point of `max + bonus` and returns the box width.
+    fn paint_boxes(&self, painter: &egui::Painter, rect: Rect) -> f32 {
+        let gap = 1.0;
+        let boxes = self.max + self.bonus;
+        if boxes == 0 {
+            return 0.0;
+        }
+        let total_gaps = if boxes > 1 {
+            (boxes - 1) as f32 * gap
+        } else {
+            0.0
+        };
+        let bw = (rect.width() - total_gaps) / boxes as f32;
+
+        for i in 0..boxes {
+            let x = rect.min.x + i as f32 * (bw + gap);
+            let box_rect =
+                Rect::from_min_size(egui::pos2(x, rect.min.y), egui::vec2(bw, rect.height()));
+
+            let color = if i >= self.max {
+                self.bonus_color
+            } else if i < self.current {
+                self.active_color
+            } else {
+                self.spent_color
+            };
+
+            let shape = ShapeBox::new(Shape::Rectangle)
+                .fill(color)
+                .stroke(Stroke::NONE)
+                .set_rounding(CornerRadius::same(4));
+            shape.paint(painter, box_rect);
+        }
+        bw
+    }
+
     /// Renders the progress bar and returns `Some(new_value)` if a cell was clicked,
     /// where `new_value` is the 1-indexed position of the clicked cell.
     pub fn show(self, ui: &mut egui::Ui) -> Option<u32> {
+        self.show_with_response(ui).0
+    }
+
+    /// Like [`ProgressBar::show`], but also returns the bar's response so the
+    /// caller can attach a context menu.
+    pub fn show_with_response(self, ui: &mut egui::Ui) -> (Option<u32>, egui::Response) {
         let available_width = ui.available_width();
         let available_height = ui.available_height();
 
@@ -47,35 +124,7 @@ impl ProgressBar {
         let painter = ui.painter();
 
         let gap = 1.0;
-        let box_width = if self.max > 0 {
-            let total_gaps = if self.max > 1 {
-                (self.max - 1) as f32 * gap
-            } else {
-                0.0
-            };
-            let bw = (rect.width() - total_gaps) / self.max as f32;
-
-            for i in 0..self.max {
-                let x = rect.min.x + i as f32 * (bw + gap);
-                let box_rect =
-                    Rect::from_min_size(egui::pos2(x, rect.min.y), egui::vec2(bw, rect.height()));
-
-                let color = if i < self.current {
-                    self.active_color
-                } else {
-                    self.spent_color
-                };
-
-                let shape = ShapeBox::new(Shape::Rectangle)
-                    .fill(color)
-                    .stroke(Stroke::NONE)
-                    .set_rounding(CornerRadius::same(4));
-                shape.paint(painter, box_rect);
-            }
-            bw
-        } else {
-            0.0
-        };
+        let box_width = self.paint_boxes(painter, rect);
 
         // Center overlay with label and value
         let overlay_color = Color32::from_rgba_unmultiplied(0xEB, 0xEB, 0xF5, 0xCC);
@@ -84,9 +133,14 @@ impl ProgressBar {
         let pad = overlay_height * 0.08;
 
         let font_id = egui::FontId::proportional(text_size);
-        let label_galley = painter.layout_no_wrap(self.label.clone(), font_id.clone(), TEXT_COLOR);
-        let value_text = format!("{}/{}", self.current, self.max);
-        let value_galley = painter.layout_no_wrap(value_text.clone(), font_id, TEXT_COLOR);
+        let label_galley =
+            painter.layout_no_wrap(self.label.clone(), font_id.clone(), palette().text);
+        let value_text = if self.bonus > 0 {
+            format!("{}+{}/{}", self.current, self.bonus, self.max)
+        } else {
+            format!("{}/{}", self.current, self.max)
+        };
+        let value_galley = painter.layout_no_wrap(value_text.clone(), font_id, palette().text);
         let overlay_width = pad + label_galley.size().x + 6.0 + value_galley.size().x + pad;
 
         let overlay_rect =
@@ -104,28 +158,29 @@ impl ProgressBar {
         );
 
         Text::new(&self.label)
-            .color(TEXT_COLOR)
+            .color(palette().text)
             .size(text_size)
             .align(Align2::LEFT_CENTER)
             .paint(painter, text_rect);
 
         Text::new(value_text)
-            .color(TEXT_COLOR)
+            .color(palette().text)
             .size(text_size)
             .bold()
             .align(Align2::RIGHT_CENTER)
             .paint(painter, text_rect);
 
+        let mut clicked = None;
         if response.clicked() && self.max > 0 {
             if let Some(pos) = response.interact_pointer_pos() {
                 let relative_x = pos.x - rect.min.x;
                 let cell = (relative_x / (box_width + gap)).floor() as u32;
                 if cell < self.max {
-                    return Some(cell + 1);
+                    clicked = Some(cell + 1);
                 }
             }
         }
 
-        None
+        (clicked, response)
     }
 }
@@ -1,5 +1,5 @@
 use crate::atoms::{Shape, ShapeBox, Text};
-use crate::colors::{MAIN_COLOR, STROKE_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, Align2, Color32, CornerRadius, Rect, Stroke, TextureId};
 use crate::traits::{Roundable, WithText};
 
@@ -10,6 +10,7 @@ pub struct SmallAbility<'a> {
     mp_cost: Option<u32>,
     fill: Color32,
     learned: bool,
+    locked: bool,
 }
 
 impl<'a> SmallAbility<'a> {
@@ -18,8 +19,9 @@ impl<'a> SmallAbility<'a> {
             name,
             image,
             mp_cost: None,
-            fill: MAIN_COLOR,
+            fill: palette().main,
             learned: false,
+            locked: false,
         }
     }
 
@@ -38,6 +40,12 @@ impl<'a> SmallAbility<'a> {
         self
     }
 
+    /// Marks the ability as locked: a padlock is drawn over the icon.
+    pub fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
     pub fn paint(&self, painter: &egui::Painter, rect: Rect) {
         let side = rect.height();
         let square = Rect::from_center_size(rect.center(), egui::vec2(side, side));
@@ -53,7 +61,7 @@ impl<'a> SmallAbility<'a> {
         let stroke_color = if self.learned {
             Color32::from_rgb(0x6C, 0x5C, 0xE7)
         } else {
-            STROKE_COLOR
+            palette().stroke
         };
         let stroke_width = if self.learned { 2.0 } else { 1.0 };
         ShapeBox::new(Shape::Rectangle)
@@ -91,23 +99,56 @@ impl<'a> SmallAbility<'a> {
             );
 
             ShapeBox::new(Shape::Rectangle)
-                .fill(MAIN_COLOR)
-                .stroke(Stroke::new(1.0, STROKE_COLOR))
+                .fill(palette().main)
+                .stroke(Stroke::new(1.0, palette().stroke))
                 .set_rounding(CornerRadius::same(8))
                 .set_text(mp.to_string())
-                .set_text_color(TEXT_COLOR)
+                .set_text_color(palette().text)
                 .set_text_align(Align2::CENTER_CENTER)
                 .paint(painter, badge_rect);
         }
 
+        if self.locked {
+            paint_padlock(painter, icon_rect);
+        }
+
         // Name text below icon
         let text_top = icon_rect.bottom() + pad * 0.5;
         let text_rect = Rect::from_min_max(egui::pos2(inner.min.x, text_top), inner.max);
         let font_size = (text_rect.height() * 0.4).min(14.0);
         Text::new(self.name)
-            .color(TEXT_COLOR)
+            .color(palette().text)
             .size(font_size)
             .align(Align2::CENTER_CENTER)
             .paint(painter, text_rect);
     }
 }
+
+/// Paints a small padlock in the top-right corner of `icon_rect`.
+fn paint_padlock(painter: &egui::Painter, icon_rect: Rect) {
+    let side = icon_rect.width() * 0.34;
+    let area = Rect::from_min_size(
+        egui::pos2(icon_rect.right() - side, icon_rect.top()),
+        egui::vec2(side, side),
+    );
+    let stroke = Stroke::new((side * 0.1).max(1.0), palette().stroke);
+
+    // Shackle: an arc drawn as a circle whose lower half is covered by the body
+    let shackle_radius = side * 0.24;
+    let shackle_center = egui::pos2(area.center().x, area.top() + side * 0.4);
+    painter.circle_stroke(shackle_center, shackle_radius, stroke);
+
+    // Body
+    let body = Rect::from_min_max(
+        egui::pos2(area.left() + side * 0.15, shackle_center.y),
+        egui::pos2(area.right() - side * 0.15, area.bottom()),
+    );
+    painter.rect_filled(body, CornerRadius::same(3), palette().main);
+    painter.rect_stroke(
+        body,
+        CornerRadius::same(3),
+        stroke,
+        egui::StrokeKind::Inside,
+    );
+    painter.circle_filled(body.center(), side * 0.07, palette().stroke);
+}
@@ -1,7 +1,7 @@
 use std::f32::consts::FRAC_PI_2;
 
 use crate::atoms::{Shape, ShapeBox, Text};
-use crate::colors::{SECONDARY_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, Align2, Color32, CornerRadius, Rect, Stroke, Widget};
 use crate::styles::UiStyle;
 use crate::traits::Roundable;
@@ -43,7 +43,7 @@ impl TitledBox {
             title_position: TitlePosition::default(),
             title_angle: None,
             header_ratio: None,
-            fill: SECONDARY_COLOR,
+            fill: palette().secondary,
             rounding: CornerRadius::ZERO,
             content_fill: None,
             content_rounding: CornerRadius::ZERO,
@@ -143,7 +143,7 @@ impl TitledBox {
         let angle = self.title_angle.unwrap_or(default_angle);
 
         let title = Text::new(&self.title)
-            .color(TEXT_COLOR)
+            .color(palette().text)
             .size(12.0)
             .align(Align2::CENTER_CENTER)
             .angle(angle);
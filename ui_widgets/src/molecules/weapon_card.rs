@@ -1,4 +1,4 @@
-use crate::colors::{MAIN_COLOR, STROKE_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, CornerRadius, RichText, Stroke};
 
 /// Data for displaying a weapon popup card.
@@ -69,8 +69,8 @@ impl WeaponCard {
             .fixed_pos(pos)
             .show(ctx, |ui| {
                 egui::Frame::NONE
-                    .fill(MAIN_COLOR)
-                    .stroke(Stroke::new(0.5, STROKE_COLOR))
+                    .fill(palette().main)
+                    .stroke(Stroke::new(0.5, palette().stroke))
                     .corner_radius(CornerRadius::same(10))
                     .inner_margin(10.0)
                     .show(ui, |ui| {
@@ -82,7 +82,7 @@ impl WeaponCard {
                                 RichText::new(&self.name)
                                     .strong()
                                     .size(15.0)
-                                    .color(TEXT_COLOR),
+                                    .color(palette().text),
                             );
                             if !self.kind.is_empty() {
                                 ui.with_layout(
@@ -91,7 +91,7 @@ impl WeaponCard {
                                         ui.label(
                                             RichText::new(&self.kind)
                                                 .size(12.0)
-                                                .color(STROKE_COLOR),
+                                                .color(palette().stroke),
                                         );
                                     },
                                 );
@@ -104,21 +104,21 @@ impl WeaponCard {
                             ui.label(
                                 RichText::new(format!("Attack: {}", self.attack))
                                     .size(12.0)
-                                    .color(TEXT_COLOR),
+                                    .color(palette().text),
                             );
                         }
                         if !self.damage.is_empty() {
                             ui.label(
                                 RichText::new(format!("Damage: {}", self.damage))
                                     .size(12.0)
-                                    .color(TEXT_COLOR),
+                                    .color(palette().text),
                             );
                         }
                         if !self.range.is_empty() {
                             ui.label(
                                 RichText::new(format!("Range: {}", self.range))
                                     .size(12.0)
-                                    .color(TEXT_COLOR),
+                                    .color(palette().text),
                             );
                         }
                         if !self.description.is_empty() {
@@ -126,7 +126,7 @@ impl WeaponCard {
                             ui.label(
                                 RichText::new(&self.description)
                                     .size(11.0)
-                                    .color(TEXT_COLOR),
+                                    .color(palette().text),
                             );
                         }
                         if !self.description.is_empty() && !self.condition.is_empty() {
@@ -138,7 +138,7 @@ impl WeaponCard {
                                 RichText::new(&self.condition)
                                     .size(11.0)
                                     .italics()
-                                    .color(STROKE_COLOR),
+                                    .color(palette().stroke),
                             );
                         }
                         if !self.effects.is_empty() {
@@ -146,7 +146,7 @@ impl WeaponCard {
                             ui.separator();
                             ui.add_space(2.0);
                             for effect in &self.effects {
-                                ui.label(RichText::new(effect).size(11.0).color(TEXT_COLOR));
+                                ui.label(RichText::new(effect).size(11.0).color(palette().text));
                             }
                         }
                     });
@@ -1,5 +1,5 @@
 use crate::atoms::{Shape, ShapeBox, Text};
-use crate::colors::{MAIN_COLOR, TEXT_COLOR};
+use crate::colors::palette;
 use crate::egui::{self, pos2, vec2, Align2, CornerRadius, Rect, Stroke, TextureId};
 use crate::traits::Roundable;
 
@@ -55,7 +55,7 @@ impl WeaponEntry {
 
     pub fn paint(&self, painter: &egui::Painter, rect: Rect) {
         let shape = ShapeBox::new(Shape::Rectangle)
-            .fill(MAIN_COLOR)
+            .fill(palette().main)
             .stroke(Stroke::NONE)
             .set_rounding(CornerRadius::same(14));
         shape.paint(painter, rect);
@@ -85,7 +85,7 @@ impl WeaponEntry {
 
         if self.name.is_empty() {
             Text::new("Not selected")
-                .color(TEXT_COLOR)
+                .color(palette().text)
                 .size(FONT_SIZE)
                 .align(Align2::LEFT_CENTER)
                 .paint(painter, text_rect);
@@ -95,14 +95,14 @@ impl WeaponEntry {
             // Row 1: name (left, bold) + type (right, normal)
             let row1_rect = Rect::from_min_size(text_rect.min, vec2(text_rect.width(), half_h));
             Text::new(&self.name)
-                .color(TEXT_COLOR)
+                .color(palette().text)
                 .size(TITLE_FONT_SIZE)
                 .bold()
                 .align(Align2::LEFT_CENTER)
                 .paint(painter, row1_rect);
             if !self.kind.is_empty() {
-                Text::new(&format!("Type: {}", self.kind))
-                    .color(TEXT_COLOR)
+                Text::new(format!("Type: {}", self.kind))
+                    .color(palette().text)
                     .size(FONT_SIZE)
                     .align(Align2::RIGHT_CENTER)
                     .paint(painter, row1_rect);
@@ -114,22 +114,22 @@ impl WeaponEntry {
                 vec2(text_rect.width(), half_h),
             );
             if !self.attack.is_empty() {
-                Text::new(&format!("Attack: {}", self.attack))
-                    .color(TEXT_COLOR)
+                Text::new(format!("Attack: {}", self.attack))
+                    .color(palette().text)
                     .size(FONT_SIZE)
                     .align(Align2::LEFT_CENTER)
                     .paint(painter, stats_rect);
             }
             if !self.damage.is_empty() {
-                Text::new(&format!("Damage: {}", self.damage))
-                    .color(TEXT_COLOR)
+                Text::new(format!("Damage: {}", self.damage))
+                    .color(palette().text)
                     .size(FONT_SIZE)
                     .align(Align2::CENTER_CENTER)
                     .paint(painter, stats_rect);
             }
             if !self.range.is_empty() {
-                Text::new(&format!("Range: {}", self.range))
-                    .color(TEXT_COLOR)
+                Text::new(format!("Range: {}", self.range))
+                    .color(palette().text)
                     .size(FONT_SIZE)
                     .align(Align2::RIGHT_CENTER)
                     .paint(painter, stats_rect);
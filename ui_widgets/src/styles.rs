@@ -1,4 +1,4 @@
-use crate::colors::{MAIN_COLOR, SECONDARY_COLOR, STROKE_COLOR, TEXT_COLOR};
+use crate::colors::{set_palette, ThemePalette};
 use crate::egui::{self, Stroke};
 
 /// Global UI style settings shared across widgets.
@@ -15,39 +15,52 @@ impl UiStyle {
         ui.ctx().content_rect().height() * Self::CONTENT_PADDING_RATIO
     }
 
-    /// Sets the global egui visuals on the [`egui::Context`] to match the app's
-    /// light color scheme. Call once at startup so that all egui-managed overlays
-    /// (context menus, popup windows, tooltips) use the correct frame and colors.
-    pub fn apply_global_style(ctx: &egui::Context) {
-        let mut v = egui::Visuals::light();
-        v.window_fill = MAIN_COLOR;
-        v.panel_fill = MAIN_COLOR;
-        v.window_stroke = Stroke::new(1.0, STROKE_COLOR);
+    /// Sets the global egui visuals on the [`egui::Context`] to match `palette`
+    /// and makes it the palette widgets paint with. Call at startup and on every
+    /// theme switch so that all egui-managed overlays (context menus, popup
+    /// windows, tooltips) use the correct frame and colors.
+    pub fn apply_global_style(ctx: &egui::Context, palette: ThemePalette) {
+        set_palette(palette);
+        let ThemePalette {
+            main,
+            secondary,
+            stroke,
+            text,
+            ..
+        } = palette;
+        let mut v = if palette.dark_mode {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        };
+        v.window_fill = main;
+        v.panel_fill = main;
+        v.window_stroke = Stroke::new(1.0, stroke);
         v.window_shadow = egui::Shadow::NONE;
         v.popup_shadow = egui::Shadow::NONE;
-        v.faint_bg_color = SECONDARY_COLOR;
-        v.extreme_bg_color = SECONDARY_COLOR;
-        v.override_text_color = Some(TEXT_COLOR);
-
-        v.widgets.noninteractive.bg_fill = MAIN_COLOR;
-        v.widgets.noninteractive.weak_bg_fill = MAIN_COLOR;
-        v.widgets.noninteractive.bg_stroke = Stroke::new(1.0, STROKE_COLOR);
-        v.widgets.noninteractive.fg_stroke = Stroke::new(1.0, TEXT_COLOR);
-
-        v.widgets.inactive.bg_fill = SECONDARY_COLOR;
-        v.widgets.inactive.weak_bg_fill = SECONDARY_COLOR;
-        v.widgets.inactive.bg_stroke = Stroke::new(1.0, STROKE_COLOR);
-        v.widgets.inactive.fg_stroke = Stroke::new(1.0, TEXT_COLOR);
-
-        v.widgets.hovered.bg_fill = STROKE_COLOR;
-        v.widgets.hovered.weak_bg_fill = STROKE_COLOR;
-        v.widgets.hovered.bg_stroke = Stroke::new(1.0, STROKE_COLOR);
-        v.widgets.hovered.fg_stroke = Stroke::new(1.0, TEXT_COLOR);
-
-        v.widgets.active.bg_fill = STROKE_COLOR;
-        v.widgets.active.weak_bg_fill = STROKE_COLOR;
-        v.widgets.active.bg_stroke = Stroke::new(1.0, STROKE_COLOR);
-        v.widgets.active.fg_stroke = Stroke::new(1.0, TEXT_COLOR);
+        v.faint_bg_color = secondary;
+        v.extreme_bg_color = secondary;
+        v.override_text_color = Some(text);
+
+        v.widgets.noninteractive.bg_fill = main;
+        v.widgets.noninteractive.weak_bg_fill = main;
+        v.widgets.noninteractive.bg_stroke = Stroke::new(1.0, stroke);
+        v.widgets.noninteractive.fg_stroke = Stroke::new(1.0, text);
+
+        v.widgets.inactive.bg_fill = secondary;
+        v.widgets.inactive.weak_bg_fill = secondary;
+        v.widgets.inactive.bg_stroke = Stroke::new(1.0, stroke);
+        v.widgets.inactive.fg_stroke = Stroke::new(1.0, text);
+
+        v.widgets.hovered.bg_fill = stroke;
+        v.widgets.hovered.weak_bg_fill = stroke;
+        v.widgets.hovered.bg_stroke = Stroke::new(1.0, stroke);
+        v.widgets.hovered.fg_stroke = Stroke::new(1.0, text);
+
+        v.widgets.active.bg_fill = stroke;
+        v.widgets.active.weak_bg_fill = stroke;
+        v.widgets.active.bg_stroke = Stroke::new(1.0, stroke);
+        v.widgets.active.fg_stroke = Stroke::new(1.0, text);
 
         ctx.set_visuals(v);
     }